@@ -1,5 +1,6 @@
 //! HTTP content digest
 use regex::Regex;
+use sha2::{Digest, Sha256, Sha512};
 use sfv::{
     BareItem,
     Dictionary,
@@ -63,6 +64,66 @@ impl ContentDigest {
     }
 }
 
+enum HasherState {
+    Sha256(Sha256),
+    Sha512(Sha512),
+}
+
+/// Incremental content digest computation
+///
+/// Can be used when the body is too large to be buffered in memory.
+pub struct ContentDigestHasher {
+    state: HasherState,
+}
+
+impl ContentDigestHasher {
+    /// Creates SHA-256 hasher
+    pub fn new() -> Self {
+        Self { state: HasherState::Sha256(Sha256::new()) }
+    }
+
+    /// Creates SHA-512 hasher
+    pub fn new_sha512() -> Self {
+        Self { state: HasherState::Sha512(Sha512::new()) }
+    }
+
+    /// Creates hasher that uses the same algorithm as the given digest
+    pub fn for_digest(digest: &ContentDigest) -> Self {
+        match digest.algorithm {
+            Algorithm::Sha256 => Self::new(),
+            Algorithm::Sha512 => Self::new_sha512(),
+        }
+    }
+
+    /// Processes the next chunk of the body
+    pub fn update(&mut self, chunk: &[u8]) {
+        match self.state {
+            HasherState::Sha256(ref mut hasher) => hasher.update(chunk),
+            HasherState::Sha512(ref mut hasher) => hasher.update(chunk),
+        }
+    }
+
+    /// Returns digest of the processed body
+    pub fn finalize(self) -> ContentDigest {
+        match self.state {
+            HasherState::Sha256(hasher) => ContentDigest {
+                algorithm: Algorithm::Sha256,
+                digest: hasher.finalize().to_vec(),
+            },
+            HasherState::Sha512(hasher) => ContentDigest {
+                algorithm: Algorithm::Sha512,
+                digest: hasher.finalize().to_vec(),
+            },
+        }
+    }
+}
+
+impl Default for ContentDigestHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub(crate) fn create_digest_header(digest: &ContentDigest) -> String {
     let algorithm = digest.algorithm.to_str().to_uppercase();
     let digest_b64 = base64::encode(&digest.digest);
@@ -122,6 +183,25 @@ pub(crate) fn parse_content_digest_header(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_content_digest_hasher() {
+        let request_body = "test*123";
+        let mut hasher = ContentDigestHasher::new();
+        for chunk in request_body.as_bytes().chunks(3) {
+            hasher.update(chunk);
+        };
+        let digest = hasher.finalize();
+        assert_eq!(digest, ContentDigest::new(request_body.as_bytes()));
+
+        let expected_digest = ContentDigest::new_sha512(request_body.as_bytes());
+        let mut hasher = ContentDigestHasher::for_digest(&expected_digest);
+        for chunk in request_body.as_bytes().chunks(5) {
+            hasher.update(chunk);
+        };
+        let digest = hasher.finalize();
+        assert_eq!(digest, expected_digest);
+    }
+
     #[test]
     fn test_create_and_parse_digest_header() {
         let request_body = "test*123";
@@ -1,16 +1,63 @@
 //! Agent: responsible for making requests to other nodes.
 
+use std::sync::Arc;
+use std::time::Duration;
+
 pub use apx_core::http_signatures::create::HttpSigner;
 
+use super::webfinger::WebfingerCache;
+
+/// Retry policy for fetch requests
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts (including the first one).
+    pub max_attempts: u32,
+    /// Delay before the first retry. Doubled after every attempt.
+    pub initial_delay: Duration,
+    /// Maximum delay between attempts.
+    pub max_delay: Duration,
+    /// Respect Retry-After header in 429 and 503 responses?
+    pub retry_after_enabled: bool,
+}
+
+impl RetryPolicy {
+    /// Disables retries
+    pub fn none() -> Self {
+        Self { max_attempts: 1, ..Default::default() }
+    }
+
+    /// Returns delay before the next attempt
+    pub(crate) fn backoff_delay(&self, attempt: u32) -> Duration {
+        let multiplier = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay
+            .saturating_mul(multiplier)
+            .min(self.max_delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            retry_after_enabled: true,
+        }
+    }
+}
+
 /// Federation agent parameters
 pub struct FederationAgent {
     /// User-Agent string.
     pub user_agent: Option<String>,
+    /// Value of X-Request-Id header.
+    pub request_id: Option<String>,
     // https://www.w3.org/TR/activitypub/#security-localhost
     pub ssrf_protection_enabled: bool,
 
     pub response_size_limit: usize,
     pub fetcher_timeout: u64,
+    pub fetcher_retry_policy: RetryPolicy,
     pub deliverer_timeout: u64,
 
     // Proxy for outgoing requests
@@ -24,15 +71,22 @@ pub struct FederationAgent {
     pub signer: Option<HttpSigner>,
     /// Enable RFC-9421 signatures?
     pub rfc9421_enabled: bool,
+
+    /// Storage for WebFinger lookup results.
+    pub webfinger_cache: Option<Arc<dyn WebfingerCache>>,
+    /// Lifetime of WebFinger cache entries, in seconds.
+    pub webfinger_cache_ttl: u64,
 }
 
 impl Default for FederationAgent {
     fn default() -> Self {
         Self {
             user_agent: None,
+            request_id: None,
             ssrf_protection_enabled: true,
             response_size_limit: 2_000_000,
             fetcher_timeout: 10,
+            fetcher_retry_policy: RetryPolicy::default(),
             deliverer_timeout: 10,
             proxy_url: None,
             onion_proxy_url: None,
@@ -40,6 +94,28 @@ impl Default for FederationAgent {
             no_proxy: vec![],
             signer: None,
             rfc9421_enabled: false,
+            webfinger_cache: None,
+            webfinger_cache_ttl: 3600,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_policy_backoff_delay() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(3),
+            retry_after_enabled: true,
+        };
+        assert_eq!(policy.backoff_delay(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff_delay(2), Duration::from_millis(1000));
+        assert_eq!(policy.backoff_delay(3), Duration::from_millis(2000));
+        assert_eq!(policy.backoff_delay(4), Duration::from_secs(3));
+        assert_eq!(policy.backoff_delay(100), Duration::from_secs(3));
+    }
+}
@@ -1,5 +1,7 @@
 //! Delivering activities.
 
+use std::collections::HashMap;
+
 use bytes::Bytes;
 use reqwest::{header, Client, Method, StatusCode};
 use serde_json::{Value as JsonValue};
@@ -206,10 +208,154 @@ pub async fn delete_media(
     }
 }
 
+/// Recipient of an activity
+#[derive(Clone, Debug)]
+pub struct Recipient {
+    /// Actor ID
+    pub id: String,
+    pub inbox: String,
+    pub shared_inbox: Option<String>,
+}
+
+impl Recipient {
+    /// Extracts recipient information from actor object
+    pub fn from_actor(actor: &JsonValue) -> Option<Self> {
+        let actor_id = actor["id"].as_str()?;
+        let inbox = actor["inbox"].as_str()?;
+        let maybe_shared_inbox = actor["endpoints"]["sharedInbox"].as_str();
+        let recipient = Self {
+            id: actor_id.to_owned(),
+            inbox: inbox.to_owned(),
+            shared_inbox: maybe_shared_inbox.map(|inbox| inbox.to_owned()),
+        };
+        Some(recipient)
+    }
+}
+
+/// Groups recipients by inbox.
+///
+/// Shared inbox is used if it is available.
+/// Returns a list of inboxes with corresponding actor IDs,
+/// in the order of first appearance.
+pub fn resolve_inboxes(
+    recipients: &[Recipient],
+    use_shared_inbox: bool,
+) -> Vec<(String, Vec<String>)> {
+    let mut inboxes: Vec<(String, Vec<String>)> = vec![];
+    let mut positions = HashMap::new();
+    for recipient in recipients {
+        let inbox = if use_shared_inbox {
+            recipient.shared_inbox.as_ref().unwrap_or(&recipient.inbox)
+        } else {
+            &recipient.inbox
+        };
+        let position = *positions.entry(inbox.clone()).or_insert_with(|| {
+            inboxes.push((inbox.clone(), vec![]));
+            inboxes.len() - 1
+        });
+        let actor_ids = &mut inboxes[position].1;
+        if !actor_ids.contains(&recipient.id) {
+            actor_ids.push(recipient.id.clone());
+        };
+    };
+    inboxes
+}
+
+/// Result of delivery to a single inbox
+#[derive(Debug)]
+pub struct DeliveryResult {
+    pub inbox: String,
+    /// Recipients served by this inbox
+    pub recipients: Vec<String>,
+    pub result: Result<Response, DelivererError>,
+}
+
+/// Delivers activity to recipients.
+///
+/// Each inbox receives a separately signed request.
+/// Deliveries are performed sequentially.
+pub async fn deliver_to_audience(
+    agent: &FederationAgent,
+    activity: &JsonValue,
+    recipients: &[Recipient],
+    use_shared_inbox: bool,
+) -> Vec<DeliveryResult> {
+    let mut results = vec![];
+    for (inbox, actor_ids) in resolve_inboxes(recipients, use_shared_inbox) {
+        let result = send_object(agent, &inbox, activity, &[]).await;
+        results.push(DeliveryResult {
+            inbox,
+            recipients: actor_ids,
+            result,
+        });
+    };
+    results
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::Ordering;
+    use serde_json::json;
+    use crate::test_utils::{mock_response, start_mock_server};
     use super::*;
 
+    #[test]
+    fn test_recipient_from_actor() {
+        let actor = json!({
+            "id": "https://social.example/users/1",
+            "type": "Person",
+            "inbox": "https://social.example/users/1/inbox",
+            "endpoints": {
+                "sharedInbox": "https://social.example/inbox",
+            },
+        });
+        let recipient = Recipient::from_actor(&actor).unwrap();
+        assert_eq!(recipient.id, "https://social.example/users/1");
+        assert_eq!(recipient.inbox, "https://social.example/users/1/inbox");
+        assert_eq!(
+            recipient.shared_inbox.as_deref(),
+            Some("https://social.example/inbox"),
+        );
+    }
+
+    #[test]
+    fn test_resolve_inboxes() {
+        let recipients = vec![
+            Recipient {
+                id: "https://one.example/users/1".to_owned(),
+                inbox: "https://one.example/users/1/inbox".to_owned(),
+                shared_inbox: Some("https://one.example/inbox".to_owned()),
+            },
+            Recipient {
+                id: "https://two.example/users/1".to_owned(),
+                inbox: "https://two.example/users/1/inbox".to_owned(),
+                shared_inbox: None,
+            },
+            Recipient {
+                id: "https://one.example/users/2".to_owned(),
+                inbox: "https://one.example/users/2/inbox".to_owned(),
+                shared_inbox: Some("https://one.example/inbox".to_owned()),
+            },
+        ];
+        let inboxes = resolve_inboxes(&recipients, true);
+        assert_eq!(inboxes, vec![
+            (
+                "https://one.example/inbox".to_owned(),
+                vec![
+                    "https://one.example/users/1".to_owned(),
+                    "https://one.example/users/2".to_owned(),
+                ],
+            ),
+            (
+                "https://two.example/users/1/inbox".to_owned(),
+                vec!["https://two.example/users/1".to_owned()],
+            ),
+        ]);
+
+        let inboxes = resolve_inboxes(&recipients, false);
+        assert_eq!(inboxes.len(), 3);
+    }
+
     #[test]
     fn test_http_error_to_string() {
         let response = Response {
@@ -219,4 +365,53 @@ mod tests {
         let error = DelivererError::HttpError(response);
         assert_eq!(error.to_string(), "HTTP error 500");
     }
+
+    #[tokio::test]
+    async fn test_deliver_to_audience() {
+        let (server_url, request_count) = start_mock_server(|request| {
+            if request.path == "/inbox" {
+                mock_response("202 Accepted", &[], "")
+            } else {
+                mock_response("410 Gone", &[], "")
+            }
+        }).await;
+        let recipients = vec![
+            Recipient {
+                id: format!("{server_url}/users/1"),
+                inbox: format!("{server_url}/users/1/inbox"),
+                shared_inbox: Some(format!("{server_url}/inbox")),
+            },
+            Recipient {
+                id: format!("{server_url}/users/2"),
+                inbox: format!("{server_url}/users/2/inbox"),
+                shared_inbox: Some(format!("{server_url}/inbox")),
+            },
+            Recipient {
+                id: format!("{server_url}/users/3"),
+                inbox: format!("{server_url}/users/3/inbox"),
+                shared_inbox: None,
+            },
+        ];
+        let agent = FederationAgent {
+            ssrf_protection_enabled: false, // allow connections to 127.0.0.1
+            ..Default::default()
+        };
+        let activity = json!({"type": "Create"});
+        let results = deliver_to_audience(
+            &agent,
+            &activity,
+            &recipients,
+            true,
+        ).await;
+        assert_eq!(request_count.load(Ordering::SeqCst), 2);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].recipients.len(), 2);
+        assert!(results[0].result.is_ok());
+        assert_eq!(results[1].recipients.len(), 1);
+        assert!(matches!(
+            results[1].result,
+            Err(DelivererError::HttpError(ref response))
+                if response.status == StatusCode::GONE,
+        ));
+    }
 }
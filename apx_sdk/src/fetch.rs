@@ -1,5 +1,8 @@
 //! Retrieving objects or media.
 
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
 #[cfg(not(target_arch = "wasm32"))]
 use http_body_util::{
     combinators::MapErr,
@@ -7,10 +10,13 @@ use http_body_util::{
     BodyExt,
     Limited,
 };
+use bytes::Bytes;
 use reqwest::{
     header,
     Client,
     Method,
+    RequestBuilder,
+    Response,
     StatusCode,
     Url,
 };
@@ -29,24 +35,29 @@ use apx_core::{
 };
 
 use super::{
-    agent::FederationAgent,
+    agent::{FederationAgent, RetryPolicy},
     authentication::{
         AuthenticationError,
     },
     constants::{AP_MEDIA_TYPE, AS_MEDIA_TYPE},
     http_client::{
         build_http_request,
-        create_http_client,
         describe_request_error,
         limited_response,
         sign_http_request,
-        RedirectAction,
-        UnsafeUrlError,
         REDIRECT_LIMIT,
     },
     utils::extract_media_type,
 };
 
+pub use crate::http_client::{
+    create_http_client,
+    is_safe_addr,
+    require_safe_url,
+    RedirectAction,
+    UnsafeUrlError,
+};
+
 const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
 
 /// Errors that may occur when fetching an object
@@ -138,6 +149,90 @@ fn fetcher_error_for_status(error: reqwest::Error) -> FetchError {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS |
+            StatusCode::INTERNAL_SERVER_ERROR |
+            StatusCode::BAD_GATEWAY |
+            StatusCode::SERVICE_UNAVAILABLE |
+            StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Parses Retry-After header (delay-seconds format only)
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_retry_after(response: &Response) -> Option<Duration> {
+    let seconds: u64 = response.headers()
+        .get(header::RETRY_AFTER)?
+        .to_str().ok()?
+        .trim()
+        .parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Sends a request, retrying on connection errors
+/// and on temporary server errors.
+///
+/// The request is re-built before every attempt.
+#[cfg(not(target_arch = "wasm32"))]
+async fn send_with_retry(
+    policy: &RetryPolicy,
+    mut build_request: impl FnMut() -> Result<RequestBuilder, FetchError>,
+) -> Result<Response, FetchError> {
+    let mut attempt = 1;
+    loop {
+        let result = build_request()?.send().await;
+        let is_last_attempt = attempt >= policy.max_attempts;
+        let delay = match result {
+            Ok(response) => {
+                let status = response.status();
+                if !is_retryable_status(status) || is_last_attempt {
+                    return Ok(response);
+                };
+                let maybe_retry_after = if policy.retry_after_enabled &&
+                    matches!(
+                        status,
+                        StatusCode::TOO_MANY_REQUESTS |
+                            StatusCode::SERVICE_UNAVAILABLE
+                    )
+                {
+                    parse_retry_after(&response)
+                } else {
+                    None
+                };
+                match maybe_retry_after {
+                    // Don't wait longer than allowed by the policy
+                    Some(delay) if delay > policy.max_delay => {
+                        return Ok(response);
+                    },
+                    Some(delay) => delay,
+                    None => policy.backoff_delay(attempt),
+                }
+            },
+            Err(error) => {
+                if !error.is_connect() || is_last_attempt {
+                    return Err(error.into());
+                };
+                policy.backoff_delay(attempt)
+            },
+        };
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+// Timers are not available on wasm32
+#[cfg(target_arch = "wasm32")]
+async fn send_with_retry(
+    _policy: &RetryPolicy,
+    mut build_request: impl FnMut() -> Result<RequestBuilder, FetchError>,
+) -> Result<Response, FetchError> {
+    let response = build_request()?.send().await?;
+    Ok(response)
+}
+
 /// Returns next URL in redirection chain
 fn get_target_url(
     current_url: &Url,
@@ -185,6 +280,13 @@ pub struct FetchedObject {
 }
 
 impl FetchedObject {
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn for_test(value: JsonValue, location: &str) -> Self {
+        let location = Url::parse(location)
+            .expect("location should be a valid URL");
+        Self { value, location }
+    }
+
     /// Verifies the origin of this object
     pub fn verify_origin(&self) -> Result<(), FetchError> {
         let object_id = self.value["id"].as_str()
@@ -232,23 +334,24 @@ pub async fn fetch_object(
     let mut redirect_count = 0;
     let mut target_url = object_url.to_owned();
     let response = loop {
-        let mut request_builder =
-            build_http_request(agent, &client, Method::GET, &target_url)?
-                .header(header::ACCEPT, AP_MEDIA_TYPE);
-
-        if let Some(ref signer) = agent.signer {
-            // Only public instances can send signed requests
-            request_builder = sign_http_request(
-                request_builder,
-                Method::GET,
-                &target_url,
-                None,
-                signer,
-                agent.rfc9421_enabled,
-            )?;
-        };
-        let response = request_builder
-            .send().await?
+        let response = send_with_retry(&agent.fetcher_retry_policy, || {
+            let mut request_builder =
+                build_http_request(agent, &client, Method::GET, &target_url)?
+                    .header(header::ACCEPT, AP_MEDIA_TYPE);
+
+            if let Some(ref signer) = agent.signer {
+                // Only public instances can send signed requests
+                request_builder = sign_http_request(
+                    request_builder,
+                    Method::GET,
+                    &target_url,
+                    None,
+                    signer,
+                    agent.rfc9421_enabled,
+                )?;
+            };
+            Ok(request_builder)
+        }).await?
             .error_for_status()
             .map_err(fetcher_error_for_status)?;
         if !response.status().is_redirection() {
@@ -320,9 +423,11 @@ pub async fn fetch_media(
         url,
         RedirectAction::Follow,
     )?;
-    let request_builder =
-        build_http_request(agent, &client, Method::GET, url)?;
-    let response = request_builder.send().await?.error_for_status()?;
+    let response = send_with_retry(&agent.fetcher_retry_policy, || {
+        let request_builder =
+            build_http_request(agent, &client, Method::GET, url)?;
+        Ok(request_builder)
+    }).await?.error_for_status()?;
     if let Some(content_length) = response.content_length() {
         let content_length: usize = content_length.try_into()
             .map_err(|_| FetchError::ResponseTooLarge)?;
@@ -368,9 +473,11 @@ pub async fn stream_media(
         url,
         RedirectAction::Follow,
     )?;
-    let request_builder =
-        build_http_request(agent, &client, Method::GET, url)?;
-    let response = request_builder.send().await?.error_for_status()?;
+    let response = send_with_retry(&agent.fetcher_retry_policy, || {
+        let request_builder =
+            build_http_request(agent, &client, Method::GET, url)?;
+        Ok(request_builder)
+    }).await?.error_for_status()?;
     let media_type = response.headers()
         .get(header::CONTENT_TYPE)
         .and_then(extract_media_type)
@@ -384,30 +491,45 @@ pub async fn stream_media(
     Ok((stream, media_type))
 }
 
-/// Fetches arbitrary JSON data (unsigned request)
-pub async fn fetch_json(
+/// Fetches arbitrary data (unsigned request)
+///
+/// Returns response body and the final URL.
+pub async fn fetch_data(
     agent: &FederationAgent,
     url: &str,
-    accept: Option<&str>,
-) -> Result<JsonValue, FetchError> {
-    const APPLICATION_JSON: &str = "application/json";
+    accept: &str,
+) -> Result<(Bytes, String), FetchError> {
     // Redirects are allowed
     let client = create_fetcher_client(
         agent,
         url,
         RedirectAction::Follow,
     )?;
-    let request_builder =
-        build_http_request(agent, &client, Method::GET, url)?;
-    let response = request_builder
-        .header(header::ACCEPT, accept.unwrap_or(APPLICATION_JSON))
-        .send()
-        .await?
-        .error_for_status()?;
+    let response = send_with_retry(&agent.fetcher_retry_policy, || {
+        let request_builder =
+            build_http_request(agent, &client, Method::GET, url)?
+                .header(header::ACCEPT, accept);
+        Ok(request_builder)
+    }).await?.error_for_status()?;
     let response_url = response.url().to_string();
     let data = limited_response(response, agent.response_size_limit)
         .await
         .ok_or(FetchError::ResponseTooLarge)?;
+    Ok((data, response_url))
+}
+
+/// Fetches arbitrary JSON data (unsigned request)
+pub async fn fetch_json(
+    agent: &FederationAgent,
+    url: &str,
+    accept: Option<&str>,
+) -> Result<JsonValue, FetchError> {
+    const APPLICATION_JSON: &str = "application/json";
+    let (data, response_url) = fetch_data(
+        agent,
+        url,
+        accept.unwrap_or(APPLICATION_JSON),
+    ).await?;
     let object_json = serde_json::from_slice(&data)
         .map_err(|_| FetchError::JsonParseError(response_url))?;
     Ok(object_json)
@@ -415,9 +537,106 @@ pub async fn fetch_json(
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::Ordering;
     use serde_json::json;
+    use crate::test_utils::{mock_response, start_mock_server};
     use super::*;
 
+    fn response_ok() -> String {
+        mock_response(
+            "200 OK",
+            &[("Content-Type", "application/json")],
+            r#"{"id":"1"}"#,
+        )
+    }
+
+    fn response_unavailable() -> String {
+        mock_response("503 Service Unavailable", &[], "")
+    }
+
+    fn response_too_many_requests(retry_after: &str) -> String {
+        mock_response(
+            "429 Too Many Requests",
+            &[("Retry-After", retry_after)],
+            "",
+        )
+    }
+
+    fn create_test_agent(max_attempts: u32) -> FederationAgent {
+        FederationAgent {
+            ssrf_protection_enabled: false, // allow connections to 127.0.0.1
+            fetcher_retry_policy: RetryPolicy {
+                max_attempts,
+                initial_delay: Duration::from_millis(1),
+                max_delay: Duration::from_millis(10),
+                retry_after_enabled: true,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_json_retry_on_server_error() {
+        let (server_url, request_count) = start_mock_server(|request| {
+            if request.index < 2 { response_unavailable() } else { response_ok() }
+        }).await;
+        let agent = create_test_agent(3);
+        let value = fetch_json(&agent, &server_url, None).await.unwrap();
+        assert_eq!(value, json!({"id": "1"}));
+        assert_eq!(request_count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_json_retry_limit() {
+        let (server_url, request_count) =
+            start_mock_server(|_| response_unavailable()).await;
+        let agent = create_test_agent(2);
+        let error = fetch_json(&agent, &server_url, None).await.err().unwrap();
+        assert!(matches!(error, FetchError::RequestError(_)));
+        assert_eq!(request_count.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_json_retry_after() {
+        let (server_url, request_count) = start_mock_server(|request| {
+            if request.index == 0 {
+                response_too_many_requests("0")
+            } else {
+                response_ok()
+            }
+        }).await;
+        let agent = create_test_agent(3);
+        let value = fetch_json(&agent, &server_url, None).await.unwrap();
+        assert_eq!(value, json!({"id": "1"}));
+        assert_eq!(request_count.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_json_retry_after_exceeds_max_delay() {
+        let (server_url, request_count) = start_mock_server(|request| {
+            if request.index == 0 {
+                response_too_many_requests("3600")
+            } else {
+                response_ok()
+            }
+        }).await;
+        let agent = create_test_agent(3);
+        let error = fetch_json(&agent, &server_url, None).await.err().unwrap();
+        assert!(matches!(error, FetchError::RequestError(_)));
+        assert_eq!(request_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_json_no_retry_on_client_error() {
+        let (server_url, request_count) = start_mock_server(|_| {
+            mock_response("404 Not Found", &[], "")
+        }).await;
+        let agent = create_test_agent(3);
+        let result = fetch_json(&agent, &server_url, None).await;
+        assert!(result.is_err());
+        assert_eq!(request_count.load(Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn test_get_target_url() {
         let current_url = Url::parse("https://social.example/users/1").unwrap();
@@ -64,7 +64,7 @@ pub enum RedirectAction {
 
 // https://www.w3.org/TR/activitypub/#security-localhost
 // https://cheatsheetseries.owasp.org/cheatsheets/Server_Side_Request_Forgery_Prevention_Cheat_Sheet.html
-fn is_safe_addr(ip_addr: IpAddr) -> bool {
+pub fn is_safe_addr(ip_addr: IpAddr) -> bool {
     // Reference:
     // https://www.iana.org/assignments/iana-ipv4-special-registry/iana-ipv4-special-registry.xhtml
     let is_unsafe_ipv4 = |addr: Ipv4Addr| {
@@ -122,7 +122,7 @@ fn is_safe_url(url: &str) -> bool {
 #[error("unsafe URL: {0}")]
 pub struct UnsafeUrlError(String);
 
-fn require_safe_url(url: &str) -> Result<(), UnsafeUrlError> {
+pub fn require_safe_url(url: &str) -> Result<(), UnsafeUrlError> {
     if !is_safe_url(url) {
         return Err(UnsafeUrlError(url.to_string()));
     };
@@ -272,6 +272,10 @@ pub fn build_http_request(
         request_builder = request_builder
             .header(header::USER_AGENT, user_agent);
     };
+    if let Some(ref request_id) = agent.request_id {
+        request_builder = request_builder
+            .header("X-Request-Id", request_id);
+    };
     Ok(request_builder)
 }
 
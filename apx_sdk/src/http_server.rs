@@ -1,9 +1,16 @@
 //! Helper functions for implementing ActivityPub servers.
 
-use apx_core::http_types::{
-    header,
-    HeaderMap,
+#[cfg(feature = "actix-web")]
+pub mod actix;
+
+use apx_core::{
+    http_digest::{ContentDigest, ContentDigestHasher},
+    http_types::{
+        header,
+        HeaderMap,
+    },
 };
+use thiserror::Error;
 
 use super::constants::{AP_MEDIA_TYPE, AS_MEDIA_TYPE};
 use super::utils::extract_media_type;
@@ -31,6 +38,34 @@ pub fn is_activitypub_request(
     MEDIA_TYPES.contains(&media_type.as_str())
 }
 
+#[derive(Debug, Error)]
+#[error("digest mismatch")]
+pub struct DigestMismatch;
+
+/// Verifies content digest of a body that is received in chunks
+pub struct ContentDigestVerifier {
+    expected_digest: ContentDigest,
+    hasher: ContentDigestHasher,
+}
+
+impl ContentDigestVerifier {
+    pub fn new(expected_digest: ContentDigest) -> Self {
+        let hasher = ContentDigestHasher::for_digest(&expected_digest);
+        Self { expected_digest, hasher }
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.hasher.update(chunk);
+    }
+
+    pub fn verify(self) -> Result<(), DigestMismatch> {
+        if self.hasher.finalize() != self.expected_digest {
+            return Err(DigestMismatch);
+        };
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use apx_core::http_types::HeaderValue;
@@ -90,4 +125,18 @@ mod tests {
         let result = is_activitypub_request(&request_headers);
         assert_eq!(result, false);
     }
+
+    #[test]
+    fn test_content_digest_verifier() {
+        let body = "test*123";
+        let expected_digest = ContentDigest::new_sha512(body.as_bytes());
+        let mut verifier = ContentDigestVerifier::new(expected_digest.clone());
+        verifier.update(&body.as_bytes()[..4]);
+        verifier.update(&body.as_bytes()[4..]);
+        assert!(verifier.verify().is_ok());
+
+        let mut verifier = ContentDigestVerifier::new(expected_digest);
+        verifier.update(&body.as_bytes()[..4]);
+        assert!(verifier.verify().is_err());
+    }
 }
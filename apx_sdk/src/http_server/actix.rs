@@ -0,0 +1,397 @@
+//! Extractors for actix-web.
+//!
+//! Requires `actix-web` feature.
+//!
+//! ```no_run
+//! use actix_web::{post, App, HttpResponse};
+//! use apx_sdk::{
+//!     http_server::actix::{
+//!         KeyFuture,
+//!         KeyResolver,
+//!         SignedActivity,
+//!         SignedRequestConfig,
+//!         SignedRequestError,
+//!     },
+//! };
+//!
+//! struct Keys;
+//!
+//! impl KeyResolver for Keys {
+//!     fn resolve_key<'a>(
+//!         &'a self,
+//!         _actor_id: &'a str,
+//!         key_id: &'a str,
+//!     ) -> KeyFuture<'a> {
+//!         // Look up the key in local cache or fetch actor
+//!         Box::pin(async move {
+//!             Err(SignedRequestError::KeyNotFound(key_id.to_owned()))
+//!         })
+//!     }
+//! }
+//!
+//! #[post("/inbox")]
+//! async fn inbox(signed: SignedActivity) -> HttpResponse {
+//!     println!("activity from {}", signed.actor_id);
+//!     HttpResponse::Accepted().finish()
+//! }
+//!
+//! let app = App::new()
+//!     .app_data(SignedRequestConfig::new(Keys))
+//!     .service(inbox);
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use actix_web::{
+    dev::Payload,
+    http::StatusCode,
+    FromRequest,
+    HttpRequest,
+    ResponseError,
+};
+use futures_util::StreamExt;
+use serde_json::{Value as JsonValue};
+use thiserror::Error;
+
+use apx_core::{
+    crypto::common::PublicKey,
+    http_digest::ContentDigestHasher,
+    http_signatures::verify::{
+        parse_http_signature,
+        verify_http_signature,
+        HttpSignatureVerificationError,
+        VerificationMethod,
+    },
+    http_types::{
+        header_map_adapter,
+        method_adapter,
+        Uri,
+    },
+    url::canonical::CanonicalUri,
+};
+
+use crate::{
+    authentication::{verify_portable_object, AuthenticationError},
+    deserialization::object_to_id,
+    utils::key_id_to_actor_id,
+};
+
+const BODY_SIZE_LIMIT: usize = 2_000_000;
+
+/// Errors that may occur when verifying a signed request
+#[derive(Debug, Error)]
+pub enum SignedRequestError {
+    #[error("signed request extractor is not configured")]
+    NotConfigured,
+
+    #[error("payload error: {0}")]
+    PayloadError(String),
+
+    #[error("payload too large")]
+    PayloadTooLarge,
+
+    #[error("invalid JSON")]
+    InvalidJson,
+
+    #[error("invalid actor ID")]
+    InvalidActorId,
+
+    #[error("no HTTP signature")]
+    NoSignature,
+
+    #[error("invalid HTTP signature: {0}")]
+    HttpSignatureError(#[from] HttpSignatureVerificationError),
+
+    #[error("invalid portable object: {0}")]
+    PortableObjectError(#[from] AuthenticationError),
+
+    #[error("unsupported verification method")]
+    UnsupportedVerificationMethod,
+
+    #[error("key not found: {0}")]
+    KeyNotFound(String),
+
+    #[error("actor and request signer do not match")]
+    UnexpectedSigner,
+}
+
+impl ResponseError for SignedRequestError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NotConfigured => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::PayloadError(_) |
+                Self::InvalidJson |
+                Self::InvalidActorId => StatusCode::BAD_REQUEST,
+            _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+pub type KeyFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<PublicKey, SignedRequestError>> + 'a>>;
+
+/// Retrieves public keys of request signers
+pub trait KeyResolver {
+    /// Returns public key with the given ID.
+    ///
+    /// Implementations must verify that the key belongs to the actor.
+    fn resolve_key<'a>(
+        &'a self,
+        actor_id: &'a str,
+        key_id: &'a str,
+    ) -> KeyFuture<'a>;
+}
+
+/// Configuration of signed request extractors.
+///
+/// Should be added to application data.
+#[derive(Clone)]
+pub struct SignedRequestConfig {
+    key_resolver: Rc<dyn KeyResolver>,
+    body_size_limit: usize,
+}
+
+impl SignedRequestConfig {
+    pub fn new(key_resolver: impl KeyResolver + 'static) -> Self {
+        Self {
+            key_resolver: Rc::new(key_resolver),
+            body_size_limit: BODY_SIZE_LIMIT,
+        }
+    }
+
+    pub fn body_size_limit(mut self, limit: usize) -> Self {
+        self.body_size_limit = limit;
+        self
+    }
+}
+
+/// Activity with verified actor.
+///
+/// Accepts requests with HTTP signatures
+/// and portable activities with integrity proofs (FEP-ef61).
+pub struct SignedActivity {
+    /// ID of the verified actor
+    pub actor_id: String,
+    pub activity: JsonValue,
+}
+
+fn is_same_actor(actor_id: &str, signer_id: &str) -> bool {
+    match (CanonicalUri::parse(actor_id), CanonicalUri::parse(signer_id)) {
+        (Ok(actor_id), Ok(signer_id)) => actor_id == signer_id,
+        _ => false,
+    }
+}
+
+async fn verify_request(
+    request: HttpRequest,
+    mut payload: Payload,
+) -> Result<SignedActivity, SignedRequestError> {
+    let config = request.app_data::<SignedRequestConfig>()
+        .ok_or(SignedRequestError::NotConfigured)?
+        .clone();
+    let request_method = method_adapter(request.method());
+    let request_uri = Uri::try_from(request.full_url().as_str())
+        .map_err(|_| SignedRequestError::PayloadError("invalid URI".to_owned()))?;
+    let request_headers = header_map_adapter(request.headers().iter());
+    let maybe_signature = match parse_http_signature(
+        &request_method,
+        &request_uri,
+        &request_headers,
+    ) {
+        Ok(signature_data) => Some(signature_data),
+        Err(HttpSignatureVerificationError::NoSignature) => None,
+        Err(other_error) => return Err(other_error.into()),
+    };
+
+    // Read body and compute its digest
+    let mut maybe_hasher = maybe_signature.as_ref()
+        .and_then(|signature_data| signature_data.content_digest.as_ref())
+        .map(ContentDigestHasher::for_digest);
+    let mut body = Vec::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk
+            .map_err(|error| SignedRequestError::PayloadError(error.to_string()))?;
+        if body.len() + chunk.len() > config.body_size_limit {
+            return Err(SignedRequestError::PayloadTooLarge);
+        };
+        if let Some(ref mut hasher) = maybe_hasher {
+            hasher.update(&chunk);
+        };
+        body.extend_from_slice(&chunk);
+    };
+    let maybe_content_digest = maybe_hasher.map(|hasher| hasher.finalize());
+    let activity: JsonValue = serde_json::from_slice(&body)
+        .map_err(|_| SignedRequestError::InvalidJson)?;
+    let actor_id = object_to_id(&activity["actor"])
+        .map_err(|_| SignedRequestError::InvalidActorId)?;
+
+    // Portable activities are authenticated with integrity proofs
+    match verify_portable_object(&activity) {
+        Ok(activity_id) => {
+            let canonical_actor_id = CanonicalUri::parse(&actor_id)
+                .map_err(|_| SignedRequestError::InvalidActorId)?;
+            match canonical_actor_id {
+                CanonicalUri::Ap(ref ap_uri)
+                    if ap_uri.authority() == activity_id.authority() => (),
+                _ => return Err(SignedRequestError::UnexpectedSigner),
+            };
+            return Ok(SignedActivity { actor_id, activity });
+        },
+        Err(AuthenticationError::NotPortable) => (),
+        Err(AuthenticationError::NoProof) => (),
+        Err(other_error) => return Err(other_error.into()),
+    };
+
+    let signature_data = maybe_signature
+        .ok_or(SignedRequestError::NoSignature)?;
+    let public_key = match signature_data.key_id {
+        VerificationMethod::HttpUri(ref key_id) => {
+            let signer_id = key_id_to_actor_id(key_id.as_str())
+                .map_err(|_| SignedRequestError::UnsupportedVerificationMethod)?;
+            if !is_same_actor(&actor_id, &signer_id) {
+                return Err(SignedRequestError::UnexpectedSigner);
+            };
+            config.key_resolver
+                .resolve_key(&actor_id, key_id.as_str())
+                .await?
+        },
+        VerificationMethod::ApUri(ref key_id) => {
+            let signer_id = key_id.without_fragment().to_string();
+            if !is_same_actor(&actor_id, &signer_id) {
+                return Err(SignedRequestError::UnexpectedSigner);
+            };
+            config.key_resolver
+                .resolve_key(&actor_id, &key_id.to_string())
+                .await?
+        },
+        VerificationMethod::DidUrl(ref did_url) => {
+            // Portable actor: key is derived from DID
+            let canonical_actor_id = CanonicalUri::parse(&actor_id)
+                .map_err(|_| SignedRequestError::InvalidActorId)?;
+            match canonical_actor_id {
+                CanonicalUri::Ap(ref ap_uri)
+                    if ap_uri.authority() == did_url.did() => (),
+                _ => return Err(SignedRequestError::UnexpectedSigner),
+            };
+            let public_key = did_url.did().as_did_key()
+                .ok_or(SignedRequestError::UnsupportedVerificationMethod)?
+                .try_ed25519_key()
+                .map_err(|_| SignedRequestError::UnsupportedVerificationMethod)?;
+            PublicKey::Ed25519(public_key)
+        },
+    };
+    verify_http_signature(
+        &signature_data,
+        &public_key,
+        maybe_content_digest,
+    )?;
+    Ok(SignedActivity { actor_id, activity })
+}
+
+impl FromRequest for SignedActivity {
+    type Error = SignedRequestError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(request: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        Box::pin(verify_request(request.clone(), payload.take()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use apx_core::{
+        crypto::eddsa::{
+            ed25519_public_key_from_secret_key,
+            generate_ed25519_key,
+            Ed25519SecretKey,
+        },
+        http_signatures::create::{
+            create_http_signature_cavage,
+            HttpSigner,
+        },
+        http_types::Method,
+    };
+    use super::*;
+
+    struct TestKeyResolver(Ed25519SecretKey);
+
+    impl KeyResolver for TestKeyResolver {
+        fn resolve_key<'a>(
+            &'a self,
+            _actor_id: &'a str,
+            _key_id: &'a str,
+        ) -> KeyFuture<'a> {
+            let public_key = ed25519_public_key_from_secret_key(&self.0);
+            Box::pin(async move { Ok(PublicKey::Ed25519(public_key)) })
+        }
+    }
+
+    const ACTIVITY: &str = r#"{"id":"https://remote.example/activities/1","type":"Like","actor":"https://remote.example/users/1","object":"https://local.example/objects/1"}"#;
+
+    fn create_signed_request(signer: &HttpSigner) -> TestRequest {
+        let signed_headers = create_http_signature_cavage(
+            Method::POST,
+            "https://local.example/inbox",
+            Some(ACTIVITY.as_bytes()),
+            signer,
+        ).unwrap();
+        TestRequest::post()
+            .uri("https://local.example/inbox")
+            .insert_header(("Host", signed_headers.host))
+            .insert_header(("Date", signed_headers.date))
+            .insert_header(("Digest", signed_headers.digest.unwrap()))
+            .insert_header(("Signature", signed_headers.signature))
+            .set_payload(ACTIVITY)
+    }
+
+    #[actix_web::test]
+    async fn test_signed_activity() {
+        let signer_key = generate_ed25519_key();
+        let signer = HttpSigner::new_ed25519(
+            signer_key,
+            "https://remote.example/users/1#main-key".to_owned(),
+        );
+        let config = SignedRequestConfig::new(TestKeyResolver(signer_key));
+        let (request, mut payload) = create_signed_request(&signer)
+            .app_data(config)
+            .to_http_parts();
+        let signed = SignedActivity::from_request(&request, &mut payload)
+            .await.unwrap();
+        assert_eq!(signed.actor_id, "https://remote.example/users/1");
+        assert_eq!(signed.activity["type"], "Like");
+    }
+
+    #[actix_web::test]
+    async fn test_signed_activity_unexpected_signer() {
+        let signer_key = generate_ed25519_key();
+        let signer = HttpSigner::new_ed25519(
+            signer_key,
+            "https://remote.example/users/2#main-key".to_owned(),
+        );
+        let config = SignedRequestConfig::new(TestKeyResolver(signer_key));
+        let (request, mut payload) = create_signed_request(&signer)
+            .app_data(config)
+            .to_http_parts();
+        let error = SignedActivity::from_request(&request, &mut payload)
+            .await.err().unwrap();
+        assert!(matches!(error, SignedRequestError::UnexpectedSigner));
+    }
+
+    #[actix_web::test]
+    async fn test_signed_activity_no_signature() {
+        let signer_key = generate_ed25519_key();
+        let config = SignedRequestConfig::new(TestKeyResolver(signer_key));
+        let (request, mut payload) = TestRequest::post()
+            .uri("https://local.example/inbox")
+            .set_payload(ACTIVITY)
+            .app_data(config)
+            .to_http_parts();
+        let error = SignedActivity::from_request(&request, &mut payload)
+            .await.err().unwrap();
+        assert!(matches!(error, SignedRequestError::NoSignature));
+    }
+}
@@ -105,6 +105,13 @@ impl JsonResourceDescriptor {
         const GROUP: &str = "Group";
         self.actor_id_for_type(GROUP)
     }
+
+    /// Returns template of the first link with the given relation type
+    pub fn link_template(&self, rel: &str) -> Option<&str> {
+        self.links.iter()
+            .filter(|link| link.rel == rel)
+            .find_map(|link| link.template.as_deref())
+    }
 }
 
 #[cfg(test)]
@@ -226,4 +233,30 @@ mod tests {
             "https://piefed.example/u/user",
         );
     }
+
+    #[test]
+    fn test_jrd_link_template() {
+        let template = "https://social.example/authorize_interaction?uri={uri}";
+        let jrd_value = json!({
+            "subject": "acct:test@social.example",
+            "links": [
+                {
+                    "rel": "self",
+                    "type": "application/activity+json",
+                    "href": "https://social.example/users/test",
+                },
+                {
+                    "rel": "http://ostatus.org/schema/1.0/subscribe",
+                    "template": template,
+                },
+            ],
+        });
+        let jrd: JsonResourceDescriptor =
+            serde_json::from_value(jrd_value).unwrap();
+        assert_eq!(
+            jrd.link_template("http://ostatus.org/schema/1.0/subscribe"),
+            Some(template),
+        );
+        assert_eq!(jrd.link_template("self"), None);
+    }
 }
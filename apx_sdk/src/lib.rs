@@ -55,5 +55,9 @@ pub mod identifiers;
 pub mod jrd;
 pub mod ownership;
 pub mod utils;
+pub mod webfinger;
+
+#[cfg(test)]
+mod test_utils;
 
 pub use apx_core as core;
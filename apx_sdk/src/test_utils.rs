@@ -0,0 +1,63 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+pub struct MockRequest<'a> {
+    /// Sequence number of the request (starting from 0)
+    pub index: usize,
+    pub path: &'a str,
+    pub server_url: &'a str,
+}
+
+pub fn mock_response(
+    status: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> String {
+    let mut response = format!("HTTP/1.1 {status}\r\n");
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    };
+    response.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    response.push_str("Connection: close\r\n\r\n");
+    response.push_str(body);
+    response
+}
+
+/// Starts HTTP server that responds with the output of the handler.
+///
+/// Returns server URL and request counter.
+pub async fn start_mock_server(
+    handler: impl Fn(MockRequest) -> String + Send + 'static,
+) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let server_url = format!("http://{}", listener.local_addr().unwrap());
+    let request_count = Arc::new(AtomicUsize::new(0));
+    let request_count_ref = request_count.clone();
+    let server_url_ref = server_url.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0; 4096];
+            let size = stream.read(&mut buffer).await.unwrap();
+            let request = String::from_utf8_lossy(&buffer[..size]);
+            // Request line: <method> <path> <version>
+            let path = request.split_whitespace().nth(1).unwrap_or("/");
+            let index = request_count_ref.fetch_add(1, Ordering::SeqCst);
+            let response = handler(MockRequest {
+                index,
+                path,
+                server_url: &server_url_ref,
+            });
+            stream.write_all(response.as_bytes()).await.unwrap();
+            stream.shutdown().await.unwrap();
+        };
+    });
+    (server_url, request_count)
+}
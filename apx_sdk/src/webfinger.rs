@@ -0,0 +1,261 @@
+//! WebFinger client.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use regex::Regex;
+use reqwest::StatusCode;
+use serde_json::{Value as JsonValue};
+
+use apx_core::url::{
+    common::url_encode,
+    hostname::guess_protocol,
+};
+
+use super::{
+    addresses::WebfingerAddress,
+    agent::FederationAgent,
+    fetch::{fetch_data, fetch_json, FetchError},
+    jrd::JRD_MEDIA_TYPE,
+};
+
+// https://www.rfc-editor.org/rfc/rfc6415#section-6.3
+const LRDD_RELATION_TYPE: &str = "lrdd";
+const XRD_MEDIA_TYPE: &str = "application/xrd+xml";
+const LRDD_LINK_RE: &str = r#"<Link\s[^>]*rel=["']lrdd["'][^>]*>"#;
+const TEMPLATE_ATTRIBUTE_RE: &str = r#"template=["'](?P<template>[^"']+)["']"#;
+
+pub type CacheFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Storage for WebFinger lookup results
+///
+/// Implementations should handle storage errors internally.
+pub trait WebfingerCache: Send + Sync {
+    /// Returns cached JRD if it exists and is not expired
+    fn get<'a>(
+        &'a self,
+        resource: &'a str,
+    ) -> CacheFuture<'a, Option<JsonValue>>;
+
+    /// Saves JRD
+    fn set<'a>(
+        &'a self,
+        resource: &'a str,
+        jrd: &'a JsonValue,
+        ttl: Duration,
+    ) -> CacheFuture<'a, ()>;
+}
+
+fn parse_lrdd_template_json(host_meta: &JsonValue) -> Option<String> {
+    host_meta["links"].as_array()?.iter()
+        .filter(|link| link["rel"].as_str() == Some(LRDD_RELATION_TYPE))
+        .find_map(|link| link["template"].as_str())
+        .map(|template| template.to_owned())
+}
+
+fn parse_lrdd_template_xml(host_meta: &str) -> Option<String> {
+    let link_re = Regex::new(LRDD_LINK_RE).expect("regexp should be valid");
+    let template_re = Regex::new(TEMPLATE_ATTRIBUTE_RE)
+        .expect("regexp should be valid");
+    let link = link_re.find(host_meta)?;
+    let caps = template_re.captures(link.as_str())?;
+    let template = caps["template"].replace("&amp;", "&");
+    Some(template)
+}
+
+/// Retrieves LRDD template from host-meta document
+/// <https://www.rfc-editor.org/rfc/rfc6415>
+async fn get_lrdd_template(
+    agent: &FederationAgent,
+    server_uri: &str,
+) -> Result<String, FetchError> {
+    let host_meta_uri = format!("{server_uri}/.well-known/host-meta");
+    let (host_meta, _) =
+        fetch_data(agent, &host_meta_uri, XRD_MEDIA_TYPE).await?;
+    let host_meta = String::from_utf8_lossy(&host_meta);
+    if let Some(template) = parse_lrdd_template_xml(&host_meta) {
+        return Ok(template);
+    };
+    // Some servers return JSON
+    let host_meta: JsonValue = serde_json::from_str(&host_meta)
+        .map_err(|_| FetchError::JsonParseError(host_meta_uri.clone()))?;
+    parse_lrdd_template_json(&host_meta)
+        .ok_or(FetchError::NotFound(host_meta_uri))
+}
+
+fn is_not_found(error: &FetchError) -> bool {
+    match error {
+        FetchError::RequestError(error) => {
+            error.status() == Some(StatusCode::NOT_FOUND)
+        },
+        FetchError::NotFound(_) => true,
+        _ => false,
+    }
+}
+
+async fn query_server(
+    agent: &FederationAgent,
+    server_uri: &str,
+    resource: &str,
+) -> Result<JsonValue, FetchError> {
+    let resource_encoded = url_encode(resource);
+    let webfinger_uri = format!(
+        "{server_uri}/.well-known/webfinger?resource={resource_encoded}",
+    );
+    let jrd = match fetch_json(
+        agent,
+        &webfinger_uri,
+        Some(JRD_MEDIA_TYPE),
+    ).await {
+        Ok(jrd) => jrd,
+        Err(error) if is_not_found(&error) => {
+            let template = get_lrdd_template(agent, server_uri)
+                .await
+                .map_err(|_| error)?;
+            let jrd_uri = template.replace("{uri}", &resource_encoded);
+            fetch_json(agent, &jrd_uri, Some(JRD_MEDIA_TYPE)).await?
+        },
+        Err(other_error) => return Err(other_error),
+    };
+    Ok(jrd)
+}
+
+/// Performs WebFinger query and returns JRD.
+///
+/// Falls back to LRDD template from host-meta document
+/// if WebFinger endpoint is not found.
+/// Results are cached if cache is configured.
+pub async fn fetch_jrd(
+    agent: &FederationAgent,
+    webfinger_address: &WebfingerAddress,
+) -> Result<JsonValue, FetchError> {
+    let resource = webfinger_address.to_acct_uri();
+    if let Some(ref cache) = agent.webfinger_cache {
+        if let Some(jrd) = cache.get(&resource).await {
+            return Ok(jrd);
+        };
+    };
+    let hostname = webfinger_address.hostname();
+    let server_uri = format!("{}://{}", guess_protocol(hostname), hostname);
+    let jrd = query_server(agent, &server_uri, &resource).await?;
+    if let Some(ref cache) = agent.webfinger_cache {
+        let ttl = Duration::from_secs(agent.webfinger_cache_ttl);
+        cache.set(&resource, &jrd, ttl).await;
+    };
+    Ok(jrd)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{atomic::Ordering, Arc, Mutex};
+    use serde_json::json;
+    use crate::test_utils::{mock_response, start_mock_server};
+    use super::*;
+
+    #[derive(Default)]
+    struct TestCache(Mutex<HashMap<String, JsonValue>>);
+
+    impl WebfingerCache for TestCache {
+        fn get<'a>(
+            &'a self,
+            resource: &'a str,
+        ) -> CacheFuture<'a, Option<JsonValue>> {
+            let maybe_jrd = self.0.lock().unwrap().get(resource).cloned();
+            Box::pin(async move { maybe_jrd })
+        }
+
+        fn set<'a>(
+            &'a self,
+            resource: &'a str,
+            jrd: &'a JsonValue,
+            _ttl: Duration,
+        ) -> CacheFuture<'a, ()> {
+            self.0.lock().unwrap().insert(resource.to_owned(), jrd.clone());
+            Box::pin(async {})
+        }
+    }
+
+    #[test]
+    fn test_parse_lrdd_template_xml() {
+        let host_meta = r#"<?xml version="1.0" encoding="UTF-8"?>
+<XRD xmlns="http://docs.oasis-open.org/ns/xri/xrd-1.0">
+  <Link rel="lrdd" template="https://social.example/.well-known/webfinger?resource={uri}&amp;format=json"/>
+</XRD>"#;
+        let template = parse_lrdd_template_xml(host_meta).unwrap();
+        assert_eq!(
+            template,
+            "https://social.example/.well-known/webfinger?resource={uri}&format=json",
+        );
+    }
+
+    #[test]
+    fn test_parse_lrdd_template_json() {
+        let host_meta = json!({
+            "links": [{
+                "rel": "lrdd",
+                "template": "https://social.example/.well-known/webfinger?resource={uri}",
+            }],
+        });
+        let template = parse_lrdd_template_json(&host_meta).unwrap();
+        assert_eq!(
+            template,
+            "https://social.example/.well-known/webfinger?resource={uri}",
+        );
+    }
+
+    fn test_jrd() -> JsonValue {
+        json!({
+            "subject": "acct:test@social.example",
+            "links": [],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_query_server_host_meta_fallback() {
+        let (server_url, request_count) = start_mock_server(|request| {
+            if request.path.starts_with("/.well-known/webfinger") {
+                mock_response("404 Not Found", &[], "")
+            } else if request.path == "/.well-known/host-meta" {
+                let host_meta = format!(
+                    r#"<XRD><Link rel="lrdd" template="{}/users/lookup?resource={{uri}}"/></XRD>"#,
+                    request.server_url,
+                );
+                mock_response("200 OK", &[], &host_meta)
+            } else if request.path.starts_with("/users/lookup?resource=acct%3Atest") {
+                mock_response("200 OK", &[], &test_jrd().to_string())
+            } else {
+                mock_response("404 Not Found", &[], "")
+            }
+        }).await;
+        let agent = FederationAgent {
+            ssrf_protection_enabled: false, // allow connections to 127.0.0.1
+            ..Default::default()
+        };
+        let jrd = query_server(
+            &agent,
+            &server_url,
+            "acct:test@social.example",
+        ).await.unwrap();
+        assert_eq!(jrd, test_jrd());
+        assert_eq!(request_count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_jrd_cached() {
+        let cache = TestCache::default();
+        let address = WebfingerAddress::new_unchecked("test", "social.example");
+        cache.set(
+            &address.to_acct_uri(),
+            &test_jrd(),
+            Duration::from_secs(60),
+        ).await;
+        let agent = FederationAgent {
+            webfinger_cache: Some(Arc::new(cache)),
+            ..Default::default()
+        };
+        let jrd = fetch_jrd(&agent, &address).await.unwrap();
+        assert_eq!(jrd, test_jrd());
+    }
+}
@@ -1,7 +1,10 @@
 use anyhow::Error;
 use clap::{CommandFactory, Parser};
 use log::Level;
-use tokio::runtime::Builder;
+use tokio::{
+    runtime::Builder,
+    signal::unix::{signal, SignalKind},
+};
 
 use mitra_adapters::init::{
     check_app_directories,
@@ -10,9 +13,10 @@ use mitra_adapters::init::{
     initialize_app,
     initialize_database,
     initialize_storage,
+    reload_config,
 };
 use mitra_api::server::run_server;
-use mitra_config::SoftwareMetadata;
+use mitra_config::{Config, SoftwareMetadata};
 use mitra_cli::cli::{
     print_completer,
     Command,
@@ -27,6 +31,24 @@ fn get_software_metadata() -> SoftwareMetadata {
     }
 }
 
+/// Reloads configuration on SIGHUP
+fn start_config_reloader(config: Config) -> () {
+    tokio::spawn(async move {
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(stream) => stream,
+            Err(error) => {
+                log::error!("failed to install SIGHUP handler: {error}");
+                return;
+            },
+        };
+        while hangup.recv().await.is_some() {
+            if let Err(error) = reload_config(&config) {
+                log::error!("failed to reload configuration: {error}");
+            };
+        };
+    });
+}
+
 /// Mitra admin CLI
 #[derive(Parser)]
 #[command(name = "mitra", version)]
@@ -47,6 +69,16 @@ async fn run_async() -> Result<(), Error> {
         return Ok(());
     };
 
+    if let Command::Healthcheck(cmd) = opts.command {
+        // Database is not initialized before checking
+        let config = initialize_app(
+            get_software_metadata(),
+            Some(opts.log_level),
+        );
+        let db_pool = create_database_connection_pool(&config);
+        return cmd.execute(&config, &db_pool).await;
+    };
+
     let maybe_override_log_level = match opts.command {
         Command::Server | Command::Worker(_) => {
             // Do not override log level when running a process
@@ -71,6 +103,7 @@ async fn run_async() -> Result<(), Error> {
     let db_pool = create_database_connection_pool(&config);
     let result = match opts.command {
         Command::Server => {
+            start_config_reloader(config.clone());
             start_workers(config.clone(), db_pool.clone());
             let result = run_server(config, db_pool).await;
             result.map_err(Into::into)
@@ -88,13 +121,23 @@ async fn run_async() -> Result<(), Error> {
         Command::AddFilterRule(cmd) => cmd.execute(&db_pool).await,
         Command::RemoveFilterRule(cmd) => cmd.execute(&db_pool).await,
         Command::ListFilterRules(cmd) => cmd.execute(&db_pool).await,
+        Command::AddServerRule(cmd) => cmd.execute(&db_pool).await,
+        Command::UpdateServerRule(cmd) => cmd.execute(&db_pool).await,
+        Command::DeleteServerRule(cmd) => cmd.execute(&db_pool).await,
+        Command::ListServerRules(cmd) => cmd.execute(&db_pool).await,
+        Command::AddAnnouncement(cmd) => cmd.execute(&db_pool).await,
+        Command::DeleteAnnouncement(cmd) => cmd.execute(&db_pool).await,
+        Command::ListAnnouncements(cmd) => cmd.execute(&db_pool).await,
         Command::GenerateInviteCode(cmd) => cmd.execute(&db_pool).await,
         Command::ListInviteCodes(cmd) => cmd.execute(&db_pool).await,
         Command::CreateAccount(cmd) => cmd.execute(&config, &db_pool).await,
         Command::CreateSystemAccount(cmd) => cmd.execute(&config, &db_pool).await,
         Command::ListAccounts(cmd) => cmd.execute(&db_pool).await,
         Command::SetPassword(cmd) => cmd.execute(&db_pool).await,
+        Command::RenameAccount(cmd) => cmd.execute(&config, &db_pool).await,
         Command::SetRole(cmd) => cmd.execute(&db_pool).await,
+        Command::SetFeatureFlag(cmd) => cmd.execute(&db_pool).await,
+        Command::SetStorageQuota(cmd) => cmd.execute(&db_pool).await,
         Command::RevokeOauthTokens(cmd) => cmd.execute(&db_pool).await,
         Command::ImportObject(cmd) => cmd.execute(&config, &db_pool).await,
         Command::LoadReplies(cmd) => cmd.execute(&config, &db_pool).await,
@@ -103,21 +146,32 @@ async fn run_async() -> Result<(), Error> {
         Command::LoadPortableObject(cmd) => cmd.execute(&config, &db_pool).await,
         Command::CreateActivity(cmd) => cmd.execute(&config, &db_pool).await,
         Command::SendActivity(cmd) => cmd.execute(&config, &db_pool).await,
+        Command::ListDeadLetters(cmd) => cmd.execute(&db_pool).await,
+        Command::RequeueDeadLetter(cmd) => cmd.execute(&db_pool).await,
+        Command::PurgeDeadLetters(cmd) => cmd.execute(&db_pool).await,
         Command::DeleteUser(cmd) => cmd.execute(&config, &db_pool).await,
         Command::CreatePost(cmd) => cmd.execute(&config, &db_pool).await,
         Command::ImportPosts(cmd) => cmd.execute(&config, &db_pool).await,
+        Command::MovePosts(cmd) => cmd.execute(&config, &db_pool).await,
+        Command::ImportPleroma(cmd) => cmd.execute(&config, &db_pool).await,
         Command::ExportPosts(cmd) => cmd.execute(&config, &db_pool).await,
         Command::DeletePost(cmd) => cmd.execute(&config, &db_pool).await,
         Command::AddEmoji(cmd) => cmd.execute(&config, &db_pool).await,
         Command::ImportEmoji(cmd) => cmd.execute(&config, &db_pool).await,
         Command::DeleteEmoji(cmd) => cmd.execute(&config, &db_pool).await,
+        Command::ListEmojis(cmd) => cmd.execute(&db_pool).await,
+        Command::PruneEmojis(cmd) => cmd.execute(&config, &db_pool).await,
         Command::DeleteExtraneousPosts(cmd) => cmd.execute(&config, &db_pool).await,
         Command::PruneReposts(cmd) => cmd.execute(&config, &db_pool).await,
         Command::DeleteUnusedAttachments(cmd) => cmd.execute(&config, &db_pool).await,
         Command::DeleteEmptyProfiles(cmd) => cmd.execute(&config, &db_pool).await,
         Command::ListLocalFiles(cmd) => cmd.execute(&config, &db_pool).await,
         Command::DeleteOrphanedFiles(cmd) => cmd.execute(&config, &db_pool).await,
+        Command::VerifyMedia(cmd) => cmd.execute(&config, &db_pool).await,
+        Command::ActivitypubObjects(cmd) => cmd.execute(&config, &db_pool).await,
         Command::ListUnreachableActors(cmd) => cmd.execute(&config, &db_pool).await,
+        Command::MergeProfiles(cmd) => cmd.execute(&db_pool).await,
+        Command::UnmergeProfile(cmd) => cmd.execute(&db_pool).await,
         Command::CheckUris(cmd) => cmd.execute(&config, &db_pool).await,
         Command::CreateMoneroWallet(cmd) => cmd.execute(&config).await,
         Command::CreateMoneroSignature(cmd) => cmd.execute(&config).await,
@@ -126,7 +180,12 @@ async fn run_async() -> Result<(), Error> {
         Command::RepairInvoice(cmd) => cmd.execute(&config, &db_pool).await,
         Command::ListActiveAddresses(cmd) => cmd.execute(&config).await,
         Command::GetPaymentAddress(cmd) => cmd.execute(&config, &db_pool).await,
+        Command::GrantSubscription(cmd) => cmd.execute(&config, &db_pool).await,
+        Command::ListFeeds(cmd) => cmd.execute(&db_pool).await,
+        Command::UpdateFeed(cmd) => cmd.execute(&config, &db_pool).await,
         Command::InstanceReport(cmd) => cmd.execute(&config, &db_pool).await,
+        Command::GenerateFixtures(cmd) => cmd.execute(&config, &db_pool).await,
+        Command::Healthcheck(_) => unreachable!(),
         Command::Completion { .. } => unreachable!(),
     };
     result
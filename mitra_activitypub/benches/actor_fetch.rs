@@ -0,0 +1,79 @@
+//! Actor fetch storm: many servers request the same actor document
+//! at once (for example, after a post goes viral).
+//!
+//! Run with `cargo bench -p mitra_activitypub --bench actor_fetch`
+use std::thread;
+use std::time::{Duration, Instant};
+
+use apx_core::url::http_uri::HttpUri;
+use chrono::Utc;
+
+use mitra_activitypub::{
+    actors::{builders::build_local_actor, cache::ActorCache},
+    authority::Authority,
+};
+use mitra_models::{
+    accounts::types::User,
+    profiles::types::DbActorProfile,
+};
+use mitra_services::media::MediaServer;
+
+const INSTANCE_URI: &str = "https://server.example";
+const WORKERS: usize = 16;
+const REQUESTS_PER_WORKER: usize = 1000;
+
+fn build_actor_json(user: &User) -> String {
+    let instance_uri = HttpUri::parse(INSTANCE_URI)
+        .expect("URI should be valid");
+    let authority = Authority::server(&instance_uri);
+    let media_server = MediaServer::for_test(INSTANCE_URI);
+    let actor = build_local_actor(&authority, &media_server, user)
+        .expect("actor should be built");
+    serde_json::to_string(&actor).expect("actor should be serializable")
+}
+
+fn run_storm(handle_request: impl Fn() -> usize + Sync) -> Duration {
+    let started_at = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..WORKERS {
+            scope.spawn(|| {
+                for _ in 0..REQUESTS_PER_WORKER {
+                    std::hint::black_box(handle_request());
+                };
+            });
+        };
+    });
+    started_at.elapsed()
+}
+
+fn main() {
+    let mut profile = DbActorProfile::local_for_test("test");
+    profile.bio = Some("bio ".repeat(100));
+    profile.updated_at = Utc::now();
+    let user = User { profile, ..Default::default() };
+    let profile_id = user.id;
+    let updated_at = user.profile.updated_at;
+
+    let uncached_time = run_storm(|| build_actor_json(&user).len());
+
+    let actor_cache = ActorCache::default();
+    let cached_time = run_storm(|| {
+        match actor_cache.get(profile_id, updated_at, 0) {
+            Some(actor_json) => actor_json.len(),
+            None => {
+                let actor_json = build_actor_json(&user);
+                let length = actor_json.len();
+                actor_cache.set(profile_id, updated_at, 0, actor_json);
+                length
+            },
+        }
+    });
+
+    let requests = WORKERS * REQUESTS_PER_WORKER;
+    for (name, time) in [("uncached", uncached_time), ("cached", cached_time)] {
+        println!(
+            "{name}: {requests} requests in {time:?} ({:?} per request)",
+            time / requests as u32,
+        );
+    };
+}
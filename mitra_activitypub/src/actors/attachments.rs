@@ -16,6 +16,7 @@ use apx_sdk::{
     constants::AP_MEDIA_TYPE,
     deserialization::deserialize_string_array,
 };
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value as JsonValue};
 use uuid::Uuid;
@@ -135,6 +136,7 @@ pub fn parse_identity_proof_fep_c390(
         issuer: signer,
         proof_type: identity_proof_type,
         value: attachment.clone(),
+        verified_at: Some(Utc::now()),
     };
     Ok(proof)
 }
@@ -353,6 +355,7 @@ mod tests {
         assert_eq!(parsed.issuer, identity_proof.issuer);
         assert_eq!(parsed.proof_type, identity_proof.proof_type);
         assert_eq!(parsed.value, identity_proof.value);
+        assert!(parsed.verified_at.is_some());
     }
 
     #[test]
@@ -1,15 +1,16 @@
 use apx_core::{
     crypto::common::KeySerializationError,
+    multihash::encode_sha256_multihash,
     url::canonical::NonCanonicalUri,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value as JsonValue};
 
 use mitra_config::Instance;
 use mitra_models::{
-    accounts::types::ManagedAccount,
+    accounts::types::{ManagedAccount, User},
     profiles::types::{
         ActorType,
         DbActor,
@@ -28,6 +29,7 @@ use crate::{
         MASTODON_CONTEXT,
         MITRA_CONTEXT,
         SCHEMA_ORG_CONTEXT,
+        VCARD_CONTEXT,
         W3C_CID_CONTEXT,
         W3ID_DATA_INTEGRITY_CONTEXT,
         W3ID_SECURITY_CONTEXT,
@@ -35,6 +37,7 @@ use crate::{
     identifiers::{
         local_actor_id,
         local_actor_id_canonical,
+        local_actor_id_unified,
         local_affiliations_collection_path,
         local_instance_actor_id,
         LocalActorCollection,
@@ -71,6 +74,7 @@ fn build_actor_context() -> Context {
             ("discoverable", "toot:discoverable"),
             ("featured", "toot:featured"),
             ("Emoji", "toot:Emoji"),
+            ("vcard", VCARD_CONTEXT),
             ("mitra", MITRA_CONTEXT),
             ("subscribers", "mitra:subscribers"),
             ("VerifiableIdentityStatement", "mitra:VerifiableIdentityStatement"),
@@ -95,6 +99,8 @@ pub struct ActorImage {
     pub object_type: String,
     pub url: String,
     pub media_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest_multibase: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -176,10 +182,19 @@ pub struct Actor {
 
     #[serde(skip_serializing_if = "Vec::is_empty")]
     also_known_as: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    moved_to: Option<String>,
 
     #[serde(skip_serializing_if = "Vec::is_empty")]
     attachment: Vec<JsonValue>,
 
+    #[serde(rename = "vcard:Address", skip_serializing_if = "Option::is_none")]
+    vcard_address: Option<String>,
+    #[serde(rename = "vcard:bday", skip_serializing_if = "Option::is_none")]
+    vcard_bday: Option<NaiveDate>,
+    #[serde(rename = "vcard:pronouns", skip_serializing_if = "Option::is_none")]
+    vcard_pronouns: Option<String>,
+
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tag: Vec<Emoji>,
 
@@ -264,6 +279,8 @@ pub fn build_local_actor(
                 object_type: IMAGE.to_string(),
                 url: media_server.url_for(&file_info.file_name),
                 media_type: file_info.media_type.clone(),
+                digest_multibase: file_info.digest
+                    .map(encode_sha256_multihash),
             };
             Some(actor_image)
         },
@@ -276,6 +293,8 @@ pub fn build_local_actor(
                 object_type: IMAGE.to_string(),
                 url: media_server.url_for(&file_info.file_name),
                 media_type: file_info.media_type.clone(),
+                digest_multibase: file_info.digest
+                    .map(encode_sha256_multihash),
             };
             Some(actor_image)
         },
@@ -331,8 +350,13 @@ pub fn build_local_actor(
     let gateways = authority.is_fep_ef61()
         .then_some(vec![server_uri.to_string()])
         .unwrap_or_default();
+    let mut context = build_actor_context();
+    let maybe_moved_to = account.moved_to().map(|actor_id| actor_id.to_string());
+    if maybe_moved_to.is_some() {
+        context.map.insert("movedTo", "as:movedTo");
+    };
     let actor = Actor {
-        _context: build_actor_context(),
+        _context: context,
         id: actor_id,
         object_type: actor_data.object_type,
         name: profile.display_name.clone(),
@@ -358,7 +382,11 @@ pub fn build_local_actor(
         image: banner,
         summary: profile.bio.clone(),
         also_known_as: aliases,
+        moved_to: maybe_moved_to,
         attachment: attachments,
+        vcard_address: profile.location.clone(),
+        vcard_bday: profile.birthday,
+        vcard_pronouns: profile.pronouns.clone(),
         tag: emojis,
         manually_approves_followers: profile.manually_approves_followers,
         // Some applications don't work properly if this flag is not set
@@ -371,6 +399,27 @@ pub fn build_local_actor(
     Ok(actor)
 }
 
+/// Builds actor document for previous username of a renamed account
+pub fn build_moved_local_actor(
+    authority: &Authority,
+    media_server: &MediaServer,
+    user: &User,
+    previous_username: &str,
+) -> Result<Actor, KeySerializationError> {
+    let current_actor_id = local_actor_id_unified(
+        authority,
+        user.id,
+        &user.profile.username,
+    );
+    let mut previous_user = user.clone();
+    previous_user.profile.username = previous_username.to_string();
+    let mut actor = build_local_actor(authority, media_server, &previous_user)?;
+    actor._context.map.insert("movedTo", "as:movedTo");
+    actor.also_known_as = vec![];
+    actor.moved_to = Some(current_actor_id);
+    Ok(actor)
+}
+
 pub fn build_instance_actor(
     instance: &Instance,
 ) -> Result<Actor, KeySerializationError> {
@@ -404,7 +453,11 @@ pub fn build_instance_actor(
         image: None,
         summary: None,
         also_known_as: vec![],
+        moved_to: None,
         attachment: vec![],
+        vcard_address: None,
+        vcard_bday: None,
+        vcard_pronouns: None,
         tag: vec![],
         manually_approves_followers: false,
         discoverable: false,
@@ -422,7 +475,7 @@ mod tests {
     use serde_json::json;
     use uuid::uuid;
     use mitra_models::{
-        accounts::types::{AutomatedAccountDetailed, User},
+        accounts::types::AutomatedAccountDetailed,
         profiles::types::DbActorProfile,
     };
     use super::*;
@@ -434,6 +487,8 @@ mod tests {
         let instance_uri = HttpUri::parse(INSTANCE_URI).unwrap();
         let mut profile = DbActorProfile::local_for_test("testuser");
         profile.bio = Some("testbio".to_string());
+        profile.pronouns = Some("they/them".to_string());
+        profile.birthday = NaiveDate::from_ymd_opt(1990, 5, 17);
         profile.created_at = DateTime::parse_from_rfc3339("2023-02-24T23:36:38Z")
             .unwrap()
             .with_timezone(&Utc);
@@ -462,6 +517,7 @@ mod tests {
                     "discoverable": "toot:discoverable",
                     "featured": "toot:featured",
                     "Emoji": "toot:Emoji",
+                    "vcard": "http://www.w3.org/2006/vcard/ns#",
                     "mitra": "http://jsonld.mitra.social#",
                     "subscribers": "mitra:subscribers",
                     "VerifiableIdentityStatement": "mitra:VerifiableIdentityStatement",
@@ -514,6 +570,8 @@ mod tests {
                 ],
             },
             "summary": "testbio",
+            "vcard:bday": "1990-05-17",
+            "vcard:pronouns": "they/them",
             "manuallyApprovesFollowers": false,
             "discoverable": true,
             "url": "https://server.example/users/testuser",
@@ -544,6 +602,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_local_actor_moved_to() {
+        let instance_uri = HttpUri::parse(INSTANCE_URI).unwrap();
+        let authority = Authority::server(&instance_uri);
+        let media_server = MediaServer::for_test(INSTANCE_URI);
+        let target_id = "https://social.example/users/test";
+        let user = User {
+            profile: DbActorProfile::local_for_test("testuser"),
+            moved_to: Some(target_id.to_string()),
+            ..Default::default()
+        };
+        let actor = build_local_actor(&authority, &media_server, &user).unwrap();
+        assert_eq!(actor.moved_to.unwrap(), target_id);
+        assert_eq!(actor._context.map["movedTo"], "as:movedTo");
+    }
+
+    #[test]
+    fn test_build_moved_local_actor() {
+        let instance_uri = HttpUri::parse(INSTANCE_URI).unwrap();
+        let authority = Authority::server(&instance_uri);
+        let media_server = MediaServer::for_test(INSTANCE_URI);
+        let user = User {
+            profile: DbActorProfile::local_for_test("newname"),
+            ..Default::default()
+        };
+        let actor = build_moved_local_actor(
+            &authority,
+            &media_server,
+            &user,
+            "oldname",
+        ).unwrap();
+        assert_eq!(actor.id, "https://server.example/users/oldname");
+        assert_eq!(actor.preferred_username, "oldname");
+        assert_eq!(
+            actor.moved_to.unwrap(),
+            "https://server.example/users/newname",
+        );
+        assert_eq!(actor._context.map["movedTo"], "as:movedTo");
+    }
+
     #[test]
     fn test_build_local_actor_fep_ef61() {
         let instance_uri = HttpUri::parse(INSTANCE_URI).unwrap();
@@ -581,6 +679,7 @@ mod tests {
                     "discoverable": "toot:discoverable",
                     "featured": "toot:featured",
                     "Emoji": "toot:Emoji",
+                    "vcard": "http://www.w3.org/2006/vcard/ns#",
                     "mitra": "http://jsonld.mitra.social#",
                     "subscribers": "mitra:subscribers",
                     "VerifiableIdentityStatement": "mitra:VerifiableIdentityStatement",
@@ -665,6 +764,7 @@ mod tests {
                     "discoverable": "toot:discoverable",
                     "featured": "toot:featured",
                     "Emoji": "toot:Emoji",
+                    "vcard": "http://www.w3.org/2006/vcard/ns#",
                     "mitra": "http://jsonld.mitra.social#",
                     "subscribers": "mitra:subscribers",
                     "VerifiableIdentityStatement": "mitra:VerifiableIdentityStatement",
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+pub const ACTOR_CACHE_TTL: u64 = 60 * 5; // 5 minutes
+const ACTOR_CACHE_SIZE: usize = 1000;
+
+struct CachedActor {
+    // Last update time of the profile
+    updated_at: DateTime<Utc>,
+    // Configuration revision
+    config_revision: u64,
+    actor_json: String,
+    cached_at: Instant,
+}
+
+impl CachedActor {
+    fn is_expired(&self) -> bool {
+        self.cached_at.elapsed() >= Duration::from_secs(ACTOR_CACHE_TTL)
+    }
+}
+
+/// Serialized documents of local actors.
+/// Entries are keyed by profile ID and become stale
+/// when profile's last update time changes or configuration is reloaded.
+#[derive(Default)]
+pub struct ActorCache {
+    entries: Mutex<HashMap<Uuid, CachedActor>>,
+}
+
+impl ActorCache {
+    fn entries(&self) -> MutexGuard<'_, HashMap<Uuid, CachedActor>> {
+        self.entries.lock().expect("lock should not be poisoned")
+    }
+
+    pub fn get(
+        &self,
+        profile_id: Uuid,
+        updated_at: DateTime<Utc>,
+        config_revision: u64,
+    ) -> Option<String> {
+        let entries = self.entries();
+        let entry = entries.get(&profile_id)?;
+        if entry.updated_at != updated_at ||
+            entry.config_revision != config_revision ||
+            entry.is_expired()
+        {
+            return None;
+        };
+        Some(entry.actor_json.clone())
+    }
+
+    pub fn set(
+        &self,
+        profile_id: Uuid,
+        updated_at: DateTime<Utc>,
+        config_revision: u64,
+        actor_json: String,
+    ) -> () {
+        let mut entries = self.entries();
+        if entries.len() >= ACTOR_CACHE_SIZE {
+            entries.retain(|_, entry| !entry.is_expired());
+            if entries.len() >= ACTOR_CACHE_SIZE {
+                return;
+            };
+        };
+        let entry = CachedActor {
+            updated_at,
+            config_revision,
+            actor_json,
+            cached_at: Instant::now(),
+        };
+        entries.insert(profile_id, entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+    use mitra_utils::id::generate_ulid;
+    use super::*;
+
+    #[test]
+    fn test_actor_cache() {
+        let cache = ActorCache::default();
+        let profile_id = generate_ulid();
+        let updated_at = Utc::now();
+        let actor_json = r#"{"type":"Person"}"#.to_string();
+        assert_eq!(cache.get(profile_id, updated_at, 0), None);
+        cache.set(profile_id, updated_at, 0, actor_json.clone());
+        assert_eq!(cache.get(profile_id, updated_at, 0), Some(actor_json));
+        // Configuration reloaded
+        assert_eq!(cache.get(profile_id, updated_at, 1), None);
+        // Profile updated
+        let updated_at = updated_at + TimeDelta::seconds(1);
+        assert_eq!(cache.get(profile_id, updated_at, 0), None);
+    }
+}
@@ -1,4 +1,5 @@
 use apx_core::{
+    multihash::decode_sha256_multihash,
     url::{
         canonical::CanonicalUri,
         common::Origin,
@@ -18,6 +19,7 @@ use apx_sdk::{
     },
     fetch::fetch_media,
 };
+use chrono::NaiveDate;
 use serde::{
     Deserialize,
     Deserializer,
@@ -26,6 +28,7 @@ use serde::{
 use serde_json::{Value as JsonValue};
 use uuid::Uuid;
 
+use mitra_adapters::profiles::truncate_remote_profile_data;
 use mitra_models::{
     activitypub::queries::save_actor,
     database::{
@@ -34,7 +37,12 @@ use mitra_models::{
     },
     filter_rules::types::FilterAction,
     media::types::{MediaInfo, PartialMediaInfo},
-    profiles::queries::{create_profile, update_profile},
+    profiles::helpers::link_aliases,
+    profiles::queries::{
+        create_profile,
+        get_revoked_identity_proofs,
+        update_profile,
+    },
     profiles::types::{
         ActorType,
         DbActor,
@@ -46,6 +54,7 @@ use mitra_models::{
         PaymentOption,
         ProfileCreateData,
         ProfileUpdateData,
+        PublicKeys,
         WebfingerHostname,
     },
 };
@@ -133,6 +142,16 @@ impl Actor {
             .map_err(|_| ValidationError("invalid actor ID"))?;
         Ok(canonical_actor_id.origin() == local_origin)
     }
+
+    /// Returns `true` if actor document presents keys
+    /// that are different from the stored ones
+    pub fn have_public_keys_changed(
+        &self,
+        profile: &DbActorProfile,
+    ) -> Result<bool, ValidationError> {
+        let public_keys = parse_public_keys(&self.inner)?;
+        Ok(have_public_keys_changed(&profile.public_keys, &public_keys))
+    }
 }
 
 fn deserialize_image_opt<'de, D>(
@@ -175,6 +194,24 @@ fn deserialize_url_opt<'de, D>(
     Ok(maybe_url)
 }
 
+// Invalid values are ignored
+fn deserialize_string_opt<'de, D>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error>
+    where D: Deserializer<'de>
+{
+    let maybe_value: Option<JsonValue> = Option::deserialize(deserializer)?;
+    let maybe_string = match maybe_value {
+        Some(JsonValue::String(value)) => Some(value),
+        Some(value) => {
+            log::warn!("ignoring invalid string: {value}");
+            None
+        },
+        None => None,
+    };
+    Ok(maybe_string)
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct Endpoints {
@@ -225,6 +262,25 @@ struct ValidatedActor {
     #[serde(default)]
     manually_approves_followers: bool,
 
+    #[serde(
+        default,
+        rename = "vcard:Address",
+        deserialize_with = "deserialize_string_opt",
+    )]
+    vcard_address: Option<String>,
+    #[serde(
+        default,
+        rename = "vcard:bday",
+        deserialize_with = "deserialize_string_opt",
+    )]
+    vcard_bday: Option<String>,
+    #[serde(
+        default,
+        rename = "vcard:pronouns",
+        deserialize_with = "deserialize_string_opt",
+    )]
+    vcard_pronouns: Option<String>,
+
     #[serde(default, deserialize_with = "deserialize_object_array")]
     tag: Vec<JsonValue>,
 
@@ -244,6 +300,13 @@ impl ValidatedActor {
         }
     }
 
+    fn birthday(&self) -> Option<NaiveDate> {
+        let value = self.vcard_bday.as_ref()?;
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .inspect_err(|_| log::warn!("ignoring invalid birthday: {value}"))
+            .ok()
+    }
+
     fn to_db_actor(&self) -> Result<DbActor, ValidationError> {
         let canonical_actor_id = canonicalize_id(&self.id)?;
         let canonical_inbox = canonicalize_id(&self.inbox)?;
@@ -349,6 +412,7 @@ async fn get_webfinger_hostname(
 
 enum ActorImageResult {
     Some(MediaInfo),
+    Unchanged,
     None,
     Error,
 }
@@ -364,31 +428,73 @@ impl ActorImageResult {
     fn ok_or_default(self, default: Option<PartialMediaInfo>) -> Option<PartialMediaInfo> {
         match self {
             Self::Some(media_info) => Some(PartialMediaInfo::from(media_info)),
+            Self::Unchanged => default,
             Self::None => None,
             Self::Error => default,
         }
     }
 }
 
+/// Returns `true` if stored image doesn't need to be downloaded again
+fn is_actor_image_unchanged(
+    actor_image: &ActorImage,
+    current_image: &PartialMediaInfo,
+    is_proxy_enabled: bool,
+) -> bool {
+    if current_image.url() != Some(&actor_image.url) {
+        return false;
+    };
+    match current_image {
+        PartialMediaInfo::Link { .. } => is_proxy_enabled,
+        PartialMediaInfo::File { file_info, .. } => {
+            if is_proxy_enabled {
+                return false;
+            };
+            match (&actor_image.digest_multibase, file_info.digest) {
+                (Some(digest_multibase), Some(digest)) => {
+                    decode_sha256_multihash(digest_multibase)
+                        .map(|new_digest| new_digest == digest)
+                        .unwrap_or(false)
+                },
+                // Digest is not known, rely on URL
+                _ => true,
+            }
+        },
+    }
+}
+
 async fn fetch_actor_image(
     ap_client: &ApClient,
     moderation_domain: &Hostname,
     actor_image: &Option<ActorImage>,
+    current_image: Option<&PartialMediaInfo>,
 ) -> Result<ActorImageResult, MediaStorageError> {
     let media_limits = &ap_client.limits.media;
-    let is_filter_enabled = ap_client.filter.is_action_required(
-        moderation_domain.as_str(),
-        FilterAction::RejectProfileImages,
-    );
     let maybe_image = if let Some(actor_image) = actor_image {
         if let Err(error) = validate_media_url(&actor_image.url) {
             log::warn!("invalid actor image URL ({error}): {}", actor_image.url);
             return Ok(ActorImageResult::Error);
         };
-        if is_filter_enabled {
+        if ap_client.filter.apply_action(
+            moderation_domain.as_str(),
+            FilterAction::RejectProfileImages,
+        ) {
             log::warn!("actor image removed by filter: {}", actor_image.url);
             return Ok(ActorImageResult::None);
         };
+        let is_proxy_enabled = ap_client.filter.apply_action(
+            moderation_domain.as_str(),
+            FilterAction::ProxyMedia,
+        );
+        if let Some(current_image) = current_image {
+            if is_actor_image_unchanged(
+                actor_image,
+                current_image,
+                is_proxy_enabled,
+            ) {
+                return Ok(ActorImageResult::Unchanged);
+            };
+        };
         match fetch_media(
             &ap_client.agent(),
             &actor_image.url,
@@ -396,10 +502,6 @@ async fn fetch_actor_image(
             media_limits.profile_image_size_limit,
         ).await {
             Ok((file_data, media_type)) => {
-                let is_proxy_enabled = ap_client.filter.is_action_required(
-                    moderation_domain.as_str(),
-                    FilterAction::ProxyMedia,
-                );
                 let media_info = if is_proxy_enabled {
                     log::info!("linked actor image {}", actor_image.url);
                     MediaInfo::link(media_type, actor_image.url.clone())
@@ -426,16 +528,20 @@ async fn fetch_actor_images(
     ap_client: &ApClient,
     moderation_domain: &Hostname,
     actor: &ValidatedActor,
+    current_avatar: Option<&PartialMediaInfo>,
+    current_banner: Option<&PartialMediaInfo>,
 ) -> Result<(ActorImageResult, ActorImageResult), MediaStorageError> {
     let maybe_avatar = fetch_actor_image(
         ap_client,
         moderation_domain,
         &actor.icon,
+        current_avatar,
     ).await?;
     let maybe_banner = fetch_actor_image(
         ap_client,
         moderation_domain,
         &actor.image,
+        current_banner,
     ).await?;
     Ok((maybe_avatar, maybe_banner))
 }
@@ -480,6 +586,18 @@ fn parse_public_keys(
     Ok(keys)
 }
 
+fn have_public_keys_changed(
+    current_keys: &PublicKeys,
+    new_keys: &[DbActorKey],
+) -> bool {
+    let current_keys = current_keys.inner();
+    current_keys.len() != new_keys.len() ||
+        current_keys.iter().zip(new_keys).any(|(current_key, new_key)| {
+            current_key.id != new_key.id ||
+                current_key.key_data != new_key.key_data
+        })
+}
+
 fn parse_attachments(actor: &ValidatedActor) -> (
     Vec<IdentityProof>,
     Vec<PaymentOption>,
@@ -667,7 +785,7 @@ pub async fn create_remote_profile(
 ) -> Result<DbActorProfile, HandlerError> {
     let Actor { inner: actor, value: actor_json } = actor;
     let (server_hostname, webfinger_hostname) = get_webfinger_hostname(
-        &ap_client.agent(),
+        &ap_client.agent_with_webfinger_cache(db_pool),
         ap_client.instance.uri(),
         &actor,
         false,
@@ -676,7 +794,7 @@ pub async fn create_remote_profile(
     validate_actor_data(&actor_data)?;
     let moderation_domain = get_moderation_domain(&actor_data)
         .expect("actor data should be valid");
-    if ap_client.filter.is_action_required(
+    if ap_client.filter.apply_action(
         moderation_domain.as_str(),
         FilterAction::Reject,
     ) {
@@ -687,6 +805,8 @@ pub async fn create_remote_profile(
         ap_client,
         &moderation_domain,
         &actor,
+        None,
+        None,
     ).await?;
     let public_keys = parse_public_keys(&actor)?;
     let (identity_proofs, mut payment_options, proposals, extra_fields) =
@@ -719,10 +839,18 @@ pub async fn create_remote_profile(
         identity_proofs,
         payment_options,
         extra_fields,
+        location: actor.vcard_address.clone(),
+        birthday: actor.birthday(),
+        pronouns: actor.vcard_pronouns.clone(),
         aliases,
         emojis,
         actor_json: Some(actor_data),
     };
+    truncate_remote_profile_data(
+        &ap_client.limits.profiles,
+        &mut profile_data.bio,
+        &mut profile_data.extra_fields,
+    );
     clean_profile_create_data(&mut profile_data)?;
     let db_client = &mut **get_database_client(db_pool).await?;
     let profile = create_profile(db_client, profile_data).await?;
@@ -734,6 +862,7 @@ pub async fn create_remote_profile(
         &actor_json,
         profile.id,
     ).await?;
+    link_aliases(db_client, &profile).await?;
     Ok(profile)
 }
 
@@ -752,7 +881,7 @@ pub async fn update_remote_profile(
     let actor_data = actor.to_db_actor()?;
     assert_eq!(actor_data_old.id, actor_data.id, "actor ID shouldn't change");
     let (server_hostname, webfinger_hostname) = get_webfinger_hostname(
-        &ap_client.agent(),
+        &ap_client.agent_with_webfinger_cache(db_pool),
         ap_client.instance.uri(),
         &actor,
         profile.has_portable_account(),
@@ -764,10 +893,29 @@ pub async fn update_remote_profile(
         ap_client,
         &moderation_domain,
         &actor,
+        profile.avatar.as_ref(),
+        profile.banner.as_ref(),
     ).await?;
     let public_keys = parse_public_keys(&actor)?;
-    let (identity_proofs, mut payment_options, proposals, extra_fields) =
+    if have_public_keys_changed(&profile.public_keys, &public_keys) {
+        log::info!("public keys of {} have changed", actor_data.id);
+    };
+    let (mut identity_proofs, mut payment_options, proposals, extra_fields) =
         parse_attachments(&actor);
+    // Proofs removed from actor document may be presented again
+    // by outdated copies of that document
+    let revoked_proofs = get_revoked_identity_proofs(
+        &**get_database_client(db_pool).await?,
+        profile.id,
+    ).await?;
+    identity_proofs.retain(|proof| {
+        let is_revoked = revoked_proofs.iter()
+            .any(|item| item.issuer == proof.issuer && item.value == proof.value);
+        if is_revoked {
+            log::warn!("ignoring revoked identity proof: {}", proof.issuer);
+        };
+        !is_revoked
+    });
     let subscription_options = fetch_proposals(
         ap_client,
         proposals,
@@ -796,10 +944,18 @@ pub async fn update_remote_profile(
         identity_proofs,
         payment_options,
         extra_fields,
+        location: actor.vcard_address.clone(),
+        birthday: actor.birthday(),
+        pronouns: actor.vcard_pronouns.clone(),
         aliases,
         emojis,
         actor_json: Some(actor_data),
     };
+    truncate_remote_profile_data(
+        &ap_client.limits.profiles,
+        &mut profile_data.bio,
+        &mut profile_data.extra_fields,
+    );
     clean_profile_update_data(&mut profile_data)?;
     let db_client = &mut **get_database_client(db_pool).await?;
     // update_profile() clears unreachable_since
@@ -815,6 +971,7 @@ pub async fn update_remote_profile(
         &actor_json,
         profile.id,
     ).await?;
+    link_aliases(db_client, &profile).await?;
     Ok(profile)
 }
 
@@ -831,8 +988,12 @@ mod tests {
                 rsa_public_key_to_pkcs1_der,
             },
         },
+        multihash::encode_sha256_multihash,
+    };
+    use mitra_models::{
+        media::types::PartialFileInfo,
+        profiles::types::PublicKeyType,
     };
-    use mitra_models::profiles::types::PublicKeyType;
     use super::*;
 
     #[test]
@@ -863,6 +1024,28 @@ mod tests {
         assert!(!is_local);
     }
 
+    #[test]
+    fn test_deserialize_actor_metadata() {
+        let actor_value = serde_json::json!({
+            "id": "https://social.example/users/1",
+            "type": "Person",
+            "preferredUsername": "test",
+            "inbox": "https://social.example/users/1/inbox",
+            "outbox": "https://social.example/users/1/outbox",
+            "vcard:Address": "Paris",
+            "vcard:bday": "1990-05-17",
+            "vcard:pronouns": ["they/them"],
+        });
+        let actor: ValidatedActor =
+            serde_json::from_value(actor_value).unwrap();
+        assert_eq!(actor.vcard_address.as_deref(), Some("Paris"));
+        assert_eq!(
+            actor.birthday(),
+            NaiveDate::from_ymd_opt(1990, 5, 17),
+        );
+        assert_eq!(actor.vcard_pronouns, None);
+    }
+
     #[test]
     fn test_deserialize_image_opt() {
         #[derive(Deserialize)]
@@ -920,4 +1103,77 @@ mod tests {
         assert_eq!(public_keys[1].key_type, PublicKeyType::RsaPkcs1);
         assert_eq!(public_keys[1].key_data, rsa_public_key_der);
     }
+
+    #[test]
+    fn test_is_actor_image_unchanged() {
+        let image_url = "https://social.example/media/avatar.png";
+        let digest = [1; 32];
+        let current_image = PartialMediaInfo::File {
+            file_info: PartialFileInfo {
+                file_name: "avatar.png".to_string(),
+                file_size: Some(1000),
+                digest: Some(digest),
+                media_type: Some("image/png".to_string()),
+            },
+            url: Some(image_url.to_string()),
+        };
+        let mut actor_image = ActorImage {
+            object_type: "Image".to_string(),
+            url: image_url.to_string(),
+            media_type: Some("image/png".to_string()),
+            digest_multibase: None,
+        };
+        assert_eq!(
+            is_actor_image_unchanged(&actor_image, &current_image, false),
+            true,
+        );
+        // Proxying is enabled
+        assert_eq!(
+            is_actor_image_unchanged(&actor_image, &current_image, true),
+            false,
+        );
+
+        actor_image.digest_multibase = Some(encode_sha256_multihash(digest));
+        assert_eq!(
+            is_actor_image_unchanged(&actor_image, &current_image, false),
+            true,
+        );
+        // Different digest
+        actor_image.digest_multibase =
+            Some(encode_sha256_multihash([2; 32]));
+        assert_eq!(
+            is_actor_image_unchanged(&actor_image, &current_image, false),
+            false,
+        );
+        // Different URL
+        actor_image.digest_multibase = None;
+        actor_image.url = "https://social.example/media/new.png".to_string();
+        assert_eq!(
+            is_actor_image_unchanged(&actor_image, &current_image, false),
+            false,
+        );
+    }
+
+    #[test]
+    fn test_have_public_keys_changed() {
+        let key = DbActorKey {
+            id: "https://social.example/users/1#main-key".to_string(),
+            key_type: PublicKeyType::Ed25519,
+            key_data: vec![1; 32],
+        };
+        let current_keys = PublicKeys(vec![key.clone()]);
+        assert_eq!(
+            have_public_keys_changed(&current_keys, std::slice::from_ref(&key)),
+            false,
+        );
+        let new_key = DbActorKey {
+            key_data: vec![2; 32],
+            ..key.clone()
+        };
+        assert_eq!(
+            have_public_keys_changed(&current_keys, &[new_key]),
+            true,
+        );
+        assert_eq!(have_public_keys_changed(&current_keys, &[]), true);
+    }
 }
@@ -1,3 +1,4 @@
-mod attachments;
+pub(crate) mod attachments;
 pub mod builders;
+pub mod cache;
 pub(crate) mod handlers;
@@ -0,0 +1,64 @@
+use mitra_config::Instance;
+use mitra_models::{
+    custom_feeds::{
+        queries::find_custom_feed_posts_to_boost,
+        types::CustomFeedActor,
+    },
+    database::{DatabaseClient, DatabaseError},
+    posts::{
+        queries::{create_post, get_post_by_id},
+        types::{PostCreateData, RelatedPosts, Visibility},
+    },
+};
+
+use crate::builders::announce::prepare_announce;
+
+const BOOST_BATCH_SIZE: u16 = 20;
+
+/// Reposts new posts from custom feed on behalf of the feed actor.
+/// Returns the number of created reposts.
+pub async fn boost_custom_feed_posts(
+    instance: &Instance,
+    db_client: &mut impl DatabaseClient,
+    feed_actor: &CustomFeedActor,
+) -> Result<usize, DatabaseError> {
+    let post_ids = find_custom_feed_posts_to_boost(
+        db_client,
+        feed_actor.feed.id,
+        feed_actor.account.id,
+        BOOST_BATCH_SIZE,
+    ).await?;
+    let mut repost_count = 0;
+    for post_id in post_ids {
+        let mut post = match get_post_by_id(db_client, post_id).await {
+            Ok(post) => post,
+            // Post has been deleted
+            Err(DatabaseError::NotFound(_)) => continue,
+            Err(other_error) => return Err(other_error),
+        };
+        let repost_data =
+            PostCreateData::repost(post.id, Visibility::Public, None);
+        let mut repost = match create_post(
+            db_client,
+            feed_actor.account.id,
+            repost_data,
+        ).await {
+            Ok(repost) => repost,
+            Err(DatabaseError::AlreadyExists(_)) => continue,
+            Err(other_error) => return Err(other_error),
+        };
+        post.repost_count += 1;
+        repost.related_posts = Some(RelatedPosts {
+            repost_of: Some(Box::new(post)),
+            ..Default::default()
+        });
+        prepare_announce(
+            db_client,
+            instance,
+            &feed_actor.account,
+            &repost,
+        ).await?.save_and_enqueue(db_client).await?;
+        repost_count += 1;
+    };
+    Ok(repost_count)
+}
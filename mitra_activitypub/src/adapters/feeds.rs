@@ -0,0 +1,138 @@
+use apx_sdk::{
+    constants::AP_PUBLIC,
+    fetch::fetch_data,
+};
+use chrono::Utc;
+
+use mitra_config::{Config, Instance};
+use mitra_models::{
+    database::{DatabaseClient, DatabaseError},
+    feed_sources::{
+        queries::{add_feed_entry, set_feed_source_fetched},
+        types::FeedSourceDetailed,
+    },
+    posts::{
+        queries::create_post,
+        types::{PostContext, PostCreateData, Visibility},
+    },
+    profiles::types::Origin::Local,
+};
+use mitra_services::{
+    feeds::{parse_feed, Feed, FeedEntry, FEED_MEDIA_TYPES},
+    media::MediaServer,
+};
+use mitra_utils::html::escape_html;
+use mitra_validators::posts::{
+    clean_local_content,
+    clean_title,
+    validate_post_create_data,
+};
+
+use crate::{
+    agent::build_federation_agent,
+    builders::create_note::{prepare_create_note, save_note_recipients},
+    errors::HandlerError,
+};
+
+pub async fn fetch_feed(
+    instance: &Instance,
+    feed_url: &str,
+) -> Result<Feed, HandlerError> {
+    let agent = build_federation_agent(instance, None);
+    let (data, _) = fetch_data(
+        &agent,
+        feed_url,
+        &FEED_MEDIA_TYPES.join(", "),
+    ).await?;
+    let feed = parse_feed(&data)
+        .map_err(|error| HandlerError::ValidationError(error.to_string()))?;
+    Ok(feed)
+}
+
+fn build_post_data(entry: &FeedEntry) -> PostCreateData {
+    let mut content = entry.content.clone().unwrap_or_default();
+    if let Some(ref url) = entry.url {
+        let url = escape_html(url);
+        content += &format!(r#"<p><a href="{url}">{url}</a></p>"#);
+    };
+    PostCreateData {
+        id: None,
+        context: PostContext::Top {
+            group_id: None,
+            object_id: None,
+            audience: Some(AP_PUBLIC.to_owned()),
+        },
+        title: entry.title.as_deref().map(clean_title),
+        content: clean_local_content(&content),
+        content_source: None,
+        language: None,
+        content_map: vec![],
+        visibility: Visibility::Public,
+        is_sensitive: false,
+        poll: None,
+        attachments: vec![],
+        mentions: vec![],
+        tags: vec![],
+        links: vec![],
+        emojis: vec![],
+        url: None,
+        object_id: None,
+        created_at: Utc::now(),
+    }
+}
+
+/// Publishes new feed entries on behalf of the feed actor.
+/// Returns the number of created posts.
+pub async fn publish_feed_entries(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    source: &FeedSourceDetailed,
+    feed: Feed,
+) -> Result<usize, HandlerError> {
+    let instance = config.instance();
+    let media_server = MediaServer::new(config);
+    let mut transaction = db_client.transaction().await
+        .map_err(DatabaseError::from)?;
+    let mut new_entries = vec![];
+    for entry in feed.entries {
+        // All entries are marked as seen,
+        // even if they are not going to be published
+        let is_new = add_feed_entry(
+            &transaction,
+            source.account.id,
+            &entry.id,
+        ).await?;
+        if is_new {
+            new_entries.push(entry);
+        };
+    };
+    let entry_limit = usize::try_from(source.source.entry_limit)
+        .unwrap_or_default();
+    new_entries.truncate(entry_limit);
+    let mut post_count = 0;
+    // Publish older entries first
+    for entry in new_entries.iter().rev() {
+        let post_data = build_post_data(entry);
+        if let Err(error) = validate_post_create_data(&post_data, Local) {
+            log::warn!("skipping feed entry {}: {}", entry.id, error);
+            continue;
+        };
+        let post = create_post(
+            &mut transaction,
+            source.account.id,
+            post_data,
+        ).await?;
+        prepare_create_note(
+            &transaction,
+            &instance,
+            &media_server,
+            &source.account,
+            &post,
+        ).await?.save_and_enqueue(&transaction).await?;
+        save_note_recipients(&transaction, &post).await?;
+        post_count += 1;
+    };
+    set_feed_source_fetched(&transaction, source.account.id).await?;
+    transaction.commit().await.map_err(DatabaseError::from)?;
+    Ok(post_count)
+}
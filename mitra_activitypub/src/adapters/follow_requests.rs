@@ -2,7 +2,10 @@ use uuid::Uuid;
 
 use mitra_config::Instance;
 use mitra_models::{
-    accounts::types::User,
+    accounts::{
+        queries::get_user_by_id,
+        types::User,
+    },
     activitypub::queries::add_relationship,
     database::{DatabaseClient, DatabaseError},
     notifications::helpers::create_follow_request_notification,
@@ -65,6 +68,25 @@ pub async fn accept_and_add_follower(
     Ok(())
 }
 
+/// Returns `true` if follow request should be approved manually
+pub async fn requires_manual_approval(
+    db_client: &impl DatabaseClient,
+    source_profile: &DbActorProfile,
+    target_profile: &DbActorProfile,
+) -> Result<bool, DatabaseError> {
+    if !target_profile.manually_approves_followers {
+        return Ok(false);
+    };
+    let target_user = match get_user_by_id(db_client, target_profile.id).await {
+        Ok(user) => user,
+        // Automated accounts don't have auto-accept rules
+        Err(DatabaseError::NotFound(_)) => return Ok(true),
+        Err(other_error) => return Err(other_error),
+    };
+    let rules = target_user.shared_client_config.follow_request_rules;
+    Ok(!rules.accepts(source_profile))
+}
+
 pub async fn follow_or_create_request(
     db_client: &mut impl DatabaseClient,
     instance: &Instance,
@@ -84,7 +106,11 @@ pub async fn follow_or_create_request(
                     remote_actor,
                     follow_request.id,
                 )?.save_and_enqueue(db_client).await?;
-            } else if target_profile.manually_approves_followers {
+            } else if requires_manual_approval(
+                db_client,
+                &current_user.profile,
+                target_profile,
+            ).await? {
                 create_follow_request_notification(
                     db_client,
                     current_user.id,
@@ -92,6 +118,7 @@ pub async fn follow_or_create_request(
                 ).await?;
             } else {
                 // Auto-accept if local profile is not locked
+                // or if request matches auto-accept rules
                 let authority = Authority::from(instance);
                 accept_and_add_follower(
                     authority.root(),
@@ -113,18 +140,69 @@ mod tests {
     use serial_test::serial;
     use mitra_models::{
         accounts::test_utils::create_test_user,
-        database::test_utils::create_test_database,
+        database::{
+            get_database_client,
+            test_utils::create_test_database,
+        },
+        profiles::{
+            queries::{get_remote_profile_by_actor_id, update_profile},
+            types::ProfileUpdateData,
+        },
         relationships::{
             helpers::create_follow_request,
-            queries::has_relationship,
+            queries::{get_follow_request_by_participants, has_relationship},
             types::RelationshipType,
         },
     };
+    use crate::test_utils::SimulatedNetwork;
     use super::*;
 
     #[tokio::test]
     #[serial]
     async fn test_accept_and_add_follower() {
+        let network = SimulatedNetwork::start(&[
+            "https://a.example",
+            "https://b.example",
+        ]).await;
+        let server_a = network.instance("https://a.example");
+        let server_b = network.instance("https://b.example");
+        let alice = server_a.create_user("alice").await;
+        let bob = server_b.create_user("bob").await;
+        let db_client_a = &mut **get_database_client(&server_a.db_pool).await.unwrap();
+        let profile_data = ProfileUpdateData {
+            manually_approves_followers: true,
+            ..ProfileUpdateData::from(&alice.profile)
+        };
+        update_profile(db_client_a, alice.id, profile_data).await.unwrap();
+        network.follow(server_b, &bob, &server_a.actor_id("alice")).await;
+
+        let bob_on_a = get_remote_profile_by_actor_id(
+            db_client_a,
+            &server_b.actor_id("bob"),
+        ).await.unwrap();
+        let follow_request = get_follow_request_by_participants(
+            db_client_a,
+            bob_on_a.id,
+            alice.id,
+        ).await.unwrap();
+        let authority = Authority::from(&server_a.config.instance());
+        accept_and_add_follower(
+            authority.root(),
+            db_client_a,
+            follow_request.id,
+        ).await.unwrap();
+        let is_follower = has_relationship(
+            db_client_a,
+            bob_on_a.id,
+            alice.id,
+            RelationshipType::Follow,
+        ).await.unwrap();
+        assert!(is_follower);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_accept_and_add_local_follower() {
         let db_client = &mut create_test_database().await;
         let source = create_test_user(db_client, "source").await;
         let target = create_test_user(db_client, "target").await;
@@ -1,3 +1,6 @@
+pub mod custom_feeds;
+pub mod feeds;
 pub mod follow_requests;
 pub mod posts;
+pub mod subscriptions;
 pub mod users;
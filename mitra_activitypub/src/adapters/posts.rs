@@ -1,10 +1,24 @@
+use serde_json::{Value as JsonValue};
+
 use mitra_config::Config;
 use mitra_models::{
-    accounts::queries::get_user_by_id,
+    accounts::{
+        queries::get_user_by_id,
+        types::User,
+    },
     database::{DatabaseClient, DatabaseError},
     posts::{
-        queries::delete_post,
-        types::PostDetailed,
+        helpers::add_related_posts,
+        queries::{
+            create_post,
+            delete_post,
+            delete_repost,
+            get_post_by_id,
+            get_public_post_ids_by_author,
+            get_repost_by_author,
+            reassign_post,
+        },
+        types::{PostCreateData, PostDetailed, RelatedPosts},
     },
 };
 use mitra_services::media::MediaServer;
@@ -12,10 +26,84 @@ use mitra_services::media::MediaServer;
 use crate::{
     builders::{
         add_context_activity::sync_conversation,
+        announce::prepare_announce,
+        create_note::{prepare_create_note, save_note_recipients},
         delete_note::prepare_delete_note,
+        undo_announce::prepare_undo_announce,
     },
+    identifiers::{local_actor_id, LocalActorCollection},
 };
 
+/// Returns Create(Note) activity that would be federated
+/// if the post was created. The post is not saved,
+/// so object IDs in the preview will differ from real ones.
+pub async fn preview_local_post(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    author: &User,
+    post_data: PostCreateData,
+) -> Result<JsonValue, DatabaseError> {
+    let instance = config.instance();
+    let media_server = MediaServer::new(config);
+    let mut transaction = db_client.transaction().await?;
+    let mut post = create_post(&mut transaction, author.id, post_data).await?;
+    add_related_posts(&transaction, vec![&mut post]).await?;
+    let create_note = prepare_create_note(
+        &transaction,
+        &instance,
+        &media_server,
+        author,
+        &post,
+    ).await?;
+    // Discard changes
+    transaction.rollback().await?;
+    Ok(create_note.activity().clone())
+}
+
+/// Saves post and federates Create(Note) activity.
+/// Returns created post and the activity.
+pub async fn publish_local_post(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    author: &User,
+    post_data: PostCreateData,
+    maybe_in_reply_to: Option<PostDetailed>,
+    linked: Vec<PostDetailed>,
+) -> Result<(PostDetailed, JsonValue), DatabaseError> {
+    let instance = config.instance();
+    let mut post = create_post(db_client, author.id, post_data).await?;
+    // Same as add_related_posts
+    post.related_posts = Some(RelatedPosts {
+        in_reply_to: maybe_in_reply_to.map(|mut in_reply_to| {
+            in_reply_to.reply_count += 1;
+            Box::new(in_reply_to)
+        }),
+        repost_of: None,
+        linked: linked,
+        quote_authorization: None,
+    });
+    // Federate
+    let media_server = MediaServer::new(config);
+    let create_note = prepare_create_note(
+        db_client,
+        &instance,
+        &media_server,
+        author,
+        &post,
+    ).await?;
+    let create_note_json = create_note.activity().clone();
+    create_note.save_and_enqueue(db_client).await?;
+    save_note_recipients(db_client, &post).await?;
+    sync_conversation(
+        db_client,
+        &instance,
+        post.expect_conversation(),
+        create_note_json.clone(),
+        post.visibility,
+    ).await?;
+    Ok((post, create_note_json))
+}
+
 // 1. Generate activity
 // 2. Update database
 // 3. Send activity
@@ -47,3 +135,103 @@ pub async fn delete_local_post(
     ).await?;
     Ok(())
 }
+
+/// Changes author of public local posts and reposts.
+/// Notes are deleted on behalf of the previous author
+/// and then created again with new IDs on behalf of the new author.
+/// Reposts are undone and then announced again.
+pub async fn reassign_local_posts(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    from_user: &User,
+    to_user: &User,
+) -> Result<usize, DatabaseError> {
+    let instance = config.instance();
+    let media_server = MediaServer::new(config);
+    let from_actor_id = local_actor_id(
+        instance.uri_str(),
+        &from_user.profile.username,
+    );
+    let to_actor_id = local_actor_id(
+        instance.uri_str(),
+        &to_user.profile.username,
+    );
+    let audience_map: Vec<_> = [
+        LocalActorCollection::Followers,
+        LocalActorCollection::Subscribers,
+    ]
+        .iter()
+        .map(|collection| {
+            (collection.of(&from_actor_id), collection.of(&to_actor_id))
+        })
+        .collect();
+    let post_ids = get_public_post_ids_by_author(db_client, from_user.id).await?;
+    let mut moved_count = 0;
+    for post_id in post_ids {
+        let mut post = get_post_by_id(db_client, post_id).await?;
+        add_related_posts(db_client, vec![&mut post]).await?;
+        let removal = if let Some(repost_of_id) = post.repost_of_id {
+            let reposted = get_post_by_id(db_client, repost_of_id).await?;
+            let repost = get_repost_by_author(
+                db_client,
+                repost_of_id,
+                from_user.id,
+            ).await?;
+            prepare_undo_announce(
+                db_client,
+                &instance,
+                from_user,
+                &reposted,
+                &repost,
+            ).await?
+        } else {
+            prepare_delete_note(
+                db_client,
+                &instance,
+                &media_server,
+                from_user,
+                &post,
+            ).await?
+        };
+        let new_post_id = match reassign_post(
+            db_client,
+            post.id,
+            to_user.id,
+            &audience_map,
+        ).await {
+            Ok(new_post_id) => new_post_id,
+            Err(DatabaseError::AlreadyExists(_)) if post.repost_of_id.is_some() => {
+                // New author has already reposted this post
+                delete_repost(db_client, post.id).await?;
+                removal.save_and_enqueue(db_client).await?;
+                continue;
+            },
+            Err(other_error) => return Err(other_error),
+        };
+        removal.save_and_enqueue(db_client).await?;
+        let mut new_post = get_post_by_id(db_client, new_post_id).await?;
+        add_related_posts(db_client, vec![&mut new_post]).await?;
+        let creation = if new_post.repost_of_id.is_some() {
+            prepare_announce(
+                db_client,
+                &instance,
+                to_user,
+                &new_post,
+            ).await?
+        } else {
+            prepare_create_note(
+                db_client,
+                &instance,
+                &media_server,
+                to_user,
+                &new_post,
+            ).await?
+        };
+        creation.save_and_enqueue(db_client).await?;
+        if new_post.repost_of_id.is_none() {
+            save_note_recipients(db_client, &new_post).await?;
+        };
+        moved_count += 1;
+    };
+    Ok(moved_count)
+}
@@ -0,0 +1,37 @@
+use mitra_adapters::payments::subscriptions::create_or_update_local_subscription;
+use mitra_config::Instance;
+use mitra_models::{
+    accounts::types::User,
+    database::{DatabaseClient, DatabaseError},
+    profiles::types::DbActorProfile,
+    subscriptions::types::Subscription,
+};
+
+use crate::builders::add_person::prepare_add_subscriber;
+
+/// Grants subscription without payment
+/// (e.g. as a gift or after out-of-band payment)
+pub async fn grant_subscription(
+    instance: &Instance,
+    db_client: &mut impl DatabaseClient,
+    subscriber: &DbActorProfile,
+    recipient: &User,
+    duration_secs: i64,
+) -> Result<Subscription, DatabaseError> {
+    let subscription = create_or_update_local_subscription(
+        db_client,
+        subscriber, // sender
+        recipient,
+        duration_secs,
+    ).await?;
+    if let Some(ref remote_subscriber) = subscriber.actor_json {
+        prepare_add_subscriber(
+            instance,
+            remote_subscriber,
+            recipient,
+            subscription.expires_at,
+            None, // no invoice
+        ).save_and_enqueue(db_client).await?;
+    };
+    Ok(subscription)
+}
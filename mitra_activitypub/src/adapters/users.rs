@@ -1,12 +1,17 @@
 use mitra_config::Config;
 use mitra_models::{
-    accounts::types::ManagedAccount,
+    accounts::{
+        queries::{get_user_by_id, rename_user, set_user_moved_to},
+        types::{ManagedAccount, User},
+    },
     activitypub::queries::save_actor,
     database::{DatabaseClient, DatabaseError},
+    notifications::helpers::create_move_notification,
     profiles::{
-        queries::delete_profile,
-        types::{DbActor, DbActorProfile},
+        queries::{delete_profile, update_profile},
+        types::{DbActor, DbActorProfile, ProfileUpdateData},
     },
+    relationships::queries::{get_followers, unfollow},
 };
 use mitra_services::media::MediaServer;
 
@@ -15,9 +20,14 @@ use crate::{
         build_local_actor,
         local_actor_data,
     },
+    adapters::follow_requests::follow_or_create_request,
     authority::{Authority, AuthorityRoot},
-    builders::delete_person::prepare_delete_person,
-    identifiers::local_actor_id_canonical,
+    builders::{
+        delete_person::prepare_delete_person,
+        move_person::prepare_move_person,
+        update_person::prepare_update_person,
+    },
+    identifiers::{local_actor_id, local_actor_id_canonical, profile_actor_id},
 };
 
 pub fn get_actor_data(
@@ -67,3 +77,149 @@ pub async fn delete_account(
     activity.save_and_enqueue(db_client).await?;
     Ok(())
 }
+
+/// Changes username of a local user and moves remote followers
+/// from the previous actor ID to the new one
+pub async fn rename_account(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    user: &User,
+    new_username: &str,
+) -> Result<User, DatabaseError> {
+    let instance = config.instance();
+    let media_server = MediaServer::new(config);
+    let previous_username = user.profile.username.clone();
+    let previous_actor_id =
+        local_actor_id(instance.uri_str(), &previous_username);
+    let mut transaction = db_client.transaction().await?;
+    let mut renamed_user = rename_user(
+        &mut transaction,
+        user.id,
+        new_username,
+    ).await?;
+    // New actor must declare the previous one as an alias
+    let mut profile_data = ProfileUpdateData::from(&renamed_user.profile);
+    if !profile_data.aliases.contains(&previous_actor_id) {
+        profile_data.aliases.push(previous_actor_id);
+    };
+    // Media cleanup is not needed
+    let (updated_profile, _) = update_profile(
+        &mut transaction,
+        renamed_user.id,
+        profile_data,
+    ).await?;
+    renamed_user.profile = updated_profile;
+    // Document of the previous actor is built on request
+    create_or_update_local_actor(config, &mut transaction, &renamed_user).await?;
+    prepare_update_person(
+        &transaction,
+        &instance,
+        &media_server,
+        &renamed_user,
+    ).await?.save_and_enqueue(&transaction).await?;
+    // Move(Person) is signed with the key of the previous actor
+    let mut previous_user = renamed_user.clone();
+    previous_user.profile.username = previous_username;
+    let remote_followers = get_followers(&transaction, renamed_user.id).await?
+        .into_iter()
+        .filter_map(|follower| follower.actor_json)
+        .collect();
+    let current_actor_id =
+        local_actor_id(instance.uri_str(), &renamed_user.profile.username);
+    prepare_move_person(
+        &instance,
+        &previous_user,
+        &current_actor_id,
+        false, // push mode
+        remote_followers,
+    ).save_and_enqueue(&transaction).await?;
+    transaction.commit().await?;
+    Ok(renamed_user)
+}
+
+/// Moves followers of a local user to a remote actor
+/// and marks local account as moved
+pub async fn move_account(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    user: &User,
+    target: &DbActorProfile,
+) -> Result<User, DatabaseError> {
+    let instance = config.instance();
+    let media_server = MediaServer::new(config);
+    let authority = Authority::from(&instance);
+    let target_actor_id = profile_actor_id(&authority, target);
+    let mut transaction = db_client.transaction().await?;
+    let followers = get_followers(&transaction, user.id).await?;
+    let mut remote_followers = vec![];
+    for follower in followers {
+        if follower.id == target.id {
+            continue;
+        };
+        if let Some(remote_actor) = follower.actor_json {
+            remote_followers.push(remote_actor);
+            continue;
+        };
+        let follower = get_user_by_id(&transaction, follower.id).await?;
+        unfollow(&mut transaction, follower.id, user.id).await?;
+        follow_or_create_request(
+            &mut transaction,
+            &instance,
+            &follower,
+            target,
+        ).await?;
+        create_move_notification(
+            &transaction,
+            target.id,
+            follower.id,
+        ).await?;
+    };
+    // Mark account as moved (movedTo property will be added to actor)
+    set_user_moved_to(
+        &mut transaction,
+        user.id,
+        Some(&target_actor_id),
+    ).await?;
+    let mut moved_user = user.clone();
+    moved_user.moved_to = Some(target_actor_id.clone());
+    create_or_update_local_actor(config, &mut transaction, &moved_user).await?;
+    prepare_update_person(
+        &transaction,
+        &instance,
+        &media_server,
+        &moved_user,
+    ).await?.save_and_enqueue(&transaction).await?;
+    prepare_move_person(
+        &instance,
+        &moved_user,
+        &target_actor_id,
+        false, // push mode
+        remote_followers,
+    ).save_and_enqueue(&transaction).await?;
+    transaction.commit().await?;
+    Ok(moved_user)
+}
+
+/// Removes movedTo property from the actor of a local user.
+/// Followers are not moved back.
+pub async fn cancel_account_move(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    user: &User,
+) -> Result<User, DatabaseError> {
+    let instance = config.instance();
+    let media_server = MediaServer::new(config);
+    let mut transaction = db_client.transaction().await?;
+    set_user_moved_to(&mut transaction, user.id, None).await?;
+    let mut updated_user = user.clone();
+    updated_user.moved_to = None;
+    create_or_update_local_actor(config, &mut transaction, &updated_user).await?;
+    prepare_update_person(
+        &transaction,
+        &instance,
+        &media_server,
+        &updated_user,
+    ).await?.save_and_enqueue(&transaction).await?;
+    transaction.commit().await?;
+    Ok(updated_user)
+}
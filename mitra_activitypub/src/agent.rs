@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use apx_core::crypto::rsa::RsaSecretKey;
-use apx_sdk::agent::{FederationAgent, HttpSigner};
+use apx_sdk::agent::{FederationAgent, HttpSigner, RetryPolicy};
 
+use mitra_adapters::correlation::get_correlation_id;
 use mitra_config::Instance;
 use mitra_models::{
     accounts::types::User,
@@ -18,6 +21,7 @@ use super::{
 // Roughly equals to content size limit * collection size limit
 // See also: mitra_validators::posts::CONTENT_MAX_SIZE
 const RESPONSE_SIZE_LIMIT: usize = 2_000_000;
+const WEBFINGER_CACHE_TTL: u64 = 60 * 60; // 1 hour
 
 pub(super) fn build_federation_agent_with_key(
     instance: &Instance,
@@ -31,11 +35,19 @@ pub(super) fn build_federation_agent_with_key(
     } else {
         None
     };
+    let fetcher_retry_policy = RetryPolicy {
+        max_attempts: instance.federation.fetcher_retry_max_attempts.max(1),
+        initial_delay: Duration::from_secs(instance.federation.fetcher_retry_delay),
+        max_delay: Duration::from_secs(instance.federation.fetcher_retry_max_delay),
+        retry_after_enabled: instance.federation.fetcher_retry_after_enabled,
+    };
     FederationAgent {
         user_agent: instance.user_agent.clone(),
+        request_id: get_correlation_id(),
         ssrf_protection_enabled: instance.federation.ssrf_protection_enabled,
         response_size_limit: RESPONSE_SIZE_LIMIT,
         fetcher_timeout: instance.federation.fetcher_timeout,
+        fetcher_retry_policy: fetcher_retry_policy,
         deliverer_timeout: instance.federation.deliverer_timeout,
         proxy_url: instance.federation.proxy_url.clone(),
         onion_proxy_url: instance.federation.onion_proxy_url.clone(),
@@ -43,6 +55,9 @@ pub(super) fn build_federation_agent_with_key(
         no_proxy: instance.federation.no_proxy.clone(),
         signer: maybe_signer,
         rfc9421_enabled: false,
+        // Cache is added by ApClient::agent_with_webfinger_cache
+        webfinger_cache: None,
+        webfinger_cache_ttl: WEBFINGER_CACHE_TTL,
     }
 }
 
@@ -78,8 +93,10 @@ mod tests {
         let instance = Instance::for_test(instance_uri);
         let agent = build_federation_agent(&instance, None);
         assert_eq!(agent.user_agent.is_none(), true);
+        assert_eq!(agent.request_id.is_none(), true);
         assert_eq!(agent.ssrf_protection_enabled, true);
         assert_eq!(agent.response_size_limit, RESPONSE_SIZE_LIMIT);
+        assert_eq!(agent.fetcher_retry_policy.max_attempts, 3);
         assert_eq!(agent.signer.is_none(), true);
     }
 
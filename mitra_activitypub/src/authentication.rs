@@ -48,7 +48,7 @@ use apx_sdk::{
         CoreType,
     },
 };
-use chrono::Utc;
+use chrono::{TimeDelta, Utc};
 use serde_json::{Value as JsonValue};
 use thiserror::Error;
 
@@ -75,6 +75,8 @@ use crate::{
 };
 
 const AUTHENTICATION_FETCHER_TIMEOUT: u64 = 10;
+// Minimum time between key-triggered re-fetches of the same actor
+const KEY_REFRESH_INTERVAL: i64 = 10; // minutes
 
 #[derive(Debug, Error)]
 pub enum AuthenticationError {
@@ -155,27 +157,41 @@ async fn get_signer(
             Err(other_error) => return Err(other_error.into()),
         }
     } else {
-        let mut ap_client = ap_client.clone();
-        ap_client.instance.federation.fetcher_timeout = AUTHENTICATION_FETCHER_TIMEOUT;
-        match ActorIdResolver::default().only_remote().resolve(
-            &ap_client,
+        resolve_signer(
+            ap_client,
             db_pool,
             signer_id,
-        ).await {
-            Ok(profile) => profile,
-            Err(HandlerError::DatabaseError(DatabaseError::NotFound(_))) => {
-                return Err(AuthenticationError::ActorNotFound(signer_id.to_string()));
-            },
-            Err(HandlerError::DatabaseError(error)) => return Err(error.into()),
-            Err(other_error) => {
-                return Err(AuthenticationError::ImportError(other_error.to_string()));
-            },
-        }
+            ActorIdResolver::default().only_remote(),
+        ).await?
     };
     assert!(!signer.is_local(), "signer should not be local actor");
     Ok(signer)
 }
 
+async fn resolve_signer(
+    ap_client: &ApClient,
+    db_pool: &DatabaseConnectionPool,
+    signer_id: &str,
+    resolver: ActorIdResolver,
+) -> Result<DbActorProfile, AuthenticationError> {
+    let mut ap_client = ap_client.clone();
+    ap_client.instance.federation.fetcher_timeout = AUTHENTICATION_FETCHER_TIMEOUT;
+    match resolver.resolve(
+        &ap_client,
+        db_pool,
+        signer_id,
+    ).await {
+        Ok(profile) => Ok(profile),
+        Err(HandlerError::DatabaseError(DatabaseError::NotFound(_))) => {
+            Err(AuthenticationError::ActorNotFound(signer_id.to_string()))
+        },
+        Err(HandlerError::DatabaseError(error)) => Err(error.into()),
+        Err(other_error) => {
+            Err(AuthenticationError::ImportError(other_error.to_string()))
+        },
+    }
+}
+
 fn get_signer_key(
     profile: &DbActorProfile,
     key_id: &str,
@@ -219,6 +235,35 @@ fn get_signer_key(
     Ok(public_key)
 }
 
+/// Returns signer's key.
+/// If the key is not found, stored keys are considered outdated
+/// (e.g. after key rotation) and signer's profile is re-fetched.
+async fn get_signer_key_or_refresh(
+    ap_client: &ApClient,
+    db_pool: &DatabaseConnectionPool,
+    signer: DbActorProfile,
+    key_id: &str,
+    no_fetch: bool,
+) -> Result<(DbActorProfile, PublicKey), AuthenticationError> {
+    match get_signer_key(&signer, key_id) {
+        Err(AuthenticationError::KeyNotFound(_)) if !no_fetch &&
+            signer.updated_at < Utc::now() - TimeDelta::minutes(KEY_REFRESH_INTERVAL) =>
+        {
+            log::info!("key {key_id} is not known, re-fetching signer");
+            let signer_id = signer.expect_remote_actor_id().to_owned();
+            let signer = resolve_signer(
+                ap_client,
+                db_pool,
+                &signer_id,
+                ActorIdResolver::default().only_remote().force_refetch(),
+            ).await?;
+            let public_key = get_signer_key(&signer, key_id)?;
+            Ok((signer, public_key))
+        },
+        result => result.map(|public_key| (signer, public_key)),
+    }
+}
+
 fn get_signer_ed25519_key(
     profile: &DbActorProfile,
     key_id: &str,
@@ -275,10 +320,13 @@ pub async fn verify_signed_request(
     let signer = get_signer(ap_client, db_pool, &signer_id, no_fetch).await?;
     let key_id = signature_data.key_id.to_string();
     // Check reciprocal claim
-    let public_key = get_signer_key(
-        &signer,
+    let (signer, public_key) = get_signer_key_or_refresh(
+        ap_client,
+        db_pool,
+        signer,
         key_id.as_str(),
-    )?;
+        no_fetch,
+    ).await?;
     if matches!(public_key, PublicKey::Ed25519(_)) {
         log::info!("Ed25519 key found");
     };
@@ -0,0 +1,127 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use mitra_config::Instance;
+use mitra_models::{
+    accounts::types::User,
+    database::DatabaseError,
+    profiles::types::{DbActor, DbActorProfile},
+};
+use mitra_utils::id::generate_ulid;
+
+use crate::{
+    authority::Authority,
+    contexts::{build_default_context, Context},
+    deliverer::Recipient,
+    identifiers::{
+        compatible_id,
+        local_activity_id_unified,
+        local_actor_id_unified,
+        local_quote_authorization_id,
+    },
+    queues::OutgoingActivityJobData,
+    vocabulary::ACCEPT,
+};
+
+#[derive(Serialize)]
+struct AcceptQuoteRequest {
+    #[serde(rename = "@context")]
+    _context: Context,
+
+    #[serde(rename = "type")]
+    activity_type: String,
+
+    id: String,
+    actor: String,
+    object: String,
+    result: String,
+
+    to: Vec<String>,
+}
+
+fn build_accept_quote_request(
+    authority: &Authority,
+    actor_profile: &DbActorProfile,
+    source_actor_id: &str,
+    quote_request_id: &str,
+    authorization_id: Uuid,
+) -> AcceptQuoteRequest {
+    let activity_id = local_activity_id_unified(authority, ACCEPT, generate_ulid());
+    let actor_id = local_actor_id_unified(
+        authority,
+        actor_profile.id,
+        &actor_profile.username,
+    );
+    let instance_uri = authority.expect_server_uri();
+    let authorization_id = local_quote_authorization_id(
+        instance_uri.as_str(),
+        authorization_id,
+    );
+    AcceptQuoteRequest {
+        _context: build_default_context(),
+        activity_type: ACCEPT.to_string(),
+        id: activity_id,
+        actor: actor_id,
+        object: quote_request_id.to_string(),
+        result: authorization_id,
+        to: vec![source_actor_id.to_string()],
+    }
+}
+
+pub fn prepare_accept_quote_request(
+    instance: &Instance,
+    sender: &User,
+    source_actor: &DbActor,
+    quote_request_id: &str,
+    authorization_id: Uuid,
+) -> Result<OutgoingActivityJobData, DatabaseError> {
+    let authority = Authority::from(instance);
+    let source_actor_id = compatible_id(source_actor, &source_actor.id)?;
+    let quote_request_id = compatible_id(source_actor, quote_request_id)?;
+    let activity = build_accept_quote_request(
+        &authority,
+        &sender.profile,
+        &source_actor_id,
+        &quote_request_id,
+        authorization_id,
+    );
+    let recipients = Recipient::for_inbox(source_actor);
+    Ok(OutgoingActivityJobData::new(
+        &authority,
+        sender,
+        activity,
+        recipients,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSTANCE_URI: &str = "https://social.example";
+
+    #[test]
+    fn test_build_accept_quote_request() {
+        let authority = Authority::server_unchecked(INSTANCE_URI);
+        let author = DbActorProfile::local_for_test("user");
+        let quote_request_id = "https://remote.example/quote_requests/1";
+        let requester_id = "https://remote.example/users/123";
+        let authorization_id = generate_ulid();
+        let activity = build_accept_quote_request(
+            &authority,
+            &author,
+            requester_id,
+            quote_request_id,
+            authorization_id,
+        );
+
+        assert_eq!(activity.id.starts_with(INSTANCE_URI), true);
+        assert_eq!(activity.activity_type, "Accept");
+        assert_eq!(activity.object, quote_request_id);
+        assert_eq!(
+            activity.result,
+            format!("{INSTANCE_URI}/objects/quote_authorizations/{authorization_id}"),
+        );
+        assert_eq!(activity.to, vec![requester_id]);
+    }
+}
@@ -5,7 +5,7 @@ use uuid::Uuid;
 
 use mitra_config::Instance;
 use mitra_models::{
-    accounts::types::User,
+    accounts::types::ManagedAccount,
     database::{DatabaseClient, DatabaseError},
     posts::types::{PostDetailed, Visibility},
     profiles::types::DbActor,
@@ -116,14 +116,14 @@ pub fn build_announce(
 
 pub async fn get_announce_recipients(
     db_client: &impl DatabaseClient,
-    current_user: &User,
+    sender: &impl ManagedAccount,
     repost_visibility: Visibility,
     post: &PostDetailed,
 ) -> Result<Vec<Recipient>, DatabaseError> {
     let mut recipients = vec![];
     match repost_visibility {
         Visibility::Public | Visibility::Followers => {
-            let followers = get_followers(db_client, current_user.id).await?;
+            let followers = get_followers(db_client, sender.id()).await?;
             for profile in followers {
                 if let Some(remote_actor) = profile.actor_json {
                     recipients.extend(Recipient::for_inbox(&remote_actor));
@@ -141,10 +141,10 @@ pub async fn get_announce_recipients(
 pub async fn prepare_announce(
     db_client: &impl DatabaseClient,
     instance: &Instance,
-    sender: &User,
+    sender: &impl ManagedAccount,
     repost: &PostDetailed,
 ) -> Result<OutgoingActivityJobData, DatabaseError> {
-    assert_eq!(sender.id, repost.author.id);
+    assert_eq!(sender.id(), repost.author.id);
     let authority = Authority::from(instance);
     let post = repost
         .expect_related_posts()
@@ -27,6 +27,9 @@ pub struct OrderedCollection {
     #[serde(skip_serializing_if = "Option::is_none")]
     first: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     total_items: Option<i32>,
 }
@@ -46,6 +49,7 @@ impl OrderedCollection {
             attributed_to: None,
             ordered_items: None,
             first: first_page_id,
+            next: None,
             total_items,
         }
     }
@@ -61,6 +65,7 @@ impl OrderedCollection {
             attributed_to: None,
             ordered_items: Some(items),
             first: None,
+            next: None,
             total_items: None,
         }
     }
@@ -76,6 +81,7 @@ impl OrderedCollection {
             attributed_to: None,
             ordered_items: Some(items),
             first: None,
+            next: None,
             total_items: None,
         }
     }
@@ -84,6 +90,11 @@ impl OrderedCollection {
         self.attributed_to = Some(attributed_to.to_owned());
         self
     }
+
+    pub fn with_next(mut self, next_page_id: String) -> Self {
+        self.next = Some(next_page_id);
+        self
+    }
 }
 
 #[cfg(test)]
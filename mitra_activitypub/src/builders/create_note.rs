@@ -2,9 +2,12 @@ use serde::Serialize;
 
 use mitra_config::Instance;
 use mitra_models::{
-    accounts::types::User,
+    accounts::types::ManagedAccount,
     database::{DatabaseClient, DatabaseError},
-    posts::types::PostDetailed,
+    posts::{
+        queries::add_post_recipients,
+        types::PostDetailed,
+    },
 };
 use mitra_services::media::MediaServer;
 
@@ -16,7 +19,12 @@ use crate::{
     vocabulary::CREATE,
 };
 
-use super::note::{build_note, get_note_recipients, Note};
+use super::note::{
+    build_note,
+    get_audience_recipients,
+    get_note_audience,
+    Note,
+};
 
 #[derive(Serialize)]
 pub struct CreateNote {
@@ -69,10 +77,10 @@ pub async fn prepare_create_note(
     db_client: &impl DatabaseClient,
     instance: &Instance,
     media_server: &MediaServer,
-    author: &User,
+    author: &impl ManagedAccount,
     post: &PostDetailed,
 ) -> Result<OutgoingActivityJobData, DatabaseError> {
-    assert_eq!(author.id, post.author.id);
+    assert_eq!(author.id(), post.author.id);
     let authority = Authority::from(instance);
     let activity = build_create_note(
         &authority,
@@ -80,7 +88,12 @@ pub async fn prepare_create_note(
         media_server,
         post,
     );
-    let recipients = get_note_recipients(db_client, post).await?;
+    let (primary_audience, secondary_audience) =
+        get_note_audience(db_client, post).await?;
+    let recipients = get_audience_recipients(
+        primary_audience,
+        secondary_audience,
+    );
     Ok(OutgoingActivityJobData::new(
         &authority,
         author,
@@ -89,6 +102,20 @@ pub async fn prepare_create_note(
     ))
 }
 
+/// Records remote primary recipients of a note.
+/// They will not receive Create again when the post is edited.
+pub async fn save_note_recipients(
+    db_client: &impl DatabaseClient,
+    post: &PostDetailed,
+) -> Result<(), DatabaseError> {
+    let (primary_audience, _) = get_note_audience(db_client, post).await?;
+    let primary_recipient_ids: Vec<_> = primary_audience.iter()
+        .filter(|profile| !profile.is_local())
+        .map(|profile| profile.id)
+        .collect();
+    add_post_recipients(db_client, post.id, &primary_recipient_ids).await
+}
+
 #[cfg(test)]
 mod tests {
     use apx_sdk::constants::AP_PUBLIC;
@@ -0,0 +1,124 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use mitra_config::Instance;
+use mitra_models::{
+    database::DatabaseError,
+    posts::types::PostDetailed,
+    profiles::types::DbActor,
+};
+
+use crate::{
+    authority::Authority,
+    contexts::{build_default_context, Context},
+    deliverer::Recipient,
+    identifiers::{
+        compatible_id,
+        compatible_post_object_id,
+        local_activity_id_unified,
+        local_instance_actor_id,
+    },
+    queues::OutgoingActivityJobData,
+    vocabulary::FLAG,
+};
+
+#[derive(Serialize)]
+struct Flag {
+    #[serde(rename = "@context")]
+    _context: Context,
+
+    #[serde(rename = "type")]
+    activity_type: String,
+
+    id: String,
+    actor: String,
+    // Reported actor followed by reported objects
+    object: Vec<String>,
+    content: String,
+}
+
+/// Builds Flag activity.
+/// The activity is sent by the instance actor
+/// and is not addressed to the reported actor,
+/// so the reporter is not revealed.
+fn build_flag(
+    authority: &Authority,
+    report_id: Uuid,
+    target_actor_id: &str,
+    post_ids: Vec<String>,
+    comment: &str,
+) -> Flag {
+    let activity_id = local_activity_id_unified(authority, FLAG, report_id);
+    let actor_id = local_instance_actor_id(
+        authority.expect_server_uri().as_str(),
+    );
+    let mut objects = vec![target_actor_id.to_string()];
+    objects.extend(post_ids);
+    Flag {
+        _context: build_default_context(),
+        activity_type: FLAG.to_string(),
+        id: activity_id,
+        actor: actor_id,
+        object: objects,
+        content: comment.to_string(),
+    }
+}
+
+pub fn prepare_flag(
+    instance: &Instance,
+    report_id: Uuid,
+    target_actor: &DbActor,
+    posts: &[PostDetailed],
+    comment: &str,
+) -> Result<OutgoingActivityJobData, DatabaseError> {
+    let authority = Authority::from(instance);
+    let target_actor_id = compatible_id(target_actor, &target_actor.id)?;
+    let post_ids = posts.iter()
+        .map(|post| compatible_post_object_id(&authority, post))
+        .collect();
+    let activity = build_flag(
+        &authority,
+        report_id,
+        &target_actor_id,
+        post_ids,
+        comment,
+    );
+    let recipients = Recipient::for_shared_inbox(target_actor);
+    Ok(OutgoingActivityJobData::new_from_instance(
+        instance,
+        activity,
+        recipients,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use mitra_utils::id::generate_ulid;
+    use super::*;
+
+    const INSTANCE_URI: &str = "https://social.example";
+
+    #[test]
+    fn test_build_flag() {
+        let authority = Authority::server_unchecked(INSTANCE_URI);
+        let report_id = generate_ulid();
+        let target_actor_id = "https://remote.example/users/123";
+        let post_id = "https://remote.example/objects/456";
+        let activity = build_flag(
+            &authority,
+            report_id,
+            target_actor_id,
+            vec![post_id.to_string()],
+            "spam",
+        );
+
+        assert_eq!(
+            activity.id,
+            format!("{}/activities/flag/{}", INSTANCE_URI, report_id),
+        );
+        assert_eq!(activity.activity_type, "Flag");
+        assert_eq!(activity.actor, "https://social.example/actor");
+        assert_eq!(activity.object, vec![target_actor_id, post_id]);
+        assert_eq!(activity.content, "spam");
+    }
+}
@@ -1,5 +1,6 @@
 pub mod accept_follow;
 pub mod accept_offer;
+pub(crate) mod accept_quote_request;
 pub mod add_context_activity;
 pub mod add_note;
 pub mod add_person;
@@ -13,6 +14,7 @@ pub mod create_question_vote;
 pub(crate) mod delete_note;
 pub(crate) mod delete_person;
 pub mod emoji;
+pub mod flag;
 pub mod follow;
 pub(crate) mod group_announce;
 pub mod like;
@@ -20,7 +22,10 @@ pub mod move_person;
 pub mod note;
 pub mod offer_agreement;
 pub mod proposal;
+pub mod quote_authorization;
+pub mod quote_request;
 pub mod reject_follow;
+pub(crate) mod reject_quote_request;
 pub mod remove_note;
 pub mod remove_person;
 pub mod undo_announce;
@@ -16,12 +16,15 @@ use mitra_models::{
     attachments::types::AttachmentType,
     polls::queries::get_voters,
     posts::{
+        helpers::can_link_post,
         queries::get_post_author,
         types::{PostDetailed, Visibility},
     },
+    profiles::types::DbActorProfile,
     relationships::queries::{get_followers, get_subscribers},
 };
 use mitra_services::media::MediaServer;
+use mitra_utils::languages::to_language_tag;
 
 use crate::{
     authority::Authority,
@@ -110,6 +113,22 @@ struct MediaAttachment {
     media_type: Option<String>,
     digest_multibase: Option<String>,
     url: String,
+    // Per-attachment sensitivity flag (used by Misskey)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sensitive: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InteractionRule {
+    automatic_approval: Vec<String>,
+}
+
+// https://docs.gotosocial.org/en/latest/federation/interaction_policy/
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InteractionPolicy {
+    can_quote: InteractionRule,
 }
 
 #[derive(Serialize)]
@@ -166,6 +185,10 @@ pub struct Note {
     quote: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     quote_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quote_authorization: Option<String>,
+
+    interaction_policy: InteractionPolicy,
 
     published: DateTime<Utc>,
 
@@ -188,7 +211,14 @@ pub fn build_note(
     let attachments: Vec<_> = post.attachments.iter().map(|db_item| {
         // Media is expected to be local (verified on database read)
         let file_info = db_item.media.expect_file_info();
-        let url = media_server.url_for(&file_info.file_name);
+        // Media of non-public posts may be restricted.
+        // Signed URLs expire, so recipients are expected
+        // to download media upon receiving the object.
+        let url = if post.visibility == Visibility::Public {
+            media_server.url_for(&file_info.file_name)
+        } else {
+            media_server.restricted_url_for(&file_info.file_name)
+        };
         let object_type = match db_item.attachment_type() {
             AttachmentType::Image => IMAGE,
             _ => DOCUMENT,
@@ -200,6 +230,7 @@ pub fn build_note(
             digest_multibase: file_info.digest
                 .map(encode_sha256_multihash),
             url,
+            sensitive: db_item.is_sensitive.then_some(true),
         }
     }).collect();
 
@@ -300,6 +331,15 @@ pub fn build_note(
     let maybe_quote_url = related_posts
         .linked.first()
         .map(|linked| compatible_post_object_id(authority, linked));
+    let maybe_quote_authorization = maybe_quote_url.as_ref()
+        .and(related_posts.quote_authorization.clone());
+    // FEP-044f: posts that can be linked are quotable by anyone,
+    // other posts can only be quoted by author
+    let quote_policy = if can_link_post(post) {
+        AP_PUBLIC.to_string()
+    } else {
+        actor_id.clone()
+    };
 
     for emoji in &post.emojis {
         // TODO: FEP-EF61: portable or anonymous emojis?
@@ -368,11 +408,7 @@ pub fn build_note(
         replies: replies_collection_id,
         name: post.title.clone(),
         content: post.content.clone(),
-        content_map: post.language
-            .and_then(|language| language.to_639_1())
-            .map(|code| {
-                HashMap::from([(code.to_owned(), post.content.clone())])
-            }),
+        content_map: build_content_map(post),
         sensitive: post.is_sensitive,
         tag: tags,
         one_of: one_of,
@@ -384,15 +420,36 @@ pub fn build_note(
         audience: group_audience,
         quote: maybe_quote_url.clone(),
         quote_url: maybe_quote_url,
+        quote_authorization: maybe_quote_authorization,
+        interaction_policy: InteractionPolicy {
+            can_quote: InteractionRule {
+                automatic_approval: vec![quote_policy],
+            },
+        },
         published: post.created_at,
         updated: post.updated_at,
     }
 }
 
-pub async fn get_note_recipients(
+fn build_content_map(post: &PostDetailed) -> Option<HashMap<String, String>> {
+    let language = post.language?;
+    let mut content_map = HashMap::from([
+        (to_language_tag(language).to_owned(), post.content.clone()),
+    ]);
+    for variant in post.content_map.iter() {
+        content_map.insert(
+            to_language_tag(variant.language).to_owned(),
+            variant.content.clone(),
+        );
+    };
+    Some(content_map)
+}
+
+/// Returns primary and secondary audiences of a note
+pub async fn get_note_audience(
     db_client: &impl DatabaseClient,
     post: &PostDetailed,
-) -> Result<Vec<Recipient>, DatabaseError> {
+) -> Result<(Vec<DbActorProfile>, Vec<DbActorProfile>), DatabaseError> {
     let mut primary_audience = vec![];
     let mut secondary_audience = vec![];
     match post.visibility {
@@ -424,7 +481,13 @@ pub async fn get_note_recipients(
         let voters = get_voters(db_client, poll.id).await?;
         secondary_audience.extend(voters);
     };
+    Ok((primary_audience, secondary_audience))
+}
 
+pub fn get_audience_recipients(
+    primary_audience: Vec<DbActorProfile>,
+    secondary_audience: Vec<DbActorProfile>,
+) -> Vec<Recipient> {
     let mut recipients = vec![];
     for profile in primary_audience {
         if let Some(remote_actor) = profile.actor_json {
@@ -439,21 +502,43 @@ pub async fn get_note_recipients(
             recipients.extend(Recipient::for_inbox(&remote_actor));
         };
     };
+    recipients
+}
+
+pub async fn get_note_recipients(
+    db_client: &impl DatabaseClient,
+    post: &PostDetailed,
+) -> Result<Vec<Recipient>, DatabaseError> {
+    let (primary_audience, secondary_audience) =
+        get_note_audience(db_client, post).await?;
+    let recipients = get_audience_recipients(
+        primary_audience,
+        secondary_audience,
+    );
     Ok(recipients)
 }
 
 #[cfg(test)]
 mod tests {
     use apx_core::url::http_uri::HttpUri;
+    use chrono::Utc;
     use serde_json::json;
     use uuid::uuid;
     use mitra_models::{
         accounts::types::User,
+        attachments::types::MediaAttachment as DbMediaAttachment,
         conversations::types::Conversation,
+        media::types::MediaInfo,
         polls::types::{Poll, PollResult, PollResults},
-        posts::types::RelatedPosts,
+        posts::types::{ContentVariant, RelatedPosts},
         profiles::types::{DbActor, DbActorProfile},
     };
+    use mitra_services::media::{
+        generate_media_url_key,
+        FilesystemServer,
+        MediaUrlSigner,
+    };
+    use mitra_utils::languages::Language;
     use super::*;
 
     const INSTANCE_URI: &str = "https://server.example";
@@ -535,6 +620,38 @@ mod tests {
         assert_eq!(note.updated, None);
     }
 
+    #[test]
+    fn test_build_note_with_content_map() {
+        let instance_uri = HttpUri::parse(INSTANCE_URI).unwrap();
+        let author = DbActorProfile::local_for_test("author");
+        let post = PostDetailed {
+            author,
+            content: "<p>Hello</p>".to_string(),
+            language: Some(Language::Eng),
+            content_map: vec![ContentVariant {
+                language: Language::Deu,
+                content: "<p>Hallo</p>".to_string(),
+                content_source: None,
+            }],
+            related_posts: Some(RelatedPosts::default()),
+            ..Default::default()
+        };
+        let authority = Authority::server(&instance_uri);
+        let media_server = MediaServer::for_test(INSTANCE_URI);
+        let note = build_note(
+            INSTANCE_HOSTNAME,
+            &authority,
+            &media_server,
+            &post,
+            false,
+        );
+        assert_eq!(note.content, "<p>Hello</p>");
+        let content_map = note.content_map.unwrap();
+        assert_eq!(content_map.len(), 2);
+        assert_eq!(content_map["en"], "<p>Hello</p>");
+        assert_eq!(content_map["de"], "<p>Hallo</p>");
+    }
+
     #[test]
     fn test_build_question() {
         let instance_uri = HttpUri::parse(INSTANCE_URI).unwrap();
@@ -608,6 +725,11 @@ mod tests {
             ],
             "votersCount": 0,
             "endTime": "2023-03-27T12:13:46Z",
+            "interactionPolicy": {
+                "canQuote": {
+                    "automaticApproval": [AP_PUBLIC],
+                },
+            },
             "published": "2023-02-24T23:36:38Z",
             "to": [AP_PUBLIC],
             "cc": ["https://server.example/users/author/followers"],
@@ -639,6 +761,47 @@ mod tests {
         assert_eq!(note.cc.is_empty(), true);
     }
 
+    #[test]
+    fn test_build_note_followers_only_with_attachment() {
+        let instance_uri = HttpUri::parse(INSTANCE_URI).unwrap();
+        let attachment = DbMediaAttachment {
+            id: uuid!("11fa64ff-b5a3-47bf-b23d-22b360581c3f"),
+            owner_id: uuid!("a0d6eb04-cc5c-4b94-8a70-2ee4c6fc3d33"),
+            media: MediaInfo::png_for_test().into(),
+            description: None,
+            ipfs_cid: None,
+            post_id: None,
+            is_sensitive: false,
+            metadata: None,
+            created_at: Utc::now(),
+        };
+        let post = PostDetailed {
+            visibility: Visibility::Followers,
+            attachments: vec![attachment],
+            related_posts: Some(RelatedPosts::default()),
+            ..Default::default()
+        };
+        let authority = Authority::server(&instance_uri);
+        let url_signer = MediaUrlSigner::new(generate_media_url_key(), 3600);
+        let media_server = MediaServer::Filesystem(
+            FilesystemServer::new(INSTANCE_URI).with_url_signer(url_signer),
+        );
+        let note = build_note(
+            INSTANCE_HOSTNAME,
+            &authority,
+            &media_server,
+            &post,
+            true,
+        );
+
+        assert_eq!(note.attachment.len(), 1);
+        let attachment_url = &note.attachment[0].url;
+        assert!(attachment_url.starts_with(
+            "https://server.example/media/test.png?expires=",
+        ));
+        assert!(attachment_url.contains("&signature="));
+    }
+
     #[test]
     fn test_build_note_subscribers_only() {
         let instance_uri = HttpUri::parse(INSTANCE_URI).unwrap();
@@ -698,6 +861,46 @@ mod tests {
         assert_eq!(note.cc.is_empty(), true);
     }
 
+    #[test]
+    fn test_build_note_with_quote() {
+        let instance_uri = HttpUri::parse(INSTANCE_URI).unwrap();
+        let quoted_author = DbActorProfile::remote_for_test(
+            "test",
+            "https://social.example/users/1",
+        );
+        let quoted_id = "https://social.example/objects/1";
+        let quoted = PostDetailed::remote_for_test(&quoted_author, quoted_id);
+        let authorization_id = "https://social.example/quote_authorizations/1";
+        let post = PostDetailed {
+            links: vec![quoted.id],
+            related_posts: Some(RelatedPosts {
+                linked: vec![quoted],
+                quote_authorization: Some(authorization_id.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let authority = Authority::server(&instance_uri);
+        let media_server = MediaServer::for_test(INSTANCE_URI);
+        let note = build_note(
+            INSTANCE_HOSTNAME,
+            &authority,
+            &media_server,
+            &post,
+            false,
+        );
+        assert_eq!(note.quote.as_deref(), Some(quoted_id));
+        assert_eq!(note.quote_url.as_deref(), Some(quoted_id));
+        assert_eq!(
+            note.quote_authorization.as_deref(),
+            Some(authorization_id),
+        );
+        assert_eq!(
+            note.interaction_policy.can_quote.automatic_approval,
+            vec![AP_PUBLIC],
+        );
+    }
+
     #[test]
     fn test_build_note_with_local_parent() {
         let instance_uri = HttpUri::parse(INSTANCE_URI).unwrap();
@@ -871,6 +1074,11 @@ mod tests {
                 },
             ],
             "replies": "https://server.example/objects/11fa64ff-b5a3-47bf-b23d-22b360581c3f/replies",
+            "interactionPolicy": {
+                "canQuote": {
+                    "automaticApproval": ["https://server.example/users/test"],
+                },
+            },
             "published": "2023-02-24T23:36:38Z",
             "to": [
                 "https://social.example/user/test",
@@ -933,6 +1141,11 @@ mod tests {
             "content": "",
             "sensitive": false,
             "replies": "https://server.example/objects/11fa64ff-b5a3-47bf-b23d-22b360581c3f/replies",
+            "interactionPolicy": {
+                "canQuote": {
+                    "automaticApproval": [AP_PUBLIC],
+                },
+            },
             "published": "2023-02-24T23:36:38Z",
             "to": [
                 "https://www.w3.org/ns/activitystreams#Public",
@@ -1009,6 +1222,11 @@ mod tests {
                 "name": "@another@server.example",
                 "href": "https://server.example/.well-known/apgateway/did:key:z6MkvUie7gDQugJmyDQQPhMCCBfKJo7aGvzQYF2BqvFvdwx6/actors/c9386582-c7c3-4e90-8dde-4ab4b1943d96",
             }],
+            "interactionPolicy": {
+                "canQuote": {
+                    "automaticApproval": [AP_PUBLIC],
+                },
+            },
             "published": "2023-02-24T23:36:38Z",
             "to": [
                 "https://www.w3.org/ns/activitystreams#Public",
@@ -0,0 +1,129 @@
+use serde::Serialize;
+
+use mitra_models::{
+    posts::types::PostDetailed,
+    quote_authorizations::types::{
+        QuoteAuthorization as DbQuoteAuthorization,
+    },
+};
+
+use crate::{
+    authority::Authority,
+    contexts::{build_default_context, Context},
+    identifiers::{
+        local_actor_id_unified,
+        local_object_id_unified,
+        local_quote_authorization_id,
+    },
+    vocabulary::QUOTE_AUTHORIZATION,
+};
+
+// https://codeberg.org/fediverse/fep/src/branch/main/fep/044f/fep-044f.md
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteAuthorization {
+    #[serde(rename = "@context")]
+    _context: Context,
+
+    id: String,
+
+    #[serde(rename = "type")]
+    object_type: String,
+
+    attributed_to: String,
+    interacting_object: String,
+    interaction_target: String,
+}
+
+pub fn build_quote_authorization(
+    authority: &Authority,
+    authorization: &DbQuoteAuthorization,
+    quoted_post: &PostDetailed,
+) -> QuoteAuthorization {
+    assert!(authorization.is_local());
+    assert_eq!(authorization.quoted_post_id, quoted_post.id);
+    let instance_uri = authority.expect_server_uri();
+    let authorization_id = local_quote_authorization_id(
+        instance_uri.as_str(),
+        authorization.id,
+    );
+    let actor_id = local_actor_id_unified(
+        authority,
+        quoted_post.author.id,
+        &quoted_post.author.username,
+    );
+    let quoted_post_id = local_object_id_unified(authority, quoted_post.id);
+    let quote_object_id = authorization.quote_object_id.clone()
+        .expect("quote object ID should be present");
+    let mut context = build_default_context();
+    context.map.insert("QuoteAuthorization", "https://w3id.org/fep/044f#QuoteAuthorization");
+    context.map.insert("gts", "https://gotosocial.org/ns#");
+    context.map.insert("interactingObject", "gts:interactingObject");
+    context.map.insert("interactionTarget", "gts:interactionTarget");
+    QuoteAuthorization {
+        _context: context,
+        id: authorization_id,
+        object_type: QUOTE_AUTHORIZATION.to_string(),
+        attributed_to: actor_id,
+        interacting_object: quote_object_id,
+        interaction_target: quoted_post_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use serde_json::json;
+    use uuid::uuid;
+    use mitra_models::profiles::types::DbActorProfile;
+    use super::*;
+
+    const INSTANCE_URI: &str = "https://social.example";
+
+    #[test]
+    fn test_build_quote_authorization() {
+        let authority = Authority::server_unchecked(INSTANCE_URI);
+        let author = DbActorProfile::local_for_test("test");
+        let quoted_post = PostDetailed {
+            id: uuid!("11fa64ff-b5a3-47bf-b23d-22b360581c3f"),
+            ..PostDetailed::local_for_test(&author)
+        };
+        let authorization = DbQuoteAuthorization {
+            id: uuid!("0197a1f6-3bd6-2c8a-e7a4-d1b6a4f8a6f0"),
+            quoted_post_id: quoted_post.id,
+            quote_post_id: None,
+            quote_object_id: Some("https://remote.example/objects/1".to_string()),
+            object_id: None,
+            created_at: Utc::now(),
+        };
+        let object = build_quote_authorization(
+            &authority,
+            &authorization,
+            &quoted_post,
+        );
+        let value = serde_json::to_value(object).unwrap();
+        let expected_value = json!({
+            "@context": [
+                "https://www.w3.org/ns/activitystreams",
+                "https://w3id.org/security/v1",
+                "https://w3id.org/security/data-integrity/v2",
+                {
+                    "Hashtag": "as:Hashtag",
+                    "sensitive": "as:sensitive",
+                    "toot": "http://joinmastodon.org/ns#",
+                    "Emoji": "toot:Emoji",
+                    "QuoteAuthorization": "https://w3id.org/fep/044f#QuoteAuthorization",
+                    "gts": "https://gotosocial.org/ns#",
+                    "interactingObject": "gts:interactingObject",
+                    "interactionTarget": "gts:interactionTarget",
+                },
+            ],
+            "id": "https://social.example/objects/quote_authorizations/0197a1f6-3bd6-2c8a-e7a4-d1b6a4f8a6f0",
+            "type": "QuoteAuthorization",
+            "attributedTo": "https://social.example/users/test",
+            "interactingObject": "https://remote.example/objects/1",
+            "interactionTarget": "https://social.example/objects/11fa64ff-b5a3-47bf-b23d-22b360581c3f",
+        });
+        assert_eq!(value, expected_value);
+    }
+}
@@ -0,0 +1,163 @@
+use serde::Serialize;
+
+use mitra_config::Instance;
+use mitra_models::{
+    accounts::types::User,
+    posts::types::PostDetailed,
+};
+use mitra_services::media::MediaServer;
+
+use crate::{
+    authority::Authority,
+    contexts::{build_default_context, Context},
+    deliverer::Recipient,
+    identifiers::{
+        compatible_post_object_id,
+        compatible_profile_actor_id,
+        local_activity_id_unified,
+    },
+    queues::OutgoingActivityJobData,
+    vocabulary::QUOTE_REQUEST,
+};
+
+use super::note::{build_note, Note};
+
+// https://codeberg.org/fediverse/fep/src/branch/main/fep/044f/fep-044f.md
+#[derive(Serialize)]
+pub struct QuoteRequest {
+    #[serde(rename = "@context")]
+    _context: Context,
+
+    #[serde(rename = "type")]
+    activity_type: String,
+
+    id: String,
+    actor: String,
+    object: String,
+    instrument: Note,
+
+    to: Vec<String>,
+}
+
+fn build_quote_request(
+    authority: &Authority,
+    instance_webfinger_hostname: &str,
+    media_server: &MediaServer,
+    post: &PostDetailed,
+    quoted: &PostDetailed,
+) -> QuoteRequest {
+    // Only one quote request can exist for a post,
+    // so its ID is derived from post ID
+    let activity_id = local_activity_id_unified(
+        authority,
+        QUOTE_REQUEST,
+        post.id,
+    );
+    let note = build_note(
+        instance_webfinger_hostname,
+        authority,
+        media_server,
+        post,
+        false, // no context
+    );
+    let quoted_id = compatible_post_object_id(authority, quoted);
+    let quoted_author_id = compatible_profile_actor_id(authority, &quoted.author);
+    let mut context = build_default_context();
+    context.map.insert("QuoteRequest", "https://w3id.org/fep/044f#QuoteRequest");
+    QuoteRequest {
+        _context: context,
+        activity_type: QUOTE_REQUEST.to_string(),
+        id: activity_id,
+        actor: note.attributed_to.clone(),
+        object: quoted_id,
+        instrument: note,
+        to: vec![quoted_author_id],
+    }
+}
+
+/// Returns QuoteRequest activity if the post quotes a remote post
+/// and the quote has not been authorized yet
+pub fn prepare_quote_request(
+    instance: &Instance,
+    media_server: &MediaServer,
+    sender: &User,
+    post: &PostDetailed,
+) -> Option<OutgoingActivityJobData> {
+    assert_eq!(sender.id, post.author.id);
+    let related_posts = post.expect_related_posts();
+    if related_posts.quote_authorization.is_some() {
+        return None;
+    };
+    // First link is presented as a quote
+    let quoted = related_posts.linked.first()?;
+    let remote_actor = quoted.author.actor_json.as_ref()?;
+    let authority = Authority::from(instance);
+    let activity = build_quote_request(
+        &authority,
+        &instance.webfinger_hostname(),
+        media_server,
+        post,
+        quoted,
+    );
+    let recipients = Recipient::for_inbox(remote_actor);
+    let quote_request = OutgoingActivityJobData::new(
+        &authority,
+        sender,
+        activity,
+        recipients,
+    );
+    Some(quote_request)
+}
+
+#[cfg(test)]
+mod tests {
+    use apx_core::url::http_uri::HttpUri;
+    use mitra_models::{
+        posts::types::RelatedPosts,
+        profiles::types::DbActorProfile,
+    };
+    use super::*;
+
+    const INSTANCE_URI: &str = "https://server.example";
+    const INSTANCE_HOSTNAME: &str = "server.example";
+
+    #[test]
+    fn test_build_quote_request() {
+        let instance_uri = HttpUri::parse(INSTANCE_URI).unwrap();
+        let quoted_author_id = "https://social.example/users/1";
+        let quoted_author =
+            DbActorProfile::remote_for_test("test", quoted_author_id);
+        let quoted_id = "https://social.example/objects/1";
+        let quoted = PostDetailed::remote_for_test(&quoted_author, quoted_id);
+        let author = DbActorProfile::local_for_test("author");
+        let post = PostDetailed {
+            links: vec![quoted.id],
+            related_posts: Some(RelatedPosts {
+                linked: vec![quoted.clone()],
+                ..Default::default()
+            }),
+            ..PostDetailed::local_for_test(&author)
+        };
+        let authority = Authority::server(&instance_uri);
+        let media_server = MediaServer::for_test(INSTANCE_URI);
+        let activity = build_quote_request(
+            &authority,
+            INSTANCE_HOSTNAME,
+            &media_server,
+            &post,
+            &quoted,
+        );
+        assert_eq!(
+            activity.id,
+            format!("{INSTANCE_URI}/activities/quoterequest/{}", post.id),
+        );
+        assert_eq!(activity.activity_type, "QuoteRequest");
+        assert_eq!(activity.actor, format!("{INSTANCE_URI}/users/author"));
+        assert_eq!(activity.object, quoted_id);
+        assert_eq!(
+            activity.instrument.id,
+            format!("{INSTANCE_URI}/objects/{}", post.id),
+        );
+        assert_eq!(activity.to, vec![quoted_author_id]);
+    }
+}
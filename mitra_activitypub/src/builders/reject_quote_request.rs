@@ -0,0 +1,110 @@
+use serde::Serialize;
+
+use mitra_config::Instance;
+use mitra_models::{
+    accounts::types::User,
+    database::DatabaseError,
+    profiles::types::{DbActor, DbActorProfile},
+};
+use mitra_utils::id::generate_ulid;
+
+use crate::{
+    authority::Authority,
+    contexts::{build_default_context, Context},
+    deliverer::Recipient,
+    identifiers::{
+        compatible_id,
+        local_activity_id_unified,
+        local_actor_id_unified,
+    },
+    queues::OutgoingActivityJobData,
+    vocabulary::REJECT,
+};
+
+#[derive(Serialize)]
+struct RejectQuoteRequest {
+    #[serde(rename = "@context")]
+    _context: Context,
+
+    #[serde(rename = "type")]
+    activity_type: String,
+
+    id: String,
+    actor: String,
+    object: String,
+
+    to: Vec<String>,
+}
+
+fn build_reject_quote_request(
+    authority: &Authority,
+    actor_profile: &DbActorProfile,
+    source_actor_id: &str,
+    quote_request_id: &str,
+) -> RejectQuoteRequest {
+    let activity_id =
+        local_activity_id_unified(authority, REJECT, generate_ulid());
+    let actor_id = local_actor_id_unified(
+        authority,
+        actor_profile.id,
+        &actor_profile.username,
+    );
+    RejectQuoteRequest {
+        _context: build_default_context(),
+        activity_type: REJECT.to_string(),
+        id: activity_id,
+        actor: actor_id,
+        object: quote_request_id.to_string(),
+        to: vec![source_actor_id.to_string()],
+    }
+}
+
+pub fn prepare_reject_quote_request(
+    instance: &Instance,
+    sender: &User,
+    source_actor: &DbActor,
+    quote_request_id: &str,
+) -> Result<OutgoingActivityJobData, DatabaseError> {
+    let authority = Authority::from(instance);
+    let source_actor_id = compatible_id(source_actor, &source_actor.id)?;
+    let quote_request_id = compatible_id(source_actor, quote_request_id)?;
+    let activity = build_reject_quote_request(
+        &authority,
+        &sender.profile,
+        &source_actor_id,
+        &quote_request_id,
+    );
+    let recipients = Recipient::for_inbox(source_actor);
+    Ok(OutgoingActivityJobData::new(
+        &authority,
+        sender,
+        activity,
+        recipients,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSTANCE_URI: &str = "https://social.example";
+
+    #[test]
+    fn test_build_reject_quote_request() {
+        let authority = Authority::server_unchecked(INSTANCE_URI);
+        let author = DbActorProfile::local_for_test("user");
+        let quote_request_id = "https://remote.example/quote_requests/1";
+        let requester_id = "https://remote.example/users/123";
+        let activity = build_reject_quote_request(
+            &authority,
+            &author,
+            requester_id,
+            quote_request_id,
+        );
+
+        assert_eq!(activity.id.starts_with(INSTANCE_URI), true);
+        assert_eq!(activity.activity_type, "Reject");
+        assert_eq!(activity.object, quote_request_id);
+        assert_eq!(activity.to, vec![requester_id]);
+    }
+}
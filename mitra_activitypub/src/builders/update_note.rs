@@ -4,7 +4,10 @@ use mitra_config::Instance;
 use mitra_models::{
     accounts::types::User,
     database::{DatabaseClient, DatabaseError},
-    posts::types::PostDetailed,
+    posts::{
+        queries::get_post_recipients,
+        types::PostDetailed,
+    },
 };
 use mitra_services::media::MediaServer;
 use mitra_utils::id::generate_ulid;
@@ -17,7 +20,13 @@ use crate::{
     vocabulary::UPDATE,
 };
 
-use super::note::{build_note, get_note_recipients, Note};
+use super::create_note::build_create_note;
+use super::note::{
+    build_note,
+    get_audience_recipients,
+    get_note_audience,
+    Note,
+};
 
 #[derive(Serialize)]
 struct UpdateNote {
@@ -67,28 +76,66 @@ fn build_update_note(
     }
 }
 
+/// Prepares Update(Note) activity for actors who have already seen the post
+/// and Create(Note) activity for new primary recipients (e.g. new mentions).
+/// New recipients should be recorded with `save_note_recipients`
+/// after Create(Note) is enqueued.
 pub async fn prepare_update_note(
     db_client: &impl DatabaseClient,
     instance: &Instance,
     media_server: &MediaServer,
     author: &User,
     post: &PostDetailed,
-) -> Result<OutgoingActivityJobData, DatabaseError> {
+) -> Result<(OutgoingActivityJobData, Option<OutgoingActivityJobData>), DatabaseError> {
     assert_eq!(author.id, post.author.id);
     let authority = Authority::from(instance);
+    let (primary_audience, secondary_audience) =
+        get_note_audience(db_client, post).await?;
+    let known_recipient_ids = get_post_recipients(db_client, post.id).await?;
+    let (known_primary_audience, new_primary_audience): (Vec<_>, Vec<_>) =
+        primary_audience.into_iter()
+            .partition(|profile| {
+                profile.is_local()
+                    || known_recipient_ids.contains(&profile.id)
+                    // Followers have already received the post
+                    || secondary_audience.iter()
+                        .any(|other| other.id == profile.id)
+            });
     let activity = build_update_note(
         &authority,
         &instance.webfinger_hostname(),
         media_server,
         post,
     );
-    let recipients = get_note_recipients(db_client, post).await?;
-    Ok(OutgoingActivityJobData::new(
+    let recipients = get_audience_recipients(
+        known_primary_audience,
+        secondary_audience,
+    );
+    let update_note = OutgoingActivityJobData::new(
         &authority,
         author,
         activity,
         recipients,
-    ))
+    );
+    let maybe_create_note = if new_primary_audience.is_empty() {
+        None
+    } else {
+        let activity = build_create_note(
+            &authority,
+            &instance.webfinger_hostname(),
+            media_server,
+            post,
+        );
+        let recipients = get_audience_recipients(new_primary_audience, vec![]);
+        let create_note = OutgoingActivityJobData::new(
+            &authority,
+            author,
+            activity,
+            recipients,
+        );
+        Some(create_note)
+    };
+    Ok((update_note, maybe_create_note))
 }
 
 #[cfg(test)]
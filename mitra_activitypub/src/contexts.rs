@@ -12,6 +12,7 @@ pub const W3ID_VALUEFLOWS_CONTEXT: &str = "https://w3id.org/valueflows/ont/vf#";
 pub const SCHEMA_ORG_CONTEXT: &str = "http://schema.org/";
 pub const MASTODON_CONTEXT: &str = "http://joinmastodon.org/ns#";
 pub const MITRA_CONTEXT: &str = "http://jsonld.mitra.social#";
+pub const VCARD_CONTEXT: &str = "http://www.w3.org/2006/vcard/ns#";
 
 #[derive(Debug, PartialEq)]
 pub struct Context {
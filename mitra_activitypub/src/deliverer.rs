@@ -29,6 +29,7 @@ use apx_sdk::{
     agent::FederationAgent,
     deliver::{send_object, DelivererError},
 };
+use chrono::{DateTime, Utc};
 use futures::{
     stream::FuturesUnordered,
     StreamExt,
@@ -52,7 +53,11 @@ use mitra_models::{
 use crate::{
     agent::build_federation_agent_with_key,
     authority::Authority,
-    identifiers::{local_actor_id, local_actor_key_id},
+    identifiers::{
+        local_actor_id,
+        local_actor_key_id,
+        local_instance_actor_id,
+    },
     utils::db_url_to_http_url,
 };
 
@@ -147,6 +152,24 @@ impl Sender {
         }
     }
 
+    pub fn from_instance(instance: &Instance) -> Self {
+        let actor_id = local_instance_actor_id(instance.uri_str());
+        let rsa_key_id = local_actor_key_id(
+            &actor_id,
+            PublicKeyType::RsaPkcs1,
+        );
+        let ed25519_key_id = local_actor_key_id(
+            &actor_id,
+            PublicKeyType::Ed25519,
+        );
+        Self {
+            rsa_secret_key: instance.rsa_secret_key.clone(),
+            rsa_key_id: rsa_key_id,
+            ed25519_secret_key: instance.ed25519_secret_key,
+            ed25519_key_id: ed25519_key_id,
+        }
+    }
+
     // Returns None if the registered secret key doesn't correspond to
     // any of public keys associated with the actor
     pub fn from_portable_user(
@@ -206,6 +229,13 @@ pub struct Recipient {
     // This flag is set if inbox is 410 Gone
     #[serde(default)]
     pub is_gone: bool,
+
+    // Errors from failed delivery attempts
+    #[serde(default)]
+    pub errors: Vec<String>,
+
+    #[serde(default)]
+    pub first_failed_at: Option<DateTime<Utc>>,
 }
 
 impl Recipient {
@@ -217,6 +247,8 @@ impl Recipient {
             is_delivered: false,
             is_unreachable: false,
             is_gone: false,
+            errors: vec![],
+            first_failed_at: None,
         }
     }
 
@@ -236,6 +268,16 @@ impl Recipient {
         recipients
     }
 
+    /// Prefers shared inbox if it is available
+    pub fn for_shared_inbox(actor: &DbActor) -> Vec<Self> {
+        match actor.shared_inbox {
+            Some(ref shared_inbox) if !actor.is_portable() => {
+                vec![Self::new(&actor.id, shared_inbox)]
+            },
+            _ => Self::for_inbox(actor),
+        }
+    }
+
     pub fn is_finished(&self) -> bool {
         self.is_delivered || self.is_unreachable
     }
@@ -372,6 +414,8 @@ pub(super) async fn deliver_activity_worker(
                         recipient.inbox,
                         error_message,
                     );
+                    recipient.first_failed_at.get_or_insert_with(Utc::now);
+                    recipient.errors.push(error_message);
                 },
             };
         };
@@ -0,0 +1,380 @@
+//! Diagnostics for federation issues
+use std::fmt;
+
+use apx_sdk::{
+    core::url::http_uri::HttpUri,
+    fetch::FetchObjectOptions,
+    utils::{get_core_type, CoreType},
+};
+use serde_json::{Value as JsonValue};
+
+use mitra_models::{
+    database::{
+        get_database_client,
+        DatabaseClient,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    filter_rules::types::FilterAction,
+    posts::types::Visibility,
+};
+
+use crate::{
+    authentication::verify_signed_fetched_object,
+    authority::Authority,
+    errors::HandlerError,
+    filter::get_moderation_domain,
+    handlers::note::{
+        get_audience,
+        get_object_visibility,
+        reduce_visibility,
+        AttributedObjectJson,
+    },
+    identifiers::canonicalize_id,
+    importers::{
+        get_post_by_object_id,
+        get_profile_by_actor_id,
+        ApClient,
+        FetcherContext,
+    },
+    ownership::{
+        get_object_id,
+        get_owner,
+        is_local_origin,
+        verify_activity_owner,
+        verify_object_owner,
+    },
+};
+
+#[derive(Debug, PartialEq)]
+pub enum StepStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+impl fmt::Display for StepStatus {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let status_str = match self {
+            Self::Passed => "pass",
+            Self::Failed => "fail",
+            Self::Skipped => "skip",
+        };
+        write!(formatter, "{status_str}")
+    }
+}
+
+pub struct Step {
+    pub name: &'static str,
+    pub status: StepStatus,
+    pub message: String,
+}
+
+/// Results of processing steps, in order of execution
+#[derive(Default)]
+pub struct Report {
+    pub steps: Vec<Step>,
+}
+
+impl Report {
+    fn add(
+        &mut self,
+        name: &'static str,
+        status: StepStatus,
+        message: impl ToString,
+    ) {
+        self.steps.push(Step { name, status, message: message.to_string() });
+    }
+
+    fn pass(&mut self, name: &'static str, message: impl ToString) {
+        self.add(name, StepStatus::Passed, message);
+    }
+
+    fn fail(&mut self, name: &'static str, message: impl ToString) {
+        self.add(name, StepStatus::Failed, message);
+    }
+
+    fn skip(&mut self, name: &'static str, message: impl ToString) {
+        self.add(name, StepStatus::Skipped, message);
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.steps.iter().all(|step| step.status != StepStatus::Failed)
+    }
+}
+
+fn get_visibility_name(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public => "public",
+        Visibility::Followers => "followers",
+        Visibility::Subscribers => "subscribers",
+        Visibility::Conversation => "conversation",
+        Visibility::Direct => "direct",
+    }
+}
+
+async fn explain_post(
+    ap_client: &ApClient,
+    db_client: &impl DatabaseClient,
+    object: JsonValue,
+    report: &mut Report,
+) -> Result<(), DatabaseError> {
+    let authority = Authority::from(&ap_client.instance);
+    let object: AttributedObjectJson = match serde_json::from_value(object) {
+        Ok(object) => object,
+        Err(error) => {
+            report.fail("deserialization", error);
+            return Ok(());
+        },
+    };
+    report.pass("deserialization", format!("type: {}", object.inner.object_type));
+    let canonical_author_id = match canonicalize_id(object.attributed_to()) {
+        Ok(author_id) => author_id,
+        Err(error) => {
+            report.fail("author", error);
+            return Ok(());
+        },
+    };
+    let author = match get_profile_by_actor_id(
+        db_client,
+        &authority,
+        &canonical_author_id,
+    ).await {
+        Ok(author) => author,
+        Err(DatabaseError::NotFound(_)) => {
+            report.skip("author", "author is not in database, it will be imported");
+            report.skip("visibility", "author is unknown");
+            report.pass("database", "new profile and new post will be created");
+            return Ok(());
+        },
+        Err(other_error) => return Err(other_error),
+    };
+    report.pass("author", format!("profile {}", author.id));
+    let maybe_in_reply_to = match object.in_reply_to() {
+        Some(object_id) => {
+            let canonical_object_id = match canonicalize_id(object_id) {
+                Ok(object_id) => object_id,
+                Err(error) => {
+                    report.fail("parent", error);
+                    return Ok(());
+                },
+            };
+            match get_post_by_object_id(
+                db_client,
+                &authority,
+                &canonical_object_id,
+            ).await {
+                Ok(post) => {
+                    report.pass("parent", format!("post {}", post.id));
+                    Some(post)
+                },
+                Err(DatabaseError::NotFound(_)) => {
+                    report.skip("parent", "parent post is not in database, it will be imported");
+                    report.skip("visibility", "parent post is unknown");
+                    report.pass("database", "new post will be created");
+                    return Ok(());
+                },
+                Err(other_error) => return Err(other_error),
+            }
+        },
+        None => None,
+    };
+    let audience = match get_audience(&object.inner) {
+        Ok(audience) => audience,
+        Err(error) => {
+            report.fail("visibility", error);
+            return Ok(());
+        },
+    };
+    let (visibility, context) = get_object_visibility(
+        &author,
+        None,
+        &audience,
+        maybe_in_reply_to.as_ref(),
+        None,
+    );
+    let author_hostname = get_moderation_domain(author.expect_actor_data())?;
+    if visibility == Visibility::Public &&
+        ap_client.filter.is_action_required(
+            author_hostname.as_str(),
+            FilterAction::ReduceVisibility,
+        )
+    {
+        let (reduced_visibility, _) = reduce_visibility(&author, context);
+        report.pass("visibility", format!(
+            "{} (reduced from {})",
+            get_visibility_name(reduced_visibility),
+            get_visibility_name(visibility),
+        ));
+    } else {
+        report.pass("visibility", get_visibility_name(visibility));
+    };
+    let canonical_object_id = match canonicalize_id(object.id()) {
+        Ok(object_id) => object_id,
+        Err(error) => {
+            report.fail("database", error);
+            return Ok(());
+        },
+    };
+    match get_post_by_object_id(
+        db_client,
+        &authority,
+        &canonical_object_id,
+    ).await {
+        Ok(post) => {
+            report.pass("database", format!("post {} will be updated", post.id));
+        },
+        Err(DatabaseError::NotFound(_)) => {
+            report.pass("database", "new post will be created");
+        },
+        Err(other_error) => return Err(other_error),
+    };
+    Ok(())
+}
+
+/// Fetches remote object and explains how it would be processed.
+/// Doesn't make any changes to the database.
+pub async fn explain_object(
+    ap_client: &ApClient,
+    db_pool: &DatabaseConnectionPool,
+    object_id: &str,
+) -> Result<Report, DatabaseError> {
+    let mut report = Report::default();
+    let http_uri = match FetcherContext::default().prepare_object_id(object_id) {
+        Ok(http_uri) => http_uri,
+        Err(error) => {
+            report.fail("fetch", error);
+            return Ok(report);
+        },
+    };
+    let fetched = match ap_client.fetch_object_raw(
+        &http_uri,
+        FetchObjectOptions::default(),
+    ).await {
+        Ok(fetched) => fetched,
+        Err(error) => {
+            report.fail("fetch", error);
+            return Ok(report);
+        },
+    };
+    let object = match fetched.extract_fragment() {
+        Ok(object) => object,
+        Err(error) => {
+            report.fail("fetch", error);
+            return Ok(report);
+        },
+    };
+    report.pass("fetch", format!("fetched from {}", fetched.location));
+
+    match verify_signed_fetched_object(ap_client, db_pool, &fetched).await {
+        Ok(()) => report.pass("authentication", "origin or integrity proof verified"),
+        Err(HandlerError::DatabaseError(db_error)) => return Err(db_error),
+        Err(error) => report.fail("authentication", error),
+    };
+
+    let object_id = match get_object_id(&object) {
+        Ok(object_id) => object_id,
+        Err(error) => {
+            report.fail("canonicalization", error);
+            return Ok(report);
+        },
+    };
+    if is_local_origin(&ap_client.instance, object_id) {
+        report.fail("canonicalization", "local object");
+        return Ok(report);
+    };
+    let canonical_object_id = match canonicalize_id(object_id) {
+        Ok(canonical_object_id) => canonical_object_id,
+        Err(error) => {
+            report.fail("canonicalization", error);
+            return Ok(report);
+        },
+    };
+    report.pass("canonicalization", format!("canonical ID: {canonical_object_id}"));
+
+    let core_type = get_core_type(&object);
+    let ownership_result = match core_type {
+        CoreType::Actor => Ok(object_id.to_owned()),
+        CoreType::Object => verify_object_owner(&object)
+            .and_then(|_| get_owner(&object, core_type)),
+        CoreType::Activity => verify_activity_owner(&object)
+            .and_then(|_| get_owner(&object, core_type)),
+        _ => {
+            report.skip("ownership", format!("unsupported type: {core_type:?}"));
+            return Ok(report);
+        },
+    };
+    let owner_id = match ownership_result {
+        Ok(owner_id) => {
+            report.pass("ownership", format!("owner: {owner_id}"));
+            owner_id
+        },
+        Err(error) => {
+            report.fail("ownership", error);
+            return Ok(report);
+        },
+    };
+
+    match HttpUri::parse(&owner_id) {
+        Ok(http_owner_id) => {
+            let hostname = http_owner_id.hostname();
+            if ap_client.filter.is_incoming_blocked(hostname.as_str()) {
+                report.fail("filter", format!("{hostname} is blocked"));
+                return Ok(report);
+            };
+            let actions = ap_client.filter.get_required_actions(hostname.as_str());
+            if actions.is_empty() {
+                report.pass("filter", "no filter rules are applied");
+            } else {
+                report.pass("filter", format!("applied actions: {}", actions.join(", ")));
+            };
+        },
+        Err(_) => {
+            report.skip("filter", "owner ID is not an HTTP URI");
+        },
+    };
+
+    let db_client = &**get_database_client(db_pool).await?;
+    match core_type {
+        CoreType::Object => {
+            explain_post(ap_client, db_client, object, &mut report).await?;
+        },
+        CoreType::Actor => {
+            let authority = Authority::from(&ap_client.instance);
+            match get_profile_by_actor_id(
+                db_client,
+                &authority,
+                &canonical_object_id,
+            ).await {
+                Ok(profile) => {
+                    report.pass("database", format!("profile {} will be updated", profile.id));
+                },
+                Err(DatabaseError::NotFound(_)) => {
+                    report.pass("database", "new profile will be created");
+                },
+                Err(other_error) => return Err(other_error),
+            };
+        },
+        _ => {
+            let activity_type = object["type"].as_str().unwrap_or("unknown");
+            report.pass("database", format!("activity of type {activity_type} will be processed"));
+        },
+    };
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_is_ok() {
+        let mut report = Report::default();
+        report.pass("fetch", "fetched");
+        report.skip("filter", "skipped");
+        assert!(report.is_ok());
+        report.fail("ownership", "owner has different origin");
+        assert!(!report.is_ok());
+        assert_eq!(report.steps[2].status.to_string(), "fail");
+    }
+}
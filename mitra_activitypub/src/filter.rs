@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
 use apx_core::url::http_uri::Hostname;
 use wildmatch::WildMatch;
 
@@ -50,6 +53,79 @@ fn is_hostname_allowed(
     }
 }
 
+fn filter_action_name(action: FilterAction, is_reversed: bool) -> &'static str {
+    match (action, is_reversed) {
+        (FilterAction::RejectIncoming, false) => "reject-incoming",
+        (FilterAction::RejectIncoming, true) => "accept-incoming",
+        (FilterAction::Reject, false) => "reject",
+        (FilterAction::Reject, true) => "accept",
+        (FilterAction::RejectMediaAttachments, false) => "reject-media-attachments",
+        (FilterAction::RejectMediaAttachments, true) => "accept-media-attachments",
+        (FilterAction::RejectProfileImages, false) => "reject-profile-images",
+        (FilterAction::RejectProfileImages, true) => "accept-profile-images",
+        (FilterAction::RejectCustomEmojis, false) => "reject-custom-emojis",
+        (FilterAction::RejectCustomEmojis, true) => "accept-custom-emojis",
+        (FilterAction::MarkSensitive, _) => "mark-sensitive",
+        (FilterAction::RejectKeywords, false) => "reject-keywords",
+        (FilterAction::RejectKeywords, true) => "accept-keywords",
+        (FilterAction::ProxyMedia, false) => "proxy-media",
+        (FilterAction::ProxyMedia, true) => "cache-media",
+        (FilterAction::LimitMediaAttachments, _) => "limit-media-attachments",
+        (FilterAction::ReduceVisibility, _) => "reduce-visibility",
+    }
+}
+
+const FILTER_ACTIONS: [FilterAction; 10] = [
+    FilterAction::RejectIncoming,
+    FilterAction::Reject,
+    FilterAction::RejectMediaAttachments,
+    FilterAction::RejectProfileImages,
+    FilterAction::RejectCustomEmojis,
+    FilterAction::MarkSensitive,
+    FilterAction::RejectKeywords,
+    FilterAction::ProxyMedia,
+    FilterAction::LimitMediaAttachments,
+    FilterAction::ReduceVisibility,
+];
+
+// Number of times each rule was applied (since process start).
+// Keys are (action name, target) pairs
+static FILTER_STATS: OnceLock<Mutex<HashMap<(&'static str, String), u64>>> =
+    OnceLock::new();
+
+fn record_stat(action_name: &'static str, target: String) {
+    let mut stats = FILTER_STATS.get_or_init(Default::default)
+        .lock()
+        .expect("lock should not be poisoned");
+    *stats.entry((action_name, target)).or_default() += 1;
+}
+
+fn record_match(rule: &FilterRule) {
+    let action_name = filter_action_name(rule.filter_action, rule.is_reversed);
+    record_stat(action_name, rule.target.clone());
+}
+
+/// Counts reactions rejected by reaction policy.
+/// Target is "local" or "remote"
+pub fn record_rejected_reaction(is_local: bool) {
+    let target = if is_local { "local" } else { "remote" };
+    record_stat("reject-reactions", target.to_owned());
+}
+
+/// Returns (action name, target, match count) tuples
+pub fn get_filter_stats() -> Vec<(&'static str, String, u64)> {
+    let Some(stats) = FILTER_STATS.get() else {
+        return vec![];
+    };
+    let mut stats: Vec<_> = stats.lock()
+        .expect("lock should not be poisoned")
+        .iter()
+        .map(|((action_name, target), count)| (*action_name, target.clone(), *count))
+        .collect();
+    stats.sort();
+    stats
+}
+
 #[derive(Clone)]
 pub struct FederationFilter {
     blocklist: Vec<String>,
@@ -64,8 +140,8 @@ impl FederationFilter {
     ) -> Result<Self, DatabaseError> {
         let rules = get_filter_rules(db_client).await?;
         Ok(Self {
-            blocklist: config.blocked_instances.clone().unwrap_or_default(),
-            allowlist: config.allowed_instances.clone().unwrap_or_default(),
+            blocklist: config.blocked_instances().unwrap_or_default(),
+            allowlist: config.allowed_instances().unwrap_or_default(),
             rules,
         })
     }
@@ -78,11 +154,12 @@ impl FederationFilter {
         Self::init(config, db_client).await
     }
 
-    pub fn is_action_required(
+    // Returns the matched rule, if the outcome was determined by a rule
+    fn evaluate(
         &self,
         hostname: &str,
         action: FilterAction,
-    ) -> bool {
+    ) -> (bool, Option<&FilterRule>) {
         // Rules are checked in order. The last matching rule wins.
         let mut is_required = false;
         // Blocklist and allowlist have lower priority than filter rules
@@ -97,8 +174,34 @@ impl FederationFilter {
             .filter(|rule| WildMatch::new(&rule.target).matches(hostname))
             .filter(|rule| rule.filter_action == action);
         // Apply rules, starting with less specific
+        let mut maybe_matched_rule = None;
         for rule in applicable_rules {
             is_required = !rule.is_reversed;
+            maybe_matched_rule = Some(rule);
+        };
+        (is_required, maybe_matched_rule)
+    }
+
+    /// Checks whether the action is required, without updating statistics
+    pub fn is_action_required(
+        &self,
+        hostname: &str,
+        action: FilterAction,
+    ) -> bool {
+        let (is_required, _) = self.evaluate(hostname, action);
+        is_required
+    }
+
+    /// Checks whether the action is required and records the match.
+    /// Should be used only when the result is going to be acted upon.
+    pub fn apply_action(
+        &self,
+        hostname: &str,
+        action: FilterAction,
+    ) -> bool {
+        let (is_required, maybe_matched_rule) = self.evaluate(hostname, action);
+        if let Some(rule) = maybe_matched_rule {
+            record_match(rule);
         };
         is_required
     }
@@ -107,6 +210,20 @@ impl FederationFilter {
         self.is_action_required(hostname, FilterAction::RejectIncoming) ||
         self.is_action_required(hostname, FilterAction::Reject)
     }
+
+    /// Same as `is_incoming_blocked`, but records the match
+    pub fn apply_incoming_filter(&self, hostname: &str) -> bool {
+        self.apply_action(hostname, FilterAction::RejectIncoming) ||
+        self.apply_action(hostname, FilterAction::Reject)
+    }
+
+    /// Returns names of filter actions that are applied to the given hostname
+    pub fn get_required_actions(&self, hostname: &str) -> Vec<&'static str> {
+        FILTER_ACTIONS.into_iter()
+            .filter(|action| self.is_action_required(hostname, *action))
+            .map(|action| filter_action_name(action, false))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -174,8 +291,67 @@ mod tests {
             allowlist: vec![target_3.to_string()], // overridden
             rules,
         };
-        assert_eq!(filter.is_incoming_blocked("one.example"), false);
-        assert_eq!(filter.is_incoming_blocked("two.example"), true);
+        assert_eq!(filter.apply_incoming_filter("one.example"), false);
+        assert_eq!(filter.apply_incoming_filter("two.example"), true);
+        assert_eq!(filter.apply_incoming_filter("any.example"), true);
+        // Not recorded
         assert_eq!(filter.is_incoming_blocked("any.example"), true);
+        assert_eq!(
+            filter.get_required_actions("other.example"),
+            vec!["reject-incoming"],
+        );
+
+        let stats = get_filter_stats();
+        let (.., count) = stats.iter()
+            .find(|(action, target, _)| {
+                *action == "accept-incoming" && target == target_2
+            })
+            .unwrap();
+        assert!(*count >= 1);
+        let (.., count) = stats.iter()
+            .find(|(action, target, _)| {
+                *action == "reject-incoming" && target == target_1
+            })
+            .unwrap();
+        assert!(*count >= 2);
+    }
+
+    #[test]
+    fn test_record_rejected_reaction() {
+        record_rejected_reaction(false);
+        let stats = get_filter_stats();
+        let (.., count) = stats.iter()
+            .find(|(action, target, _)| {
+                *action == "reject-reactions" && target == "remote"
+            })
+            .unwrap();
+        assert!(*count >= 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_required_actions() {
+        let db_client = &create_test_database().await;
+        add_filter_rule(
+            db_client,
+            "one.example",
+            FilterAction::MarkSensitive,
+            false,
+        ).await.unwrap();
+        let rules = get_filter_rules(db_client).await.unwrap();
+        let filter = FederationFilter {
+            blocklist: vec!["bad.example".to_string()],
+            allowlist: vec![],
+            rules,
+        };
+        assert_eq!(
+            filter.get_required_actions("one.example"),
+            vec!["mark-sensitive"],
+        );
+        assert_eq!(
+            filter.get_required_actions("bad.example"),
+            vec!["reject-incoming"],
+        );
+        assert!(filter.get_required_actions("two.example").is_empty());
     }
 }
@@ -1,11 +1,12 @@
 use apx_core::caip10::AccountId;
 use apx_sdk::{
     core::url::canonical::CanonicalUri,
-    deserialization::deserialize_into_object_id,
+    deserialization::{deserialize_into_object_id, object_to_id},
 };
 use serde::Deserialize;
 use serde_json::{Value as JsonValue};
 
+use mitra_config::Config;
 use mitra_models::{
     database::{
         get_database_client,
@@ -36,13 +37,18 @@ use crate::{
     adapters::follow_requests::accept_and_add_follower,
     authority::Authority,
     c2s::followers::add_follower,
-    identifiers::{canonicalize_id, parse_local_activity_id},
+    identifiers::{
+        canonicalize_id,
+        parse_local_activity_id,
+        parse_local_quote_request_id,
+    },
     importers::ApClient,
     vocabulary::{FOLLOW, OFFER},
 };
 
 use super::{
     agreement::Agreement,
+    quote_request::handle_accept_quote_request,
     Descriptor,
     HandlerResult,
 };
@@ -75,10 +81,23 @@ struct Accept {
 }
 
 pub async fn handle_accept(
+    config: &Config,
     ap_client: &ApClient,
     db_pool: &DatabaseConnectionPool,
     activity: JsonValue,
 ) -> HandlerResult {
+    let authority = Authority::from(&ap_client.instance);
+    if let Ok(object_id) = object_to_id(&activity["object"]) {
+        if parse_local_quote_request_id(&authority, &object_id).is_ok() {
+            // Accept(QuoteRequest)
+            return handle_accept_quote_request(
+                config,
+                ap_client,
+                db_pool,
+                activity,
+            ).await;
+        };
+    };
     if activity["result"].as_object().is_some() {
         // Accept(Offer)
         return handle_accept_offer(ap_client, db_pool, activity).await;
@@ -92,7 +111,6 @@ pub async fn handle_accept(
         &canonical_actor_id.to_string(),
     ).await?;
     let canonical_object_id = canonicalize_id(&accept.object)?;
-    let authority = Authority::from(&ap_client.instance);
     let follow_request = get_follow_request_by_activity_id(
         db_client,
         &authority,
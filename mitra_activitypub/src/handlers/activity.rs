@@ -50,6 +50,7 @@ use super::{
     block::handle_block,
     create::handle_create,
     delete::handle_delete,
+    flag::handle_flag,
     follow::handle_follow,
     like::{
         handle_like,
@@ -58,10 +59,12 @@ use super::{
     r#move::handle_move,
     note::normalize_audience,
     offer::handle_offer,
+    quote_request::handle_quote_request,
     reject::handle_reject,
     remove::handle_remove,
     undo::handle_undo,
     update::handle_update,
+    view::handle_view,
     HandlerError,
 };
 
@@ -164,7 +167,8 @@ pub async fn handle_activity(
     let activity_clone = activity.clone();
     let maybe_descriptor = match activity_type.as_str() {
         ACCEPT => {
-            handle_accept(ap_client, db_pool, activity).await?
+            // `config` is required by Accept(QuoteRequest) handler
+            handle_accept(config, ap_client, db_pool, activity).await?
         },
         ACCEPT_AGREEMENT => {
             handle_accept_offer(ap_client, db_pool, activity).await?
@@ -192,7 +196,8 @@ pub async fn handle_activity(
             ).await?
         },
         DELETE => {
-            handle_delete(ap_client, db_pool, activity).await?
+            // `config` is required by Delete(Person) handler
+            handle_delete(config, ap_client, db_pool, activity).await?
         },
         ENCRYPTED_ACTIVITY => {
             // No side effects
@@ -200,14 +205,17 @@ pub async fn handle_activity(
             log::info!("received encrypted activity: {activity}");
             Some(Descriptor::None)
         },
+        FLAG => {
+            handle_flag(ap_client, db_pool, activity).await?
+        },
         FOLLOW => {
             handle_follow(ap_client, db_pool, activity).await?
         },
         DISLIKE | LIKE | EMOJI_REACT => {
             handle_like(ap_client, db_pool, activity).await?
         },
-        LISTEN => {
-            None // ignore
+        LISTEN | READ | VIEW => {
+            handle_view(ap_client, db_pool, activity).await?
         },
         MOVE => {
             handle_move(ap_client, db_pool, activity).await?
@@ -216,8 +224,12 @@ pub async fn handle_activity(
             // `config` required by `create_payment_address`
             handle_offer(config, ap_client, db_pool, activity).await?
         },
+        QUOTE_REQUEST => {
+            handle_quote_request(ap_client, db_pool, activity).await?
+        },
         REJECT => {
-            handle_reject(ap_client, db_pool, activity).await?
+            // `config` is required by Reject(QuoteRequest) handler
+            handle_reject(config, ap_client, db_pool, activity).await?
         },
         REMOVE => {
             handle_remove(ap_client, db_pool, activity).await?
@@ -147,6 +147,7 @@ async fn handle_fep_171b_add(
         },
         DELETE => {
             let maybe_type = handle_delete(
+                config,
                 ap_client,
                 db_pool,
                 activity,
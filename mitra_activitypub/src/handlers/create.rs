@@ -7,10 +7,14 @@ use apx_sdk::{
 use serde::Deserialize;
 use serde_json::{Value as JsonValue};
 
-use mitra_adapters::dynamic_config::get_dynamic_config;
-use mitra_config::Config;
+use mitra_adapters::{
+    dynamic_config::get_dynamic_config,
+    posts::{check_duplicate_post, save_post_content_hash},
+};
+use mitra_config::{Config, DuplicatePostAction};
 use mitra_models::{
     database::{
+        db_client_await,
         get_database_client,
         DatabaseClient,
         DatabaseConnectionPool,
@@ -32,6 +36,7 @@ use crate::{
     importers::{
         get_post_by_object_id,
         import_post,
+        ActorIdResolver,
         ApClient,
     },
     ownership::{parse_attributed_to, verify_object_owner},
@@ -155,7 +160,7 @@ pub async fn handle_create(
     if is_question_vote(&object) {
         return handle_question_vote(config, ap_client, db_pool, object).await;
     };
-    let object: AttributedObjectJson = serde_json::from_value(object)?;
+    let mut object: AttributedObjectJson = serde_json::from_value(object)?;
     if let Some(sender_id) = maybe_sender_id {
         let db_client = &**get_database_client(db_pool).await?;
         let authority = Authority::from(&ap_client.instance);
@@ -168,13 +173,16 @@ pub async fn handle_create(
         // TODO: FEP-EF61: keyword filtering for portable messages
         if let Ok(http_uri) = HttpUri::parse(&author_id) {
             let author_hostname = http_uri.hostname();
-            let (maybe_title, content) = get_object_content(&object.inner)?;
+            let (maybe_title, content) = get_object_content(
+                &ap_client.limits.posts,
+                &object.inner,
+            )?;
             let full_text = format!(
                 "{} {}",
                 maybe_title.unwrap_or_default(),
                 content,
             );
-            if ap_client.filter.is_action_required(
+            if ap_client.filter.apply_action(
                 author_hostname.as_str(),
                 FilterAction::RejectKeywords,
             ) {
@@ -190,16 +198,75 @@ pub async fn handle_create(
         };
     };
 
+    // Duplicate post detection (only for new posts delivered to inbox)
+    let limits = config.limits();
+    let duplicate_limits = &limits.duplicate_posts;
+    let mut maybe_new_content = None;
+    if duplicate_limits.enabled && maybe_sender_id.is_some() {
+        let authority = Authority::from(&ap_client.instance);
+        let canonical_object_id = canonicalize_id(object.id())?;
+        let is_new = match get_post_by_object_id(
+            db_client_await!(db_pool),
+            &authority,
+            &canonical_object_id,
+        ).await {
+            Ok(_) => false,
+            Err(DatabaseError::NotFound(_)) => true,
+            Err(other_error) => return Err(other_error.into()),
+        };
+        if is_new {
+            let author = ActorIdResolver::default().only_remote().resolve(
+                ap_client,
+                db_pool,
+                &author_id,
+            ).await?;
+            let (_, content) = get_object_content(
+                &ap_client.limits.posts,
+                &object.inner,
+            )?;
+            if check_duplicate_post(
+                db_client_await!(db_pool),
+                duplicate_limits,
+                author.id,
+                &content,
+            ).await? {
+                match duplicate_limits.action {
+                    DuplicatePostAction::Reject => {
+                        let error_message =
+                            format!("duplicate post from {author_id}");
+                        return Err(HandlerError::Filtered(error_message));
+                    },
+                    DuplicatePostAction::Flag => {
+                        log::warn!("duplicate post flagged: {author_id}");
+                        object.inner.sensitive = Some(true);
+                    },
+                };
+            };
+            maybe_new_content = Some(content);
+        };
+    };
+
     let object_id = object.id().to_owned();
     let object_type = object.inner.object_type.clone();
-    let post = import_post(
+    // Import future is large, allocate it on the heap
+    let post = Box::pin(import_post(
         ap_client,
         db_pool,
         object_id,
         Some(object),
-    ).await?;
+    )).await?;
     // NOTE: import_post always returns a post; activity will be re-distributed
     let db_client = &**get_database_client(db_pool).await?;
+    if let Some(content) = maybe_new_content {
+        // Record content only after the post is accepted
+        save_post_content_hash(
+            db_client,
+            duplicate_limits,
+            post.id,
+            post.author.id,
+            &content,
+        ).await?;
+    };
     sync_conversation(
         db_client,
         &ap_client.instance,
@@ -209,3 +276,61 @@ pub async fn handle_create(
     ).await?;
     Ok(Some(Descriptor::object(object_type)))
 }
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use mitra_models::posts::{
+        queries::get_remote_post_by_object_id,
+        types::PostCreateData,
+    };
+    use crate::{
+        adapters::posts::publish_local_post,
+        identifiers::local_object_id,
+        test_utils::SimulatedNetwork,
+    };
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_note_delivered_to_follower() {
+        let network = SimulatedNetwork::start(&[
+            "https://a.example",
+            "https://b.example",
+        ]).await;
+        let server_a = network.instance("https://a.example");
+        let server_b = network.instance("https://b.example");
+        let alice = server_a.create_user("alice").await;
+        let bob = server_b.create_user("bob").await;
+        let alice_on_b = network.follow(
+            server_b,
+            &bob,
+            &server_a.actor_id("alice"),
+        ).await;
+
+        let db_client_a = &mut **get_database_client(&server_a.db_pool).await.unwrap();
+        let post_data = PostCreateData {
+            content: "<p>test</p>".to_string(),
+            content_source: Some("test".to_string()),
+            ..PostCreateData::for_test()
+        };
+        let (post, _) = publish_local_post(
+            &server_a.config,
+            db_client_a,
+            &alice,
+            post_data,
+            None,
+            vec![],
+        ).await.unwrap();
+        network.deliver_all().await;
+
+        let db_client_b = &**get_database_client(&server_b.db_pool).await.unwrap();
+        let object_id = local_object_id("https://a.example", post.id);
+        let post_on_b = get_remote_post_by_object_id(
+            db_client_b,
+            &object_id,
+        ).await.unwrap();
+        assert_eq!(post_on_b.author.id, alice_on_b.id);
+        assert_eq!(post_on_b.content, "<p>test</p>");
+    }
+}
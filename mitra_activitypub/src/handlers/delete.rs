@@ -2,6 +2,7 @@ use apx_sdk::deserialization::deserialize_into_object_id;
 use serde::Deserialize;
 use serde_json::{Value as JsonValue};
 
+use mitra_config::Config;
 use mitra_models::{
     database::{
         get_database_client,
@@ -15,6 +16,7 @@ use mitra_models::{
     profiles::queries::{
         delete_profile,
         get_remote_profile_by_actor_id,
+        set_profile_deleted,
     },
 };
 use mitra_validators::errors::ValidationError;
@@ -36,6 +38,7 @@ struct Delete {
 }
 
 pub async fn handle_delete(
+    config: &Config,
     ap_client: &ApClient,
     db_pool: &DatabaseConnectionPool,
     activity: JsonValue,
@@ -55,9 +58,15 @@ pub async fn handle_delete(
             Err(DatabaseError::NotFound(_)) => return Ok(None),
             Err(other_error) => return Err(other_error.into()),
         };
-        let deletion_queue = delete_profile(db_client, profile.id).await?;
-        deletion_queue.into_job(db_client).await?;
-        log::info!("deleted remote actor {}", delete.object);
+        if config.retention.deleted_profiles.is_some() {
+            // Profile will be removed by periodic task
+            set_profile_deleted(db_client, profile.id).await?;
+            log::info!("marked remote actor as deleted {}", delete.object);
+        } else {
+            let deletion_queue = delete_profile(db_client, profile.id).await?;
+            deletion_queue.into_job(db_client).await?;
+            log::info!("deleted remote actor {}", delete.object);
+        };
         return Ok(Some(Descriptor::object("Actor")));
     };
     // Delete(Note)
@@ -103,7 +103,7 @@ pub async fn handle_emoji(
         log::warn!("invalid emoji URL ({error}): {}", emoji.icon.url);
         return Ok(None);
     };
-    let is_filter_enabled = ap_client.filter.is_action_required(
+    let is_filter_enabled = ap_client.filter.apply_action(
         moderation_domain.as_str(),
         FilterAction::RejectCustomEmojis,
     );
@@ -141,7 +141,7 @@ pub async fn handle_emoji(
             return Ok(None);
         },
     };
-    let is_proxy_enabled = ap_client.filter.is_action_required(
+    let is_proxy_enabled = ap_client.filter.apply_action(
         moderation_domain.as_str(),
         FilterAction::ProxyMedia,
     );
@@ -0,0 +1,125 @@
+use apx_sdk::deserialization::deserialize_into_id_array;
+use serde::Deserialize;
+use serde_json::{Value as JsonValue};
+
+use mitra_models::{
+    database::{
+        get_database_client,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    notifications::helpers::create_report_notifications,
+    posts::helpers::get_local_post_by_id,
+    reports::queries::create_report,
+    reports::types::ReportCreateData,
+};
+use mitra_validators::{
+    errors::ValidationError,
+    reports::{clean_report_comment, validate_report_comment},
+};
+
+use crate::{
+    authority::Authority,
+    identifiers::{canonicalize_id, parse_local_object_id},
+    importers::{
+        get_user_by_actor_id,
+        ActorIdResolver,
+        ApClient,
+    },
+    vocabulary::FLAG,
+};
+
+use super::{Descriptor, HandlerResult};
+
+#[derive(Deserialize)]
+struct Flag {
+    id: String,
+    actor: String,
+
+    // Reported actor and (optionally) reported objects
+    #[serde(deserialize_with = "deserialize_into_id_array")]
+    object: Vec<String>,
+
+    #[serde(default)]
+    content: Option<String>,
+}
+
+pub async fn handle_flag(
+    ap_client: &ApClient,
+    db_pool: &DatabaseConnectionPool,
+    activity: JsonValue,
+) -> HandlerResult {
+    let flag: Flag = serde_json::from_value(activity)?;
+    let canonical_flag_id = canonicalize_id(&flag.id)?;
+    let reporter = ActorIdResolver::default().only_remote().resolve(
+        ap_client,
+        db_pool,
+        &flag.actor,
+    ).await?;
+    let comment = clean_report_comment(flag.content.as_deref().unwrap_or(""));
+    validate_report_comment(comment)?;
+    let db_client = &mut **get_database_client(db_pool).await?;
+    let authority = Authority::from(&ap_client.instance);
+    let mut maybe_target = None;
+    let mut posts = vec![];
+    for object_id in flag.object {
+        let canonical_object_id = canonicalize_id(&object_id)?;
+        match get_user_by_actor_id(
+            db_client,
+            &authority,
+            &canonical_object_id,
+        ).await {
+            Ok(user) => {
+                maybe_target = Some(user);
+                continue;
+            },
+            Err(DatabaseError::NotFound(_)) => (),
+            Err(other_error) => return Err(other_error.into()),
+        };
+        let Ok(post_id) = parse_local_object_id(
+            &authority,
+            &canonical_object_id.to_string(),
+        ) else {
+            log::warn!("reported object is not local: {object_id}");
+            continue;
+        };
+        match get_local_post_by_id(db_client, post_id).await {
+            Ok(post) => posts.push(post),
+            Err(DatabaseError::NotFound(_)) => {
+                log::warn!("reported post not found: {object_id}");
+            },
+            Err(other_error) => return Err(other_error.into()),
+        };
+    };
+    let target_id = match maybe_target {
+        Some(target) => target.id,
+        // Reported actor can be omitted if posts are reported
+        None => match posts.first() {
+            Some(post) => post.author.id,
+            None => return Err(ValidationError("no local objects in report").into()),
+        },
+    };
+    let post_ids = posts.into_iter()
+        .filter(|post| post.author.id == target_id)
+        .map(|post| post.id)
+        .collect();
+    let report_data = ReportCreateData {
+        reporter_id: reporter.id,
+        target_id: target_id,
+        post_ids: post_ids,
+        comment: comment.to_string(),
+        object_id: Some(canonical_flag_id.to_string()),
+        is_forwarded: false,
+    };
+    match create_report(db_client, report_data).await {
+        Ok(report) => {
+            log::info!("report {} received from {}", report.id, flag.actor);
+            create_report_notifications(db_client, reporter.id).await?;
+        },
+        Err(DatabaseError::AlreadyExists(_)) => {
+            log::warn!("report has already been received");
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
+    Ok(Some(Descriptor::object(FLAG)))
+}
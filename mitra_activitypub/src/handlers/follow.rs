@@ -17,7 +17,10 @@ use mitra_validators::{
 };
 
 use crate::{
-    adapters::follow_requests::accept_and_add_follower,
+    adapters::follow_requests::{
+        accept_and_add_follower,
+        requires_manual_approval,
+    },
     authority::Authority,
     builders::accept_follow::prepare_accept_follow,
     identifiers::canonicalize_id,
@@ -47,7 +50,7 @@ pub async fn handle_follow(
         db_pool,
         &follow.actor,
     ).await?;
-    let source_actor = source_profile.actor_json
+    let source_actor = source_profile.actor_json.as_ref()
         .expect("actor data should be present");
     let target_profile = ActorIdResolver::default()
         .include_automated_accounts()
@@ -70,7 +73,12 @@ pub async fn handle_follow(
         // Activity has been performed by a portable account
         return Ok(Some(Descriptor::object("Actor")));
     };
-    if follow_request_created && target_account.profile().manually_approves_followers {
+    let is_manual_approval_required = requires_manual_approval(
+        db_client,
+        &source_profile,
+        target_account.profile(),
+    ).await?;
+    if follow_request_created && is_manual_approval_required {
         create_follow_request_notification(
             db_client,
             follow_request.source_id,
@@ -92,9 +100,65 @@ pub async fn handle_follow(
         prepare_accept_follow(
             &ap_client.instance,
             &target_account,
-            &source_actor,
+            source_actor,
             &canonical_activity_id.to_string(),
         )?.save_and_enqueue(db_client).await?;
     };
     Ok(Some(Descriptor::object("Actor")))
 }
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use mitra_models::{
+        profiles::queries::get_remote_profile_by_actor_id,
+        relationships::{
+            queries::has_relationship,
+            types::RelationshipType,
+        },
+    };
+    use crate::test_utils::SimulatedNetwork;
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_follow_remote_actor() {
+        let network = SimulatedNetwork::start(&[
+            "https://a.example",
+            "https://b.example",
+        ]).await;
+        let server_a = network.instance("https://a.example");
+        let server_b = network.instance("https://b.example");
+        let alice = server_a.create_user("alice").await;
+        let bob = server_b.create_user("bob").await;
+
+        let alice_on_b = network.follow(
+            server_b,
+            &bob,
+            &server_a.actor_id("alice"),
+        ).await;
+        // Follow request is accepted
+        let db_client_b = &**get_database_client(&server_b.db_pool).await.unwrap();
+        let is_following = has_relationship(
+            db_client_b,
+            bob.id,
+            alice_on_b.id,
+            RelationshipType::Follow,
+        ).await.unwrap();
+        assert!(is_following);
+        // Follower is added
+        let db_client_a = &**get_database_client(&server_a.db_pool).await.unwrap();
+        let bob_on_a = get_remote_profile_by_actor_id(
+            db_client_a,
+            &server_b.actor_id("bob"),
+        ).await.unwrap();
+        assert_eq!(bob_on_a.preferred_handle(), "bob@b.example");
+        let is_follower = has_relationship(
+            db_client_a,
+            bob_on_a.id,
+            alice.id,
+            RelationshipType::Follow,
+        ).await.unwrap();
+        assert!(is_follower);
+    }
+}
@@ -35,7 +35,7 @@ use crate::{
     authority::Authority,
     builders::add_context_activity::sync_conversation,
     deliverer::Recipient,
-    filter::get_moderation_domain,
+    filter::{get_moderation_domain, record_rejected_reaction},
     forwarder::get_activity_recipients,
     identifiers::canonicalize_id,
     importers::{
@@ -52,6 +52,7 @@ use super::{
     emoji::handle_emoji,
     note::normalize_audience,
     Descriptor,
+    HandlerError,
     HandlerResult,
 };
 
@@ -129,7 +130,7 @@ pub async fn handle_like(
         &like.to,
         &like.cc,
     )?;
-    let (maybe_content, maybe_emoji_id) = match like.content {
+    let (maybe_content, maybe_emoji) = match like.content {
         Some(content) if is_single_character(&content) => {
             (Some(content), None)
         },
@@ -150,7 +151,7 @@ pub async fn handle_like(
                 None
             };
             if let Some(db_emoji) = maybe_db_emoji {
-                (Some(content), Some(db_emoji.id))
+                (Some(content), Some(db_emoji))
             } else {
                 log::warn!("ignoring reaction content: {content}");
                 (None, None)
@@ -170,11 +171,22 @@ pub async fn handle_like(
         author_id: author.id,
         post_id: post.id,
         content: maybe_content,
-        emoji_id: maybe_emoji_id,
+        emoji_id: maybe_emoji.as_ref().map(|db_emoji| db_emoji.id),
         visibility: visibility,
         activity_id: Some(canonical_activity_id.to_string()),
     };
     validate_reaction_data(&reaction_data)?;
+    if !ap_client.reaction_filter.is_allowed(
+        &reaction_data,
+        maybe_emoji.as_ref(),
+    ) {
+        log::warn!(
+            "rejected reaction: {}",
+            reaction_data.content.as_deref().unwrap_or_default(),
+        );
+        record_rejected_reaction(false);
+        return Err(HandlerError::Filtered("reaction is not allowed".to_owned()));
+    };
     let db_client = &mut **get_database_client(db_pool).await?;
     match create_reaction(db_client, reaction_data).await {
         Ok(reaction) => {
@@ -272,3 +284,78 @@ pub async fn handle_like_c2s(
     ).await?;
     Ok(Some(Descriptor::object("Object")))
 }
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use mitra_models::posts::{
+        queries::{get_post_by_id, get_remote_post_by_object_id},
+        types::PostCreateData,
+    };
+    use mitra_services::media::MediaServer;
+    use crate::{
+        adapters::posts::publish_local_post,
+        builders::like::prepare_like,
+        identifiers::local_object_id,
+        test_utils::SimulatedNetwork,
+    };
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_like_delivered_to_author() {
+        let network = SimulatedNetwork::start(&[
+            "https://a.example",
+            "https://b.example",
+        ]).await;
+        let server_a = network.instance("https://a.example");
+        let server_b = network.instance("https://b.example");
+        let alice = server_a.create_user("alice").await;
+        let bob = server_b.create_user("bob").await;
+        network.follow(server_b, &bob, &server_a.actor_id("alice")).await;
+        let db_client_a = &mut **get_database_client(&server_a.db_pool).await.unwrap();
+        let post_data = PostCreateData {
+            content: "<p>test</p>".to_string(),
+            ..PostCreateData::for_test()
+        };
+        let (post, _) = publish_local_post(
+            &server_a.config,
+            db_client_a,
+            &alice,
+            post_data,
+            None,
+            vec![],
+        ).await.unwrap();
+        network.deliver_all().await;
+
+        let db_client_b = &mut **get_database_client(&server_b.db_pool).await.unwrap();
+        let object_id = local_object_id("https://a.example", post.id);
+        let post_on_b = get_remote_post_by_object_id(
+            db_client_b,
+            &object_id,
+        ).await.unwrap();
+        let reaction_data = ReactionData {
+            author_id: bob.id,
+            post_id: post_on_b.id,
+            content: None,
+            emoji_id: None,
+            visibility: Visibility::Direct,
+            activity_id: None,
+        };
+        let reaction = create_reaction(db_client_b, reaction_data).await.unwrap();
+        let reaction = ReactionDetailed::new(reaction, bob.profile.clone(), None)
+            .unwrap();
+        prepare_like(
+            db_client_b,
+            &server_b.config.instance(),
+            &MediaServer::new(&server_b.config),
+            &bob,
+            &post_on_b,
+            &reaction,
+        ).await.unwrap().save_and_enqueue(db_client_b).await.unwrap();
+        network.deliver_all().await;
+
+        let post = get_post_by_id(db_client_a, post.id).await.unwrap();
+        assert_eq!(post.reaction_count, 1);
+    }
+}
@@ -7,6 +7,7 @@ mod block;
 mod create;
 mod delete;
 pub mod emoji;
+mod flag;
 mod follow;
 mod like;
 mod r#move;
@@ -14,10 +15,12 @@ pub mod note;
 mod offer;
 pub mod proposal;
 mod question_vote;
+mod quote_request;
 mod reject;
 mod remove;
 mod undo;
 mod update;
+mod view;
 
 use crate::errors::HandlerError;
 
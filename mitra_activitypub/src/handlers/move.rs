@@ -122,3 +122,86 @@ pub async fn handle_move(
 
     Ok(Some(Descriptor::object("Actor")))
 }
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use mitra_models::{
+        profiles::{
+            queries::{get_remote_profile_by_actor_id, update_profile},
+            types::ProfileUpdateData,
+        },
+        relationships::{
+            queries::has_relationship,
+            types::RelationshipType,
+        },
+    };
+    use crate::{
+        builders::move_person::prepare_move_person,
+        test_utils::SimulatedNetwork,
+    };
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_move_followers() {
+        let network = SimulatedNetwork::start(&[
+            "https://a.example",
+            "https://b.example",
+        ]).await;
+        let server_a = network.instance("https://a.example");
+        let server_b = network.instance("https://b.example");
+        let alice = server_a.create_user("alice").await;
+        let alice_new = server_a.create_user("alice_new").await;
+        let bob = server_b.create_user("bob").await;
+        let alice_on_b = network.follow(
+            server_b,
+            &bob,
+            &server_a.actor_id("alice"),
+        ).await;
+
+        let db_client_a = &mut **get_database_client(&server_a.db_pool).await.unwrap();
+        let profile_data = ProfileUpdateData {
+            aliases: vec![server_a.actor_id("alice")],
+            ..ProfileUpdateData::from(&alice_new.profile)
+        };
+        update_profile(db_client_a, alice_new.id, profile_data).await.unwrap();
+        let followers = get_followers(db_client_a, alice.id).await.unwrap()
+            .into_iter()
+            .filter_map(|profile| profile.actor_json)
+            .collect();
+        prepare_move_person(
+            &server_a.config.instance(),
+            &alice,
+            &server_a.actor_id("alice_new"),
+            false, // push mode
+            followers,
+        ).save_and_enqueue(db_client_a).await.unwrap();
+        network.deliver_all().await;
+
+        let db_client_b = &**get_database_client(&server_b.db_pool).await.unwrap();
+        let alice_new_on_b = get_remote_profile_by_actor_id(
+            db_client_b,
+            &server_a.actor_id("alice_new"),
+        ).await.unwrap();
+        let is_following_old = has_relationship(
+            db_client_b,
+            bob.id,
+            alice_on_b.id,
+            RelationshipType::Follow,
+        ).await.unwrap();
+        assert!(!is_following_old);
+        let is_following_new = has_relationship(
+            db_client_b,
+            bob.id,
+            alice_new_on_b.id,
+            RelationshipType::Follow,
+        ).await.unwrap();
+        assert!(is_following_new);
+        let followers_old = get_followers(db_client_a, alice.id).await.unwrap();
+        assert_eq!(followers_old.len(), 0);
+        let followers_new = get_followers(db_client_a, alice_new.id).await.unwrap();
+        assert_eq!(followers_new.len(), 1);
+        assert_eq!(followers_new[0].preferred_handle(), "bob@b.example");
+    }
+}
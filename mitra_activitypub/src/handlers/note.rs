@@ -1,3 +1,4 @@
+use std::cmp::min;
 use std::collections::HashMap;
 
 use apx_sdk::{
@@ -24,15 +25,21 @@ use serde_json::{Value as JsonValue};
 use uuid::Uuid;
 
 use mitra_adapters::{
+    dynamic_config::get_dynamic_config,
     permissions::filter_mentions,
-    posts::check_post_limits,
+    posts::{check_post_limits, truncate_remote_content},
 };
+use mitra_config::PostLimits;
 use mitra_models::{
     activitypub::queries::save_attributed_object,
-    attachments::queries::create_attachment,
+    attachments::{
+        queries::create_attachment,
+        types::MediaMetadata,
+    },
     database::{
         db_client_await,
         get_database_client,
+        DatabaseClient,
         DatabaseConnectionPool,
         DatabaseError,
     },
@@ -40,7 +47,7 @@ use mitra_models::{
     media::types::MediaInfo,
     polls::types::{PollData, PollResult},
     posts::{
-        helpers::can_link_post,
+        helpers::{can_link_post, can_quote_post},
         queries::{create_post, update_post},
         types::{
             PostContext,
@@ -54,7 +61,9 @@ use mitra_models::{
         DbActorProfile,
         Origin::Remote,
     },
+    quote_authorizations::queries::get_local_quote_authorization,
 };
+use mitra_services::media_metadata::get_media_metadata;
 use mitra_utils::{
     languages::{parse_language_tag, Language},
 };
@@ -84,6 +93,7 @@ use crate::{
     filter::get_moderation_domain,
     identifiers::{
         canonicalize_id,
+        parse_local_quote_authorization_id,
     },
     importers::{
         get_or_import_actor_by_webfinger_address,
@@ -152,6 +162,13 @@ pub struct MediaAttachment {
 
     #[serde(deserialize_with = "deserialize_into_link_href")]
     pub url: String,
+
+    // Used by Misskey
+    sensitive: Option<bool>,
+
+    // Used by Mastodon
+    width: Option<u32>,
+    height: Option<u32>,
 }
 
 #[derive(Clone)]
@@ -271,6 +288,8 @@ pub struct AttributedObject {
 
     quote: Option<String>,
     quote_url: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_into_object_id_opt")]
+    quote_authorization: Option<String>,
 
     // TODO: Use is_object?
     inbox: Option<String>,
@@ -375,6 +394,7 @@ fn get_object_url(
 }
 
 pub(super) fn get_object_content(
+    limits: &PostLimits,
     object: &AttributedObject,
 ) -> Result<(Option<String>, String), ValidationError> {
     let maybe_title = if object.in_reply_to.is_none() {
@@ -398,6 +418,7 @@ pub(super) fn get_object_content(
     } else {
         "".to_string()
     };
+    let content = truncate_remote_content(limits, &content);
     let content_safe = clean_remote_content(&content);
     validate_content(&content_safe, Remote)?;
     Ok((maybe_title, content_safe))
@@ -432,15 +453,6 @@ async fn get_object_attachments(
 ) -> Result<(Vec<Uuid>, Vec<String>), HandlerError> {
     let agent = ap_client.agent();
     let author_hostname = get_moderation_domain(author.expect_actor_data())?;
-    let is_filter_enabled = ap_client.filter.is_action_required(
-        author_hostname.as_str(),
-        FilterAction::RejectMediaAttachments,
-    );
-    let is_proxy_enabled = ap_client.filter.is_action_required(
-        author_hostname.as_str(),
-        FilterAction::ProxyMedia,
-    );
-
     let mut values = object.attachment.clone();
     if object.object_type == IMAGE {
         if let Some(url) = get_object_url(object)? {
@@ -450,6 +462,9 @@ async fn get_object_attachments(
                 summary: object.summary.clone(),
                 media_type: object.media_type.clone(),
                 url: url,
+                sensitive: None,
+                width: None,
+                height: None,
             };
             values.push(Attachment::Media(attachment));
         };
@@ -459,10 +474,39 @@ async fn get_object_attachments(
         let thumbnails = object.icon.iter().cloned().map(Attachment::Media);
         values.extend(thumbnails.take(1));
     };
+    if values.is_empty() {
+        return Ok((vec![], vec![]));
+    };
+    let is_filter_enabled = ap_client.filter.apply_action(
+        author_hostname.as_str(),
+        FilterAction::RejectMediaAttachments,
+    );
+    let is_proxy_enabled = ap_client.filter.apply_action(
+        author_hostname.as_str(),
+        FilterAction::ProxyMedia,
+    );
+    let attachment_limit = if ap_client.filter.apply_action(
+        author_hostname.as_str(),
+        FilterAction::LimitMediaAttachments,
+    ) {
+        let dynamic_config =
+            get_dynamic_config(db_client_await!(db_pool)).await?;
+        min(
+            dynamic_config.filter_media_attachment_limit,
+            ap_client.limits.posts.attachment_limit,
+        )
+    } else {
+        ap_client.limits.posts.attachment_limit
+    };
 
     let mut attachments = vec![];
     let mut unprocessed = vec![];
-    let mut downloaded: Vec<(MediaInfo, Option<String>)> = vec![];
+    let mut downloaded: Vec<(
+        MediaInfo,
+        Option<String>, // description
+        bool, // is sensitive
+        Option<MediaMetadata>,
+    )> = vec![];
     for attachment_value in values {
         let attachment = match attachment_value {
             Attachment::Media(attachment) => attachment,
@@ -496,13 +540,14 @@ async fn get_object_attachments(
                     .map_err(|error| log::warn!("{error}"))
                     .is_ok()
             });
+        let is_sensitive = attachment.sensitive.unwrap_or(false);
         if is_filter_enabled {
             // Do not download
             log::warn!("attachment removed by filter: {attachment_url}");
             unprocessed.push(attachment_url);
             continue;
         };
-        if downloaded.len() >= ap_client.limits.posts.attachment_limit {
+        if downloaded.len() >= attachment_limit {
             // Stop downloading if limit is reached
             log::warn!("too many attachments");
             unprocessed.push(attachment_url);
@@ -525,24 +570,44 @@ async fn get_object_attachments(
                 continue;
             },
         };
-        let media_info = if is_proxy_enabled {
+        let (media_info, maybe_metadata) = if is_proxy_enabled {
             log::info!("linked attachment {}", attachment_url);
-            MediaInfo::link(media_type, attachment_url)
+            // Use dimensions provided by the remote server
+            let metadata = MediaMetadata {
+                width: attachment.width,
+                height: attachment.height,
+                ..Default::default()
+            };
+            let maybe_metadata = (!metadata.is_empty()).then_some(metadata);
+            (MediaInfo::link(media_type, attachment_url), maybe_metadata)
         } else {
             let file_info = ap_client.media_storage
                 .save_file(file_data, &media_type)?;
             log::info!("downloaded attachment {}", attachment_url);
-            MediaInfo::remote(file_info, attachment_url)
+            let maybe_metadata = get_media_metadata(
+                &ap_client.limits.media,
+                &ap_client.media_storage,
+                &file_info.file_name,
+                &file_info.media_type,
+            ).await;
+            (MediaInfo::remote(file_info, attachment_url), maybe_metadata)
         };
-        downloaded.push((media_info, maybe_description));
+        downloaded.push((
+            media_info,
+            maybe_description,
+            is_sensitive,
+            maybe_metadata,
+        ));
     };
     let db_client = &**get_database_client(db_pool).await?;
-    for (media_info, description) in downloaded {
+    for (media_info, description, is_sensitive, metadata) in downloaded {
         let db_attachment = create_attachment(
             db_client,
             author.id,
             media_info,
             description.as_deref(),
+            is_sensitive,
+            metadata,
         ).await?;
         attachments.push(db_attachment.id);
     };
@@ -739,6 +804,16 @@ async fn get_object_tags(
                 log::warn!("post can not be linked");
                 continue;
             };
+            if linked.is_local() && !is_local_quote_allowed(
+                db_client_await!(db_pool),
+                &authority,
+                object,
+                author,
+                &linked,
+            ).await? {
+                log::warn!("quote is not authorized");
+                continue;
+            };
             if !links.contains(&linked.id) {
                 links.push(linked.id);
             };
@@ -802,12 +877,18 @@ async fn get_object_tags(
             &authority,
             &canonical_object_id,
         ).await?;
-        if can_link_post(&linked) {
-            if links.len() < LINK_LIMIT && !links.contains(&linked.id) {
-                links.push(linked.id);
-            };
-        } else {
+        if !can_link_post(&linked) {
             log::warn!("post can not be linked");
+        } else if linked.is_local() && !is_local_quote_allowed(
+            db_client,
+            &authority,
+            object,
+            author,
+            &linked,
+        ).await? {
+            log::warn!("quote is not authorized");
+        } else if links.len() < LINK_LIMIT && !links.contains(&linked.id) {
+            links.push(linked.id);
         };
     };
 
@@ -826,6 +907,52 @@ async fn get_object_tags(
     Ok((mentions, hashtags, links, emojis))
 }
 
+/// FEP-044f: quotes of local posts must be authorized.
+/// Quotes from servers that don't support authorization are accepted
+/// if they would be approved automatically.
+async fn is_local_quote_allowed(
+    db_client: &impl DatabaseClient,
+    authority: &Authority,
+    object: &AttributedObject,
+    author: &DbActorProfile,
+    quoted: &PostDetailed,
+) -> Result<bool, HandlerError> {
+    if object.quote_authorization.is_some() {
+        return is_quote_authorized(db_client, authority, object, quoted).await;
+    };
+    let is_allowed = can_quote_post(db_client, quoted, author.id).await?;
+    Ok(is_allowed)
+}
+
+async fn is_quote_authorized(
+    db_client: &impl DatabaseClient,
+    authority: &Authority,
+    object: &AttributedObject,
+    quoted: &PostDetailed,
+) -> Result<bool, HandlerError> {
+    let Some(ref authorization_id) = object.quote_authorization else {
+        return Ok(false);
+    };
+    let Ok(authorization_id) = parse_local_quote_authorization_id(
+        authority,
+        authorization_id,
+    ) else {
+        return Ok(false);
+    };
+    let authorization = match get_local_quote_authorization(
+        db_client,
+        authorization_id,
+    ).await {
+        Ok(authorization) => authorization,
+        Err(DatabaseError::NotFound(_)) => return Ok(false),
+        Err(other_error) => return Err(other_error.into()),
+    };
+    let canonical_object_id = canonicalize_id(&object.id)?;
+    let is_authorized = authorization.quoted_post_id == quoted.id &&
+        authorization.quote_object_id == Some(canonical_object_id.to_string());
+    Ok(is_authorized)
+}
+
 pub(super) fn normalize_audience(
     audience: &[impl AsRef<str>],
 ) -> Result<Vec<CanonicalUri>, ValidationError> {
@@ -845,7 +972,7 @@ pub(super) fn normalize_audience(
     Ok(normalized_audience)
 }
 
-pub(super) fn get_audience(
+pub(crate) fn get_audience(
     object: &AttributedObject,
 ) -> Result<Vec<String>, ValidationError> {
     let mut audience = vec![];
@@ -855,7 +982,7 @@ pub(super) fn get_audience(
     Ok(audience)
 }
 
-fn get_object_visibility(
+pub(crate) fn get_object_visibility(
     author: &DbActorProfile,
     context_id: Option<&CanonicalUri>,
     audience: &[String],
@@ -924,6 +1051,28 @@ fn get_object_visibility(
     }
 }
 
+/// Changes visibility of a public post to followers-only
+pub(crate) fn reduce_visibility(
+    author: &DbActorProfile,
+    context: PostContext,
+) -> (Visibility, PostContext) {
+    let maybe_followers = author.expect_actor_data().followers.clone();
+    let visibility = if maybe_followers.is_some() {
+        Visibility::Followers
+    } else {
+        Visibility::Direct
+    };
+    let context = match context {
+        PostContext::Top { group_id, object_id, .. } => PostContext::Top {
+            group_id,
+            object_id,
+            audience: maybe_followers,
+        },
+        other => other,
+    };
+    (visibility, context)
+}
+
 fn parse_poll_results(
     object: &AttributedObject,
 ) -> Result<PollData, ValidationError> {
@@ -1021,7 +1170,10 @@ pub async fn create_remote_post(
         None => None,
     };
 
-    let (maybe_title, mut content) = get_object_content(&object)?;
+    let (maybe_title, mut content) = get_object_content(
+        &ap_client.limits.posts,
+        &object,
+    )?;
     let maybe_poll_data = if object.object_type == QUESTION {
         match parse_poll_results(&object) {
             Ok(poll_data) => Some(poll_data),
@@ -1049,13 +1201,14 @@ pub async fn create_remote_post(
         content += &create_content_link(&attachment_url);
     };
 
-    let (mentions, hashtags, links, emojis) = get_object_tags(
+    // Tag processing future is large, allocate it on the heap
+    let (mentions, hashtags, links, emojis) = Box::pin(get_object_tags(
         ap_client,
         db_pool,
         &object,
         &author,
         redirects,
-    ).await?;
+    )).await?;
 
     let maybe_group_id = if let Some(actor_id) = object.audience.first() {
         match ActorIdResolver::default().only_remote().resolve(
@@ -1105,9 +1258,20 @@ pub async fn create_remote_post(
         maybe_in_reply_to.as_ref(),
         maybe_group_id,
     );
+    let (visibility, context) = if visibility == Visibility::Public &&
+        ap_client.filter.apply_action(
+            author_hostname.as_str(),
+            FilterAction::ReduceVisibility,
+        )
+    {
+        log::info!("reducing post visibility");
+        reduce_visibility(&author, context)
+    } else {
+        (visibility, context)
+    };
     let is_sensitive =
         object.sensitive.unwrap_or(false) ||
-        ap_client.filter.is_action_required(
+        ap_client.filter.apply_action(
             author_hostname.as_str(),
             FilterAction::MarkSensitive,
         );
@@ -1126,6 +1290,7 @@ pub async fn create_remote_post(
         content: content,
         content_source: None,
         language: object.language(),
+        content_map: vec![],
         visibility,
         is_sensitive,
         poll: maybe_poll_data,
@@ -1149,13 +1314,20 @@ pub async fn create_remote_post(
             &post_data.mentions,
         ).unwrap_or_else(|error| log::warn!("{error}"));
     };
-    check_post_limits(&ap_client.limits.posts, &post_data.attachments, Remote)?;
+    check_post_limits(
+        &ap_client.limits.posts,
+        &post_data.attachments,
+        post_data.poll.as_ref(),
+        Remote,
+    )?;
     let post = create_post(db_client, author.id, post_data).await?;
     save_attributed_object(
         db_client,
         &canonical_object_id.to_string(),
         &object_value,
         post.id,
+        ap_client.instance.federation.compress_attributed_objects &&
+            !author.is_portable(),
     ).await?;
     Ok(post)
 }
@@ -1191,7 +1363,10 @@ pub async fn update_remote_post(
         return Err(ValidationError("inReplyTo can't be changed").into());
     };
 
-    let (maybe_title, mut content) = get_object_content(object)?;
+    let (maybe_title, mut content) = get_object_content(
+        &ap_client.limits.posts,
+        object,
+    )?;
     let maybe_poll_data = if object.object_type == QUESTION {
         match parse_poll_results(object) {
             Ok(poll_data) => {
@@ -1225,16 +1400,17 @@ pub async fn update_remote_post(
     for attachment_url in unprocessed {
         content += &create_content_link(&attachment_url);
     };
-    let (mentions, hashtags, links, emojis) = get_object_tags(
+    // Tag processing future is large, allocate it on the heap
+    let (mentions, hashtags, links, emojis) = Box::pin(get_object_tags(
         ap_client,
         db_pool,
         object,
         &post.author,
         &HashMap::new(),
-    ).await?;
+    )).await?;
     let is_sensitive =
         object.sensitive.unwrap_or(false) ||
-        ap_client.filter.is_action_required(
+        ap_client.filter.apply_action(
             author_hostname.as_str(),
             FilterAction::MarkSensitive,
         );
@@ -1270,6 +1446,7 @@ pub async fn update_remote_post(
         content,
         content_source: None,
         language: object.language(),
+        content_map: vec![],
         is_sensitive,
         poll: maybe_poll_data,
         attachments,
@@ -1291,7 +1468,12 @@ pub async fn update_remote_post(
             &post_data.mentions,
         ).unwrap_or_else(|error| log::warn!("{error}"));
     };
-    check_post_limits(&ap_client.limits.posts, &post_data.attachments, Remote)?;
+    check_post_limits(
+        &ap_client.limits.posts,
+        &post_data.attachments,
+        post_data.poll.as_ref(),
+        Remote,
+    )?;
     let (post, deletion_queue) =
         update_post(db_client, post.id, post_data).await?;
     deletion_queue.into_job(db_client).await?;
@@ -1300,6 +1482,8 @@ pub async fn update_remote_post(
         post.expect_remote_object_id(),
         object_json,
         post.id,
+        ap_client.instance.federation.compress_attributed_objects &&
+            !post.author.is_portable(),
     ).await?;
     Ok(post)
 }
@@ -1374,7 +1558,10 @@ mod tests {
             object_type: NOTE.to_string(),
             ..Default::default()
         };
-        let (maybe_title, content) = get_object_content(&object).unwrap();
+        let (maybe_title, content) = get_object_content(
+            &PostLimits::default(),
+            &object,
+        ).unwrap();
         assert!(maybe_title.is_none());
         assert_eq!(content, "test");
     }
@@ -1392,7 +1579,10 @@ mod tests {
             }])),
             ..Default::default()
         };
-        let (maybe_title, mut content) = get_object_content(&object).unwrap();
+        let (maybe_title, mut content) = get_object_content(
+            &PostLimits::default(),
+            &object,
+        ).unwrap();
         assert_eq!(maybe_title.unwrap(), "test-name");
         let object_url = get_object_url(&object).unwrap().unwrap();
         content += &create_content_link(&object_url);
@@ -1573,6 +1763,30 @@ mod tests {
         assert!(matches!(context, PostContext::Reply { .. }));
     }
 
+    #[test]
+    fn test_reduce_visibility() {
+        let author_id = "https://example.com/users/author";
+        let author_followers = "https://example.com/users/author/followers";
+        let author = DbActorProfile::remote_for_test_with_data(
+            "author",
+            DbActor {
+                id: author_id.to_string(),
+                followers: Some(author_followers.to_string()),
+                ..Default::default()
+            },
+        );
+        let context = PostContext::Top {
+            group_id: None,
+            object_id: None,
+            audience: Some(AP_PUBLIC.to_string()),
+        };
+        let (visibility, context) = reduce_visibility(&author, context);
+        assert_eq!(visibility, Visibility::Followers);
+        let PostContext::Top { audience, .. } =
+            context else { unreachable!() };
+        assert_eq!(audience.unwrap(), author_followers);
+    }
+
     #[test]
     fn test_get_object_visibility_subscribers() {
         let author_id = "https://example.com/users/author";
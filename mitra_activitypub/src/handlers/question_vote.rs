@@ -21,7 +21,10 @@ use mitra_validators::errors::ValidationError;
 
 use crate::{
     authority::Authority,
-    builders::update_note::prepare_update_note,
+    builders::{
+        create_note::save_note_recipients,
+        update_note::prepare_update_note,
+    },
     identifiers::parse_local_object_id,
     importers::{
         ActorIdResolver,
@@ -109,13 +112,18 @@ pub async fn handle_question_vote(
     add_related_posts(db_client, vec![&mut post]).await?;
     let post_author = get_user_by_id(db_client, post.author.id).await?;
     let media_server = MediaServer::new(config);
-    prepare_update_note(
+    let (update_note, maybe_create_note) = prepare_update_note(
         db_client,
         instance,
         &media_server,
         &post_author,
         &post,
-    ).await?.save_and_enqueue(db_client).await?;
+    ).await?;
+    update_note.save_and_enqueue(db_client).await?;
+    if let Some(create_note) = maybe_create_note {
+        create_note.save_and_enqueue(db_client).await?;
+        save_note_recipients(db_client, &post).await?;
+    };
     Ok(Some(Descriptor::object("Vote")))
 }
 
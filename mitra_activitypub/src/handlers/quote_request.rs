@@ -0,0 +1,238 @@
+use apx_sdk::deserialization::deserialize_into_object_id;
+use serde::Deserialize;
+use serde_json::{Value as JsonValue};
+use uuid::Uuid;
+
+use mitra_config::{Config, Instance};
+use mitra_models::{
+    accounts::queries::get_user_by_id,
+    database::{
+        get_database_client,
+        DatabaseClient,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    posts::{
+        helpers::{add_related_posts, can_quote_post, get_local_post_by_id},
+        queries::delete_post_link,
+    },
+    profiles::queries::get_remote_profile_by_actor_id,
+    quote_authorizations::queries::{
+        create_local_quote_authorization,
+        delete_remote_quote_authorization,
+        save_remote_quote_authorization,
+    },
+};
+use mitra_services::media::MediaServer;
+use mitra_validators::{
+    activitypub::validate_any_object_id,
+    errors::ValidationError,
+};
+
+use crate::{
+    authority::Authority,
+    builders::{
+        accept_quote_request::prepare_accept_quote_request,
+        reject_quote_request::prepare_reject_quote_request,
+        create_note::save_note_recipients,
+        update_note::prepare_update_note,
+    },
+    identifiers::{
+        canonicalize_id,
+        parse_local_object_id,
+        parse_local_quote_request_id,
+    },
+    importers::{ActorIdResolver, ApClient},
+    ownership::is_same_origin,
+    vocabulary::{NOTE, QUOTE_REQUEST},
+};
+
+use super::{Descriptor, HandlerResult};
+
+#[derive(Deserialize)]
+struct QuoteRequest {
+    id: String,
+    #[serde(deserialize_with = "deserialize_into_object_id")]
+    actor: String,
+    #[serde(deserialize_with = "deserialize_into_object_id")]
+    object: String,
+    #[serde(deserialize_with = "deserialize_into_object_id")]
+    instrument: String,
+}
+
+/// Handles FEP-044f quote request.
+/// Quotes are approved automatically if allowed by
+/// post visibility and author's quote policy.
+pub async fn handle_quote_request(
+    ap_client: &ApClient,
+    db_pool: &DatabaseConnectionPool,
+    activity: JsonValue,
+) -> HandlerResult {
+    let request: QuoteRequest = serde_json::from_value(activity)?;
+    let authority = Authority::from(&ap_client.instance);
+    let Ok(post_id) = parse_local_object_id(&authority, &request.object) else {
+        // Not a local post
+        return Ok(None);
+    };
+    if !is_same_origin(&request.instrument, &request.actor)? {
+        return Err(ValidationError("quote is not owned by actor").into());
+    };
+    let canonical_quote_id = canonicalize_id(&request.instrument)?;
+    validate_any_object_id(&canonical_quote_id.to_string())?;
+    let requester = ActorIdResolver::default().only_remote().resolve(
+        ap_client,
+        db_pool,
+        &request.actor,
+    ).await?;
+    let requester_actor = requester.expect_actor_data();
+    let db_client = &**get_database_client(db_pool).await?;
+    let post = get_local_post_by_id(db_client, post_id).await?;
+    let author = get_user_by_id(db_client, post.author.id).await?;
+    if can_quote_post(db_client, &post, requester.id).await? {
+        let authorization = create_local_quote_authorization(
+            db_client,
+            post.id,
+            &canonical_quote_id.to_string(),
+        ).await?;
+        prepare_accept_quote_request(
+            &ap_client.instance,
+            &author,
+            requester_actor,
+            &request.id,
+            authorization.id,
+        )?.save_and_enqueue(db_client).await?;
+    } else {
+        log::info!("quote request rejected: {}", request.id);
+        prepare_reject_quote_request(
+            &ap_client.instance,
+            &author,
+            requester_actor,
+            &request.id,
+        )?.save_and_enqueue(db_client).await?;
+    };
+    Ok(Some(Descriptor::object(NOTE)))
+}
+
+/// Sends Update(Note) after the change of quote status
+async fn update_quote_post(
+    config: &Config,
+    instance: &Instance,
+    db_client: &impl DatabaseClient,
+    post_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let mut post = get_local_post_by_id(db_client, post_id).await?;
+    add_related_posts(db_client, vec![&mut post]).await?;
+    let author = get_user_by_id(db_client, post.author.id).await?;
+    let media_server = MediaServer::new(config);
+    let (update_note, maybe_create_note) = prepare_update_note(
+        db_client,
+        instance,
+        &media_server,
+        &author,
+        &post,
+    ).await?;
+    update_note.save_and_enqueue(db_client).await?;
+    if let Some(create_note) = maybe_create_note {
+        create_note.save_and_enqueue(db_client).await?;
+        save_note_recipients(db_client, &post).await?;
+    };
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct AcceptQuoteRequest {
+    #[serde(deserialize_with = "deserialize_into_object_id")]
+    actor: String,
+    #[serde(deserialize_with = "deserialize_into_object_id")]
+    object: String,
+    #[serde(deserialize_with = "deserialize_into_object_id")]
+    result: String,
+}
+
+pub(super) async fn handle_accept_quote_request(
+    config: &Config,
+    ap_client: &ApClient,
+    db_pool: &DatabaseConnectionPool,
+    activity: JsonValue,
+) -> HandlerResult {
+    let accept: AcceptQuoteRequest = serde_json::from_value(activity)?;
+    let authority = Authority::from(&ap_client.instance);
+    let post_id = parse_local_quote_request_id(&authority, &accept.object)?;
+    if !is_same_origin(&accept.result, &accept.actor)? {
+        return Err(ValidationError("authorization is not owned by actor").into());
+    };
+    let canonical_authorization_id = canonicalize_id(&accept.result)?;
+    validate_any_object_id(&canonical_authorization_id.to_string())?;
+    let db_client = &**get_database_client(db_pool).await?;
+    let canonical_actor_id = canonicalize_id(&accept.actor)?;
+    let actor_profile = get_remote_profile_by_actor_id(
+        db_client,
+        &canonical_actor_id.to_string(),
+    ).await?;
+    let mut post = get_local_post_by_id(db_client, post_id).await?;
+    add_related_posts(db_client, vec![&mut post]).await?;
+    let related_posts = post.expect_related_posts();
+    let quoted = related_posts.linked.first()
+        .ok_or(ValidationError("post is not a quote"))?;
+    if quoted.author.id != actor_profile.id {
+        return Err(ValidationError("actor is not an author of quoted post").into());
+    };
+    if related_posts.quote_authorization.as_deref() ==
+        Some(&canonical_authorization_id.to_string())
+    {
+        // Ignore Accept if quote has already been authorized
+        return Ok(None);
+    };
+    save_remote_quote_authorization(
+        db_client,
+        quoted.id,
+        post.id,
+        &canonical_authorization_id.to_string(),
+    ).await?;
+    update_quote_post(config, &ap_client.instance, db_client, post.id).await?;
+    Ok(Some(Descriptor::object(QUOTE_REQUEST)))
+}
+
+#[derive(Deserialize)]
+struct RejectQuoteRequest {
+    #[serde(deserialize_with = "deserialize_into_object_id")]
+    actor: String,
+    #[serde(deserialize_with = "deserialize_into_object_id")]
+    object: String,
+}
+
+pub(super) async fn handle_reject_quote_request(
+    config: &Config,
+    ap_client: &ApClient,
+    db_pool: &DatabaseConnectionPool,
+    activity: JsonValue,
+) -> HandlerResult {
+    let reject: RejectQuoteRequest = serde_json::from_value(activity)?;
+    let authority = Authority::from(&ap_client.instance);
+    let post_id = parse_local_quote_request_id(&authority, &reject.object)?;
+    let db_client = &**get_database_client(db_pool).await?;
+    let canonical_actor_id = canonicalize_id(&reject.actor)?;
+    let actor_profile = get_remote_profile_by_actor_id(
+        db_client,
+        &canonical_actor_id.to_string(),
+    ).await?;
+    let mut post = match get_local_post_by_id(db_client, post_id).await {
+        Ok(post) => post,
+        // Ignore Reject if quote post has been deleted
+        Err(DatabaseError::NotFound(_)) => return Ok(None),
+        Err(other_error) => return Err(other_error.into()),
+    };
+    add_related_posts(db_client, vec![&mut post]).await?;
+    let Some(quoted) = post.expect_related_posts().linked.first() else {
+        // Ignore Reject if quote has already been removed
+        return Ok(None);
+    };
+    if quoted.author.id != actor_profile.id {
+        return Err(ValidationError("actor is not an author of quoted post").into());
+    };
+    // Quote is not authorized (or authorization is revoked)
+    delete_remote_quote_authorization(db_client, post.id).await?;
+    delete_post_link(db_client, post.id, quoted.id).await?;
+    update_quote_post(config, &ap_client.instance, db_client, post.id).await?;
+    Ok(Some(Descriptor::object(QUOTE_REQUEST)))
+}
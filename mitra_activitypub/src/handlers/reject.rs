@@ -1,7 +1,8 @@
-use apx_sdk::deserialization::deserialize_into_object_id;
+use apx_sdk::deserialization::{deserialize_into_object_id, object_to_id};
 use serde::Deserialize;
 use serde_json::Value;
 
+use mitra_config::Config;
 use mitra_models::{
     database::{
         get_database_client,
@@ -18,13 +19,14 @@ use mitra_validators::errors::ValidationError;
 
 use crate::{
     authority::Authority,
-    identifiers::canonicalize_id,
+    identifiers::{canonicalize_id, parse_local_quote_request_id},
     importers::ApClient,
     vocabulary::FOLLOW,
 };
 
 use super::{
     accept::get_follow_request_by_activity_id,
+    quote_request::handle_reject_quote_request,
     Descriptor,
     HandlerResult,
 };
@@ -37,10 +39,23 @@ struct Reject {
 }
 
 pub async fn handle_reject(
+    config: &Config,
     ap_client: &ApClient,
     db_pool: &DatabaseConnectionPool,
     activity: Value,
 ) -> HandlerResult {
+    let authority = Authority::from(&ap_client.instance);
+    if let Ok(object_id) = object_to_id(&activity["object"]) {
+        if parse_local_quote_request_id(&authority, &object_id).is_ok() {
+            // Reject(QuoteRequest)
+            return handle_reject_quote_request(
+                config,
+                ap_client,
+                db_pool,
+                activity,
+            ).await;
+        };
+    };
     // Reject(Follow)
     let reject: Reject = serde_json::from_value(activity)?;
     let db_client = &mut **get_database_client(db_pool).await?;
@@ -49,7 +64,6 @@ pub async fn handle_reject(
         &reject.actor,
     ).await?;
     let canonical_object_id = canonicalize_id(&reject.object)?;
-    let authority = Authority::from(&ap_client.instance);
     let follow_request = match get_follow_request_by_activity_id(
         db_client,
         &authority,
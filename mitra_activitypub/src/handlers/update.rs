@@ -129,6 +129,7 @@ async fn handle_update_person(
     ap_client: &ApClient,
     db_pool: &DatabaseConnectionPool,
     activity: JsonValue,
+    is_fetched: bool,
 ) -> HandlerResult {
     let update: UpdatePerson = serde_json::from_value(activity)?;
     if update.object.id() != update.actor {
@@ -144,11 +145,24 @@ async fn handle_update_person(
         Err(DatabaseError::NotFound(_)) => return Ok(None),
         Err(other_error) => return Err(other_error.into()),
     };
+    let actor = if !is_fetched &&
+        update.object.have_public_keys_changed(&profile)?
+    {
+        // Key rotation: use the document provided by actor's server
+        log::info!("re-fetching actor {} after key change", update.object.id());
+        let actor: Actor = ap_client.fetch_object(update.object.id()).await?;
+        if actor.id() != update.object.id() {
+            return Err(ValidationError("actor ID mismatch").into());
+        };
+        actor
+    } else {
+        update.object
+    };
     let profile = update_remote_profile(
         ap_client,
         db_pool,
         profile,
-        update.object,
+        actor,
     ).await?;
     let actor_type = &profile.expect_actor_data().object_type;
     Ok(Some(Descriptor::object(actor_type)))
@@ -161,7 +175,8 @@ pub async fn handle_update(
     is_authenticated: bool,
 ) -> HandlerResult {
     let is_not_embedded = activity["object"].as_str().is_some();
-    if is_not_embedded || !is_authenticated {
+    let is_fetched = is_not_embedded || !is_authenticated;
+    if is_fetched {
         // Fetch object if it is not embedded or if activity is forwarded
         let object_id = object_to_id(&activity["object"])
             .map_err(|_| ValidationError("invalid activity object"))?;
@@ -180,7 +195,7 @@ pub async fn handle_update(
         },
     };
     if is_actor(&activity["object"]) {
-        handle_update_person(ap_client, db_pool, activity).await
+        handle_update_person(ap_client, db_pool, activity, is_fetched).await
     } else if is_object(&activity["object"]) {
         verify_object_owner(&activity["object"])?;
         if activity["object"]["type"].as_str() == Some(AGREEMENT) {
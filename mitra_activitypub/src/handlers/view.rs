@@ -0,0 +1,79 @@
+//! Listen, Read and View activities
+//! (used by Funkwhale, BookWyrm and PeerTube).
+use apx_sdk::deserialization::deserialize_into_object_id;
+use serde::Deserialize;
+use serde_json::{Value as JsonValue};
+
+use mitra_models::{
+    database::{
+        db_client_await,
+        get_database_client,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    notifications::helpers::create_view_notification,
+    posts::{
+        helpers::get_local_post_by_id,
+        types::Visibility,
+    },
+};
+
+use crate::{
+    authority::Authority,
+    identifiers::{canonicalize_id, parse_local_object_id},
+    importers::{ActorIdResolver, ApClient},
+    vocabulary::NOTE,
+};
+
+use super::{Descriptor, HandlerResult};
+
+#[derive(Deserialize)]
+struct View {
+    #[serde(deserialize_with = "deserialize_into_object_id")]
+    actor: String,
+
+    #[serde(deserialize_with = "deserialize_into_object_id")]
+    object: String,
+}
+
+pub async fn handle_view(
+    ap_client: &ApClient,
+    db_pool: &DatabaseConnectionPool,
+    activity: JsonValue,
+) -> HandlerResult {
+    let view: View = serde_json::from_value(activity)?;
+    let canonical_object_id = canonicalize_id(&view.object)?;
+    let authority = Authority::from(&ap_client.instance);
+    let Ok(post_id) = parse_local_object_id(
+        &authority,
+        &canonical_object_id.to_string(),
+    ) else {
+        // Not a local post
+        return Ok(None);
+    };
+    let post = match get_local_post_by_id(
+        db_client_await!(db_pool),
+        post_id,
+    ).await {
+        Ok(post) => post,
+        Err(DatabaseError::NotFound(_)) => return Ok(None),
+        Err(other_error) => return Err(other_error.into()),
+    };
+    if post.visibility != Visibility::Public {
+        // Don't reveal non-public posts
+        return Ok(None);
+    };
+    let viewer = ActorIdResolver::default().only_remote().resolve(
+        ap_client,
+        db_pool,
+        &view.actor,
+    ).await?;
+    let db_client = &**get_database_client(db_pool).await?;
+    create_view_notification(
+        db_client,
+        viewer.id,
+        post.author.id,
+        post.id,
+    ).await?;
+    Ok(Some(Descriptor::object(NOTE)))
+}
@@ -51,6 +51,7 @@ pub enum LocalActorCollection {
     Following,
     Subscribers,
     Featured,
+    SubscribersOnly,
 }
 
 impl LocalActorCollection {
@@ -63,6 +64,7 @@ impl LocalActorCollection {
             // TODO: collections/subscribers
             Self::Subscribers => "subscribers",
             Self::Featured => "collections/featured",
+            Self::SubscribersOnly => "collections/subscribers_only",
         };
         format!("{}/{}", actor_id, name)
     }
@@ -125,6 +127,13 @@ pub fn local_agreement_id(instance_uri: &str, invoice_id: Uuid) -> String {
     format!("{}/objects/agreements/{}", instance_uri, invoice_id)
 }
 
+pub fn local_quote_authorization_id(
+    instance_uri: &str,
+    authorization_id: Uuid,
+) -> String {
+    format!("{}/objects/quote_authorizations/{}", instance_uri, authorization_id)
+}
+
 // This URI redirects to the web client (it is not an actual collection)
 pub fn local_tag_collection(instance_uri: &str, tag_name: &str) -> String {
     format!("{}/collections/tags/{}", instance_uri, url_encode(tag_name))
@@ -271,6 +280,36 @@ pub(crate) fn parse_local_activity_id(
     Ok(internal_activity_id)
 }
 
+pub(crate) fn parse_local_quote_request_id(
+    authority: &Authority,
+    activity_id: &str,
+) -> Result<Uuid, ValidationError> {
+    let path_re = Regex::new("^/activities/quoterequest/(?P<uuid>[0-9a-f-]+)$")
+        .expect("regexp should be valid");
+    let (base_uri, (internal_post_id,)) =
+        parse_object_id(activity_id, path_re)
+            .map_err(|_| ValidationError("invalid local quote request ID"))?;
+    if base_uri != authority.root().to_string() {
+        return Err(ValidationError("authority mismatch"));
+    };
+    Ok(internal_post_id)
+}
+
+pub(crate) fn parse_local_quote_authorization_id(
+    authority: &Authority,
+    authorization_id: &str,
+) -> Result<Uuid, ValidationError> {
+    let path_re = Regex::new("^/objects/quote_authorizations/(?P<uuid>[0-9a-f-]+)$")
+        .expect("regexp should be valid");
+    let (base_uri, (internal_authorization_id,)) =
+        parse_object_id(authorization_id, path_re)
+            .map_err(|_| ValidationError("invalid local quote authorization ID"))?;
+    if base_uri != authority.root().to_string() {
+        return Err(ValidationError("authority mismatch"));
+    };
+    Ok(internal_authorization_id)
+}
+
 // Returns canonical post URI
 pub fn post_object_id(authority: &Authority, post: &PostDetailed) -> String {
     match post.object_id {
@@ -622,6 +661,40 @@ mod tests {
         assert_eq!(internal_id, expected_internal_id);
     }
 
+    #[test]
+    fn test_parse_local_quote_request_id() {
+        let authority = Authority::server_unchecked(INSTANCE_URI);
+        let expected_post_id = generate_ulid();
+        let activity_id =
+            local_activity_id(INSTANCE_URI, "QuoteRequest", expected_post_id);
+        let post_id = parse_local_quote_request_id(
+            &authority,
+            &activity_id,
+        ).unwrap();
+        assert_eq!(post_id, expected_post_id);
+
+        let like_id = local_activity_id(INSTANCE_URI, "Like", expected_post_id);
+        let result = parse_local_quote_request_id(&authority, &like_id);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_local_quote_authorization_id() {
+        let authority = Authority::server_unchecked(INSTANCE_URI);
+        let expected_id = generate_ulid();
+        let authorization_id =
+            local_quote_authorization_id(INSTANCE_URI, expected_id);
+        assert_eq!(
+            authorization_id,
+            format!("{INSTANCE_URI}/objects/quote_authorizations/{expected_id}"),
+        );
+        let internal_id = parse_local_quote_authorization_id(
+            &authority,
+            &authorization_id,
+        ).unwrap();
+        assert_eq!(internal_id, expected_id);
+    }
+
     #[test]
     fn test_profile_actor_url() {
         let authority = Authority::server_unchecked(INSTANCE_URI);
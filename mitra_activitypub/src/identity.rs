@@ -17,8 +17,12 @@ use mitra_models::profiles::types::{
     IdentityProof as DbIdentityProof,
     IdentityProofType,
 };
+use mitra_validators::errors::ValidationError;
 
-use super::vocabulary::VERIFIABLE_IDENTITY_STATEMENT;
+use super::{
+    actors::attachments::parse_identity_proof_fep_c390,
+    vocabulary::VERIFIABLE_IDENTITY_STATEMENT,
+};
 
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -127,9 +131,24 @@ pub fn create_identity_proof_fep_c390(
         issuer: identity_proof.statement.subject,
         proof_type: proof_type.clone(),
         value: proof_value,
+        verified_at: None,
     }
 }
 
+/// Verifies stored identity proof again
+/// (only FEP-c390 proofs are supported)
+pub fn verify_identity_proof(
+    actor_id: &str,
+    proof: &DbIdentityProof,
+) -> Result<DbIdentityProof, ValidationError> {
+    let verified_proof =
+        parse_identity_proof_fep_c390(actor_id, &proof.value)?;
+    if verified_proof.issuer != proof.issuer {
+        return Err(ValidationError("issuer mismatch"));
+    };
+    Ok(verified_proof)
+}
+
 #[cfg(test)]
 mod tests {
     use apx_core::{
@@ -274,5 +293,15 @@ mod tests {
             &signature_data.signature,
         );
         assert_eq!(result.is_ok(), true);
+
+        let verified_proof = verify_identity_proof(actor_id, &db_proof)
+            .unwrap();
+        assert_eq!(verified_proof.issuer, db_proof.issuer);
+        assert_eq!(verified_proof.verified_at.is_some(), true);
+        let error = verify_identity_proof(
+            "https://server.example/users/bob",
+            &db_proof,
+        ).err().unwrap();
+        assert_eq!(error.to_string(), "actor ID mismatch");
     }
 }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use apx_core::{
     crypto::{
@@ -19,7 +20,6 @@ use apx_sdk::{
     },
     deserialization::{deserialize_into_object_id_opt, object_to_id},
     fetch::{
-        fetch_object,
         FetchedObject,
         FetchError,
         FetchObjectOptions,
@@ -33,6 +33,10 @@ use serde::{
 };
 use serde_json::{Value as JsonValue};
 
+use mitra_adapters::{
+    dynamic_config::get_dynamic_config,
+    reactions::ReactionFilter,
+};
 use mitra_config::{Config, Instance, Limits, RegistrationType};
 use mitra_models::{
     accounts::{
@@ -41,6 +45,7 @@ use mitra_models::{
             get_managed_account_by_id,
             get_managed_account_by_username,
             get_portable_user_by_actor_id,
+            get_portable_user_count,
             get_user_by_id,
             get_user_by_name,
             is_valid_invite_code,
@@ -104,6 +109,7 @@ use crate::{
         UuidOrUsername,
     },
     ownership::{get_object_id, is_local_origin, verify_object_owner},
+    transport::{HttpTransport, Transport},
     webfinger::perform_webfinger_query,
 };
 
@@ -167,9 +173,11 @@ impl FetcherContext {
 pub struct ApClient {
     pub instance: Instance,
     pub filter: FederationFilter,
-    pub limits: Limits,
+    pub reaction_filter: ReactionFilter,
+    pub limits: Arc<Limits>,
     pub media_storage: MediaStorage,
     pub as_user: Option<User>,
+    pub transport: Arc<dyn Transport>,
 }
 
 impl ApClient {
@@ -180,9 +188,11 @@ impl ApClient {
         let ap_client = Self {
             instance: config.instance(),
             filter: FederationFilter::init(config, db_client).await?,
-            limits: config.limits.clone(),
+            reaction_filter: ReactionFilter::init(db_client).await?,
+            limits: config.limits(),
             media_storage: MediaStorage::new(config),
             as_user: None,
+            transport: Arc::new(HttpTransport),
         };
         Ok(ap_client)
     }
@@ -202,13 +212,23 @@ impl ApClient {
         )
     }
 
+    /// Returns agent that caches WebFinger lookup results
+    pub fn agent_with_webfinger_cache(
+        &self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> FederationAgent {
+        let mut agent = self.agent();
+        agent.webfinger_cache = Some(self.transport.webfinger_cache(db_pool));
+        agent
+    }
+
     pub async fn fetch_object_raw(
         &self,
         object_id: &str,
         options: FetchObjectOptions,
     ) -> Result<FetchedObject, FetchError> {
         let agent = self.agent();
-        let object = fetch_object(
+        let object = self.transport.fetch_object(
             &agent,
             object_id,
             options,
@@ -224,7 +244,7 @@ impl ApClient {
         let hostname = HttpUri::parse(object_id)
             .map_err(|_| ValidationError("invalid HTTP URI"))?
             .hostname();
-        if self.filter.is_action_required(
+        if self.filter.apply_action(
             hostname.as_str(),
             FilterAction::Reject,
         ) {
@@ -504,7 +524,7 @@ pub async fn import_actor_by_webfinger_address(
     if webfinger_address.hostname() == ap_client.instance.webfinger_hostname() {
         return Err(HandlerError::LocalObject);
     };
-    let agent = ap_client.agent();
+    let agent = ap_client.agent_with_webfinger_cache(db_pool);
     let actor_id = perform_webfinger_query(&agent, webfinger_address).await?;
     let actor: JsonValue = ap_client.fetch_object(&actor_id).await?;
     import_actor(ap_client, db_pool, actor).await
@@ -683,12 +703,13 @@ pub(crate) async fn import_post(
     // starting with the root
     objects.reverse();
     for object in objects {
-        let post = create_remote_post(
+        // Post creation future is large, allocate it on the heap
+        let post = Box::pin(create_remote_post(
             ap_client,
             db_pool,
             object,
             &redirects,
-        ).await?;
+        )).await?;
         posts.push(post);
     };
 
@@ -731,6 +752,18 @@ pub async fn import_object(
     }
 }
 
+/// Re-fetches remote object and updates cached post
+pub async fn refetch_object(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+    object_id: &str,
+) -> Result<(), HandlerError> {
+    let ap_client = ApClient::new_with_pool(config, db_pool).await?;
+    let object: JsonValue = ap_client.fetch_object(object_id).await?;
+    import_object(&ap_client, db_pool, object).await?;
+    Ok(())
+}
+
 // Activity must be authenticated
 pub async fn import_activity(
     config: &Config,
@@ -764,6 +797,7 @@ async fn fetch_collection(
         items: Vec<JsonValue>,
         #[serde(default)]
         ordered_items: Vec<JsonValue>,
+        total_items: Option<u64>,
     }
     #[derive(Deserialize)]
     #[serde(rename_all = "camelCase")]
@@ -778,6 +812,15 @@ async fn fetch_collection(
 
     let collection: Collection = ap_client.fetch_object(collection_id).await?;
     log::info!("fetched collection: {collection_id}");
+    if collection.first.is_none() &&
+        collection.items.is_empty() &&
+        collection.ordered_items.is_empty() &&
+        collection.total_items.is_some_and(|total_items| total_items > 0)
+    {
+        // Collection items are hidden (e.g. social graph collections)
+        log::info!("collection is hidden");
+        return Ok(vec![]);
+    };
     let mut items = [collection.items, collection.ordered_items].concat();
 
     let mut page_count = 0;
@@ -1067,6 +1110,13 @@ pub async fn register_portable_actor(
         Err(DatabaseError::NotFound(_)) => (), // continue registration
         Err(other_error) => return Err(other_error.into()),
     };
+    let dynamic_config = get_dynamic_config(db_client_await!(db_pool)).await?;
+    if let Some(limit) = dynamic_config.portable_accounts_limit {
+        let count = get_portable_user_count(db_client_await!(db_pool)).await?;
+        if count >= i64::from(limit) {
+            return Err(ValidationError("portable account limit reached").into());
+        };
+    };
     let maybe_invite_code = match config.registration.registration_type {
         RegistrationType::Open if !config.instance().federation.enabled => None,
         _ => {
@@ -9,6 +9,7 @@ mod constants;
 mod contexts;
 pub mod deliverer;
 pub mod errors;
+pub mod explain;
 pub mod filter;
 pub mod forwarder;
 pub mod handlers;
@@ -18,6 +19,9 @@ pub mod importers;
 pub mod keys;
 pub mod ownership;
 pub mod queues;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+pub mod transport;
 pub mod utils;
 pub mod vocabulary;
 pub mod webfinger;
@@ -1,22 +1,42 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::time::{Duration, Instant};
 
-use apx_sdk::fetch::FetchError;
+use apx_sdk::{
+    deserialization::{object_to_id, parse_into_id_array},
+    fetch::FetchError,
+};
 use chrono::{TimeDelta, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value as JsonValue};
 use uuid::Uuid;
 
-use mitra_config::Config;
+use mitra_adapters::correlation::{
+    generate_correlation_id,
+    get_correlation_id,
+    with_correlation_id,
+    with_span_timing,
+};
+use mitra_config::{Config, Instance};
 use mitra_models::{
     accounts::types::{ManagedAccount, PortableUser},
-    activitypub::queries::{
-        save_activity,
-        add_object_to_collection,
+    activitypub::{
+        queries::{
+            save_activity,
+            save_outgoing_activity_records,
+            add_object_to_collection,
+        },
+        types::{
+            DeliveryRecordData,
+            DeliveryStatus,
+            OutgoingActivityRecordData,
+        },
     },
     background_jobs::{
         queries::{
+            create_dead_letter,
+            delete_dead_letter,
             enqueue_job,
+            get_dead_letter,
             get_job_batch,
             delete_job_from_queue,
         },
@@ -57,6 +77,7 @@ use crate::{
         import_from_outbox,
         import_replies,
         is_actor_importer_error,
+        refetch_object,
         ApClient,
     },
     utils::{db_url_to_http_url, parse_http_url_from_db},
@@ -71,6 +92,8 @@ pub struct IncomingActivityJobData {
     sender_id: Option<String>, // only when delivered to inbox
     is_authenticated: bool,
     failure_count: u32,
+    #[serde(default)]
+    correlation_id: Option<String>,
 }
 
 impl IncomingActivityJobData {
@@ -87,6 +110,7 @@ impl IncomingActivityJobData {
                 .map(|(_, sender_id)| sender_id.to_owned()),
             is_authenticated,
             failure_count: 0,
+            correlation_id: get_correlation_id(),
         }
     }
 
@@ -115,6 +139,78 @@ const fn incoming_queue_backoff(_failure_count: u32) -> u32 {
     60 * 10
 }
 
+async fn process_incoming_activity_job(
+    config: &Config,
+    ap_client: &ApClient,
+    db_pool: &DatabaseConnectionPool,
+    job_id: Uuid,
+    mut job_data: IncomingActivityJobData,
+) -> Result<(), DatabaseError> {
+    let duration_max =
+        Duration::from_secs((JOB_TIMEOUT / 6).into());
+    let start_time = Instant::now();
+    let handler_future = handle_activity(
+        config,
+        ap_client,
+        db_pool,
+        &job_data.activity,
+        job_data.is_authenticated,
+        job_data.recipient_id.as_deref(),
+        job_data.sender_id.as_deref(),
+    );
+    let handler_result = match tokio::time::timeout(
+        duration_max,
+        handler_future,
+    ).await {
+        Ok(result) => result,
+        Err(_) => {
+            log::error!(
+                "failed to process activity (timeout): {}",
+                job_data.activity,
+            );
+            let db_client = &**get_database_client(db_pool).await?;
+            delete_job_from_queue(db_client, job_id).await?;
+            return Ok(());
+        },
+    };
+    log::debug!(
+        "incoming activity job (ID: {}): {:.2?}",
+        job_id,
+        start_time.elapsed(),
+    );
+    let db_client = &**get_database_client(db_pool).await?;
+    if let Err(error) = handler_result {
+        if !matches!(
+            error,
+            HandlerError::FetchError(FetchError::RequestError(_))
+        ) {
+            // Error is not retriable
+            log::warn!(
+                "failed to process activity ({}): {}",
+                error,
+                job_data.activity,
+            );
+            delete_job_from_queue(db_client, job_id).await?;
+            return Ok(());
+        };
+        job_data.failure_count += 1;
+        log::warn!(
+            "failed to process activity ({}) (attempt #{}): {}",
+            error,
+            job_data.failure_count,
+            job_data.activity,
+        );
+        if job_data.failure_count <= INCOMING_QUEUE_RETRIES_MAX {
+            // Re-queue
+            let retry_after = incoming_queue_backoff(job_data.failure_count);
+            job_data.into_job(db_client, retry_after).await?;
+            log::info!("activity re-queued");
+        };
+    };
+    delete_job_from_queue(db_client, job_id).await?;
+    Ok(())
+}
+
 pub async fn process_queued_incoming_activities(
     config: &Config,
     db_pool: &DatabaseConnectionPool,
@@ -127,65 +223,23 @@ pub async fn process_queued_incoming_activities(
     ).await?;
     let ap_client = ApClient::new_with_pool(config, db_pool).await?;
     for job in batch {
-        let mut job_data: IncomingActivityJobData =
+        let job_data: IncomingActivityJobData =
             serde_json::from_value(job.job_data)
                 .map_err(|_| DatabaseTypeError)?;
-        let duration_max =
-            Duration::from_secs((JOB_TIMEOUT / 6).into());
-        let handler_future = handle_activity(
-            config,
-            &ap_client,
-            db_pool,
-            &job_data.activity,
-            job_data.is_authenticated,
-            job_data.recipient_id.as_deref(),
-            job_data.sender_id.as_deref(),
-        );
-        let handler_result = match tokio::time::timeout(
-            duration_max,
-            handler_future,
-        ).await {
-            Ok(result) => result,
-            Err(_) => {
-                log::error!(
-                    "failed to process activity (timeout): {}",
-                    job_data.activity,
-                );
-                let db_client = &**get_database_client(db_pool).await?;
-                delete_job_from_queue(db_client, job.id).await?;
-                continue;
-            },
-        };
-        let db_client = &**get_database_client(db_pool).await?;
-        if let Err(error) = handler_result {
-            if !matches!(
-                error,
-                HandlerError::FetchError(FetchError::RequestError(_))
-            ) {
-                // Error is not retriable
-                log::warn!(
-                    "failed to process activity ({}): {}",
-                    error,
-                    job_data.activity,
-                );
-                delete_job_from_queue(db_client, job.id).await?;
-                continue;
-            };
-            job_data.failure_count += 1;
-            log::warn!(
-                "failed to process activity ({}) (attempt #{}): {}",
-                error,
-                job_data.failure_count,
-                job_data.activity,
-            );
-            if job_data.failure_count <= INCOMING_QUEUE_RETRIES_MAX {
-                // Re-queue
-                let retry_after = incoming_queue_backoff(job_data.failure_count);
-                job_data.into_job(db_client, retry_after).await?;
-                log::info!("activity re-queued");
-            };
-        };
-        delete_job_from_queue(db_client, job.id).await?;
+        let correlation_id = job_data.correlation_id.clone()
+            .unwrap_or_else(generate_correlation_id);
+        // Span is always enabled, request ID will be attached to all log records
+        let span = tracing::error_span!("inbox", request_id = %correlation_id);
+        with_span_timing(span, with_correlation_id(
+            correlation_id,
+            process_incoming_activity_job(
+                config,
+                &ap_client,
+                db_pool,
+                job.id,
+                job_data,
+            ),
+        )).await?;
     };
     Ok(())
 }
@@ -196,6 +250,11 @@ pub struct OutgoingActivityJobData {
     sender: Sender,
     recipients: Vec<Recipient>,
     failure_count: u32,
+    #[serde(default)]
+    correlation_id: Option<String>,
+    // Local account (used in audit log)
+    #[serde(default)]
+    sender_id: Option<Uuid>,
 }
 
 impl OutgoingActivityJobData {
@@ -224,6 +283,7 @@ impl OutgoingActivityJobData {
         let recipients = Self::sort_recipients(recipients);
         let activity = serde_json::to_value(activity)
             .expect("activity should be serializable");
+        let sender_id = sender.id();
         let sender = Sender::from_account(authority, sender);
         let activity_signed = sign_activity(
             &sender,
@@ -234,6 +294,32 @@ impl OutgoingActivityJobData {
             sender: sender,
             recipients: recipients,
             failure_count: 0,
+            correlation_id: get_correlation_id(),
+            sender_id: Some(sender_id),
+        }
+    }
+
+    /// Creates job for activity sent by the instance actor
+    pub(super) fn new_from_instance(
+        instance: &Instance,
+        activity: impl Serialize,
+        recipients: Vec<Recipient>,
+    ) -> Self {
+        let recipients = Self::sort_recipients(recipients);
+        let activity = serde_json::to_value(activity)
+            .expect("activity should be serializable");
+        let sender = Sender::from_instance(instance);
+        let activity_signed = sign_activity(
+            &sender,
+            activity,
+        ).expect("activity should be valid");
+        Self {
+            activity: activity_signed,
+            sender: sender,
+            recipients: recipients,
+            failure_count: 0,
+            correlation_id: get_correlation_id(),
+            sender_id: None,
         }
     }
 
@@ -267,12 +353,15 @@ impl OutgoingActivityJobData {
             recipients.push(recipient);
         };
         let recipients = Self::sort_recipients(recipients);
+        let sender_id = sender.id;
         let sender = Sender::from_portable_user(instance_uri, sender)?;
         let job_data = Self {
             activity: activity.clone(),
             sender: sender,
             recipients: recipients,
             failure_count: 0,
+            correlation_id: get_correlation_id(),
+            sender_id: Some(sender_id),
         };
         Some(job_data)
     }
@@ -371,6 +460,112 @@ async fn delete_gone_actors(
     Ok(())
 }
 
+/// Saves failed delivery to dead letter table
+async fn save_dead_letter(
+    db_client: &impl DatabaseClient,
+    activity_id: &str,
+    activity: &JsonValue,
+    sender: &Sender,
+    sender_id: Option<Uuid>,
+    recipient: &Recipient,
+) -> Result<(), DatabaseError> {
+    let retry_recipient = Recipient::new(&recipient.id, &recipient.inbox);
+    let job_data = OutgoingActivityJobData {
+        activity: activity.clone(),
+        sender: sender.clone(),
+        recipients: vec![Recipient {
+            is_primary: recipient.is_primary,
+            ..retry_recipient
+        }],
+        failure_count: 0,
+        correlation_id: None,
+        sender_id: sender_id,
+    };
+    let job_data = serde_json::to_value(job_data)
+        .expect("activity should be serializable");
+    create_dead_letter(
+        db_client,
+        activity_id,
+        &recipient.inbox,
+        &job_data,
+        &recipient.errors,
+        recipient.first_failed_at.unwrap_or_else(Utc::now),
+    ).await?;
+    log::warn!("delivery to {} moved to dead letter queue", recipient.inbox);
+    Ok(())
+}
+
+/// Saves delivery statuses to audit log
+async fn save_delivery_records(
+    db_client: &impl DatabaseClient,
+    activity: &JsonValue,
+    sender_id: Option<Uuid>,
+    recipients: &[Recipient],
+    attempted: &HashSet<String>,
+    is_retry_scheduled: bool,
+) -> Result<(), DatabaseError> {
+    let activity_id = activity["id"].as_str()
+        .ok_or(DatabaseTypeError)?;
+    let activity_type = activity["type"].as_str()
+        .unwrap_or_default();
+    let object_id = object_to_id(&activity["object"]).ok();
+    let mut audience = vec![];
+    for property in ["to", "cc"] {
+        let ids = parse_into_id_array(&activity[property])
+            .unwrap_or_default();
+        audience.extend(ids);
+    };
+    let deliveries = recipients.iter()
+        .map(|recipient| {
+            let status = if recipient.is_delivered {
+                DeliveryStatus::Delivered
+            } else if recipient.is_gone {
+                DeliveryStatus::Failed
+            } else if recipient.is_unreachable {
+                DeliveryStatus::Skipped
+            } else if is_retry_scheduled {
+                DeliveryStatus::Pending
+            } else {
+                DeliveryStatus::Failed
+            };
+            DeliveryRecordData {
+                recipient_id: recipient.id.clone(),
+                inbox: recipient.inbox.clone(),
+                status: status,
+                is_attempted: attempted.contains(&recipient.inbox),
+            }
+        })
+        .collect();
+    let record_data = OutgoingActivityRecordData {
+        activity_id: activity_id.to_owned(),
+        activity_type: activity_type.to_owned(),
+        object_id: object_id,
+        sender_id: sender_id,
+        audience: audience,
+        deliveries: deliveries,
+    };
+    save_outgoing_activity_records(db_client, record_data).await?;
+    Ok(())
+}
+
+/// Re-queues failed delivery and removes it from dead letter table
+pub async fn requeue_dead_letter(
+    db_client: &mut impl DatabaseClient,
+    dead_letter_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let transaction = db_client.transaction().await?;
+    let dead_letter = get_dead_letter(&transaction, dead_letter_id).await?;
+    // Row is locked until the end of transaction,
+    // concurrent requests will fail with NotFound error
+    delete_dead_letter(&transaction, dead_letter.id).await?;
+    let job_data: OutgoingActivityJobData =
+        serde_json::from_value(dead_letter.job_data)
+            .map_err(|_| DatabaseTypeError)?;
+    job_data.enqueue(&transaction).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
 pub async fn process_queued_outgoing_activities(
     config: &Config,
     db_pool: &DatabaseConnectionPool,
@@ -383,6 +578,7 @@ pub async fn process_queued_outgoing_activities(
         JOB_TIMEOUT,
     ).await?;
     let instance = config.instance();
+    let is_audit_enabled = config.retention.outgoing_activities.is_some();
     for job in batch {
         let mut job_data: OutgoingActivityJobData =
             serde_json::from_value(job.job_data)
@@ -394,6 +590,11 @@ pub async fn process_queued_outgoing_activities(
             delete_job_from_queue(db_client, job.id).await?;
             continue;
         };
+        // Deliveries that are finished don't need to be recorded again
+        let unfinished: HashSet<String> = recipients.iter()
+            .filter(|recipient| !recipient.is_finished())
+            .map(|recipient| recipient.inbox.clone())
+            .collect();
 
         let activity_id = job_data.activity["id"].as_str()
             .ok_or(DatabaseTypeError)?;
@@ -434,6 +635,33 @@ pub async fn process_queued_outgoing_activities(
                 job_data.activity,
             );
             let db_client = &**get_database_client(db_pool).await?;
+            if is_audit_enabled {
+                // Only local deliveries were attempted
+                let attempted: HashSet<String> = recipients.iter()
+                    .filter(|recipient| {
+                        recipient.is_delivered &&
+                        unfinished.contains(&recipient.inbox)
+                    })
+                    .map(|recipient| recipient.inbox.clone())
+                    .collect();
+                let recipients: Vec<_> = recipients.into_iter()
+                    .filter(|recipient| unfinished.contains(&recipient.inbox))
+                    .map(|mut recipient| {
+                        if !recipient.is_delivered {
+                            recipient.is_unreachable = true;
+                        };
+                        recipient
+                    })
+                    .collect();
+                save_delivery_records(
+                    db_client,
+                    &job_data.activity,
+                    job_data.sender_id,
+                    &recipients,
+                    &attempted,
+                    false,
+                ).await?;
+            };
             delete_job_from_queue(db_client, job.id).await?;
             continue;
         };
@@ -449,7 +677,7 @@ pub async fn process_queued_outgoing_activities(
             if !recipient.is_finished() {
                 let recipient_hostname =
                     parse_http_url_from_db(&recipient.inbox)?.hostname();
-                if filter.is_action_required(
+                if filter.apply_action(
                     recipient_hostname.as_str(),
                     FilterAction::Reject,
                 ) {
@@ -459,13 +687,26 @@ pub async fn process_queued_outgoing_activities(
             };
         };
 
+        // Local deliveries and deliveries that are not blocked
+        let attempted: HashSet<String> = recipients.iter()
+            .filter(|recipient| unfinished.contains(&recipient.inbox))
+            .filter(|recipient| recipient.is_delivered || !recipient.is_unreachable)
+            .map(|recipient| recipient.inbox.clone())
+            .collect();
+
         let start_time = Instant::now();
-        let worker_result = deliver_activity_worker(
-            instance.clone(),
-            job_data.sender.clone(),
-            job_data.activity.clone(),
-            &mut recipients,
-        ).await;
+        let correlation_id = job_data.correlation_id.clone()
+            .unwrap_or_else(generate_correlation_id);
+        let span = tracing::error_span!("delivery", request_id = %correlation_id);
+        let worker_result = with_span_timing(span, with_correlation_id(
+            correlation_id,
+            deliver_activity_worker(
+                instance.clone(),
+                job_data.sender.clone(),
+                job_data.activity.clone(),
+                &mut recipients,
+            ),
+        )).await;
 
         let db_client = &mut **get_database_client(db_pool).await?;
         match worker_result {
@@ -521,9 +762,24 @@ pub async fn process_queued_outgoing_activities(
                 };
             };
         };
-        if recipients.iter().any(|recipient| !recipient.is_finished()) &&
-            job_data.failure_count < OUTGOING_QUEUE_RETRIES_MAX
-        {
+        let is_retry_scheduled =
+            recipients.iter().any(|recipient| !recipient.is_finished()) &&
+            job_data.failure_count < OUTGOING_QUEUE_RETRIES_MAX;
+        if is_audit_enabled {
+            let updated_recipients: Vec<_> = recipients.iter()
+                .filter(|recipient| unfinished.contains(&recipient.inbox))
+                .cloned()
+                .collect();
+            save_delivery_records(
+                db_client,
+                &job_data.activity,
+                job_data.sender_id,
+                &updated_recipients,
+                &attempted,
+                is_retry_scheduled,
+            ).await?;
+        };
+        if is_retry_scheduled {
             job_data.failure_count += 1;
             // Re-queue if some deliveries are not successful
             job_data.recipients = recipients;
@@ -531,6 +787,23 @@ pub async fn process_queued_outgoing_activities(
             let job_id = job_data.into_job(db_client, retry_after).await?;
             log::info!("delivery job re-queued (ID: {job_id})");
         } else {
+            // Move permanently failed deliveries to dead letter queue
+            for recipient in recipients.iter() {
+                if recipient.is_delivered ||
+                    recipient.is_gone ||
+                    recipient.errors.is_empty()
+                {
+                    continue;
+                };
+                save_dead_letter(
+                    db_client,
+                    &canonical_activity_id.to_string(),
+                    &job_data.activity,
+                    &job_data.sender,
+                    job_data.sender_id,
+                    recipient,
+                ).await?;
+            };
             // Update reachability statuses if all deliveries are successful
             // or if retry limit is reached
             // TODO: track reachability status of servers, not actors
@@ -570,6 +843,7 @@ pub async fn process_queued_outgoing_activities(
 pub enum FetcherJobData {
     Outbox { actor_id: String },
     Featured { actor_id: String },
+    Object { object_id: String },
     Context {
         object_id: String,
         #[serde(default)]
@@ -578,20 +852,21 @@ pub enum FetcherJobData {
 }
 
 impl FetcherJobData {
+    /// Returns ID of the created job
     pub async fn into_job(
         self,
         db_client: &impl DatabaseClient,
-    ) -> Result<(), DatabaseError> {
+    ) -> Result<Uuid, DatabaseError> {
         let job_data = serde_json::to_value(self)
             .expect("job data should be serializable");
         let scheduled_for = Utc::now(); // run immediately
-        enqueue_job(
+        let job_id = enqueue_job(
             db_client,
             JobType::Fetcher,
             &job_data,
             scheduled_for,
         ).await?;
-        Ok(())
+        Ok(job_id)
     }
 }
 
@@ -630,6 +905,13 @@ pub async fn fetcher_queue_executor(
                     COLLECTION_LIMIT,
                 ).await
             },
+            FetcherJobData::Object { object_id } => {
+                refetch_object(
+                    config,
+                    db_pool,
+                    &object_id,
+                ).await
+            },
             FetcherJobData::Context { object_id, use_context } => {
                 import_replies(
                     config,
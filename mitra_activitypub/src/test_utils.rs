@@ -0,0 +1,362 @@
+//! Federation simulation.
+//!
+//! Runs several instances in one process. Each instance has its own
+//! database, and instances communicate through in-memory transport:
+//! - objects are fetched by [`ApClient`] from the peer database
+//!   (the network is used as [`Transport`]);
+//! - WebFinger queries are answered by the network acting as a
+//!   WebFinger cache;
+//! - queued outgoing activities are passed directly to the handler
+//!   of the receiving instance (see [`SimulatedNetwork::deliver_all`]).
+//!
+//! HTTP signatures are not simulated, all deliveries are treated
+//! as authenticated.
+use std::sync::Arc;
+use std::time::Duration;
+
+use apx_sdk::{
+    addresses::WebfingerAddress,
+    agent::FederationAgent,
+    core::url::http_uri::HttpUri,
+    fetch::{FetchError, FetchedObject, FetchObjectOptions},
+    jrd::{JsonResourceDescriptor, Link},
+    webfinger::{CacheFuture, WebfingerCache},
+};
+use serde::Deserialize;
+use serde_json::{Value as JsonValue};
+
+use mitra_config::Config;
+use mitra_models::{
+    accounts::{
+        queries::get_user_by_name,
+        test_utils::create_test_user,
+        types::User,
+    },
+    background_jobs::{
+        queries::{delete_job_from_queue, get_job_batch},
+        types::JobType,
+    },
+    database::{
+        get_database_client,
+        test_utils::create_test_database_pool,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    posts::helpers::{add_related_posts, get_post_by_id_for_view},
+    profiles::types::DbActorProfile,
+};
+use mitra_services::media::MediaServer;
+
+use crate::{
+    actors::builders::build_local_actor,
+    adapters::follow_requests::follow_or_create_request,
+    authority::Authority,
+    builders::note::build_note,
+    deliverer::Recipient,
+    handlers::activity::handle_activity,
+    identifiers::{
+        local_actor_id,
+        parse_local_actor_id,
+        parse_local_object_id,
+        UuidOrUsername,
+    },
+    importers::{ActorIdResolver, ApClient},
+    transport::{Transport, TransportFuture},
+};
+
+const DELIVERY_ROUNDS_MAX: usize = 20;
+
+#[derive(Deserialize)]
+struct QueuedDelivery {
+    activity: JsonValue,
+    recipients: Vec<Recipient>,
+}
+
+pub struct SimulatedInstance {
+    pub config: Config,
+    pub db_pool: DatabaseConnectionPool,
+}
+
+impl SimulatedInstance {
+    async fn new(instance_url: &str) -> Self {
+        let config = Config::for_test(instance_url);
+        let hostname = config.instance().uri().hostname();
+        let db_pool = create_test_database_pool(hostname.as_str()).await;
+        Self { config, db_pool }
+    }
+
+    fn hostname(&self) -> String {
+        self.config.instance().uri().hostname().to_string()
+    }
+
+    pub async fn create_user(&self, username: &str) -> User {
+        let db_client = &mut **get_database_client(&self.db_pool).await.unwrap();
+        create_test_user(db_client, username).await
+    }
+
+    pub fn actor_id(&self, username: &str) -> String {
+        local_actor_id(self.config.instance().uri_str(), username)
+    }
+
+    /// Returns JRD if local user exists
+    async fn get_jrd(
+        &self,
+        username: &str,
+    ) -> Result<Option<JsonResourceDescriptor>, DatabaseError> {
+        let db_client = &**get_database_client(&self.db_pool).await?;
+        let user = match get_user_by_name(db_client, username).await {
+            Ok(user) => user,
+            Err(DatabaseError::NotFound(_)) => return Ok(None),
+            Err(other_error) => return Err(other_error),
+        };
+        let instance = self.config.instance();
+        let address = WebfingerAddress::new_unchecked(
+            &user.profile.username,
+            &instance.webfinger_hostname(),
+        );
+        let actor_id = local_actor_id(
+            instance.uri_str(),
+            &user.profile.username,
+        );
+        let jrd = JsonResourceDescriptor {
+            subject: address.to_acct_uri(),
+            links: vec![Link::actor(&actor_id)],
+        };
+        Ok(Some(jrd))
+    }
+
+    /// Returns JSON document of a local actor or a local post
+    async fn get_object(
+        &self,
+        object_id: &str,
+    ) -> Result<Option<JsonValue>, DatabaseError> {
+        let db_client = &**get_database_client(&self.db_pool).await?;
+        let instance = self.config.instance();
+        let authority = Authority::server(instance.uri());
+        let media_server = MediaServer::new(&self.config);
+        if let Ok(UuidOrUsername::Username(username)) =
+            parse_local_actor_id(&authority, object_id)
+        {
+            let user = match get_user_by_name(db_client, &username).await {
+                Ok(user) => user,
+                Err(DatabaseError::NotFound(_)) => return Ok(None),
+                Err(other_error) => return Err(other_error),
+            };
+            let actor = build_local_actor(&authority, &media_server, &user)
+                .map_err(|_| DatabaseError::type_error())?;
+            let actor_json = serde_json::to_value(actor)
+                .expect("actor should be serializable");
+            return Ok(Some(actor_json));
+        };
+        if let Ok(post_id) = parse_local_object_id(&authority, object_id) {
+            let mut post = match get_post_by_id_for_view(
+                db_client,
+                None,
+                post_id,
+            ).await {
+                Ok(post) if post.is_local() => post,
+                Ok(_) | Err(DatabaseError::NotFound(_)) => return Ok(None),
+                Err(other_error) => return Err(other_error),
+            };
+            add_related_posts(db_client, vec![&mut post]).await?;
+            let note = build_note(
+                &instance.webfinger_hostname(),
+                &authority,
+                &media_server,
+                &post,
+                true, // with_context
+            );
+            let note_json = serde_json::to_value(note)
+                .expect("note should be serializable");
+            return Ok(Some(note_json));
+        };
+        Ok(None)
+    }
+}
+
+/// A set of instances connected by in-memory transport
+#[derive(Clone)]
+pub struct SimulatedNetwork {
+    instances: Arc<Vec<SimulatedInstance>>,
+}
+
+impl SimulatedNetwork {
+    /// Creates instances with the given URLs (and separate databases)
+    pub async fn start(instance_urls: &[&str]) -> Self {
+        let mut instances = vec![];
+        for instance_url in instance_urls {
+            let instance = SimulatedInstance::new(instance_url).await;
+            instances.push(instance);
+        };
+        Self { instances: Arc::new(instances) }
+    }
+
+    pub fn instance(&self, instance_url: &str) -> &SimulatedInstance {
+        self.instances.iter()
+            .find(|instance| instance.config.instance().uri_str() == instance_url)
+            .expect("instance should exist")
+    }
+
+    fn find_instance(&self, hostname: &str) -> Option<&SimulatedInstance> {
+        self.instances.iter()
+            .find(|instance| instance.hostname() == hostname)
+    }
+
+    fn find_instance_by_url(&self, url: &str) -> Option<&SimulatedInstance> {
+        let hostname = HttpUri::parse(url).ok()?.hostname();
+        self.find_instance(hostname.as_str())
+    }
+
+    /// Returns client that fetches objects from the simulated network
+    pub async fn ap_client(&self, instance: &SimulatedInstance) -> ApClient {
+        let mut ap_client = ApClient::new_with_pool(
+            &instance.config,
+            &instance.db_pool,
+        ).await.expect("client should be created");
+        ap_client.transport = Arc::new(self.clone());
+        ap_client
+    }
+
+    /// Makes local user follow a remote actor
+    /// and delivers all queued activities.
+    pub async fn follow(
+        &self,
+        instance: &SimulatedInstance,
+        follower: &User,
+        target_actor_id: &str,
+    ) -> DbActorProfile {
+        let ap_client = self.ap_client(instance).await;
+        let target_profile = Box::pin(ActorIdResolver::default().resolve(
+            &ap_client,
+            &instance.db_pool,
+            target_actor_id,
+        )).await.unwrap();
+        let db_client = &mut **get_database_client(&instance.db_pool).await.unwrap();
+        follow_or_create_request(
+            db_client,
+            &ap_client.instance,
+            follower,
+            &target_profile,
+        ).await.unwrap();
+        self.deliver_all().await;
+        target_profile
+    }
+
+    /// Delivers queued outgoing activities.
+    /// Returns number of processed deliveries.
+    async fn deliver_queued(&self) -> usize {
+        let mut delivery_count = 0;
+        for sender in self.instances.iter() {
+            let db_client = &**get_database_client(&sender.db_pool).await
+                .expect("database client should be available");
+            let batch = get_job_batch(
+                db_client,
+                JobType::OutgoingActivity,
+                u32::MAX,
+                3600,
+            ).await.expect("jobs should be retrieved");
+            for job in batch {
+                let delivery: QueuedDelivery =
+                    serde_json::from_value(job.job_data)
+                        .expect("job data should be valid");
+                for recipient in delivery.recipients {
+                    let Some(receiver) = self.find_instance_by_url(&recipient.inbox) else {
+                        log::warn!("inbox doesn't exist: {}", recipient.inbox);
+                        continue;
+                    };
+                    let ap_client = self.ap_client(receiver).await;
+                    let sender_id = delivery.activity["actor"].as_str();
+                    // Handler future is large, allocate it on the heap
+                    if let Err(error) = Box::pin(handle_activity(
+                        &receiver.config,
+                        &ap_client,
+                        &receiver.db_pool,
+                        &delivery.activity,
+                        true, // is_authenticated
+                        Some(&recipient.id),
+                        sender_id,
+                    )).await {
+                        log::warn!(
+                            "failed to process activity ({}): {}",
+                            error,
+                            delivery.activity,
+                        );
+                    };
+                    delivery_count += 1;
+                };
+                delete_job_from_queue(db_client, job.id).await
+                    .expect("job should be deleted");
+            };
+        };
+        delivery_count
+    }
+
+    /// Delivers queued activities until all queues are empty
+    pub async fn deliver_all(&self) {
+        for _ in 0..DELIVERY_ROUNDS_MAX {
+            if Box::pin(self.deliver_queued()).await == 0 {
+                return;
+            };
+        };
+        panic!("delivery loop detected");
+    }
+}
+
+/// Replacement for HTTP requests
+impl Transport for SimulatedNetwork {
+    fn fetch_object<'a>(
+        &'a self,
+        _agent: &'a FederationAgent,
+        object_id: &'a str,
+        _options: FetchObjectOptions,
+    ) -> TransportFuture<'a, Result<FetchedObject, FetchError>> {
+        Box::pin(async move {
+            let not_found = || FetchError::NotFound(object_id.to_string());
+            let instance = self.find_instance_by_url(object_id)
+                .ok_or_else(not_found)?;
+            let object_json = instance.get_object(object_id).await
+                .map_err(|error| FetchError::StreamError(error.to_string()))?
+                .ok_or_else(not_found)?;
+            Ok(FetchedObject::for_test(object_json, object_id))
+        })
+    }
+
+    fn webfinger_cache(
+        &self,
+        _db_pool: &DatabaseConnectionPool,
+    ) -> Arc<dyn WebfingerCache> {
+        Arc::new(self.clone())
+    }
+}
+
+/// Replacement for WebFinger queries
+impl WebfingerCache for SimulatedNetwork {
+    fn get<'a>(
+        &'a self,
+        resource: &'a str,
+    ) -> CacheFuture<'a, Option<JsonValue>> {
+        Box::pin(async move {
+            let address = WebfingerAddress::from_acct_uri(resource).ok()?;
+            let instance = self.find_instance(address.hostname())?;
+            // JRD without links is returned if user doesn't exist
+            let jrd = instance.get_jrd(address.username()).await
+                .expect("database query should succeed")
+                .unwrap_or(JsonResourceDescriptor {
+                    subject: resource.to_string(),
+                    links: vec![],
+                });
+            let jrd_value = serde_json::to_value(jrd)
+                .expect("JRD should be serializable");
+            Some(jrd_value)
+        })
+    }
+
+    fn set<'a>(
+        &'a self,
+        _resource: &'a str,
+        _jrd: &'a JsonValue,
+        _ttl: Duration,
+    ) -> CacheFuture<'a, ()> {
+        Box::pin(async {})
+    }
+}
@@ -0,0 +1,58 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use apx_sdk::{
+    agent::FederationAgent,
+    fetch::{
+        fetch_object,
+        FetchedObject,
+        FetchError,
+        FetchObjectOptions,
+    },
+    webfinger::WebfingerCache,
+};
+
+use mitra_models::database::DatabaseConnectionPool;
+
+use crate::webfinger::DatabaseWebfingerCache;
+
+pub type TransportFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Means of retrieving objects from other servers
+pub trait Transport: Send + Sync {
+    /// Fetches object
+    fn fetch_object<'a>(
+        &'a self,
+        agent: &'a FederationAgent,
+        object_id: &'a str,
+        options: FetchObjectOptions,
+    ) -> TransportFuture<'a, Result<FetchedObject, FetchError>>;
+
+    /// Returns storage for WebFinger lookup results
+    fn webfinger_cache(
+        &self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Arc<dyn WebfingerCache>;
+}
+
+/// Transport that makes HTTP requests
+pub struct HttpTransport;
+
+impl Transport for HttpTransport {
+    fn fetch_object<'a>(
+        &'a self,
+        agent: &'a FederationAgent,
+        object_id: &'a str,
+        options: FetchObjectOptions,
+    ) -> TransportFuture<'a, Result<FetchedObject, FetchError>> {
+        Box::pin(fetch_object(agent, object_id, options))
+    }
+
+    fn webfinger_cache(
+        &self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Arc<dyn WebfingerCache> {
+        Arc::new(DatabaseWebfingerCache::new(db_pool))
+    }
+}
@@ -10,15 +10,19 @@ pub const CREATE: &str = "Create";
 pub const DELETE: &str = "Delete";
 pub const DISLIKE: &str = "Dislike";
 pub const EMOJI_REACT: &str = "EmojiReact";
+pub const FLAG: &str = "Flag";
 pub const FOLLOW: &str = "Follow";
 pub const LIKE: &str = "Like";
 pub const LISTEN: &str = "Listen";
 pub const MOVE: &str = "Move";
 pub const OFFER: &str = "Offer";
+pub const QUOTE_REQUEST: &str = "QuoteRequest";
+pub const READ: &str = "Read";
 pub const REJECT: &str = "Reject";
 pub const REMOVE: &str = "Remove";
 pub const UNDO: &str = "Undo";
 pub const UPDATE: &str = "Update";
+pub const VIEW: &str = "View";
 
 // Actor types
 pub const APPLICATION: &str = "Application";
@@ -63,4 +67,5 @@ pub const LOCK: &str = "Lock"; // Lemmy
 pub const MULTIKEY: &str = "Multikey";
 pub const PROPERTY_VALUE: &str = "PropertyValue";
 pub const QUOTATION: &str = "Quotation";
+pub const QUOTE_AUTHORIZATION: &str = "QuoteAuthorization";
 pub const VERIFIABLE_IDENTITY_STATEMENT: &str = "VerifiableIdentityStatement";
@@ -1,29 +1,99 @@
+use std::time::Duration;
+
 use apx_sdk::{
     addresses::WebfingerAddress,
     agent::FederationAgent,
     core::url::{
+        common::url_encode,
         hostname::is_same_apex_domain,
         http_uri::HttpUri,
     },
-    fetch::fetch_json,
-    jrd::{JsonResourceDescriptor, JRD_MEDIA_TYPE},
+    jrd::JsonResourceDescriptor,
+    webfinger::{fetch_jrd, CacheFuture, WebfingerCache},
 };
+use chrono::{TimeDelta, Utc};
 use serde_json::{Value as JsonValue};
 
+use mitra_models::{
+    activitypub::queries::{get_cached_jrd, save_cached_jrd},
+    database::{
+        get_database_client,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+};
 use mitra_validators::errors::ValidationError;
 
 use crate::errors::HandlerError;
 
+pub const REMOTE_INTERACTION_RELATION_TYPE: &str = "http://ostatus.org/schema/1.0/subscribe";
+// https://codeberg.org/fediverse/fep/src/commit/78a31a92cb264ca603af24b4fcaae944b62edb9b/fep/3b86/fep-3b86.md#5-1-object-intent
+pub const FEP_3B86_OBJECT_INTENT_RELATION_TYPE: &str = "https://w3id.org/fep/3b86/Object";
+
+/// WebFinger cache backed by the database
+pub struct DatabaseWebfingerCache {
+    db_pool: DatabaseConnectionPool,
+}
+
+impl DatabaseWebfingerCache {
+    pub fn new(db_pool: &DatabaseConnectionPool) -> Self {
+        Self { db_pool: db_pool.clone() }
+    }
+
+    async fn get_jrd(&self, resource: &str) -> Result<JsonValue, DatabaseError> {
+        let db_client = &**get_database_client(&self.db_pool).await?;
+        get_cached_jrd(db_client, resource).await
+    }
+
+    async fn save_jrd(
+        &self,
+        resource: &str,
+        jrd: &JsonValue,
+        ttl: Duration,
+    ) -> Result<(), DatabaseError> {
+        let ttl = TimeDelta::from_std(ttl).unwrap_or(TimeDelta::zero());
+        let expires_at = Utc::now() + ttl;
+        let db_client = &**get_database_client(&self.db_pool).await?;
+        save_cached_jrd(db_client, resource, jrd, expires_at).await
+    }
+}
+
+impl WebfingerCache for DatabaseWebfingerCache {
+    fn get<'a>(
+        &'a self,
+        resource: &'a str,
+    ) -> CacheFuture<'a, Option<JsonValue>> {
+        Box::pin(async move {
+            match self.get_jrd(resource).await {
+                Ok(jrd) => Some(jrd),
+                Err(DatabaseError::NotFound(_)) => None,
+                Err(other_error) => {
+                    log::error!("failed to read webfinger cache: {other_error}");
+                    None
+                },
+            }
+        })
+    }
+
+    fn set<'a>(
+        &'a self,
+        resource: &'a str,
+        jrd: &'a JsonValue,
+        ttl: Duration,
+    ) -> CacheFuture<'a, ()> {
+        Box::pin(async move {
+            if let Err(error) = self.save_jrd(resource, jrd, ttl).await {
+                log::error!("failed to update webfinger cache: {error}");
+            };
+        })
+    }
+}
+
 pub async fn fetch_webfinger_jrd(
     agent: &FederationAgent,
     webfinger_address: &WebfingerAddress,
 ) -> Result<JsonValue, HandlerError> {
-    let webfinger_uri = webfinger_address.resource_uri();
-    let jrd_value = fetch_json(
-        agent,
-        &webfinger_uri,
-        Some(JRD_MEDIA_TYPE),
-    ).await?;
+    let jrd_value = fetch_jrd(agent, webfinger_address).await?;
     Ok(jrd_value)
 }
 
@@ -71,3 +141,101 @@ pub(super) async fn perform_reverse_webfinger_query(
     };
     Ok(discovered_address.hostname().to_owned())
 }
+
+fn get_remote_interaction_url_from_jrd(
+    jrd: &JsonResourceDescriptor,
+    object_id: &str,
+) -> Option<String> {
+    let object_id_encoded = url_encode(object_id);
+    // Prefer FEP-3b86 object intent
+    if let Some(template) =
+        jrd.link_template(FEP_3B86_OBJECT_INTENT_RELATION_TYPE)
+    {
+        if template.contains("{object}") {
+            return Some(template.replace("{object}", &object_id_encoded));
+        };
+    };
+    let template = jrd.link_template(REMOTE_INTERACTION_RELATION_TYPE)?;
+    if !template.contains("{uri}") {
+        return None;
+    };
+    Some(template.replace("{uri}", &object_id_encoded))
+}
+
+/// Discovers the page where a remote user can interact with an object
+pub async fn get_remote_interaction_url(
+    agent: &FederationAgent,
+    webfinger_address: &WebfingerAddress,
+    object_id: &str,
+) -> Result<String, HandlerError> {
+    let jrd_value = fetch_webfinger_jrd(agent, webfinger_address).await?;
+    let jrd: JsonResourceDescriptor = serde_json::from_value(jrd_value)?;
+    let url = get_remote_interaction_url_from_jrd(&jrd, object_id)
+        .ok_or(ValidationError("remote interaction template is not found in JRD"))?;
+    let url = HttpUri::parse(&url)
+        .map_err(|_| ValidationError("invalid remote interaction URL"))?;
+    Ok(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn test_get_remote_interaction_url_from_jrd() {
+        let jrd_value = json!({
+            "subject": "acct:user@social.example",
+            "links": [{
+                "rel": "http://ostatus.org/schema/1.0/subscribe",
+                "template": "https://social.example/authorize_interaction?uri={uri}",
+            }],
+        });
+        let jrd: JsonResourceDescriptor =
+            serde_json::from_value(jrd_value).unwrap();
+        let object_id = "https://local.example/objects/1";
+        let url = get_remote_interaction_url_from_jrd(&jrd, object_id).unwrap();
+        assert_eq!(
+            url,
+            "https://social.example/authorize_interaction?uri=https%3A%2F%2Flocal.example%2Fobjects%2F1",
+        );
+    }
+
+    #[test]
+    fn test_get_remote_interaction_url_from_jrd_fep_3b86() {
+        let jrd_value = json!({
+            "subject": "acct:user@social.example",
+            "links": [
+                {
+                    "rel": "http://ostatus.org/schema/1.0/subscribe",
+                    "template": "https://social.example/authorize_interaction?uri={uri}",
+                },
+                {
+                    "rel": "https://w3id.org/fep/3b86/Object",
+                    "template": "https://social.example/intent?object={object}",
+                },
+            ],
+        });
+        let jrd: JsonResourceDescriptor =
+            serde_json::from_value(jrd_value).unwrap();
+        let object_id = "https://local.example/objects/1";
+        let url = get_remote_interaction_url_from_jrd(&jrd, object_id).unwrap();
+        assert_eq!(
+            url,
+            "https://social.example/intent?object=https%3A%2F%2Flocal.example%2Fobjects%2F1",
+        );
+    }
+
+    #[test]
+    fn test_get_remote_interaction_url_from_jrd_not_found() {
+        let jrd_value = json!({
+            "subject": "acct:user@social.example",
+            "links": [],
+        });
+        let jrd: JsonResourceDescriptor =
+            serde_json::from_value(jrd_value).unwrap();
+        let object_id = "https://local.example/objects/1";
+        let maybe_url = get_remote_interaction_url_from_jrd(&jrd, object_id);
+        assert_eq!(maybe_url, None);
+    }
+}
@@ -0,0 +1,58 @@
+//! Correlation IDs for tracing requests through the whole pipeline
+use std::future::Future;
+use std::time::Instant;
+
+use tracing::{Instrument, Span};
+use uuid::Uuid;
+
+tokio::task_local! {
+    static CORRELATION_ID: String;
+}
+
+pub fn generate_correlation_id() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+/// Returns correlation ID of the current task
+pub fn get_correlation_id() -> Option<String> {
+    CORRELATION_ID.try_with(|value| value.clone()).ok()
+}
+
+/// Runs future with the given correlation ID
+pub async fn with_correlation_id<F: Future>(
+    correlation_id: String,
+    future: F,
+) -> F::Output {
+    CORRELATION_ID.scope(correlation_id, future).await
+}
+
+/// Runs future within the given span and logs its execution time
+pub async fn with_span_timing<F: Future>(
+    span: Span,
+    future: F,
+) -> F::Output {
+    let start_time = Instant::now();
+    let output = future.instrument(span.clone()).await;
+    span.in_scope(|| {
+        tracing::info!(elapsed = ?start_time.elapsed(), "span completed");
+    });
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_with_correlation_id() {
+        assert_eq!(get_correlation_id(), None);
+        let correlation_id = generate_correlation_id();
+        assert_eq!(correlation_id.len(), 32);
+        let output = with_correlation_id(
+            correlation_id.clone(),
+            async { get_correlation_id() },
+        ).await;
+        assert_eq!(output, Some(correlation_id));
+        assert_eq!(get_correlation_id(), None);
+    }
+}
@@ -1,16 +1,29 @@
+use apx_core::url::http_uri::HttpUri;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value as JsonValue};
+use uuid::Uuid;
 
 use mitra_models::{
     database::{DatabaseClient, DatabaseError, DatabaseTypeError},
     posts::constants::PREINSTALLED_FTS_CONFIG,
     properties::constants::{
+        APP_APPROVAL_REQUIRED,
         DEFAULT_FTS_CONFIG,
         FAVORITE_EMOJIS,
         FEDERATED_TIMELINE_RESTRICTED,
         FILTER_BLOCKLIST_PUBLIC,
         FILTER_KEYWORDS,
+        FILTER_MEDIA_ATTACHMENT_LIMIT,
+        INSTANCE_CONTACT_ACCOUNT,
+        INSTANCE_DESCRIPTION,
+        INSTANCE_EXTENDED_DESCRIPTION,
+        INSTANCE_THUMBNAIL,
         LIKE_EMOJI,
+        MAINTENANCE_MODE,
+        PORTABLE_ACCOUNTS_LIMIT,
+        REACTION_ALLOWLIST,
+        REACTION_POLICY,
+        ROLE_LIMITS,
     },
     properties::queries::{
         get_internal_properties_json,
@@ -19,14 +32,30 @@ use mitra_models::{
 use mitra_validators::errors::ValidationError;
 use mitra_utils::unicode::is_single_character;
 
+use crate::{
+    reactions::{validate_reaction_allowlist, ReactionPolicy},
+    roles::{validate_role_limits, RoleLimitsMap},
+};
+
 // Dynamic configuration parameters
-pub const EDITABLE_PROPERTIES: [&str; 6] = [
+pub const EDITABLE_PROPERTIES: [&str; 17] = [
+    APP_APPROVAL_REQUIRED,
     DEFAULT_FTS_CONFIG,
     FAVORITE_EMOJIS,
     FEDERATED_TIMELINE_RESTRICTED,
     FILTER_BLOCKLIST_PUBLIC,
     FILTER_KEYWORDS,
+    FILTER_MEDIA_ATTACHMENT_LIMIT,
+    INSTANCE_CONTACT_ACCOUNT,
+    INSTANCE_DESCRIPTION,
+    INSTANCE_EXTENDED_DESCRIPTION,
+    INSTANCE_THUMBNAIL,
     LIKE_EMOJI,
+    MAINTENANCE_MODE,
+    PORTABLE_ACCOUNTS_LIMIT,
+    REACTION_ALLOWLIST,
+    REACTION_POLICY,
+    ROLE_LIMITS,
 ];
 
 const LIKE_EMOJI_VARIANTS: [&str; 2] = [
@@ -52,8 +81,10 @@ pub fn validate_editable_parameter(
                 return Err(ValidationError("invalid unicode emoji"));
             };
         },
-        FEDERATED_TIMELINE_RESTRICTED
-            | FILTER_BLOCKLIST_PUBLIC =>
+        APP_APPROVAL_REQUIRED
+            | FEDERATED_TIMELINE_RESTRICTED
+            | FILTER_BLOCKLIST_PUBLIC
+            | MAINTENANCE_MODE =>
         {
             let _: bool = serde_json::from_value(value)
                 .map_err(|_| ValidationError("invalid value type"))?;
@@ -62,6 +93,26 @@ pub fn validate_editable_parameter(
             let _: Vec<String> = serde_json::from_value(value)
                 .map_err(|_| ValidationError("invalid value type"))?;
         },
+        FILTER_MEDIA_ATTACHMENT_LIMIT => {
+            let _: usize = serde_json::from_value(value)
+                .map_err(|_| ValidationError("invalid value type"))?;
+        },
+        INSTANCE_CONTACT_ACCOUNT => {
+            let _: Option<Uuid> = serde_json::from_value(value)
+                .map_err(|_| ValidationError("invalid value type"))?;
+        },
+        INSTANCE_DESCRIPTION | INSTANCE_EXTENDED_DESCRIPTION => {
+            let _: Option<String> = serde_json::from_value(value)
+                .map_err(|_| ValidationError("invalid value type"))?;
+        },
+        INSTANCE_THUMBNAIL => {
+            let maybe_url: Option<String> = serde_json::from_value(value)
+                .map_err(|_| ValidationError("invalid value type"))?;
+            if let Some(url) = maybe_url {
+                HttpUri::parse(&url)
+                    .map_err(|_| ValidationError("invalid thumbnail URL"))?;
+            };
+        },
         LIKE_EMOJI => {
             let value_str: String = serde_json::from_value(value)
                 .map_err(|_| ValidationError("invalid value type"))?;
@@ -69,6 +120,24 @@ pub fn validate_editable_parameter(
                 return Err(ValidationError("invalid emoji name"));
             };
         },
+        PORTABLE_ACCOUNTS_LIMIT => {
+            let _: Option<u32> = serde_json::from_value(value)
+                .map_err(|_| ValidationError("invalid value type"))?;
+        },
+        REACTION_ALLOWLIST => {
+            let allowlist: Vec<String> = serde_json::from_value(value)
+                .map_err(|_| ValidationError("invalid value type"))?;
+            validate_reaction_allowlist(&allowlist)?;
+        },
+        REACTION_POLICY => {
+            let _: ReactionPolicy = serde_json::from_value(value)
+                .map_err(|_| ValidationError("invalid value type"))?;
+        },
+        ROLE_LIMITS => {
+            let role_limits: RoleLimitsMap = serde_json::from_value(value)
+                .map_err(|_| ValidationError("invalid value type"))?;
+            validate_role_limits(&role_limits)?;
+        },
         _ => return Err(ValidationError("invalid parameter name")),
     };
     Ok(())
@@ -77,17 +146,34 @@ pub fn validate_editable_parameter(
 #[derive(Deserialize, Serialize)]
 #[serde(default)]
 pub struct DynamicConfig {
+    pub app_approval_required: bool,
     pub default_fts_config: String,
     pub favorite_emojis: Vec<String>,
     pub federated_timeline_restricted: bool,
     pub filter_blocklist_public: bool,
     pub filter_keywords: Vec<String>,
+    // Used by "limit-media-attachments" filter rules
+    pub filter_media_attachment_limit: usize,
+    // Instance information (overrides values from configuration file)
+    pub instance_contact_account: Option<Uuid>,
+    pub instance_description: Option<String>,
+    pub instance_extended_description: Option<String>,
+    pub instance_thumbnail: Option<String>,
     pub like_emoji: String,
+    pub maintenance_mode: bool,
+    // Maximum number of portable accounts (no limit if not set)
+    pub portable_accounts_limit: Option<u32>,
+    // Emojis accepted when reaction policy is "allowlist"
+    pub reaction_allowlist: Vec<String>,
+    pub reaction_policy: ReactionPolicy,
+    // Per-role limits (override values from configuration file)
+    pub role_limits: RoleLimitsMap,
 }
 
 impl Default for DynamicConfig {
     fn default() -> Self {
         Self {
+            app_approval_required: false,
             default_fts_config: PREINSTALLED_FTS_CONFIG.to_owned(),
             favorite_emojis: DEFAULT_FAVORITE_EMOJIS
                 .iter()
@@ -96,7 +182,17 @@ impl Default for DynamicConfig {
             federated_timeline_restricted: false,
             filter_blocklist_public: false,
             filter_keywords: vec![],
+            filter_media_attachment_limit: 1,
+            instance_contact_account: None,
+            instance_description: None,
+            instance_extended_description: None,
+            instance_thumbnail: None,
             like_emoji: LIKE_EMOJI_VARIANTS[0].to_string(),
+            maintenance_mode: false,
+            portable_accounts_limit: None,
+            reaction_allowlist: vec![],
+            reaction_policy: ReactionPolicy::default(),
+            role_limits: RoleLimitsMap::new(),
         }
     }
 }
@@ -128,6 +224,28 @@ mod tests {
         assert_eq!(error.to_string(), "invalid parameter name");
     }
 
+    #[test]
+    fn test_validate_editable_parameter_instance_thumbnail() {
+        let value = json!("https://social.example/thumbnail.png");
+        assert!(validate_editable_parameter(INSTANCE_THUMBNAIL, &value).is_ok());
+        let value = json!(null);
+        assert!(validate_editable_parameter(INSTANCE_THUMBNAIL, &value).is_ok());
+        let value = json!("ftp://social.example/thumbnail.png");
+        let error = validate_editable_parameter(INSTANCE_THUMBNAIL, &value)
+            .err().unwrap();
+        assert_eq!(error.to_string(), "invalid thumbnail URL");
+    }
+
+    #[test]
+    fn test_validate_editable_parameter_reaction_policy() {
+        let value = json!("likes_only");
+        assert!(validate_editable_parameter(REACTION_POLICY, &value).is_ok());
+        let value = json!("none");
+        let error = validate_editable_parameter(REACTION_POLICY, &value)
+            .err().unwrap();
+        assert_eq!(error.to_string(), "invalid value type");
+    }
+
     #[test]
     fn test_dynamic_config_keys() {
         let config = DynamicConfig::default();
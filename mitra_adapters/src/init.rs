@@ -20,7 +20,9 @@ use log::Level;
 
 use mitra_config::{
     parse_config,
+    try_parse_config,
     Config,
+    ConfigError,
     SoftwareMetadata,
 };
 use mitra_models::{
@@ -40,15 +42,20 @@ use mitra_models::{
     properties::constants::{
         INSTANCE_ED25519_SECRET_KEY,
         INSTANCE_RSA_SECRET_KEY,
+        INSTANCE_VAPID_SECRET_KEY,
+        MEDIA_URL_SECRET_KEY,
     },
     properties::queries::{
         get_internal_property,
         set_internal_property,
     },
 };
-use mitra_services::media::MediaStorage;
+use mitra_services::{
+    media::{generate_media_url_key, MediaStorage, MediaUrlKey},
+    push::{generate_vapid_key, get_vapid_public_key, VapidSecretKey},
+};
 
-use crate::logger::configure_logger;
+use crate::logger::{configure_logger, set_log_level};
 
 pub fn initialize_app(
     with_metadata: SoftwareMetadata,
@@ -56,7 +63,7 @@ pub fn initialize_app(
 ) -> Config {
     let (config, config_warnings) = parse_config(with_metadata);
     let log_level = override_log_level.unwrap_or(config.log_level);
-    configure_logger(log_level);
+    configure_logger(log_level, &config.log_filters, config.log_format);
     log::info!(
         "{} v{}, environment = '{:?}'",
         config.software.name,
@@ -70,6 +77,23 @@ pub fn initialize_app(
     config
 }
 
+/// Re-reads configuration file and applies parameters
+/// that can be changed without restart (log levels, limits,
+/// blocked and allowed instances).
+/// Filter rules and dynamic configuration parameters are stored
+/// in the database and don't require reloading.
+pub fn reload_config(config: &Config) -> Result<Vec<String>, ConfigError> {
+    let (new_config, config_warnings) =
+        try_parse_config(config.software)?;
+    config.reload(&new_config);
+    set_log_level(new_config.log_level, &new_config.log_filters);
+    log::info!("config reloaded from {}", new_config.config_path);
+    for warning in config_warnings.iter() {
+        log::warn!("{}", warning);
+    };
+    Ok(config_warnings)
+}
+
 unsafe extern "C" {
     fn geteuid() -> u32;
 }
@@ -196,6 +220,51 @@ async fn prepare_instance_ed25519_key(
     Ok(secret_key)
 }
 
+async fn prepare_instance_vapid_key(
+    db_client: &impl DatabaseClient,
+) -> Result<VapidSecretKey, DatabaseError> {
+    let maybe_secret_key_bytes: Option<Vec<u8>> =
+        get_internal_property(db_client, INSTANCE_VAPID_SECRET_KEY)
+            .await?;
+    let secret_key = if let Some(secret_key_bytes) = maybe_secret_key_bytes {
+        let secret_key: VapidSecretKey = secret_key_bytes.try_into()
+            .map_err(|_| DatabaseTypeError)?;
+        // Verify key
+        get_vapid_public_key(&secret_key).map_err(|_| DatabaseTypeError)?;
+        secret_key
+    } else {
+        let secret_key = generate_vapid_key();
+        set_internal_property(
+            db_client,
+            INSTANCE_VAPID_SECRET_KEY,
+            &secret_key,
+        ).await?;
+        log::info!("instance VAPID key generated");
+        secret_key
+    };
+    Ok(secret_key)
+}
+
+async fn prepare_media_url_key(
+    db_client: &impl DatabaseClient,
+) -> Result<MediaUrlKey, DatabaseError> {
+    let maybe_secret_key_bytes: Option<Vec<u8>> =
+        get_internal_property(db_client, MEDIA_URL_SECRET_KEY).await?;
+    let secret_key = if let Some(secret_key_bytes) = maybe_secret_key_bytes {
+        secret_key_bytes.try_into().map_err(|_| DatabaseTypeError)?
+    } else {
+        let secret_key = generate_media_url_key();
+        set_internal_property(
+            db_client,
+            MEDIA_URL_SECRET_KEY,
+            &secret_key,
+        ).await?;
+        log::info!("media URL key generated");
+        secret_key
+    };
+    Ok(secret_key)
+}
+
 async fn prepare_instance_keys(
     config: &mut Config,
     db_client: &impl DatabaseClient,
@@ -204,6 +273,10 @@ async fn prepare_instance_keys(
     config.set_instance_rsa_key(instance_rsa_key);
     let instance_ed25519_key = prepare_instance_ed25519_key(db_client).await?;
     config.set_instance_ed25519_key(instance_ed25519_key);
+    let instance_vapid_key = prepare_instance_vapid_key(db_client).await?;
+    config.set_instance_vapid_key(instance_vapid_key);
+    let media_url_key = prepare_media_url_key(db_client).await?;
+    config.set_media_url_key(media_url_key);
     Ok(())
 }
 
@@ -246,4 +319,22 @@ mod tests {
         let key_2 = prepare_instance_ed25519_key(db_client).await.unwrap();
         assert_eq!(key_1, key_2);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_prepare_media_url_key() {
+        let db_client = &create_test_database().await;
+        let key_1 = prepare_media_url_key(db_client).await.unwrap();
+        let key_2 = prepare_media_url_key(db_client).await.unwrap();
+        assert_eq!(key_1, key_2);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_prepare_instance_vapid_key() {
+        let db_client = &create_test_database().await;
+        let key_1 = prepare_instance_vapid_key(db_client).await.unwrap();
+        let key_2 = prepare_instance_vapid_key(db_client).await.unwrap();
+        assert_eq!(key_1, key_2);
+    }
 }
@@ -0,0 +1,33 @@
+use mitra_config::Config;
+use mitra_models::{
+    accounts::{
+        queries::{get_admin_user, get_user_by_id},
+        types::User,
+    },
+    database::{DatabaseClient, DatabaseError},
+};
+
+use super::dynamic_config::DynamicConfig;
+
+/// Returns the user who should be listed as instance contact
+pub async fn get_instance_contact(
+    config: &Config,
+    dynamic_config: &DynamicConfig,
+    db_client: &impl DatabaseClient,
+) -> Result<Option<User>, DatabaseError> {
+    if let Some(user_id) = dynamic_config.instance_contact_account {
+        match get_user_by_id(db_client, user_id).await {
+            Ok(user) => return Ok(Some(user)),
+            Err(DatabaseError::NotFound(_)) => {
+                log::warn!("contact account {user_id} doesn't exist");
+            },
+            Err(other_error) => return Err(other_error),
+        };
+    };
+    let maybe_admin = if config.instance_staff_public {
+        get_admin_user(db_client).await?
+    } else {
+        None
+    };
+    Ok(maybe_admin)
+}
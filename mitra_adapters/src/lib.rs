@@ -1,9 +1,13 @@
+pub mod correlation;
 pub mod dynamic_config;
 pub mod init;
+pub mod instance;
 mod logger;
 pub mod media;
+pub mod passwords;
 pub mod payments;
 pub mod permissions;
 pub mod posts;
 pub mod profiles;
+pub mod reactions;
 pub mod roles;
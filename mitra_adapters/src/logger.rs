@@ -1,7 +1,20 @@
-use std::io::Write;
+use std::collections::HashMap;
+use std::sync::OnceLock;
 
 use log::Level;
-use chrono::Local;
+use tracing_subscriber::{
+    filter::EnvFilter,
+    fmt::{self, time::ChronoLocal},
+    layer::SubscriberExt,
+    reload,
+    util::SubscriberInitExt,
+    Registry,
+};
+
+use mitra_config::LogFormat;
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> =
+    OnceLock::new();
 
 // Next level (less verbose)
 fn next_level(level: Level) -> Level {
@@ -11,20 +24,84 @@ fn next_level(level: Level) -> Level {
         .unwrap_or(Level::Error)
 }
 
-pub fn configure_logger(base_level: Level) -> () {
+fn build_filter(
+    base_level: Level,
+    module_levels: &HashMap<String, Level>,
+) -> EnvFilter {
+    // RUST_LOG overrides configuration
+    if let Ok(filter) = EnvFilter::try_from_default_env() {
+        return filter;
+    };
     let actix_level = next_level(base_level);
-    env_logger::Builder::new()
-        .format(|buf, record| {
-            writeln!(buf,
-                "{} {} [{}] {}",
-                Local::now().format("%Y-%m-%dT%H:%M:%S"),
-                record.target(),
-                record.level(),
-                record.args(),
-            )
-        })
-        .filter_level(base_level.to_level_filter())
-        .filter_module("actix_web::middleware::logger", actix_level.to_level_filter())
-        .parse_default_env()
+    let mut directives = vec![
+        base_level.to_string(),
+        format!("actix_web::middleware::logger={actix_level}"),
+    ];
+    // Module levels can be more verbose than the base level
+    for (module, level) in module_levels {
+        directives.push(format!("{module}={level}"));
+    };
+    EnvFilter::builder().parse_lossy(directives.join(","))
+}
+
+pub fn configure_logger(
+    base_level: Level,
+    module_levels: &HashMap<String, Level>,
+    format: LogFormat,
+) -> () {
+    let filter = build_filter(base_level, module_levels);
+    let (filter_layer, filter_handle) = reload::Layer::new(filter);
+    let (text_layer, json_layer) = match format {
+        LogFormat::Text => {
+            let layer = fmt::layer()
+                .with_ansi(false)
+                .with_timer(ChronoLocal::new("%Y-%m-%dT%H:%M:%S".to_owned()));
+            (Some(layer), None)
+        },
+        LogFormat::Json => {
+            let layer = fmt::layer()
+                .json()
+                .with_timer(ChronoLocal::rfc_3339())
+                .with_current_span(true)
+                .with_span_list(false);
+            (None, Some(layer))
+        },
+    };
+    // Records created with `log` macros are converted into tracing events
+    tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(text_layer)
+        .with(json_layer)
         .init();
+    FILTER_HANDLE.set(filter_handle)
+        .expect("logger should be initialized only once");
+}
+
+/// Changes log levels of already initialized logger
+pub(crate) fn set_log_level(
+    base_level: Level,
+    module_levels: &HashMap<String, Level>,
+) -> () {
+    let Some(filter_handle) = FILTER_HANDLE.get() else {
+        return;
+    };
+    let filter = build_filter(base_level, module_levels);
+    if let Err(error) = filter_handle.reload(filter) {
+        log::error!("failed to change log level: {error}");
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::filter::LevelFilter;
+    use super::*;
+
+    #[test]
+    fn test_build_filter_module_level_more_verbose() {
+        let module_levels = HashMap::from([
+            ("mitra_activitypub".to_owned(), Level::Debug),
+        ]);
+        let filter = build_filter(Level::Warn, &module_levels);
+        assert_eq!(filter.max_level_hint(), Some(LevelFilter::DEBUG));
+    }
 }
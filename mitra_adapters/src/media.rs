@@ -1,5 +1,7 @@
 use mitra_config::Config;
 use mitra_models::{
+    accounts::types::User,
+    attachments::queries::get_storage_usage,
     database::{DatabaseClient, DatabaseError},
     media::types::DeletionQueue,
 };
@@ -8,6 +10,42 @@ use mitra_services::{
     media::MediaStorage,
 };
 
+use crate::roles::{get_user_storage_quota, RoleLimitsMap};
+
+/// Storage quota of a local user and current storage usage, in bytes
+pub struct StorageQuota {
+    pub limit: u64,
+    pub usage: u64,
+}
+
+impl StorageQuota {
+    pub fn available(&self) -> u64 {
+        self.limit.saturating_sub(self.usage)
+    }
+
+    pub fn can_store(&self, file_size: usize) -> bool {
+        u64::try_from(file_size).unwrap_or(u64::MAX) <= self.available()
+    }
+
+    pub fn add_usage(&mut self, file_size: usize) -> () {
+        self.usage = self.usage
+            .saturating_add(u64::try_from(file_size).unwrap_or(u64::MAX));
+    }
+}
+
+/// Returns storage quota of a local user (None if quota is not set)
+pub async fn get_storage_quota(
+    db_client: &impl DatabaseClient,
+    role_limits: &RoleLimitsMap,
+    user: &User,
+) -> Result<Option<StorageQuota>, DatabaseError> {
+    let Some(limit) = get_user_storage_quota(role_limits, user) else {
+        return Ok(None);
+    };
+    let usage = get_storage_usage(db_client, user.id).await?;
+    Ok(Some(StorageQuota { limit, usage }))
+}
+
 pub fn delete_files(
     storage: &MediaStorage,
     files: &[String],
@@ -55,3 +93,19 @@ pub async fn delete_orphaned_media(
     delete_media(config, queue).await;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_quota() {
+        let mut storage_quota = StorageQuota { limit: 1000, usage: 600 };
+        assert_eq!(storage_quota.available(), 400);
+        assert_eq!(storage_quota.can_store(400), true);
+        assert_eq!(storage_quota.can_store(401), false);
+        storage_quota.add_usage(500);
+        assert_eq!(storage_quota.available(), 0);
+        assert_eq!(storage_quota.can_store(1), false);
+    }
+}
@@ -0,0 +1,82 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+
+use mitra_config::PasswordPolicy;
+use mitra_validators::passwords::{validate_password, PasswordError};
+
+fn contains_password(
+    reader: impl BufRead,
+    password: &str,
+) -> Result<bool, std::io::Error> {
+    for line in reader.lines() {
+        if line?.trim_end() == password {
+            return Ok(true);
+        };
+    };
+    Ok(false)
+}
+
+async fn is_breached_password(
+    file_path: PathBuf,
+    password: String,
+) -> Result<bool, std::io::Error> {
+    // Breached password lists can be large, don't block the runtime
+    tokio::task::spawn_blocking(move || {
+        let file = File::open(file_path)?;
+        contains_password(BufReader::new(file), &password)
+    }).await.map_err(std::io::Error::other)?
+}
+
+/// Verifies that password satisfies the password policy
+pub async fn check_password(
+    policy: &PasswordPolicy,
+    password: &str,
+) -> Result<(), PasswordError> {
+    validate_password(password, policy.min_length, policy.min_score)?;
+    if let Some(ref file_path) = policy.breached_passwords_file {
+        let result = is_breached_password(
+            file_path.clone(),
+            password.to_string(),
+        ).await;
+        match result {
+            Ok(true) => return Err(PasswordError::Breached),
+            Ok(false) => (),
+            Err(error) => {
+                // Don't prevent registration if list is not available
+                log::error!(
+                    "failed to read breached password list {}: {}",
+                    file_path.display(),
+                    error,
+                );
+            },
+        };
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::*;
+
+    #[test]
+    fn test_contains_password() {
+        let list = "123456\npassword\nhunter2\n";
+        assert!(contains_password(Cursor::new(list), "hunter2").unwrap());
+        assert!(!contains_password(Cursor::new(list), "hunter").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_check_password() {
+        let policy = PasswordPolicy {
+            min_length: 8,
+            ..Default::default()
+        };
+        assert_eq!(
+            check_password(&policy, "test").await,
+            Err(PasswordError::TooShort),
+        );
+        assert_eq!(check_password(&policy, "test1234").await, Ok(()));
+    }
+}
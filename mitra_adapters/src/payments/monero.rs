@@ -18,7 +18,7 @@ use mitra_models::{
     invoices::types::Invoice,
     payment_methods::{
         helpers::get_payment_method_by_type_and_chain_id,
-        types::{PaymentMethod, PaymentType},
+        types::{PaymentMethod, PaymentType, PayoutSplit},
     },
 };
 use mitra_services::monero::{
@@ -82,6 +82,36 @@ pub fn validate_monero_address(
     Ok(())
 }
 
+const PAYOUT_SPLITS_MAX: usize = 10;
+
+pub fn validate_payout_splits(
+    payout_splits: &[PayoutSplit],
+    payout_address: &str,
+    expected_chain_id: &ChainId,
+) -> Result<(), ValidationError> {
+    if payout_splits.len() > PAYOUT_SPLITS_MAX {
+        return Err(ValidationError("too many payout splits"));
+    };
+    let mut addresses = vec![payout_address];
+    let mut total_percent = 0;
+    for split in payout_splits {
+        validate_monero_address(&split.payout_address, expected_chain_id)?;
+        if addresses.contains(&split.payout_address.as_str()) {
+            return Err(ValidationError("duplicate payout address"));
+        };
+        addresses.push(&split.payout_address);
+        if split.percent == 0 {
+            return Err(ValidationError("share must be greater than 0"));
+        };
+        total_percent += u32::from(split.percent);
+    };
+    // Main payout address should receive a part of the payment
+    if total_percent >= 100 {
+        return Err(ValidationError("total share must be less than 100%"));
+    };
+    Ok(())
+}
+
 pub fn validate_monero_standard_address(
     address: &str,
     expected_chain_id: &ChainId,
@@ -271,4 +301,43 @@ mod tests {
         let error = validate_monero_address(address, &chain_id).err().unwrap();
         assert_eq!(error.to_string(), "address belongs to wrong network");
     }
+
+    #[test]
+    fn test_validate_payout_splits() {
+        let payout_address = "888tNkZrPN6JsEgekjMnABU4TBzc2Dt29EPAvkRxbANsAnjyPbb3iQ1YBRk1UXcdRsiKc9dhwMVgN5S9cQUiyoogDavup3H";
+        let split_address = "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGv7SqSsaBYBb98uNbr2VBBEt7f2wfn3RVGQBEP3A";
+        let chain_id = ChainId::monero_mainnet();
+        let payout_splits = vec![PayoutSplit {
+            payout_address: split_address.to_string(),
+            percent: 20,
+        }];
+        let result = validate_payout_splits(
+            &payout_splits,
+            payout_address,
+            &chain_id,
+        );
+        assert_eq!(result.is_ok(), true);
+
+        let payout_splits = vec![PayoutSplit {
+            payout_address: split_address.to_string(),
+            percent: 100,
+        }];
+        let error = validate_payout_splits(
+            &payout_splits,
+            payout_address,
+            &chain_id,
+        ).err().unwrap();
+        assert_eq!(error.to_string(), "total share must be less than 100%");
+
+        let payout_splits = vec![PayoutSplit {
+            payout_address: payout_address.to_string(),
+            percent: 10,
+        }];
+        let error = validate_payout_splits(
+            &payout_splits,
+            payout_address,
+            &chain_id,
+        ).err().unwrap();
+        assert_eq!(error.to_string(), "duplicate payout address");
+    }
 }
@@ -1,12 +1,20 @@
+use apx_core::crypto::hashes::sha256;
+use chrono::{TimeDelta, Utc};
 use uuid::Uuid;
 
-use mitra_config::PostLimits;
-use mitra_models::profiles::types::Origin;
+use mitra_config::{DuplicatePostLimits, PostLimits};
+use mitra_models::{
+    database::{DatabaseClient, DatabaseError},
+    polls::types::PollData,
+    posts::queries::{add_post_content_hash, get_post_content_hash_count},
+    profiles::types::Origin,
+};
 use mitra_validators::errors::ValidationError;
 
 pub fn check_post_limits(
     limits: &PostLimits,
     attachments: &[Uuid],
+    maybe_poll: Option<&PollData>,
     origin: Origin,
 ) -> Result<(), ValidationError> {
     let attachment_limit = match origin {
@@ -16,5 +24,211 @@ pub fn check_post_limits(
     if attachments.len() > attachment_limit {
         return Err(ValidationError("too many attachments"));
     };
+    if let (Some(poll), Origin::Local) = (maybe_poll, origin) {
+        if poll.results.len() > limits.poll_option_local_limit {
+            return Err(ValidationError("too many poll options"));
+        };
+        let is_option_too_long = poll.results.iter()
+            .any(|result| {
+                result.option_name.chars().count() >
+                    limits.poll_option_length_local_limit
+            });
+        if is_option_too_long {
+            return Err(ValidationError("poll option name is too long"));
+        };
+    };
+    Ok(())
+}
+
+fn get_character_limit(limits: &PostLimits, origin: Origin) -> usize {
+    match origin {
+        Origin::Local => limits.character_limit,
+        Origin::Remote => limits.character_remote_limit,
+    }
+}
+
+/// Checks the length of post text submitted by local user
+pub fn check_local_post_length(
+    limits: &PostLimits,
+    text: &str,
+) -> Result<(), ValidationError> {
+    if text.chars().count() > get_character_limit(limits, Origin::Local) {
+        return Err(ValidationError("post is too long"));
+    };
     Ok(())
 }
+
+/// Truncates content of remote post if it exceeds the character limit.
+/// The result needs to be sanitized.
+pub fn truncate_remote_content(
+    limits: &PostLimits,
+    content: &str,
+) -> String {
+    let limit = get_character_limit(limits, Origin::Remote);
+    content.chars().take(limit).collect()
+}
+
+fn get_content_hash(content: &str) -> Option<[u8; 32]> {
+    let content = content.trim();
+    if content.is_empty() {
+        return None;
+    };
+    Some(sha256(content.as_bytes()))
+}
+
+/// Returns `true` if the author already reached the number of
+/// identical posts allowed within the time window
+pub async fn check_duplicate_post(
+    db_client: &impl DatabaseClient,
+    limits: &DuplicatePostLimits,
+    author_id: Uuid,
+    content: &str,
+) -> Result<bool, DatabaseError> {
+    if !limits.enabled {
+        return Ok(false);
+    };
+    let Some(content_hash) = get_content_hash(content) else {
+        return Ok(false);
+    };
+    let created_after = Utc::now() - TimeDelta::seconds(limits.window.into());
+    let count = get_post_content_hash_count(
+        db_client,
+        author_id,
+        &content_hash,
+        created_after,
+    ).await?;
+    Ok(count >= i64::from(limits.threshold))
+}
+
+/// Records content of the created post
+pub async fn save_post_content_hash(
+    db_client: &impl DatabaseClient,
+    limits: &DuplicatePostLimits,
+    post_id: Uuid,
+    author_id: Uuid,
+    content: &str,
+) -> Result<(), DatabaseError> {
+    if !limits.enabled {
+        return Ok(());
+    };
+    let Some(content_hash) = get_content_hash(content) else {
+        return Ok(());
+    };
+    add_post_content_hash(
+        db_client,
+        post_id,
+        author_id,
+        &content_hash,
+    ).await
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use mitra_models::{
+        accounts::test_utils::create_test_user,
+        database::test_utils::create_test_database,
+        polls::types::PollResult,
+        posts::test_utils::create_test_local_post,
+    };
+    use super::*;
+
+    #[test]
+    fn test_check_post_limits_poll_options() {
+        let limits = PostLimits {
+            poll_option_local_limit: 2,
+            ..Default::default()
+        };
+        let poll_data = PollData {
+            multiple_choices: false,
+            ends_at: None,
+            results: vec![
+                PollResult::new("1"),
+                PollResult::new("2"),
+                PollResult::new("3"),
+            ],
+            voters_count: None,
+        };
+        let result = check_post_limits(
+            &limits,
+            &[],
+            Some(&poll_data),
+            Origin::Local,
+        );
+        assert_eq!(result.is_err(), true);
+        let result = check_post_limits(
+            &limits,
+            &[],
+            Some(&poll_data),
+            Origin::Remote,
+        );
+        assert_eq!(result.is_ok(), true);
+    }
+
+    #[test]
+    fn test_check_local_post_length() {
+        let limits = PostLimits {
+            character_limit: 5,
+            ..Default::default()
+        };
+        assert_eq!(check_local_post_length(&limits, "тест!").is_ok(), true);
+        assert_eq!(check_local_post_length(&limits, "тест!!").is_ok(), false);
+    }
+
+    #[test]
+    fn test_truncate_remote_content() {
+        let limits = PostLimits {
+            character_limit: 3,
+            character_remote_limit: 8,
+            ..Default::default()
+        };
+        let content = truncate_remote_content(&limits, "<p>тест</p>");
+        assert_eq!(content, "<p>тест<");
+        let content = truncate_remote_content(&limits, "<p>1</p>");
+        assert_eq!(content, "<p>1</p>");
+}
+
+    #[tokio::test]
+    #[serial]
+    async fn test_check_duplicate_post() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_user(db_client, "test").await;
+        let limits = DuplicatePostLimits {
+            enabled: true,
+            threshold: 2,
+            ..Default::default()
+        };
+        for _ in 0..2 {
+            let is_duplicate = check_duplicate_post(
+                db_client,
+                &limits,
+                author.id,
+                "spam",
+            ).await.unwrap();
+            assert_eq!(is_duplicate, false);
+            let post =
+                create_test_local_post(db_client, author.id, "spam").await;
+            save_post_content_hash(
+                db_client,
+                &limits,
+                post.id,
+                author.id,
+                &post.content,
+            ).await.unwrap();
+        };
+        let is_duplicate = check_duplicate_post(
+            db_client,
+            &limits,
+            author.id,
+            "spam ",
+        ).await.unwrap();
+        assert_eq!(is_duplicate, true);
+        let is_duplicate = check_duplicate_post(
+            db_client,
+            &limits,
+            author.id,
+            "not spam",
+        ).await.unwrap();
+        assert_eq!(is_duplicate, false);
+    }
+}
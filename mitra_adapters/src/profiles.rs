@@ -1,5 +1,23 @@
+use std::cmp::min;
+
 use apx_sdk::addresses::WebfingerAddress;
-use mitra_models::profiles::types::{DbActorProfile, WebfingerHostname};
+use mitra_config::ProfileLimits;
+use mitra_models::profiles::types::{
+    DbActorProfile,
+    ExtraField,
+    WebfingerHostname,
+};
+use mitra_validators::{
+    errors::ValidationError,
+    profiles::{
+        clean_extra_field,
+        BIO_MAX_LENGTH,
+        FIELD_LOCAL_LIMIT,
+        FIELD_NAME_LENGTH_MAX,
+        FIELD_REMOTE_LIMIT,
+        FIELD_VALUE_LENGTH_MAX,
+    },
+};
 
 pub fn profile_address(
     local_hostname: &str,
@@ -14,3 +32,133 @@ pub fn profile_address(
     let address = WebfingerAddress::new_unchecked(&profile.username, hostname);
     Some(address)
 }
+
+pub fn bio_length_limit(limits: &ProfileLimits) -> usize {
+    min(limits.bio_length_limit, BIO_MAX_LENGTH)
+}
+
+pub fn field_local_limit(limits: &ProfileLimits) -> usize {
+    min(limits.field_local_limit, FIELD_LOCAL_LIMIT)
+}
+
+pub fn field_remote_limit(limits: &ProfileLimits) -> usize {
+    min(limits.field_limit, FIELD_REMOTE_LIMIT)
+}
+
+pub fn field_name_length_limit(limits: &ProfileLimits) -> usize {
+    min(limits.field_name_length_limit, FIELD_NAME_LENGTH_MAX)
+}
+
+pub fn field_value_length_limit(limits: &ProfileLimits) -> usize {
+    min(limits.field_value_length_limit, FIELD_VALUE_LENGTH_MAX)
+}
+
+/// Checks profile data submitted by local user
+pub fn check_local_profile_limits(
+    limits: &ProfileLimits,
+    maybe_bio: Option<&str>,
+    extra_fields: &[ExtraField],
+) -> Result<(), ValidationError> {
+    if let Some(bio) = maybe_bio {
+        if bio.chars().count() > bio_length_limit(limits) {
+            return Err(ValidationError("bio is too long"));
+        };
+    };
+    if extra_fields.len() > field_local_limit(limits) {
+        return Err(ValidationError("too many fields"));
+    };
+    for field in extra_fields {
+        if field.name.chars().count() > field_name_length_limit(limits) {
+            return Err(ValidationError("field name is too long"));
+        };
+        let value = field.value_source.as_ref().unwrap_or(&field.value);
+        if value.chars().count() > field_value_length_limit(limits) {
+            return Err(ValidationError("field value is too long"));
+        };
+    };
+    Ok(())
+}
+
+/// Truncates remote profile data that exceeds configured limits
+/// (HTML should be sanitized after truncation)
+pub fn truncate_remote_profile_data(
+    limits: &ProfileLimits,
+    maybe_bio: &mut Option<String>,
+    extra_fields: &mut Vec<ExtraField>,
+) {
+    if let Some(bio) = maybe_bio {
+        let length_limit = bio_length_limit(limits);
+        if bio.chars().count() > length_limit {
+            *bio = bio.chars().take(length_limit).collect();
+        };
+    };
+    extra_fields.truncate(field_remote_limit(limits));
+    for field in extra_fields.iter_mut() {
+        let name_length_limit = field_name_length_limit(limits);
+        if field.name.chars().count() > name_length_limit {
+            field.name = field.name.chars().take(name_length_limit).collect();
+        };
+        let value_length_limit = field_value_length_limit(limits);
+        if field.value.chars().count() > value_length_limit {
+            field.value = field.value.chars().take(value_length_limit).collect();
+            clean_extra_field(field);
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extra_field(name: &str, value: &str) -> ExtraField {
+        ExtraField {
+            name: name.to_string(),
+            value: value.to_string(),
+            value_source: None,
+        }
+    }
+
+    #[test]
+    fn test_check_local_profile_limits() {
+        let limits = ProfileLimits {
+            bio_length_limit: 10,
+            field_local_limit: 1,
+            ..Default::default()
+        };
+        let fields = vec![extra_field("test", "value")];
+        let result = check_local_profile_limits(&limits, Some("bio"), &fields);
+        assert!(result.is_ok());
+
+        let error = check_local_profile_limits(
+            &limits,
+            Some("bio is too long"),
+            &fields,
+        ).unwrap_err();
+        assert_eq!(error.to_string(), "bio is too long");
+
+        let fields = vec![extra_field("1", "1"), extra_field("2", "2")];
+        let error = check_local_profile_limits(&limits, None, &fields)
+            .unwrap_err();
+        assert_eq!(error.to_string(), "too many fields");
+    }
+
+    #[test]
+    fn test_truncate_remote_profile_data() {
+        let limits = ProfileLimits {
+            bio_length_limit: 5,
+            field_limit: 1,
+            field_name_length_limit: 3,
+            ..Default::default()
+        };
+        let mut bio = Some("<p>test bio</p>".to_string());
+        let mut fields = vec![
+            extra_field("first", "value"),
+            extra_field("second", "value"),
+        ];
+        truncate_remote_profile_data(&limits, &mut bio, &mut fields);
+        assert_eq!(bio.unwrap(), "<p>te");
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].name, "fir");
+        assert_eq!(fields[0].value, "value");
+    }
+}
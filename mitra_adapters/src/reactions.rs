@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use mitra_models::{
+    database::{DatabaseClient, DatabaseError},
+    emojis::types::CustomEmoji,
+    reactions::types::ReactionData,
+};
+use mitra_utils::unicode::is_single_character;
+use mitra_validators::errors::ValidationError;
+
+use crate::dynamic_config::get_dynamic_config;
+
+/// Controls which reactions are accepted
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReactionPolicy {
+    /// Any unicode emoji or custom emoji
+    #[default]
+    Any,
+    /// Only emojis from the allow-list
+    Allowlist,
+    /// Only likes (reactions without content)
+    LikesOnly,
+}
+
+/// Allow-list entries are unicode emojis,
+/// shortcodes of local custom emojis or custom emoji IDs
+pub fn validate_reaction_allowlist(
+    allowlist: &[String],
+) -> Result<(), ValidationError> {
+    for content in allowlist {
+        let is_valid = is_single_character(content) || (
+            content.len() > 2 &&
+            content.starts_with(':') &&
+            content.ends_with(':')
+        ) || Uuid::parse_str(content).is_ok();
+        if !is_valid {
+            return Err(ValidationError("invalid emoji"));
+        };
+    };
+    Ok(())
+}
+
+#[derive(Clone, Default)]
+pub struct ReactionFilter {
+    policy: ReactionPolicy,
+    allowlist: Vec<String>,
+}
+
+impl ReactionFilter {
+    pub async fn init(
+        db_client: &impl DatabaseClient,
+    ) -> Result<Self, DatabaseError> {
+        let dynamic_config = get_dynamic_config(db_client).await?;
+        let filter = Self {
+            policy: dynamic_config.reaction_policy,
+            allowlist: dynamic_config.reaction_allowlist,
+        };
+        Ok(filter)
+    }
+
+    fn is_emoji_allowed(&self, emoji: &CustomEmoji) -> bool {
+        if self.allowlist.contains(&emoji.id.to_string()) {
+            return true;
+        };
+        // Shortcodes match only local emojis
+        emoji.hostname().is_none() &&
+            self.allowlist.contains(&emoji.shortcode())
+    }
+
+    /// Returns `true` if reaction is allowed by the policy
+    pub fn is_allowed(
+        &self,
+        reaction_data: &ReactionData,
+        maybe_emoji: Option<&CustomEmoji>,
+    ) -> bool {
+        let Some(ref content) = reaction_data.content else {
+            // Likes are always allowed
+            return true;
+        };
+        match self.policy {
+            ReactionPolicy::Any => true,
+            ReactionPolicy::Allowlist => {
+                if let Some(emoji) = maybe_emoji {
+                    self.is_emoji_allowed(emoji)
+                } else {
+                    is_single_character(content) &&
+                        self.allowlist.contains(content)
+                }
+            },
+            ReactionPolicy::LikesOnly => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mitra_models::posts::types::Visibility;
+    use super::*;
+
+    fn reaction_data(content: Option<&str>) -> ReactionData {
+        ReactionData {
+            author_id: Uuid::new_v4(),
+            post_id: Uuid::new_v4(),
+            content: content.map(|content| content.to_string()),
+            emoji_id: None,
+            visibility: Visibility::Direct,
+            activity_id: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_reaction_allowlist() {
+        let allowlist = vec![
+            "❤️".to_string(),
+            ":blobcat:".to_string(),
+            "a1b2c3d4-0000-4000-8000-000000000000".to_string(),
+        ];
+        assert!(validate_reaction_allowlist(&allowlist).is_ok());
+        let allowlist = vec!["test".to_string()];
+        let error = validate_reaction_allowlist(&allowlist).err().unwrap();
+        assert_eq!(error.to_string(), "invalid emoji");
+    }
+
+    #[test]
+    fn test_reaction_filter() {
+        let allowlist = vec!["❤️".to_string(), ":blobcat:".to_string()];
+        let like = reaction_data(None);
+        let heart = reaction_data(Some("❤️"));
+        let thumbs_up = reaction_data(Some("👍"));
+        let blobcat = reaction_data(Some(":blobcat:"));
+        let local_emoji = CustomEmoji::local_for_test("blobcat");
+        let mut remote_emoji =
+            CustomEmoji::remote_for_test("blobcat", "social.example");
+        remote_emoji.id = Uuid::new_v4();
+
+        let filter = ReactionFilter {
+            policy: ReactionPolicy::Any,
+            allowlist: allowlist.clone(),
+        };
+        assert_eq!(filter.is_allowed(&like, None), true);
+        assert_eq!(filter.is_allowed(&thumbs_up, None), true);
+
+        let filter = ReactionFilter {
+            policy: ReactionPolicy::Allowlist,
+            allowlist: allowlist.clone(),
+        };
+        assert_eq!(filter.is_allowed(&like, None), true);
+        assert_eq!(filter.is_allowed(&heart, None), true);
+        assert_eq!(filter.is_allowed(&thumbs_up, None), false);
+        assert_eq!(filter.is_allowed(&blobcat, None), false);
+        assert_eq!(filter.is_allowed(&blobcat, Some(&local_emoji)), true);
+        assert_eq!(filter.is_allowed(&blobcat, Some(&remote_emoji)), false);
+
+        let filter = ReactionFilter {
+            policy: ReactionPolicy::Allowlist,
+            allowlist: vec![remote_emoji.id.to_string()],
+        };
+        assert_eq!(filter.is_allowed(&blobcat, Some(&remote_emoji)), true);
+        assert_eq!(filter.is_allowed(&blobcat, Some(&local_emoji)), false);
+
+        let filter = ReactionFilter {
+            policy: ReactionPolicy::LikesOnly,
+            allowlist: allowlist,
+        };
+        assert_eq!(filter.is_allowed(&like, None), true);
+        assert_eq!(filter.is_allowed(&heart, None), false);
+    }
+}
@@ -1,5 +1,9 @@
-use mitra_config::DefaultRole;
-use mitra_models::accounts::types::Role;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use mitra_config::{DefaultRole, PostLimits};
+use mitra_models::accounts::types::{Role, User};
 use mitra_validators::errors::ValidationError;
 
 pub const ALLOWED_ROLES: [&str; 3] = ["admin", "user", "read_only_user"];
@@ -29,3 +33,131 @@ pub fn from_default_role(value: &DefaultRole) -> Role {
         DefaultRole::ReadOnlyUser => Role::ReadOnlyUser,
     }
 }
+
+/// Limits that override values from configuration file
+/// for users with a given role
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoleLimits {
+    pub character_limit: Option<usize>,
+    pub attachment_limit: Option<usize>,
+    // Total size of uploaded media, in bytes
+    pub storage_quota: Option<u64>,
+}
+
+/// Maps role names to limits
+pub type RoleLimitsMap = BTreeMap<String, RoleLimits>;
+
+pub fn validate_role_limits(
+    role_limits: &RoleLimitsMap,
+) -> Result<(), ValidationError> {
+    for role_str in role_limits.keys() {
+        role_from_str(role_str)?;
+    };
+    Ok(())
+}
+
+pub fn get_role_limits(
+    role_limits: &RoleLimitsMap,
+    role: Role,
+) -> RoleLimits {
+    role_limits.get(role_to_str(role))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Returns storage quota for local user.
+/// Quota set for individual user takes precedence over role quota.
+pub fn get_user_storage_quota(
+    role_limits: &RoleLimitsMap,
+    user: &User,
+) -> Option<u64> {
+    user.storage_quota
+        .or(get_role_limits(role_limits, user.role).storage_quota)
+}
+
+/// Returns post limits for local user with the given role
+pub fn get_role_post_limits(
+    limits: &PostLimits,
+    role_limits: &RoleLimitsMap,
+    role: Role,
+) -> PostLimits {
+    let RoleLimits {
+        character_limit,
+        attachment_limit,
+        ..
+    } = get_role_limits(role_limits, role);
+    PostLimits {
+        character_limit: character_limit
+            .unwrap_or(limits.character_limit),
+        attachment_local_limit: attachment_limit
+            .unwrap_or(limits.attachment_local_limit),
+        ..limits.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_role_limits() {
+        let mut role_limits = RoleLimitsMap::new();
+        role_limits.insert("read_only_user".to_string(), RoleLimits::default());
+        assert!(validate_role_limits(&role_limits).is_ok());
+        role_limits.insert("moderator".to_string(), RoleLimits::default());
+        let error = validate_role_limits(&role_limits).err().unwrap();
+        assert_eq!(error.to_string(), "unknown role");
+    }
+
+    #[test]
+    fn test_get_role_post_limits() {
+        let limits = PostLimits::default();
+        let mut role_limits = RoleLimitsMap::new();
+        role_limits.insert("user".to_string(), RoleLimits {
+            character_limit: Some(500),
+            ..Default::default()
+        });
+        let post_limits = get_role_post_limits(
+            &limits,
+            &role_limits,
+            Role::NormalUser,
+        );
+        assert_eq!(post_limits.character_limit, 500);
+        assert_eq!(
+            post_limits.attachment_local_limit,
+            limits.attachment_local_limit,
+        );
+        let post_limits = get_role_post_limits(
+            &limits,
+            &role_limits,
+            Role::Admin,
+        );
+        assert_eq!(post_limits.character_limit, limits.character_limit);
+    }
+
+    #[test]
+    fn test_get_user_storage_quota() {
+        let mut role_limits = RoleLimitsMap::new();
+        role_limits.insert("user".to_string(), RoleLimits {
+            storage_quota: Some(1000),
+            ..Default::default()
+        });
+        let user = User {
+            role: Role::NormalUser,
+            ..Default::default()
+        };
+        assert_eq!(get_user_storage_quota(&role_limits, &user), Some(1000));
+        let user = User {
+            role: Role::NormalUser,
+            storage_quota: Some(5000),
+            ..Default::default()
+        };
+        assert_eq!(get_user_storage_quota(&role_limits, &user), Some(5000));
+        let user = User {
+            role: Role::Admin,
+            ..Default::default()
+        };
+        assert_eq!(get_user_storage_quota(&role_limits, &user), None);
+    }
+}
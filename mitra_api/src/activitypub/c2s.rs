@@ -0,0 +1,573 @@
+//! ActivityPub client-to-server API
+//!
+//! Activities are translated into Mastodon API operations.
+//! https://www.w3.org/TR/activitypub/#client-to-server-interactions
+use apx_sdk::{
+    constants::AP_PUBLIC,
+    deserialization::object_to_id,
+};
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+use mitra_activitypub::{
+    adapters::{
+        follow_requests::follow_or_create_request,
+        posts::publish_local_post,
+    },
+    authority::Authority,
+    c2s::authorization::verify_activity_actor,
+    handlers::activity::get_activity_audience,
+    identifiers::{
+        canonicalize_id,
+        local_activity_id_unified,
+        local_actor_id_unified,
+        LocalActorCollection,
+    },
+    importers::{get_post_by_object_id, get_profile_by_actor_id},
+    vocabulary::{CREATE, FOLLOW, LIKE, MENTION, NOTE},
+};
+use mitra_config::Config;
+use mitra_models::{
+    accounts::types::User,
+    database::{DatabaseClient, DatabaseError},
+    posts::helpers::get_post_by_id_for_view,
+    relationships::queries::get_follow_request_by_participants,
+};
+use mitra_validators::errors::ValidationError;
+
+use crate::{
+    errors::HttpError,
+    mastodon_api::statuses::{
+        helpers::{favourite_post, prepare_post_create_data},
+        types::{StatusCreateForm, POST_CONTENT_TYPE_HTML},
+    },
+};
+
+#[derive(Deserialize)]
+struct Tag {
+    #[serde(rename = "type")]
+    tag_type: String,
+    href: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Note {
+    #[serde(rename = "type")]
+    object_type: String,
+    name: Option<String>,
+    content: Option<String>,
+    in_reply_to: Option<JsonValue>,
+    #[serde(default)]
+    sensitive: bool,
+    #[serde(default)]
+    tag: Vec<Tag>,
+}
+
+async fn get_mentioned_profiles(
+    db_client: &impl DatabaseClient,
+    authority: &Authority,
+    actor_ids: Vec<String>,
+) -> Result<Vec<Uuid>, DatabaseError> {
+    let mut mentions = vec![];
+    for actor_id in actor_ids {
+        let Ok(canonical_actor_id) = canonicalize_id(&actor_id) else {
+            continue;
+        };
+        match get_profile_by_actor_id(
+            db_client,
+            authority,
+            &canonical_actor_id,
+        ).await {
+            Ok(profile) => mentions.push(profile.id),
+            // Unknown actors are ignored
+            Err(DatabaseError::NotFound(_)) => continue,
+            Err(other_error) => return Err(other_error),
+        };
+    };
+    Ok(mentions)
+}
+
+async fn handle_create(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    current_user: &User,
+    object: &JsonValue,
+) -> Result<String, HttpError> {
+    let note: Note = serde_json::from_value(object.clone())
+        .map_err(|_| ValidationError("invalid object"))?;
+    if note.object_type != NOTE {
+        return Err(ValidationError("object type is not supported").into());
+    };
+    let authority = Authority::from(&config.instance());
+    let actor_id = local_actor_id_unified(
+        &authority,
+        current_user.id,
+        &current_user.profile.username,
+    );
+    let followers = LocalActorCollection::Followers.of(&actor_id);
+    let audience: Vec<String> = get_activity_audience(object, None)?
+        .iter()
+        .map(|target| target.to_string())
+        .collect();
+    let visibility = if audience.iter().any(|target| target == AP_PUBLIC) {
+        "public"
+    } else if audience.contains(&followers) {
+        "private"
+    } else {
+        "direct"
+    };
+    let mut mentioned_actors: Vec<String> = note.tag.into_iter()
+        .filter(|tag| tag.tag_type == MENTION)
+        .filter_map(|tag| tag.href)
+        .collect();
+    mentioned_actors.extend(audience.into_iter()
+        .filter(|target| *target != AP_PUBLIC && *target != followers));
+    let mentions = get_mentioned_profiles(
+        db_client,
+        &authority,
+        mentioned_actors,
+    ).await?;
+    let maybe_in_reply_to_id = match note.in_reply_to {
+        Some(ref value) => {
+            let object_id = object_to_id(value)
+                .map_err(|_| ValidationError("invalid inReplyTo property"))?;
+            let canonical_object_id = canonicalize_id(&object_id)?;
+            let in_reply_to = get_post_by_object_id(
+                db_client,
+                &authority,
+                &canonical_object_id,
+            ).await?;
+            Some(in_reply_to.id)
+        },
+        None => None,
+    };
+    let status_form: StatusCreateForm = serde_json::from_value(json!({
+        "status": note.content.unwrap_or_default(),
+        "content_type": POST_CONTENT_TYPE_HTML,
+        "title": note.name,
+        "in_reply_to_id": maybe_in_reply_to_id,
+        "visibility": visibility,
+        "sensitive": note.sensitive,
+    }))
+        .map_err(HttpError::from_internal)?;
+    let (post_data, maybe_in_reply_to, linked) = prepare_post_create_data(
+        db_client,
+        config,
+        current_user,
+        status_form,
+        mentions,
+    ).await?;
+    let (_, create_note) = publish_local_post(
+        config,
+        db_client,
+        current_user,
+        post_data,
+        maybe_in_reply_to,
+        linked,
+    ).await?;
+    let activity_id = object_to_id(&create_note)
+        .map_err(HttpError::from_internal)?;
+    Ok(activity_id)
+}
+
+async fn handle_like(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    current_user: &User,
+    object: &JsonValue,
+) -> Result<String, HttpError> {
+    let object_id = object_to_id(object)
+        .map_err(|_| ValidationError("invalid object"))?;
+    let canonical_object_id = canonicalize_id(&object_id)?;
+    let authority = Authority::from(&config.instance());
+    let post = get_post_by_object_id(
+        db_client,
+        &authority,
+        &canonical_object_id,
+    ).await?;
+    // Check visibility
+    let mut post = get_post_by_id_for_view(
+        db_client,
+        Some(&current_user.profile),
+        post.id,
+    ).await?;
+    let like = favourite_post(
+        config,
+        db_client,
+        current_user,
+        &mut post,
+    ).await?
+        .ok_or(ValidationError("post is already favourited"))?;
+    let activity_id = object_to_id(&like)
+        .map_err(HttpError::from_internal)?;
+    Ok(activity_id)
+}
+
+async fn handle_follow(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    current_user: &User,
+    object: &JsonValue,
+) -> Result<String, HttpError> {
+    let actor_id = object_to_id(object)
+        .map_err(|_| ValidationError("invalid object"))?;
+    let canonical_actor_id = canonicalize_id(&actor_id)?;
+    let instance = config.instance();
+    let authority = Authority::from(&instance);
+    let target = get_profile_by_actor_id(
+        db_client,
+        &authority,
+        &canonical_actor_id,
+    ).await?;
+    if target.id == current_user.id {
+        return Err(ValidationError("target is current user").into());
+    };
+    follow_or_create_request(
+        db_client,
+        &instance,
+        current_user,
+        &target,
+    ).await?;
+    let follow_request = get_follow_request_by_participants(
+        db_client,
+        current_user.id,
+        target.id,
+    ).await?;
+    let activity_id = follow_request.activity_id.unwrap_or_else(|| {
+        local_activity_id_unified(&authority, FOLLOW, follow_request.id)
+    });
+    Ok(activity_id)
+}
+
+/// Processes activity submitted by a client.
+/// Returns ID of the activity that has been published.
+pub async fn handle_client_activity(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    current_user: &User,
+    activity: JsonValue,
+) -> Result<String, HttpError> {
+    let activity_type = activity["type"].as_str()
+        .ok_or(ValidationError("type property is missing"))?;
+    // Objects are wrapped in Create activity
+    let activity = if activity_type == NOTE {
+        json!({
+            "type": CREATE,
+            "object": activity,
+        })
+    } else {
+        activity
+    };
+    if !activity["actor"].is_null() {
+        verify_activity_actor(&config.instance(), current_user, &activity)?;
+    };
+    // Client-provided IDs are ignored
+    let activity_id = match activity["type"].as_str() {
+        Some(CREATE) => {
+            handle_create(
+                config,
+                db_client,
+                current_user,
+                &activity["object"],
+            ).await?
+        },
+        Some(LIKE) => {
+            handle_like(
+                config,
+                db_client,
+                current_user,
+                &activity["object"],
+            ).await?
+        },
+        Some(FOLLOW) => {
+            handle_follow(
+                config,
+                db_client,
+                current_user,
+                &activity["object"],
+            ).await?
+        },
+        _ => return Err(ValidationError("activity type is not supported").into()),
+    };
+    Ok(activity_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use mitra_models::{
+        accounts::test_utils::create_test_user,
+        database::test_utils::create_test_database,
+        posts::{
+            queries::get_posts_by_author,
+            test_utils::create_test_remote_post,
+            types::{PostDetailed, Visibility},
+        },
+        profiles::test_utils::create_test_remote_profile,
+    };
+    use super::*;
+
+    const INSTANCE_URL: &str = "https://local.example";
+
+    async fn get_latest_post(
+        db_client: &impl DatabaseClient,
+        user: &User,
+    ) -> PostDetailed {
+        let mut posts = get_posts_by_author(
+            db_client,
+            user.id,
+            Some(user.id),
+            true, // include replies
+            false,
+            false,
+            false,
+            None,
+            1,
+        ).await.unwrap();
+        posts.remove(0)
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_handle_client_activity_create_note_public() {
+        let db_client = &mut create_test_database().await;
+        let config = Config::for_test(INSTANCE_URL);
+        let user = create_test_user(db_client, "test").await;
+        let note = json!({
+            "type": "Note",
+            "content": "<p>test</p>",
+            "to": [AP_PUBLIC],
+        });
+        let activity_id = handle_client_activity(
+            &config,
+            db_client,
+            &user,
+            note,
+        ).await.unwrap();
+        assert!(activity_id.starts_with(INSTANCE_URL));
+        let post = get_latest_post(db_client, &user).await;
+        assert_eq!(post.content, "<p>test</p>");
+        assert_eq!(post.visibility, Visibility::Public);
+        assert_eq!(post.mentions.is_empty(), true);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_handle_client_activity_create_note_followers() {
+        let db_client = &mut create_test_database().await;
+        let config = Config::for_test(INSTANCE_URL);
+        let user = create_test_user(db_client, "test").await;
+        let authority = Authority::from(&config.instance());
+        let actor_id = local_actor_id_unified(
+            &authority,
+            user.id,
+            &user.profile.username,
+        );
+        let activity = json!({
+            "type": "Create",
+            "actor": actor_id,
+            "object": {
+                "type": "Note",
+                "content": "<p>test</p>",
+                "to": [LocalActorCollection::Followers.of(&actor_id)],
+            },
+        });
+        handle_client_activity(
+            &config,
+            db_client,
+            &user,
+            activity,
+        ).await.unwrap();
+        let post = get_latest_post(db_client, &user).await;
+        assert_eq!(post.visibility, Visibility::Followers);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_handle_client_activity_create_note_with_mentions() {
+        let db_client = &mut create_test_database().await;
+        let config = Config::for_test(INSTANCE_URL);
+        let user = create_test_user(db_client, "test").await;
+        let tagged = create_test_remote_profile(
+            db_client,
+            "tagged",
+            "social.example",
+            "https://social.example/users/tagged",
+        ).await;
+        let recipient = create_test_remote_profile(
+            db_client,
+            "recipient",
+            "social.example",
+            "https://social.example/users/recipient",
+        ).await;
+        let note = json!({
+            "type": "Note",
+            "content": "<p>test</p>",
+            "to": [
+                "https://social.example/users/recipient",
+                // Unknown actors are ignored
+                "https://social.example/users/unknown",
+            ],
+            "tag": [{
+                "type": "Mention",
+                "href": "https://social.example/users/tagged",
+            }],
+        });
+        handle_client_activity(
+            &config,
+            db_client,
+            &user,
+            note,
+        ).await.unwrap();
+        let post = get_latest_post(db_client, &user).await;
+        // No public or followers audience
+        assert_eq!(post.visibility, Visibility::Direct);
+        assert_eq!(post.mentions.len(), 2);
+        assert!(post.mentions.iter().any(|profile| profile.id == tagged.id));
+        assert!(post.mentions.iter().any(|profile| profile.id == recipient.id));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_handle_client_activity_create_note_direct_without_mentions() {
+        let db_client = &mut create_test_database().await;
+        let config = Config::for_test(INSTANCE_URL);
+        let user = create_test_user(db_client, "test").await;
+        let note = json!({
+            "type": "Note",
+            "content": "<p>test</p>",
+        });
+        let error = handle_client_activity(
+            &config,
+            db_client,
+            &user,
+            note,
+        ).await.err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "direct message should have at least one mention",
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_handle_client_activity_create_note_reply() {
+        let db_client = &mut create_test_database().await;
+        let config = Config::for_test(INSTANCE_URL);
+        let user = create_test_user(db_client, "test").await;
+        let author = create_test_remote_profile(
+            db_client,
+            "author",
+            "social.example",
+            "https://social.example/users/author",
+        ).await;
+        let in_reply_to = create_test_remote_post(
+            db_client,
+            author.id,
+            "test",
+            "https://social.example/objects/1",
+        ).await;
+        let note = json!({
+            "type": "Note",
+            "content": "<p>reply</p>",
+            "inReplyTo": "https://social.example/objects/1",
+            "to": [AP_PUBLIC],
+        });
+        handle_client_activity(
+            &config,
+            db_client,
+            &user,
+            note,
+        ).await.unwrap();
+        let post = get_latest_post(db_client, &user).await;
+        assert_eq!(post.in_reply_to_id, Some(in_reply_to.id));
+        // Author of the parent post is mentioned
+        assert_eq!(post.mentions.len(), 1);
+        assert_eq!(post.mentions[0].id, author.id);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_handle_client_activity_actor_mismatch() {
+        let db_client = &mut create_test_database().await;
+        let config = Config::for_test(INSTANCE_URL);
+        let user = create_test_user(db_client, "test").await;
+        let activity = json!({
+            "type": "Create",
+            "actor": "https://local.example/users/other",
+            "object": {
+                "type": "Note",
+                "content": "<p>test</p>",
+                "to": [AP_PUBLIC],
+            },
+        });
+        let error = handle_client_activity(
+            &config,
+            db_client,
+            &user,
+            activity,
+        ).await.err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "actor is not authorized to perform activity",
+        );
+        let posts = get_posts_by_author(
+            db_client,
+            user.id,
+            Some(user.id),
+            true,
+            false,
+            false,
+            false,
+            None,
+            1,
+        ).await.unwrap();
+        assert_eq!(posts.is_empty(), true);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_handle_client_activity_unsupported_types() {
+        let db_client = &mut create_test_database().await;
+        let config = Config::for_test(INSTANCE_URL);
+        let user = create_test_user(db_client, "test").await;
+        let activity = json!({
+            "type": "Announce",
+            "object": "https://social.example/objects/1",
+        });
+        let error = handle_client_activity(
+            &config,
+            db_client,
+            &user,
+            activity,
+        ).await.err().unwrap();
+        assert_eq!(error.to_string(), "activity type is not supported");
+
+        let activity = json!({
+            "type": "Create",
+            "object": {
+                "type": "Article",
+                "content": "<p>test</p>",
+                "to": [AP_PUBLIC],
+            },
+        });
+        let error = handle_client_activity(
+            &config,
+            db_client,
+            &user,
+            activity,
+        ).await.err().unwrap();
+        assert_eq!(error.to_string(), "object type is not supported");
+
+        let activity = json!({"object": "https://social.example/objects/1"});
+        let error = handle_client_activity(
+            &config,
+            db_client,
+            &user,
+            activity,
+        ).await.err().unwrap();
+        assert_eq!(error.to_string(), "type property is missing");
+    }
+}
@@ -1,4 +1,5 @@
-mod auth;
+pub mod auth;
+mod c2s;
 mod errors;
 mod receiver;
 mod types;
@@ -28,6 +28,7 @@ use mitra_activitypub::{
 };
 use mitra_config::Config;
 use mitra_models::{
+    activitypub::queries::save_incoming_activity,
     database::{
         get_database_client,
         DatabaseConnectionPool,
@@ -59,7 +60,7 @@ pub async fn receive_activity(
     let filter = &ap_client.filter;
     if let Ok(possible_actor_hostname) = get_hostname(&activity_actor) {
         // This only works for HTTP URIs
-        if filter.is_incoming_blocked(&possible_actor_hostname.to_string()) {
+        if filter.apply_incoming_filter(&possible_actor_hostname.to_string()) {
             log::info!("ignoring activity from blocked instance {possible_actor_hostname}");
             return Ok(());
         };
@@ -145,11 +146,19 @@ pub async fn receive_activity(
     };
 
     let signer_hostname = get_moderation_domain(signer.expect_actor_data())?;
-    if filter.is_incoming_blocked(signer_hostname.as_str()) {
+    if filter.apply_incoming_filter(signer_hostname.as_str()) {
         log::info!("ignoring activity from blocked instance {signer_hostname}");
         return Ok(());
     };
 
+    if signer.deleted_at.is_some() {
+        log::info!(
+            "ignoring activity from deleted actor {}",
+            signer.expect_remote_actor_id(),
+        );
+        return Ok(());
+    };
+
     let signer_id = signer.expect_remote_actor_id();
     let is_authenticated = canonical_actor_id.to_string() == signer_id;
     if !is_authenticated {
@@ -164,6 +173,18 @@ pub async fn receive_activity(
     };
 
     let db_client = &**get_database_client(db_pool).await?;
+    if config.retention.incoming_activities.is_some() {
+        // Store activity for replaying
+        save_incoming_activity(
+            db_client,
+            activity_id,
+            activity,
+            signer_hostname.as_str(),
+            recipient_id,
+            signer_id,
+            is_authenticated,
+        ).await?;
+    };
     IncomingActivityJobData::new(
         activity,
         Some((recipient_id, signer_id)),
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use mitra_activitypub::keys::Multikey;
 use mitra_models::accounts::types::PortableUser;
@@ -8,6 +9,12 @@ pub struct CollectionQueryParams {
     pub page: Option<bool>,
 }
 
+#[derive(Deserialize)]
+pub struct RepliesQueryParams {
+    pub page: Option<bool>,
+    pub after: Option<Uuid>,
+}
+
 #[derive(Deserialize)]
 pub struct InboxQueryParams {
     pub after: Option<String>,
@@ -11,7 +11,15 @@ use actix_web::{
     post,
     web,
     http::{
-        header as http_header,
+        header::{
+            self as http_header,
+            CacheControl,
+            CacheDirective,
+            EntityTag,
+            ETag,
+            Header,
+            IfNoneMatch,
+        },
         StatusCode,
         Uri,
     },
@@ -23,8 +31,10 @@ use actix_web::{
 use actix_web_httpauth::extractors::bearer::BearerAuth;
 use apx_core::{
     caip2::ChainId,
+    crypto::hashes::sha256,
     hashlink::Hashlink,
     http_digest::ContentDigest,
+    json_signatures::create::is_object_signed,
     http_types::{header_map_adapter, method_adapter, uri_adapter},
     url::{
         ap_uri::ApUri,
@@ -45,9 +55,13 @@ use serde_json::{Value as JsonValue};
 use uuid::Uuid;
 
 use mitra_activitypub::{
-    actors::builders::{
-        build_instance_actor,
-        build_local_actor,
+    actors::{
+        builders::{
+            build_instance_actor,
+            build_local_actor,
+            build_moved_local_actor,
+        },
+        cache::ACTOR_CACHE_TTL,
     },
     authentication::verify_signed_request,
     authority::Authority,
@@ -59,6 +73,7 @@ use mitra_activitypub::{
         emoji::build_emoji,
         note::build_note,
         proposal::build_proposal,
+        quote_authorization::build_quote_authorization,
     },
     c2s::authorization::{
         verify_activity_actor,
@@ -97,11 +112,13 @@ use mitra_models::{
     accounts::{
         queries::{
             get_group_account_by_id,
+            get_local_actor_version,
             get_managed_account_by_username,
             get_portable_user_by_id,
             get_portable_user_by_inbox_id,
             get_portable_user_by_outbox_id,
             get_user_by_name,
+            get_user_by_previous_username,
         },
         types::Role,
     },
@@ -119,6 +136,7 @@ use mitra_models::{
     database::{
         db_client_await,
         get_database_client,
+        DatabaseClient,
         DatabaseConnectionPool,
         DatabaseError,
     },
@@ -126,19 +144,27 @@ use mitra_models::{
     oauth::queries::get_user_by_oauth_token,
     posts::helpers::{
         add_related_posts,
+        can_create_post,
+        get_local_post_by_id,
         get_post_by_id_for_view,
     },
     posts::queries::{
         get_conversation_items,
+        get_post_by_id,
         get_posts_by_author,
-        get_thread,
+        get_public_replies,
+        get_subscribers_only_posts_by_author,
+        increment_post_view_count,
+        ThreadOrder,
     },
+    posts::types::Visibility,
     profiles::{
         queries::get_remote_profile_by_actor_id,
         types::PaymentOption,
     },
+    quote_authorizations::queries::get_local_quote_authorization,
     relationships::{
-        queries::get_related_combined,
+        queries::{get_related_combined, has_relationship},
         types::RelationshipType,
     },
 };
@@ -150,11 +176,15 @@ use crate::{
     errors::HttpError,
     http::{get_request_full_uri, log_response_error},
     ratelimit::RatelimitConfigs,
-    web_client::urls::{
-        get_post_page_url,
-        get_profile_page_url,
-        get_subscription_page_url,
-        get_tag_page_url,
+    state::AppState,
+    web_client::{
+        urls::{
+            get_post_page_url,
+            get_profile_page_url,
+            get_subscription_page_url,
+            get_tag_page_url,
+        },
+        utils::is_opengraph_enabled,
     },
 };
 
@@ -163,6 +193,7 @@ use super::{
         check_request,
         check_request_opt,
     },
+    c2s::handle_client_activity,
     errors::EndpointError,
     receiver::receive_activity,
     types::{
@@ -171,19 +202,125 @@ use super::{
         InboxQueryParams,
         PortableActorKeys,
         PortableMedia,
+        RepliesQueryParams,
     },
 };
 
+/// Returns serialized actor document with caching headers
+fn actor_response(
+    request: &HttpRequest,
+    actor_json: String,
+) -> HttpResponse {
+    let etag = EntityTag::new_strong(hex::encode(sha256(actor_json.as_bytes())));
+    let cache_control = CacheControl(vec![
+        CacheDirective::Public,
+        CacheDirective::MaxAge(ACTOR_CACHE_TTL as u32),
+    ]);
+    let is_modified = match IfNoneMatch::parse(request) {
+        Ok(IfNoneMatch::Any) => false,
+        Ok(IfNoneMatch::Items(ref items)) => {
+            !items.iter().any(|item| item.weak_eq(&etag))
+        },
+        Err(_) => true,
+    };
+    if !is_modified {
+        return HttpResponse::NotModified()
+            .insert_header(ETag(etag))
+            .insert_header(cache_control)
+            .finish();
+    };
+    HttpResponse::Ok()
+        .content_type(AP_MEDIA_TYPE)
+        .insert_header(ETag(etag))
+        .insert_header(cache_control)
+        .body(actor_json)
+}
+
+/// Redirects requests for collections of a previous actor
+/// to collections of the current actor
+async fn redirect_to_renamed_actor(
+    db_client: &impl DatabaseClient,
+    config: &Config,
+    request: &HttpRequest,
+    previous_username: &str,
+    collection: LocalActorCollection,
+) -> Result<HttpResponse, HttpError> {
+    let user =
+        get_user_by_previous_username(db_client, previous_username).await?;
+    let actor_id = local_actor_id(
+        config.instance().uri_str(),
+        &user.profile.username,
+    );
+    let mut location = collection.of(&actor_id);
+    if let Some(query) = request.uri().query() {
+        location = format!("{location}?{query}");
+    };
+    let response = HttpResponse::MovedPermanently()
+        .append_header((http_header::LOCATION, location))
+        .finish();
+    Ok(response)
+}
+
 #[get("")]
 async fn actor_view(
+    app_state: web::Data<AppState>,
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
     request: HttpRequest,
     username: web::Path<String>,
 ) -> Result<HttpResponse, HttpError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let account = get_managed_account_by_username(db_client, &username).await?;
     let instance = config.instance();
+    let config_revision = config.revision();
+    if is_activitypub_request(&header_map_adapter(request.headers())) {
+        // Cached document is valid if profile hasn't been updated
+        // and configuration hasn't been reloaded
+        match get_local_actor_version(db_client, &username).await {
+            Ok((profile_id, updated_at)) => {
+                let maybe_actor_json = app_state.actor_cache.get(
+                    profile_id,
+                    updated_at,
+                    config_revision,
+                );
+                if let Some(actor_json) = maybe_actor_json {
+                    return Ok(actor_response(&request, actor_json));
+                };
+            },
+            Err(DatabaseError::NotFound(_)) => (),
+            Err(other_error) => return Err(other_error.into()),
+        };
+    };
+    let account = match get_managed_account_by_username(db_client, &username).await {
+        Ok(account) => account,
+        Err(DatabaseError::NotFound(_)) => {
+            // Previous username of a renamed account
+            let user =
+                get_user_by_previous_username(db_client, &username).await?;
+            if !is_activitypub_request(&header_map_adapter(request.headers())) {
+                let page_url = get_profile_page_url(
+                    instance.uri_str(),
+                    &user.profile.username,
+                );
+                let response = HttpResponse::Found()
+                    .append_header((http_header::LOCATION, page_url))
+                    .finish();
+                return Ok(response);
+            };
+            let authority = Authority::server(instance.uri());
+            let media_server = MediaServer::new(&config);
+            let actor = build_moved_local_actor(
+                &authority,
+                &media_server,
+                &user,
+                &username,
+            ).map_err(|_| DatabaseError::type_error())?;
+            let response = HttpResponse::Ok()
+                .content_type(AP_MEDIA_TYPE)
+                .json(actor);
+            return Ok(response);
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
     if !is_activitypub_request(&header_map_adapter(request.headers())) {
         let page_url = get_profile_page_url(
             instance.uri_str(),
@@ -201,10 +338,15 @@ async fn actor_view(
         &media_server,
         &account,
     ).map_err(|_| DatabaseError::type_error())?;
-    let response = HttpResponse::Ok()
-        .content_type(AP_MEDIA_TYPE)
-        .json(actor);
-    Ok(response)
+    let actor_json = serde_json::to_string(&actor)
+        .map_err(|_| DatabaseError::type_error())?;
+    app_state.actor_cache.set(
+        account.id(),
+        account.profile().updated_at,
+        config_revision,
+        actor_json.clone(),
+    );
+    Ok(actor_response(&request, actor_json))
 }
 
 #[post("/inbox")]
@@ -229,22 +371,32 @@ async fn inbox(
     let activity_digest = ContentDigest::new(&request_body);
     drop(request_body);
 
-    let recipient = match get_managed_account_by_username(
-        db_client_await!(&db_pool),
-        &username,
-    ).await {
-        Ok(recipient) => recipient,
-        Err(DatabaseError::NotFound(_)) => {
-            // Return 410 Gone if inbox doesn't exist
-            // Doesn't work with Mastodon:
-            // https://github.com/mastodon/mastodon/issues/22070
-            return Ok(HttpResponse::Gone().finish())
-        },
-        Err(other_error) => return Err(other_error.into()),
+    let recipient_username = {
+        let db_client = &**get_database_client(&db_pool).await?;
+        match get_managed_account_by_username(
+            db_client,
+            &username,
+        ).await {
+            Ok(recipient) => recipient.profile().username.clone(),
+            Err(DatabaseError::NotFound(_)) => {
+                // Previous username of a renamed account
+                match get_user_by_previous_username(db_client, &username).await {
+                    Ok(user) => user.profile.username,
+                    Err(DatabaseError::NotFound(_)) => {
+                        // Return 410 Gone if inbox doesn't exist
+                        // Doesn't work with Mastodon:
+                        // https://github.com/mastodon/mastodon/issues/22070
+                        return Ok(HttpResponse::Gone().finish())
+                    },
+                    Err(other_error) => return Err(other_error.into()),
+                }
+            },
+            Err(other_error) => return Err(other_error.into()),
+        }
     };
     let recipient_id = local_actor_id(
         config.instance().uri_str(),
-        &recipient.profile().username,
+        &recipient_username,
     );
     receive_activity(
         &config,
@@ -274,11 +426,25 @@ async fn inbox(
 async fn outbox(
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     username: web::Path<String>,
     query_params: web::Query<CollectionQueryParams>,
 ) -> Result<HttpResponse, HttpError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let user = get_user_by_name(db_client, &username).await?;
+    let user = match get_user_by_name(db_client, &username).await {
+        Ok(user) => user,
+        Err(DatabaseError::NotFound(_)) => {
+            // Previous username of a renamed account
+            return redirect_to_renamed_actor(
+                db_client,
+                &config,
+                &request,
+                &username,
+                LocalActorCollection::Outbox,
+            ).await;
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
     let instance = config.instance();
     let actor_id = local_actor_id(instance.uri_str(), &username);
     let collection_id = LocalActorCollection::Outbox.of(&actor_id);
@@ -344,6 +510,7 @@ async fn outbox_client_to_server(
     auth: BearerAuth,
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
+    username: web::Path<String>,
     activity: web::Json<JsonValue>,
 ) -> Result<HttpResponse, HttpError> {
     if !config.federation.activitypub_c2s_enabled {
@@ -353,6 +520,26 @@ async fn outbox_client_to_server(
         db_client_await!(&db_pool),
         auth.token(),
     ).await?;
+    if !is_object_signed(&activity) {
+        // Unsigned activity: assign ID and publish
+        if account.profile.username != *username {
+            return Err(HttpError::PermissionError);
+        };
+        if !can_create_post(&account) {
+            return Err(HttpError::PermissionError);
+        };
+        let db_client = &mut **get_database_client(&db_pool).await?;
+        let activity_id = handle_client_activity(
+            &config,
+            db_client,
+            &account,
+            activity.into_inner(),
+        ).await?;
+        let response = HttpResponse::Created()
+            .insert_header((http_header::LOCATION, activity_id))
+            .finish();
+        return Ok(response);
+    };
     // WARNING: C2S API is unsafe
     // Only admins are allowed to use the outbox
     if account.role != Role::Admin {
@@ -378,21 +565,38 @@ async fn outbox_client_to_server(
 async fn followers_collection(
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     username: web::Path<String>,
     query_params: web::Query<CollectionQueryParams>,
 ) -> Result<HttpResponse, HttpError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let user = get_user_by_name(db_client, &username).await?;
+    let user = match get_user_by_name(db_client, &username).await {
+        Ok(user) => user,
+        Err(DatabaseError::NotFound(_)) => {
+            // Previous username of a renamed account
+            return redirect_to_renamed_actor(
+                db_client,
+                &config,
+                &request,
+                &username,
+                LocalActorCollection::Followers,
+            ).await;
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
     if query_params.page.is_some() {
         // Social graph is not available
         return Err(HttpError::PermissionError);
     };
     let actor_id = local_actor_id(config.instance().uri_str(), &username);
     let collection_id = LocalActorCollection::Followers.of(&actor_id);
+    // Hidden collections don't report total
+    let maybe_total_items = (!user.profile.hide_followers)
+        .then_some(user.profile.follower_count);
     let collection = OrderedCollection::new(
         collection_id,
         None,
-        Some(user.profile.follower_count),
+        maybe_total_items,
     );
     let response = HttpResponse::Ok()
         .content_type(AP_MEDIA_TYPE)
@@ -404,21 +608,38 @@ async fn followers_collection(
 async fn following_collection(
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     username: web::Path<String>,
     query_params: web::Query<CollectionQueryParams>,
 ) -> Result<HttpResponse, HttpError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let user = get_user_by_name(db_client, &username).await?;
+    let user = match get_user_by_name(db_client, &username).await {
+        Ok(user) => user,
+        Err(DatabaseError::NotFound(_)) => {
+            // Previous username of a renamed account
+            return redirect_to_renamed_actor(
+                db_client,
+                &config,
+                &request,
+                &username,
+                LocalActorCollection::Following,
+            ).await;
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
     if query_params.page.is_some() {
         // Social graph is not available
         return Err(HttpError::PermissionError);
     };
     let actor_id = local_actor_id(config.instance().uri_str(), &username);
     let collection_id = LocalActorCollection::Following.of(&actor_id);
+    // Hidden collections don't report total
+    let maybe_total_items = (!user.profile.hide_following)
+        .then_some(user.profile.following_count);
     let collection = OrderedCollection::new(
         collection_id,
         None,
-        Some(user.profile.following_count),
+        maybe_total_items,
     );
     let response = HttpResponse::Ok()
         .content_type(AP_MEDIA_TYPE)
@@ -430,11 +651,25 @@ async fn following_collection(
 async fn subscribers_collection(
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     username: web::Path<String>,
     query_params: web::Query<CollectionQueryParams>,
 ) -> Result<HttpResponse, HttpError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let user = get_user_by_name(db_client, &username).await?;
+    let user = match get_user_by_name(db_client, &username).await {
+        Ok(user) => user,
+        Err(DatabaseError::NotFound(_)) => {
+            // Previous username of a renamed account
+            return redirect_to_renamed_actor(
+                db_client,
+                &config,
+                &request,
+                &username,
+                LocalActorCollection::Subscribers,
+            ).await;
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
     if query_params.page.is_some() {
         // Subscriber list is hidden
         return Err(HttpError::PermissionError);
@@ -456,10 +691,24 @@ async fn subscribers_collection(
 async fn featured_collection(
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     username: web::Path<String>,
 ) -> Result<HttpResponse, HttpError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let user = get_user_by_name(db_client, &username).await?;
+    let user = match get_user_by_name(db_client, &username).await {
+        Ok(user) => user,
+        Err(DatabaseError::NotFound(_)) => {
+            // Previous username of a renamed account
+            return redirect_to_renamed_actor(
+                db_client,
+                &config,
+                &request,
+                &username,
+                LocalActorCollection::Featured,
+            ).await;
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
     let instance = config.instance();
     let actor_id = local_actor_id(instance.uri_str(), &username);
     let collection_id = LocalActorCollection::Featured.of(&actor_id);
@@ -497,6 +746,80 @@ async fn featured_collection(
     Ok(response)
 }
 
+/// Subscribers-only posts.
+/// Allows subscriber's server to retrieve posts on behalf of the subscriber.
+/// The request must be signed by the subscriber's actor.
+#[get("/collections/subscribers_only")]
+async fn subscribers_only_collection(
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
+    username: web::Path<String>,
+) -> Result<HttpResponse, HttpError> {
+    let request_full_uri = get_request_full_uri(&connection_info, request.uri());
+    let ap_client = ApClient::new_with_pool(&config, &db_pool).await?;
+    let signer = check_request(
+        &ap_client,
+        &db_pool,
+        &request,
+        &request_full_uri,
+    ).await?;
+    let db_client = &**get_database_client(&db_pool).await?;
+    let user = match get_user_by_name(db_client, &username).await {
+        Ok(user) => user,
+        Err(DatabaseError::NotFound(_)) => {
+            // Previous username of a renamed account
+            return redirect_to_renamed_actor(
+                db_client,
+                &config,
+                &request,
+                &username,
+                LocalActorCollection::SubscribersOnly,
+            ).await;
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
+    let is_subscriber = has_relationship(
+        db_client,
+        signer.id,
+        user.id,
+        RelationshipType::Subscription,
+    ).await?;
+    if !is_subscriber {
+        return Err(HttpError::PermissionError);
+    };
+    let instance = config.instance();
+    let actor_id = local_actor_id(instance.uri_str(), &username);
+    let collection_id = LocalActorCollection::SubscribersOnly.of(&actor_id);
+    let mut posts = get_subscribers_only_posts_by_author(
+        db_client,
+        user.id,
+        OrderedCollection::PAGE_SIZE,
+    ).await?;
+    add_related_posts(db_client, posts.iter_mut().collect()).await?;
+    let authority = Authority::server(instance.uri());
+    let media_server = MediaServer::new(&config);
+    let objects = posts.iter().map(|post| {
+        let note = build_note(
+            &instance.webfinger_hostname(),
+            &authority,
+            &media_server,
+            post,
+            false,
+        );
+        serde_json::to_value(note)
+            .expect("note should be serializable")
+    }).collect();
+    let collection =
+        OrderedCollection::new_with_items(collection_id, objects)
+            .with_attributed_to(&actor_id);
+    let response = HttpResponse::Ok()
+        .content_type(AP_MEDIA_TYPE)
+        .json(collection);
+    Ok(response)
+}
+
 #[get("/proposals/{chain_id}")]
 async fn proposal_view(
     config: web::Data<Config>,
@@ -547,6 +870,7 @@ pub fn actor_scope() -> Scope {
         .service(following_collection)
         .service(subscribers_collection)
         .service(featured_collection)
+        .service(subscribers_only_collection)
         .service(proposal_view)
 }
 
@@ -649,6 +973,10 @@ pub async fn object_view(
         return Err(HttpError::NotFound("post"));
     };
     if !is_activitypub_request(&header_map_adapter(request.headers())) {
+        if config.post_view_count_enabled && !is_opengraph_enabled(&config) {
+            // Otherwise the view is counted when post page is served
+            increment_post_view_count(db_client, post.id).await?;
+        };
         let page_url = get_post_page_url(instance.uri_str(), post.id);
         let response = HttpResponse::Found()
             .append_header((http_header::LOCATION, page_url))
@@ -676,15 +1004,12 @@ pub async fn replies_collection(
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
     internal_object_id: web::Path<Uuid>,
-    query_params: web::Query<CollectionQueryParams>,
+    query_params: web::Query<RepliesQueryParams>,
 ) -> Result<HttpResponse, HttpError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let internal_object_id = internal_object_id.into_inner();
-    let posts = get_thread(db_client, internal_object_id, None).await?;
-    let post = posts.iter().find(|post| post.id == internal_object_id)
-        .expect("get_thread return value should contain target post");
-    // Visibility check is done in get_thread
-    if !post.is_local() {
+    let post = get_post_by_id(db_client, internal_object_id).await?;
+    if !post.is_local() || post.visibility != Visibility::Public {
         return Err(HttpError::NotFound("post"));
     };
     let instance = config.instance();
@@ -703,19 +1028,28 @@ pub async fn replies_collection(
             .json(collection);
         return Ok(response);
     };
-    let replies: Vec<_> = posts.into_iter()
-        .filter(|post| post.in_reply_to_id == Some(internal_object_id))
-        .take(OrderedCollection::PAGE_SIZE.into())
-        .collect();
+    let replies = get_public_replies(
+        db_client,
+        internal_object_id,
+        query_params.after,
+        OrderedCollection::PAGE_SIZE,
+    ).await?;
+    let page_id = match query_params.after {
+        Some(after) => format!("{first_page_id}&after={after}"),
+        None => first_page_id.clone(),
+    };
+    let maybe_next_page_id = replies.last()
+        .filter(|_| replies.len() == usize::from(OrderedCollection::PAGE_SIZE))
+        .map(|last_reply| format!("{first_page_id}&after={}", last_reply.id));
     let objects = replies.iter().map(|post| {
         let object_id = compatible_post_object_id(&authority, post);
         serde_json::to_value(object_id)
             .expect("string should be serializable")
     }).collect();
-    let collection_page = OrderedCollection::new_page(
-        first_page_id,
-        objects,
-    );
+    let mut collection_page = OrderedCollection::new_page(page_id, objects);
+    if let Some(next_page_id) = maybe_next_page_id {
+        collection_page = collection_page.with_next(next_page_id);
+    };
     let response = HttpResponse::Ok()
         .content_type(AP_MEDIA_TYPE)
         .json(collection_page);
@@ -745,6 +1079,33 @@ pub async fn emoji_view(
     Ok(response)
 }
 
+#[get("/objects/quote_authorizations/{authorization_id}")]
+pub async fn quote_authorization_view(
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    authorization_id: web::Path<Uuid>,
+) -> Result<HttpResponse, HttpError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let authorization = get_local_quote_authorization(
+        db_client,
+        *authorization_id,
+    ).await?;
+    let quoted_post = get_local_post_by_id(
+        db_client,
+        authorization.quoted_post_id,
+    ).await?;
+    let authority = Authority::from(&config.instance());
+    let object = build_quote_authorization(
+        &authority,
+        &authorization,
+        &quoted_post,
+    );
+    let response = HttpResponse::Ok()
+        .content_type(AP_MEDIA_TYPE)
+        .json(object);
+    Ok(response)
+}
+
 #[get("/collections/tags/{tag_name}")]
 pub async fn tag_view(
     config: web::Data<Config>,
@@ -769,6 +1130,7 @@ pub async fn conversation_view(
         db_client,
         *conversation_id,
         None, // viewing as guest
+        ThreadOrder::Oldest,
     ).await?;
     if !root.is_local() {
         return Err(HttpError::NotFound("conversation"));
@@ -863,13 +1225,32 @@ async fn apgateway_create_actor_view(
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
     request: HttpRequest,
-    actor: web::Json<JsonValue>,
+    payload: web::Json<JsonValue>,
 ) -> Result<HttpResponse, HttpError> {
     let instance = config.instance();
     let maybe_invite_code = request.headers()
         .get("X-Invite-Code")
         .and_then(|value| value.to_str().ok())
         .map(|value| value.to_owned());
+    let payload = payload.into_inner();
+    let actor = if payload["type"].as_str() == Some("Create") {
+        // Signed registration activity
+        verify_portable_object(&payload).map_err(|error| {
+            log::warn!("invalid registration activity: {error}");
+            HttpError::PermissionError
+        })?;
+        let activity_actor = object_to_id(&payload["actor"])
+            .map_err(|_| ValidationError("invalid 'actor' property"))?;
+        let actor = payload["object"].clone();
+        let actor_id = object_to_id(&actor)
+            .map_err(|_| ValidationError("invalid 'object' property"))?;
+        if canonicalize_id(&activity_actor)? != canonicalize_id(&actor_id)? {
+            return Err(ValidationError("actor mismatch").into());
+        };
+        actor
+    } else {
+        payload
+    };
     verify_public_keys(
         &instance,
         None,
@@ -880,7 +1261,7 @@ async fn apgateway_create_actor_view(
     let (user, created) = register_portable_actor(
         &config,
         &db_pool,
-        actor.into_inner(),
+        actor,
         maybe_invite_code,
     ).await.inspect_err(|error| {
         log::warn!("failed to register portable actor ({error})");
@@ -1239,10 +1620,11 @@ async fn apgateway_media_upload_view(
         .and_then(|value| value.to_str().ok())
         .unwrap_or(APPLICATION_OCTET_STREAM);
     let file_data = request_body.to_vec();
-    if file_data.len() > config.limits.media.file_size_limit {
+    let limits = config.limits();
+    if file_data.len() > limits.media.file_size_limit {
         return Err(HttpError::PayloadTooLarge);
     };
-    if !config.limits.media.supported_media_types().contains(&media_type) {
+    if !limits.media.supported_media_types().contains(&media_type) {
         return Err(ValidationError("invalid media type").into());
     };
     let file_info = storage.save_file(file_data, media_type)
@@ -0,0 +1,33 @@
+use actix_web::{
+    body::{BoxBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{HeaderName, HeaderValue},
+    middleware::Next,
+    Error,
+};
+use tracing::Instrument;
+
+use mitra_adapters::correlation::{
+    generate_correlation_id,
+    with_correlation_id,
+};
+
+const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Assigns correlation ID to request
+pub async fn correlation_id_middleware(
+    request: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let correlation_id = generate_correlation_id();
+    let header_value = HeaderValue::from_str(&correlation_id)
+        .expect("correlation ID should be a valid header value");
+    // Span is always enabled, request ID will be attached to all log records
+    let span = tracing::error_span!("request", request_id = %correlation_id);
+    let mut response = with_correlation_id(
+        correlation_id,
+        next.call(request),
+    ).instrument(span).await?;
+    response.headers_mut().insert(X_REQUEST_ID, header_value);
+    Ok(response.map_into_boxed_body())
+}
@@ -0,0 +1,97 @@
+/// Liveness and readiness probes
+use actix_web::{
+    get,
+    web,
+    HttpResponse,
+    Scope,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde_json::json;
+
+use mitra_models::{
+    database::{
+        get_database_client,
+        migrate::get_pending_migration_count,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    properties::{
+        constants::WORKER_HEARTBEAT,
+        queries::get_internal_property,
+    },
+};
+
+// Workers save heartbeat every minute
+const WORKER_HEARTBEAT_TIMEOUT: i64 = 5 * 60;
+
+#[get("/live")]
+async fn liveness_view() -> HttpResponse {
+    HttpResponse::Ok().json(json!({"status": "ok"}))
+}
+
+struct ReadinessReport {
+    pending_migrations: usize,
+    worker_heartbeat: Option<DateTime<Utc>>,
+}
+
+async fn get_readiness_report(
+    db_pool: &DatabaseConnectionPool,
+) -> Result<ReadinessReport, DatabaseError> {
+    let db_client = &**get_database_client(db_pool).await?;
+    let pending_migrations = get_pending_migration_count(db_client).await?;
+    let worker_heartbeat = get_internal_property(
+        db_client,
+        WORKER_HEARTBEAT,
+    ).await?;
+    let report = ReadinessReport { pending_migrations, worker_heartbeat };
+    Ok(report)
+}
+
+#[get("/ready")]
+async fn readiness_view(
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> HttpResponse {
+    let pool_status = db_pool.status();
+    let database = json!({
+        "size": pool_status.size,
+        "available": pool_status.available,
+        "max_size": pool_status.max_size,
+    });
+    let (is_ready, body) = match get_readiness_report(&db_pool).await {
+        Ok(report) => {
+            let is_worker_alive = report.worker_heartbeat
+                .map(|heartbeat| {
+                    Utc::now() - heartbeat <
+                        TimeDelta::seconds(WORKER_HEARTBEAT_TIMEOUT)
+                })
+                .unwrap_or(false);
+            let is_ready = report.pending_migrations == 0 && is_worker_alive;
+            let body = json!({
+                "status": if is_ready { "ok" } else { "error" },
+                "database": database,
+                "pending_migrations": report.pending_migrations,
+                "worker_heartbeat": report.worker_heartbeat,
+            });
+            (is_ready, body)
+        },
+        Err(error) => {
+            log::error!("readiness check failed: {error}");
+            let body = json!({
+                "status": "error",
+                "database": database,
+            });
+            (false, body)
+        },
+    };
+    if is_ready {
+        HttpResponse::Ok().json(body)
+    } else {
+        HttpResponse::ServiceUnavailable().json(body)
+    }
+}
+
+pub fn health_api_scope() -> Scope {
+    web::scope("/health")
+        .service(liveness_view)
+        .service(readiness_view)
+}
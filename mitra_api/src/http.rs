@@ -16,6 +16,8 @@ use actix_web::{
 use log::Level;
 use serde_qs::actix::{QsForm, QsQuery};
 
+use mitra_utils::languages::{parse_accept_language, Language};
+
 use crate::{
     errors::HttpError,
 };
@@ -162,6 +164,14 @@ pub fn get_request_full_uri(
         })
 }
 
+pub fn get_language_preferences(request: &HttpRequest) -> Vec<Language> {
+    request.headers()
+        .get(http_header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map(parse_accept_language)
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
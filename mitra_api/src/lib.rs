@@ -1,8 +1,12 @@
 mod activitypub;
 mod atom;
+mod correlation;
 mod errors;
+mod health;
 mod http;
+mod maintenance;
 mod mastodon_api;
+mod media;
 mod metrics;
 mod nodeinfo;
 mod ratelimit;
@@ -0,0 +1,74 @@
+use actix_web::{
+    body::{BoxBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::{header as http_header, Method},
+    middleware::Next,
+    web,
+    Error,
+    HttpResponse,
+};
+use serde_json::json;
+
+use mitra_models::{
+    database::{
+        get_database_client,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    properties::{
+        constants::MAINTENANCE_MODE,
+        queries::get_internal_property,
+    },
+};
+
+// Seconds
+const RETRY_AFTER: u32 = 600;
+
+/// Returns true if request is allowed in maintenance mode
+fn is_allowed(request: &ServiceRequest) -> bool {
+    // Read-only requests
+    matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS)
+        // Administrators should be able to log in
+        // and to disable maintenance mode
+        || request.path() == "/oauth/token"
+        || request.path().starts_with("/api/v1/admin/")
+}
+
+async fn is_maintenance_mode_enabled(
+    db_pool: &DatabaseConnectionPool,
+) -> Result<bool, DatabaseError> {
+    let db_client = &**get_database_client(db_pool).await?;
+    let is_enabled = get_internal_property(db_client, MAINTENANCE_MODE)
+        .await?
+        .unwrap_or(false);
+    Ok(is_enabled)
+}
+
+pub async fn maintenance_mode_middleware(
+    request: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    if !is_allowed(&request) {
+        let db_pool = request.app_data::<web::Data<DatabaseConnectionPool>>()
+            .expect("app data should contain database connection pool");
+        let is_enabled = is_maintenance_mode_enabled(db_pool).await
+            .unwrap_or_else(|error| {
+                log::error!("failed to get maintenance mode status: {error}");
+                false
+            });
+        if is_enabled {
+            let mut response_builder = HttpResponse::ServiceUnavailable();
+            // Servers should retry delivery later
+            response_builder.insert_header((
+                http_header::RETRY_AFTER,
+                RETRY_AFTER.to_string(),
+            ));
+            let response = response_builder.json(json!({
+                "error": "server is in maintenance mode, please try again later",
+            }));
+            return Ok(request.into_response(response));
+        };
+    };
+    let response = next.call(request).await?;
+    Ok(response.map_into_boxed_body())
+}
@@ -2,17 +2,15 @@ use uuid::Uuid;
 
 use mitra_activitypub::authority::Authority;
 use mitra_models::{
-    database::{DatabaseClient, DatabaseError},
+    database::{DatabaseClient, DatabaseError, DatabaseTypeError},
     profiles::helpers::{
         find_declared_aliases,
         find_verified_aliases,
     },
+    profiles::queries::{get_linked_profiles, get_remote_profile_by_actor_id},
     profiles::types::DbActorProfile,
-    relationships::queries::{
-        get_relationships as get_relationships_one,
-        get_relationships_many,
-    },
-    relationships::types::{RelationshipOrFollowRequest, RelationshipType},
+    relationships::queries::get_relationship_flags,
+    relationships::types::RelationshipFlags,
 };
 use mitra_utils::markdown::markdown_basic_to_html;
 use mitra_validators::errors::ValidationError;
@@ -74,60 +72,23 @@ pub async fn parse_microsyntaxes(
     Ok(ProfileText { display_name, bio, emojis })
 }
 
-fn create_relationship_map(
-    source_id: Uuid,
-    target_id: Uuid,
-    relationships: Vec<RelationshipOrFollowRequest>,
-) -> Result<RelationshipMap, DatabaseError> {
-    let mut relationship_map = RelationshipMap { id: target_id, ..Default::default() };
-    for relationship in relationships {
-        match relationship.relationship_type {
-            RelationshipType::Follow => {
-                if relationship.is_direct(source_id, target_id)? {
-                    relationship_map.following = true;
-                } else {
-                    relationship_map.followed_by = true;
-                };
-            },
-            RelationshipType::FollowRequest => {
-                if relationship.is_direct(source_id, target_id)? {
-                    relationship_map.requested = true;
-                } else {
-                    relationship_map.requested_by = true;
-                };
-            },
-            RelationshipType::Subscription => {
-                if relationship.is_direct(source_id, target_id)? {
-                    relationship_map.subscription_to = true;
-                } else {
-                    relationship_map.subscription_from = true;
-                };
-            },
-            RelationshipType::HideReposts => {
-                if relationship.is_direct(source_id, target_id)? {
-                    relationship_map.showing_reblogs = false;
-                };
-            },
-            RelationshipType::HideReplies => {
-                if relationship.is_direct(source_id, target_id)? {
-                    relationship_map.showing_replies = false;
-                };
-            },
-            RelationshipType::Mute => {
-                if relationship.is_direct(source_id, target_id)? {
-                    relationship_map.muting = true;
-                    relationship_map.muting_notifications = true;
-                };
-            },
-            RelationshipType::Reject => {
-                if !relationship.is_direct(source_id, target_id)? {
-                    relationship_map.rejected_by = true;
-                };
-            },
-            RelationshipType::GroupAdmin => (),
-        };
-    };
-    Ok(relationship_map)
+fn create_relationship_map(flags: RelationshipFlags) -> RelationshipMap {
+    RelationshipMap {
+        id: flags.target_id,
+        following: flags.following,
+        followed_by: flags.followed_by,
+        requested: flags.requested,
+        requested_by: flags.requested_by,
+        rejected_by: flags.rejected_by,
+        subscription_to: flags.subscription_to,
+        subscription_from: flags.subscription_from,
+        showing_reblogs: !flags.hiding_reposts,
+        showing_replies: !flags.hiding_replies,
+        muting: flags.muting,
+        muting_notifications: flags.muting,
+        notifying: flags.notifying,
+        ..Default::default()
+    }
 }
 
 pub async fn get_relationship(
@@ -136,9 +97,11 @@ pub async fn get_relationship(
     target_id: Uuid,
 ) -> Result<RelationshipMap, DatabaseError> {
     // NOTE: this method returns relationship map even if target does not exist
-    let relationships =
-        get_relationships_one(db_client, source_id, target_id).await?;
-    create_relationship_map(source_id, target_id, relationships)
+    let flags = get_relationship_flags(db_client, source_id, &[target_id])
+        .await?
+        .pop()
+        .ok_or(DatabaseTypeError)?;
+    Ok(create_relationship_map(flags))
 }
 
 pub async fn get_relationships(
@@ -147,17 +110,20 @@ pub async fn get_relationships(
     target_ids: &[Uuid],
 ) -> Result<Vec<RelationshipMap>, DatabaseError> {
     // NOTE: this method returns relationship map even if target does not exist
-    let relationships =
-        get_relationships_many(db_client, source_id, target_ids).await?;
-    let mut results = vec![];
-    for (target_id, target_relationships) in relationships {
-        let relationship_map = create_relationship_map(
-            source_id,
-            target_id,
-            target_relationships,
-        )?;
-        results.push(relationship_map);
+    let mut unique_target_ids = vec![];
+    for target_id in target_ids {
+        if !unique_target_ids.contains(target_id) {
+            unique_target_ids.push(*target_id);
+        };
     };
+    let results = get_relationship_flags(
+        db_client,
+        source_id,
+        &unique_target_ids,
+    ).await?
+        .into_iter()
+        .map(create_relationship_map)
+        .collect();
     Ok(results)
 }
 
@@ -197,10 +163,54 @@ pub async fn get_aliases(
             profile,
         ))
         .collect();
-    let aliases = Aliases { declared, declared_all, verified };
+    let (maybe_primary, alternates) =
+        get_linked_profiles(db_client, profile.id).await?;
+    let primary = maybe_primary.map(|profile| Account::from_profile(
+        authority,
+        media_server,
+        profile,
+    ));
+    let linked = alternates.into_iter()
+        .map(|profile| Account::from_profile(
+            authority,
+            media_server,
+            profile,
+        ))
+        .collect();
+    let aliases = Aliases {
+        declared,
+        declared_all,
+        verified,
+        primary,
+        linked,
+    };
     Ok(aliases)
 }
 
+/// Adds `moved` attribute to the account of a local user
+pub async fn add_moved_account(
+    db_client: &impl DatabaseClient,
+    authority: &Authority,
+    media_server: &ClientMediaServer,
+    account: &mut Account,
+    moved_to: &str,
+) -> Result<(), DatabaseError> {
+    // Followers can only be moved to remote actors
+    let target = match get_remote_profile_by_actor_id(db_client, moved_to).await {
+        Ok(target) => target,
+        // Target has been deleted
+        Err(DatabaseError::NotFound(_)) => return Ok(()),
+        Err(other_error) => return Err(other_error),
+    };
+    let target_account = Account::from_profile(
+        authority,
+        media_server,
+        target,
+    );
+    account.moved = Some(Box::new(target_account));
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use serial_test::serial;
@@ -224,6 +234,57 @@ mod tests {
     };
     use super::*;
 
+    #[test]
+    fn test_create_relationship_map() {
+        let target_id = Uuid::new_v4();
+        let flags = RelationshipFlags {
+            target_id,
+            following: true,
+            requested_by: true,
+            subscription_from: true,
+            hiding_replies: true,
+            muting: true,
+            ..Default::default()
+        };
+        let relationship = create_relationship_map(flags);
+        assert_eq!(relationship.id, target_id);
+        assert!(relationship.following);
+        assert!(!relationship.followed_by);
+        assert!(!relationship.requested);
+        assert!(relationship.requested_by);
+        assert!(!relationship.rejected_by);
+        assert!(!relationship.subscription_to);
+        assert!(relationship.subscription_from);
+        assert!(relationship.showing_reblogs);
+        assert!(!relationship.showing_replies);
+        assert!(relationship.muting);
+        assert!(relationship.muting_notifications);
+        assert!(!relationship.notifying);
+        assert!(!relationship.blocking);
+        assert!(!relationship.blocked_by);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_relationships_order() {
+        let db_client = &mut create_test_database().await;
+        let (user_1, user_2) = create_users(db_client).await;
+        let user_3 = create_test_user(db_client, "third-user").await;
+        let follow_request =
+            create_follow_request(db_client, user_1.id, user_3.id).await.unwrap();
+        follow_request_accepted(db_client, follow_request.id).await.unwrap();
+        let relationships = get_relationships(
+            db_client,
+            user_1.id,
+            &[user_3.id, user_2.id, user_3.id],
+        ).await.unwrap();
+        assert_eq!(relationships.len(), 2);
+        assert_eq!(relationships[0].id, user_3.id);
+        assert!(relationships[0].following);
+        assert_eq!(relationships[1].id, user_2.id);
+        assert!(!relationships[1].following);
+    }
+
     async fn create_users(
         db_client: &mut impl DatabaseClient,
     ) -> (User, User) {
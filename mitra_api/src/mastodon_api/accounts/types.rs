@@ -10,7 +10,7 @@ use apx_core::{
     caip2::ChainId,
     did::Did,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -21,22 +21,33 @@ use mitra_activitypub::{
         profile_actor_url,
     },
 };
-use mitra_adapters::payments::subscriptions::MONERO_PAYMENT_AMOUNT_MIN;
+use mitra_adapters::{
+    media::StorageQuota,
+    payments::subscriptions::MONERO_PAYMENT_AMOUNT_MIN,
+};
 use mitra_config::MediaLimits;
 use mitra_models::{
     accounts::types::{
         ClientConfig,
         Permission,
+        QuotePolicy,
         Role as DbRole,
         SharedClientConfig,
         User,
     },
     media::types::{MediaInfo, PartialMediaInfo},
-    posts::types::{DbLanguage, Visibility},
+    posts::types::{
+        DbLanguage,
+        PostActivity as DbPostActivity,
+        Visibility,
+    },
     profiles::types::{
         ActorType,
         DbActorProfile,
         ExtraField,
+        IdentityProof as DbIdentityProof,
+        IdentityProofRecord as DbIdentityProofRecord,
+        IdentityProofType,
         MentionPolicy,
         PaymentOption,
         ProfileUpdateData,
@@ -78,6 +89,7 @@ use super::helpers::parse_profile_bio;
 pub const AUTHENTICATION_METHOD_PASSWORD: &str = "password";
 pub const AUTHENTICATION_METHOD_EIP4361: &str = "eip4361";
 pub const AUTHENTICATION_METHOD_CAIP122_MONERO: &str = "caip122_monero";
+pub const AUTHENTICATION_METHOD_DID: &str = "did";
 
 /// https://docs.joinmastodon.org/entities/field/
 #[derive(Serialize)]
@@ -89,6 +101,60 @@ pub struct AccountField {
     is_legacy_proof: bool,
 }
 
+fn identity_proof_type_to_str(proof_type: &IdentityProofType) -> &'static str {
+    match proof_type {
+        IdentityProofType::LegacyEip191IdentityProof => "ethereum-legacy",
+        IdentityProofType::LegacyMinisignIdentityProof => "minisign-legacy",
+        IdentityProofType::FepC390JcsBlake2Ed25519Proof => "minisign",
+        IdentityProofType::FepC390JcsEip191Proof => "ethereum",
+        IdentityProofType::FepC390LegacyJcsEddsaProof => "eddsa-legacy",
+        IdentityProofType::FepC390EddsaJcsProof => "minisign-unhashed",
+    }
+}
+
+/// Structured representation of identity proof (not part of Mastodon API)
+#[derive(Serialize)]
+pub struct AccountIdentityProof {
+    proof_type: &'static str,
+    did: Did,
+    identifier: String,
+    #[serde(serialize_with = "serialize_datetime_opt")]
+    verified_at: Option<DateTime<Utc>>,
+}
+
+impl AccountIdentityProof {
+    fn from_db(proof: &DbIdentityProof, identifier: String) -> Self {
+        Self {
+            proof_type: identity_proof_type_to_str(&proof.proof_type),
+            did: proof.issuer.clone(),
+            identifier: identifier,
+            verified_at: proof.verified_at,
+        }
+    }
+}
+
+/// Identity proof history entry (not part of Mastodon API)
+#[derive(Serialize)]
+pub struct IdentityProofHistoryEntry {
+    proof_type: &'static str,
+    did: Did,
+    #[serde(serialize_with = "serialize_datetime")]
+    valid_from: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_datetime_opt")]
+    valid_until: Option<DateTime<Utc>>,
+}
+
+impl IdentityProofHistoryEntry {
+    pub fn from_db(record: DbIdentityProofRecord) -> Self {
+        Self {
+            proof_type: identity_proof_type_to_str(&record.proof.proof_type),
+            did: record.proof.issuer,
+            valid_from: record.valid_from,
+            valid_until: record.valid_until,
+        }
+    }
+}
+
 /// Contains only public information
 #[derive(Serialize)]
 #[serde(tag = "type", rename_all = "kebab-case")]
@@ -110,6 +176,10 @@ pub struct AccountSource {
     privacy: &'static str,
     sensitive: bool,
     language: Option<String>,
+    hide_followers: bool,
+    hide_following: bool,
+    quote_policy: &'static str,
+    timezone: Option<String>,
 }
 
 // https://docs.joinmastodon.org/entities/Role/
@@ -143,6 +213,16 @@ impl Role {
                     ("delete_any_post", 0x1),
                 Permission::DeleteAnyProfile =>
                     ("delete_any_profile", 0x1),
+                Permission::ManageAnnouncements =>
+                    ("manage_announcements", 0x1),
+                Permission::ManageApplications =>
+                    ("manage_applications", 0x1),
+                Permission::ManageReports =>
+                    ("manage_reports", 0x10),
+                Permission::ManageServerRules =>
+                    ("manage_server_rules", 0x1),
+                Permission::ManageSettings =>
+                    ("manage_settings", 0x1),
                 Permission::ManageSubscriptionOptions =>
                     ("manage_subscription_options", 0x0),
             };
@@ -168,6 +248,14 @@ fn mention_policy_to_str(mention_policy: MentionPolicy) -> &'static str {
     }
 }
 
+fn quote_policy_to_str(quote_policy: QuotePolicy) -> &'static str {
+    match quote_policy {
+        QuotePolicy::Public => "public",
+        QuotePolicy::Followers => "followers",
+        QuotePolicy::Nobody => "nobody",
+    }
+}
+
 // https://docs.joinmastodon.org/entities/account/
 #[derive(Serialize)]
 pub struct Account {
@@ -190,13 +278,20 @@ pub struct Account {
     is_group: bool, // not part of Mastodon API
     discoverable: bool,
     pub identity_proofs: Vec<AccountField>,
+    pub verified_identities: Vec<AccountIdentityProof>, // not part of Mastodon API
     pub payment_options: Vec<AccountPaymentOption>,
     pub fields: Vec<AccountField>,
+    // Not part of Mastodon API
+    pub location: Option<String>,
+    pub birthday: Option<NaiveDate>,
+    pub pronouns: Option<String>,
     pub emojis: Vec<CustomEmoji>,
     pub followers_count: i32,
     pub following_count: i32,
     pub subscribers_count: i32,
     pub statuses_count: i32,
+    // Only for local accounts that have been moved
+    pub moved: Option<Box<Account>>,
 
     // CredentialAccount attributes
     pub source: Option<AccountSource>,
@@ -218,29 +313,28 @@ impl Account {
 
         let avatar_url = profile.avatar
             .map(|image| media_server.url_for(&image))
-            .unwrap_or(format!(
-                "{}/api/v1/accounts/identicon?input={actor_id}",
-                media_server.base_url(),
-            ));
+            .unwrap_or(media_server.default_avatar_url(&actor_id));
         let header_url = profile.banner
             .map(|image| media_server.url_for(&image))
-            .unwrap_or(format!(
-                "{}/api/v1/accounts/identicon",
-                media_server.base_url(),
-            ));
+            .unwrap_or(media_server.default_header_url());
 
         let mut identity_proofs = vec![];
+        let mut verified_identities = vec![];
         for proof in profile.identity_proofs.into_inner() {
             let (field_name, field_value) = match proof.issuer {
-                Did::Key(did_key) => {
+                Did::Key(ref did_key) => {
                     ("Key".to_string(), did_key.key_multibase())
                 },
-                Did::Pkh(did_pkh) => {
+                Did::Pkh(ref did_pkh) => {
                     let field_name =
                         Currency::from(did_pkh.chain_id()).field_name();
                     (field_name, did_pkh.address())
                 }
             };
+            verified_identities.push(AccountIdentityProof::from_db(
+                &proof,
+                field_value.clone(),
+            ));
             let field = AccountField {
                 name: field_name,
                 value: field_value,
@@ -317,13 +411,19 @@ impl Account {
             is_group: profile.actor_type == ActorType::Group,
             discoverable: true,
             identity_proofs,
+            verified_identities,
             payment_options,
             fields: extra_fields,
+            location: profile.location,
+            birthday: profile.birthday,
+            pronouns: profile.pronouns,
             emojis,
-            followers_count: profile.follower_count,
-            following_count: profile.following_count,
+            // Hidden collections don't report size
+            followers_count: if profile.hide_followers { 0 } else { profile.follower_count },
+            following_count: if profile.hide_following { 0 } else { profile.following_count },
             subscribers_count: profile.subscriber_count,
             statuses_count: profile.post_count,
+            moved: None,
             source: None,
             role: None,
             authentication_methods: None,
@@ -353,6 +453,11 @@ impl Account {
             language: user.shared_client_config.default_post_language
                 .and_then(|language| language.inner().to_639_1())
                 .map(|code| code.to_owned()),
+            hide_followers: user.profile.hide_followers,
+            hide_following: user.profile.hide_following,
+            quote_policy:
+                quote_policy_to_str(user.shared_client_config.quote_policy),
+            timezone: user.shared_client_config.timezone.clone(),
         };
         let role = Role::from_db(user.role);
         let mut authentication_methods = vec![];
@@ -365,11 +470,16 @@ impl Account {
         if user.login_address_monero.is_some() {
             authentication_methods.push(AUTHENTICATION_METHOD_CAIP122_MONERO.to_string());
         };
+        let follower_count = user.profile.follower_count;
+        let following_count = user.profile.following_count;
         let mut account = Self::from_profile(
             authority,
             media_server,
             user.profile,
         );
+        // Collection sizes are always visible to the owner
+        account.followers_count = follower_count;
+        account.following_count = following_count;
         account.source = Some(source);
         account.role = Some(role);
         account.authentication_methods = Some(authentication_methods);
@@ -393,6 +503,27 @@ pub struct AccountCreateForm {
     pub signature: Option<String>,
 
     pub invite_code: Option<String>,
+
+    // Acceptance of server rules
+    #[serde(default)]
+    pub agreement: bool,
+}
+
+/// Account created via registration API
+#[derive(Serialize)]
+pub struct CreatedAccount {
+    #[serde(flatten)]
+    pub account: Account,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub struct PasswordResetRequest {
+    pub username: String,
+    pub recovery_code: String,
+    pub new_password: String,
 }
 
 #[derive(Deserialize)]
@@ -406,6 +537,9 @@ struct AccountFieldSource {
 pub struct AccountSourceData {
     privacy: Option<String>,
     language: Option<String>,
+    pub(super) hide_followers: Option<bool>,
+    pub(super) hide_following: Option<bool>,
+    quote_policy: Option<String>,
 }
 
 impl AccountSourceData {
@@ -428,6 +562,14 @@ impl AccountSourceData {
             let language = parse_language_code(language_code)?;
             client_config.default_post_language = Some(DbLanguage::new(language));
         };
+        if let Some(ref quote_policy) = self.quote_policy {
+            client_config.quote_policy = match quote_policy.as_str() {
+                "public" => QuotePolicy::Public,
+                "followers" => QuotePolicy::Followers,
+                "nobody" => QuotePolicy::Nobody,
+                _ => return Err(ValidationError("invalid quote policy")),
+            };
+        };
         Ok(client_config)
     }
 }
@@ -448,6 +590,10 @@ pub struct AccountUpdateForm {
 
     // Not supported by Mastodon API clients
     mention_policy: Option<String>,
+    // Empty string removes the value
+    location: Option<String>,
+    birthday: Option<String>,
+    pronouns: Option<String>,
 }
 
 fn process_b64_image_field_value(
@@ -456,6 +602,7 @@ fn process_b64_image_field_value(
     db_value: Option<PartialMediaInfo>,
     media_limits: &MediaLimits,
     media_storage: &MediaStorage,
+    maybe_storage_quota: Option<&mut StorageQuota>,
 ) -> Result<Option<PartialMediaInfo>, UploadError> {
     let maybe_file_name = match form_value {
         Some(b64_data) => {
@@ -472,7 +619,11 @@ fn process_b64_image_field_value(
                     media_storage,
                     media_limits.profile_image_local_size_limit,
                     &allowed_profile_image_media_types(&media_limits.supported_media_types()),
+                    maybe_storage_quota.as_deref(),
                 )?;
+                if let Some(storage_quota) = maybe_storage_quota {
+                    storage_quota.add_usage(file_info.file_size);
+                };
                 let image = PartialMediaInfo::from(MediaInfo::local(file_info));
                 Some(image)
             }
@@ -489,6 +640,7 @@ impl AccountUpdateForm {
         profile: &DbActorProfile,
         media_limits: &MediaLimits,
         media_storage: &MediaStorage,
+        mut maybe_storage_quota: Option<StorageQuota>,
     ) -> Result<ProfileUpdateData, MastodonError> {
         assert!(profile.has_user_account());
         let mut profile_data = ProfileUpdateData::from(profile);
@@ -505,6 +657,7 @@ impl AccountUpdateForm {
             profile.avatar.clone(),
             media_limits,
             media_storage,
+            maybe_storage_quota.as_mut(),
         )?;
         profile_data.banner = process_b64_image_field_value(
             self.header,
@@ -512,6 +665,7 @@ impl AccountUpdateForm {
             profile.banner.clone(),
             media_limits,
             media_storage,
+            maybe_storage_quota.as_mut(),
         )?;
         if let Some(bot) = self.bot {
             profile_data.actor_type = if bot {
@@ -550,6 +704,23 @@ impl AccountUpdateForm {
             };
             profile_data.extra_fields = extra_fields;
         };
+        if let Some(location) = self.location {
+            profile_data.location = Some(location)
+                .filter(|value| !value.is_empty());
+        };
+        if let Some(birthday) = self.birthday {
+            profile_data.birthday = if birthday.is_empty() {
+                None
+            } else {
+                let birthday = NaiveDate::parse_from_str(&birthday, "%Y-%m-%d")
+                    .map_err(|_| ValidationError("invalid birthday"))?;
+                Some(birthday)
+            };
+        };
+        if let Some(pronouns) = self.pronouns {
+            profile_data.pronouns = Some(pronouns)
+                .filter(|value| !value.is_empty());
+        };
         Ok(profile_data)
     }
 }
@@ -585,6 +756,16 @@ pub struct AccountUpdateMultipartForm {
     source_privacy: Option<Text<String>>,
     #[multipart(rename = "source[language]")]
     source_language: Option<Text<String>>,
+    #[multipart(rename = "source[hide_followers]")]
+    source_hide_followers: Option<Text<bool>>,
+    #[multipart(rename = "source[hide_following]")]
+    source_hide_following: Option<Text<bool>>,
+    #[multipart(rename = "source[quote_policy]")]
+    source_quote_policy: Option<Text<String>>,
+
+    location: Option<Text<String>>,
+    birthday: Option<Text<String>>,
+    pronouns: Option<Text<String>>,
 }
 
 impl From<AccountUpdateMultipartForm> for AccountUpdateForm {
@@ -612,6 +793,12 @@ impl From<AccountUpdateMultipartForm> for AccountUpdateForm {
         let source_data = AccountSourceData {
             privacy: form.source_privacy.map(|value| value.into_inner()),
             language: form.source_language.map(|value| value.into_inner()),
+            hide_followers: form.source_hide_followers
+                .map(|value| value.into_inner()),
+            hide_following: form.source_hide_following
+                .map(|value| value.into_inner()),
+            quote_policy: form.source_quote_policy
+                .map(|value| value.into_inner()),
         };
         Self {
             display_name: form.display_name
@@ -640,6 +827,12 @@ impl From<AccountUpdateMultipartForm> for AccountUpdateForm {
                 .then_some(fields_attributes),
             source: Some(source_data),
             mention_policy: None,
+            location: form.location
+                .map(|value| value.into_inner()),
+            birthday: form.birthday
+                .map(|value| value.into_inner()),
+            pronouns: form.pronouns
+                .map(|value| value.into_inner()),
         }
     }
 }
@@ -665,6 +858,11 @@ pub struct IdentityProofForm {
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Deserialize)]
+pub struct IdentityProofRefreshForm {
+    pub did: Did,
+}
+
 #[derive(Deserialize)]
 pub struct IdentityProofDeleteForm {
     pub did: Did,
@@ -766,6 +964,8 @@ pub struct FollowForm {
     pub reblogs: bool,
     #[serde(default = "default_showing_replies")]
     pub replies: bool,
+    #[serde(default)]
+    pub notify: bool,
 }
 
 impl Default for FollowForm {
@@ -773,6 +973,7 @@ impl Default for FollowForm {
         Self {
             reblogs: default_showing_reblogs(),
             replies: default_showing_replies(),
+            notify: false,
         }
     }
 }
@@ -876,6 +1077,9 @@ pub struct Aliases {
     pub declared: Vec<Account>,
     pub declared_all: Vec<Alias>,
     pub verified: Vec<Account>,
+    // Profiles of the same person
+    pub primary: Option<Account>,
+    pub linked: Vec<Account>,
 }
 
 fn default_actor_collection() -> String {
@@ -888,10 +1092,27 @@ pub struct LoadActivitiesRequest {
     pub collection: String,
 }
 
+/// Weekly activity (format is similar to Mastodon's instance activity)
+#[derive(Serialize)]
+pub struct AccountActivity {
+    week: String,
+    statuses: String,
+}
+
+impl From<DbPostActivity> for AccountActivity {
+    fn from(activity: DbPostActivity) -> Self {
+        Self {
+            week: activity.week.timestamp().to_string(),
+            statuses: activity.posts.to_string(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use mitra_models::{
         media::types::{MediaInfo, PartialMediaInfo},
+        profiles::types::IdentityProofs,
     };
     use super::*;
 
@@ -916,6 +1137,43 @@ mod tests {
         assert!(account.source.is_none());
     }
 
+    #[test]
+    fn test_create_account_with_identity_proof() {
+        let authority = Authority::server_unchecked(INSTANCE_URI);
+        let media_server = ClientMediaServer::for_test(INSTANCE_URI);
+        let did: Did = "did:pkh:eip155:1:0xb9c5714089478a327f09197987f16f9e5d936e8a"
+            .parse().unwrap();
+        let proof = DbIdentityProof {
+            issuer: did.clone(),
+            proof_type: IdentityProofType::FepC390JcsEip191Proof,
+            value: serde_json::json!({
+                "proof": {"created": "2023-07-07T23:52:12Z"},
+            }),
+            verified_at: Some("2023-07-08T10:00:00Z".parse().unwrap()),
+        };
+        let mut profile = DbActorProfile::local_for_test("test");
+        profile.identity_proofs = IdentityProofs(vec![proof]);
+        let account = Account::from_profile(
+            &authority,
+            &media_server,
+            profile,
+        );
+
+        assert_eq!(account.identity_proofs.len(), 1);
+        assert_eq!(account.verified_identities.len(), 1);
+        let verified_identity = &account.verified_identities[0];
+        assert_eq!(verified_identity.proof_type, "ethereum");
+        assert_eq!(verified_identity.did, did);
+        assert_eq!(
+            verified_identity.identifier,
+            "0xb9c5714089478a327f09197987f16f9e5d936e8a",
+        );
+        assert_eq!(
+            verified_identity.verified_at.unwrap().to_rfc3339(),
+            "2023-07-08T10:00:00+00:00",
+        );
+    }
+
     #[test]
     fn test_create_account_from_user() {
         let authority = Authority::server_unchecked(INSTANCE_URI);
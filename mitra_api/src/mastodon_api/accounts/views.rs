@@ -14,10 +14,10 @@ use actix_web::{
     post,
     web,
     Either,
+    HttpRequest,
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 use apx_core::{
     caip2::ChainId,
     crypto::{
@@ -61,10 +61,17 @@ use mitra_activitypub::{
     identity::{
         create_identity_claim_fep_c390,
         create_identity_proof_fep_c390,
+        verify_identity_proof,
     },
     queues::FetcherJobData,
 };
-use mitra_adapters::roles::from_default_role;
+use mitra_adapters::{
+    dynamic_config::get_dynamic_config,
+    media::get_storage_quota,
+    passwords::check_password,
+    profiles::check_local_profile_limits,
+    roles::from_default_role,
+};
 use mitra_config::{
     AuthenticationMethod,
     Config,
@@ -73,12 +80,17 @@ use mitra_config::{
 use mitra_models::{
     accounts::{
         queries::{
+            create_recovery_codes,
             create_user,
             get_user_by_did,
+            get_user_by_id,
+            get_user_by_name,
             is_valid_invite_code,
             set_shared_client_config,
+            set_user_password,
+            use_recovery_code,
         },
-        types::UserCreateData,
+        types::{FeatureFlag, UserCreateData},
     },
     custom_feeds::queries::get_custom_feeds_by_source,
     database::{
@@ -88,11 +100,14 @@ use mitra_models::{
         DatabaseError,
     },
     notifications::helpers::create_signup_notifications,
-    posts::queries::get_posts_by_author,
+    oauth::queries::delete_oauth_tokens,
+    posts::queries::{get_post_activity, get_posts_by_author},
     profiles::queries::{
+        get_identity_proof_history,
         get_profile_by_acct,
         get_profile_by_id,
         search_profiles_by_did,
+        set_hidden_collections,
         update_profile,
     },
     profiles::types::{
@@ -101,6 +116,8 @@ use mitra_models::{
     },
     relationships::helpers::remove_follower,
     relationships::queries::{
+        disable_post_notifications,
+        enable_post_notifications,
         get_followers_paginated,
         get_following_paginated,
         hide_replies,
@@ -111,6 +128,7 @@ use mitra_models::{
         mute,
         unmute,
     },
+    server_rules::queries::get_server_rules,
     subscriptions::queries::get_incoming_subscriptions,
 };
 use mitra_services::{
@@ -130,12 +148,13 @@ use mitra_validators::{
 
 use crate::{
     http::{
+        get_language_preferences,
         get_request_base_url,
         JsonOrForm,
         MultiQuery,
     },
     mastodon_api::{
-        auth::get_current_user,
+        auth::{get_current_user, BearerAuth},
         errors::MastodonError,
         lists::types::List,
         media_server::ClientMediaServer,
@@ -144,9 +163,11 @@ use crate::{
         statuses::helpers::get_paginated_status_list,
     },
     ratelimit::RatelimitConfigs,
+    state::AppState,
 };
 
 use super::helpers::{
+    add_moved_account,
     get_aliases,
     get_relationship,
     get_relationships,
@@ -154,9 +175,11 @@ use super::helpers::{
 };
 use super::types::{
     Account,
+    AccountActivity,
     AccountCreateForm,
     AccountUpdateForm,
     AccountUpdateMultipartForm,
+    CreatedAccount,
     AUTHENTICATION_METHOD_CAIP122_MONERO,
     AUTHENTICATION_METHOD_EIP4361,
     AUTHENTICATION_METHOD_PASSWORD,
@@ -166,9 +189,12 @@ use super::types::{
     IdentityClaim,
     IdentityClaimQueryParams,
     IdentityProofForm,
+    IdentityProofRefreshForm,
     IdentityProofDeleteForm,
+    IdentityProofHistoryEntry,
     LoadActivitiesRequest,
     LookupAcctQueryParams,
+    PasswordResetRequest,
     RelationshipQueryParams,
     SearchAcctQueryParams,
     SearchDidQueryParams,
@@ -200,6 +226,16 @@ pub async fn create_account(
 
     validate_local_username(&account_form.username)?;
 
+    let rules = get_server_rules(db_client).await?;
+    let maybe_rules_accepted_at = if rules.is_empty() {
+        None
+    } else {
+        if !account_form.agreement {
+            return Err(ValidationError("server rules must be accepted").into());
+        };
+        Some(Utc::now())
+    };
+
     let authentication_method = match account_form.authentication_method.as_str() {
         AUTHENTICATION_METHOD_PASSWORD => AuthenticationMethod::Password,
         AUTHENTICATION_METHOD_EIP4361 => AuthenticationMethod::Eip4361,
@@ -214,6 +250,7 @@ pub async fn create_account(
     let maybe_password_digest = if authentication_method == AuthenticationMethod::Password {
         let password = account_form.password.as_ref()
             .ok_or(ValidationError("password is required"))?;
+        check_password(&config.password_policy, password).await?;
         let password_digest = hash_password(password)
             .map_err(MastodonError::from_internal)?;
         Some(password_digest)
@@ -276,6 +313,7 @@ pub async fn create_account(
         ed25519_secret_key: ed25519_secret_key,
         invite_code: maybe_invite_code,
         role,
+        rules_accepted_at: maybe_rules_accepted_at,
     };
     let user = match create_user(db_client, user_data).await {
         Ok(user) => user,
@@ -286,6 +324,13 @@ pub async fn create_account(
     create_or_update_local_actor(&config, db_client, &user).await?;
     create_signup_notifications(db_client, user.id).await?;
     log::warn!("created user {}", user);
+    let maybe_recovery_codes = if user.password_digest.is_some() {
+        // Codes can be used to reset password
+        let recovery_codes = create_recovery_codes(db_client, user.id).await?;
+        Some(recovery_codes)
+    } else {
+        None
+    };
     let base_url = get_request_base_url(connection_info);
     let authority = Authority::from(&instance);
     let media_server = ClientMediaServer::new(&config, &base_url);
@@ -294,7 +339,57 @@ pub async fn create_account(
         &media_server,
         user,
     );
-    Ok(HttpResponse::Created().json(account))
+    let created_account = CreatedAccount {
+        account,
+        recovery_codes: maybe_recovery_codes,
+    };
+    Ok(HttpResponse::Created().json(created_account))
+}
+
+async fn reset_password_view(
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request_data: JsonOrForm<PasswordResetRequest>,
+) -> Result<HttpResponse, MastodonError> {
+    let request_data = request_data.into_inner();
+    check_password(&config.password_policy, &request_data.new_password).await?;
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let user = match get_user_by_name(db_client, &request_data.username).await {
+        Ok(user) => user,
+        Err(DatabaseError::NotFound(_)) => {
+            log::warn!(
+                "password reset failed: user {} doesn't exist",
+                request_data.username,
+            );
+            return Err(ValidationError("invalid recovery code").into());
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
+    let transaction = db_client.transaction().await
+        .map_err(DatabaseError::from)?;
+    match use_recovery_code(
+        &transaction,
+        user.id,
+        &request_data.recovery_code,
+    ).await {
+        Ok(()) => (),
+        Err(DatabaseError::NotFound(_)) => {
+            log::warn!(
+                "password reset failed: invalid recovery code for {}",
+                user,
+            );
+            return Err(ValidationError("invalid recovery code").into());
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
+    let password_digest = hash_password(&request_data.new_password)
+        .map_err(MastodonError::from_internal)?;
+    set_user_password(&transaction, user.id, &password_digest).await?;
+    // Terminate all sessions
+    delete_oauth_tokens(&transaction, user.id).await?;
+    transaction.commit().await.map_err(DatabaseError::from)?;
+    log::warn!("password reset using recovery code: {}", user);
+    Ok(HttpResponse::NoContent().finish())
 }
 
 // https://docs.joinmastodon.org/methods/accounts/#verify_credentials
@@ -306,15 +401,25 @@ async fn verify_credentials(
     db_pool: web::Data<DatabaseConnectionPool>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let user = get_current_user(db_client, auth.token()).await?;
+    let user = get_current_user(db_client, &auth).await?;
     let base_url = get_request_base_url(connection_info);
     let authority = Authority::from(&config.instance());
     let media_server = ClientMediaServer::new(&config, &base_url);
-    let account = Account::from_user(
+    let maybe_moved_to = user.moved_to.clone();
+    let mut account = Account::from_user(
         &authority,
         &media_server,
         user,
     );
+    if let Some(moved_to) = maybe_moved_to {
+        add_moved_account(
+            db_client,
+            &authority,
+            &media_server,
+            &mut account,
+            &moved_to,
+        ).await?;
+    };
     Ok(HttpResponse::Ok().json(account))
 }
 
@@ -331,7 +436,7 @@ async fn update_credentials(
     >,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let mut current_user = get_current_user(db_client, auth.token()).await?;
+    let mut current_user = get_current_user(db_client, &auth).await?;
     let account_form = match account_form {
         Either::Left(form) => form.into_inner().into(),
         Either::Right(json) => json.into_inner(),
@@ -343,11 +448,29 @@ async fn update_credentials(
     } else {
         None
     };
+    let maybe_hidden_collections = account_form.source.as_ref()
+        .filter(|source| {
+            source.hide_followers.is_some() || source.hide_following.is_some()
+        })
+        .map(|source| (
+            source.hide_followers
+                .unwrap_or(current_user.profile.hide_followers),
+            source.hide_following
+                .unwrap_or(current_user.profile.hide_following),
+        ));
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let maybe_storage_quota = get_storage_quota(
+        db_client,
+        &dynamic_config.role_limits,
+        &current_user,
+    ).await?;
     let media_storage = MediaStorage::new(&config);
+    let limits = config.limits();
     let mut profile_data = account_form.into_profile_data(
         &current_user.profile,
-        &config.limits.media,
+        &limits.media,
         &media_storage,
+        maybe_storage_quota,
     )?;
     let profile_text = parse_microsyntaxes(
         db_client,
@@ -358,13 +481,26 @@ async fn update_credentials(
     profile_data.bio = profile_text.bio;
     profile_data.emojis = profile_text.emojis;
     clean_profile_update_data(&mut profile_data)?;
+    check_local_profile_limits(
+        &limits.profiles,
+        profile_data.bio_source.as_deref().or(profile_data.bio.as_deref()),
+        &profile_data.extra_fields,
+    )?;
 
     // Update profile
-    let (updated_profile, deletion_queue) = update_profile(
+    let (mut updated_profile, deletion_queue) = update_profile(
         db_client,
         current_user.id,
         profile_data,
     ).await?;
+    if let Some((hide_followers, hide_following)) = maybe_hidden_collections {
+        updated_profile = set_hidden_collections(
+            db_client,
+            current_user.id,
+            hide_followers,
+            hide_following,
+        ).await?;
+    };
     current_user.profile = updated_profile;
     create_or_update_local_actor(&config, db_client, &current_user).await?;
     // Delete orphaned images after update
@@ -399,6 +535,8 @@ async fn update_credentials(
     Ok(HttpResponse::Ok().json(account))
 }
 
+/// Returns identity claim (signing challenge) for the given signer.
+/// The signed claim should be submitted to `/identity_proof` endpoint.
 #[get("/identity_claim")]
 async fn get_identity_claim(
     auth: BearerAuth,
@@ -407,7 +545,7 @@ async fn get_identity_claim(
     query_params: web::Query<IdentityClaimQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let (did, proof_type) = match query_params.proof_type.as_str() {
         "ethereum" => {
             let did_pkh = DidPkh::from_ethereum_address(&query_params.signer);
@@ -440,6 +578,8 @@ async fn get_identity_claim(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Adds identity proof to the profile
+/// or refreshes the existing proof if it has the same issuer.
 #[post("/identity_proof")]
 async fn create_identity_proof(
     auth: BearerAuth,
@@ -449,7 +589,7 @@ async fn create_identity_proof(
     proof_form: web::Json<IdentityProofForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let mut current_user = get_current_user(db_client, auth.token()).await?;
+    let mut current_user = get_current_user(db_client, &auth).await?;
     let proof_type = match proof_form.proof_type.as_str() {
         // MitraJcsEip191Signature2022
         "ethereum" => IdentityProofType::FepC390JcsEip191Proof,
@@ -556,13 +696,14 @@ async fn create_identity_proof(
         },
     };
 
-    let proof = create_identity_proof_fep_c390(
+    let mut proof = create_identity_proof_fep_c390(
         &actor_id,
         &did,
         &proof_type,
         proof_form.created_at,
         &signature_bin,
     );
+    proof.verified_at = Some(Utc::now());
     let mut profile_data = ProfileUpdateData::from(&current_user.profile);
     profile_data.add_identity_proof(proof);
     validate_identity_proofs(&profile_data.identity_proofs)?;
@@ -595,6 +736,65 @@ async fn create_identity_proof(
     Ok(HttpResponse::Ok().json(account))
 }
 
+/// Verifies existing identity proof again and updates its verification time
+#[post("/identity_proof/refresh")]
+async fn refresh_identity_proof(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    proof_form: web::Json<IdentityProofRefreshForm>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let mut current_user = get_current_user(db_client, &auth).await?;
+    let proof = current_user.profile.identity_proofs.inner().iter()
+        .find(|proof| proof.issuer == proof_form.did)
+        .ok_or(MastodonError::NotFound("identity proof"))?;
+    let actor_id = local_actor_id(
+        config.instance().uri_str(),
+        &current_user.profile.username,
+    );
+    let verified_proof = verify_identity_proof(&actor_id, proof)?;
+    let mut profile_data = ProfileUpdateData::from(&current_user.profile);
+    profile_data.add_identity_proof(verified_proof);
+    // Only identity proofs are updated, media cleanup is not needed
+    let (updated_profile, _) = update_profile(
+        db_client,
+        current_user.id,
+        profile_data,
+    ).await?;
+    current_user.profile = updated_profile;
+
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let account = Account::from_user(
+        &authority,
+        &media_server,
+        current_user,
+    );
+    Ok(HttpResponse::Ok().json(account))
+}
+
+/// Returns identity proofs created by the current user,
+/// including revoked ones.
+#[get("/identity_proof/history")]
+async fn get_identity_proof_history_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let history: Vec<_> = get_identity_proof_history(
+        db_client,
+        current_user.id,
+    ).await?
+        .into_iter()
+        .map(IdentityProofHistoryEntry::from_db)
+        .collect();
+    Ok(HttpResponse::Ok().json(history))
+}
+
 #[delete("/identity_proof")]
 async fn delete_identity_proof(
     auth: BearerAuth,
@@ -604,7 +804,7 @@ async fn delete_identity_proof(
     proof_form: web::Json<IdentityProofDeleteForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let mut current_user = get_current_user(db_client, auth.token()).await?;
+    let mut current_user = get_current_user(db_client, &auth).await?;
     let mut profile_data = ProfileUpdateData::from(&current_user.profile);
     profile_data.remove_identity_proof(&proof_form.did);
     validate_identity_proofs(&profile_data.identity_proofs)?;
@@ -644,7 +844,7 @@ async fn get_relationships_view(
     query_params: MultiQuery<RelationshipQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let relationships = get_relationships(
         db_client,
         current_user.id,
@@ -697,7 +897,7 @@ async fn search_by_acct(
         Some(auth) => {
             get_current_user(
                 db_client_await!(&db_pool),
-                auth.token(),
+                &auth,
             ).await?;
         },
         None => {
@@ -786,14 +986,28 @@ async fn get_account(
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let profile = get_profile_by_id(db_client, *account_id).await?;
+    let maybe_moved_to = if profile.is_local() {
+        get_user_by_id(db_client, profile.id).await?.moved_to
+    } else {
+        None
+    };
     let base_url = get_request_base_url(connection_info);
     let authority = Authority::from(&config.instance());
     let media_server = ClientMediaServer::new(&config, &base_url);
-    let account = Account::from_profile(
+    let mut account = Account::from_profile(
         &authority,
         &media_server,
         profile,
     );
+    if let Some(moved_to) = maybe_moved_to {
+        add_moved_account(
+            db_client,
+            &authority,
+            &media_server,
+            &mut account,
+            &moved_to,
+        ).await?;
+    };
     Ok(HttpResponse::Ok().json(account))
 }
 
@@ -810,7 +1024,7 @@ async fn follow_account(
         .map(|form| form.into_inner())
         .unwrap_or_default();
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let target = get_profile_by_id(db_client, *account_id).await?;
     if target.id == current_user.id {
         return Err(ValidationError("target is current user").into());
@@ -832,6 +1046,11 @@ async fn follow_account(
     } else {
         hide_replies(db_client, current_user.id, target.id).await?;
     };
+    if follow_form.notify {
+        enable_post_notifications(db_client, current_user.id, target.id).await?;
+    } else {
+        disable_post_notifications(db_client, current_user.id, target.id).await?;
+    };
     let relationship = get_relationship(
         db_client,
         current_user.id,
@@ -848,7 +1067,7 @@ async fn unfollow_account(
     account_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let target = get_profile_by_id(db_client, *account_id).await?;
     match unfollow(db_client, current_user.id, target.id).await {
         Ok(maybe_follow_request_deleted) => {
@@ -888,7 +1107,7 @@ async fn remove_follower_view(
     account_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let follower = get_profile_by_id(db_client, *account_id).await?;
     let maybe_follow_activity_id = match remove_follower(
         db_client,
@@ -925,7 +1144,7 @@ async fn mute_account(
     account_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let target = get_profile_by_id(db_client, *account_id).await?;
     if target.id == current_user.id {
         return Err(ValidationError("target is current user").into());
@@ -952,7 +1171,7 @@ async fn unmute_account(
     account_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let target = get_profile_by_id(db_client, *account_id).await?;
 
     match unmute(db_client, current_user.id, target.id).await {
@@ -973,15 +1192,15 @@ async fn unmute_account(
 async fn get_account_statuses(
     auth: Option<BearerAuth>,
     config: web::Data<Config>,
-    connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     request_uri: Uri,
     account_id: web::Path<Uuid>,
     query_params: web::Query<StatusListQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let maybe_current_user = match auth {
-        Some(auth) => Some(get_current_user(db_client, auth.token()).await?),
+        Some(auth) => Some(get_current_user(db_client, &auth).await?),
         None => None,
     };
     let profile = get_profile_by_id(db_client, *account_id).await?;
@@ -997,7 +1216,7 @@ async fn get_account_statuses(
         query_params.max_id,
         query_params.limit.inner(),
     ).await?;
-    let base_url = get_request_base_url(connection_info);
+    let base_url = get_request_base_url(request.connection_info().clone());
     let authority = Authority::from(&config.instance());
     let media_server = ClientMediaServer::new(&config, &base_url);
     let response = get_paginated_status_list(
@@ -1009,6 +1228,7 @@ async fn get_account_statuses(
         maybe_current_user.as_ref(),
         posts,
         &query_params.limit,
+        &get_language_preferences(&request),
     ).await?;
     Ok(response)
 }
@@ -1024,7 +1244,7 @@ async fn get_account_followers(
     query_params: web::Query<FollowListQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let profile = get_profile_by_id(db_client, *account_id).await?;
     if profile.id != current_user.id {
         // Social graph is hidden
@@ -1069,7 +1289,7 @@ async fn get_account_following(
     query_params: web::Query<FollowListQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let profile = get_profile_by_id(db_client, *account_id).await?;
     if profile.id != current_user.id {
         // Social graph is hidden
@@ -1114,7 +1334,7 @@ async fn get_account_subscribers(
     query_params: web::Query<SubscriptionListQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let profile = get_profile_by_id(db_client, *account_id).await?;
     if profile.id != current_user.id {
         // Social graph is hidden
@@ -1158,7 +1378,7 @@ async fn get_account_lists(
     account_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let feeds = get_custom_feeds_by_source(
         db_client,
         current_user.id,
@@ -1168,6 +1388,54 @@ async fn get_account_lists(
     Ok(HttpResponse::Ok().json(lists))
 }
 
+const ACCOUNT_ACTIVITY_WEEKS: u16 = 52;
+
+#[get("/{account_id}/activity")]
+async fn get_account_activity(
+    app_state: web::Data<AppState>,
+    auth: Option<BearerAuth>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    account_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let maybe_current_user = match auth {
+        Some(auth) => Some(get_current_user(db_client, &auth).await?),
+        None => None,
+    };
+    let profile = get_profile_by_id(db_client, *account_id).await?;
+    // Only public posts are counted, unless user requests
+    // statistics for their own account
+    let only_public = maybe_current_user.as_ref()
+        .is_none_or(|user| user.id != profile.id);
+    let cache_key = format!("{}:{}", profile.id, only_public);
+    let maybe_activity_json = app_state.account_activity_cache.lock().await
+        .get(&cache_key)
+        .map(|activity_json| activity_json.to_owned());
+    if let Some(activity_json) = maybe_activity_json {
+        let response = HttpResponse::Ok()
+            .content_type("application/json")
+            .body(activity_json);
+        return Ok(response);
+    };
+    let activity: Vec<AccountActivity> = get_post_activity(
+        db_client,
+        profile.id,
+        only_public,
+        ACCOUNT_ACTIVITY_WEEKS,
+    ).await?
+        .into_iter()
+        .map(AccountActivity::from)
+        .collect();
+    let activity_json = serde_json::to_string(&activity)
+        .map_err(MastodonError::from_internal)?;
+    app_state.account_activity_cache.lock().await
+        .set(cache_key, activity_json.clone());
+    let response = HttpResponse::Ok()
+        .content_type("application/json")
+        .body(activity_json);
+    Ok(response)
+}
+
 #[get("/{account_id}/aliases/all")]
 async fn get_account_aliases(
     config: web::Data<Config>,
@@ -1194,9 +1462,18 @@ async fn load_activities(
     db_pool: web::Data<DatabaseConnectionPool>,
     account_id: web::Path<Uuid>,
     request_data: web::Json<LoadActivitiesRequest>,
+    governor_result: GovernorExtractor,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let _current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if !current_user.has_feature(FeatureFlag::HigherRateLimits) {
+        if let Some(wait) = governor_result.0.check()
+            .map_err(MastodonError::from_internal)?
+            .map(Duration::from_millis)
+        {
+            return Err(MastodonError::RateLimit(wait));
+        };
+    };
     let profile = get_profile_by_id(db_client, *account_id).await?;
     let Some(remote_actor) = profile.actor_json.as_ref() else {
         // Local profile
@@ -1221,6 +1498,9 @@ pub fn account_api_scope(
     let create_account_limited = web::resource("")
         .post(create_account)
         .wrap(Governor::new(&ratelimit_configs.registration));
+    let reset_password_limited = web::resource("/reset_password")
+        .post(reset_password_view)
+        .wrap(Governor::new(&ratelimit_configs.login));
     let load_activities_limited = web
         ::resource("/{account_id}/load_activities")
         .post(load_activities)
@@ -1228,11 +1508,14 @@ pub fn account_api_scope(
     web::scope("/v1/accounts")
         // Routes without account ID
         .service(create_account_limited)
+        .service(reset_password_limited)
         .service(verify_credentials)
         .service(update_credentials)
         .service(get_identity_claim)
         .service(create_identity_proof)
+        .service(refresh_identity_proof)
         .service(delete_identity_proof)
+        .service(get_identity_proof_history_view)
         .service(get_relationships_view)
         .service(lookup_acct)
         .service(search_by_acct_limited)
@@ -1250,6 +1533,7 @@ pub fn account_api_scope(
         .service(get_account_following)
         .service(get_account_subscribers)
         .service(get_account_lists)
+        .service(get_account_activity)
         .service(get_account_aliases)
         .service(load_activities_limited)
 }
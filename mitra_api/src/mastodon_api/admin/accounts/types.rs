@@ -0,0 +1,14 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct ProfileMergeData {
+    pub primary_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct AccountRenameData {
+    pub username: String,
+    #[serde(default)]
+    pub regenerate_keys: bool,
+}
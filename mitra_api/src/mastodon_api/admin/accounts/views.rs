@@ -1,30 +1,63 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
 use actix_web::{
     delete,
+    dev::ConnectionInfo,
+    patch,
+    post,
     web,
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
+use apx_core::crypto::{
+    eddsa::generate_ed25519_key,
+    rsa::{generate_rsa_key, rsa_secret_key_to_pkcs8_pem},
+};
 use uuid::Uuid;
 
 use mitra_activitypub::{
-    adapters::users::delete_account,
+    adapters::users::{delete_account, rename_account},
+    authority::Authority,
 };
+use mitra_adapters::dynamic_config::get_dynamic_config;
 use mitra_config::Config;
 use mitra_models::{
     accounts::{
-        queries::get_managed_account_by_id,
-        types::Permission,
+        queries::{
+            get_managed_account_by_id,
+            get_user_by_id,
+            set_user_feature_flag,
+            set_user_keys,
+            set_user_storage_quota,
+        },
+        types::{FeatureFlag, Permission},
     },
-    database::{get_database_client, DatabaseConnectionPool},
-    profiles::queries::{delete_profile, get_profile_by_id},
+    attachments::queries::get_storage_usage,
+    database::{get_database_client, DatabaseConnectionPool, DatabaseError},
+    profiles::queries::{
+        delete_profile,
+        get_profile_by_id,
+        link_profiles,
+        unlink_profile,
+    },
+};
+use mitra_validators::{
+    accounts::validate_local_username,
+    errors::ValidationError,
 };
 
+use crate::http::get_request_base_url;
 use crate::mastodon_api::{
-    auth::get_current_user,
+    accounts::types::Account,
+    admin::storage_usage::types::{StorageQuotaData, StorageUsage},
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
+    media_server::ClientMediaServer,
 };
 
+use super::types::{AccountRenameData, ProfileMergeData};
+
 // https://docs.joinmastodon.org/methods/admin/accounts/#delete
 #[delete("/{account_id}")]
 async fn delete_account_view(
@@ -34,7 +67,7 @@ async fn delete_account_view(
     account_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     if !current_user.role.has_permission(Permission::DeleteAnyProfile) {
         return Err(MastodonError::PermissionError);
     };
@@ -56,7 +89,174 @@ async fn delete_account_view(
     Ok(HttpResponse::NoContent().json(empty))
 }
 
+// Keys are feature flag names, values are their statuses
+#[patch("/{account_id}/feature_flags")]
+async fn update_feature_flags_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    account_id: web::Path<Uuid>,
+    feature_flags: web::Json<HashMap<String, bool>>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if !current_user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    let mut updates = vec![];
+    for (name, is_enabled) in feature_flags.into_inner() {
+        let feature_flag = FeatureFlag::from_str(&name)
+            .map_err(|_| ValidationError("unknown feature flag"))?;
+        updates.push((feature_flag, is_enabled));
+    };
+    let user = get_user_by_id(db_client, *account_id).await?;
+    for (feature_flag, is_enabled) in updates {
+        set_user_feature_flag(
+            db_client,
+            user.id,
+            feature_flag,
+            is_enabled,
+        ).await?;
+    };
+    let user = get_user_by_id(db_client, user.id).await?;
+    let feature_flags: Vec<_> = user.feature_flags.iter()
+        .map(|feature_flag| feature_flag.as_str())
+        .collect();
+    Ok(HttpResponse::Ok().json(feature_flags))
+}
+
+#[patch("/{account_id}/storage_quota")]
+async fn update_storage_quota_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    account_id: web::Path<Uuid>,
+    quota_data: web::Json<StorageQuotaData>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if !current_user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    let user = get_user_by_id(db_client, *account_id).await?;
+    set_user_storage_quota(
+        db_client,
+        user.id,
+        quota_data.storage_quota,
+    ).await?;
+    let user = get_user_by_id(db_client, user.id).await?;
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let storage_usage = get_storage_usage(db_client, user.id).await?;
+    let storage_usage = StorageUsage::new(
+        &dynamic_config.role_limits,
+        &user,
+        storage_usage,
+    );
+    Ok(HttpResponse::Ok().json(storage_usage))
+}
+
+// Marks profile as an alternate profile of the same person
+#[post("/{account_id}/merge")]
+async fn merge_account_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    account_id: web::Path<Uuid>,
+    merge_data: web::Json<ProfileMergeData>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if !current_user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    let profile = get_profile_by_id(db_client, *account_id).await?;
+    let primary = get_profile_by_id(db_client, merge_data.primary_id).await?;
+    if profile.id == primary.id {
+        return Err(ValidationError("profile can't be merged with itself").into());
+    };
+    let is_linked = link_profiles(
+        db_client,
+        profile.id,
+        primary.id,
+        true, // manual
+    ).await?;
+    if !is_linked {
+        return Err(MastodonError::OperationError("profiles are already merged"));
+    };
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/{account_id}/unmerge")]
+async fn unmerge_account_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    account_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if !current_user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    let profile = get_profile_by_id(db_client, *account_id).await?;
+    unlink_profile(db_client, profile.id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/{account_id}/rename")]
+async fn rename_account_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    account_id: web::Path<Uuid>,
+    rename_data: web::Json<AccountRenameData>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if !current_user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    validate_local_username(&rename_data.username)?;
+    let mut user = get_user_by_id(db_client, *account_id).await?;
+    if rename_data.regenerate_keys {
+        let rsa_secret_key = match web::block(generate_rsa_key).await {
+            Ok(Ok(secret_key)) => secret_key,
+            Ok(Err(error)) => return Err(MastodonError::from_internal(error)),
+            Err(error) => return Err(MastodonError::from_internal(error)),
+        };
+        let rsa_secret_key_pem = rsa_secret_key_to_pkcs8_pem(&rsa_secret_key)
+            .map_err(MastodonError::from_internal)?;
+        let ed25519_secret_key = generate_ed25519_key();
+        set_user_keys(
+            db_client,
+            user.id,
+            &rsa_secret_key_pem,
+            &ed25519_secret_key,
+        ).await?;
+        user = get_user_by_id(db_client, user.id).await?;
+    };
+    let user = match rename_account(
+        &config,
+        db_client,
+        &user,
+        &rename_data.username,
+    ).await {
+        Ok(user) => user,
+        Err(DatabaseError::AlreadyExists(_)) =>
+            return Err(ValidationError("username is not available").into()),
+        Err(other_error) => return Err(other_error.into()),
+    };
+    log::warn!("renamed user {}", user);
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let account = Account::from_user(&authority, &media_server, user);
+    Ok(HttpResponse::Ok().json(account))
+}
+
 pub fn admin_account_api_scope() -> Scope {
     web::scope("/v1/admin/accounts")
         .service(delete_account_view)
+        .service(update_feature_flags_view)
+        .service(update_storage_quota_view)
+        .service(merge_account_view)
+        .service(unmerge_account_view)
+        .service(rename_account_view)
 }
@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use mitra_models::announcements::types::AnnouncementData;
+
+#[derive(Deserialize)]
+pub struct AnnouncementForm {
+    content: String,
+    published_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl AnnouncementForm {
+    pub fn into_announcement_data(self) -> AnnouncementData {
+        AnnouncementData {
+            content: self.content,
+            published_at: self.published_at.unwrap_or(Utc::now()),
+            expires_at: self.expires_at,
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use actix_web::{
+    delete,
+    get,
+    post,
+    put,
+    web,
+    HttpResponse,
+    Scope,
+};
+
+use mitra_models::{
+    accounts::types::{Permission, User},
+    announcements::queries::{
+        create_announcement,
+        delete_announcement,
+        get_announcements,
+        update_announcement,
+    },
+    database::{get_database_client, DatabaseConnectionPool},
+};
+use mitra_validators::announcements::validate_announcement_data;
+
+use crate::mastodon_api::{
+    announcements::types::Announcement,
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+};
+
+use super::types::AnnouncementForm;
+
+fn check_permission(user: &User) -> Result<(), MastodonError> {
+    if !user.role.has_permission(Permission::ManageAnnouncements) {
+        return Err(MastodonError::PermissionError);
+    };
+    Ok(())
+}
+
+#[get("")]
+async fn announcement_list_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let announcements: Vec<_> = get_announcements(db_client).await?
+        .into_iter()
+        .map(Announcement::from_db)
+        .collect();
+    Ok(HttpResponse::Ok().json(announcements))
+}
+
+#[post("")]
+async fn create_announcement_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    announcement_form: web::Json<AnnouncementForm>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let announcement_data = announcement_form.into_inner()
+        .into_announcement_data();
+    validate_announcement_data(&announcement_data)?;
+    let db_announcement =
+        create_announcement(db_client, announcement_data).await?;
+    let announcement = Announcement::from_db(db_announcement);
+    Ok(HttpResponse::Ok().json(announcement))
+}
+
+#[put("/{announcement_id}")]
+async fn update_announcement_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    announcement_id: web::Path<i32>,
+    announcement_form: web::Json<AnnouncementForm>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let announcement_data = announcement_form.into_inner()
+        .into_announcement_data();
+    validate_announcement_data(&announcement_data)?;
+    let db_announcement = update_announcement(
+        db_client,
+        *announcement_id,
+        announcement_data,
+    ).await?;
+    let announcement = Announcement::from_db(db_announcement);
+    Ok(HttpResponse::Ok().json(announcement))
+}
+
+#[delete("/{announcement_id}")]
+async fn delete_announcement_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    announcement_id: web::Path<i32>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    delete_announcement(db_client, *announcement_id).await?;
+    let empty = serde_json::json!({});
+    Ok(HttpResponse::NoContent().json(empty))
+}
+
+pub fn admin_announcement_api_scope() -> Scope {
+    web::scope("/v1/admin/announcements")
+        .service(announcement_list_view)
+        .service(create_announcement_view)
+        .service(update_announcement_view)
+        .service(delete_announcement_view)
+}
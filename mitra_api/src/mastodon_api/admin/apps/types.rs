@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use mitra_models::oauth::types::OauthAppAdminInfo;
+
+// Similar to Application entity, but without client credentials
+#[derive(Serialize)]
+pub struct AdminApp {
+    id: String,
+    name: String,
+    website: Option<String>,
+    scopes: Vec<String>,
+    redirect_uri: String,
+    created_at: DateTime<Utc>,
+    is_approved: bool,
+    token_count: i64,
+}
+
+impl From<OauthAppAdminInfo> for AdminApp {
+    fn from(app_info: OauthAppAdminInfo) -> Self {
+        let db_app = app_info.app;
+        Self {
+            id: db_app.id.to_string(),
+            name: db_app.app_name,
+            website: db_app.website,
+            scopes: db_app.scopes,
+            redirect_uri: db_app.redirect_uri,
+            created_at: db_app.created_at,
+            is_approved: db_app.is_approved,
+            token_count: app_info.token_count,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use actix_web::{
+    get,
+    post,
+    web,
+    HttpResponse,
+    Scope,
+};
+
+use mitra_models::{
+    accounts::types::{Permission, User},
+    database::{get_database_client, DatabaseConnectionPool},
+    oauth::queries::{
+        delete_oauth_tokens_by_app,
+        get_oauth_app_admin_info,
+        get_oauth_app_by_id,
+        get_oauth_apps,
+        set_oauth_app_approved,
+    },
+};
+
+use crate::mastodon_api::{
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+};
+
+use super::types::AdminApp;
+
+fn check_permission(user: &User) -> Result<(), MastodonError> {
+    if !user.role.has_permission(Permission::ManageApplications) {
+        return Err(MastodonError::PermissionError);
+    };
+    Ok(())
+}
+
+#[get("")]
+async fn app_list_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let apps: Vec<AdminApp> = get_oauth_apps(db_client).await?
+        .into_iter()
+        .map(AdminApp::from)
+        .collect();
+    Ok(HttpResponse::Ok().json(apps))
+}
+
+#[post("/{app_id}/approve")]
+async fn approve_app_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    app_id: web::Path<i32>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let db_app = set_oauth_app_approved(db_client, *app_id, true).await?;
+    log::warn!("application {} approved", db_app.app_name);
+    let app_info = get_oauth_app_admin_info(db_client, db_app.id).await?;
+    let app = AdminApp::from(app_info);
+    Ok(HttpResponse::Ok().json(app))
+}
+
+#[post("/{app_id}/reject")]
+async fn reject_app_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    app_id: web::Path<i32>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let db_app = set_oauth_app_approved(db_client, *app_id, false).await?;
+    // Tokens can't be used after approval is revoked
+    delete_oauth_tokens_by_app(db_client, db_app.id).await?;
+    log::warn!("application {} rejected", db_app.app_name);
+    let app_info = get_oauth_app_admin_info(db_client, db_app.id).await?;
+    let app = AdminApp::from(app_info);
+    Ok(HttpResponse::Ok().json(app))
+}
+
+#[post("/{app_id}/revoke_tokens")]
+async fn revoke_app_tokens_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    app_id: web::Path<i32>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let db_app = get_oauth_app_by_id(db_client, *app_id).await?;
+    let deleted_count =
+        delete_oauth_tokens_by_app(db_client, db_app.id).await?;
+    log::warn!(
+        "revoked {} tokens of application {}",
+        deleted_count,
+        db_app.app_name,
+    );
+    let app_info = get_oauth_app_admin_info(db_client, db_app.id).await?;
+    let app = AdminApp::from(app_info);
+    Ok(HttpResponse::Ok().json(app))
+}
+
+pub fn admin_app_api_scope() -> Scope {
+    web::scope("/v1/admin/apps")
+        .service(app_list_view)
+        .service(approve_app_view)
+        .service(reject_app_view)
+        .service(revoke_app_tokens_view)
+}
@@ -0,0 +1,52 @@
+use actix_web::{
+    post,
+    web,
+    HttpResponse,
+    Scope,
+};
+
+use mitra_adapters::init::reload_config;
+use mitra_config::Config;
+use mitra_models::{
+    accounts::types::{Permission, User},
+    database::{get_database_client, DatabaseConnectionPool},
+};
+
+use crate::mastodon_api::{
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+};
+
+use super::types::ConfigReloadResult;
+
+fn check_permission(user: &User) -> Result<(), MastodonError> {
+    if !user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    Ok(())
+}
+
+/// Same as sending SIGHUP to the server process
+#[post("/reload")]
+async fn reload_config_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let warnings = reload_config(&config)
+        .map_err(|error| {
+            log::error!("failed to reload configuration: {error}");
+            MastodonError::ValidationError(error.to_string())
+        })?;
+    log::info!("configuration reloaded by {}", current_user.profile.username);
+    let result = ConfigReloadResult { warnings };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+pub fn admin_config_api_scope() -> Scope {
+    web::scope("/v1/admin/config")
+        .service(reload_config_view)
+}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use mitra_models::emojis::types::{
+    CustomEmoji as DbCustomEmoji,
+    EmojiUsage as DbEmojiUsage,
+};
+
+use crate::mastodon_api::{
+    media_server::ClientMediaServer,
+    pagination::PageSize,
+};
+
+fn default_page_size() -> PageSize { PageSize::new(40) }
+
+#[derive(Deserialize)]
+pub struct EmojiQueryParams {
+    pub q: Option<String>,
+
+    pub local: Option<bool>,
+
+    #[serde(default)]
+    pub offset: u16,
+
+    #[serde(default = "default_page_size")]
+    pub limit: PageSize,
+}
+
+#[derive(Serialize)]
+pub struct EmojiUsage {
+    posts: i64,
+    reactions: i64,
+    profiles: i64,
+}
+
+// Similar to CustomEmoji entity, but includes usage statistics
+#[derive(Serialize)]
+pub struct AdminCustomEmoji {
+    id: Uuid,
+    shortcode: String,
+    domain: Option<String>,
+    url: String,
+    category: Option<String>,
+    updated_at: DateTime<Utc>,
+    usage: EmojiUsage,
+}
+
+impl AdminCustomEmoji {
+    pub fn from_db(
+        media_server: &ClientMediaServer,
+        db_emoji: DbCustomEmoji,
+        db_usage: DbEmojiUsage,
+    ) -> Self {
+        Self {
+            id: db_emoji.id,
+            domain: db_emoji.hostname().map(|hostname| hostname.to_owned()),
+            url: media_server.url_for(&db_emoji.image),
+            shortcode: db_emoji.emoji_name,
+            category: db_emoji.category,
+            updated_at: db_emoji.updated_at,
+            usage: EmojiUsage {
+                posts: db_usage.posts,
+                reactions: db_usage.reactions,
+                profiles: db_usage.profiles,
+            },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PruneEmojisData {
+    pub days: u32,
+}
+
+#[derive(Serialize)]
+pub struct PruneEmojisResult {
+    pub deleted_count: usize,
+}
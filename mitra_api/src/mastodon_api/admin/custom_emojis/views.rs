@@ -0,0 +1,104 @@
+use actix_web::{
+    dev::ConnectionInfo,
+    get,
+    post,
+    web,
+    HttpResponse,
+    Scope,
+};
+
+use mitra_config::Config;
+use mitra_models::{
+    accounts::types::{Permission, User},
+    database::{get_database_client, DatabaseConnectionPool},
+    emojis::queries::{
+        delete_emoji,
+        find_unused_remote_emojis,
+        search_emojis,
+    },
+    profiles::types::Origin,
+};
+use mitra_utils::datetime::days_before_now;
+
+use crate::http::get_request_base_url;
+use crate::mastodon_api::{
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+    media_server::ClientMediaServer,
+};
+
+use super::types::{
+    AdminCustomEmoji,
+    EmojiQueryParams,
+    PruneEmojisData,
+    PruneEmojisResult,
+};
+
+fn check_permission(user: &User) -> Result<(), MastodonError> {
+    if !user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    Ok(())
+}
+
+#[get("")]
+async fn emoji_list_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    query_params: web::Query<EmojiQueryParams>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let maybe_origin = query_params.local
+        .map(|is_local| if is_local { Origin::Local } else { Origin::Remote });
+    let base_url = get_request_base_url(connection_info);
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let emojis: Vec<AdminCustomEmoji> = search_emojis(
+        db_client,
+        query_params.q.as_deref(),
+        maybe_origin,
+        query_params.limit.inner(),
+        query_params.offset,
+    )
+        .await?
+        .into_iter()
+        .map(|(db_emoji, db_usage)| {
+            AdminCustomEmoji::from_db(&media_server, db_emoji, db_usage)
+        })
+        .collect();
+    Ok(HttpResponse::Ok().json(emojis))
+}
+
+/// Deletes remote emojis that are not used
+/// and haven't been updated for the given number of days
+#[post("/prune")]
+async fn prune_emojis_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    prune_data: web::Json<PruneEmojisData>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let updated_before = days_before_now(prune_data.days);
+    let emojis = find_unused_remote_emojis(
+        db_client,
+        Some(updated_before),
+    ).await?;
+    let deleted_count = emojis.len();
+    for emoji_id in emojis {
+        let deletion_queue = delete_emoji(db_client, emoji_id).await?;
+        deletion_queue.into_job(db_client).await?;
+    };
+    let result = PruneEmojisResult { deleted_count };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+pub fn admin_custom_emoji_api_scope() -> Scope {
+    web::scope("/v1/admin/custom_emojis")
+        .service(emoji_list_view)
+        .service(prune_emojis_view)
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use mitra_models::background_jobs::types::DeadLetter as DbDeadLetter;
+
+use crate::mastodon_api::pagination::PageSize;
+
+fn default_page_size() -> PageSize { PageSize::new(40) }
+
+#[derive(Deserialize)]
+pub struct DeadLetterQueryParams {
+    #[serde(default)]
+    pub offset: u16,
+
+    #[serde(default = "default_page_size")]
+    pub limit: PageSize,
+}
+
+#[derive(Serialize)]
+pub struct DeadLetter {
+    id: Uuid,
+    activity_id: String,
+    inbox: String,
+    errors: Vec<String>,
+    first_failed_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<DbDeadLetter> for DeadLetter {
+    fn from(db_dead_letter: DbDeadLetter) -> Self {
+        Self {
+            id: db_dead_letter.id,
+            activity_id: db_dead_letter.activity_id,
+            inbox: db_dead_letter.inbox,
+            errors: db_dead_letter.errors,
+            first_failed_at: db_dead_letter.first_failed_at,
+            created_at: db_dead_letter.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PurgeDeadLettersData {
+    pub days: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct PurgeDeadLettersResult {
+    pub deleted_count: u64,
+}
@@ -0,0 +1,97 @@
+use actix_web::{
+    get,
+    post,
+    web,
+    HttpResponse,
+    Scope,
+};
+use uuid::Uuid;
+
+use mitra_activitypub::queues::requeue_dead_letter;
+use mitra_models::{
+    accounts::types::{Permission, User},
+    background_jobs::queries::{
+        delete_dead_letters,
+        get_dead_letters,
+    },
+    database::{get_database_client, DatabaseConnectionPool},
+};
+use mitra_utils::datetime::days_before_now;
+
+use crate::mastodon_api::{
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+};
+
+use super::types::{
+    DeadLetter,
+    DeadLetterQueryParams,
+    PurgeDeadLettersData,
+    PurgeDeadLettersResult,
+};
+
+fn check_permission(user: &User) -> Result<(), MastodonError> {
+    if !user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    Ok(())
+}
+
+#[get("")]
+async fn dead_letter_list_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    query_params: web::Query<DeadLetterQueryParams>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let dead_letters: Vec<DeadLetter> = get_dead_letters(
+        db_client,
+        query_params.limit.inner(),
+        query_params.offset,
+    )
+        .await?
+        .into_iter()
+        .map(DeadLetter::from)
+        .collect();
+    Ok(HttpResponse::Ok().json(dead_letters))
+}
+
+#[post("/{dead_letter_id}/requeue")]
+async fn requeue_dead_letter_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    dead_letter_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    requeue_dead_letter(db_client, *dead_letter_id).await?;
+    let empty = serde_json::json!({});
+    Ok(HttpResponse::NoContent().json(empty))
+}
+
+/// Deletes dead letters older than the given number of days
+/// (or all dead letters if number of days is not specified)
+#[post("/purge")]
+async fn purge_dead_letters_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    purge_data: web::Json<PurgeDeadLettersData>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let created_before = purge_data.days.map(days_before_now);
+    let deleted_count = delete_dead_letters(db_client, created_before).await?;
+    let result = PurgeDeadLettersResult { deleted_count };
+    Ok(HttpResponse::Ok().json(result))
+}
+
+pub fn admin_dead_letter_api_scope() -> Scope {
+    web::scope("/v1/admin/dead_letters")
+        .service(dead_letter_list_view)
+        .service(purge_dead_letters_view)
+        .service(requeue_dead_letter_view)
+}
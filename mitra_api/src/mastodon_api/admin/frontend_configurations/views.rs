@@ -0,0 +1,128 @@
+use actix_web::{
+    get,
+    patch,
+    web,
+    HttpResponse,
+    Scope,
+};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use mitra_models::{
+    accounts::types::{Permission, User},
+    database::{get_database_client, DatabaseConnectionPool},
+    properties::{
+        constants::FRONTEND_CONFIGURATIONS,
+        queries::set_internal_property,
+    },
+};
+use mitra_validators::errors::ValidationError;
+
+use crate::mastodon_api::{
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+    frontend_configurations::views::get_frontend_configurations,
+};
+
+const FRONTEND_NAME_LENGTH_MAX: usize = 100;
+const CONFIGURATIONS_SIZE_MAX: usize = 100_000;
+
+fn check_permission(user: &User) -> Result<(), MastodonError> {
+    if !user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    Ok(())
+}
+
+fn validate_frontend_configurations(
+    configurations: &JsonMap<String, JsonValue>,
+) -> Result<(), ValidationError> {
+    for (frontend_name, configuration) in configurations {
+        if frontend_name.is_empty() ||
+            frontend_name.len() > FRONTEND_NAME_LENGTH_MAX
+        {
+            return Err(ValidationError("invalid frontend name"));
+        };
+        if !configuration.is_object() {
+            return Err(ValidationError("configuration must be an object"));
+        };
+    };
+    let size = JsonValue::from(configurations.clone()).to_string().len();
+    if size > CONFIGURATIONS_SIZE_MAX {
+        return Err(ValidationError("configuration is too large"));
+    };
+    Ok(())
+}
+
+#[get("")]
+async fn get_frontend_configurations_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let configurations = get_frontend_configurations(db_client).await?;
+    Ok(HttpResponse::Ok().json(configurations))
+}
+
+// Supports partial updates.
+// Configuration is removed if its value is null.
+#[patch("")]
+async fn update_frontend_configurations_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    configurations_data: web::Json<JsonMap<String, JsonValue>>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let mut configurations = get_frontend_configurations(db_client).await?;
+    for (frontend_name, configuration) in configurations_data.into_inner() {
+        if configuration.is_null() {
+            configurations.remove(&frontend_name);
+        } else {
+            configurations.insert(frontend_name, configuration);
+        };
+    };
+    validate_frontend_configurations(&configurations)?;
+    set_internal_property(
+        db_client,
+        FRONTEND_CONFIGURATIONS,
+        &configurations,
+    ).await?;
+    Ok(HttpResponse::Ok().json(configurations))
+}
+
+pub fn admin_frontend_configuration_api_scope() -> Scope {
+    web::scope("/v1/admin/frontend_configurations")
+        .service(get_frontend_configurations_view)
+        .service(update_frontend_configurations_view)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn test_validate_frontend_configurations() {
+        let configurations = json!({
+            "pleroma_fe": {"theme": "dark"},
+        });
+        let result = validate_frontend_configurations(
+            configurations.as_object().unwrap(),
+        );
+        assert_eq!(result.is_ok(), true);
+    }
+
+    #[test]
+    fn test_validate_frontend_configurations_not_object() {
+        let configurations = json!({
+            "pleroma_fe": "dark",
+        });
+        let result = validate_frontend_configurations(
+            configurations.as_object().unwrap(),
+        );
+        assert_eq!(result.is_ok(), false);
+    }
+}
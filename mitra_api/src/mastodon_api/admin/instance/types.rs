@@ -0,0 +1,69 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use mitra_adapters::{
+    dynamic_config::DynamicConfig,
+    roles::RoleLimitsMap,
+};
+use mitra_models::properties::constants::{
+    INSTANCE_CONTACT_ACCOUNT,
+    INSTANCE_DESCRIPTION,
+    INSTANCE_EXTENDED_DESCRIPTION,
+    INSTANCE_THUMBNAIL,
+    MAINTENANCE_MODE,
+    PORTABLE_ACCOUNTS_LIMIT,
+    ROLE_LIMITS,
+};
+
+/// Maps API parameter names to dynamic configuration parameters
+pub fn get_property_name(name: &str) -> Option<&'static str> {
+    let property_name = match name {
+        "contact_account_id" => INSTANCE_CONTACT_ACCOUNT,
+        "description" => INSTANCE_DESCRIPTION,
+        "extended_description" => INSTANCE_EXTENDED_DESCRIPTION,
+        "maintenance_mode" => MAINTENANCE_MODE,
+        "portable_accounts_limit" => PORTABLE_ACCOUNTS_LIMIT,
+        "role_limits" => ROLE_LIMITS,
+        "thumbnail" => INSTANCE_THUMBNAIL,
+        _ => return None,
+    };
+    Some(property_name)
+}
+
+/// Instance information set by administrator
+/// (null values are replaced with values from configuration file)
+#[derive(Serialize)]
+pub struct InstanceSettings {
+    contact_account_id: Option<Uuid>,
+    description: Option<String>,
+    extended_description: Option<String>,
+    maintenance_mode: bool,
+    portable_accounts_limit: Option<u32>,
+    role_limits: RoleLimitsMap,
+    thumbnail: Option<String>,
+}
+
+impl From<DynamicConfig> for InstanceSettings {
+    fn from(dynamic_config: DynamicConfig) -> Self {
+        Self {
+            contact_account_id: dynamic_config.instance_contact_account,
+            description: dynamic_config.instance_description,
+            extended_description: dynamic_config.instance_extended_description,
+            maintenance_mode: dynamic_config.maintenance_mode,
+            portable_accounts_limit: dynamic_config.portable_accounts_limit,
+            role_limits: dynamic_config.role_limits,
+            thumbnail: dynamic_config.instance_thumbnail,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_property_name() {
+        assert_eq!(get_property_name("description"), Some(INSTANCE_DESCRIPTION));
+        assert_eq!(get_property_name("like_emoji"), None);
+    }
+}
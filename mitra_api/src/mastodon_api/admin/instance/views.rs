@@ -0,0 +1,93 @@
+use actix_web::{
+    get,
+    patch,
+    web,
+    HttpResponse,
+    Scope,
+};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use uuid::Uuid;
+
+use mitra_adapters::dynamic_config::{
+    get_dynamic_config,
+    validate_editable_parameter,
+};
+use mitra_models::{
+    accounts::{
+        queries::get_user_by_id,
+        types::{Permission, User},
+    },
+    database::{get_database_client, DatabaseConnectionPool},
+    properties::{
+        constants::INSTANCE_CONTACT_ACCOUNT,
+        queries::set_internal_property,
+    },
+};
+use mitra_validators::errors::ValidationError;
+
+use crate::mastodon_api::{
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+};
+
+use super::types::{get_property_name, InstanceSettings};
+
+fn check_permission(user: &User) -> Result<(), MastodonError> {
+    if !user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    Ok(())
+}
+
+#[get("")]
+async fn get_instance_settings_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let settings = InstanceSettings::from(dynamic_config);
+    Ok(HttpResponse::Ok().json(settings))
+}
+
+// Supports partial updates
+#[patch("")]
+async fn update_instance_settings_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    settings_data: web::Json<JsonMap<String, JsonValue>>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let mut updates = vec![];
+    for (name, value) in settings_data.into_inner() {
+        let property_name = get_property_name(&name)
+            .ok_or(ValidationError("invalid parameter name"))?;
+        validate_editable_parameter(property_name, &value)?;
+        if property_name == INSTANCE_CONTACT_ACCOUNT {
+            let maybe_user_id: Option<Uuid> =
+                serde_json::from_value(value.clone())
+                    .map_err(|_| ValidationError("invalid account ID"))?;
+            if let Some(user_id) = maybe_user_id {
+                // Contact account must be local
+                get_user_by_id(db_client, user_id).await?;
+            };
+        };
+        updates.push((property_name, value));
+    };
+    for (property_name, value) in updates {
+        set_internal_property(db_client, property_name, &value).await?;
+    };
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let settings = InstanceSettings::from(dynamic_config);
+    Ok(HttpResponse::Ok().json(settings))
+}
+
+pub fn admin_instance_api_scope() -> Scope {
+    web::scope("/v1/admin/instance")
+        .service(get_instance_settings_view)
+        .service(update_instance_settings_view)
+}
@@ -1,2 +1,14 @@
 pub mod accounts;
+pub mod announcements;
+pub mod apps;
+pub mod config;
+pub mod custom_emojis;
+pub mod dead_letters;
+pub mod frontend_configurations;
+pub mod instance;
+pub mod outgoing_activities;
 pub mod posts;
+pub mod reports;
+pub mod rules;
+pub mod storage_usage;
+pub mod trends;
@@ -0,0 +1,72 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use mitra_models::activitypub::types::{
+    DeliveryStatus,
+    OutgoingActivityRecord as DbOutgoingActivityRecord,
+};
+
+use crate::mastodon_api::pagination::PageSize;
+
+fn default_page_size() -> PageSize { PageSize::new(40) }
+
+#[derive(Deserialize)]
+pub struct OutgoingActivityQueryParams {
+    pub account_id: Option<Uuid>,
+    pub activity_id: Option<String>,
+
+    #[serde(default)]
+    pub offset: u16,
+
+    #[serde(default = "default_page_size")]
+    pub limit: PageSize,
+}
+
+fn delivery_status_to_str(status: DeliveryStatus) -> &'static str {
+    match status {
+        DeliveryStatus::Pending => "pending",
+        DeliveryStatus::Delivered => "delivered",
+        DeliveryStatus::Failed => "failed",
+        DeliveryStatus::Skipped => "skipped",
+    }
+}
+
+#[derive(Serialize)]
+pub struct OutgoingActivityRecord {
+    id: String,
+    activity_id: String,
+    activity_type: String,
+    object_id: Option<String>,
+    account_id: Option<Uuid>,
+    audience: Vec<String>,
+    recipient_id: String,
+    inbox: String,
+    status: &'static str,
+    attempts: i32,
+    pub created_at: DateTime<Utc>,
+    // Set if user's timezone is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at_local: Option<DateTime<FixedOffset>>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<DbOutgoingActivityRecord> for OutgoingActivityRecord {
+    fn from(db_record: DbOutgoingActivityRecord) -> Self {
+        Self {
+            id: db_record.id.to_string(),
+            activity_id: db_record.activity_id,
+            activity_type: db_record.activity_type,
+            object_id: db_record.object_id,
+            account_id: db_record.sender_id,
+            audience: db_record.audience,
+            recipient_id: db_record.recipient_id,
+            inbox: db_record.inbox,
+            status: delivery_status_to_str(db_record.delivery_status),
+            attempts: db_record.attempt_count,
+            created_at: db_record.created_at,
+            created_at_local: None,
+            updated_at: db_record.updated_at,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use actix_web::{
+    get,
+    web,
+    HttpResponse,
+    Scope,
+};
+
+use mitra_models::{
+    accounts::types::Permission,
+    activitypub::queries::get_outgoing_activity_records,
+    database::{get_database_client, DatabaseConnectionPool},
+};
+
+use crate::mastodon_api::{
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+};
+
+use super::types::{
+    OutgoingActivityQueryParams,
+    OutgoingActivityRecord,
+};
+
+#[get("")]
+async fn outgoing_activity_list_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    query_params: web::Query<OutgoingActivityQueryParams>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if !current_user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    let records: Vec<OutgoingActivityRecord> = get_outgoing_activity_records(
+        db_client,
+        query_params.account_id,
+        query_params.activity_id.as_deref(),
+        query_params.limit.inner(),
+        query_params.offset,
+    )
+        .await?
+        .into_iter()
+        .map(OutgoingActivityRecord::from)
+        .collect();
+    Ok(HttpResponse::Ok().json(records))
+}
+
+pub fn admin_outgoing_activity_api_scope() -> Scope {
+    web::scope("/v1/admin/outgoing_activities")
+        .service(outgoing_activity_list_view)
+}
@@ -4,7 +4,6 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 use uuid::Uuid;
 
 use mitra_activitypub::{
@@ -18,7 +17,7 @@ use mitra_models::{
 };
 
 use crate::mastodon_api::{
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
 };
 
@@ -30,7 +29,7 @@ async fn delete_post_view(
     post_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     if !current_user.role.has_permission(Permission::DeleteAnyPost) {
         return Err(MastodonError::PermissionError);
     };
@@ -0,0 +1,2 @@
+mod types;
+pub mod views;
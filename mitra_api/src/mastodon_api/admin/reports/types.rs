@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use mitra_models::reports::types::{
+    Report as DbReport,
+    ReportNote as DbReportNote,
+};
+
+use crate::mastodon_api::{
+    accounts::types::Account,
+    pagination::PageSize,
+};
+
+fn default_page_size() -> PageSize { PageSize::new(40) }
+
+#[derive(Deserialize)]
+pub struct ReportListQueryParams {
+    #[serde(default)]
+    pub resolved: bool,
+
+    pub target_account_id: Option<Uuid>,
+
+    pub max_id: Option<Uuid>,
+
+    #[serde(default = "default_page_size")]
+    pub limit: PageSize,
+}
+
+#[derive(Serialize)]
+pub struct ReportNote {
+    id: String,
+    account_id: Uuid,
+    content: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<DbReportNote> for ReportNote {
+    fn from(db_note: DbReportNote) -> Self {
+        Self {
+            id: db_note.id.to_string(),
+            account_id: db_note.author_id,
+            content: db_note.content,
+            created_at: db_note.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ReportNoteData {
+    pub content: String,
+}
+
+// https://docs.joinmastodon.org/entities/Admin_Report/
+#[derive(Serialize)]
+pub struct AdminReport {
+    id: Uuid,
+    action_taken: bool,
+    action_taken_at: Option<DateTime<Utc>>,
+    category: String,
+    comment: String,
+    forwarded: bool,
+    created_at: DateTime<Utc>,
+    account: Account,
+    target_account: Account,
+    assigned_account: Option<Account>,
+    status_ids: Vec<Uuid>,
+    notes: Vec<ReportNote>,
+}
+
+impl AdminReport {
+    pub fn new(
+        db_report: DbReport,
+        account: Account,
+        target_account: Account,
+        assigned_account: Option<Account>,
+        db_notes: Vec<DbReportNote>,
+    ) -> Self {
+        Self {
+            id: db_report.id,
+            action_taken: db_report.is_resolved(),
+            action_taken_at: db_report.resolved_at,
+            category: "other".to_string(),
+            comment: db_report.comment,
+            forwarded: db_report.is_forwarded,
+            created_at: db_report.created_at,
+            account: account,
+            target_account: target_account,
+            assigned_account: assigned_account,
+            status_ids: db_report.post_ids,
+            notes: db_notes.into_iter().map(ReportNote::from).collect(),
+        }
+    }
+}
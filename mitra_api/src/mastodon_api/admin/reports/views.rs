@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+
+use actix_web::{
+    dev::ConnectionInfo,
+    get,
+    http::Uri,
+    post,
+    web,
+    HttpResponse,
+    Scope,
+};
+use uuid::Uuid;
+
+use mitra_activitypub::authority::Authority;
+use mitra_config::Config;
+use mitra_models::{
+    accounts::types::{Permission, User},
+    database::{
+        get_database_client,
+        DatabaseClient,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    profiles::queries::get_profiles_by_ids,
+    reports::queries::{
+        create_report_note,
+        get_report,
+        get_report_notes,
+        get_reports,
+        set_report_assignee,
+        set_report_resolved,
+    },
+    reports::types::Report,
+};
+use mitra_validators::reports::{clean_report_note, validate_report_note};
+
+use crate::http::get_request_base_url;
+use crate::mastodon_api::{
+    accounts::types::Account,
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+    media_server::ClientMediaServer,
+    pagination::{get_last_item, get_paginated_response},
+};
+
+use super::types::{
+    AdminReport,
+    ReportListQueryParams,
+    ReportNoteData,
+};
+
+fn check_permission(user: &User) -> Result<(), MastodonError> {
+    if !user.role.has_permission(Permission::ManageReports) {
+        return Err(MastodonError::PermissionError);
+    };
+    Ok(())
+}
+
+async fn build_admin_reports(
+    db_client: &impl DatabaseClient,
+    authority: &Authority,
+    media_server: &ClientMediaServer,
+    reports: Vec<Report>,
+) -> Result<Vec<AdminReport>, DatabaseError> {
+    let mut profile_ids = vec![];
+    for report in &reports {
+        profile_ids.push(report.reporter_id);
+        profile_ids.push(report.target_id);
+        profile_ids.extend(report.assigned_account_id);
+    };
+    profile_ids.sort();
+    profile_ids.dedup();
+    let profiles: HashMap<_, _> =
+        get_profiles_by_ids(db_client, &profile_ids).await?
+            .into_iter()
+            .map(|profile| (profile.id, profile))
+            .collect();
+    let report_ids: Vec<_> = reports.iter().map(|report| report.id).collect();
+    let mut notes: HashMap<_, Vec<_>> = HashMap::new();
+    for note in get_report_notes(db_client, &report_ids).await? {
+        notes.entry(note.report_id).or_default().push(note);
+    };
+    let mut admin_reports = vec![];
+    for report in reports {
+        let get_account = |profile_id| {
+            let profile = profiles.get(&profile_id)
+                .ok_or(DatabaseError::NotFound("profile"))?
+                .clone();
+            let account =
+                Account::from_profile(authority, media_server, profile);
+            Ok::<_, DatabaseError>(account)
+        };
+        let reporter = get_account(report.reporter_id)?;
+        let target = get_account(report.target_id)?;
+        let maybe_assignee = report.assigned_account_id
+            .map(get_account)
+            .transpose()?;
+        let report_notes = notes.remove(&report.id).unwrap_or_default();
+        let admin_report = AdminReport::new(
+            report,
+            reporter,
+            target,
+            maybe_assignee,
+            report_notes,
+        );
+        admin_reports.push(admin_report);
+    };
+    Ok(admin_reports)
+}
+
+async fn build_admin_report(
+    db_client: &impl DatabaseClient,
+    authority: &Authority,
+    media_server: &ClientMediaServer,
+    report: Report,
+) -> Result<AdminReport, DatabaseError> {
+    let admin_report = build_admin_reports(
+        db_client,
+        authority,
+        media_server,
+        vec![report],
+    ).await?
+        .pop()
+        .expect("one report should be returned");
+    Ok(admin_report)
+}
+
+/// https://docs.joinmastodon.org/methods/admin/reports/#get
+#[get("")]
+async fn report_list_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request_uri: Uri,
+    query_params: web::Query<ReportListQueryParams>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let reports = get_reports(
+        db_client,
+        query_params.resolved,
+        query_params.target_account_id,
+        query_params.max_id,
+        query_params.limit.inner(),
+    ).await?;
+    let maybe_last_id = get_last_item(&reports, &query_params.limit)
+        .map(|report| report.id);
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let admin_reports = build_admin_reports(
+        db_client,
+        &authority,
+        &media_server,
+        reports,
+    ).await?;
+    let response = get_paginated_response(
+        &base_url,
+        &request_uri,
+        admin_reports,
+        maybe_last_id,
+    );
+    Ok(response)
+}
+
+/// https://docs.joinmastodon.org/methods/admin/reports/#get-one
+#[get("/{report_id}")]
+async fn report_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    report_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let report = get_report(db_client, *report_id).await?;
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let admin_report = build_admin_report(
+        db_client,
+        &authority,
+        &media_server,
+        report,
+    ).await?;
+    Ok(HttpResponse::Ok().json(admin_report))
+}
+
+/// https://docs.joinmastodon.org/methods/admin/reports/#assign_to_self
+#[post("/{report_id}/assign_to_self")]
+async fn assign_report_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    report_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let report = set_report_assignee(
+        db_client,
+        *report_id,
+        Some(current_user.id),
+    ).await?;
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let admin_report = build_admin_report(
+        db_client,
+        &authority,
+        &media_server,
+        report,
+    ).await?;
+    Ok(HttpResponse::Ok().json(admin_report))
+}
+
+/// https://docs.joinmastodon.org/methods/admin/reports/#unassign
+#[post("/{report_id}/unassign")]
+async fn unassign_report_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    report_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let report = set_report_assignee(db_client, *report_id, None).await?;
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let admin_report = build_admin_report(
+        db_client,
+        &authority,
+        &media_server,
+        report,
+    ).await?;
+    Ok(HttpResponse::Ok().json(admin_report))
+}
+
+/// https://docs.joinmastodon.org/methods/admin/reports/#resolve
+#[post("/{report_id}/resolve")]
+async fn resolve_report_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    report_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let report = set_report_resolved(db_client, *report_id, true).await?;
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let admin_report = build_admin_report(
+        db_client,
+        &authority,
+        &media_server,
+        report,
+    ).await?;
+    Ok(HttpResponse::Ok().json(admin_report))
+}
+
+/// https://docs.joinmastodon.org/methods/admin/reports/#reopen
+#[post("/{report_id}/reopen")]
+async fn reopen_report_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    report_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let report = set_report_resolved(db_client, *report_id, false).await?;
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let admin_report = build_admin_report(
+        db_client,
+        &authority,
+        &media_server,
+        report,
+    ).await?;
+    Ok(HttpResponse::Ok().json(admin_report))
+}
+
+#[post("/{report_id}/notes")]
+async fn create_report_note_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    report_id: web::Path<Uuid>,
+    note_data: web::Json<ReportNoteData>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let content = clean_report_note(&note_data.content);
+    validate_report_note(content)?;
+    let report = get_report(db_client, *report_id).await?;
+    create_report_note(
+        db_client,
+        report.id,
+        current_user.id,
+        content,
+    ).await?;
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let admin_report = build_admin_report(
+        db_client,
+        &authority,
+        &media_server,
+        report,
+    ).await?;
+    Ok(HttpResponse::Ok().json(admin_report))
+}
+
+pub fn admin_report_api_scope() -> Scope {
+    web::scope("/v1/admin/reports")
+        .service(report_list_view)
+        .service(report_view)
+        .service(assign_report_view)
+        .service(unassign_report_view)
+        .service(resolve_report_view)
+        .service(reopen_report_view)
+        .service(create_report_note_view)
+}
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct RuleData {
+    pub text: String,
+    #[serde(default)]
+    pub hint: String,
+}
@@ -0,0 +1,113 @@
+use actix_web::{
+    delete,
+    get,
+    post,
+    put,
+    web,
+    HttpResponse,
+    Scope,
+};
+
+use mitra_models::{
+    accounts::types::{Permission, User},
+    database::{get_database_client, DatabaseConnectionPool},
+    server_rules::queries::{
+        create_server_rule,
+        delete_server_rule,
+        get_server_rules,
+        update_server_rule,
+    },
+};
+use mitra_validators::server_rules::validate_server_rule;
+
+use crate::mastodon_api::{
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+    instance::types::Rule,
+};
+
+use super::types::RuleData;
+
+fn check_permission(user: &User) -> Result<(), MastodonError> {
+    if !user.role.has_permission(Permission::ManageServerRules) {
+        return Err(MastodonError::PermissionError);
+    };
+    Ok(())
+}
+
+#[get("")]
+async fn rule_list_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let rules: Vec<Rule> = get_server_rules(db_client).await?
+        .into_iter()
+        .map(Rule::from)
+        .collect();
+    Ok(HttpResponse::Ok().json(rules))
+}
+
+#[post("")]
+async fn create_rule_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    rule_data: web::Json<RuleData>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    validate_server_rule(&rule_data.text, &rule_data.hint)?;
+    let db_rule = create_server_rule(
+        db_client,
+        &rule_data.text,
+        &rule_data.hint,
+    ).await?;
+    let rule = Rule::from(db_rule);
+    Ok(HttpResponse::Ok().json(rule))
+}
+
+#[put("/{rule_id}")]
+async fn update_rule_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    rule_id: web::Path<i32>,
+    rule_data: web::Json<RuleData>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    validate_server_rule(&rule_data.text, &rule_data.hint)?;
+    let db_rule = update_server_rule(
+        db_client,
+        *rule_id,
+        &rule_data.text,
+        &rule_data.hint,
+    ).await?;
+    let rule = Rule::from(db_rule);
+    Ok(HttpResponse::Ok().json(rule))
+}
+
+#[delete("/{rule_id}")]
+async fn delete_rule_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    rule_id: web::Path<i32>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    delete_server_rule(db_client, *rule_id).await?;
+    let empty = serde_json::json!({});
+    Ok(HttpResponse::NoContent().json(empty))
+}
+
+pub fn admin_rule_api_scope() -> Scope {
+    web::scope("/v1/admin/rules")
+        .service(rule_list_view)
+        .service(create_rule_view)
+        .service(update_rule_view)
+        .service(delete_rule_view)
+}
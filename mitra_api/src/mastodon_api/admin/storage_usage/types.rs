@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use mitra_adapters::roles::{get_user_storage_quota, RoleLimitsMap};
+use mitra_models::accounts::types::User;
+
+use crate::mastodon_api::pagination::PageSize;
+
+fn default_page_size() -> PageSize { PageSize::new(40) }
+
+#[derive(Deserialize)]
+pub struct StorageUsageQueryParams {
+    #[serde(default = "default_page_size")]
+    pub limit: PageSize,
+}
+
+#[derive(Deserialize)]
+pub struct StorageQuotaData {
+    // Removes individual quota if null
+    pub storage_quota: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct StorageUsage {
+    account_id: Uuid,
+    username: String,
+    storage_usage: u64,
+    // Effective quota
+    storage_quota: Option<u64>,
+    is_custom_quota: bool,
+}
+
+impl StorageUsage {
+    pub fn new(
+        role_limits: &RoleLimitsMap,
+        user: &User,
+        storage_usage: u64,
+    ) -> Self {
+        Self {
+            account_id: user.id,
+            username: user.profile.username.clone(),
+            storage_usage,
+            storage_quota: get_user_storage_quota(role_limits, user),
+            is_custom_quota: user.storage_quota.is_some(),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use actix_web::{
+    get,
+    web,
+    HttpResponse,
+    Scope,
+};
+
+use mitra_adapters::dynamic_config::get_dynamic_config;
+use mitra_models::{
+    accounts::types::Permission,
+    attachments::queries::get_storage_usage_report,
+    database::{get_database_client, DatabaseConnectionPool},
+};
+
+use crate::mastodon_api::{
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+};
+
+use super::types::{StorageUsage, StorageUsageQueryParams};
+
+// Returns local users with the largest storage usage
+#[get("")]
+async fn storage_usage_report_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    query_params: web::Query<StorageUsageQueryParams>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if !current_user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let report: Vec<StorageUsage> = get_storage_usage_report(
+        db_client,
+        query_params.limit.inner(),
+    )
+        .await?
+        .iter()
+        .map(|(user, storage_usage)| StorageUsage::new(
+            &dynamic_config.role_limits,
+            user,
+            *storage_usage,
+        ))
+        .collect();
+    Ok(HttpResponse::Ok().json(report))
+}
+
+pub fn admin_storage_usage_api_scope() -> Scope {
+    web::scope("/v1/admin/storage_usage")
+        .service(storage_usage_report_view)
+}
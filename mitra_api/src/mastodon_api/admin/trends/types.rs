@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+use crate::mastodon_api::statuses::types::Tag;
+
+/// https://docs.joinmastodon.org/entities/Tag/#admin
+#[derive(Serialize)]
+pub struct AdminTag {
+    #[serde(flatten)]
+    tag: Tag,
+    trendable: bool,
+    requires_review: bool,
+}
+
+impl AdminTag {
+    pub fn new(tag: Tag, trendable: Option<bool>) -> Self {
+        Self {
+            tag,
+            trendable: trendable.unwrap_or(true),
+            requires_review: trendable.is_none(),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use std::cmp::min;
+
+use actix_web::{
+    get,
+    post,
+    web,
+    HttpResponse,
+    Scope,
+};
+use chrono::{TimeDelta, Utc};
+
+use mitra_config::Config;
+use mitra_models::{
+    accounts::types::{Permission, User},
+    database::{get_database_client, DatabaseConnectionPool},
+    tags::queries::{
+        get_tag_usage_history,
+        get_trending_tags,
+        set_tag_trendable,
+    },
+};
+
+use crate::mastodon_api::{
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+    statuses::types::Tag,
+    trends::{
+        types::TrendsQueryParams,
+        views::{TAG_HISTORY_DAYS, TRENDS_LIMIT_MAX, TRENDS_WINDOW_HOURS},
+    },
+};
+
+use super::types::AdminTag;
+
+fn check_permission(user: &User) -> Result<(), MastodonError> {
+    if !user.role.has_permission(Permission::ManageSettings) {
+        return Err(MastodonError::PermissionError);
+    };
+    Ok(())
+}
+
+// https://docs.joinmastodon.org/methods/admin/trends/#tags
+#[get("/tags")]
+async fn trending_tags_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    query_params: web::Query<TrendsQueryParams>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    let created_after = Utc::now() - TimeDelta::hours(TRENDS_WINDOW_HOURS);
+    let trending_tags = get_trending_tags(
+        db_client,
+        created_after,
+        true, // with hidden
+        min(query_params.limit.inner(), TRENDS_LIMIT_MAX),
+        query_params.offset,
+    ).await?;
+    let mut tags = vec![];
+    for (tag_name, trendable) in trending_tags {
+        let history = get_tag_usage_history(
+            db_client,
+            &tag_name,
+            TAG_HISTORY_DAYS,
+        ).await?;
+        let tag = Tag::with_history(
+            config.instance().uri_str(),
+            tag_name,
+            history,
+        );
+        tags.push(AdminTag::new(tag, trendable));
+    };
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+async fn set_trendable(
+    auth: BearerAuth,
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+    tag_name: &str,
+    trendable: bool,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    check_permission(&current_user)?;
+    set_tag_trendable(db_client, tag_name, trendable).await?;
+    let history = get_tag_usage_history(
+        db_client,
+        tag_name,
+        TAG_HISTORY_DAYS,
+    ).await?;
+    let tag = Tag::with_history(
+        config.instance().uri_str(),
+        tag_name.to_lowercase(),
+        history,
+    );
+    let admin_tag = AdminTag::new(tag, Some(trendable));
+    Ok(HttpResponse::Ok().json(admin_tag))
+}
+
+// https://docs.joinmastodon.org/methods/admin/trends/#approve-tag
+#[post("/tags/{tag_name}/approve")]
+async fn approve_tag_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    tag_name: web::Path<String>,
+) -> Result<HttpResponse, MastodonError> {
+    set_trendable(auth, &config, &db_pool, &tag_name, true).await
+}
+
+// https://docs.joinmastodon.org/methods/admin/trends/#reject-tag
+#[post("/tags/{tag_name}/reject")]
+async fn reject_tag_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    tag_name: web::Path<String>,
+) -> Result<HttpResponse, MastodonError> {
+    set_trendable(auth, &config, &db_pool, &tag_name, false).await
+}
+
+pub fn admin_trends_api_scope() -> Scope {
+    web::scope("/v1/admin/trends")
+        .service(trending_tags_view)
+        .service(approve_tag_view)
+        .service(reject_tag_view)
+}
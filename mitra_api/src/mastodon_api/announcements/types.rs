@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use mitra_models::announcements::types::{
+    Announcement as DbAnnouncement,
+    AnnouncementDetailed,
+    AnnouncementReaction as DbAnnouncementReaction,
+};
+use mitra_utils::markdown::markdown_to_html;
+
+use crate::mastodon_api::{
+    custom_emojis::types::CustomEmoji,
+    media_server::ClientMediaServer,
+    statuses::types::{Mention, Tag},
+};
+
+// https://docs.joinmastodon.org/entities/Announcement/#Reaction
+#[derive(Serialize)]
+pub struct AnnouncementReaction {
+    name: String,
+    count: i64,
+    me: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    static_url: Option<String>,
+}
+
+impl AnnouncementReaction {
+    fn from_db(
+        media_server: &ClientMediaServer,
+        db_reaction: DbAnnouncementReaction,
+    ) -> Self {
+        let (name, maybe_url) = if let Some(emoji) = db_reaction.emoji {
+            (emoji.emoji_name, Some(media_server.url_for(&emoji.image)))
+        } else {
+            (db_reaction.content, None)
+        };
+        Self {
+            name: name,
+            count: db_reaction.count,
+            me: db_reaction.is_own,
+            url: maybe_url.clone(),
+            static_url: maybe_url,
+        }
+    }
+}
+
+// https://docs.joinmastodon.org/entities/Announcement/
+#[derive(Serialize)]
+pub struct Announcement {
+    id: String,
+    content: String,
+    starts_at: Option<DateTime<Utc>>,
+    ends_at: Option<DateTime<Utc>>,
+    all_day: bool,
+    published_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    read: Option<bool>,
+    mentions: Vec<Mention>,
+    tags: Vec<Tag>,
+    emojis: Vec<CustomEmoji>,
+    reactions: Vec<AnnouncementReaction>,
+}
+
+impl Announcement {
+    pub fn from_db(db_announcement: DbAnnouncement) -> Self {
+        Self {
+            id: db_announcement.id.to_string(),
+            content: markdown_to_html(&db_announcement.content),
+            starts_at: None,
+            ends_at: db_announcement.expires_at,
+            all_day: false,
+            published_at: db_announcement.published_at,
+            updated_at: db_announcement.updated_at,
+            read: None,
+            mentions: vec![],
+            tags: vec![],
+            emojis: vec![],
+            reactions: vec![],
+        }
+    }
+
+    pub fn from_detailed(
+        media_server: &ClientMediaServer,
+        announcement: AnnouncementDetailed,
+    ) -> Self {
+        let reactions = announcement.reactions.into_iter()
+            .map(|reaction| AnnouncementReaction::from_db(media_server, reaction))
+            .collect();
+        Self {
+            read: Some(announcement.is_read),
+            reactions: reactions,
+            ..Self::from_db(announcement.announcement)
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AnnouncementQueryParams {
+    #[serde(default)]
+    pub with_dismissed: bool,
+}
@@ -0,0 +1,139 @@
+// https://docs.joinmastodon.org/methods/announcements/
+use actix_web::{
+    delete,
+    dev::ConnectionInfo,
+    get,
+    post,
+    put,
+    web,
+    HttpResponse,
+    Scope,
+};
+
+use mitra_config::Config;
+use mitra_models::{
+    announcements::queries::{
+        create_announcement_reaction,
+        delete_announcement_reaction,
+        dismiss_announcement,
+        get_active_announcements,
+    },
+    database::{get_database_client, DatabaseConnectionPool},
+    emojis::queries::get_local_emoji_by_name,
+};
+use mitra_utils::unicode::is_single_character;
+use mitra_validators::emojis::clean_emoji_name;
+
+use crate::{
+    http::get_request_base_url,
+    mastodon_api::{
+        auth::{get_current_user, BearerAuth},
+        errors::MastodonError,
+        media_server::ClientMediaServer,
+    },
+};
+
+use super::types::{Announcement, AnnouncementQueryParams};
+
+#[get("")]
+async fn announcement_list_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    query_params: web::Query<AnnouncementQueryParams>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let db_announcements = get_active_announcements(
+        db_client,
+        current_user.id,
+        query_params.with_dismissed,
+    ).await?;
+    let base_url = get_request_base_url(connection_info);
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let announcements: Vec<_> = db_announcements.into_iter()
+        .map(|announcement| {
+            Announcement::from_detailed(&media_server, announcement)
+        })
+        .collect();
+    Ok(HttpResponse::Ok().json(announcements))
+}
+
+#[post("/{announcement_id}/dismiss")]
+async fn dismiss_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    announcement_id: web::Path<i32>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    dismiss_announcement(
+        db_client,
+        current_user.id,
+        *announcement_id,
+    ).await?;
+    let empty = serde_json::json!({});
+    Ok(HttpResponse::Ok().json(empty))
+}
+
+#[put("/{announcement_id}/reactions/{name}")]
+async fn create_reaction_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    path: web::Path<(i32, String)>,
+) -> Result<HttpResponse, MastodonError> {
+    let (announcement_id, name) = path.into_inner();
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let (content, maybe_emoji_id) = if is_single_character(&name) {
+        (name, None)
+    } else {
+        let emoji_name = clean_emoji_name(&name);
+        let emoji = get_local_emoji_by_name(db_client, emoji_name).await?;
+        (emoji.shortcode(), Some(emoji.id))
+    };
+    create_announcement_reaction(
+        db_client,
+        current_user.id,
+        announcement_id,
+        &content,
+        maybe_emoji_id,
+    ).await?;
+    let empty = serde_json::json!({});
+    Ok(HttpResponse::Ok().json(empty))
+}
+
+#[delete("/{announcement_id}/reactions/{name}")]
+async fn delete_reaction_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    path: web::Path<(i32, String)>,
+) -> Result<HttpResponse, MastodonError> {
+    let (announcement_id, name) = path.into_inner();
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let content = if is_single_character(&name) {
+        name
+    } else {
+        // The value could be a name or a shortcode
+        let emoji_name = clean_emoji_name(&name);
+        format!(":{emoji_name}:")
+    };
+    delete_announcement_reaction(
+        db_client,
+        current_user.id,
+        announcement_id,
+        &content,
+    ).await?;
+    let empty = serde_json::json!({});
+    Ok(HttpResponse::Ok().json(empty))
+}
+
+pub fn announcement_api_scope() -> Scope {
+    web::scope("/v1/announcements")
+        .service(announcement_list_view)
+        .service(dismiss_view)
+        .service(create_reaction_view)
+        .service(delete_reaction_view)
+}
@@ -8,6 +8,7 @@ use actix_web::{
 };
 use uuid::Uuid;
 
+use mitra_adapters::dynamic_config::get_dynamic_config;
 use mitra_models::{
     database::{get_database_client, DatabaseConnectionPool},
     oauth::queries::create_oauth_app,
@@ -40,6 +41,7 @@ async fn create_app_view(
         Either::Right(form) => form.into_inner().into(),
     };
     let db_client = &**get_database_client(&db_pool).await?;
+    let dynamic_config = get_dynamic_config(db_client).await?;
     let db_app_data = DbOauthAppData {
         app_name: app_form.client_name,
         website: app_form.website,
@@ -47,10 +49,15 @@ async fn create_app_view(
         redirect_uri: app_form.redirect_uris,
         client_id: Uuid::new_v4(),
         client_secret: generate_oauth_token(),
+        is_approved: !dynamic_config.app_approval_required,
     };
     validate_redirect_uri(&db_app_data.redirect_uri)?;
     let db_app = create_oauth_app(db_client, db_app_data).await?;
-    log::info!("registered app with scopes: {:?}", db_app.scopes);
+    log::info!(
+        "registered app with scopes: {:?} (approved: {})",
+        db_app.scopes,
+        db_app.is_approved,
+    );
     let app = OauthApp::from_db(db_app);
     Ok(HttpResponse::Ok().json(app))
 }
@@ -1,12 +1,83 @@
+use std::future::{ready, Ready};
+
+use actix_web::{
+    body::{BoxBody, MessageBody},
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    http::{header as http_header, Method},
+    middleware::Next,
+    web,
+    Error,
+    FromRequest,
+    HttpMessage,
+    HttpRequest,
+};
+use actix_web_httpauth::extractors::bearer::{
+    BearerAuth as HttpBearerAuth,
+};
+use serde::Deserialize;
+
+use mitra_config::{Config, PublicApiAccessMode};
 use mitra_models::{
     accounts::types::User,
-    database::{DatabaseClient, DatabaseError},
+    database::{
+        get_database_client,
+        DatabaseClient,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
     oauth::queries::get_user_by_oauth_token,
 };
 
 use super::errors::MastodonError;
 
-pub async fn get_current_session(
+/// Session validated by [`public_api_access_middleware`]
+#[derive(Clone)]
+struct AuthenticatedSession {
+    access_token: String,
+    session: (i32, User),
+}
+
+/// Returns session that has been validated by middleware
+pub fn get_validated_session(
+    request: &HttpRequest,
+    access_token: &str,
+) -> Option<(i32, User)> {
+    request.extensions()
+        .get::<AuthenticatedSession>()
+        .filter(|item| item.access_token == access_token)
+        .map(|item| item.session.clone())
+}
+
+/// Bearer token extractor.
+/// Re-uses session validated by middleware, if present.
+pub struct BearerAuth {
+    access_token: String,
+    session: Option<(i32, User)>,
+}
+
+impl BearerAuth {
+    pub fn token(&self) -> &str {
+        &self.access_token
+    }
+}
+
+impl FromRequest for BearerAuth {
+    type Error = <HttpBearerAuth as FromRequest>::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(request: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let result = HttpBearerAuth::from_request(request, payload)
+            .into_inner()
+            .map(|auth| {
+                let access_token = auth.token().to_owned();
+                let session = get_validated_session(request, &access_token);
+                Self { access_token, session }
+            });
+        ready(result)
+    }
+}
+
+async fn get_session_by_token(
     db_client: &impl DatabaseClient,
     token: &str,
 ) -> Result<(i32, User), MastodonError> {
@@ -20,10 +91,177 @@ pub async fn get_current_session(
     Ok(session_info)
 }
 
-pub async fn get_current_user(
+pub async fn get_user_by_token(
     db_client: &impl DatabaseClient,
     token: &str,
 ) -> Result<User, MastodonError> {
-    let (_, user) = get_current_session(db_client, token).await?;
+    let (_, user) = get_session_by_token(db_client, token).await?;
+    Ok(user)
+}
+
+pub async fn get_current_session(
+    db_client: &impl DatabaseClient,
+    auth: &BearerAuth,
+) -> Result<(i32, User), MastodonError> {
+    if let Some(ref session) = auth.session {
+        return Ok(session.clone());
+    };
+    get_session_by_token(db_client, auth.token()).await
+}
+
+pub async fn get_current_user(
+    db_client: &impl DatabaseClient,
+    auth: &BearerAuth,
+) -> Result<User, MastodonError> {
+    let (_, user) = get_current_session(db_client, auth).await?;
     Ok(user)
 }
+
+/// Returns access token from Authorization header
+fn get_bearer_token(request: &HttpRequest) -> Option<String> {
+    request.headers()
+        .get(http_header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.to_owned())
+}
+
+#[derive(Deserialize)]
+struct AccessTokenQueryParams {
+    access_token: Option<String>,
+}
+
+/// Returns access token from query parameter, Sec-WebSocket-Protocol header
+/// or Authorization header (streaming API)
+pub fn get_streaming_access_token(request: &HttpRequest) -> Option<String> {
+    if let Ok(query_params) =
+        web::Query::<AccessTokenQueryParams>::from_query(request.query_string())
+    {
+        if let Some(token) = query_params.into_inner().access_token {
+            return Some(token);
+        };
+    };
+    if let Some(protocol) = request.headers()
+        .get(http_header::SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|value| value.to_str().ok())
+    {
+        return Some(protocol.to_owned());
+    };
+    get_bearer_token(request)
+}
+
+fn is_streaming_api_endpoint(path: &str) -> bool {
+    path.starts_with("/api/v1/streaming")
+}
+
+/// Returns true if endpoint is available to unauthenticated clients
+fn is_public_api_endpoint(
+    mode: PublicApiAccessMode,
+    method: &Method,
+    path: &str,
+) -> bool {
+    // Endpoints required for registration and logging in
+    let is_essential =
+        path == "/api/openapi.json"
+        || path == "/api/v1/apps"
+        || path == "/api/v1/instance"
+        || path == "/api/v2/instance"
+        || path == "/api/pleroma/frontend_configurations"
+        || (path == "/api/v1/accounts" && *method == Method::POST)
+        || path == "/api/v1/accounts/reset_password"
+        // Used in HTML documents
+        || path == "/api/v1/accounts/identicon"
+        || path.starts_with("/api/media_proxy/");
+    match mode {
+        PublicApiAccessMode::Full => true,
+        PublicApiAccessMode::Limited => {
+            is_essential || !(
+                path.starts_with("/api/v1/timelines/")
+                || path == "/api/v1/directory"
+                || path == "/api/v2/search"
+                || path == "/api/v1/accounts/search"
+                || path == "/api/v1/accounts/search_did"
+                || path.starts_with("/api/v1/trends/")
+                || path.starts_with("/api/v1/tags/")
+            )
+        },
+        PublicApiAccessMode::Closed => is_essential,
+    }
+}
+
+async fn check_access_token(
+    db_pool: &DatabaseConnectionPool,
+    token: &str,
+) -> Result<(i32, User), MastodonError> {
+    let db_client = &**get_database_client(db_pool).await?;
+    get_session_by_token(db_client, token).await
+}
+
+/// Restricts access to API for clients without valid access token
+pub async fn public_api_access_middleware(
+    request: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let config = request.app_data::<web::Data<Config>>()
+        .expect("app data should contain configuration");
+    if !is_public_api_endpoint(
+        config.public_api_access,
+        request.method(),
+        request.path(),
+    ) {
+        let maybe_token = if is_streaming_api_endpoint(request.path()) {
+            get_streaming_access_token(request.request())
+        } else {
+            get_bearer_token(request.request())
+        };
+        let Some(token) = maybe_token else {
+            let error = MastodonError::AuthError("authentication required");
+            return Ok(request.error_response(error));
+        };
+        let db_pool = request.app_data::<web::Data<DatabaseConnectionPool>>()
+            .expect("app data should contain database connection pool");
+        let session = match check_access_token(db_pool, &token).await {
+            Ok(session) => session,
+            Err(error) => return Ok(request.error_response(error)),
+        };
+        // Views don't need to look up the token again
+        request.extensions_mut().insert(AuthenticatedSession {
+            access_token: token,
+            session,
+        });
+    };
+    let response = next.call(request).await?;
+    Ok(response.map_into_boxed_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public_api_endpoint() {
+        let full = PublicApiAccessMode::Full;
+        let limited = PublicApiAccessMode::Limited;
+        let closed = PublicApiAccessMode::Closed;
+        let timeline = "/api/v1/timelines/public";
+        assert!(is_public_api_endpoint(full, &Method::GET, timeline));
+        assert!(!is_public_api_endpoint(limited, &Method::GET, timeline));
+        assert!(!is_public_api_endpoint(closed, &Method::GET, timeline));
+        let status = "/api/v1/statuses/019a0f7d-3a44-7b2e-a0f3-2a3c0b5f3e10";
+        assert!(is_public_api_endpoint(limited, &Method::GET, status));
+        assert!(!is_public_api_endpoint(closed, &Method::GET, status));
+        let instance = "/api/v1/instance";
+        assert!(is_public_api_endpoint(closed, &Method::GET, instance));
+        let peers = "/api/v1/instance/peers";
+        assert!(!is_public_api_endpoint(closed, &Method::GET, peers));
+        let apps = "/api/v1/apps";
+        assert!(is_public_api_endpoint(closed, &Method::POST, apps));
+        let accounts = "/api/v1/accounts";
+        assert!(is_public_api_endpoint(closed, &Method::POST, accounts));
+        let trends = "/api/v1/trends/tags";
+        assert!(!is_public_api_endpoint(limited, &Method::GET, trends));
+        let streaming = "/api/v1/streaming";
+        assert!(is_public_api_endpoint(limited, &Method::GET, streaming));
+        assert!(!is_public_api_endpoint(closed, &Method::GET, streaming));
+    }
+}
@@ -1,4 +1,6 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use mitra_models::bookmarks::types::BookmarkFolder as DbBookmarkFolder;
 
 use crate::mastodon_api::{
     pagination::PageSize,
@@ -8,8 +10,30 @@ fn default_page_size() -> PageSize { PageSize::new(20) }
 
 #[derive(Deserialize)]
 pub struct BookmarkListQueryParams {
+    pub folder_id: Option<i32>,
+
     pub max_id: Option<i32>,
 
     #[serde(default = "default_page_size")]
     pub limit: PageSize,
 }
+
+#[derive(Serialize)]
+pub struct BookmarkFolder {
+    id: String,
+    name: String,
+}
+
+impl BookmarkFolder {
+    pub fn from_db(db_folder: DbBookmarkFolder) -> Self {
+        Self {
+            id: db_folder.id.to_string(),
+            name: db_folder.folder_name,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct BookmarkFolderForm {
+    pub name: String,
+}
@@ -1,30 +1,48 @@
 use actix_web::{
+    delete,
     dev::ConnectionInfo,
     http::Uri,
     get,
+    post,
+    put,
     web,
+    HttpRequest,
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 
 use mitra_activitypub::authority::Authority;
 use mitra_config::Config;
 use mitra_models::{
-    bookmarks::queries::get_bookmarked_posts,
+    bookmarks::queries::{
+        create_bookmark_folder,
+        delete_bookmark_folder,
+        get_bookmark_folder,
+        get_bookmark_folders,
+        get_bookmarked_posts,
+        update_bookmark_folder,
+    },
     database::{get_database_client, DatabaseConnectionPool},
 };
+use mitra_validators::bookmarks::{
+    clean_bookmark_folder_name,
+    validate_bookmark_folder_name,
+};
 
-use crate::http::get_request_base_url;
+use crate::http::{get_language_preferences, get_request_base_url, JsonOrForm};
 use crate::mastodon_api::{
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
     media_server::ClientMediaServer,
     pagination::{get_last_item, get_paginated_response},
     statuses::helpers::build_status_list,
 };
 
-use super::types::BookmarkListQueryParams;
+use super::types::{
+    BookmarkFolder,
+    BookmarkFolderForm,
+    BookmarkListQueryParams,
+};
 
 /// https://docs.joinmastodon.org/methods/bookmarks/
 #[get("")]
@@ -33,14 +51,20 @@ async fn bookmark_list_view(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     request_uri: Uri,
     query_params: web::Query<BookmarkListQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if let Some(folder_id) = query_params.folder_id {
+        // Verify ownership
+        get_bookmark_folder(db_client, folder_id, current_user.id).await?;
+    };
     let bookmarks = get_bookmarked_posts(
         db_client,
         current_user.id,
+        query_params.folder_id,
         query_params.max_id,
         query_params.limit.inner(),
     ).await?;
@@ -58,6 +82,7 @@ async fn bookmark_list_view(
         &media_server,
         Some(&current_user),
         posts,
+        &get_language_preferences(&request),
     ).await?;
     let response = get_paginated_response(
         &base_url,
@@ -68,7 +93,88 @@ async fn bookmark_list_view(
     Ok(response)
 }
 
+#[get("")]
+async fn bookmark_folder_list_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let db_folders = get_bookmark_folders(db_client, current_user.id).await?;
+    let folders: Vec<_> = db_folders.into_iter()
+        .map(BookmarkFolder::from_db)
+        .collect();
+    Ok(HttpResponse::Ok().json(folders))
+}
+
+#[post("")]
+async fn create_bookmark_folder_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    folder_form: JsonOrForm<BookmarkFolderForm>,
+) -> Result<HttpResponse, MastodonError> {
+    let folder_form = folder_form.into_inner();
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let folder_name = clean_bookmark_folder_name(&folder_form.name);
+    validate_bookmark_folder_name(folder_name)?;
+    let db_folder = create_bookmark_folder(
+        db_client,
+        current_user.id,
+        folder_name,
+    ).await?;
+    let folder = BookmarkFolder::from_db(db_folder);
+    Ok(HttpResponse::Ok().json(folder))
+}
+
+#[put("/{folder_id}")]
+async fn update_bookmark_folder_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    folder_id: web::Path<i32>,
+    folder_form: JsonOrForm<BookmarkFolderForm>,
+) -> Result<HttpResponse, MastodonError> {
+    let folder_form = folder_form.into_inner();
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let folder_name = clean_bookmark_folder_name(&folder_form.name);
+    validate_bookmark_folder_name(folder_name)?;
+    let db_folder = update_bookmark_folder(
+        db_client,
+        *folder_id,
+        current_user.id,
+        folder_name,
+    ).await?;
+    let folder = BookmarkFolder::from_db(db_folder);
+    Ok(HttpResponse::Ok().json(folder))
+}
+
+#[delete("/{folder_id}")]
+async fn delete_bookmark_folder_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    folder_id: web::Path<i32>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    delete_bookmark_folder(
+        db_client,
+        *folder_id,
+        current_user.id,
+    ).await?;
+    let empty = serde_json::json!({});
+    Ok(HttpResponse::Ok().json(empty))
+}
+
 pub fn bookmark_api_scope() -> Scope {
     web::scope("/v1/bookmarks")
         .service(bookmark_list_view)
 }
+
+pub fn bookmark_folder_api_scope() -> Scope {
+    web::scope("/v1/bookmark_folders")
+        .service(bookmark_folder_list_view)
+        .service(create_bookmark_folder_view)
+        .service(update_bookmark_folder_view)
+        .service(delete_bookmark_folder_view)
+}
@@ -6,7 +6,6 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 
 use mitra_activitypub::authority::Authority;
 use mitra_config::Config;
@@ -18,7 +17,7 @@ use mitra_models::{
 use crate::http::get_request_base_url;
 use crate::mastodon_api::{
     accounts::types::Account,
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
     media_server::ClientMediaServer,
 };
@@ -33,7 +32,7 @@ async fn profile_directory(
     query_params: web::Query<DirectoryQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    get_current_user(db_client, auth.token()).await?;
+    get_current_user(db_client, &auth).await?;
     let profiles = get_profiles_paginated(
         db_client,
         query_params.local,
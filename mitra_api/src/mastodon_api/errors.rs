@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::error::{Error as StdError};
 use std::time::Duration;
 
@@ -11,7 +12,12 @@ use serde::Serialize;
 use thiserror::Error;
 
 use mitra_models::database::DatabaseError;
-use mitra_validators::errors::ValidationError;
+use mitra_validators::{
+    errors::ValidationError,
+    passwords::PasswordError,
+};
+
+use crate::errors::HttpError;
 
 #[derive(Debug, Error)]
 pub enum MastodonError {
@@ -21,6 +27,13 @@ pub enum MastodonError {
     #[error("{0}")]
     ValidationError(String),
 
+    #[error("validation failed: {message}")]
+    FieldValidationError {
+        field: &'static str,
+        code: &'static str,
+        message: String,
+    },
+
     #[error("{0}")]
     AuthError(&'static str),
 
@@ -76,11 +89,50 @@ impl From<ValidationError> for MastodonError {
     }
 }
 
+impl From<PasswordError> for MastodonError {
+    fn from(error: PasswordError) -> Self {
+        Self::FieldValidationError {
+            field: "password",
+            code: error.code(),
+            message: error.to_string(),
+        }
+    }
+}
+
+// Used when Mastodon API helpers are called from other APIs
+impl From<MastodonError> for HttpError {
+    fn from(error: MastodonError) -> Self {
+        match error {
+            MastodonError::DatabaseError(db_error) => Self::DatabaseError(db_error),
+            MastodonError::ValidationError(message) => Self::ValidationError(message),
+            MastodonError::FieldValidationError { message, .. } => {
+                Self::ValidationError(message)
+            },
+            MastodonError::AuthError(message) => Self::AuthError(message),
+            MastodonError::PermissionError => Self::PermissionError,
+            MastodonError::NotFound(name) => Self::NotFound(name),
+            MastodonError::OperationError(message) => {
+                Self::ValidationError(message.to_owned())
+            },
+            other_error => Self::from_internal(other_error),
+        }
+    }
+}
+
+/// https://docs.joinmastodon.org/methods/accounts/#422-unprocessable-entity
+#[derive(Serialize)]
+pub struct FieldErrorDetails {
+    error: &'static str,
+    description: String,
+}
+
 /// https://docs.joinmastodon.org/entities/Error/
 #[derive(Serialize)]
 pub struct MastodonErrorData {
     error: String,
     error_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<HashMap<&'static str, Vec<FieldErrorDetails>>>,
 }
 
 impl MastodonErrorData {
@@ -88,6 +140,7 @@ impl MastodonErrorData {
         Self {
             error: message.to_string(),
             error_description: Some(message.to_string()),
+            details: None,
         }
     }
 }
@@ -95,9 +148,20 @@ impl MastodonErrorData {
 impl ResponseError for MastodonError {
     fn error_response(&self) -> HttpResponse {
         let error_message = self.error_message();
+        let maybe_details = match self {
+            Self::FieldValidationError { field, code, message } => {
+                let field_error = FieldErrorDetails {
+                    error: code,
+                    description: message.clone(),
+                };
+                Some(HashMap::from([(*field, vec![field_error])]))
+            },
+            _ => None,
+        };
         let error_data = MastodonErrorData {
             error: error_message.clone(),
             error_description: Some(error_message),
+            details: maybe_details,
         };
         HttpResponseBuilder::new(self.status_code()).json(error_data)
     }
@@ -105,6 +169,7 @@ impl ResponseError for MastodonError {
     fn status_code(&self) -> StatusCode {
         match self {
             Self::ValidationError(_) => StatusCode::BAD_REQUEST,
+            Self::FieldValidationError { .. } => StatusCode::UNPROCESSABLE_ENTITY,
             Self::AuthError(_) => StatusCode::UNAUTHORIZED,
             Self::PermissionError => StatusCode::FORBIDDEN,
             Self::NotFound(_) => StatusCode::NOT_FOUND,
@@ -135,4 +200,14 @@ mod tests {
         assert_eq!(error.to_string(), "internal error: database type error");
         assert_eq!(error.error_message(), "internal error");
     }
+
+    #[test]
+    fn test_password_error() {
+        let error = MastodonError::from(PasswordError::TooShort);
+        assert_eq!(
+            error.error_message(),
+            "validation failed: password is too short",
+        );
+        assert_eq!(error.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
 }
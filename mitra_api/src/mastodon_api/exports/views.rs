@@ -0,0 +1,34 @@
+use actix_web::{get, web, HttpResponse, Scope};
+
+use mitra_config::Config;
+use mitra_models::database::{get_database_client, DatabaseConnectionPool};
+
+use crate::mastodon_api::{
+    auth::{get_current_user, BearerAuth},
+    errors::MastodonError,
+    settings::helpers::export_follows,
+};
+
+#[get("/follows.csv")]
+async fn export_follows_csv_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let csv = export_follows(
+        db_client,
+        &config.instance().webfinger_hostname(),
+        current_user.id,
+    ).await?;
+    let response = HttpResponse::Ok()
+        .content_type("text/csv")
+        .body(csv);
+    Ok(response)
+}
+
+pub fn export_api_scope() -> Scope {
+    web::scope("/v1/exports")
+        .service(export_follows_csv_view)
+}
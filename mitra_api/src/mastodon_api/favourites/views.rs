@@ -3,10 +3,10 @@ use actix_web::{
     http::Uri,
     get,
     web,
+    HttpRequest,
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 
 use mitra_activitypub::authority::Authority;
 use mitra_config::Config;
@@ -15,13 +15,14 @@ use mitra_models::{
         get_database_client,
         DatabaseConnectionPool,
     },
+    posts::helpers::can_view_post,
     reactions::queries::get_reactions,
 };
 
 use crate::{
-    http::get_request_base_url,
+    http::{get_language_preferences, get_request_base_url},
     mastodon_api::{
-        auth::get_current_user,
+        auth::{get_current_user, BearerAuth},
         errors::MastodonError,
         media_server::ClientMediaServer,
         pagination::{get_last_item, get_paginated_response},
@@ -38,11 +39,12 @@ async fn get_favourites_view(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     request_uri: Uri,
     query_params: web::Query<FavListQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let reactions = get_reactions(
         db_client,
         current_user.id,
@@ -55,15 +57,25 @@ async fn get_favourites_view(
     let media_server = ClientMediaServer::new(&config, &base_url);
     let maybe_last_id = get_last_item(&reactions, &query_params.limit)
         .map(|reaction| reaction.reaction_id);
-    let posts = reactions.into_iter()
-        .map(|reaction| reaction.post)
-        .collect();
+    let mut posts = vec![];
+    for reaction in reactions {
+        // Visibility might have changed after the post was liked
+        // (e.g. the user stopped following the author)
+        if can_view_post(
+            db_client,
+            Some(&current_user.profile),
+            &reaction.post,
+        ).await? {
+            posts.push(reaction.post);
+        };
+    };
     let statuses = build_status_list(
         db_client,
         &authority,
         &media_server,
         Some(&current_user),
         posts,
+        &get_language_preferences(&request),
     ).await?;
     let response = get_paginated_response(
         &base_url,
@@ -0,0 +1,222 @@
+use actix_web::{
+    dev::ConnectionInfo,
+    post,
+    web,
+    HttpResponse,
+    Scope,
+};
+use apx_sdk::{
+    core::{
+        crypto::{
+            eddsa::generate_ed25519_key,
+            rsa::generate_rsa_key,
+        },
+        url::http_uri::HttpUri,
+    },
+    fetch::require_safe_url,
+};
+
+use mitra_activitypub::{
+    adapters::{
+        feeds::fetch_feed,
+        follow_requests::accept_and_add_follower,
+        users::create_or_update_local_actor,
+    },
+    authority::Authority,
+};
+use mitra_config::Config;
+use mitra_models::{
+    database::{
+        get_database_client,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    feed_sources::{
+        queries::{
+            create_feed_source,
+            get_feed_source_by_url,
+            get_feed_source_count_by_creator,
+        },
+        types::FeedSourceCreateData,
+    },
+    relationships::{
+        helpers::create_follow_request,
+        queries::has_relationship,
+        types::RelationshipType,
+    },
+};
+use mitra_utils::html::escape_html;
+use mitra_validators::{
+    errors::ValidationError,
+    feed_sources::{
+        clean_feed_source_create_data,
+        validate_feed_source_create_data,
+    },
+};
+
+use crate::{
+    http::get_request_base_url,
+    mastodon_api::{
+        accounts::types::Account,
+        auth::{get_current_user, BearerAuth},
+        errors::MastodonError,
+        media_server::ClientMediaServer,
+    },
+};
+
+use super::types::FeedFollowForm;
+
+const FEED_USERNAME_LENGTH_MAX: usize = 27;
+const FEED_USERNAME_ATTEMPTS: usize = 10;
+
+/// Derives username of a feed actor from the host name
+fn get_feed_username(feed_url: &HttpUri) -> String {
+    let hostname = feed_url.hostname().as_str().to_owned();
+    let username: String = hostname
+        .trim_start_matches("www.")
+        .chars()
+        .map(|char_| if char_.is_ascii_alphanumeric() { char_ } else { '_' })
+        .take(FEED_USERNAME_LENGTH_MAX)
+        .collect();
+    if username.is_empty() {
+        "feed".to_owned()
+    } else {
+        username
+    }
+}
+
+#[post("")]
+async fn follow_feed_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    feed_form: web::Json<FeedFollowForm>,
+) -> Result<HttpResponse, MastodonError> {
+    if !config.feeds.enabled {
+        return Err(MastodonError::NotSupported);
+    };
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let feed_url = HttpUri::parse(feed_form.url.trim())
+        .map_err(|_| ValidationError("invalid feed URL"))?;
+    let source = match get_feed_source_by_url(db_client, feed_url.as_str()).await {
+        Ok(source) => source,
+        Err(DatabaseError::NotFound(_)) => {
+            let feed_count = get_feed_source_count_by_creator(
+                db_client,
+                current_user.id,
+            ).await?;
+            if feed_count >= i64::from(config.feeds.user_feed_limit) {
+                return Err(MastodonError::OperationError("feed limit reached"));
+            };
+            if config.federation.ssrf_protection_enabled {
+                require_safe_url(feed_url.as_str())
+                    .map_err(|_| ValidationError("feed URL is not allowed"))?;
+            };
+            let feed = fetch_feed(&config.instance(), feed_url.as_str())
+                .await
+                .map_err(|error| {
+                    MastodonError::ValidationError(
+                        format!("failed to fetch feed: {error}"),
+                    )
+                })?;
+            let mut bio = String::new();
+            if let Some(ref title) = feed.title {
+                bio += &format!("<p>{}</p>", escape_html(title));
+            };
+            if let Some(ref description) = feed.description {
+                bio += &format!("<p>{}</p>", escape_html(description));
+            };
+            bio += &format!(
+                r#"<p><a href="{0}">{0}</a></p>"#,
+                escape_html(feed_url.as_str()),
+            );
+            let rsa_secret_key = match web::block(generate_rsa_key).await {
+                Ok(Ok(secret_key)) => secret_key,
+                Ok(Err(error)) => return Err(MastodonError::from_internal(error)),
+                Err(error) => return Err(MastodonError::from_internal(error)),
+            };
+            let ed25519_secret_key = generate_ed25519_key();
+            let base_username = get_feed_username(&feed_url);
+            let mut maybe_source = None;
+            // Feeds hosted on the same server get numbered usernames
+            for index in 1..=FEED_USERNAME_ATTEMPTS {
+                let username = if index == 1 {
+                    base_username.clone()
+                } else {
+                    format!("{base_username}_{index}")
+                };
+                let mut source_data = FeedSourceCreateData {
+                    username: username,
+                    bio: Some(bio.clone()),
+                    bio_source: None,
+                    feed_url: feed_url.as_str().to_owned(),
+                    fetch_interval: config.feeds.fetch_interval,
+                    entry_limit: config.feeds.entry_limit,
+                    created_by: Some(current_user.id),
+                    rsa_secret_key: rsa_secret_key.clone(),
+                    ed25519_secret_key: ed25519_secret_key,
+                };
+                clean_feed_source_create_data(&mut source_data);
+                validate_feed_source_create_data(&source_data)?;
+                match create_feed_source(db_client, source_data).await {
+                    Ok(source) => {
+                        maybe_source = Some(source);
+                        break;
+                    },
+                    Err(DatabaseError::AlreadyExists("user")) => continue,
+                    Err(other_error) => return Err(other_error.into()),
+                };
+            };
+            let source = maybe_source
+                .ok_or(MastodonError::OperationError("username is not available"))?;
+            create_or_update_local_actor(&config, db_client, &source.account).await?;
+            source
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
+    let authority = Authority::from(&config.instance());
+    if !has_relationship(
+        db_client,
+        current_user.id,
+        source.account.id,
+        RelationshipType::Follow,
+    ).await? {
+        let follow_request = create_follow_request(
+            db_client,
+            current_user.id,
+            source.account.id,
+        ).await?;
+        accept_and_add_follower(
+            authority.root(),
+            db_client,
+            follow_request.id,
+        ).await?;
+    };
+    let base_url = get_request_base_url(connection_info);
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let account = Account::from_profile(
+        &authority,
+        &media_server,
+        source.account.profile,
+    );
+    Ok(HttpResponse::Ok().json(account))
+}
+
+pub fn feed_api_scope() -> Scope {
+    web::scope("/v1/feeds")
+        .service(follow_feed_view)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_feed_username() {
+        let feed_url = HttpUri::parse("https://www.blog.example/feed.xml").unwrap();
+        let username = get_feed_username(&feed_url);
+        assert_eq!(username, "blog_example");
+    }
+}
@@ -5,14 +5,13 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 
 use mitra_models::{
     database::{get_database_client, DatabaseConnectionPool},
 };
 
 use crate::mastodon_api::{
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
 };
 
@@ -22,7 +21,7 @@ async fn filters_view(
     db_pool: web::Data<DatabaseConnectionPool>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    get_current_user(db_client, auth.token()).await?;
+    get_current_user(db_client, &auth).await?;
     let empty = serde_json::json!([]);
     Ok(HttpResponse::Ok().json(empty))
 }
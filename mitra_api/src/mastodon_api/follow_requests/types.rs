@@ -1,7 +1,11 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::mastodon_api::pagination::PageSize;
+use crate::mastodon_api::{
+    accounts::types::Account,
+    pagination::PageSize,
+    statuses::types::Status,
+};
 
 fn default_request_list_page_size() -> PageSize { PageSize::new(40) }
 
@@ -11,4 +15,27 @@ pub struct RequestListQueryParams {
 
     #[serde(default = "default_request_list_page_size")]
     pub limit: PageSize,
+
+    #[serde(default)]
+    pub with_statuses: bool,
+}
+
+/// Account that sent a follow request
+#[derive(Serialize)]
+pub struct FollowRequestAccount {
+    #[serde(flatten)]
+    pub account: Account,
+
+    /// Recent public posts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statuses: Option<Vec<Status>>,
+}
+
+fn default_notify() -> bool { true }
+
+#[derive(Deserialize)]
+pub struct RejectFollowRequestData {
+    /// Send Reject activity to the requester
+    #[serde(default = "default_notify")]
+    pub notify: bool,
 }
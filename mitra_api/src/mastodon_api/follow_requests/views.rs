@@ -4,11 +4,12 @@ use actix_web::{
     http::Uri,
     get,
     post,
+    put,
     web,
+    HttpRequest,
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 use uuid::Uuid;
 
 use mitra_activitypub::{
@@ -21,11 +22,16 @@ use mitra_activitypub::{
 };
 use mitra_config::Config;
 use mitra_models::{
+    accounts::{
+        queries::set_shared_client_config,
+        types::FollowRequestRules,
+    },
     database::{
         get_database_client,
         DatabaseConnectionPool,
         DatabaseError,
     },
+    posts::queries::get_posts_by_author,
     profiles::queries::get_profile_by_id,
     relationships::{
         queries::{
@@ -37,19 +43,31 @@ use mitra_models::{
     },
 };
 
-use crate::http::get_request_base_url;
+use mitra_validators::{
+    errors::ValidationError,
+    profiles::validate_hostname,
+};
+
+use crate::http::{get_language_preferences, get_request_base_url};
 use crate::mastodon_api::{
     accounts::{
         helpers::get_relationship,
         types::Account,
     },
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
     media_server::ClientMediaServer,
     pagination::{get_last_item, get_paginated_response},
+    statuses::helpers::build_status_list,
 };
 
-use super::types::RequestListQueryParams;
+use super::types::{
+    FollowRequestAccount,
+    RejectFollowRequestData,
+    RequestListQueryParams,
+};
+
+const RECENT_STATUSES_LIMIT: u16 = 3;
 
 #[get("")]
 async fn incoming_follow_request_list(
@@ -57,11 +75,12 @@ async fn incoming_follow_request_list(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     request_uri: Uri,
     query_params: web::Query<RequestListQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let profiles = get_follow_requests_paginated(
         db_client,
         current_user.id,
@@ -74,13 +93,43 @@ async fn incoming_follow_request_list(
     let base_url = get_request_base_url(connection_info);
     let authority = Authority::from(&config.instance());
     let media_server = ClientMediaServer::new(&config, &base_url);
-    let accounts: Vec<Account> = profiles.into_iter()
-        .map(|item| Account::from_profile(
+    let mut accounts = vec![];
+    for item in profiles {
+        let maybe_statuses = if query_params.with_statuses {
+            // Only public posts
+            let posts = get_posts_by_author(
+                db_client,
+                item.profile.id,
+                None,
+                false, // exclude replies
+                false, // exclude reposts
+                false,
+                false,
+                None,
+                RECENT_STATUSES_LIMIT,
+            ).await?;
+            let statuses = build_status_list(
+                db_client,
+                &authority,
+                &media_server,
+                Some(&current_user),
+                posts,
+                &get_language_preferences(&request),
+            ).await?;
+            Some(statuses)
+        } else {
+            None
+        };
+        let account = Account::from_profile(
             &authority,
             &media_server,
             item.profile,
-        ))
-        .collect();
+        );
+        accounts.push(FollowRequestAccount {
+            account,
+            statuses: maybe_statuses,
+        });
+    };
     let response = get_paginated_response(
         &base_url,
         &request_uri,
@@ -100,7 +149,7 @@ async fn outgoing_follow_request_list(
     query_params: web::Query<RequestListQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let profiles = get_follow_requests_paginated(
         db_client,
         current_user.id,
@@ -138,7 +187,7 @@ async fn accept_follow_request_view(
     account_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let source_profile = get_profile_by_id(db_client, *account_id).await?;
     let follow_request = get_follow_request_by_participants(
         db_client,
@@ -176,9 +225,13 @@ async fn reject_follow_request_view(
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
     account_id: web::Path<Uuid>,
+    reject_data: Option<web::Json<RejectFollowRequestData>>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let should_notify = reject_data
+        .map(|data| data.notify)
+        .unwrap_or(true);
     let source = get_profile_by_id(db_client, *account_id).await?;
     let follow_request = get_follow_request_by_participants(
         db_client,
@@ -186,7 +239,7 @@ async fn reject_follow_request_view(
         current_user.id,
     ).await?;
     follow_request_rejected(db_client, follow_request.id).await?;
-    if let Some(remote_actor) = source.actor_json {
+    if let Some(remote_actor) = source.actor_json.filter(|_| should_notify) {
         let activity_id = follow_request.activity_id
             .ok_or(DatabaseError::type_error())?;
         prepare_reject_follow(
@@ -204,8 +257,49 @@ async fn reject_follow_request_view(
     Ok(HttpResponse::Ok().json(relationship))
 }
 
+#[get("/rules")]
+async fn get_follow_request_rules(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let rules = current_user.shared_client_config.follow_request_rules;
+    Ok(HttpResponse::Ok().json(rules))
+}
+
+#[put("/rules")]
+async fn update_follow_request_rules(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    rules_data: web::Json<FollowRequestRules>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let mut rules = rules_data.into_inner();
+    for domain in rules.accept_domains.iter_mut() {
+        *domain = domain.trim().to_lowercase();
+        validate_hostname(domain)?;
+    };
+    rules.accept_domains.sort();
+    rules.accept_domains.dedup();
+    if rules.accept_known_for_days == Some(0) {
+        return Err(ValidationError("number of days must be positive").into());
+    };
+    let mut client_config = current_user.shared_client_config;
+    client_config.follow_request_rules = rules;
+    let client_config = set_shared_client_config(
+        db_client,
+        current_user.id,
+        client_config,
+    ).await?;
+    Ok(HttpResponse::Ok().json(client_config.follow_request_rules))
+}
+
 pub fn follow_request_api_scope() -> Scope {
     web::scope("/v1/follow_requests")
+        .service(get_follow_request_rules)
+        .service(update_follow_request_rules)
         .service(incoming_follow_request_list)
         .service(outgoing_follow_request_list)
         .service(accept_follow_request_view)
@@ -0,0 +1,47 @@
+use actix_web::{
+    get,
+    web,
+    HttpResponse,
+    Scope,
+};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use mitra_models::{
+    database::{
+        get_database_client,
+        DatabaseClient,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    properties::{
+        constants::FRONTEND_CONFIGURATIONS,
+        queries::get_internal_property,
+    },
+};
+
+use crate::mastodon_api::errors::MastodonError;
+
+pub async fn get_frontend_configurations(
+    db_client: &impl DatabaseClient,
+) -> Result<JsonMap<String, JsonValue>, DatabaseError> {
+    let configurations = get_internal_property(
+        db_client,
+        FRONTEND_CONFIGURATIONS,
+    ).await?.unwrap_or_default();
+    Ok(configurations)
+}
+
+/// Pleroma-compatible frontend configuration endpoint
+#[get("")]
+async fn frontend_configurations_view(
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let configurations = get_frontend_configurations(db_client).await?;
+    Ok(HttpResponse::Ok().json(configurations))
+}
+
+pub fn frontend_configuration_api_scope() -> Scope {
+    web::scope("/pleroma/frontend_configurations")
+        .service(frontend_configurations_view)
+}
@@ -8,7 +8,6 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 use apx_sdk::core::crypto::{
     eddsa::generate_ed25519_key,
     rsa::generate_rsa_key,
@@ -72,7 +71,7 @@ use crate::{
             },
             types::Account,
         },
-        auth::get_current_user,
+        auth::{get_current_user, BearerAuth},
         errors::MastodonError,
         media_server::ClientMediaServer,
     },
@@ -94,7 +93,7 @@ async fn create_group_view(
     group_form: web::Json<GroupCreateForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     if !can_create_post(&current_user) {
         return Err(MastodonError::PermissionError);
     };
@@ -158,7 +157,7 @@ async fn get_followed_groups_view(
     query_params: web::Query<GroupListQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let groups = get_related_groups(
         db_client,
         current_user.id,
@@ -186,7 +185,7 @@ async fn get_group_source(
     group_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let group = get_group_account_by_id(db_client, *group_id).await?;
     if !has_relationship(
         db_client,
@@ -212,7 +211,7 @@ async fn update_group_view(
     group_form: web::Json<GroupUpdateForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let mut group = get_group_account_by_id(db_client, *group_id).await?;
     if !has_relationship(
         db_client,
@@ -272,7 +271,7 @@ async fn delete_group_view(
     group_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let group = get_group_account_by_id(db_client, *group_id).await?;
     if !has_relationship(
         db_client,
@@ -0,0 +1,46 @@
+use serde::Deserialize;
+
+use mitra_models::data_imports::types::ImportType;
+use mitra_validators::errors::ValidationError;
+
+#[derive(Deserialize)]
+pub struct ImportRequest {
+    #[serde(rename = "type")]
+    import_type: String,
+    pub data: String,
+}
+
+impl ImportRequest {
+    pub fn import_type(&self) -> Result<ImportType, ValidationError> {
+        let import_type = match self.import_type.as_str() {
+            "following" => ImportType::Follows,
+            "muting" => ImportType::Mutes,
+            "blocking" => ImportType::Blocks,
+            _ => return Err(ValidationError("unsupported import type")),
+        };
+        Ok(import_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_request_import_type() {
+        let request = ImportRequest {
+            import_type: "following".to_string(),
+            data: "user@social.example".to_string(),
+        };
+        assert_eq!(request.import_type().unwrap(), ImportType::Follows);
+    }
+
+    #[test]
+    fn test_import_request_import_type_unsupported() {
+        let request = ImportRequest {
+            import_type: "bookmarks".to_string(),
+            data: "".to_string(),
+        };
+        assert_eq!(request.import_type().is_err(), true);
+    }
+}
@@ -0,0 +1,74 @@
+use actix_web::{get, post, web, HttpResponse, Scope};
+
+use uuid::Uuid;
+
+use mitra_models::{
+    data_imports::queries::{create_data_import, get_data_import},
+    database::{get_database_client, DatabaseConnectionPool},
+};
+use mitra_workers::importer::ImporterJobData;
+
+use crate::{
+    http::JsonOrForm,
+    mastodon_api::{
+        auth::{get_current_user, BearerAuth},
+        errors::MastodonError,
+        settings::{
+            helpers::parse_address_list,
+            types::DataImport,
+        },
+    },
+};
+
+use super::types::ImportRequest;
+
+/// Entries are processed in background.
+/// Import status can be retrieved with `/api/v1/import/{import_id}`.
+#[post("")]
+async fn create_import_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request_data: JsonOrForm<ImportRequest>,
+) -> Result<HttpResponse, MastodonError> {
+    let request_data = request_data.into_inner();
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let import_type = request_data.import_type()?;
+    let address_list = parse_address_list(&request_data.data)?
+        .iter()
+        .map(|address| address.to_string())
+        .collect();
+    let db_import = create_data_import(
+        db_client,
+        current_user.id,
+        import_type,
+        address_list,
+    ).await?;
+    ImporterJobData::for_data_import(&db_import)
+        .into_job(db_client).await?;
+    let data_import = DataImport::from_db(db_import);
+    Ok(HttpResponse::Ok().json(data_import))
+}
+
+#[get("/{import_id}")]
+async fn get_import_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    import_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let db_import = get_data_import(
+        db_client,
+        current_user.id,
+        *import_id,
+    ).await?;
+    let data_import = DataImport::from_db(db_import);
+    Ok(HttpResponse::Ok().json(data_import))
+}
+
+pub fn import_api_scope() -> Scope {
+    web::scope("/v1/import")
+        .service(create_import_view)
+        .service(get_import_view)
+}
@@ -1,3 +1,5 @@
+use std::cmp::min;
+
 use apx_sdk::core::crypto::hashes::sha256;
 use serde::Serialize;
 
@@ -5,6 +7,15 @@ use mitra_activitypub::authority::Authority;
 use mitra_adapters::{
     dynamic_config::DynamicConfig,
     payments::subscriptions::MONERO_PAYMENT_AMOUNT_MIN,
+    profiles::{
+        bio_length_limit,
+        field_local_limit,
+        field_name_length_limit,
+        field_remote_limit,
+        field_value_length_limit,
+    },
+    reactions::ReactionPolicy,
+    roles::{get_role_post_limits, get_user_storage_quota, RoleLimitsMap},
 };
 use mitra_config::{
     AuthenticationMethod,
@@ -13,27 +24,27 @@ use mitra_config::{
     DefaultRole,
     MoneroConfig,
     MoneroLightConfig,
+    PostLimits,
+    ProfileLimits,
     RegistrationType,
     SoftwareMetadata,
 };
-use mitra_models::accounts::types::User;
+use mitra_models::{
+    accounts::types::User,
+    server_rules::types::ServerRule,
+};
+use mitra_services::push::get_vapid_public_key;
 use mitra_utils::markdown::markdown_to_html;
 use mitra_validators::{
     polls::{POLL_OPTION_COUNT_MAX, POLL_OPTION_NAME_LENGTH_MAX},
-    profiles::{
-        BIO_MAX_LENGTH,
-        DISPLAY_NAME_MAX_LENGTH,
-        FIELD_LOCAL_LIMIT,
-        FIELD_NAME_LENGTH_MAX,
-        FIELD_REMOTE_LIMIT,
-        FIELD_VALUE_LENGTH_MAX,
-    },
+    profiles::DISPLAY_NAME_MAX_LENGTH,
 };
 
 use crate::mastodon_api::{
     accounts::types::{
         Account,
         AUTHENTICATION_METHOD_CAIP122_MONERO,
+        AUTHENTICATION_METHOD_DID,
         AUTHENTICATION_METHOD_PASSWORD,
         AUTHENTICATION_METHOD_EIP4361,
     },
@@ -62,13 +73,13 @@ struct AccountLimits {
 }
 
 impl AccountLimits {
-    fn new() -> Self {
+    fn new(limits: &ProfileLimits) -> Self {
         Self {
             max_display_name_length: DISPLAY_NAME_MAX_LENGTH,
-            max_note_length: BIO_MAX_LENGTH,
-            max_profile_fields: FIELD_LOCAL_LIMIT,
-            profile_field_name_limit: FIELD_NAME_LENGTH_MAX,
-            profile_field_value_limit: FIELD_VALUE_LENGTH_MAX,
+            max_note_length: bio_length_limit(limits),
+            max_profile_fields: field_local_limit(limits),
+            profile_field_name_limit: field_name_length_limit(limits),
+            profile_field_value_limit: field_value_length_limit(limits),
         }
     }
 }
@@ -83,6 +94,8 @@ struct StatusLimits {
 struct MediaLimits {
     supported_mime_types: Vec<String>,
     image_size_limit: usize,
+    // Storage quota of the current user
+    storage_quota: Option<u64>,
 }
 
 #[derive(Serialize)]
@@ -94,10 +107,16 @@ struct PollLimits {
 }
 
 impl PollLimits {
-    fn new() -> Self {
+    fn new(limits: &PostLimits) -> Self {
         Self {
-            max_options: POLL_OPTION_COUNT_MAX,
-            max_characters_per_option: POLL_OPTION_NAME_LENGTH_MAX,
+            max_options: min(
+                limits.poll_option_local_limit,
+                POLL_OPTION_COUNT_MAX,
+            ),
+            max_characters_per_option: min(
+                limits.poll_option_length_local_limit,
+                POLL_OPTION_NAME_LENGTH_MAX,
+            ),
             min_expiration: 0,
             // Not using u32::MAX to avoid integer overflow in clients
             max_expiration: 2_u32.pow(30),
@@ -118,6 +137,7 @@ fn map_authentication_method(method: &AuthenticationMethod) -> String {
         AuthenticationMethod::Password => AUTHENTICATION_METHOD_PASSWORD,
         AuthenticationMethod::Eip4361 => AUTHENTICATION_METHOD_EIP4361,
         AuthenticationMethod::Caip122Monero => AUTHENTICATION_METHOD_CAIP122_MONERO,
+        AuthenticationMethod::Did => AUTHENTICATION_METHOD_DID,
     };
     value.to_string()
 }
@@ -222,7 +242,7 @@ struct PleromaMetadata {
 }
 
 impl PleromaMetadata {
-    fn new() -> Self {
+    fn new(limits: &ProfileLimits) -> Self {
         Self {
             features: [
                 "quote_posting",
@@ -230,10 +250,10 @@ impl PleromaMetadata {
                 "pleroma_custom_emoji_reactions",
             ],
             fields_limits: PleromaFieldsLimits {
-                max_fields: FIELD_LOCAL_LIMIT,
-                max_remote_fields: FIELD_REMOTE_LIMIT,
-                name_length: FIELD_NAME_LENGTH_MAX,
-                value_length: FIELD_VALUE_LENGTH_MAX,
+                max_fields: field_local_limit(limits),
+                max_remote_fields: field_remote_limit(limits),
+                name_length: field_name_length_limit(limits),
+                value_length: field_value_length_limit(limits),
             },
             post_formats: [
                 POST_CONTENT_TYPE_HTML,
@@ -256,6 +276,7 @@ pub struct InstanceInfo {
     short_description: String,
     description: String,
     version: String,
+    thumbnail: Option<String>,
     registrations: bool,
     approval_required: bool,
     invites_enabled: bool,
@@ -276,6 +297,8 @@ pub struct InstanceInfo {
     // Custom field, used by Husky
     max_toot_chars: usize,
 
+    rules: Vec<Rule>,
+
     pleroma: PleromaInfo,
 }
 
@@ -288,22 +311,51 @@ fn get_full_api_version(software: SoftwareMetadata) -> String {
     )
 }
 
+/// Returns post limits for the current user
+fn get_user_post_limits(
+    limits: &PostLimits,
+    role_limits: &RoleLimitsMap,
+    maybe_current_user: Option<&User>,
+) -> PostLimits {
+    match maybe_current_user {
+        Some(user) => get_role_post_limits(limits, role_limits, user.role),
+        None => limits.clone(),
+    }
+}
+
 impl InstanceInfo {
+    #[expect(clippy::too_many_arguments)]
     pub fn create(
         config: &Config,
         dynamic_config: DynamicConfig,
         media_server: &ClientMediaServer,
         maybe_admin: Option<User>,
+        rules: Vec<ServerRule>,
         user_count: i64,
         post_count: i64,
         peer_count: i64,
+        maybe_current_user: Option<&User>,
     ) -> Self {
+        let limits = config.limits();
+        let post_limits = &get_user_post_limits(
+            &limits.posts,
+            &dynamic_config.role_limits,
+            maybe_current_user,
+        );
+        let maybe_storage_quota = maybe_current_user.and_then(|user| {
+            get_user_storage_quota(&dynamic_config.role_limits, user)
+        });
+        let short_description = dynamic_config.instance_description
+            .unwrap_or(config.instance_short_description.clone());
+        let description = dynamic_config.instance_extended_description
+            .unwrap_or(config.instance_description.clone());
         Self {
             uri: config.instance().webfinger_hostname(),
             title: config.instance_title.clone(),
-            short_description: config.instance_short_description.clone(),
-            description: markdown_to_html(&config.instance_description),
+            short_description: short_description,
+            description: markdown_to_html(&description),
             version: get_full_api_version(config.software),
+            thumbnail: dynamic_config.instance_thumbnail,
             registrations:
                 config.registration.registration_type !=
                 RegistrationType::Invite,
@@ -317,18 +369,19 @@ impl InstanceInfo {
                 domain_count: peer_count,
             },
             configuration: Configuration {
-                accounts: AccountLimits::new(),
+                accounts: AccountLimits::new(&limits.profiles),
                 statuses: StatusLimits {
-                    max_characters: config.limits.posts.character_limit,
-                    max_media_attachments: config.limits.posts.attachment_local_limit,
+                    max_characters: post_limits.character_limit,
+                    max_media_attachments: post_limits.attachment_local_limit,
                 },
                 media_attachments: MediaLimits {
-                    supported_mime_types: config.limits.media
+                    supported_mime_types: limits.media
                         .supported_media_types().iter()
                         .map(|media_type| media_type.to_string()).collect(),
-                    image_size_limit: config.limits.media.file_size_limit,
+                    image_size_limit: limits.media.file_size_limit,
+                    storage_quota: maybe_storage_quota,
                 },
-                polls: PollLimits::new(),
+                polls: PollLimits::new(post_limits),
             },
             contact_account: maybe_admin.map(|user| Account::from_profile(
                 &Authority::from(&config.instance()),
@@ -349,14 +402,20 @@ impl InstanceInfo {
                 .map(BlockchainInfo::from)
                 .collect(),
             ipfs_gateway_url: config.ipfs_gateway_url.clone(),
-            max_toot_chars: config.limits.posts.character_limit,
+            max_toot_chars: post_limits.character_limit,
+            rules: rules.into_iter().map(Rule::from).collect(),
             pleroma: PleromaInfo {
-                metadata: PleromaMetadata::new(),
+                metadata: PleromaMetadata::new(&limits.profiles),
             },
         }
     }
 }
 
+#[derive(Serialize)]
+struct Thumbnail {
+    url: String,
+}
+
 #[derive(Serialize)]
 struct UsageUsers {
     active_month: i64,
@@ -374,6 +433,7 @@ struct ConfigurationV2 {
     media_attachments: MediaLimits,
     polls: PollLimits,
     timelines_access: TimelinesAccess,
+    vapid: VapidInfo,
 }
 
 #[derive(Serialize)]
@@ -389,6 +449,11 @@ struct Contact {
     account: Option<Account>,
 }
 
+#[derive(Serialize)]
+struct VapidInfo {
+    public_key: String,
+}
+
 #[derive(Serialize)]
 struct TimelineAccess {
     local: String,
@@ -411,6 +476,7 @@ pub struct InstanceInfoV2 {
     extended_description: String,
     version: String,
     source_url: String,
+    thumbnail: Option<Thumbnail>,
     usage: Usage,
     configuration: ConfigurationV2,
     registrations: Registrations,
@@ -421,8 +487,11 @@ pub struct InstanceInfoV2 {
     new_accounts_read_only: bool,
     like_emoji: String,
     favorite_emojis: Vec<String>,
+    reaction_policy: ReactionPolicy,
+    reaction_allowlist: Vec<String>,
     blockchains: Vec<BlockchainInfo>,
     ipfs_gateway_url: Option<String>,
+    rules: Vec<Rule>,
 
     pleroma: PleromaInfo,
 }
@@ -433,33 +502,51 @@ impl InstanceInfoV2 {
         dynamic_config: DynamicConfig,
         media_server: &ClientMediaServer,
         maybe_admin: Option<User>,
+        rules: Vec<ServerRule>,
         user_count_active_month: i64,
+        maybe_current_user: Option<&User>,
     ) -> Self {
+        let limits = config.limits();
+        let post_limits = &get_user_post_limits(
+            &limits.posts,
+            &dynamic_config.role_limits,
+            maybe_current_user,
+        );
+        let maybe_storage_quota = maybe_current_user.and_then(|user| {
+            get_user_storage_quota(&dynamic_config.role_limits, user)
+        });
+        let description = dynamic_config.instance_description
+            .unwrap_or(config.instance_short_description.clone());
+        let extended_description = dynamic_config.instance_extended_description
+            .unwrap_or(config.instance_description.clone());
         Self {
             domain: config.instance().webfinger_hostname(),
             title: config.instance_title.clone(),
-            description: config.instance_short_description.clone(),
-            extended_description: markdown_to_html(&config.instance_description),
+            description: description,
+            extended_description: markdown_to_html(&extended_description),
             version: get_full_api_version(config.software),
             source_url: config.software.repository.to_owned(),
+            thumbnail: dynamic_config.instance_thumbnail
+                .map(|url| Thumbnail { url }),
             usage: Usage {
                 users: UsageUsers {
                     active_month: user_count_active_month,
                 },
             },
             configuration: ConfigurationV2 {
-                accounts: AccountLimits::new(),
+                accounts: AccountLimits::new(&limits.profiles),
                 statuses: StatusLimits {
-                    max_characters: config.limits.posts.character_limit,
-                    max_media_attachments: config.limits.posts.attachment_local_limit,
+                    max_characters: post_limits.character_limit,
+                    max_media_attachments: post_limits.attachment_local_limit,
                 },
                 media_attachments: MediaLimits {
-                    supported_mime_types: config.limits.media
+                    supported_mime_types: limits.media
                         .supported_media_types().iter()
                         .map(|media_type| media_type.to_string()).collect(),
-                    image_size_limit: config.limits.media.file_size_limit,
+                    image_size_limit: limits.media.file_size_limit,
+                    storage_quota: maybe_storage_quota,
                 },
-                polls: PollLimits::new(),
+                polls: PollLimits::new(post_limits),
                 timelines_access: TimelinesAccess {
                     live_feeds: TimelineAccess {
                         local:
@@ -484,6 +571,10 @@ impl InstanceInfoV2 {
                         remote: "disabled".to_string(),
                     },
                 },
+                vapid: VapidInfo {
+                    public_key: get_vapid_public_key(&config.instance_vapid_key())
+                        .expect("instance VAPID key should be valid"),
+                },
             },
             registrations: Registrations {
                 enabled:
@@ -508,17 +599,38 @@ impl InstanceInfoV2 {
                 matches!(config.registration.default_role, DefaultRole::ReadOnlyUser),
             like_emoji: dynamic_config.like_emoji,
             favorite_emojis: dynamic_config.favorite_emojis,
+            reaction_policy: dynamic_config.reaction_policy,
+            reaction_allowlist: dynamic_config.reaction_allowlist,
             blockchains: config.blockchains().iter()
                 .map(BlockchainInfo::from)
                 .collect(),
             ipfs_gateway_url: config.ipfs_gateway_url.clone(),
+            rules: rules.into_iter().map(Rule::from).collect(),
             pleroma: PleromaInfo {
-                metadata: PleromaMetadata::new(),
+                metadata: PleromaMetadata::new(&limits.profiles),
             },
         }
     }
 }
 
+// https://docs.joinmastodon.org/entities/Rule/
+#[derive(Serialize)]
+pub struct Rule {
+    id: String,
+    text: String,
+    hint: String,
+}
+
+impl From<ServerRule> for Rule {
+    fn from(db_rule: ServerRule) -> Self {
+        Self {
+            id: db_rule.id.to_string(),
+            text: db_rule.rule_text,
+            hint: db_rule.rule_hint,
+        }
+    }
+}
+
 // https://docs.joinmastodon.org/entities/DomainBlock/
 #[derive(Serialize)]
 pub struct DomainBlock {
@@ -551,6 +663,7 @@ mod tests {
             chain_id: ChainId::monero_mainnet(),
             chain_metadata: None,
             wallet_rpc_url: "http://127.0.0.1:18083".to_owned(),
+            wallet_rpc_fallback_urls: vec![],
             wallet_rpc_username: None,
             wallet_rpc_password: None,
             wallet_name: None,
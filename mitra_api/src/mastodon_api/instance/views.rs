@@ -5,14 +5,15 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 
-use mitra_adapters::dynamic_config::get_dynamic_config;
+use mitra_adapters::{
+    dynamic_config::get_dynamic_config,
+    instance::get_instance_contact,
+};
 use mitra_config::Config;
 use mitra_models::{
     accounts::queries::{
         get_active_user_count,
-        get_admin_user,
         get_user_count,
     },
     database::{get_database_client, DatabaseConnectionPool},
@@ -22,12 +23,13 @@ use mitra_models::{
     },
     instances::queries::{get_peers, get_peer_count},
     posts::queries::get_post_count,
+    server_rules::queries::get_server_rules,
 };
 use mitra_utils::datetime::days_before_now;
 
 use crate::http::get_request_base_url;
 use crate::mastodon_api::{
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
     media_server::ClientMediaServer,
 };
@@ -36,25 +38,32 @@ use super::types::{
     DomainBlock,
     InstanceInfo,
     InstanceInfoV2,
+    Rule,
 };
 
 // https://docs.joinmastodon.org/methods/instance/#v1
 #[get("")]
 async fn instance_view(
+    auth: Option<BearerAuth>,
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let maybe_admin = if config.instance_staff_public {
-        get_admin_user(db_client).await?
-    } else {
-        None
+    let maybe_current_user = match auth {
+        Some(auth) => Some(get_current_user(db_client, &auth).await?),
+        None => None,
     };
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let maybe_admin = get_instance_contact(
+        &config,
+        &dynamic_config,
+        db_client,
+    ).await?;
     let user_count = get_user_count(db_client).await?;
     let post_count = get_post_count(db_client, true).await?;
     let peer_count = get_peer_count(db_client).await?;
-    let dynamic_config = get_dynamic_config(db_client).await?;
+    let rules = get_server_rules(db_client).await?;
     let base_url = get_request_base_url(connection_info);
     let media_server = ClientMediaServer::new(&config, &base_url);
     let instance = InstanceInfo::create(
@@ -62,9 +71,11 @@ async fn instance_view(
         dynamic_config,
         &media_server,
         maybe_admin,
+        rules,
         user_count,
         post_count,
         peer_count,
+        maybe_current_user.as_ref(),
     );
     Ok(HttpResponse::Ok().json(instance))
 }
@@ -79,6 +90,19 @@ async fn instance_peers_view(
     Ok(HttpResponse::Ok().json(peers))
 }
 
+// https://docs.joinmastodon.org/methods/instance/#rules
+#[get("/rules")]
+async fn rules_view(
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let rules: Vec<Rule> = get_server_rules(db_client).await?
+        .into_iter()
+        .map(Rule::from)
+        .collect();
+    Ok(HttpResponse::Ok().json(rules))
+}
+
 // https://docs.joinmastodon.org/methods/instance/#domain_blocks
 #[get("/domain_blocks")]
 async fn domain_blocks_view(
@@ -90,7 +114,7 @@ async fn domain_blocks_view(
     if !dynamic_config.filter_blocklist_public {
         let auth = maybe_auth
             .ok_or(MastodonError::AuthError("authentication required"))?;
-        get_current_user(db_client, auth.token()).await?;
+        get_current_user(db_client, &auth).await?;
     };
     let filter_rules = get_filter_rules(db_client).await?;
     let domain_blocks: Vec<_> = filter_rules
@@ -110,26 +134,33 @@ pub fn instance_api_v1_scope() -> Scope {
     web::scope("/v1/instance")
         .service(instance_view)
         .service(instance_peers_view)
+        .service(rules_view)
         .service(domain_blocks_view)
 }
 
 #[get("")]
 async fn instance_v2_view(
+    auth: Option<BearerAuth>,
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let dynamic_config = get_dynamic_config(db_client).await?;
-    let maybe_admin = if config.instance_staff_public {
-        get_admin_user(db_client).await?
-    } else {
-        None
+    let maybe_current_user = match auth {
+        Some(auth) => Some(get_current_user(db_client, &auth).await?),
+        None => None,
     };
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let maybe_admin = get_instance_contact(
+        &config,
+        &dynamic_config,
+        db_client,
+    ).await?;
     let user_count_active_month = get_active_user_count(
         db_client,
         days_before_now(28), // 4 weeks
     ).await?;
+    let rules = get_server_rules(db_client).await?;
     let base_url = get_request_base_url(connection_info);
     let media_server = ClientMediaServer::new(&config, &base_url);
     let instance = InstanceInfoV2::create(
@@ -137,7 +168,9 @@ async fn instance_v2_view(
         dynamic_config,
         &media_server,
         maybe_admin,
+        rules,
         user_count_active_month,
+        maybe_current_user.as_ref(),
     );
     Ok(HttpResponse::Ok().json(instance))
 }
@@ -16,6 +16,11 @@ pub struct List {
     title: String,
     replies_policy: String,
     exclusive: bool,
+
+    // Custom fields
+    owner_id: Uuid,
+    shared: bool,
+    actor_id: Option<Uuid>,
 }
 
 impl List {
@@ -27,6 +32,9 @@ impl List {
             replies_policy: "followed".to_string(),
             // All custom feeds are "exclusive"
             exclusive: true,
+            owner_id: db_feed.owner_id,
+            shared: db_feed.is_shared,
+            actor_id: db_feed.actor_id,
         }
     }
 }
@@ -34,6 +42,8 @@ impl List {
 #[derive(Deserialize)]
 pub struct ListForm {
     pub title: String,
+    // Custom field
+    pub shared: Option<bool>,
 }
 
 fn default_list_accounts_page_size() -> PageSize { PageSize::new(40) }
@@ -51,3 +61,13 @@ pub struct ListAccountsQueryParams {
 pub struct ListAccountsForm {
     pub account_ids: Vec<Uuid>,
 }
+
+#[derive(Deserialize)]
+pub struct SharedListsQueryParams {
+    pub account_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct ListPublishForm {
+    pub username: String,
+}
@@ -9,10 +9,16 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
+use apx_sdk::core::crypto::{
+    eddsa::generate_ed25519_key,
+    rsa::generate_rsa_key,
+};
 use serde_json;
 
-use mitra_activitypub::authority::Authority;
+use mitra_activitypub::{
+    adapters::users::{create_or_update_local_actor, delete_account},
+    authority::Authority,
+};
 use mitra_config::Config;
 use mitra_models::{
     database::{
@@ -20,20 +26,40 @@ use mitra_models::{
         DatabaseConnectionPool,
         DatabaseError,
     },
-    custom_feeds::queries::{
-        add_custom_feed_sources,
-        create_custom_feed,
-        delete_custom_feed,
-        get_custom_feed,
-        get_custom_feeds,
-        get_custom_feed_sources,
-        remove_custom_feed_sources,
-        update_custom_feed,
+    custom_feeds::{
+        queries::{
+            add_custom_feed_sources,
+            create_custom_feed,
+            create_custom_feed_actor,
+            delete_custom_feed,
+            get_custom_feed,
+            get_custom_feed_actor,
+            get_custom_feeds,
+            get_custom_feed_sources,
+            get_readable_custom_feed,
+            get_shared_custom_feed,
+            get_shared_custom_feeds,
+            get_subscribed_custom_feeds,
+            remove_custom_feed_sources,
+            set_custom_feed_shared,
+            subscribe_to_custom_feed,
+            unsubscribe_from_custom_feed,
+            update_custom_feed,
+        },
+        types::CustomFeedActorData,
+    },
+    relationships::{
+        queries::has_relationship,
+        types::RelationshipType,
     },
 };
-use mitra_validators::custom_feeds::{
-    clean_custom_feed_name,
-    validate_custom_feed_name,
+use mitra_utils::html::escape_html;
+use mitra_validators::{
+    accounts::validate_local_username,
+    custom_feeds::{
+        clean_custom_feed_name,
+        validate_custom_feed_name,
+    },
 };
 
 use crate::{
@@ -45,7 +71,7 @@ use crate::{
     },
     mastodon_api::{
         accounts::types::Account,
-        auth::get_current_user,
+        auth::{get_current_user, BearerAuth},
         errors::MastodonError,
         media_server::ClientMediaServer,
         pagination::PageSize,
@@ -57,6 +83,8 @@ use super::types::{
     ListAccountsForm,
     ListAccountsQueryParams,
     ListForm,
+    ListPublishForm,
+    SharedListsQueryParams,
 };
 
 /// https://docs.joinmastodon.org/methods/lists/#get
@@ -66,7 +94,7 @@ async fn get_lists(
     db_pool: web::Data<DatabaseConnectionPool>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let feeds = get_custom_feeds(db_client, current_user.id).await?;
     let lists: Vec<List> = feeds.into_iter().map(List::from_db).collect();
     Ok(HttpResponse::Ok().json(lists))
@@ -81,14 +109,22 @@ async fn create_list(
 ) -> Result<HttpResponse, MastodonError> {
     let list_form = list_form.into_inner();
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let feed_name = clean_custom_feed_name(&list_form.title);
     validate_custom_feed_name(feed_name)?;
-    let feed = create_custom_feed(
+    let mut feed = create_custom_feed(
         db_client,
         current_user.id,
         feed_name,
     ).await?;
+    if list_form.shared == Some(true) {
+        feed = set_custom_feed_shared(
+            db_client,
+            feed.id,
+            current_user.id,
+            true,
+        ).await?;
+    };
     let list = List::from_db(feed);
     Ok(HttpResponse::Ok().json(list))
 }
@@ -101,8 +137,8 @@ async fn get_list(
     list_id: web::Path<i32>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
-    let feed = get_custom_feed(
+    let current_user = get_current_user(db_client, &auth).await?;
+    let feed = get_readable_custom_feed(
         db_client,
         *list_id,
         current_user.id,
@@ -120,15 +156,23 @@ async fn update_list(
     list_form: web::Json<ListForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let feed_name = clean_custom_feed_name(&list_form.title);
     validate_custom_feed_name(feed_name)?;
-    let feed = update_custom_feed(
+    let mut feed = update_custom_feed(
         db_client,
         *list_id,
         current_user.id,
         feed_name,
     ).await?;
+    if let Some(is_shared) = list_form.shared {
+        feed = set_custom_feed_shared(
+            db_client,
+            feed.id,
+            current_user.id,
+            is_shared,
+        ).await?;
+    };
     let list = List::from_db(feed);
     Ok(HttpResponse::Ok().json(list))
 }
@@ -137,16 +181,30 @@ async fn update_list(
 #[delete("/{list_id}")]
 async fn delete_list(
     auth: BearerAuth,
+    config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
     list_id: web::Path<i32>,
 ) -> Result<HttpResponse, MastodonError> {
-    let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
-    delete_custom_feed(
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let feed = get_custom_feed(
         db_client,
         *list_id,
         current_user.id,
     ).await?;
+    if feed.actor_id.is_some() {
+        let feed_actor = get_custom_feed_actor(
+            db_client,
+            feed.id,
+            current_user.id,
+        ).await?;
+        delete_account(&config, db_client, &feed_actor.account).await?;
+    };
+    delete_custom_feed(
+        db_client,
+        feed.id,
+        current_user.id,
+    ).await?;
     let empty = serde_json::json!({});
     Ok(HttpResponse::Ok().json(empty))
 }
@@ -162,8 +220,8 @@ async fn get_list_accounts(
     query_params: web::Query<ListAccountsQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
-    let feed = get_custom_feed(
+    let current_user = get_current_user(db_client, &auth).await?;
+    let feed = get_readable_custom_feed(
         db_client,
         *list_id,
         current_user.id,
@@ -205,7 +263,7 @@ async fn add_accounts_to_list(
         Either::Right(form) => form.into_inner(),
     };
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let feed = get_custom_feed(
         db_client,
         *list_id,
@@ -235,7 +293,7 @@ async fn remove_accounts_from_list(
     query_params: MultiQuery<ListAccountsForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let feed = get_custom_feed(
         db_client,
         *list_id,
@@ -250,14 +308,202 @@ async fn remove_accounts_from_list(
     Ok(HttpResponse::Ok().json(empty))
 }
 
+#[get("/subscriptions")]
+async fn get_list_subscriptions(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let feeds = get_subscribed_custom_feeds(db_client, current_user.id).await?;
+    let lists: Vec<List> = feeds.into_iter().map(List::from_db).collect();
+    Ok(HttpResponse::Ok().json(lists))
+}
+
+/// Shared feeds are visible only to followers of their owner
+#[get("/shared")]
+async fn get_shared_lists(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    query_params: web::Query<SharedListsQueryParams>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if query_params.account_id != current_user.id && !has_relationship(
+        db_client,
+        current_user.id,
+        query_params.account_id,
+        RelationshipType::Follow,
+    ).await? {
+        return Err(MastodonError::PermissionError);
+    };
+    let feeds =
+        get_shared_custom_feeds(db_client, query_params.account_id).await?;
+    let lists: Vec<List> = feeds.into_iter().map(List::from_db).collect();
+    Ok(HttpResponse::Ok().json(lists))
+}
+
+#[post("/{list_id}/subscribe")]
+async fn subscribe_to_list(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    list_id: web::Path<i32>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let feed = get_shared_custom_feed(db_client, *list_id).await?;
+    if feed.owner_id == current_user.id {
+        return Err(MastodonError::OperationError("can't subscribe to own list"));
+    };
+    if !has_relationship(
+        db_client,
+        current_user.id,
+        feed.owner_id,
+        RelationshipType::Follow,
+    ).await? {
+        return Err(MastodonError::PermissionError);
+    };
+    match subscribe_to_custom_feed(
+        db_client,
+        feed.id,
+        current_user.id,
+    ).await {
+        Ok(_) => (),
+        Err(DatabaseError::AlreadyExists(_)) => (), // already subscribed
+        Err(other_error) => return Err(other_error.into()),
+    };
+    let list = List::from_db(feed);
+    Ok(HttpResponse::Ok().json(list))
+}
+
+#[post("/{list_id}/unsubscribe")]
+async fn unsubscribe_from_list(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    list_id: web::Path<i32>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    unsubscribe_from_custom_feed(
+        db_client,
+        *list_id,
+        current_user.id,
+    ).await?;
+    let empty = serde_json::json!({});
+    Ok(HttpResponse::Ok().json(empty))
+}
+
+/// Creates an actor that can be followed by remote users.
+/// The actor reposts public posts from the list.
+#[post("/{list_id}/publish")]
+async fn publish_list(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    list_id: web::Path<i32>,
+    publish_form: web::Json<ListPublishForm>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let feed = get_custom_feed(
+        db_client,
+        *list_id,
+        current_user.id,
+    ).await?;
+    validate_local_username(&publish_form.username)?;
+    let rsa_secret_key = match web::block(generate_rsa_key).await {
+        Ok(Ok(secret_key)) => secret_key,
+        Ok(Err(error)) => return Err(MastodonError::from_internal(error)),
+        Err(error) => return Err(MastodonError::from_internal(error)),
+    };
+    let bio = format!(
+        "<p>{}</p><p>Curated by @{}</p>",
+        escape_html(&feed.feed_name),
+        escape_html(&current_user.profile.username),
+    );
+    let actor_data = CustomFeedActorData {
+        username: publish_form.username.clone(),
+        bio: Some(bio),
+        rsa_secret_key: rsa_secret_key,
+        ed25519_secret_key: generate_ed25519_key(),
+    };
+    let feed_actor = match create_custom_feed_actor(
+        db_client,
+        feed.id,
+        current_user.id,
+        actor_data,
+    ).await {
+        Ok(feed_actor) => feed_actor,
+        Err(DatabaseError::AlreadyExists("custom feed actor")) => {
+            return Err(MastodonError::OperationError("list is already published"));
+        },
+        Err(DatabaseError::AlreadyExists(_)) => {
+            return Err(MastodonError::OperationError("username is not available"));
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
+    create_or_update_local_actor(&config, db_client, &feed_actor.account).await?;
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let account = Account::from_profile(
+        &authority,
+        &media_server,
+        feed_actor.account.profile,
+    );
+    Ok(HttpResponse::Ok().json(account))
+}
+
+/// Deletes the actor created by publish_list
+#[post("/{list_id}/unpublish")]
+async fn unpublish_list(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    list_id: web::Path<i32>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let feed_actor = match get_custom_feed_actor(
+        db_client,
+        *list_id,
+        current_user.id,
+    ).await {
+        Ok(feed_actor) => feed_actor,
+        Err(DatabaseError::NotFound(_)) => {
+            // Returns NotFound if feed doesn't exist
+            get_custom_feed(db_client, *list_id, current_user.id).await?;
+            return Err(MastodonError::OperationError("list is not published"));
+        },
+        Err(other_error) => return Err(other_error.into()),
+    };
+    delete_account(&config, db_client, &feed_actor.account).await?;
+    let feed = get_custom_feed(
+        db_client,
+        feed_actor.feed.id,
+        current_user.id,
+    ).await?;
+    let list = List::from_db(feed);
+    Ok(HttpResponse::Ok().json(list))
+}
+
 pub fn list_api_scope() -> Scope {
     web::scope("/v1/lists")
         .service(get_lists)
         .service(create_list)
+        // Routes without list ID
+        .service(get_list_subscriptions)
+        .service(get_shared_lists)
+        // Routes with list ID
         .service(get_list)
         .service(update_list)
         .service(delete_list)
         .service(get_list_accounts)
         .service(add_accounts_to_list)
         .service(remove_accounts_from_list)
+        .service(subscribe_to_list)
+        .service(unsubscribe_from_list)
+        .service(publish_list)
+        .service(unpublish_list)
 }
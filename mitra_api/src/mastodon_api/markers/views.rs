@@ -7,7 +7,6 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 
 use mitra_models::{
     database::{get_database_client, DatabaseConnectionPool},
@@ -22,7 +21,7 @@ use mitra_validators::errors::ValidationError;
 use crate::{
     http::{JsonOrForm, MultiQuery},
     mastodon_api::{
-        auth::get_current_user,
+        auth::{get_current_user, BearerAuth},
         errors::MastodonError,
     },
 };
@@ -42,7 +41,7 @@ async fn get_marker_view(
     query_params: MultiQuery<MarkerQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let timelines = query_params.to_timelines()?;
     let mut maybe_home_marker = None;
     let mut maybe_notifications_marker = None;
@@ -80,7 +79,7 @@ async fn update_marker_view(
         Either::Right(form) => form.into_inner().into(),
     };
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let (timeline, last_read_id) = if let Some(last_read_id) = marker_form.home_last_read_id() {
         (Timeline::Home, last_read_id)
     } else if let Some(last_read_id) = marker_form.notifications_last_read_id() {
@@ -13,6 +13,7 @@ use uuid::Uuid;
 use mitra_models::attachments::types::{
     AttachmentType,
     MediaAttachment as DbMediaAttachment,
+    MediaMetadata,
 };
 use mitra_utils::files::APPLICATION_OCTET_STREAM;
 
@@ -24,12 +25,15 @@ pub struct AttachmentForm {
     pub file: String,
     pub media_type: String,
     pub description: Option<String>,
+    #[serde(default)]
+    pub sensitive: bool,
 }
 
 #[derive(MultipartForm)]
 pub struct AttachmentMultipartForm {
     file: Bytes,
     description: Option<Text<String>>,
+    sensitive: Option<Text<bool>>,
 }
 
 impl From<AttachmentMultipartForm> for AttachmentForm {
@@ -45,6 +49,9 @@ impl From<AttachmentMultipartForm> for AttachmentForm {
             file: base64::encode(form.file.data),
             media_type: media_type,
             description: form.description.map(|text| text.into_inner()),
+            sensitive: form.sensitive
+                .map(|text| text.into_inner())
+                .unwrap_or(false),
         }
     }
 }
@@ -52,6 +59,71 @@ impl From<AttachmentMultipartForm> for AttachmentForm {
 #[derive(Deserialize)]
 pub struct AttachmentUpdateForm {
     pub description: Option<String>,
+    pub sensitive: Option<bool>,
+}
+
+#[derive(Serialize)]
+struct MediaDimensions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aspect: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bitrate: Option<u64>,
+}
+
+impl MediaDimensions {
+    fn from_metadata(metadata: &MediaMetadata) -> Self {
+        let (size, aspect) = match (metadata.width, metadata.height) {
+            (Some(width), Some(height)) if height > 0 => (
+                Some(format!("{width}x{height}")),
+                Some(f64::from(width) / f64::from(height)),
+            ),
+            _ => (None, None),
+        };
+        Self {
+            width: metadata.width,
+            height: metadata.height,
+            size,
+            aspect,
+            duration: metadata.duration,
+            bitrate: metadata.bitrate,
+        }
+    }
+}
+
+/// https://docs.joinmastodon.org/entities/MediaAttachment/#meta
+#[derive(Serialize)]
+pub struct AttachmentMeta {
+    sensitive: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original: Option<MediaDimensions>,
+    // Previews are not generated, preview image has the same size
+    #[serde(skip_serializing_if = "Option::is_none")]
+    small: Option<MediaDimensions>,
+}
+
+impl AttachmentMeta {
+    fn new(is_sensitive: bool, maybe_metadata: Option<&MediaMetadata>) -> Self {
+        let original = maybe_metadata.map(MediaDimensions::from_metadata);
+        let small = maybe_metadata
+            .filter(|metadata| metadata.width.is_some())
+            .map(|metadata| {
+                let metadata = MediaMetadata {
+                    width: metadata.width,
+                    height: metadata.height,
+                    ..Default::default()
+                };
+                MediaDimensions::from_metadata(&metadata)
+            });
+        Self { sensitive: is_sensitive, original, small }
+    }
 }
 
 /// https://docs.joinmastodon.org/entities/attachment/
@@ -65,12 +137,14 @@ pub struct Attachment {
     pub url: String,
     pub preview_url: String,
     description: Option<String>,
+    meta: AttachmentMeta,
 }
 
 impl Attachment {
     pub fn from_db(
         media_server: &ClientMediaServer,
         db_attachment: DbMediaAttachment,
+        is_restricted: bool,
     ) -> Self {
         let attachment_type_mastodon = match db_attachment.attachment_type() {
             AttachmentType::Unknown => "unknown",
@@ -78,13 +152,21 @@ impl Attachment {
             AttachmentType::Video => "video",
             AttachmentType::Audio => "audio",
         };
-        let attachment_url = media_server.url_for(&db_attachment.media);
+        let attachment_url = if is_restricted {
+            media_server.restricted_url_for(&db_attachment.media)
+        } else {
+            media_server.url_for(&db_attachment.media)
+        };
         Self {
             id: db_attachment.id,
             attachment_type: attachment_type_mastodon.to_string(),
             url: attachment_url.clone(),
             preview_url: attachment_url,
             description: db_attachment.description,
+            meta: AttachmentMeta::new(
+                db_attachment.is_sensitive,
+                db_attachment.metadata.as_ref(),
+            ),
         }
     }
 }
@@ -7,9 +7,12 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 use uuid::Uuid;
 
+use mitra_adapters::{
+    dynamic_config::get_dynamic_config,
+    media::get_storage_quota,
+};
 use mitra_config::Config;
 use mitra_models::{
     attachments::queries::{
@@ -19,14 +22,18 @@ use mitra_models::{
     },
     database::{get_database_client, DatabaseConnectionPool},
     media::types::MediaInfo,
+    notifications::helpers::create_storage_quota_warning_notification,
+};
+use mitra_services::{
+    media::MediaStorage,
+    media_metadata::get_media_metadata,
 };
-use mitra_services::media::MediaStorage;
 use mitra_validators::media::validate_media_description;
 
 use crate::{
     http::get_request_base_url,
     mastodon_api::{
-        auth::get_current_user,
+        auth::{get_current_user, BearerAuth},
         errors::MastodonError,
         media_server::ClientMediaServer,
         uploads::save_b64_file,
@@ -40,6 +47,9 @@ use super::types::{
     AttachmentUpdateForm,
 };
 
+// Percentage of storage quota
+const STORAGE_QUOTA_WARNING_THRESHOLD: u64 = 90;
+
 async fn create_attachment_view(
     auth: BearerAuth,
     config: web::Data<Config>,
@@ -55,30 +65,60 @@ async fn create_attachment_view(
         Either::Right(json) => json.into_inner(),
     };
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let maybe_storage_quota = get_storage_quota(
+        db_client,
+        &dynamic_config.role_limits,
+        &current_user,
+    ).await?;
     let media_storage = MediaStorage::new(&config);
+    let limits = config.limits();
     let file_info = save_b64_file(
         &attachment_form.file,
         &attachment_form.media_type,
         &media_storage,
-        config.limits.media.file_size_limit,
-        &config.limits.media.supported_media_types(),
+        limits.media.file_size_limit,
+        &limits.media.supported_media_types(),
+        maybe_storage_quota.as_ref(),
     )?;
     if let Some(ref description) = attachment_form.description {
         validate_media_description(description)?;
     };
+    let metadata = get_media_metadata(
+        &limits.media,
+        &media_storage,
+        &file_info.file_name,
+        &file_info.media_type,
+    ).await;
+    let file_size = file_info.file_size;
     let db_attachment = create_attachment(
         db_client,
         current_user.id,
         MediaInfo::local(file_info),
         attachment_form.description.as_deref(),
+        attachment_form.sensitive,
+        metadata,
     ).await?;
+    if let Some(mut storage_quota) = maybe_storage_quota {
+        let threshold =
+            storage_quota.limit / 100 * STORAGE_QUOTA_WARNING_THRESHOLD;
+        let storage_usage = storage_quota.usage;
+        storage_quota.add_usage(file_size);
+        if storage_usage < threshold && storage_quota.usage >= threshold {
+            create_storage_quota_warning_notification(
+                db_client,
+                current_user.id,
+            ).await?;
+        };
+    };
 
     let base_url = get_request_base_url(connection_info);
     let media_server = ClientMediaServer::new(&config, &base_url);
     let attachment = Attachment::from_db(
         &media_server,
         db_attachment,
+        false,
     );
     Ok(HttpResponse::Ok().json(attachment))
 }
@@ -91,7 +131,7 @@ async fn get_attachment_view(
     attachment_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let db_attachment = get_attachment(
         db_client,
         current_user.id,
@@ -99,9 +139,12 @@ async fn get_attachment_view(
     ).await?;
     let base_url = get_request_base_url(connection_info);
     let media_server = ClientMediaServer::new(&config, &base_url);
+    // Attachment may belong to a non-public post
+    let is_restricted = db_attachment.post_id.is_some();
     let attachment = Attachment::from_db(
         &media_server,
         db_attachment,
+        is_restricted,
     );
     Ok(HttpResponse::Ok().json(attachment))
 }
@@ -115,7 +158,7 @@ async fn update_attachment_view(
     attachment_form: web::Json<AttachmentUpdateForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     if let Some(ref description) = attachment_form.description {
         validate_media_description(description)?;
     };
@@ -124,12 +167,16 @@ async fn update_attachment_view(
         current_user.id,
         *attachment_id,
         attachment_form.description.as_deref(),
+        attachment_form.sensitive,
     ).await?;
     let base_url = get_request_base_url(connection_info);
     let media_server = ClientMediaServer::new(&config, &base_url);
+    // Attachment may belong to a non-public post
+    let is_restricted = db_attachment.post_id.is_some();
     let attachment = Attachment::from_db(
         &media_server,
         db_attachment,
+        is_restricted,
     );
     Ok(HttpResponse::Ok().json(attachment))
 }
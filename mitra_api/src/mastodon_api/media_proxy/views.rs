@@ -33,8 +33,8 @@ async fn media_proxy_view(
     verify_eddsa_signature(&public_key, signature_base, &params.signature)
         .map_err(|_| HttpError::PermissionError)?;
     let agent = build_federation_agent(&config.instance(), None);
-    let supported_media_types: Vec<_> = config
-        .limits
+    let limits = config.limits();
+    let supported_media_types: Vec<_> = limits
         .media
         .supported_media_types()
         .into_iter()
@@ -46,7 +46,7 @@ async fn media_proxy_view(
         &agent,
         &media_url,
         &supported_media_types,
-        config.limits.media.file_size_limit,
+        limits.media.file_size_limit,
     ).await
         .map_err(|error| {
             log::warn!("{error}");
@@ -14,6 +14,8 @@ pub struct ClientMediaServer {
     media_server: MediaServer,
     media_proxy_key: Ed25519SecretKey,
     base_url: String,
+    default_avatar_url: Option<String>,
+    default_header_url: Option<String>,
 }
 
 impl ClientMediaServer {
@@ -22,6 +24,8 @@ impl ClientMediaServer {
             media_server: MediaServer::new(config),
             media_proxy_key: config.instance().ed25519_secret_key,
             base_url: base_url.to_string(),
+            default_avatar_url: config.default_avatar_url.clone(),
+            default_header_url: config.default_header_url.clone(),
         }
     }
 
@@ -34,14 +38,41 @@ impl ClientMediaServer {
             media_server,
             media_proxy_key,
             base_url: base_url.to_string(),
+            default_avatar_url: None,
+            default_header_url: None,
         }
     }
 
-    pub fn base_url(&self) -> &str {
-        &self.base_url
+    /// Returns URL of the image to display when profile has no avatar
+    pub fn default_avatar_url(&self, actor_id: &str) -> String {
+        self.default_avatar_url.clone().unwrap_or(format!(
+            "{}/api/v1/accounts/identicon?input={actor_id}",
+            self.base_url,
+        ))
+    }
+
+    /// Returns URL of the image to display when profile has no header
+    pub fn default_header_url(&self) -> String {
+        self.default_header_url.clone().unwrap_or(format!(
+            "{}/api/v1/accounts/identicon",
+            self.base_url,
+        ))
     }
 
     pub fn url_for(&self, media_info: &PartialMediaInfo) -> String {
+        self.get_url(media_info, false)
+    }
+
+    /// Returns URL of media attached to non-public post
+    pub fn restricted_url_for(&self, media_info: &PartialMediaInfo) -> String {
+        self.get_url(media_info, true)
+    }
+
+    fn get_url(
+        &self,
+        media_info: &PartialMediaInfo,
+        is_restricted: bool,
+    ) -> String {
         let file_name = match media_info {
             PartialMediaInfo::File { file_info, .. } => &file_info.file_name,
             PartialMediaInfo::Link { url, .. } => {
@@ -62,7 +93,11 @@ impl ClientMediaServer {
             MediaServer::Filesystem(backend) => {
                 let mut media_server = backend.clone();
                 media_server.override_base_url(&self.base_url);
-                media_server.url_for(file_name)
+                if is_restricted {
+                    media_server.restricted_url_for(file_name)
+                } else {
+                    media_server.url_for(file_name)
+                }
             },
         }
     }
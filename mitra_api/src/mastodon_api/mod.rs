@@ -5,6 +5,7 @@ use actix_web::{
     error::ResponseError,
     http::StatusCode,
     middleware::{
+        from_fn,
         ErrorHandlerResponse,
         ErrorHandlers,
     },
@@ -26,15 +27,20 @@ use crate::{
 
 mod accounts;
 mod admin;
+mod announcements;
 mod apps;
 mod bookmarks;
 mod conversations;
 mod custom_emojis;
 mod directory;
+mod exports;
 mod favourites;
+mod feeds;
 mod filters;
 mod follow_requests;
+mod frontend_configurations;
 mod groups;
+mod imports;
 mod instance;
 mod lists;
 mod markers;
@@ -43,14 +49,22 @@ mod media_proxy;
 mod mutes;
 mod notifications;
 mod oauth;
+mod openapi;
 mod polls;
 mod preferences;
+mod profile;
+mod push;
 mod reactions;
+mod remote_interaction;
+mod reports;
 mod search;
 mod settings;
-mod statuses;
+pub(crate) mod statuses;
+pub(crate) mod streaming;
 mod subscriptions;
+mod tags;
 mod timelines;
+mod trends;
 
 mod auth;
 mod errors;
@@ -136,19 +150,38 @@ pub fn mastodon_api_scope(
         .app_data(form_config)
         .app_data(multipart_form_config)
         .app_data(multiquery_config)
+        .wrap(from_fn(auth::public_api_access_middleware))
         .wrap(create_error_handlers())
-        .service(accounts::views::account_api_scope(ratelimit_configs))
+        .service(accounts::views::account_api_scope(ratelimit_configs.clone()))
         .service(admin::posts::views::admin_post_api_scope())
         .service(admin::accounts::views::admin_account_api_scope())
+        .service(admin::announcements::views::admin_announcement_api_scope())
+        .service(admin::apps::views::admin_app_api_scope())
+        .service(admin::config::views::admin_config_api_scope())
+        .service(admin::custom_emojis::views::admin_custom_emoji_api_scope())
+        .service(admin::dead_letters::views::admin_dead_letter_api_scope())
+        .service(admin::frontend_configurations::views::admin_frontend_configuration_api_scope())
+        .service(admin::instance::views::admin_instance_api_scope())
+        .service(admin::outgoing_activities::views::admin_outgoing_activity_api_scope())
+        .service(admin::reports::views::admin_report_api_scope())
+        .service(admin::rules::views::admin_rule_api_scope())
+        .service(admin::storage_usage::views::admin_storage_usage_api_scope())
+        .service(admin::trends::views::admin_trends_api_scope())
+        .service(announcements::views::announcement_api_scope())
         .service(apps::views::application_api_scope())
         .service(bookmarks::views::bookmark_api_scope())
+        .service(bookmarks::views::bookmark_folder_api_scope())
         .service(conversations::views::conversation_api_scope())
         .service(custom_emojis::views::custom_emoji_api_scope())
         .service(directory::views::directory_api_scope())
+        .service(exports::views::export_api_scope())
         .service(favourites::views::favourite_api_scope())
+        .service(feeds::views::feed_api_scope())
         .service(filters::views::filter_api_scope())
         .service(follow_requests::views::follow_request_api_scope())
+        .service(frontend_configurations::views::frontend_configuration_api_scope())
         .service(groups::views::group_api_scope())
+        .service(imports::views::import_api_scope())
         .service(instance::views::instance_api_v1_scope())
         .service(instance::views::instance_api_v2_scope())
         .service(lists::views::list_api_scope())
@@ -159,14 +192,24 @@ pub fn mastodon_api_scope(
         .service(mutes::views::mute_api_scope())
         .service(notifications::views::notification_api_v1_scope())
         .service(notifications::views::notification_api_v2_scope())
+        .service(openapi::views::openapi_spec_view)
         .service(polls::views::poll_api_scope())
         .service(preferences::views::preferences_api_scope())
+        .service(profile::views::profile_api_scope())
+        .service(push::views::push_api_scope())
         .service(reactions::views::reaction_api_scope())
+        .service(remote_interaction::views::remote_interaction_api_scope())
+        .service(reports::views::report_api_scope())
         .service(search::views::search_api_scope())
         .service(settings::views::settings_api_scope())
-        .service(statuses::views::status_api_scope())
+        .service(statuses::views::status_api_scope(ratelimit_configs))
+        .service(statuses::views::scheduled_status_api_scope())
+        .service(statuses::views::scheduled_reblog_api_scope())
+        .service(streaming::views::streaming_api_scope())
         .service(subscriptions::views::subscription_api_scope())
+        .service(tags::views::tag_api_scope())
         .service(timelines::views::timeline_api_scope())
+        .service(trends::views::trends_api_scope())
 }
 
 #[cfg(test)]
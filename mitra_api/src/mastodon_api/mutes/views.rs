@@ -6,7 +6,6 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 
 use mitra_activitypub::authority::Authority;
 use mitra_config::Config;
@@ -18,7 +17,7 @@ use mitra_models::{
 use crate::http::get_request_base_url;
 use crate::mastodon_api::{
     accounts::types::Account,
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
     media_server::ClientMediaServer,
     pagination::{get_last_item, get_paginated_response},
@@ -37,7 +36,7 @@ async fn mute_list_view(
     query_params: web::Query<MuteListQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let profiles = get_mutes_paginated(
         db_client,
         current_user.id,
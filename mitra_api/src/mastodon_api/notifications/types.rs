@@ -93,8 +93,13 @@ impl Notification {
             EventType::SubscriptionStart => "", // not supported
             EventType::SubscriptionExpiration => "subscription_expiration",
             EventType::SubscriberLeaving => "subscriber_leaving",
+            EventType::SubscriptionReminder => "subscription_reminder",
+            EventType::Post => "status",
+            EventType::StorageQuotaWarning => "storage_quota_warning",
+            EventType::View => "view",
             EventType::Move => "move",
             EventType::SignUp => "admin.sign_up",
+            EventType::Report => "admin.report",
         };
         let maybe_reaction = if let Some(content) = notification.reaction_content {
             let maybe_custom_emoji = notification.reaction_emoji
@@ -8,7 +8,6 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 
 use mitra_activitypub::authority::Authority;
 use mitra_config::Config;
@@ -22,7 +21,7 @@ use mitra_models::{
 
 use crate::http::get_request_base_url;
 use crate::mastodon_api::{
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
     media_server::ClientMediaServer,
     pagination::{get_last_item, get_paginated_response},
@@ -44,7 +43,7 @@ async fn get_notifications_view(
     query_params: web::Query<NotificationQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let base_url = get_request_base_url(connection_info);
     let authority = Authority::from(&config.instance());
     let media_server = ClientMediaServer::new(&config, &base_url);
@@ -80,7 +79,7 @@ async fn clear_notifications_view(
     db_pool: web::Data<DatabaseConnectionPool>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     delete_notifications(db_client, current_user.id).await?;
     let empty = serde_json::json!({});
     Ok(HttpResponse::Ok().json(empty))
@@ -93,7 +92,7 @@ async fn notification_policy_view(
     db_pool: web::Data<DatabaseConnectionPool>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let policy = NotificationPolicy::from_user(&current_user);
     Ok(HttpResponse::Ok().json(policy))
 }
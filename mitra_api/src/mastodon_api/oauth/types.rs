@@ -1,4 +1,5 @@
 use actix_multipart::form::{text::Text, MultipartForm};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -34,6 +35,7 @@ pub struct TokenRequest {
     pub password: Option<String>,
 
     // EIP-4361 / CAIP-122 message and signature
+    // (or signed challenge if grant type is "did")
     pub message: Option<String>,
     pub signature: Option<String>,
 }
@@ -100,3 +102,14 @@ impl TokenResponse {
 pub struct RevocationRequest {
     pub token: String,
 }
+
+#[derive(Deserialize)]
+pub struct ChallengeRequest {
+    pub did: String,
+}
+
+#[derive(Serialize)]
+pub struct ChallengeResponse {
+    pub message: String,
+    pub expires_at: DateTime<Utc>,
+}
@@ -1,9 +1,17 @@
-use apx_core::base64;
+use apx_core::{
+    base64,
+    crypto::eddsa::verify_eddsa_signature,
+    did::Did,
+    eip191::recover_address_eip191,
+    minisign::parse_minisign_signature_file,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde::Serialize;
 
 use mitra_utils::{
     random::generate_random_sequence,
 };
+use mitra_validators::errors::ValidationError;
 
 use crate::templates::render_template;
 
@@ -11,6 +19,8 @@ use crate::templates::render_template;
 // https://www.rfc-editor.org/rfc/rfc6749#section-4.1.2
 pub(super) const AUTHORIZATION_CODE_LIFETIME: i64 = 60 * 5;
 
+pub(super) const CHALLENGE_LIFETIME: i64 = 60 * 5;
+
 const NONCE_SIZE: usize = 10;
 
 #[derive(Serialize)]
@@ -61,10 +71,152 @@ pub fn generate_oauth_token() -> String {
     encode_token(value)
 }
 
+pub fn generate_challenge_nonce() -> String {
+    generate_nonce()
+}
+
+// Based on CAIP-122 message format
+// https://github.com/ChainAgnostic/CAIPs/blob/main/CAIPs/caip-122.md
+pub fn create_challenge_message(
+    domain: &str,
+    instance_uri: &str,
+    statement: &str,
+    did: &Did,
+    nonce: &str,
+    issued_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+) -> String {
+    format!(
+        "{domain} wants you to sign in with your DID:\n{did}\n\n{statement}\n\nURI: {instance_uri}\nVersion: 1\nNonce: {nonce}\nIssued At: {issued_at}\nExpiration Time: {expires_at}",
+        issued_at=issued_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        expires_at=expires_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+    )
+}
+
+/// Verifies signature created with DID key.
+/// did:key signatures must be in minisign format (non-prehashed),
+/// did:pkh signatures must be hex-encoded EIP-191 signatures.
+pub fn verify_challenge_signature(
+    signer: &Did,
+    message: &str,
+    signature: &str,
+) -> Result<(), ValidationError> {
+    match signer {
+        Did::Key(did_key) => {
+            let ed25519_key = did_key.try_ed25519_key()
+                .map_err(|_| ValidationError("invalid public key"))?;
+            let signature = parse_minisign_signature_file(signature)
+                .map_err(|_| ValidationError("invalid signature encoding"))?;
+            if signature.is_prehashed {
+                return Err(ValidationError("invalid signature type"));
+            };
+            verify_eddsa_signature(
+                &ed25519_key,
+                message.as_bytes(),
+                &signature.value,
+            ).map_err(|_| ValidationError("invalid signature"))?;
+        },
+        Did::Pkh(did_pkh) => {
+            let signature_hex = signature.strip_prefix("0x")
+                .unwrap_or(signature);
+            let signature_bin: [u8; 65] = hex::decode(signature_hex)
+                .map_err(|_| ValidationError("invalid signature encoding"))?
+                .try_into()
+                .map_err(|_| ValidationError("invalid signature length"))?;
+            let address = recover_address_eip191(
+                message.as_bytes(),
+                signature_bin,
+            ).map_err(|_| ValidationError("invalid signature"))?;
+            let address = format!("0x{}", hex::encode(address));
+            if address != did_pkh.address().to_lowercase() {
+                return Err(ValidationError("invalid signature"));
+            };
+        },
+    };
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use apx_core::{
+        crypto::{
+            ecdsa::generate_ecdsa_key,
+            eddsa::{
+                create_eddsa_signature,
+                ed25519_public_key_from_secret_key,
+                generate_weak_ed25519_key,
+            },
+        },
+        did_key::DidKey,
+        did_pkh::DidPkh,
+        eip191::{create_eip191_signature, ecdsa_public_key_to_address_hex},
+    };
+    use chrono::TimeZone;
     use super::*;
 
+    #[test]
+    fn test_create_challenge_message() {
+        let did: Did = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"
+            .parse().unwrap();
+        let issued_at = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let expires_at = Utc.with_ymd_and_hms(2026, 1, 1, 0, 5, 0).unwrap();
+        let message = create_challenge_message(
+            "social.example",
+            "https://social.example",
+            "Do not sign this message on other sites!",
+            &did,
+            "abc",
+            issued_at,
+            expires_at,
+        );
+        let expected_message = concat!(
+            "social.example wants you to sign in with your DID:\n",
+            "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK\n",
+            "\n",
+            "Do not sign this message on other sites!\n",
+            "\n",
+            "URI: https://social.example\n",
+            "Version: 1\n",
+            "Nonce: abc\n",
+            "Issued At: 2026-01-01T00:00:00Z\n",
+            "Expiration Time: 2026-01-01T00:05:00Z",
+        );
+        assert_eq!(message, expected_message);
+    }
+
+    #[test]
+    fn test_verify_challenge_signature_did_key() {
+        let secret_key = generate_weak_ed25519_key();
+        let public_key = ed25519_public_key_from_secret_key(&secret_key);
+        let did = Did::Key(DidKey::from_ed25519_key(&public_key));
+        let message = "test";
+        let signature = create_eddsa_signature(&secret_key, message.as_bytes());
+        // Minisign signature: algorithm || key ID || signature
+        let signature_file = format!(
+            "untrusted comment: signature\n{}",
+            base64::encode([b"Ed".as_slice(), &[0; 8], &signature].concat()),
+        );
+        let result = verify_challenge_signature(&did, message, &signature_file);
+        assert!(result.is_ok());
+        let result = verify_challenge_signature(&did, "other", &signature_file);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_verify_challenge_signature_did_pkh() {
+        let secret_key = generate_ecdsa_key();
+        let address = ecdsa_public_key_to_address_hex(secret_key.verifying_key());
+        let did = Did::Pkh(DidPkh::from_ethereum_address(&address));
+        let message = "test";
+        let signature =
+            create_eip191_signature(&secret_key, message.as_bytes()).unwrap();
+        let signature_hex = format!("0x{}", hex::encode(signature));
+        let result = verify_challenge_signature(&did, message, &signature_hex);
+        assert!(result.is_ok());
+        let result = verify_challenge_signature(&did, "other", &signature_hex);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_encode_token() {
         let value = [87, 31, 60, 176, 41, 131, 140, 213, 30, 64, 78, 169, 144, 138, 61, 62, 127, 26, 140, 96];
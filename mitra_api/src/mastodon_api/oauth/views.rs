@@ -13,13 +13,14 @@ use actix_web::{
     HttpResponse,
     Scope as ActixScope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
+use apx_core::did::Did;
 use chrono::{TimeDelta, Utc};
 use log::Level;
 
-use mitra_config::Config;
+use mitra_config::{AuthenticationMethod, Config};
 use mitra_models::{
     accounts::queries::{
+        get_user_by_did,
         get_user_by_name,
         get_user_by_login_address,
     },
@@ -31,8 +32,11 @@ use mitra_models::{
     },
     oauth::queries::{
         create_oauth_authorization,
+        create_oauth_challenge,
+        delete_oauth_challenge,
         delete_oauth_token,
         get_oauth_app_by_client_id,
+        get_oauth_challenge,
         get_user_by_authorization_code,
         save_oauth_token,
     },
@@ -51,7 +55,7 @@ use crate::{
         JsonOrForm,
     },
     mastodon_api::{
-        auth::get_current_user,
+        auth::{get_current_user, BearerAuth},
         errors::MastodonError,
     },
     ratelimit::RatelimitConfigs,
@@ -60,16 +64,22 @@ use crate::{
 use super::types::{
     AuthorizationRequest,
     AuthorizationQueryParams,
+    ChallengeRequest,
+    ChallengeResponse,
     RevocationRequest,
     TokenRequest,
     TokenRequestMultipartForm,
     TokenResponse,
 };
 use super::utils::{
+    create_challenge_message,
+    generate_challenge_nonce,
     generate_oauth_token,
     render_authorization_page,
     render_authorization_code_page,
+    verify_challenge_signature,
     AUTHORIZATION_CODE_LIFETIME,
+    CHALLENGE_LIFETIME,
 };
 
 #[get("/authorize")]
@@ -110,6 +120,9 @@ async fn authorize_view(
     if oauth_app.redirect_uri != query_params.redirect_uri {
         return Err(ValidationError("invalid redirect_uri parameter").into());
     };
+    if !oauth_app.is_approved {
+        return Err(MastodonError::AuthError("application is not approved"));
+    };
 
     let authorization_code = generate_oauth_token();
     let created_at = Utc::now();
@@ -162,7 +175,7 @@ async fn token_view(
         Either::Left(data) => data.into_inner(),
         Either::Right(form) => form.into_inner().into(),
     };
-    let db_client = &**get_database_client(&db_pool).await?;
+    let db_client = &mut **get_database_client(&db_pool).await?;
     let maybe_oauth_app = if let Some(client_id) = request_data.client_id {
         let oauth_app = match get_oauth_app_by_client_id(db_client, client_id).await {
             Ok(app) => app,
@@ -177,6 +190,9 @@ async fn token_view(
         } else {
             log::warn!("client secret is not provided");
         };
+        if !oauth_app.is_approved {
+            return Err(MastodonError::AuthError("application is not approved"));
+        };
         Some(oauth_app)
     } else {
         None
@@ -262,6 +278,37 @@ async fn token_view(
                 &session_data.account_id,
             ).await?
         },
+        "did" => {
+            if !config.authentication_methods
+                .contains(&AuthenticationMethod::Did)
+            {
+                return Err(MastodonError::NotSupported);
+            };
+            let message = request_data.message.as_ref()
+                .ok_or(ValidationError("message is required"))?;
+            let signature = request_data.signature.as_ref()
+                .ok_or(ValidationError("signature is required"))?;
+            let transaction = db_client.transaction().await
+                .map_err(DatabaseError::from)?;
+            let did = match get_oauth_challenge(&transaction, message).await {
+                Ok(did) => did,
+                Err(DatabaseError::NotFound(_)) => {
+                    return Err(ValidationError("invalid or expired challenge").into());
+                },
+                Err(other_error) => return Err(other_error.into()),
+            };
+            if let Err(error) =
+                verify_challenge_signature(&did, message, signature)
+            {
+                log::warn!("failed login attempt ({did}): {error}");
+                return Err(error.into());
+            };
+            // Challenge is removed, so it can't be reused
+            delete_oauth_challenge(&transaction, message).await?;
+            let user = get_user_by_did(&transaction, &did).await?;
+            transaction.commit().await.map_err(DatabaseError::from)?;
+            user
+        },
         _ => {
             return Err(ValidationError("unsupported grant type").into());
         },
@@ -295,6 +342,40 @@ async fn token_view(
     Ok(response)
 }
 
+/// Creates sign-in challenge for a user with identity proof
+async fn challenge_view(
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request_data: JsonOrForm<ChallengeRequest>,
+) -> Result<HttpResponse, MastodonError> {
+    if !config.authentication_methods.contains(&AuthenticationMethod::Did) {
+        return Err(MastodonError::NotSupported);
+    };
+    let db_client = &**get_database_client(&db_pool).await?;
+    let did = request_data.into_inner().did.parse::<Did>()
+        .map_err(|_| ValidationError("invalid DID"))?;
+    // Challenge is issued for any DID,
+    // so the response doesn't reveal whether it is registered
+    let instance = config.instance();
+    let issued_at = Utc::now();
+    let expires_at = issued_at + TimeDelta::seconds(CHALLENGE_LIFETIME);
+    let message = create_challenge_message(
+        instance.uri().authority(),
+        instance.uri_str(),
+        &config.login_message,
+        &did,
+        &generate_challenge_nonce(),
+        issued_at,
+        expires_at,
+    );
+    create_oauth_challenge(db_client, &did, &message, expires_at).await?;
+    let challenge = ChallengeResponse { message, expires_at };
+    let response = HttpResponse::Ok()
+        .append_header((http_header::CACHE_CONTROL, "no-store"))
+        .json(challenge);
+    Ok(response)
+}
+
 #[post("/revoke")]
 async fn revoke_token_view(
     auth: BearerAuth,
@@ -302,7 +383,7 @@ async fn revoke_token_view(
     request_data: JsonOrForm<RevocationRequest>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     match delete_oauth_token(
         db_client,
         current_user.id,
@@ -329,6 +410,10 @@ pub fn oauth_api_scope(
         web::post()
             .to(token_view)
             .wrap(Governor::new(&ratelimit_configs.login)));
+    let challenge_view_limited = web::resource("/challenge").route(
+        web::post()
+            .to(challenge_view)
+            .wrap(Governor::new(&ratelimit_configs.login)));
     web::scope("/oauth")
         .wrap(ErrorHandlers::new()
             .default_handler_client(|response| {
@@ -339,5 +424,6 @@ pub fn oauth_api_scope(
         .service(authorization_page_view)
         .service(authorize_view)
         .service(token_view_limited)
+        .service(challenge_view_limited)
         .service(revoke_token_view)
 }
@@ -0,0 +1,82 @@
+use std::sync::OnceLock;
+
+use serde_json::{json, Value as JsonValue};
+
+use mitra_config::Config;
+
+// Specification is maintained manually
+const OPENAPI_SPEC: &str = include_str!("../../../../docs/openapi.yaml");
+
+static OPENAPI_SPEC_PARSED: OnceLock<JsonValue> = OnceLock::new();
+
+fn parse_openapi_spec() -> &'static JsonValue {
+    OPENAPI_SPEC_PARSED.get_or_init(|| {
+        serde_yaml::from_str(OPENAPI_SPEC)
+            .expect("OpenAPI specification should be valid")
+    })
+}
+
+pub fn get_openapi_spec(config: &Config) -> JsonValue {
+    let mut spec = parse_openapi_spec().clone();
+    spec["info"]["version"] = JsonValue::from(config.software.version);
+    spec["servers"] = json!([{ "url": config.instance().uri_str() }]);
+    spec
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        test::{call_and_read_body, init_service, TestRequest},
+        web,
+        App,
+        HttpRequest,
+        HttpResponse,
+    };
+    use regex::Regex;
+    use crate::{
+        mastodon_api::{mastodon_api_scope, oauth_api_scope},
+        ratelimit::RatelimitConfigs,
+    };
+    use super::*;
+
+    #[test]
+    fn test_parse_openapi_spec() {
+        let spec = parse_openapi_spec();
+        assert_eq!(spec["openapi"], "3.0.1");
+        let paths = spec["paths"].as_object().unwrap();
+        assert!(paths.contains_key("/api/v1/statuses"));
+        assert!(paths.contains_key("/api/openapi.json"));
+        assert!(paths.keys().all(|path| path.starts_with('/')));
+        // Response codes are converted to strings
+        let responses = &spec["paths"]["/api/v1/instance"]["get"]["responses"];
+        assert!(responses["200"].is_object());
+    }
+
+    #[actix_web::test]
+    async fn test_openapi_spec_paths_registered() {
+        let ratelimit_configs = RatelimitConfigs::new(false);
+        let app = init_service(App::new()
+            .service(oauth_api_scope(ratelimit_configs.clone()))
+            .service(mastodon_api_scope(1000, ratelimit_configs))
+            .route("/", web::get().to(|request: HttpRequest| async move {
+                let parameter_re = Regex::new(r"\{[^}]+\}").unwrap();
+                let resource_map = request.resource_map();
+                let unknown_paths: Vec<_> = parse_openapi_spec()["paths"]
+                    .as_object().unwrap()
+                    .keys()
+                    .filter(|path| {
+                        let path = parameter_re.replace_all(path, "1");
+                        !resource_map.has_resource(&path)
+                    })
+                    .cloned()
+                    .collect();
+                HttpResponse::Ok().json(unknown_paths)
+            }))
+        ).await;
+        let request = TestRequest::get().uri("/").to_request();
+        let response = call_and_read_body(&app, request).await;
+        let unknown_paths: Vec<String> =
+            serde_json::from_slice(&response).unwrap();
+        assert_eq!(unknown_paths, Vec::<String>::new());
+    }
+}
@@ -0,0 +1,2 @@
+mod helpers;
+pub mod views;
@@ -0,0 +1,19 @@
+use actix_web::{
+    get,
+    web,
+    HttpResponse,
+};
+
+use mitra_config::Config;
+
+use crate::mastodon_api::errors::MastodonError;
+
+use super::helpers::get_openapi_spec;
+
+#[get("/openapi.json")]
+pub async fn openapi_spec_view(
+    config: web::Data<Config>,
+) -> Result<HttpResponse, MastodonError> {
+    let spec = get_openapi_spec(&config);
+    Ok(HttpResponse::Ok().json(spec))
+}
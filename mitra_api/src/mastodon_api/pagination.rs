@@ -29,7 +29,7 @@ fn get_pagination_header(
 pub fn get_paginated_response(
     base_url: &str,
     request_uri: &Uri,
-    items: Vec<impl Serialize>,
+    items: impl Serialize,
     maybe_last_item_id: Option<impl ToString>,
 ) -> HttpResponse {
     if let Some(last_item_id) = maybe_last_item_id {
@@ -5,10 +5,10 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 use uuid::Uuid;
 
 use mitra_activitypub::builders::{
+    create_note::save_note_recipients,
     create_question_vote::prepare_create_question_vote,
     update_note::prepare_update_note,
 };
@@ -28,7 +28,7 @@ use mitra_services::media::MediaServer;
 use crate::{
     http::get_request_base_url,
     mastodon_api::{
-        auth::get_current_user,
+        auth::{get_current_user, BearerAuth},
         errors::MastodonError,
         media_server::ClientMediaServer,
     },
@@ -47,7 +47,7 @@ async fn vote_view(
     vote_form: web::Json<VoteForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let mut post = get_post_by_id_for_view(
         db_client,
         Some(&current_user.profile),
@@ -96,13 +96,18 @@ async fn vote_view(
         // Local poll
         let post_author = get_user_by_id(db_client, post.author.id).await?;
         add_related_posts(db_client, vec![&mut post]).await?;
-        prepare_update_note(
+        let (update_note, maybe_create_note) = prepare_update_note(
             db_client,
             &instance,
             &media_server,
             &post_author,
             &post,
-        ).await?.save_and_enqueue(db_client).await?;
+        ).await?;
+        update_note.save_and_enqueue(db_client).await?;
+        if let Some(create_note) = maybe_create_note {
+            create_note.save_and_enqueue(db_client).await?;
+            save_note_recipients(db_client, &post).await?;
+        };
     };
     let base_url = get_request_base_url(connection_info);
     let media_server = ClientMediaServer::new(&config, &base_url);
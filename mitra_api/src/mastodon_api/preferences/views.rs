@@ -5,14 +5,13 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 
 use mitra_models::{
     database::{get_database_client, DatabaseConnectionPool},
 };
 
 use crate::mastodon_api::{
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
 };
 use super::types::Preferences;
@@ -23,7 +22,7 @@ async fn preferences_view(
     db_pool: web::Data<DatabaseConnectionPool>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let preferences = Preferences::new(current_user.shared_client_config);
     Ok(HttpResponse::Ok().json(preferences))
 }
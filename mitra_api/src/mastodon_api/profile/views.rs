@@ -0,0 +1,122 @@
+/// https://docs.joinmastodon.org/methods/profile/
+use actix_web::{
+    delete,
+    dev::ConnectionInfo,
+    web,
+    HttpResponse,
+    Scope,
+};
+
+use mitra_activitypub::{
+    adapters::users::create_or_update_local_actor,
+    authority::Authority,
+    builders::update_person::prepare_update_person,
+};
+use mitra_config::Config;
+use mitra_models::{
+    database::{get_database_client, DatabaseConnectionPool},
+    profiles::{
+        queries::update_profile,
+        types::ProfileUpdateData,
+    },
+};
+use mitra_services::media::MediaServer;
+
+use crate::{
+    http::get_request_base_url,
+    mastodon_api::{
+        accounts::types::Account,
+        auth::{get_current_user, BearerAuth},
+        errors::MastodonError,
+        media_server::ClientMediaServer,
+    },
+};
+
+enum ProfileImage {
+    Avatar,
+    Header,
+}
+
+async fn delete_profile_image(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    image: ProfileImage,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let mut current_user = get_current_user(db_client, &auth).await?;
+    let mut profile_data = ProfileUpdateData::from(&current_user.profile);
+    let is_changed = match image {
+        ProfileImage::Avatar => profile_data.avatar.take().is_some(),
+        ProfileImage::Header => profile_data.banner.take().is_some(),
+    };
+    if is_changed {
+        let (updated_profile, deletion_queue) = update_profile(
+            db_client,
+            current_user.id,
+            profile_data,
+        ).await?;
+        current_user.profile = updated_profile;
+        create_or_update_local_actor(&config, db_client, &current_user).await?;
+        // Delete orphaned image
+        deletion_queue.into_job(db_client).await?;
+
+        // Federate
+        let media_server = MediaServer::new(&config);
+        prepare_update_person(
+            db_client,
+            &config.instance(),
+            &media_server,
+            &current_user,
+        ).await?.save_and_enqueue(db_client).await?;
+    };
+
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let account = Account::from_user(
+        &authority,
+        &media_server,
+        current_user,
+    );
+    Ok(HttpResponse::Ok().json(account))
+}
+
+#[delete("/avatar")]
+async fn delete_avatar(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    delete_profile_image(
+        auth,
+        config,
+        connection_info,
+        db_pool,
+        ProfileImage::Avatar,
+    ).await
+}
+
+#[delete("/header")]
+async fn delete_header(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    delete_profile_image(
+        auth,
+        config,
+        connection_info,
+        db_pool,
+        ProfileImage::Header,
+    ).await
+}
+
+pub fn profile_api_scope() -> Scope {
+    web::scope("/v1/profile")
+        .service(delete_avatar)
+        .service(delete_header)
+}
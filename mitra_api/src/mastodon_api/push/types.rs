@@ -0,0 +1,211 @@
+use apx_core::base64;
+use serde::{Deserialize, Serialize};
+
+use mitra_models::push_subscriptions::types::{
+    PushAlerts,
+    PushPolicy,
+    PushSubscription as DbPushSubscription,
+    PushSubscriptionData,
+};
+use mitra_validators::errors::ValidationError;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Alerts {
+    mention: bool,
+    status: bool,
+    reblog: bool,
+    follow: bool,
+    follow_request: bool,
+    favourite: bool,
+    poll: bool,
+    update: bool,
+    #[serde(rename = "admin.sign_up")]
+    admin_sign_up: bool,
+    #[serde(rename = "admin.report")]
+    admin_report: bool,
+}
+
+impl From<Alerts> for PushAlerts {
+    fn from(alerts: Alerts) -> Self {
+        Self {
+            mention: alerts.mention,
+            status: alerts.status,
+            reblog: alerts.reblog,
+            follow: alerts.follow,
+            follow_request: alerts.follow_request,
+            favourite: alerts.favourite,
+            poll: alerts.poll,
+            update: alerts.update,
+            admin_sign_up: alerts.admin_sign_up,
+            admin_report: alerts.admin_report,
+        }
+    }
+}
+
+impl From<PushAlerts> for Alerts {
+    fn from(alerts: PushAlerts) -> Self {
+        Self {
+            mention: alerts.mention,
+            status: alerts.status,
+            reblog: alerts.reblog,
+            follow: alerts.follow,
+            follow_request: alerts.follow_request,
+            favourite: alerts.favourite,
+            poll: alerts.poll,
+            update: alerts.update,
+            admin_sign_up: alerts.admin_sign_up,
+            admin_report: alerts.admin_report,
+        }
+    }
+}
+
+fn parse_policy(value: Option<&str>) -> Result<PushPolicy, ValidationError> {
+    let policy = match value {
+        None | Some("all") => PushPolicy::All,
+        Some("followed") => PushPolicy::Followed,
+        Some("follower") => PushPolicy::Follower,
+        Some("none") => PushPolicy::None,
+        _ => return Err(ValidationError("invalid policy")),
+    };
+    Ok(policy)
+}
+
+fn policy_to_str(policy: PushPolicy) -> &'static str {
+    match policy {
+        PushPolicy::All => "all",
+        PushPolicy::Followed => "followed",
+        PushPolicy::Follower => "follower",
+        PushPolicy::None => "none",
+    }
+}
+
+/// Decodes base64url string (padding is optional)
+fn decode_key(value: &str) -> Result<Vec<u8>, ValidationError> {
+    base64::decode_urlsafe_no_pad(value.trim_end_matches('='))
+        .map_err(|_| ValidationError("invalid key encoding"))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubscriptionKeys {
+    p256dh: String,
+    auth: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubscriptionParams {
+    endpoint: String,
+    keys: SubscriptionKeys,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SubscriptionDataParams {
+    alerts: Alerts,
+    policy: Option<String>,
+}
+
+// https://docs.joinmastodon.org/methods/push/#create
+#[derive(Debug, Deserialize)]
+pub struct PushSubscriptionCreateForm {
+    subscription: SubscriptionParams,
+    #[serde(default)]
+    data: SubscriptionDataParams,
+}
+
+impl PushSubscriptionCreateForm {
+    pub fn into_subscription_data(
+        self,
+    ) -> Result<PushSubscriptionData, ValidationError> {
+        let subscription_data = PushSubscriptionData {
+            endpoint: self.subscription.endpoint,
+            public_key: decode_key(&self.subscription.keys.p256dh)?,
+            auth_secret: decode_key(&self.subscription.keys.auth)?,
+            policy: parse_policy(self.data.policy.as_deref())?,
+            alerts: self.data.alerts.into(),
+        };
+        Ok(subscription_data)
+    }
+}
+
+// https://docs.joinmastodon.org/methods/push/#update
+#[derive(Debug, Deserialize)]
+pub struct PushSubscriptionUpdateForm {
+    #[serde(default)]
+    data: SubscriptionDataParams,
+    // Top-level parameter is used by some clients
+    policy: Option<String>,
+}
+
+impl PushSubscriptionUpdateForm {
+    pub fn into_alerts_and_policy(
+        self,
+    ) -> Result<(PushAlerts, PushPolicy), ValidationError> {
+        let maybe_policy = self.data.policy.or(self.policy);
+        let policy = parse_policy(maybe_policy.as_deref())?;
+        Ok((self.data.alerts.into(), policy))
+    }
+}
+
+// https://docs.joinmastodon.org/entities/WebPushSubscription/
+#[derive(Serialize)]
+pub struct WebPushSubscription {
+    id: String,
+    endpoint: String,
+    standard: bool,
+    alerts: Alerts,
+    policy: &'static str,
+    server_key: String,
+}
+
+impl WebPushSubscription {
+    pub fn from_db(
+        db_subscription: DbPushSubscription,
+        server_key: String,
+    ) -> Self {
+        Self {
+            id: db_subscription.id.to_string(),
+            endpoint: db_subscription.endpoint,
+            // RFC 8291
+            standard: true,
+            alerts: db_subscription.alerts.into(),
+            policy: policy_to_str(db_subscription.policy),
+            server_key,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn test_deserialize_create_form() {
+        let form_value = json!({
+            "subscription": {
+                "endpoint": "https://push.example/123",
+                "keys": {
+                    "p256dh": "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4",
+                    "auth": "BTBZMqHH6r4Tts7J_aSIgg==",
+                },
+            },
+            "data": {
+                "alerts": {
+                    "mention": true,
+                    "admin.sign_up": true,
+                },
+                "policy": "followed",
+            },
+        });
+        let form: PushSubscriptionCreateForm =
+            serde_json::from_value(form_value).unwrap();
+        let subscription_data = form.into_subscription_data().unwrap();
+        assert_eq!(subscription_data.public_key.len(), 65);
+        assert_eq!(subscription_data.auth_secret.len(), 16);
+        assert_eq!(subscription_data.alerts.mention, true);
+        assert_eq!(subscription_data.alerts.admin_sign_up, true);
+        assert_eq!(subscription_data.alerts.follow, false);
+        assert_eq!(subscription_data.policy, PushPolicy::Followed);
+    }
+}
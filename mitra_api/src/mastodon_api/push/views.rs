@@ -0,0 +1,143 @@
+/// https://docs.joinmastodon.org/methods/push/
+use actix_web::{
+    delete,
+    get,
+    post,
+    put,
+    web,
+    Either,
+    HttpResponse,
+    Scope,
+};
+use apx_sdk::fetch::require_safe_url;
+
+use mitra_config::Config;
+use mitra_models::{
+    database::{get_database_client, DatabaseConnectionPool},
+    push_subscriptions::queries::{
+        create_push_subscription,
+        delete_push_subscription,
+        get_push_subscription,
+        update_push_subscription,
+    },
+};
+use mitra_services::push::get_vapid_public_key;
+use mitra_validators::{
+    errors::ValidationError,
+    push_subscriptions::validate_push_subscription_data,
+};
+
+use crate::{
+    http::JsonOrQsForm,
+    mastodon_api::{
+        auth::{get_current_session, BearerAuth},
+        errors::MastodonError,
+    },
+};
+
+use super::types::{
+    PushSubscriptionCreateForm,
+    PushSubscriptionUpdateForm,
+    WebPushSubscription,
+};
+
+fn get_server_key(config: &Config) -> Result<String, MastodonError> {
+    get_vapid_public_key(&config.instance_vapid_key())
+        .map_err(MastodonError::from_internal)
+}
+
+#[post("")]
+async fn create_subscription_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    subscription_form: JsonOrQsForm<PushSubscriptionCreateForm>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let (token_id, current_user) =
+        get_current_session(db_client, &auth).await?;
+    let subscription_form = match subscription_form {
+        Either::Left(json) => json.into_inner(),
+        Either::Right(form) => form.into_inner(),
+    };
+    let subscription_data = subscription_form.into_subscription_data()?;
+    validate_push_subscription_data(&subscription_data)?;
+    if config.federation.ssrf_protection_enabled {
+        require_safe_url(&subscription_data.endpoint)
+            .map_err(|_| ValidationError("endpoint URL is not allowed"))?;
+    };
+    let db_subscription = create_push_subscription(
+        db_client,
+        current_user.id,
+        token_id,
+        subscription_data,
+    ).await?;
+    let subscription = WebPushSubscription::from_db(
+        db_subscription,
+        get_server_key(&config)?,
+    );
+    Ok(HttpResponse::Ok().json(subscription))
+}
+
+#[get("")]
+async fn get_subscription_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let (token_id, _) = get_current_session(db_client, &auth).await?;
+    let db_subscription = get_push_subscription(db_client, token_id).await?;
+    let subscription = WebPushSubscription::from_db(
+        db_subscription,
+        get_server_key(&config)?,
+    );
+    Ok(HttpResponse::Ok().json(subscription))
+}
+
+#[put("")]
+async fn update_subscription_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    update_form: JsonOrQsForm<PushSubscriptionUpdateForm>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let (token_id, _) = get_current_session(db_client, &auth).await?;
+    let update_form = match update_form {
+        Either::Left(json) => json.into_inner(),
+        Either::Right(form) => form.into_inner(),
+    };
+    let (alerts, policy) = update_form.into_alerts_and_policy()?;
+    let db_subscription = update_push_subscription(
+        db_client,
+        token_id,
+        alerts,
+        policy,
+    ).await?;
+    let subscription = WebPushSubscription::from_db(
+        db_subscription,
+        get_server_key(&config)?,
+    );
+    Ok(HttpResponse::Ok().json(subscription))
+}
+
+#[delete("")]
+async fn delete_subscription_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let (token_id, _) = get_current_session(db_client, &auth).await?;
+    delete_push_subscription(db_client, token_id).await?;
+    let empty = serde_json::json!({});
+    Ok(HttpResponse::Ok().json(empty))
+}
+
+pub fn push_api_scope() -> Scope {
+    web::scope("/v1/push/subscription")
+        .service(create_subscription_view)
+        .service(get_subscription_view)
+        .service(update_subscription_view)
+        .service(delete_subscription_view)
+}
@@ -4,10 +4,10 @@ use actix_web::{
     get,
     put,
     web,
+    HttpRequest,
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 use uuid::Uuid;
 
 use mitra_activitypub::{
@@ -17,7 +17,9 @@ use mitra_activitypub::{
         like::prepare_like,
         undo_like::prepare_undo_like,
     },
+    filter::record_rejected_reaction,
 };
+use mitra_adapters::reactions::ReactionFilter;
 use mitra_config::Config;
 use mitra_models::{
     database::{
@@ -47,10 +49,10 @@ use mitra_validators::{
     reactions::validate_reaction_data,
 };
 
-use crate::http::get_request_base_url;
+use crate::http::{get_language_preferences, get_request_base_url};
 use crate::mastodon_api::{
     accounts::types::Account,
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     custom_emojis::types::CustomEmoji,
     errors::MastodonError,
     media_server::ClientMediaServer,
@@ -72,10 +74,11 @@ async fn create_reaction_view(
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
     path: web::Path<(Uuid, String)>,
+    request: HttpRequest,
 ) -> Result<HttpResponse, MastodonError> {
     let (status_id, content) = path.into_inner();
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let mut post = get_post_by_id_for_view(
         db_client,
         Some(&current_user.profile),
@@ -113,6 +116,11 @@ async fn create_reaction_view(
         activity_id: None,
     };
     validate_reaction_data(&reaction_data)?;
+    let reaction_filter = ReactionFilter::init(db_client).await?;
+    if !reaction_filter.is_allowed(&reaction_data, maybe_emoji.as_ref()) {
+        record_rejected_reaction(true);
+        return Err(MastodonError::OperationError("reaction is not allowed"));
+    };
     let reaction = create_reaction(db_client, reaction_data).await?;
     let reaction = ReactionDetailed
         ::new(reaction, current_user.profile.clone(), maybe_emoji)
@@ -147,6 +155,7 @@ async fn create_reaction_view(
         &media_server,
         Some(&current_user),
         post,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -158,10 +167,11 @@ async fn delete_reaction_view(
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
     path: web::Path<(Uuid, String)>,
+    request: HttpRequest,
 ) -> Result<HttpResponse, MastodonError> {
     let (status_id, content) = path.into_inner();
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let mut post = get_post_by_id_for_view(
         db_client,
         Some(&current_user.profile),
@@ -209,6 +219,7 @@ async fn delete_reaction_view(
         &media_server,
         Some(&current_user),
         post,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -222,7 +233,7 @@ async fn get_post_reactions_view(
     status_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let post = get_post_by_id_for_view(
         db_client,
         Some(&current_user.profile),
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct RemoteInteractionData {
+    pub acct: String,
+    pub uri: String,
+}
+
+#[derive(Serialize)]
+pub struct RemoteInteraction {
+    pub url: String,
+}
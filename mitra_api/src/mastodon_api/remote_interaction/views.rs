@@ -0,0 +1,63 @@
+use actix_web::{
+    post,
+    web,
+    HttpResponse,
+    Scope,
+};
+use apx_sdk::addresses::WebfingerAddress;
+
+use mitra_activitypub::{
+    agent::build_federation_agent,
+    webfinger::get_remote_interaction_url,
+};
+use mitra_config::Config;
+use mitra_validators::errors::ValidationError;
+
+use crate::{
+    http::JsonOrForm,
+    mastodon_api::errors::MastodonError,
+};
+
+use super::types::{RemoteInteraction, RemoteInteractionData};
+
+/// Returns URL of the remote interaction page on user's home server
+#[post("")]
+async fn remote_interaction_view(
+    config: web::Data<Config>,
+    interaction_data: JsonOrForm<RemoteInteractionData>,
+) -> Result<HttpResponse, MastodonError> {
+    let interaction_data = interaction_data.into_inner();
+    let instance = config.instance();
+    let webfinger_address =
+        WebfingerAddress::from_handle(interaction_data.acct.trim())
+            .map_err(|error| ValidationError(error.message()))?;
+    if webfinger_address.hostname() == instance.webfinger_hostname() {
+        return Err(ValidationError("account is local").into());
+    };
+    let local_prefix = format!("{}/", instance.uri_str());
+    if !interaction_data.uri.starts_with(&local_prefix) {
+        return Err(ValidationError("object is not local").into());
+    };
+    let agent = build_federation_agent(&instance, None);
+    let url = get_remote_interaction_url(
+        &agent,
+        &webfinger_address,
+        &interaction_data.uri,
+    )
+        .await
+        .map_err(|error| {
+            log::warn!(
+                "remote interaction page discovery failed ({}): {}",
+                webfinger_address,
+                error,
+            );
+            MastodonError::OperationError("remote interaction is not supported")
+        })?;
+    let remote_interaction = RemoteInteraction { url };
+    Ok(HttpResponse::Ok().json(remote_interaction))
+}
+
+pub fn remote_interaction_api_scope() -> Scope {
+    web::scope("/v1/remote_interaction")
+        .service(remote_interaction_view)
+}
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use mitra_models::reports::types::Report as DbReport;
+
+use crate::mastodon_api::accounts::types::Account;
+
+// https://docs.joinmastodon.org/methods/reports/#post
+#[derive(Debug, Deserialize)]
+pub struct ReportCreateForm {
+    pub account_id: Uuid,
+
+    #[serde(default, alias = "status_ids[]")]
+    pub status_ids: Vec<Uuid>,
+
+    #[serde(default)]
+    pub comment: String,
+
+    #[serde(default)]
+    pub forward: bool,
+}
+
+// https://docs.joinmastodon.org/entities/Report/
+#[derive(Serialize)]
+pub struct Report {
+    id: Uuid,
+    action_taken: bool,
+    action_taken_at: Option<DateTime<Utc>>,
+    category: String,
+    comment: String,
+    forwarded: bool,
+    created_at: DateTime<Utc>,
+    status_ids: Vec<Uuid>,
+    rule_ids: Vec<String>,
+    target_account: Account,
+}
+
+impl Report {
+    pub fn from_db(db_report: DbReport, target_account: Account) -> Self {
+        Self {
+            id: db_report.id,
+            action_taken: db_report.is_resolved(),
+            action_taken_at: db_report.resolved_at,
+            category: "other".to_string(),
+            comment: db_report.comment,
+            forwarded: db_report.is_forwarded,
+            created_at: db_report.created_at,
+            status_ids: db_report.post_ids,
+            rule_ids: vec![],
+            target_account: target_account,
+        }
+    }
+}
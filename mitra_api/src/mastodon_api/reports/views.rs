@@ -0,0 +1,114 @@
+use actix_web::{
+    dev::ConnectionInfo,
+    post,
+    web,
+    Either,
+    HttpResponse,
+    Scope,
+};
+
+use mitra_activitypub::{
+    authority::Authority,
+    builders::flag::prepare_flag,
+};
+use mitra_config::Config;
+use mitra_models::{
+    database::{get_database_client, DatabaseConnectionPool},
+    notifications::helpers::create_report_notifications,
+    posts::helpers::get_post_by_id_for_view,
+    profiles::queries::get_profile_by_id,
+    reports::queries::create_report,
+    reports::types::ReportCreateData,
+};
+use mitra_validators::{
+    errors::ValidationError,
+    reports::{clean_report_comment, validate_report_comment},
+};
+
+use crate::{
+    http::{get_request_base_url, JsonOrQsForm},
+    mastodon_api::{
+        accounts::types::Account,
+        auth::{get_current_user, BearerAuth},
+        errors::MastodonError,
+        media_server::ClientMediaServer,
+    },
+};
+
+use super::types::{Report, ReportCreateForm};
+
+const REPORT_STATUSES_MAX: usize = 20;
+
+/// https://docs.joinmastodon.org/methods/reports/#post
+#[post("")]
+async fn create_report_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    report_form: JsonOrQsForm<ReportCreateForm>,
+) -> Result<HttpResponse, MastodonError> {
+    let report_form = match report_form {
+        Either::Left(json) => json.into_inner(),
+        Either::Right(form) => form.into_inner(),
+    };
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if report_form.account_id == current_user.id {
+        return Err(ValidationError("can't report yourself").into());
+    };
+    if report_form.status_ids.len() > REPORT_STATUSES_MAX {
+        return Err(ValidationError("too many statuses").into());
+    };
+    let comment = clean_report_comment(&report_form.comment);
+    validate_report_comment(comment)?;
+    let target = get_profile_by_id(db_client, report_form.account_id).await?;
+    let mut posts = vec![];
+    for post_id in report_form.status_ids {
+        let post = get_post_by_id_for_view(
+            db_client,
+            Some(&current_user.profile),
+            post_id,
+        ).await?;
+        if post.author.id != target.id {
+            return Err(ValidationError("status doesn't belong to account").into());
+        };
+        posts.push(post);
+    };
+    let maybe_remote_actor = target.actor_json.as_ref()
+        .filter(|_| report_form.forward);
+    let report_data = ReportCreateData {
+        reporter_id: current_user.id,
+        target_id: target.id,
+        post_ids: posts.iter().map(|post| post.id).collect(),
+        comment: comment.to_string(),
+        object_id: None,
+        is_forwarded: maybe_remote_actor.is_some(),
+    };
+    let report = create_report(db_client, report_data).await?;
+    create_report_notifications(db_client, current_user.id).await?;
+    if let Some(remote_actor) = maybe_remote_actor {
+        prepare_flag(
+            &config.instance(),
+            report.id,
+            remote_actor,
+            &posts,
+            &report.comment,
+        )?.save_and_enqueue(db_client).await?;
+    };
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let target_account = Account::from_profile(
+        &authority,
+        &media_server,
+        target,
+    );
+    let report = Report::from_db(report, target_account);
+    Ok(HttpResponse::Ok().json(report))
+}
+
+pub fn report_api_scope() -> Scope {
+    web::scope("/v1/reports")
+        .service(create_report_view)
+}
@@ -14,6 +14,7 @@ use apx_sdk::{
     utils::{get_core_type, CoreType},
 };
 use regex::Regex;
+use uuid::Uuid;
 
 use mitra_activitypub::{
     authority::Authority,
@@ -40,8 +41,8 @@ use mitra_models::{
         DatabaseError,
     },
     posts::{
-        queries::search_posts,
-        helpers::can_view_post,
+        queries::{search_conversation_posts, search_posts},
+        helpers::{can_view_post, get_post_by_id_for_view},
         types::PostDetailed,
     },
     profiles::queries::{
@@ -272,6 +273,7 @@ pub async fn search(
     current_user: &User,
     db_pool: &DatabaseConnectionPool,
     search_query: &str,
+    only_following: bool,
     limit: u16,
     offset: u16,
 ) -> Result<SearchResults, DatabaseError> {
@@ -284,9 +286,11 @@ pub async fn search(
         SearchQuery::Text(text) => {
             let db_client = &**get_database_client(db_pool).await?;
             posts = search_posts_only(
+                config,
                 current_user,
                 db_client,
                 &text,
+                only_following,
                 limit,
                 offset,
             ).await?;
@@ -396,9 +400,11 @@ pub async fn search_profiles_only(
 }
 
 pub async fn search_posts_only(
+    config: &Config,
     current_user: &User,
     db_client: &impl DatabaseClient,
     search_query: &str,
+    only_following: bool,
     limit: u16,
     offset: u16,
 ) -> Result<Vec<PostDetailed>, DatabaseError> {
@@ -410,6 +416,42 @@ pub async fn search_posts_only(
         &fts_config,
         search_query,
         current_user.id,
+        only_following,
+        config.search_public_posts,
+        limit,
+        offset,
+    ).await
+}
+
+/// Searches for posts in the conversation that contains the given post
+pub async fn search_conversation_posts_only(
+    current_user: &User,
+    db_client: &impl DatabaseClient,
+    post_id: Uuid,
+    search_query: &str,
+    only_following: bool,
+    limit: u16,
+    offset: u16,
+) -> Result<Vec<PostDetailed>, DatabaseError> {
+    let post = get_post_by_id_for_view(
+        db_client,
+        Some(&current_user.profile),
+        post_id,
+    ).await?;
+    let Some(ref conversation) = post.conversation else {
+        // Reposts don't belong to conversations
+        return Ok(vec![]);
+    };
+    let fts_config = get_dynamic_config(db_client)
+        .await?
+        .default_fts_config;
+    search_conversation_posts(
+        db_client,
+        &fts_config,
+        conversation.id,
+        search_query,
+        current_user.id,
+        only_following,
         limit,
         offset,
     ).await
@@ -1,5 +1,6 @@
 /// https://docs.joinmastodon.org/methods/search/
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::mastodon_api::{
     accounts::types::Account,
@@ -21,6 +22,13 @@ pub struct SearchQueryParams {
 
     #[serde(default)]
     pub offset: u16,
+
+    // Search only in the conversation that contains this post
+    pub status_id: Option<Uuid>,
+
+    // Search only posts published by followed accounts
+    #[serde(default)]
+    pub following: bool,
 }
 
 #[derive(Serialize)]
@@ -3,25 +3,30 @@ use actix_web::{
     dev::ConnectionInfo,
     get,
     web,
+    HttpRequest,
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 
 use mitra_activitypub::authority::Authority;
 use mitra_config::Config;
 use mitra_models::database::{get_database_client, DatabaseConnectionPool};
 
-use crate::http::get_request_base_url;
+use crate::http::{get_language_preferences, get_request_base_url};
 use crate::mastodon_api::{
     accounts::types::Account,
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
     media_server::ClientMediaServer,
     statuses::helpers::build_status_list,
     statuses::types::Tag,
 };
-use super::helpers::{search, search_posts_only, search_profiles_only};
+use super::helpers::{
+    search,
+    search_conversation_posts_only,
+    search_posts_only,
+    search_profiles_only,
+};
 use super::types::{SearchQueryParams, SearchResults};
 
 #[get("")]
@@ -31,14 +36,32 @@ async fn search_view(
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
     query_params: web::Query<SearchQueryParams>,
+    request: HttpRequest,
 ) -> Result<HttpResponse, MastodonError> {
     let current_user = {
         let db_client = &**get_database_client(&db_pool).await?;
-        get_current_user(db_client, auth.token()).await?
+        get_current_user(db_client, &auth).await?
     };
     let search_query = query_params.q.trim();
-    let (profiles, posts, tags) = match query_params.search_type.as_deref() {
-        Some("accounts") => {
+    let (profiles, posts, tags) = match (
+        query_params.status_id,
+        query_params.search_type.as_deref(),
+    ) {
+        (Some(status_id), _) => {
+            // Conversation-scoped search
+            let db_client = &**get_database_client(&db_pool).await?;
+            let posts = search_conversation_posts_only(
+                &current_user,
+                db_client,
+                status_id,
+                search_query,
+                query_params.following,
+                query_params.limit.inner(),
+                query_params.offset,
+            ).await?;
+            (vec![], posts, vec![])
+        },
+        (None, Some("accounts")) => {
             let profiles = search_profiles_only(
                 &config,
                 &db_pool,
@@ -49,12 +72,14 @@ async fn search_view(
             ).await?;
             (profiles, vec![], vec![])
         },
-        Some("statuses") => {
+        (None, Some("statuses")) => {
             let db_client = &**get_database_client(&db_pool).await?;
             let posts = search_posts_only(
+                &config,
                 &current_user,
                 db_client,
                 search_query,
+                query_params.following,
                 query_params.limit.inner(),
                 query_params.offset,
             ).await?;
@@ -66,6 +91,7 @@ async fn search_view(
                 &current_user,
                 &db_pool,
                 search_query,
+                query_params.following,
                 query_params.limit.inner(),
                 query_params.offset,
             ).await?
@@ -89,6 +115,7 @@ async fn search_view(
         &media_server,
         Some(&current_user),
         posts,
+        &get_language_preferences(&request),
     ).await?;
     let hashtags = tags.into_iter()
         .map(|tag_name| Tag::from_tag_name(instance.uri_str(), tag_name))
@@ -1,3 +1,3 @@
 pub(crate) mod helpers;
-mod types;
+pub(crate) mod types;
 pub mod views;
@@ -1,13 +1,32 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-use mitra_models::oauth::types::OauthToken;
+use mitra_models::{
+    data_imports::types::{
+        DataImport as DbDataImport,
+        ImportEntry,
+        ImportStatus,
+        ImportType,
+    },
+    oauth::types::OauthToken,
+};
 
 #[derive(Deserialize)]
 pub struct PasswordChangeRequest {
     pub new_password: String,
 }
 
+#[derive(Serialize)]
+pub struct RecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct RecoveryCodeStatus {
+    pub remaining: i64,
+}
+
 #[derive(Serialize)]
 pub struct Session {
     pub id: i32,
@@ -27,6 +46,11 @@ impl Session {
     }
 }
 
+#[derive(Deserialize)]
+pub struct TimezoneUpdateRequest {
+    pub timezone: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct AddAliasRequest {
     pub acct: String,
@@ -42,6 +66,50 @@ pub struct ImportFollowsRequest {
     pub follows_csv: String,
 }
 
+#[derive(Deserialize)]
+pub struct ImportMutesRequest {
+    pub mutes_csv: String,
+}
+
+#[derive(Deserialize)]
+pub struct ImportBlocksRequest {
+    pub blocks_csv: String,
+}
+
+#[derive(Serialize)]
+pub struct DataImport {
+    id: Uuid,
+    #[serde(rename = "type")]
+    import_type: &'static str,
+    status: &'static str,
+    entries: Vec<ImportEntry>,
+    created_at: DateTime<Utc>,
+    finished_at: Option<DateTime<Utc>>,
+}
+
+impl DataImport {
+    pub fn from_db(db_import: DbDataImport) -> Self {
+        let import_type = match db_import.import_type {
+            ImportType::Mutes => "muting",
+            ImportType::Blocks => "blocking",
+            ImportType::Follows => "following",
+        };
+        let status = match db_import.import_status {
+            ImportStatus::Pending => "pending",
+            ImportStatus::Completed => "completed",
+            ImportStatus::Failed => "failed",
+        };
+        Self {
+            id: db_import.id,
+            import_type,
+            status,
+            entries: db_import.entries.into_inner(),
+            created_at: db_import.created_at,
+            finished_at: db_import.finished_at,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct ImportFollowersRequest {
     pub from_actor_id: String,
@@ -7,40 +7,43 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
-use chrono::Utc;
+use chrono::{FixedOffset, Utc};
 
 use mitra_activitypub::{
-    adapters::{
-        follow_requests::follow_or_create_request,
-        users::{
-            create_or_update_local_actor,
-            delete_account,
-        },
+    adapters::users::{
+        cancel_account_move,
+        create_or_update_local_actor,
+        delete_account,
+        move_account,
     },
     authority::Authority,
-    builders::{
-        move_person::prepare_move_person,
-        update_person::prepare_update_person,
-    },
+    builders::update_person::prepare_update_person,
     identifiers::profile_actor_id,
 };
+use mitra_adapters::passwords::check_password;
 use mitra_config::Config;
 use mitra_models::{
     accounts::{
         queries::{
-            get_user_by_id,
+            create_recovery_codes,
+            get_recovery_code_count,
+            set_shared_client_config,
             set_user_password,
             update_client_config,
         },
         types::ClientConfig,
     },
+    activitypub::queries::get_outgoing_activity_records_by_sender,
+    data_imports::{
+        queries::create_data_import,
+        types::ImportType,
+    },
     database::{
         get_database_client,
+        utils::{get_utc_offsets, is_valid_timezone},
         DatabaseConnectionPool,
         DatabaseError,
     },
-    notifications::helpers::create_move_notification,
     oauth::queries::{
         delete_oauth_token_by_id,
         get_oauth_tokens,
@@ -52,12 +55,11 @@ use mitra_models::{
         update_profile,
     },
     profiles::types::ProfileUpdateData,
-    relationships::queries::{get_followers, unfollow},
 };
 use mitra_services::media::MediaServer;
 use mitra_utils::passwords::hash_password;
 use mitra_validators::{
-    accounts::validate_client_config_update,
+    accounts::{validate_client_config_update, validate_timezone_name},
     errors::ValidationError,
     profiles::validate_aliases,
 };
@@ -67,7 +69,8 @@ use crate::http::get_request_base_url;
 use crate::mastodon_api::{
     accounts::helpers::get_aliases,
     accounts::types::Account,
-    auth::{get_current_session, get_current_user},
+    admin::outgoing_activities::types::OutgoingActivityRecord,
+    auth::{get_current_session, get_current_user, BearerAuth},
     errors::MastodonError,
     media_server::ClientMediaServer,
 };
@@ -79,12 +82,18 @@ use super::helpers::{
 };
 use super::types::{
     AddAliasRequest,
+    DataImport,
+    ImportBlocksRequest,
     ImportFollowersRequest,
     ImportFollowsRequest,
+    ImportMutesRequest,
     MoveFollowersRequest,
     PasswordChangeRequest,
+    RecoveryCodes,
+    RecoveryCodeStatus,
     RemoveAliasRequest,
     Session,
+    TimezoneUpdateRequest,
 };
 
 // Similar to Pleroma settings store
@@ -98,7 +107,7 @@ async fn client_config_view(
     request_data: web::Json<ClientConfig>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let mut current_user = get_current_user(db_client, auth.token()).await?;
+    let mut current_user = get_current_user(db_client, &auth).await?;
     validate_client_config_update(
         &current_user.client_config,
         &request_data,
@@ -122,6 +131,41 @@ async fn client_config_view(
     Ok(HttpResponse::Ok().json(account))
 }
 
+#[post("/timezone")]
+async fn timezone_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request_data: web::Json<TimezoneUpdateRequest>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let mut current_user = get_current_user(db_client, &auth).await?;
+    let maybe_timezone = request_data.into_inner().timezone;
+    if let Some(ref timezone) = maybe_timezone {
+        validate_timezone_name(timezone)?;
+        if !is_valid_timezone(db_client, timezone).await? {
+            return Err(ValidationError("unknown time zone").into());
+        };
+    };
+    let mut client_config = current_user.shared_client_config.clone();
+    client_config.timezone = maybe_timezone;
+    current_user.shared_client_config = set_shared_client_config(
+        db_client,
+        current_user.id,
+        client_config,
+    ).await?;
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let account = Account::from_user(
+        &authority,
+        &media_server,
+        current_user,
+    );
+    Ok(HttpResponse::Ok().json(account))
+}
+
 #[get("/sessions")]
 async fn session_list_view(
     auth: BearerAuth,
@@ -129,7 +173,7 @@ async fn session_list_view(
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let (current_session_id, current_user) =
-        get_current_session(db_client, auth.token()).await?;
+        get_current_session(db_client, &auth).await?;
     let tokens = get_oauth_tokens(db_client, current_user.id).await?;
     let sessions: Vec<_> = tokens.into_iter()
         .filter(|token| token.expires_at >= Utc::now())
@@ -151,7 +195,7 @@ async fn terminate_session_view(
     session_id: web::Path<i32>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     delete_oauth_token_by_id(
         db_client,
         current_user.id,
@@ -169,7 +213,8 @@ async fn change_password_view(
     request_data: web::Json<PasswordChangeRequest>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let mut current_user = get_current_user(db_client, auth.token()).await?;
+    let mut current_user = get_current_user(db_client, &auth).await?;
+    check_password(&config.password_policy, &request_data.new_password).await?;
     let password_digest = hash_password(&request_data.new_password)
         .map_err(MastodonError::from_internal)?;
     set_user_password(db_client, current_user.id, &password_digest).await?;
@@ -185,6 +230,34 @@ async fn change_password_view(
     Ok(HttpResponse::Ok().json(account))
 }
 
+#[get("/recovery_codes")]
+async fn recovery_code_status_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let remaining = get_recovery_code_count(db_client, current_user.id).await?;
+    let status = RecoveryCodeStatus { remaining };
+    Ok(HttpResponse::Ok().json(status))
+}
+
+#[post("/recovery_codes")]
+async fn generate_recovery_codes_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if current_user.password_digest.is_none() {
+        return Err(ValidationError("password auth is disabled").into());
+    };
+    let recovery_codes =
+        create_recovery_codes(db_client, current_user.id).await?;
+    log::warn!("generated recovery codes for {}", current_user);
+    Ok(HttpResponse::Ok().json(RecoveryCodes { recovery_codes }))
+}
+
 #[post("/aliases")]
 async fn add_alias_view(
     auth: BearerAuth,
@@ -194,7 +267,7 @@ async fn add_alias_view(
     request_data: web::Json<AddAliasRequest>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let mut current_user = get_current_user(db_client, auth.token()).await?;
+    let mut current_user = get_current_user(db_client, &auth).await?;
     let alias = get_profile_by_acct(db_client, &request_data.acct).await?;
     if alias.id == current_user.id {
         return Err(ValidationError("alias must differ from current account").into());
@@ -247,7 +320,7 @@ async fn remove_alias_view(
     request_data: web::Json<RemoveAliasRequest>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let mut current_user = get_current_user(db_client, auth.token()).await?;
+    let mut current_user = get_current_user(db_client, &auth).await?;
     let instance = config.instance();
     let mut profile_data = ProfileUpdateData::from(&current_user.profile);
     if profile_data.aliases.contains(&request_data.actor_id) {
@@ -289,7 +362,7 @@ async fn export_followers_view(
     db_pool: web::Data<DatabaseConnectionPool>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let csv = export_followers(
         db_client,
         &config.instance().webfinger_hostname(),
@@ -308,7 +381,7 @@ async fn export_follows_view(
     db_pool: web::Data<DatabaseConnectionPool>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let csv = export_follows(
         db_client,
         &config.instance().webfinger_hostname(),
@@ -320,6 +393,34 @@ async fn export_follows_view(
     Ok(response)
 }
 
+#[get("/export_outgoing_activities")]
+async fn export_outgoing_activities_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let mut records: Vec<OutgoingActivityRecord> =
+        get_outgoing_activity_records_by_sender(db_client, current_user.id)
+            .await?
+            .into_iter()
+            .map(OutgoingActivityRecord::from)
+            .collect();
+    if let Some(ref timezone) = current_user.shared_client_config.timezone {
+        let moments: Vec<_> = records.iter()
+            .map(|record| record.created_at)
+            .collect();
+        let offsets = get_utc_offsets(db_client, timezone, &moments).await?;
+        for (record, offset) in records.iter_mut().zip(offsets) {
+            let offset = FixedOffset::east_opt(offset)
+                .ok_or(MastodonError::InternalError("invalid UTC offset".to_owned()))?;
+            record.created_at_local =
+                Some(record.created_at.with_timezone(&offset));
+        };
+    };
+    Ok(HttpResponse::Ok().json(records))
+}
+
 #[post("/import_follows")]
 async fn import_follows_view(
     auth: BearerAuth,
@@ -327,17 +428,69 @@ async fn import_follows_view(
     request_data: web::Json<ImportFollowsRequest>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let address_list = parse_address_list(&request_data.follows_csv)?
         .iter()
         .map(|address| address.to_string())
         .collect();
-    let job_data = ImporterJobData::Follows {
-        user_id: current_user.id,
-        address_list: address_list,
-    };
-    job_data.into_job(db_client).await?;
-    Ok(HttpResponse::NoContent().finish())
+    let db_import = create_data_import(
+        db_client,
+        current_user.id,
+        ImportType::Follows,
+        address_list,
+    ).await?;
+    ImporterJobData::for_data_import(&db_import)
+        .into_job(db_client).await?;
+    let data_import = DataImport::from_db(db_import);
+    Ok(HttpResponse::Ok().json(data_import))
+}
+
+#[post("/import_mutes")]
+async fn import_mutes_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request_data: web::Json<ImportMutesRequest>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let address_list = parse_address_list(&request_data.mutes_csv)?
+        .iter()
+        .map(|address| address.to_string())
+        .collect();
+    let db_import = create_data_import(
+        db_client,
+        current_user.id,
+        ImportType::Mutes,
+        address_list,
+    ).await?;
+    ImporterJobData::for_data_import(&db_import)
+        .into_job(db_client).await?;
+    let data_import = DataImport::from_db(db_import);
+    Ok(HttpResponse::Ok().json(data_import))
+}
+
+#[post("/import_blocks")]
+async fn import_blocks_view(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request_data: web::Json<ImportBlocksRequest>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let address_list = parse_address_list(&request_data.blocks_csv)?
+        .iter()
+        .map(|address| address.to_string())
+        .collect();
+    let db_import = create_data_import(
+        db_client,
+        current_user.id,
+        ImportType::Blocks,
+        address_list,
+    ).await?;
+    ImporterJobData::for_data_import(&db_import)
+        .into_job(db_client).await?;
+    let data_import = DataImport::from_db(db_import);
+    Ok(HttpResponse::Ok().json(data_import))
 }
 
 #[post("/import_followers")]
@@ -349,7 +502,7 @@ async fn import_followers_view(
     request_data: web::Json<ImportFollowersRequest>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     if current_user.profile.identity_proofs.inner().is_empty() {
         return Err(ValidationError("identity proof is required").into());
     };
@@ -411,7 +564,7 @@ async fn move_followers_view(
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
     let instance = config.instance();
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let authority = Authority::from(&instance);
     let current_actor_id = profile_actor_id(
         &authority,
@@ -424,39 +577,45 @@ async fn move_followers_view(
     if target.is_local() {
         return Err(ValidationError("can't move followers to a local actor").into());
     };
-    let followers = get_followers(db_client, current_user.id).await?;
-    let mut remote_followers = vec![];
-    for follower in followers {
-        if follower.id == target.id {
-            continue;
-        };
-        if let Some(remote_actor) = follower.actor_json {
-            remote_followers.push(remote_actor);
-            continue;
-        };
-        let follower = get_user_by_id(db_client, follower.id).await?;
-        unfollow(db_client, follower.id, current_user.id).await?;
-        follow_or_create_request(
-            db_client,
-            &instance,
-            &follower,
-            &target,
-        ).await?;
-        create_move_notification(
-            db_client,
-            target.id,
-            follower.id,
-        ).await?;
-    };
-    let target_actor_id = profile_actor_id(&authority, &target);
-    prepare_move_person(
-        &instance,
+    let current_user = move_account(
+        &config,
+        db_client,
         &current_user,
-        &target_actor_id,
-        false, // push mode
-        remote_followers,
-    ).save_and_enqueue(db_client).await?;
+        &target,
+    ).await?;
+    let base_url = get_request_base_url(connection_info);
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let mut account = Account::from_user(
+        &authority,
+        &media_server,
+        current_user,
+    );
+    account.moved = Some(Box::new(Account::from_profile(
+        &authority,
+        &media_server,
+        target,
+    )));
+    Ok(HttpResponse::Ok().json(account))
+}
 
+#[post("/cancel_move")]
+async fn cancel_move_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if current_user.moved_to.is_none() {
+        return Err(ValidationError("account is not moved").into());
+    };
+    let current_user = cancel_account_move(
+        &config,
+        db_client,
+        &current_user,
+    ).await?;
+    let authority = Authority::from(&config.instance());
     let base_url = get_request_base_url(connection_info);
     let media_server = ClientMediaServer::new(&config, &base_url);
     let account = Account::from_user(
@@ -474,7 +633,7 @@ async fn delete_account_view(
     db_pool: web::Data<DatabaseConnectionPool>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     delete_account(&config, db_client, &current_user).await?;
     Ok(HttpResponse::NoContent().finish())
 }
@@ -482,15 +641,22 @@ async fn delete_account_view(
 pub fn settings_api_scope() -> Scope {
     web::scope("/v1/settings")
         .service(client_config_view)
+        .service(timezone_view)
         .service(session_list_view)
         .service(terminate_session_view)
         .service(change_password_view)
+        .service(recovery_code_status_view)
+        .service(generate_recovery_codes_view)
         .service(add_alias_view)
         .service(remove_alias_view)
         .service(export_followers_view)
         .service(export_follows_view)
+        .service(export_outgoing_activities_view)
         .service(import_follows_view)
+        .service(import_mutes_view)
+        .service(import_blocks_view)
         .service(import_followers_view)
         .service(move_followers_view)
+        .service(cancel_move_view)
         .service(delete_account_view)
 }
@@ -1,26 +1,82 @@
 use std::collections::HashSet;
 
 use actix_web::{http::Uri, HttpResponse};
+use apx_sdk::constants::AP_PUBLIC;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde_json::{Value as JsonValue};
 use uuid::Uuid;
 
-use mitra_activitypub::authority::Authority;
-use mitra_config::Instance;
+use mitra_activitypub::{
+    authority::Authority,
+    builders::{
+        add_context_activity::sync_conversation,
+        like::prepare_like,
+    },
+    identifiers::{
+        local_actor_id,
+        LocalActorCollection,
+    },
+};
+use mitra_adapters::{
+    dynamic_config::get_dynamic_config,
+    posts::{check_local_post_length, check_post_limits},
+    roles::get_role_post_limits,
+};
+use mitra_config::{Config, Instance};
 use mitra_models::{
     accounts::types::User,
-    database::{DatabaseClient, DatabaseError},
+    attachments::queries::get_attachment,
+    database::{
+        utils::local_time_to_utc,
+        DatabaseClient,
+        DatabaseError,
+    },
     emojis::types::{CustomEmoji as DbCustomEmoji},
+    polls::types::{PollData, PollResult},
     posts::{
-        queries::get_post_by_id,
-        helpers::{add_related_posts, add_user_actions, can_link_post},
-        types::{PostDetailed as DbPostDetailed, Visibility},
+        queries::{get_post_by_id, get_post_reactions},
+        helpers::{
+            add_related_posts,
+            add_user_actions,
+            can_link_post,
+            get_post_by_id_for_view,
+        },
+        types::{
+            ContentVariant,
+            PostContext,
+            PostCreateData,
+            PostDetailed as DbPostDetailed,
+            Visibility,
+        },
+    },
+    profiles::{
+        helpers::deduplicate_linked_profiles,
+        queries::get_profile_by_id,
+        types::Origin::Local,
+    },
+    reactions::{
+        queries::create_reaction,
+        types::{ReactionData, ReactionDetailed},
     },
     relationships::queries::get_subscribers,
+    scheduled_posts::types::ScheduledPost,
+};
+use mitra_services::media::MediaServer;
+use mitra_utils::{
+    languages::Language,
+    markdown::markdown_lite_to_html,
 };
-use mitra_utils::markdown::markdown_lite_to_html;
 use mitra_validators::{
     errors::ValidationError,
     polls::clean_poll_option_name,
-    posts::clean_local_content,
+    posts::{
+        clean_local_content,
+        validate_local_post_links,
+        validate_post_create_data,
+        validate_post_mentions,
+        validate_reply,
+    },
+    reactions::validate_reaction_data,
 };
 
 use crate::mastodon_api::{
@@ -40,9 +96,14 @@ use crate::mastodon_api::{
 };
 
 use super::types::{
+    visibility_from_str,
+    ScheduledStatus,
+    ScheduledTime,
     Status,
+    StatusCreateForm,
     POST_CONTENT_TYPE_HTML,
     POST_CONTENT_TYPE_MARKDOWN,
+    SCHEDULED_POST_LEAD_TIME_MIN,
 };
 
 pub struct PostContent {
@@ -156,6 +217,62 @@ pub async fn parse_content(
     Ok(output)
 }
 
+/// Parses content variants and merges their mentions, hashtags, links
+/// and emojis into the output of the primary content parser
+pub async fn parse_content_map(
+    db_client: &impl DatabaseClient,
+    instance: &Instance,
+    content_map: Vec<(Language, String)>,
+    content_type: &str,
+    maybe_quote_of_id: Option<Uuid>,
+    output: &mut PostContent,
+) -> Result<Vec<ContentVariant>, MastodonError> {
+    let mut variants = vec![];
+    for (language, text) in content_map {
+        let PostContent {
+            content,
+            content_source,
+            mentions,
+            hashtags,
+            links,
+            linked,
+            emojis,
+        } = parse_content(
+            db_client,
+            instance,
+            &text,
+            content_type,
+            maybe_quote_of_id,
+        ).await?;
+        for mention in mentions {
+            if !output.mentions.contains(&mention) {
+                output.mentions.push(mention);
+            };
+        };
+        for hashtag in hashtags {
+            if !output.hashtags.contains(&hashtag) {
+                output.hashtags.push(hashtag);
+            };
+        };
+        for (link, linked_post) in links.into_iter().zip(linked) {
+            if !output.links.contains(&link) {
+                output.links.push(link);
+                output.linked.push(linked_post);
+            };
+        };
+        for emoji in emojis {
+            if !output.emojis.iter()
+                .any(|known_emoji| known_emoji.id == emoji.id)
+            {
+                output.emojis.push(emoji);
+            };
+        };
+        let variant = ContentVariant { language, content, content_source };
+        variants.push(variant);
+    };
+    Ok(variants)
+}
+
 pub async fn parse_poll_options(
     db_client: &impl DatabaseClient,
     poll_options: &[String],
@@ -196,6 +313,8 @@ pub async fn prepare_mentions(
             mentions.insert(0, in_reply_to.author.id);
         };
     };
+    // Don't mention the same person twice
+    mentions = deduplicate_linked_profiles(db_client, mentions).await?;
     if visibility == Visibility::Subscribers {
         // Mention all subscribers.
         // This makes post accessible only to active subscribers
@@ -211,6 +330,303 @@ pub async fn prepare_mentions(
     Ok(mentions)
 }
 
+fn get_top_level_audience(
+    instance: &Instance,
+    current_user: &User,
+    visibility: Visibility,
+) -> Option<String> {
+    let actor_id = local_actor_id(
+        instance.uri_str(),
+        &current_user.profile.username,
+    );
+    match visibility {
+        Visibility::Public => {
+            Some(AP_PUBLIC.to_owned())
+        },
+        Visibility::Followers => {
+            Some(LocalActorCollection::Followers.of(&actor_id))
+        },
+        Visibility::Subscribers => {
+            Some(LocalActorCollection::Subscribers.of(&actor_id))
+        },
+        Visibility::Conversation => None, // will be rejected by validator
+        Visibility::Direct => None,
+    }
+}
+
+/// Parses status form and validates post data.
+/// Explicit mentions are added to mentions found in the content.
+pub async fn prepare_post_create_data(
+    db_client: &impl DatabaseClient,
+    config: &Config,
+    current_user: &User,
+    status_form: StatusCreateForm,
+    explicit_mentions: Vec<Uuid>,
+) -> Result<
+    (PostCreateData, Option<DbPostDetailed>, Vec<DbPostDetailed>),
+    MastodonError,
+> {
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let post_limits = get_role_post_limits(
+        &config.limits().posts,
+        &dynamic_config.role_limits,
+        current_user.role,
+    );
+    let instance = config.instance();
+    let maybe_in_reply_to = if let Some(in_reply_to_id) = status_form.in_reply_to_id {
+        let in_reply_to = match get_post_by_id_for_view(
+            db_client,
+            Some(&current_user.profile),
+            in_reply_to_id,
+        ).await {
+            Ok(post) => post,
+            Err(DatabaseError::NotFound(_)) => {
+                return Err(ValidationError("parent post does not exist").into());
+            },
+            Err(other_error) => return Err(other_error.into()),
+        };
+        Some(in_reply_to)
+    } else {
+        None
+    };
+    let maybe_group = if let Some(group_id) = status_form.group_id {
+        match get_profile_by_id(db_client, group_id).await {
+            Ok(profile) if profile.is_group() => Some(profile),
+            Ok(_) | Err(DatabaseError::NotFound(_)) => {
+                return Err(ValidationError("invalid group ID").into());
+            },
+            Err(other_error) => return Err(other_error.into()),
+        }
+    } else {
+        None
+    };
+    let visibility = match status_form.visibility.as_deref() {
+        Some(visibility_str) => visibility_from_str(visibility_str)?,
+        None => {
+            // Default visibility
+            maybe_in_reply_to.as_ref()
+                .map(|post| match post.visibility {
+                    Visibility::Public => Visibility::Public,
+                    _ => Visibility::Direct,
+                })
+                .unwrap_or(Visibility::Public)
+        },
+    };
+    // Parse content
+    check_local_post_length(
+        &post_limits,
+        status_form.status.as_deref().unwrap_or_default(),
+    )?;
+    let mut post_content = parse_content(
+        db_client,
+        &instance,
+        status_form.status.as_deref().unwrap_or_default(),
+        &status_form.content_type,
+        status_form.quote_id,
+    ).await?;
+    let content_map = status_form.content_map()?;
+    for (_, text) in content_map.iter() {
+        check_local_post_length(&post_limits, text)?;
+    };
+    let content_map = parse_content_map(
+        db_client,
+        &instance,
+        content_map,
+        &status_form.content_type,
+        status_form.quote_id,
+        &mut post_content,
+    ).await?;
+    let PostContent {
+        content,
+        content_source,
+        mut mentions,
+        hashtags,
+        links,
+        linked,
+        mut emojis,
+    } = post_content;
+    mentions.extend(explicit_mentions);
+    let mentions = prepare_mentions(
+        db_client,
+        current_user.id,
+        visibility,
+        maybe_in_reply_to.as_ref(),
+        mentions,
+    ).await?;
+
+    // Determine post context
+    let context = if let Some(ref in_reply_to) = maybe_in_reply_to {
+        PostContext::Reply {
+            conversation_id: in_reply_to.expect_conversation().id,
+            in_reply_to_id: in_reply_to.id,
+        }
+    } else {
+        PostContext::Top {
+            group_id: maybe_group.map(|group| group.id),
+            object_id: None,
+            audience: get_top_level_audience(
+                &instance,
+                current_user,
+                visibility,
+            ),
+        }
+    };
+
+    // Prepare poll data
+    let maybe_poll_data = if let Some(poll_params) = status_form.poll_params()? {
+        let (poll_options, poll_emojis) = parse_poll_options(
+            db_client,
+            &poll_params.options,
+        ).await?;
+        for poll_emoji in poll_emojis {
+            if !emojis.iter().any(|emoji| emoji.id == poll_emoji.id) {
+                emojis.push(poll_emoji);
+            };
+        };
+        let ends_at = poll_params.expires_at();
+        let poll_data = PollData {
+            multiple_choices: poll_params.multiple.unwrap_or(false),
+            ends_at: Some(ends_at),
+            results: poll_options.into_iter()
+                .map(|name| PollResult::new(&name))
+                .collect(),
+            voters_count: Some(0),
+        };
+        Some(poll_data)
+    } else {
+        None
+    };
+
+    // Validate post data
+    let post_data = PostCreateData {
+        id: None,
+        context: context,
+        title: status_form.title.clone(),
+        content: content,
+        content_source: content_source,
+        language: status_form.language()?,
+        content_map: content_map,
+        visibility: visibility,
+        is_sensitive: status_form.sensitive,
+        poll: maybe_poll_data,
+        attachments: status_form.media_ids,
+        mentions: mentions,
+        tags: hashtags,
+        links: links,
+        emojis: emojis.iter().map(|emoji| emoji.id).collect(),
+        url: None,
+        object_id: None,
+        created_at: Utc::now(),
+    };
+    validate_post_create_data(&post_data, Local)?;
+    validate_post_mentions(&post_data.mentions, post_data.visibility)?;
+    validate_local_post_links(&post_data.links, post_data.visibility)?;
+    if let Some(ref in_reply_to) = maybe_in_reply_to {
+        validate_reply(
+            in_reply_to,
+            current_user.id,
+            post_data.visibility,
+            &post_data.mentions,
+        )?;
+    };
+    check_post_limits(
+        &post_limits,
+        &post_data.attachments,
+        post_data.poll.as_ref(),
+        Local,
+    )?;
+    Ok((post_data, maybe_in_reply_to, linked))
+}
+
+/// Creates reaction and federates Like activity.
+/// Returns the activity, or `None` if post is already favourited.
+pub async fn favourite_post(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    current_user: &User,
+    post: &mut DbPostDetailed,
+) -> Result<Option<JsonValue>, MastodonError> {
+    let reaction_data = ReactionData {
+        author_id: current_user.id,
+        post_id: post.id,
+        content: None,
+        emoji_id: None,
+        visibility: Visibility::Direct,
+        activity_id: None,
+    };
+    validate_reaction_data(&reaction_data)?;
+    let maybe_reaction_created = match create_reaction(
+        db_client, reaction_data,
+    ).await {
+        Ok(reaction) => {
+            let reaction = ReactionDetailed
+                ::new(reaction, current_user.profile.clone(), None)
+                .map_err(DatabaseError::from)?;
+            post.reaction_count += 1;
+            post.reactions = get_post_reactions(db_client, post.id).await?;
+            Some(reaction)
+        },
+        Err(DatabaseError::AlreadyExists(_)) => None, // post already favourited
+        Err(other_error) => return Err(other_error.into()),
+    };
+    let Some(reaction) = maybe_reaction_created else {
+        return Ok(None);
+    };
+    // Federate
+    let media_server = MediaServer::new(config);
+    let like = prepare_like(
+        db_client,
+        &config.instance(),
+        &media_server,
+        current_user,
+        post,
+        &reaction,
+    ).await?;
+    let like_json = like.activity().clone();
+    like.save_and_enqueue(db_client).await?;
+    sync_conversation(
+        db_client,
+        &config.instance(),
+        post.expect_conversation(),
+        like_json.clone(),
+        reaction.visibility,
+    ).await?;
+    Ok(Some(like_json))
+}
+
+/// Replaces post content with the variant that matches
+/// client's language preferences best
+fn select_content_variant(
+    post: &mut DbPostDetailed,
+    language_preferences: &[Language],
+) {
+    if let Some(ref mut related_posts) = post.related_posts {
+        if let Some(ref mut repost_of) = related_posts.repost_of {
+            select_content_variant(repost_of, language_preferences);
+        };
+        for linked in related_posts.linked.iter_mut() {
+            select_content_variant(linked, language_preferences);
+        };
+    };
+    if post.content_map.is_empty() {
+        return;
+    };
+    for language in language_preferences {
+        if post.language == Some(*language) {
+            break;
+        };
+        if let Some(index) = post.content_map.iter()
+            .position(|variant| variant.language == *language)
+        {
+            let variant = post.content_map.remove(index);
+            post.content = variant.content;
+            post.content_source = variant.content_source;
+            post.language = Some(variant.language);
+            break;
+        };
+    };
+}
+
 /// Load related objects and build status for API response
 pub async fn build_status(
     db_client: &impl DatabaseClient,
@@ -218,11 +634,13 @@ pub async fn build_status(
     media_server: &ClientMediaServer,
     user: Option<&User>,
     mut post: DbPostDetailed,
+    language_preferences: &[Language],
 ) -> Result<Status, DatabaseError> {
     add_related_posts(db_client, vec![&mut post]).await?;
     if let Some(user) = user {
         add_user_actions(db_client, user.id, vec![&mut post]).await?;
     };
+    select_content_variant(&mut post, language_preferences);
     let status = Status::from_post(authority, media_server, post);
     Ok(status)
 }
@@ -233,6 +651,7 @@ pub async fn build_status_list(
     media_server: &ClientMediaServer,
     user: Option<&User>,
     mut posts: Vec<DbPostDetailed>,
+    language_preferences: &[Language],
 ) -> Result<Vec<Status>, DatabaseError> {
     add_related_posts(db_client, posts.iter_mut().collect()).await?;
     if let Some(user) = user {
@@ -240,7 +659,10 @@ pub async fn build_status_list(
     };
     let statuses: Vec<Status> = posts
         .into_iter()
-        .map(|post| Status::from_post(authority, media_server, post))
+        .map(|mut post| {
+            select_content_variant(&mut post, language_preferences);
+            Status::from_post(authority, media_server, post)
+        })
         .collect();
     Ok(statuses)
 }
@@ -255,6 +677,7 @@ pub async fn get_paginated_status_list(
     maybe_current_user: Option<&User>,
     posts: Vec<DbPostDetailed>,
     limit: &PageSize,
+    language_preferences: &[Language],
 ) -> Result<HttpResponse, DatabaseError> {
     let maybe_last_id = get_last_item(&posts, limit).map(|post| post.id);
     let statuses = build_status_list(
@@ -263,6 +686,7 @@ pub async fn get_paginated_status_list(
         media_server,
         maybe_current_user,
         posts,
+        language_preferences,
     ).await?;
     let response = get_paginated_response(
         base_url,
@@ -273,6 +697,53 @@ pub async fn get_paginated_status_list(
     Ok(response)
 }
 
+pub async fn build_scheduled_status(
+    db_client: &impl DatabaseClient,
+    media_server: &ClientMediaServer,
+    scheduled_post: ScheduledPost,
+) -> Result<ScheduledStatus, DatabaseError> {
+    let mut db_attachments = vec![];
+    for attachment_id in scheduled_post.expect_post_data().attachments.iter() {
+        match get_attachment(
+            db_client,
+            scheduled_post.owner_id,
+            *attachment_id,
+        ).await {
+            Ok(db_attachment) => db_attachments.push(db_attachment),
+            Err(DatabaseError::NotFound(_)) => continue,
+            Err(other_error) => return Err(other_error),
+        };
+    };
+    let scheduled_status = ScheduledStatus::from_db(
+        media_server,
+        scheduled_post,
+        db_attachments,
+    );
+    Ok(scheduled_status)
+}
+
+/// Converts scheduled time to UTC and checks that it is in the future
+pub async fn resolve_scheduled_time(
+    db_client: &impl DatabaseClient,
+    user: &User,
+    scheduled_time: ScheduledTime,
+) -> Result<DateTime<Utc>, MastodonError> {
+    let scheduled_at = match scheduled_time {
+        ScheduledTime::Utc(time) => time,
+        ScheduledTime::Local(time) => {
+            let timezone = user.shared_client_config.timezone
+                .as_deref()
+                .unwrap_or("UTC");
+            local_time_to_utc(db_client, time, timezone).await?
+        },
+    };
+    let lead_time = TimeDelta::minutes(SCHEDULED_POST_LEAD_TIME_MIN);
+    if scheduled_at < Utc::now() + lead_time {
+        return Err(ValidationError("scheduled time is too close to the current time").into());
+    };
+    Ok(scheduled_at)
+}
+
 #[cfg(test)]
 mod tests {
     use serial_test::serial;
@@ -331,4 +802,25 @@ mod tests {
         assert_eq!(content.mentions.len(), 1);
         assert_eq!(content.links.len(), 1);
     }
+
+    #[test]
+    fn test_select_content_variant() {
+        let mut post = DbPostDetailed {
+            content: "<p>test</p>".to_string(),
+            language: Some(Language::Eng),
+            content_map: vec![ContentVariant {
+                language: Language::Deu,
+                content: "<p>Test</p>".to_string(),
+                content_source: None,
+            }],
+            ..Default::default()
+        };
+        select_content_variant(
+            &mut post,
+            &[Language::Fra, Language::Deu, Language::Eng],
+        );
+        assert_eq!(post.content, "<p>Test</p>");
+        assert_eq!(post.language, Some(Language::Deu));
+        assert_eq!(post.content_map.is_empty(), true);
+    }
 }
@@ -1,6 +1,7 @@
+use std::collections::HashMap;
 use std::time::Duration;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -15,9 +16,13 @@ use mitra_activitypub::{
 };
 use mitra_models::{
     conversations::types::TrackingStatus,
+    attachments::types::{MediaAttachment as DbMediaAttachment},
     emojis::types::{CustomEmoji as DbCustomEmoji},
+    posts::queries::ThreadOrder,
     posts::types::{PostDetailed as DbPostDetailed, Visibility},
     profiles::types::DbActorProfile,
+    scheduled_posts::types::ScheduledPost,
+    tags::types::TagUsage as DbTagUsage,
 };
 use mitra_utils::languages::Language;
 use mitra_validators::{
@@ -45,6 +50,7 @@ pub const POST_CONTENT_TYPE_MARKDOWN: &str = "text/markdown";
 
 const TRACKING_STATUS_NORMAL: &str = "normal";
 const TRACKING_STATUS_FOLLOW: &str = "follow";
+const TRACKING_STATUS_MUTE: &str = "mute";
 
 /// https://docs.joinmastodon.org/entities/Quote/
 #[derive(Serialize)]
@@ -78,6 +84,11 @@ impl Mention {
 pub struct Tag {
     name: String,
     url: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    history: Option<Vec<TagHistory>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    following: Option<bool>,
 }
 
 impl Tag {
@@ -86,6 +97,42 @@ impl Tag {
         Tag {
             name: tag_name,
             url: tag_url,
+            history: None,
+            following: None,
+        }
+    }
+
+    pub fn with_history(
+        instance_uri: &str,
+        tag_name: String,
+        history: Vec<DbTagUsage>,
+    ) -> Self {
+        let history = history.into_iter()
+            .map(TagHistory::from)
+            .collect();
+        Tag {
+            history: Some(history),
+            // Following hashtags is not supported
+            following: Some(false),
+            ..Self::from_tag_name(instance_uri, tag_name)
+        }
+    }
+}
+
+/// https://docs.joinmastodon.org/entities/Tag/#history
+#[derive(Serialize)]
+struct TagHistory {
+    day: String,
+    uses: String,
+    accounts: String,
+}
+
+impl From<DbTagUsage> for TagHistory {
+    fn from(usage: DbTagUsage) -> Self {
+        Self {
+            day: usage.day.timestamp().to_string(),
+            uses: usage.uses.to_string(),
+            accounts: usage.accounts.to_string(),
         }
     }
 }
@@ -104,6 +151,7 @@ fn tracking_status_to_str(tracking_mode: Option<TrackingStatus>) -> &'static str
     match tracking_mode {
         None => TRACKING_STATUS_NORMAL,
         Some(TrackingStatus::Follow) => TRACKING_STATUS_FOLLOW,
+        Some(TrackingStatus::Mute) => TRACKING_STATUS_MUTE,
     }
 }
 
@@ -130,6 +178,7 @@ pub struct Status {
     pub replies_count: i32,
     pub favourites_count: i32,
     pub reblogs_count: i32,
+    pub quotes_count: i32,
     poll: Option<Poll>,
     pub media_attachments: Vec<Attachment>,
     quote: Option<Quote>,
@@ -141,6 +190,7 @@ pub struct Status {
     pub favourited: bool,
     pub reblogged: bool,
     bookmarked: bool,
+    muted: bool,
 
     // Pleroma API
     pleroma: PleromaData,
@@ -205,8 +255,9 @@ impl Status {
         } else {
             None
         };
+        let is_restricted = post.visibility != Visibility::Public;
         let attachments: Vec<Attachment> = post.attachments.into_iter()
-            .map(|item| Attachment::from_db(media_server, item))
+            .map(|item| Attachment::from_db(media_server, item, is_restricted))
             .collect();
         let mentions: Vec<Mention> = post.mentions.into_iter()
             .map(|item| Mention::from_profile(authority, item))
@@ -299,6 +350,7 @@ impl Status {
             replies_count: post.reply_count,
             favourites_count: favourites_count,
             reblogs_count: post.repost_count,
+            quotes_count: post.quote_count,
             poll: maybe_poll,
             media_attachments: attachments,
             quote: maybe_quote,
@@ -308,6 +360,9 @@ impl Status {
             favourited: post.actions.as_ref().is_some_and(|actions| actions.liked),
             reblogged: post.actions.as_ref().is_some_and(|actions| actions.reposted),
             bookmarked: post.actions.as_ref().is_some_and(|actions| actions.bookmarked),
+            muted: post.actions.as_ref().is_some_and(|actions| {
+                actions.conversation_tracking_status == Some(TrackingStatus::Mute)
+            }),
             conversation_tracking: post.actions.as_ref()
                 .map(|actions| tracking_status_to_str(actions.conversation_tracking_status)),
             pleroma: PleromaData {
@@ -402,6 +457,28 @@ pub struct StatusCreateForm {
 
     // Custom fields
     pub group_id: Option<Uuid>,
+
+    #[serde(default)]
+    pub content_map: HashMap<String, String>,
+
+    // Repost the post again after a given number of hours
+    pub reboost_after: Option<u32>,
+
+    pub scheduled_at: Option<ScheduledTime>,
+}
+
+fn parse_content_map(
+    content_map: &HashMap<String, String>,
+) -> Result<Vec<(Language, String)>, ValidationError> {
+    let mut variants = content_map.iter()
+        .map(|(language_code, text)| {
+            let language = parse_language_code(language_code)?;
+            Ok((language, text.clone()))
+        })
+        .collect::<Result<Vec<_>, ValidationError>>()?;
+    // Keep the order stable
+    variants.sort_by_key(|(language, _)| language.to_639_3());
+    Ok(variants)
 }
 
 impl StatusCreateForm {
@@ -411,6 +488,10 @@ impl StatusCreateForm {
             .transpose()
     }
 
+    pub fn content_map(&self) -> Result<Vec<(Language, String)>, ValidationError> {
+        parse_content_map(&self.content_map)
+    }
+
     pub fn poll_params(&self) -> Result<Option<PollParams>, ValidationError> {
         let maybe_poll_params = if let Some(ref poll_params) = self.poll {
             Some(poll_params.clone())
@@ -465,6 +546,7 @@ pub struct StatusSource {
     text: String,
     title: Option<String>,
     spoiler_text: String,
+    content_map: HashMap<String, String>, // custom field
 }
 
 impl StatusSource {
@@ -473,16 +555,30 @@ impl StatusSource {
             Some(source) => (source, POST_CONTENT_TYPE_MARKDOWN),
             None => (post.content, POST_CONTENT_TYPE_HTML),
         };
+        let content_map = post.content_map.into_iter()
+            .filter_map(|variant| {
+                let language_code = variant.language.to_639_1()?;
+                let text = variant.content_source.unwrap_or(variant.content);
+                Some((language_code.to_string(), text))
+            })
+            .collect();
         Self {
             id: post.id,
             content_type: content_type.to_string(),
             text: content_source,
             title: post.title,
             spoiler_text: "".to_string(),
+            content_map,
         }
     }
 }
 
+#[derive(Serialize)]
+pub struct StatusStats {
+    // Not available if view counting is disabled
+    pub views: Option<i32>,
+}
+
 // https://docs.joinmastodon.org/methods/statuses/#edit
 #[derive(Deserialize)]
 pub struct StatusUpdateForm {
@@ -501,6 +597,10 @@ pub struct StatusUpdateForm {
 
     // Pleroma API
     pub quote_id: Option<Uuid>,
+
+    // Custom field
+    #[serde(default)]
+    pub content_map: HashMap<String, String>,
 }
 
 impl StatusUpdateForm {
@@ -509,6 +609,10 @@ impl StatusUpdateForm {
             .map(|value| parse_language_code(value))
             .transpose()
     }
+
+    pub fn content_map(&self) -> Result<Vec<(Language, String)>, ValidationError> {
+        parse_content_map(&self.content_map)
+    }
 }
 
 #[derive(Serialize)]
@@ -517,6 +621,28 @@ pub struct Context {
     pub descendants: Vec<Status>,
 }
 
+const THREAD_ORDER_OLDEST: &str = "oldest";
+const THREAD_ORDER_NEWEST: &str = "newest";
+const THREAD_ORDER_MOST_REACTED: &str = "most_reacted";
+
+#[derive(Deserialize)]
+pub struct ThreadQueryParams {
+    pub max_id: Option<Uuid>,
+    sort: Option<String>,
+}
+
+impl ThreadQueryParams {
+    pub fn db_order(&self) -> Result<ThreadOrder, ValidationError> {
+        let order = match self.sort.as_deref() {
+            None | Some(THREAD_ORDER_OLDEST) => ThreadOrder::Oldest,
+            Some(THREAD_ORDER_NEWEST) => ThreadOrder::Newest,
+            Some(THREAD_ORDER_MOST_REACTED) => ThreadOrder::MostReacted,
+            Some(_) => return Err(ValidationError("invalid sort order")),
+        };
+        Ok(order)
+    }
+}
+
 fn default_favourite_list_page_size() -> PageSize { PageSize::new(40) }
 
 #[derive(Deserialize)]
@@ -527,9 +653,134 @@ pub struct FavouritedByQueryParams {
     pub limit: PageSize,
 }
 
+#[derive(Deserialize)]
+pub struct BookmarkForm {
+    pub folder_id: Option<String>,
+}
+
+// Maximum re-boost delay (in hours)
+pub const REBOOST_DELAY_MAX: u32 = 168;
+
+/// Publication time of a scheduled post.
+/// Time without UTC offset is interpreted in user's timezone.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ScheduledTime {
+    Utc(DateTime<Utc>),
+    Local(NaiveDateTime),
+}
+
+// Minimum delay before publication of a scheduled post (in minutes)
+pub const SCHEDULED_POST_LEAD_TIME_MIN: i64 = 5;
+// Maximum number of scheduled posts per user
+pub const SCHEDULED_POST_LIMIT: i64 = 300;
+
 #[derive(Deserialize)]
 pub struct ReblogForm {
     pub visibility: Option<String>,
+    pub comment: Option<String>,
+    pub scheduled_at: Option<ScheduledTime>,
+}
+
+/// Repost scheduled for a later time
+#[derive(Serialize)]
+pub struct ScheduledReblog {
+    id: Uuid,
+    #[serde(serialize_with = "serialize_datetime")]
+    scheduled_at: DateTime<Utc>,
+    status_id: Uuid,
+    visibility: &'static str,
+}
+
+impl ScheduledReblog {
+    pub fn from_db(scheduled_post: ScheduledPost) -> Self {
+        let status_id = scheduled_post.repost_of_id
+            .expect("repost_of_id should be present");
+        let visibility = scheduled_post.repost_visibility
+            .map(visibility_to_str)
+            .unwrap_or("public");
+        Self {
+            id: scheduled_post.id,
+            scheduled_at: scheduled_post.scheduled_at,
+            status_id: status_id,
+            visibility: visibility,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ScheduledStatusPoll {
+    options: Vec<String>,
+    expires_in: i64,
+    multiple: bool,
+}
+
+#[derive(Serialize)]
+pub struct ScheduledStatusParams {
+    text: String,
+    visibility: &'static str,
+    media_ids: Vec<Uuid>,
+    sensitive: bool,
+    in_reply_to_id: Option<Uuid>,
+    language: Option<String>,
+    poll: Option<ScheduledStatusPoll>,
+}
+
+/// https://docs.joinmastodon.org/entities/ScheduledStatus/
+#[derive(Serialize)]
+pub struct ScheduledStatus {
+    id: Uuid,
+    #[serde(serialize_with = "serialize_datetime")]
+    scheduled_at: DateTime<Utc>,
+    params: ScheduledStatusParams,
+    media_attachments: Vec<Attachment>,
+}
+
+impl ScheduledStatus {
+    pub fn from_db(
+        media_server: &ClientMediaServer,
+        scheduled_post: ScheduledPost,
+        db_attachments: Vec<DbMediaAttachment>,
+    ) -> Self {
+        let post_data = scheduled_post.post_data
+            .expect("post data should be present");
+        let visibility = post_data.visibility()
+            .map(visibility_to_str)
+            .unwrap_or("direct");
+        let language = post_data.language()
+            .and_then(|language| language.to_639_1())
+            .map(|code| code.to_owned());
+        let maybe_poll = post_data.poll.map(|poll| ScheduledStatusPoll {
+            options: poll.options,
+            expires_in: poll.duration,
+            multiple: poll.multiple_choices,
+        });
+        let params = ScheduledStatusParams {
+            text: post_data.content_source.unwrap_or(post_data.content),
+            visibility: visibility,
+            media_ids: post_data.attachments,
+            sensitive: post_data.is_sensitive,
+            in_reply_to_id: post_data.in_reply_to_id,
+            language: language,
+            poll: maybe_poll,
+        };
+        let media_attachments = db_attachments.into_iter()
+            .map(|db_attachment| {
+                Attachment::from_db(media_server, db_attachment, false)
+            })
+            .collect();
+        Self {
+            id: scheduled_post.id,
+            scheduled_at: scheduled_post.scheduled_at,
+            params: params,
+            media_attachments: media_attachments,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduledStatusUpdateForm {
+    pub scheduled_at: ScheduledTime,
 }
 
 fn default_repost_list_page_size() -> PageSize { PageSize::new(40) }
@@ -542,6 +793,16 @@ pub struct RebloggedByQueryParams {
     pub limit: PageSize,
 }
 
+fn default_quote_list_page_size() -> PageSize { PageSize::new(20) }
+
+#[derive(Deserialize)]
+pub struct QuotesQueryParams {
+    pub max_id: Option<Uuid>,
+
+    #[serde(default = "default_quote_list_page_size")]
+    pub limit: PageSize,
+}
+
 #[derive(Deserialize)]
 pub struct ConversationTrackingForm {
     status: String,
@@ -552,6 +813,7 @@ impl ConversationTrackingForm {
         let maybe_tracking_status = match self.status.as_str() {
             TRACKING_STATUS_NORMAL => None,
             TRACKING_STATUS_FOLLOW => Some(TrackingStatus::Follow),
+            TRACKING_STATUS_MUTE => Some(TrackingStatus::Mute),
             _ => return Err(ValidationError("invalid tracking status")),
         };
         Ok(maybe_tracking_status)
@@ -564,6 +826,29 @@ pub struct LoadConversationRequest {
     pub use_context: bool,
 }
 
+/// Background fetcher job
+#[derive(Serialize)]
+pub struct FetcherJob {
+    pub id: Uuid,
+    // object, context or replies
+    #[serde(rename = "type")]
+    pub job_type: &'static str,
+    pub status: &'static str,
+}
+
+impl FetcherJob {
+    pub fn queued(id: Uuid, job_type: &'static str) -> Self {
+        Self { id, job_type, status: "queued" }
+    }
+}
+
+#[derive(Serialize)]
+pub struct FetcherJobStatus {
+    pub id: Uuid,
+    // queued, running or finished
+    pub status: &'static str,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
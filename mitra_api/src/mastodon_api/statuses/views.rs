@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use actix_governor::{Governor, GovernorExtractor};
 use actix_web::{
     delete,
     dev::ConnectionInfo,
@@ -11,44 +15,63 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
-use apx_sdk::constants::AP_PUBLIC;
-use chrono::Utc;
+use chrono::{TimeDelta, Utc};
 use uuid::Uuid;
 
 use mitra_activitypub::{
-    adapters::posts::delete_local_post,
+    adapters::posts::{
+        delete_local_post,
+        preview_local_post,
+        publish_local_post,
+    },
     authority::Authority,
     builders::{
         announce::prepare_announce,
         add_context_activity::sync_conversation,
         add_note::prepare_add_note,
-        create_note::prepare_create_note,
-        like::prepare_like,
+        create_note::save_note_recipients,
         note::build_note,
+        quote_request::prepare_quote_request,
         remove_note::prepare_remove_note,
         undo_announce::prepare_undo_announce,
         undo_like::prepare_undo_like,
         update_note::prepare_update_note,
     },
-    identifiers::{
-        local_actor_id,
-        LocalActorCollection,
-    },
     queues::FetcherJobData,
 };
-use mitra_adapters::posts::check_post_limits;
-use mitra_config::Config;
+use mitra_adapters::{
+    dynamic_config::get_dynamic_config,
+    posts::{
+        check_duplicate_post,
+        check_local_post_length,
+        check_post_limits,
+        save_post_content_hash,
+    },
+    roles::get_role_post_limits,
+};
+use mitra_config::{Config, DuplicatePostAction};
 use mitra_models::{
-    accounts::types::Permission,
-    bookmarks::queries::{create_bookmark, delete_bookmark},
-    conversations::queries::set_conversation_tracking_status,
+    accounts::types::{FeatureFlag, Permission},
+    background_jobs::{
+        queries::get_job_by_id,
+        types::JobStatus,
+    },
+    bookmarks::queries::{
+        create_bookmark,
+        delete_bookmark,
+        get_bookmark_folder,
+        set_bookmark_folder,
+    },
+    conversations::{
+        queries::{set_conversation_tracking_status, unmute_conversation},
+        types::TrackingStatus,
+    },
     database::{
         get_database_client,
         DatabaseConnectionPool,
         DatabaseError,
     },
-    polls::types::{PollData, PollResult},
+    polls::types::PollData,
     posts::helpers::{
         add_related_posts,
         add_user_actions,
@@ -59,8 +82,10 @@ use mitra_models::{
         create_post,
         delete_repost,
         get_post_by_id,
+        get_post_quotes,
         get_post_reactions,
         get_post_reposts,
+        get_post_view_count,
         get_repost_by_author,
         get_thread,
         set_pinned_flag,
@@ -68,22 +93,30 @@ use mitra_models::{
         update_post,
     },
     posts::types::{
-        PostContext,
         PostCreateData,
+        PostDetailed,
         PostUpdateData,
         RelatedPosts,
         Visibility,
     },
-    profiles::{
-        queries::get_profile_by_id,
-        types::Origin::Local,
-    },
+    profiles::types::Origin::Local,
     reactions::queries::{
-        create_reaction,
         delete_reaction,
         get_post_reactions_detailed,
     },
-    reactions::types::{ReactionData, ReactionDetailed},
+    scheduled_posts::{
+        queries::{
+            create_scheduled_post,
+            create_scheduled_repost,
+            delete_scheduled_post,
+            get_scheduled_post,
+            get_scheduled_post_count,
+            get_scheduled_posts,
+            get_scheduled_reposts,
+            set_scheduled_post_time,
+        },
+        types::ScheduledPostData,
+    },
 };
 use mitra_services::{
     ipfs::{store as ipfs_store},
@@ -93,50 +126,65 @@ use mitra_validators::{
     errors::ValidationError,
     posts::{
         validate_local_post_links,
-        validate_post_create_data,
         validate_post_mentions,
         validate_post_update_data,
         validate_reply,
         validate_repost_data,
     },
-    reactions::validate_reaction_data,
 };
 
 use crate::{
-    http::{get_request_base_url, JsonOrQsForm},
+    http::{get_language_preferences, get_request_base_url, JsonOrQsForm},
     mastodon_api::{
         accounts::types::Account,
-        auth::get_current_user,
+        auth::{get_current_user, BearerAuth},
         errors::MastodonError,
         media_server::ClientMediaServer,
         pagination::{get_last_item, get_paginated_response},
     },
+    ratelimit::RatelimitConfigs,
     state::AppState,
 };
 
 use super::helpers::{
+    build_scheduled_status,
     build_status,
     build_status_list,
+    favourite_post,
     parse_content,
-    parse_poll_options,
+    parse_content_map,
     prepare_mentions,
+    prepare_post_create_data,
+    get_paginated_status_list,
+    resolve_scheduled_time,
     PostContent,
 };
 use super::types::{
     visibility_from_str,
+    REBOOST_DELAY_MAX,
+    POST_CONTENT_TYPE_MARKDOWN,
+    SCHEDULED_POST_LIMIT,
+    BookmarkForm,
     Context,
     ConversationTrackingForm,
     FavouritedByQueryParams,
+    FetcherJob,
+    FetcherJobStatus,
     LoadConversationRequest,
+    QuotesQueryParams,
     ReblogForm,
     RebloggedByQueryParams,
+    ScheduledReblog,
+    ScheduledStatusUpdateForm,
     Status,
     StatusCreateForm,
     StatusPreview,
     StatusPreviewForm,
     StatusSource,
+    StatusStats,
     StatusTombstone,
     StatusUpdateForm,
+    ThreadQueryParams,
 };
 
 // https://docs.joinmastodon.org/methods/statuses/#create
@@ -151,7 +199,7 @@ async fn create_status(
     status_form: JsonOrQsForm<StatusCreateForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     if !can_create_post(&current_user) {
         return Err(MastodonError::PermissionError);
     };
@@ -160,151 +208,44 @@ async fn create_status(
         Either::Left(json) => json.into_inner(),
         Either::Right(form) => form.into_inner(),
     };
-    let maybe_in_reply_to = if let Some(in_reply_to_id) = status_form.in_reply_to_id {
-        let in_reply_to = match get_post_by_id_for_view(
-            db_client,
-            Some(&current_user.profile),
-            in_reply_to_id,
-        ).await {
-            Ok(post) => post,
-            Err(DatabaseError::NotFound(_)) => {
-                return Err(ValidationError("parent post does not exist").into());
-            },
-            Err(other_error) => return Err(other_error.into()),
-        };
-        Some(in_reply_to)
-    } else {
-        None
-    };
-    let maybe_group = if let Some(group_id) = status_form.group_id {
-        match get_profile_by_id(db_client, group_id).await {
-            Ok(profile) if profile.is_group() => Some(profile),
-            Ok(_) | Err(DatabaseError::NotFound(_)) => {
-                return Err(ValidationError("invalid group ID").into());
-            },
-            Err(other_error) => return Err(other_error.into()),
-        }
-    } else {
-        None
-    };
-    let visibility = match status_form.visibility.as_deref() {
-        Some(visibility_str) => visibility_from_str(visibility_str)?,
-        None => {
-            // Default visibility
-            maybe_in_reply_to.as_ref()
-                .map(|post| match post.visibility {
-                    Visibility::Public => Visibility::Public,
-                    _ => Visibility::Direct,
-                })
-                .unwrap_or(Visibility::Public)
-        },
-    };
-    // Parse content
-    let PostContent { content, content_source, mentions, hashtags, links, linked, mut emojis } =
-        parse_content(
-            db_client,
-            &instance,
-            status_form.status.as_deref().unwrap_or_default(),
-            &status_form.content_type,
-            status_form.quote_id,
-        ).await?;
-    let mentions = prepare_mentions(
+    let maybe_reboost_after = status_form.reboost_after;
+    let maybe_scheduled_at = status_form.scheduled_at;
+    let (mut post_data, maybe_in_reply_to, linked) = prepare_post_create_data(
         db_client,
-        current_user.id,
-        visibility,
-        maybe_in_reply_to.as_ref(),
-        mentions,
+        &config,
+        &current_user,
+        status_form,
+        vec![],
     ).await?;
-
-    // Determine post context
-    let context = if let Some(ref in_reply_to) = maybe_in_reply_to {
-        PostContext::Reply {
-            conversation_id: in_reply_to.expect_conversation().id,
-            in_reply_to_id: in_reply_to.id,
-        }
-    } else {
-        let actor_id = local_actor_id(
-            instance.uri_str(),
-            &current_user.profile.username,
-        );
-        let audience = match visibility {
-            Visibility::Public => {
-                Some(AP_PUBLIC.to_owned())
-            },
-            Visibility::Followers => {
-                Some(LocalActorCollection::Followers.of(&actor_id))
-            },
-            Visibility::Subscribers => {
-                Some(LocalActorCollection::Subscribers.of(&actor_id))
-            },
-            Visibility::Conversation => None, // will be rejected by validator
-            Visibility::Direct => None,
+    if let Some(reboost_after) = maybe_reboost_after {
+        if reboost_after == 0 || reboost_after > REBOOST_DELAY_MAX {
+            return Err(ValidationError("invalid re-boost delay").into());
+        };
+        if post_data.visibility != Visibility::Public {
+            return Err(ValidationError("only public posts can be re-boosted").into());
         };
-        PostContext::Top {
-            group_id: maybe_group.map(|group| group.id),
-            object_id: None,
-            audience,
-        }
     };
-
-    // Prepare poll data
-    let maybe_poll_data = if let Some(poll_params) = status_form.poll_params()? {
-        let (poll_options, poll_emojis) = parse_poll_options(
-            db_client,
-            &poll_params.options,
-        ).await?;
-        for poll_emoji in poll_emojis {
-            if !emojis.iter().any(|emoji| emoji.id == poll_emoji.id) {
-                emojis.push(poll_emoji);
-            };
+    if maybe_reboost_after.is_some() || maybe_scheduled_at.is_some() {
+        let scheduled_post_count =
+            get_scheduled_post_count(db_client, current_user.id).await?;
+        if scheduled_post_count >= SCHEDULED_POST_LIMIT {
+            return Err(ValidationError("too many scheduled posts").into());
         };
-        let ends_at = poll_params.expires_at();
-        let poll_data = PollData {
-            multiple_choices: poll_params.multiple.unwrap_or(false),
-            ends_at: Some(ends_at),
-            results: poll_options.into_iter()
-                .map(|name| PollResult::new(&name))
-                .collect(),
-            voters_count: Some(0),
+    };
+    let maybe_scheduled_at = if let Some(scheduled_time) = maybe_scheduled_at {
+        if maybe_reboost_after.is_some() {
+            return Err(ValidationError("scheduled post can not be re-boosted").into());
         };
-        Some(poll_data)
+        let scheduled_at = resolve_scheduled_time(
+            db_client,
+            &current_user,
+            scheduled_time,
+        ).await?;
+        Some(scheduled_at)
     } else {
         None
     };
 
-    // Validate post data
-    let post_data = PostCreateData {
-        id: None,
-        context: context,
-        title: status_form.title.clone(),
-        content: content,
-        content_source: content_source,
-        language: status_form.language()?,
-        visibility: visibility,
-        is_sensitive: status_form.sensitive,
-        poll: maybe_poll_data,
-        attachments: status_form.media_ids,
-        mentions: mentions,
-        tags: hashtags,
-        links: links,
-        emojis: emojis.iter().map(|emoji| emoji.id).collect(),
-        url: None,
-        object_id: None,
-        created_at: Utc::now(),
-    };
-    validate_post_create_data(&post_data, Local)?;
-    validate_post_mentions(&post_data.mentions, post_data.visibility)?;
-    validate_local_post_links(&post_data.links, post_data.visibility)?;
-    if let Some(ref in_reply_to) = maybe_in_reply_to {
-        validate_reply(
-            in_reply_to,
-            current_user.id,
-            post_data.visibility,
-            &post_data.mentions,
-        )?;
-    };
-    check_post_limits(&config.limits.posts, &post_data.attachments, Local)?;
-
     // Check idempotency key
     // https://datatracker.ietf.org/doc/draft-ietf-httpapi-idempotency-key-header/
     let maybe_idempotency_key = request.headers()
@@ -318,6 +259,21 @@ async fn create_status(
             // TODO: store Uuid in cache
             let post_id = Uuid::parse_str(post_id)
                 .map_err(MastodonError::from_internal)?;
+            if maybe_scheduled_at.is_some() {
+                let scheduled_post = get_scheduled_post(
+                    db_client,
+                    current_user.id,
+                    post_id,
+                ).await?;
+                let base_url = get_request_base_url(connection_info);
+                let media_server = ClientMediaServer::new(&config, &base_url);
+                let scheduled_status = build_scheduled_status(
+                    db_client,
+                    &media_server,
+                    scheduled_post,
+                ).await?;
+                return Ok(HttpResponse::Ok().json(scheduled_status));
+            };
             let post = get_post_by_id(db_client, post_id).await?;
             if post.author.id != current_user.id {
                 return Err(MastodonError::PermissionError);
@@ -331,45 +287,88 @@ async fn create_status(
                 &media_server,
                 Some(&current_user),
                 post,
+                &get_language_preferences(&request),
             ).await?;
             return Ok(HttpResponse::Ok().json(status));
         };
     };
 
-    // Create post
-    let mut post = create_post(db_client, current_user.id, post_data).await?;
-    if let Some(idempotency_key) = maybe_idempotency_key {
-        post_id_cache.set(idempotency_key, post.id.to_string());
+    // Check for duplicates
+    let limits = config.limits();
+    if check_duplicate_post(
+        db_client,
+        &limits.duplicate_posts,
+        current_user.id,
+        &post_data.content,
+    ).await? {
+        match limits.duplicate_posts.action {
+            DuplicatePostAction::Reject => {
+                log::warn!("duplicate post rejected: {}", current_user.id);
+                return Err(MastodonError::OperationError("duplicate post"));
+            },
+            DuplicatePostAction::Flag => {
+                log::warn!("duplicate post flagged: {}", current_user.id);
+                post_data.is_sensitive = true;
+            },
+        };
     };
-    drop(post_id_cache); // release lock
 
-    // Same as add_related_posts
-    post.related_posts = Some(RelatedPosts {
-        in_reply_to: maybe_in_reply_to.map(|mut in_reply_to| {
-            in_reply_to.reply_count += 1;
-            Box::new(in_reply_to)
-        }),
-        repost_of: None,
-        linked: linked,
-    });
-    // Federate
-    let media_server = MediaServer::new(&config);
-    let create_note = prepare_create_note(
+    if let Some(scheduled_at) = maybe_scheduled_at {
+        // Duplicate check is repeated when the post is published
+        let scheduled_post_data = ScheduledPostData::new(post_data)
+            .map_err(MastodonError::from_internal)?;
+        let scheduled_post = create_scheduled_post(
+            db_client,
+            current_user.id,
+            scheduled_post_data,
+            scheduled_at,
+        ).await?;
+        if let Some(idempotency_key) = maybe_idempotency_key {
+            post_id_cache.set(idempotency_key, scheduled_post.id.to_string());
+        };
+        drop(post_id_cache); // release lock
+        let base_url = get_request_base_url(connection_info);
+        let media_server = ClientMediaServer::new(&config, &base_url);
+        let scheduled_status = build_scheduled_status(
+            db_client,
+            &media_server,
+            scheduled_post,
+        ).await?;
+        return Ok(HttpResponse::Ok().json(scheduled_status));
+    };
+
+    // Create post
+    let (post, _) = publish_local_post(
+        &config,
         db_client,
-        &instance,
-        &media_server,
         &current_user,
-        &post,
+        post_data,
+        maybe_in_reply_to,
+        linked,
     ).await?;
-    let create_note_json = create_note.activity().clone();
-    create_note.save_and_enqueue(db_client).await?;
-    sync_conversation(
+    save_post_content_hash(
         db_client,
-        &instance,
-        post.expect_conversation(),
-        create_note_json,
-        post.visibility,
+        &limits.duplicate_posts,
+        post.id,
+        current_user.id,
+        &post.content,
     ).await?;
+    if let Some(idempotency_key) = maybe_idempotency_key {
+        post_id_cache.set(idempotency_key, post.id.to_string());
+    };
+    drop(post_id_cache); // release lock
+    if let Some(reboost_after) = maybe_reboost_after {
+        let scheduled_for =
+            post.created_at + TimeDelta::hours(reboost_after.into());
+        create_scheduled_repost(
+            db_client,
+            current_user.id,
+            post.id,
+            Visibility::Public,
+            true, // replace existing
+            scheduled_for,
+        ).await?;
+    };
 
     let base_url = get_request_base_url(connection_info);
     let authority = Authority::from(&instance);
@@ -391,7 +390,7 @@ async fn preview_status(
     status_form: web::Json<StatusPreviewForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    get_current_user(db_client, auth.token()).await?;
+    get_current_user(db_client, &auth).await?;
     let instance = config.instance();
     let status_form = status_form.into_inner();
     let PostContent { content, emojis, .. } =
@@ -413,17 +412,47 @@ async fn preview_status(
     Ok(HttpResponse::Ok().json(preview))
 }
 
+// Returns activity that would be federated, without creating a post
+#[post("/preview_activity")]
+async fn preview_status_activity(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    status_form: web::Json<StatusCreateForm>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &mut **get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if !can_create_post(&current_user) {
+        return Err(MastodonError::PermissionError);
+    };
+    let (post_data, _, _) = prepare_post_create_data(
+        db_client,
+        &config,
+        &current_user,
+        status_form.into_inner(),
+        vec![],
+    ).await?;
+    let activity = preview_local_post(
+        &config,
+        db_client,
+        &current_user,
+        post_data,
+    ).await?;
+    Ok(HttpResponse::Ok().json(activity))
+}
+
 #[get("/{status_id}")]
 async fn get_status(
     auth: Option<BearerAuth>,
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     status_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let maybe_current_user = match auth {
-        Some(auth) => Some(get_current_user(db_client, auth.token()).await?),
+        Some(auth) => Some(get_current_user(db_client, &auth).await?),
         None => None,
     };
     let post = get_post_by_id_for_view(
@@ -441,6 +470,7 @@ async fn get_status(
         &media_server,
         maybe_current_user.as_ref(),
         post,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -452,7 +482,7 @@ async fn get_status_source(
     status_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let post = get_post_by_id(db_client, *status_id).await?;
     if post.author.id != current_user.id {
         return Err(MastodonError::PermissionError);
@@ -461,6 +491,29 @@ async fn get_status_source(
     Ok(HttpResponse::Ok().json(status_source))
 }
 
+#[get("/{status_id}/stats")]
+async fn get_status_stats(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    status_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let post = get_post_by_id(db_client, *status_id).await?;
+    if post.author.id != current_user.id {
+        return Err(MastodonError::PermissionError);
+    };
+    let maybe_view_count = if config.post_view_count_enabled {
+        let view_count = get_post_view_count(db_client, post.id).await?;
+        Some(view_count)
+    } else {
+        None
+    };
+    let stats = StatusStats { views: maybe_view_count };
+    Ok(HttpResponse::Ok().json(stats))
+}
+
 #[put("/{status_id}")]
 async fn edit_status(
     auth: BearerAuth,
@@ -471,11 +524,17 @@ async fn edit_status(
     status_form: web::Json<StatusUpdateForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let post = get_post_by_id(db_client, *status_id).await?;
     if post.author.id != current_user.id {
         return Err(MastodonError::PermissionError);
     };
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let post_limits = get_role_post_limits(
+        &config.limits().posts,
+        &dynamic_config.role_limits,
+        current_user.role,
+    );
     let maybe_in_reply_to = if let Some(in_reply_to_id) = post.in_reply_to_id {
         let in_reply_to = get_post_by_id(db_client, in_reply_to_id).await?;
         Some(in_reply_to)
@@ -485,14 +544,28 @@ async fn edit_status(
     let instance = config.instance();
     let status_form = status_form.into_inner();
     // Parse content
-    let PostContent { content, content_source, mentions, hashtags, links, linked, emojis } =
-        parse_content(
-            db_client,
-            &instance,
-            &status_form.status,
-            &status_form.content_type,
-            status_form.quote_id,
-        ).await?;
+    check_local_post_length(&post_limits, &status_form.status)?;
+    let mut post_content = parse_content(
+        db_client,
+        &instance,
+        &status_form.status,
+        &status_form.content_type,
+        status_form.quote_id,
+    ).await?;
+    let content_map = status_form.content_map()?;
+    for (_, text) in content_map.iter() {
+        check_local_post_length(&post_limits, text)?;
+    };
+    let content_map = parse_content_map(
+        db_client,
+        &instance,
+        content_map,
+        &status_form.content_type,
+        status_form.quote_id,
+        &mut post_content,
+    ).await?;
+    let PostContent { content, content_source, mentions, hashtags, links, emojis, .. } =
+        post_content;
     let mentions = prepare_mentions(
         db_client,
         post.author.id,
@@ -507,6 +580,7 @@ async fn edit_status(
         content: content,
         content_source: content_source,
         language: status_form.language()?,
+        content_map: content_map,
         is_sensitive: status_form.sensitive,
         poll: post.poll.map(PollData::from),
         attachments: status_form.media_ids,
@@ -528,21 +602,22 @@ async fn edit_status(
             &post_data.mentions,
         )?;
     };
-    check_post_limits(&config.limits.posts, &post_data.attachments, Local)?;
+    check_post_limits(
+        &post_limits,
+        &post_data.attachments,
+        post_data.poll.as_ref(),
+        Local,
+    )?;
     let (mut post, deletion_queue) =
         update_post(db_client, post.id, post_data).await?;
     deletion_queue.into_job(db_client).await?;
-    // Same as add_related_posts
-    post.related_posts = Some(RelatedPosts {
-        in_reply_to: maybe_in_reply_to.map(Box::new),
-        repost_of: None,
-        linked: linked,
-    });
+    // Quote authorization is preserved if quoted post didn't change
+    add_related_posts(db_client, vec![&mut post]).await?;
     add_user_actions(db_client, current_user.id, vec![&mut post]).await?;
 
     // Federate
     let media_server = MediaServer::new(&config);
-    let update_note = prepare_update_note(
+    let (update_note, maybe_create_note) = prepare_update_note(
         db_client,
         &instance,
         &media_server,
@@ -551,6 +626,19 @@ async fn edit_status(
     ).await?;
     let update_note_json = update_note.activity().clone();
     update_note.save_and_enqueue(db_client).await?;
+    if let Some(create_note) = maybe_create_note {
+        // Deliver post to new mentions
+        create_note.save_and_enqueue(db_client).await?;
+        save_note_recipients(db_client, &post).await?;
+    };
+    if let Some(quote_request) = prepare_quote_request(
+        &instance,
+        &media_server,
+        &current_user,
+        &post,
+    ) {
+        quote_request.save_and_enqueue(db_client).await?;
+    };
     sync_conversation(
         db_client,
         &instance,
@@ -579,7 +667,7 @@ async fn delete_status(
     status_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let post = get_post_by_id(db_client, *status_id).await?;
     if post.author.id != current_user.id {
         return Err(MastodonError::PermissionError);
@@ -606,25 +694,61 @@ async fn delete_status(
     Ok(HttpResponse::Ok().json(tombstone))
 }
 
+/// Returns ID of the last item if thread has more replies
+fn get_thread_last_item(
+    posts: &[PostDetailed],
+    post_id: Uuid,
+    max_id: Option<Uuid>,
+    limit: u16,
+) -> Option<Uuid> {
+    let replies_count = if max_id.is_some() {
+        posts.len()
+    } else {
+        posts.iter()
+            .position(|post| post.id == post_id)
+            .map(|index| posts.len() - index - 1)
+            .unwrap_or(0)
+    };
+    if replies_count > 0 && replies_count >= usize::from(limit) {
+        posts.last().map(|post| post.id)
+    } else {
+        None
+    }
+}
+
 #[get("/{status_id}/context")]
 async fn get_context(
     auth: Option<BearerAuth>,
     config: web::Data<Config>,
-    connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
+    request_uri: Uri,
     status_id: web::Path<Uuid>,
+    query_params: web::Query<ThreadQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let maybe_current_user = match auth {
-        Some(auth) => Some(get_current_user(db_client, auth.token()).await?),
+        Some(auth) => Some(get_current_user(db_client, &auth).await?),
         None => None,
     };
+    let limits = config.limits();
+    let post_limits = &limits.posts;
     let posts = get_thread(
         db_client,
         *status_id,
         maybe_current_user.as_ref().map(|user| user.id),
+        query_params.db_order()?,
+        post_limits.thread_depth_limit,
+        query_params.max_id,
+        post_limits.thread_size_limit,
     ).await?;
-    let base_url = get_request_base_url(connection_info);
+    let maybe_last_id = get_thread_last_item(
+        &posts,
+        *status_id,
+        query_params.max_id,
+        post_limits.thread_size_limit,
+    );
+    let base_url = get_request_base_url(request.connection_info().clone());
     let authority = Authority::from(&config.instance());
     let media_server = ClientMediaServer::new(&config, &base_url);
     let statuses = build_status_list(
@@ -633,10 +757,12 @@ async fn get_context(
         &media_server,
         maybe_current_user.as_ref(),
         posts,
+        &get_language_preferences(&request),
     ).await?;
     let mut ancestors = vec![];
     let mut descendants = vec![];
-    let mut is_ancestor = true;
+    // Next page contains only descendants
+    let mut is_ancestor = query_params.max_id.is_none();
     for status in statuses {
         if is_ancestor {
             if status.id == *status_id {
@@ -649,28 +775,48 @@ async fn get_context(
         };
     };
     let context = Context { ancestors, descendants };
-    Ok(HttpResponse::Ok().json(context))
+    let response = get_paginated_response(
+        &base_url,
+        &request_uri,
+        context,
+        maybe_last_id,
+    );
+    Ok(response)
 }
 
 #[get("/{status_id}/thread")]
 async fn get_thread_view(
     auth: Option<BearerAuth>,
     config: web::Data<Config>,
-    connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
+    request_uri: Uri,
     status_id: web::Path<Uuid>,
+    query_params: web::Query<ThreadQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let maybe_current_user = match auth {
-        Some(auth) => Some(get_current_user(db_client, auth.token()).await?),
+        Some(auth) => Some(get_current_user(db_client, &auth).await?),
         None => None,
     };
+    let limits = config.limits();
+    let post_limits = &limits.posts;
     let posts = get_thread(
         db_client,
         *status_id,
         maybe_current_user.as_ref().map(|user| user.id),
+        query_params.db_order()?,
+        post_limits.thread_depth_limit,
+        query_params.max_id,
+        post_limits.thread_size_limit,
     ).await?;
-    let base_url = get_request_base_url(connection_info);
+    let maybe_last_id = get_thread_last_item(
+        &posts,
+        *status_id,
+        query_params.max_id,
+        post_limits.thread_size_limit,
+    );
+    let base_url = get_request_base_url(request.connection_info().clone());
     let authority = Authority::from(&config.instance());
     let media_server = ClientMediaServer::new(&config, &base_url);
     let statuses = build_status_list(
@@ -679,8 +825,15 @@ async fn get_thread_view(
         &media_server,
         maybe_current_user.as_ref(),
         posts,
+        &get_language_preferences(&request),
     ).await?;
-    Ok(HttpResponse::Ok().json(statuses))
+    let response = get_paginated_response(
+        &base_url,
+        &request_uri,
+        statuses,
+        maybe_last_id,
+    );
+    Ok(response)
 }
 
 #[post("/{status_id}/favourite")]
@@ -689,60 +842,22 @@ async fn favourite(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     status_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let mut post = get_post_by_id_for_view(
         db_client,
         Some(&current_user.profile),
         *status_id,
     ).await?;
-    let reaction_data = ReactionData {
-        author_id: current_user.id,
-        post_id: status_id.into_inner(),
-        content: None,
-        emoji_id: None,
-        visibility: Visibility::Direct,
-        activity_id: None,
-    };
-    validate_reaction_data(&reaction_data)?;
-    let maybe_reaction_created = match create_reaction(
-        db_client, reaction_data,
-    ).await {
-        Ok(reaction) => {
-            let reaction = ReactionDetailed
-                ::new(reaction, current_user.profile.clone(), None)
-                .map_err(DatabaseError::from)?;
-            post.reaction_count += 1;
-            post.reactions = get_post_reactions(db_client, post.id).await?;
-            Some(reaction)
-        },
-        Err(DatabaseError::AlreadyExists(_)) => None, // post already favourited
-        Err(other_error) => return Err(other_error.into()),
-    };
-
-    let media_server = MediaServer::new(&config);
-    if let Some(reaction) = maybe_reaction_created {
-        // Federate
-        let like = prepare_like(
-            db_client,
-            &config.instance(),
-            &media_server,
-            &current_user,
-            &post,
-            &reaction,
-        ).await?;
-        let like_json = like.activity().clone();
-        like.save_and_enqueue(db_client).await?;
-        sync_conversation(
-            db_client,
-            &config.instance(),
-            post.expect_conversation(),
-            like_json,
-            reaction.visibility,
-        ).await?;
-    };
+    favourite_post(
+        &config,
+        db_client,
+        &current_user,
+        &mut post,
+    ).await?;
 
     let base_url = get_request_base_url(connection_info);
     let authority = Authority::from(&config.instance());
@@ -753,6 +868,7 @@ async fn favourite(
         &media_server,
         Some(&current_user),
         post,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -763,10 +879,11 @@ async fn unfavourite(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     status_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let mut post = get_post_by_id_for_view(
         db_client,
         Some(&current_user.profile),
@@ -817,6 +934,7 @@ async fn unfavourite(
         &media_server,
         Some(&current_user),
         post,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -834,7 +952,7 @@ async fn get_favourited_by(
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let maybe_current_user = if let Some(auth) = maybe_auth {
-        let current_user = get_current_user(db_client, auth.token()).await?;
+        let current_user = get_current_user(db_client, &auth).await?;
         Some(current_user)
     } else {
         None
@@ -889,11 +1007,12 @@ async fn reblog(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     status_id: web::Path<Uuid>,
     reblog_form: Option<web::Json<ReblogForm>>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     if !can_create_post(&current_user) {
         return Err(MastodonError::PermissionError);
     };
@@ -907,6 +1026,86 @@ async fn reblog(
         Some(visibility_str) => visibility_from_str(visibility_str)?,
         None => Visibility::Public,
     };
+    let maybe_comment = reblog_form.as_ref()
+        .and_then(|form| form.comment.as_deref())
+        .filter(|comment| !comment.trim().is_empty());
+    let maybe_scheduled_at = reblog_form.as_ref()
+        .and_then(|form| form.scheduled_at);
+    if let Some(scheduled_time) = maybe_scheduled_at {
+        if maybe_comment.is_some() {
+            return Err(ValidationError("repost with comment can not be scheduled").into());
+        };
+        let scheduled_post_count =
+            get_scheduled_post_count(db_client, current_user.id).await?;
+        if scheduled_post_count >= SCHEDULED_POST_LIMIT {
+            return Err(ValidationError("too many scheduled posts").into());
+        };
+        let scheduled_at = resolve_scheduled_time(
+            db_client,
+            &current_user,
+            scheduled_time,
+        ).await?;
+        let repost_data = PostCreateData::repost(post.id, visibility, None);
+        validate_repost_data(&repost_data)?;
+        let scheduled_post = create_scheduled_repost(
+            db_client,
+            current_user.id,
+            post.id,
+            visibility,
+            false, // don't replace existing
+            scheduled_at,
+        ).await?;
+        let scheduled_reblog = ScheduledReblog::from_db(scheduled_post);
+        return Ok(HttpResponse::Ok().json(scheduled_reblog));
+    };
+    if let Some(comment) = maybe_comment {
+        // Repost with comment is converted into a quote post
+        let status_form = StatusCreateForm {
+            status: Some(comment.to_string()),
+            title: None,
+            language: None,
+            media_ids: vec![],
+            in_reply_to_id: None,
+            visibility: reblog_form.as_ref()
+                .and_then(|form| form.visibility.clone()),
+            sensitive: false,
+            poll: None,
+            poll_options: vec![],
+            poll_expires_in: None,
+            poll_multiple: None,
+            content_type: POST_CONTENT_TYPE_MARKDOWN.to_string(),
+            quote_id: Some(post.id),
+            group_id: None,
+            content_map: HashMap::new(),
+            reboost_after: None,
+            scheduled_at: None,
+        };
+        let (post_data, maybe_in_reply_to, linked) = prepare_post_create_data(
+            db_client,
+            &config,
+            &current_user,
+            status_form,
+            vec![],
+        ).await?;
+        let (quote, _) = publish_local_post(
+            &config,
+            db_client,
+            &current_user,
+            post_data,
+            maybe_in_reply_to,
+            linked,
+        ).await?;
+
+        let base_url = get_request_base_url(connection_info);
+        let authority = Authority::from(&config.instance());
+        let media_server = ClientMediaServer::new(&config, &base_url);
+        let status = Status::from_post(
+            &authority,
+            &media_server,
+            quote,
+        );
+        return Ok(HttpResponse::Ok().json(status));
+    };
     let repost_data = PostCreateData::repost(
         status_id.into_inner(),
         visibility,
@@ -937,6 +1136,7 @@ async fn reblog(
         &media_server,
         Some(&current_user),
         repost,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -947,10 +1147,11 @@ async fn unreblog(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     status_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let repost = get_repost_by_author(
         db_client,
         *status_id,
@@ -977,6 +1178,7 @@ async fn unreblog(
         &media_server,
         Some(&current_user),
         post,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -994,7 +1196,7 @@ async fn get_reblogged_by(
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let maybe_current_user = if let Some(auth) = maybe_auth {
-        let current_user = get_current_user(db_client, auth.token()).await?;
+        let current_user = get_current_user(db_client, &auth).await?;
         Some(current_user)
     } else {
         None
@@ -1037,6 +1239,53 @@ async fn get_reblogged_by(
     Ok(response)
 }
 
+/// Returns posts that quote the given post
+#[get("/{status_id}/quotes")]
+async fn get_quotes(
+    maybe_auth: Option<BearerAuth>,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
+    status_id: web::Path<Uuid>,
+    params: web::Query<QuotesQueryParams>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let maybe_current_user = if let Some(auth) = maybe_auth {
+        let current_user = get_current_user(db_client, &auth).await?;
+        Some(current_user)
+    } else {
+        None
+    };
+    let post = get_post_by_id_for_view(
+        db_client,
+        maybe_current_user.as_ref().map(|user| &user.profile),
+        *status_id,
+    ).await?;
+    let quotes = get_post_quotes(
+        db_client,
+        post.id,
+        maybe_current_user.as_ref().map(|user| user.id),
+        params.max_id,
+        params.limit.inner(),
+    ).await?;
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let response = get_paginated_status_list(
+        db_client,
+        &base_url,
+        &authority,
+        &media_server,
+        request.uri(),
+        maybe_current_user.as_ref(),
+        quotes,
+        &params.limit,
+        &get_language_preferences(&request),
+    ).await?;
+    Ok(response)
+}
+
 /// https://docs.joinmastodon.org/methods/statuses/#bookmark
 #[post("/{status_id}/bookmark")]
 async fn bookmark_view(
@@ -1044,17 +1293,47 @@ async fn bookmark_view(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     status_id: web::Path<Uuid>,
+    bookmark_form: Option<web::Json<BookmarkForm>>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let post = get_post_by_id_for_view(
         db_client,
         Some(&current_user.profile),
         *status_id,
     ).await?;
-    match create_bookmark(db_client, current_user.id, post.id).await {
-        Ok(_) | Err(DatabaseError::AlreadyExists(_)) => (),
+    let maybe_folder_id = match bookmark_form.as_ref()
+        .and_then(|form| form.folder_id.as_ref())
+    {
+        Some(folder_id) => {
+            let folder_id: i32 = folder_id.parse()
+                .map_err(|_| ValidationError("invalid folder ID"))?;
+            // Verify ownership
+            get_bookmark_folder(db_client, folder_id, current_user.id).await?;
+            Some(folder_id)
+        },
+        None => None,
+    };
+    match create_bookmark(
+        db_client,
+        current_user.id,
+        post.id,
+        maybe_folder_id,
+    ).await {
+        Ok(_) => (),
+        Err(DatabaseError::AlreadyExists(_)) => {
+            if maybe_folder_id.is_some() {
+                // Move to another folder
+                set_bookmark_folder(
+                    db_client,
+                    current_user.id,
+                    post.id,
+                    maybe_folder_id,
+                ).await?;
+            };
+        },
         Err(other_error) => return Err(other_error.into()),
     };
     let base_url = get_request_base_url(connection_info);
@@ -1066,6 +1345,7 @@ async fn bookmark_view(
         &media_server,
         Some(&current_user),
         post,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -1077,10 +1357,11 @@ async fn unbookmark_view(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     status_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let post = get_post_by_id_for_view(
         db_client,
         Some(&current_user.profile),
@@ -1099,6 +1380,7 @@ async fn unbookmark_view(
         &media_server,
         Some(&current_user),
         post,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -1110,10 +1392,11 @@ async fn pin(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     status_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let mut post = get_post_by_id(db_client, *status_id).await?;
     if post.author.id != current_user.id || !post.is_public() {
         return Err(MastodonError::OperationError("can't pin post"));
@@ -1137,6 +1420,7 @@ async fn pin(
         &media_server,
         Some(&current_user),
         post,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -1148,10 +1432,11 @@ async fn unpin(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     status_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let mut post = get_post_by_id(db_client, *status_id).await?;
     if post.author.id != current_user.id || !post.is_public() {
         return Err(MastodonError::OperationError("can't unpin post"));
@@ -1175,6 +1460,7 @@ async fn unpin(
         &media_server,
         Some(&current_user),
         post,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -1185,11 +1471,12 @@ async fn conversation_tracking_view(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     status_id: web::Path<Uuid>,
     tracking_form: web::Json<ConversationTrackingForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let post = get_post_by_id_for_view(
         db_client,
         Some(&current_user.profile),
@@ -1211,6 +1498,87 @@ async fn conversation_tracking_view(
         &media_server,
         Some(&current_user),
         post,
+        &get_language_preferences(&request),
+    ).await?;
+    Ok(HttpResponse::Ok().json(status))
+}
+
+/// https://docs.joinmastodon.org/methods/statuses/#mute
+#[post("/{status_id}/mute")]
+async fn mute_conversation_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
+    status_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let post = get_post_by_id_for_view(
+        db_client,
+        Some(&current_user.profile),
+        *status_id,
+    ).await?;
+    if post.repost_of_id.is_some() {
+        return Err(MastodonError::NotFound("post"));
+    };
+    set_conversation_tracking_status(
+        db_client,
+        post.expect_conversation().id,
+        current_user.id,
+        Some(TrackingStatus::Mute),
+    ).await?;
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let status = build_status(
+        db_client,
+        &authority,
+        &media_server,
+        Some(&current_user),
+        post,
+        &get_language_preferences(&request),
+    ).await?;
+    Ok(HttpResponse::Ok().json(status))
+}
+
+/// https://docs.joinmastodon.org/methods/statuses/#unmute
+#[post("/{status_id}/unmute")]
+async fn unmute_conversation_view(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
+    status_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let post = get_post_by_id_for_view(
+        db_client,
+        Some(&current_user.profile),
+        *status_id,
+    ).await?;
+    if post.repost_of_id.is_some() {
+        return Err(MastodonError::NotFound("post"));
+    };
+    // Other tracking statuses are not changed
+    unmute_conversation(
+        db_client,
+        post.expect_conversation().id,
+        current_user.id,
+    ).await?;
+    let base_url = get_request_base_url(connection_info);
+    let authority = Authority::from(&config.instance());
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let status = build_status(
+        db_client,
+        &authority,
+        &media_server,
+        Some(&current_user),
+        post,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -1221,10 +1589,11 @@ async fn make_permanent(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     status_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let mut post = get_post_by_id(db_client, *status_id).await?;
     if post.ipfs_cid.is_some() {
         return Err(MastodonError::OperationError("post already saved to IPFS"));
@@ -1278,6 +1647,7 @@ async fn make_permanent(
         &media_server,
         Some(&current_user),
         post,
+        &get_language_preferences(&request),
     ).await?;
     Ok(HttpResponse::Ok().json(status))
 }
@@ -1290,7 +1660,7 @@ async fn load_conversation(
     request_data: web::Json<LoadConversationRequest>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     if !current_user.role.has_permission(Permission::DeleteAnyProfile) {
         return Err(MastodonError::PermissionError);
     };
@@ -1343,14 +1713,238 @@ async fn load_conversation(
     Ok(HttpResponse::NoContent().finish())
 }
 
-pub fn status_api_scope() -> Scope {
+async fn refetch_status(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    status_id: web::Path<Uuid>,
+    governor_result: GovernorExtractor,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    if !current_user.has_feature(FeatureFlag::HigherRateLimits) {
+        if let Some(wait) = governor_result.0.check()
+            .map_err(MastodonError::from_internal)?
+            .map(Duration::from_millis)
+        {
+            return Err(MastodonError::RateLimit(wait));
+        };
+    };
+    let post = get_post_by_id_for_view(
+        db_client,
+        Some(&current_user.profile),
+        *status_id,
+    ).await?;
+    let Some(object_id) = post.object_id.clone() else {
+        // Local post
+        return Err(MastodonError::NotFound("post"));
+    };
+    let mut jobs = vec![];
+    let job_id = FetcherJobData::Object { object_id: object_id.clone() }
+        .into_job(db_client).await?;
+    jobs.push(FetcherJob::queued(job_id, "object"));
+    let conversation = post.expect_conversation();
+    let root = get_post_by_id(db_client, conversation.root_id).await?;
+    if let Some(root_object_id) = root.object_id {
+        let job_id = FetcherJobData::Context {
+            object_id: root_object_id,
+            use_context: true,
+        }.into_job(db_client).await?;
+        jobs.push(FetcherJob::queued(job_id, "context"));
+    };
+    let job_id = FetcherJobData::Context {
+        object_id,
+        use_context: false,
+    }.into_job(db_client).await?;
+    jobs.push(FetcherJob::queued(job_id, "replies"));
+    Ok(HttpResponse::Accepted().json(jobs))
+}
+
+#[get("/{status_id}/refetch/{job_id}")]
+async fn get_refetch_status(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    get_current_user(db_client, &auth).await?;
+    let (_status_id, job_id) = path.into_inner();
+    let status = match get_job_by_id(db_client, job_id).await {
+        Ok(job) => match job.job_status {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+        },
+        // Completed jobs are removed from the queue
+        Err(DatabaseError::NotFound(_)) => "finished",
+        Err(other_error) => return Err(other_error.into()),
+    };
+    let job_status = FetcherJobStatus { id: job_id, status };
+    Ok(HttpResponse::Ok().json(job_status))
+}
+
+#[get("")]
+async fn get_scheduled_statuses(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let scheduled_posts =
+        get_scheduled_posts(db_client, current_user.id).await?;
+    let base_url = get_request_base_url(connection_info);
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let mut scheduled_statuses = vec![];
+    for scheduled_post in scheduled_posts {
+        let scheduled_status = build_scheduled_status(
+            db_client,
+            &media_server,
+            scheduled_post,
+        ).await?;
+        scheduled_statuses.push(scheduled_status);
+    };
+    Ok(HttpResponse::Ok().json(scheduled_statuses))
+}
+
+#[get("/{scheduled_status_id}")]
+async fn get_scheduled_status(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    scheduled_status_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let scheduled_post = get_scheduled_post(
+        db_client,
+        current_user.id,
+        *scheduled_status_id,
+    ).await?;
+    let base_url = get_request_base_url(connection_info);
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let scheduled_status = build_scheduled_status(
+        db_client,
+        &media_server,
+        scheduled_post,
+    ).await?;
+    Ok(HttpResponse::Ok().json(scheduled_status))
+}
+
+#[put("/{scheduled_status_id}")]
+async fn update_scheduled_status(
+    auth: BearerAuth,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    scheduled_status_id: web::Path<Uuid>,
+    update_form: JsonOrQsForm<ScheduledStatusUpdateForm>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let update_form = match update_form {
+        Either::Left(json) => json.into_inner(),
+        Either::Right(form) => form.into_inner(),
+    };
+    let scheduled_at = resolve_scheduled_time(
+        db_client,
+        &current_user,
+        update_form.scheduled_at,
+    ).await?;
+    let scheduled_post = set_scheduled_post_time(
+        db_client,
+        current_user.id,
+        *scheduled_status_id,
+        scheduled_at,
+    ).await?;
+    let base_url = get_request_base_url(connection_info);
+    let media_server = ClientMediaServer::new(&config, &base_url);
+    let scheduled_status = build_scheduled_status(
+        db_client,
+        &media_server,
+        scheduled_post,
+    ).await?;
+    Ok(HttpResponse::Ok().json(scheduled_status))
+}
+
+#[delete("/{scheduled_status_id}")]
+async fn delete_scheduled_status(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    scheduled_status_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    delete_scheduled_post(
+        db_client,
+        current_user.id,
+        *scheduled_status_id,
+    ).await?;
+    let empty = serde_json::json!({});
+    Ok(HttpResponse::Ok().json(empty))
+}
+
+pub fn scheduled_status_api_scope() -> Scope {
+    web::scope("/v1/scheduled_statuses")
+        .service(get_scheduled_statuses)
+        .service(get_scheduled_status)
+        .service(update_scheduled_status)
+        .service(delete_scheduled_status)
+}
+
+#[get("")]
+async fn get_scheduled_reblogs(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    let scheduled_reblogs: Vec<_> =
+        get_scheduled_reposts(db_client, current_user.id).await?
+            .into_iter()
+            .map(ScheduledReblog::from_db)
+            .collect();
+    Ok(HttpResponse::Ok().json(scheduled_reblogs))
+}
+
+#[delete("/{scheduled_reblog_id}")]
+async fn delete_scheduled_reblog(
+    auth: BearerAuth,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    scheduled_reblog_id: web::Path<Uuid>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
+    delete_scheduled_post(
+        db_client,
+        current_user.id,
+        *scheduled_reblog_id,
+    ).await?;
+    let empty = serde_json::json!({});
+    Ok(HttpResponse::Ok().json(empty))
+}
+
+pub fn scheduled_reblog_api_scope() -> Scope {
+    web::scope("/v1/scheduled_reblogs")
+        .service(get_scheduled_reblogs)
+        .service(delete_scheduled_reblog)
+}
+
+pub fn status_api_scope(
+    ratelimit_configs: RatelimitConfigs,
+) -> Scope {
+    let refetch_status_limited = web::resource("/{status_id}/refetch")
+        .post(refetch_status)
+        .wrap(Governor::new(&ratelimit_configs.fetch_collection));
     web::scope("/v1/statuses")
         // Routes without status ID
         .service(create_status)
         .service(preview_status)
+        .service(preview_status_activity)
         // Routes with status ID
         .service(get_status)
         .service(get_status_source)
+        .service(get_status_stats)
         .service(edit_status)
         .service(delete_status)
         .service(get_context)
@@ -1361,11 +1955,16 @@ pub fn status_api_scope() -> Scope {
         .service(reblog)
         .service(unreblog)
         .service(get_reblogged_by)
+        .service(get_quotes)
         .service(pin)
         .service(unpin)
         .service(bookmark_view)
         .service(unbookmark_view)
         .service(conversation_tracking_view)
+        .service(mute_conversation_view)
+        .service(unmute_conversation_view)
         .service(make_permanent)
         .service(load_conversation)
+        .service(refetch_status_limited)
+        .service(get_refetch_status)
 }
@@ -0,0 +1,122 @@
+use uuid::Uuid;
+
+use mitra_adapters::dynamic_config::get_dynamic_config;
+use mitra_models::{
+    accounts::types::{Permission, User},
+    custom_feeds::queries::get_readable_custom_feed,
+    database::{DatabaseClient, DatabaseError},
+    notifications::{
+        queries::get_notifications,
+        types::NotificationDetailed as DbNotificationDetailed,
+    },
+    posts::{
+        queries::{
+            get_custom_feed_timeline,
+            get_home_timeline,
+            get_posts_by_tag,
+            get_public_timeline,
+        },
+        types::PostDetailed as DbPostDetailed,
+    },
+};
+
+use crate::mastodon_api::errors::MastodonError;
+
+use super::types::Stream;
+
+/// Checks whether user is allowed to subscribe to the stream
+pub async fn check_stream_access(
+    db_client: &impl DatabaseClient,
+    user: &User,
+    stream: &Stream,
+) -> Result<(), MastodonError> {
+    match stream {
+        Stream::Public | Stream::PublicRemote => {
+            let dynamic_config = get_dynamic_config(db_client).await?;
+            if dynamic_config.federated_timeline_restricted &&
+                !user.role.has_permission(Permission::DeleteAnyPost)
+            {
+                return Err(MastodonError::PermissionError);
+            };
+        },
+        Stream::List(list_id) => {
+            get_readable_custom_feed(db_client, *list_id, user.id).await?;
+        },
+        _ => (),
+    };
+    Ok(())
+}
+
+/// Returns the post if it appears in the stream.
+/// Timeline queries are re-used, so that mutes and visibility
+/// are handled in the same way as in timeline API.
+pub async fn get_stream_post(
+    db_client: &impl DatabaseClient,
+    user: &User,
+    stream: &Stream,
+    post_id: Uuid,
+) -> Result<Option<DbPostDetailed>, DatabaseError> {
+    // Timeline queries return posts with IDs less than max_post_id
+    let max_post_id = Some(Uuid::from_u128(post_id.as_u128().saturating_add(1)));
+    let posts = match stream {
+        Stream::User => {
+            get_home_timeline(db_client, user.id, max_post_id, 1).await?
+        },
+        Stream::UserNotification => return Ok(None),
+        Stream::Public | Stream::PublicLocal | Stream::PublicRemote => {
+            get_public_timeline(
+                db_client,
+                Some(user.id),
+                *stream == Stream::PublicLocal,
+                None,
+                max_post_id,
+                1,
+            ).await?
+        },
+        Stream::Hashtag(tag) | Stream::HashtagLocal(tag) => {
+            get_posts_by_tag(
+                db_client,
+                tag,
+                Some(user.id),
+                max_post_id,
+                1,
+            ).await?
+        },
+        Stream::List(list_id) => {
+            get_custom_feed_timeline(
+                db_client,
+                *list_id,
+                user.id,
+                max_post_id,
+                1,
+            ).await?
+        },
+    };
+    let maybe_post = posts.into_iter()
+        .next()
+        .filter(|post| post.id == post_id)
+        .filter(|post| match stream {
+            Stream::PublicRemote => !post.author.is_local(),
+            Stream::HashtagLocal(_) => post.author.is_local(),
+            _ => true,
+        });
+    Ok(maybe_post)
+}
+
+/// Returns the notification unless its sender is muted
+pub async fn get_stream_notification(
+    db_client: &impl DatabaseClient,
+    user: &User,
+    notification_id: i32,
+) -> Result<Option<DbNotificationDetailed>, DatabaseError> {
+    let notifications = get_notifications(
+        db_client,
+        user.id,
+        Some(notification_id - 1),
+        Some(notification_id + 1),
+        1,
+    ).await?;
+    let maybe_notification = notifications.into_iter()
+        .find(|notification| notification.id == notification_id);
+    Ok(maybe_notification)
+}
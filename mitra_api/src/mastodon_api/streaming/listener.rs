@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use tokio::sync::broadcast::Sender;
+
+use mitra_config::Config;
+use mitra_models::{
+    database::connect::create_listening_database_client,
+    events::{
+        queries::listen_events,
+        types::StreamEvent,
+    },
+};
+
+const RECONNECT_DELAY: u64 = 5; // seconds
+
+/// Receives events published by the web server and background workers
+/// (which may run in a different process) and passes them to
+/// streaming API sessions.
+pub fn start_event_listener(
+    config: &Config,
+    sender: Sender<StreamEvent>,
+) -> () {
+    let database_url = config.database_url.clone();
+    let ca_file_path = config.database_tls_ca_file.clone();
+    tokio::spawn(async move {
+        loop {
+            match create_listening_database_client(
+                &database_url,
+                ca_file_path.as_deref(),
+            ).await {
+                Ok((db_client, mut receiver)) => {
+                    if let Err(error) = listen_events(&db_client).await {
+                        log::error!("failed to subscribe to events: {error}");
+                    } else {
+                        log::info!("listening for events");
+                        while let Some(payload) = receiver.recv().await {
+                            match serde_json::from_str(&payload) {
+                                Ok(event) => {
+                                    // Error is returned if there are no sessions
+                                    sender.send(event).ok();
+                                },
+                                Err(error) => {
+                                    log::warn!("invalid event ({error}): {payload}");
+                                },
+                            };
+                        };
+                        log::warn!("event listener disconnected");
+                    };
+                },
+                Err(error) => {
+                    log::error!("event listener connection error: {error}");
+                },
+            };
+            tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY)).await;
+        };
+    });
+}
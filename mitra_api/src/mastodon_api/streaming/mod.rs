@@ -0,0 +1,4 @@
+mod helpers;
+pub mod listener;
+mod types;
+pub mod views;
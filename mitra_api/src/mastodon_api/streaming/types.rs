@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+
+use crate::mastodon_api::errors::MastodonError;
+
+pub const STREAM_USER: &str = "user";
+pub const STREAM_USER_NOTIFICATION: &str = "user:notification";
+pub const STREAM_PUBLIC: &str = "public";
+pub const STREAM_PUBLIC_LOCAL: &str = "public:local";
+pub const STREAM_PUBLIC_REMOTE: &str = "public:remote";
+pub const STREAM_HASHTAG: &str = "hashtag";
+pub const STREAM_HASHTAG_LOCAL: &str = "hashtag:local";
+pub const STREAM_LIST: &str = "list";
+
+pub const EVENT_UPDATE: &str = "update";
+pub const EVENT_DELETE: &str = "delete";
+pub const EVENT_NOTIFICATION: &str = "notification";
+
+/// https://docs.joinmastodon.org/methods/streaming/#streams
+#[derive(Clone, Debug, PartialEq)]
+pub enum Stream {
+    User,
+    UserNotification,
+    Public,
+    PublicLocal,
+    PublicRemote,
+    Hashtag(String),
+    HashtagLocal(String),
+    List(i32),
+}
+
+impl Stream {
+    pub fn parse(
+        name: &str,
+        maybe_tag: Option<&str>,
+        maybe_list: Option<&str>,
+    ) -> Result<Self, MastodonError> {
+        let get_tag = || {
+            maybe_tag
+                .filter(|tag| !tag.is_empty())
+                .map(|tag| tag.trim_start_matches('#').to_lowercase())
+                .ok_or(MastodonError::ValidationError("tag is required".to_owned()))
+        };
+        let stream = match name {
+            STREAM_USER => Self::User,
+            STREAM_USER_NOTIFICATION => Self::UserNotification,
+            STREAM_PUBLIC => Self::Public,
+            STREAM_PUBLIC_LOCAL => Self::PublicLocal,
+            STREAM_PUBLIC_REMOTE => Self::PublicRemote,
+            STREAM_HASHTAG => Self::Hashtag(get_tag()?),
+            STREAM_HASHTAG_LOCAL => Self::HashtagLocal(get_tag()?),
+            STREAM_LIST => {
+                let list_id = maybe_list
+                    .and_then(|list| list.parse().ok())
+                    .ok_or(MastodonError::ValidationError("invalid list ID".to_owned()))?;
+                Self::List(list_id)
+            },
+            _ => return Err(MastodonError::ValidationError("unknown stream".to_owned())),
+        };
+        Ok(stream)
+    }
+
+    /// Stream identifier used in messages
+    pub fn identifier(&self) -> Vec<String> {
+        match self {
+            Self::User => vec![STREAM_USER.to_owned()],
+            Self::UserNotification => vec![STREAM_USER_NOTIFICATION.to_owned()],
+            Self::Public => vec![STREAM_PUBLIC.to_owned()],
+            Self::PublicLocal => vec![STREAM_PUBLIC_LOCAL.to_owned()],
+            Self::PublicRemote => vec![STREAM_PUBLIC_REMOTE.to_owned()],
+            Self::Hashtag(tag) => vec![STREAM_HASHTAG.to_owned(), tag.clone()],
+            Self::HashtagLocal(tag) => {
+                vec![STREAM_HASHTAG_LOCAL.to_owned(), tag.clone()]
+            },
+            Self::List(list_id) => vec![STREAM_LIST.to_owned(), list_id.to_string()],
+        }
+    }
+
+    pub fn includes_notifications(&self) -> bool {
+        matches!(self, Self::User | Self::UserNotification)
+    }
+
+    /// Returns false if the new post certainly doesn't appear in the stream.
+    /// Posts that pass this check should be verified with a timeline query.
+    pub fn may_include_post(
+        &self,
+        is_public: bool,
+        is_local: bool,
+        tags: &[String],
+    ) -> bool {
+        let has_tag = |tag: &str| {
+            tags.iter().any(|item| item.to_lowercase() == tag)
+        };
+        match self {
+            Self::User | Self::List(_) => true,
+            Self::UserNotification => false,
+            Self::Public => is_public,
+            Self::PublicLocal => is_public && is_local,
+            Self::PublicRemote => is_public && !is_local,
+            Self::Hashtag(tag) => has_tag(tag),
+            Self::HashtagLocal(tag) => is_local && has_tag(tag),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct StreamingQueryParams {
+    pub stream: Option<String>,
+    pub tag: Option<String>,
+    pub list: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientMessageType {
+    Subscribe,
+    Unsubscribe,
+}
+
+/// Message sent by a client
+#[derive(Deserialize)]
+pub struct ClientMessage {
+    #[serde(rename = "type")]
+    pub message_type: ClientMessageType,
+    pub stream: String,
+    pub tag: Option<String>,
+    pub list: Option<String>,
+}
+
+/// https://docs.joinmastodon.org/methods/streaming/#events
+#[derive(Serialize)]
+pub struct StreamMessage {
+    stream: Vec<String>,
+    event: &'static str,
+    // Serialized JSON object or ID
+    payload: String,
+}
+
+impl StreamMessage {
+    pub fn new(stream: &Stream, event: &'static str, payload: String) -> Self {
+        Self {
+            stream: stream.identifier(),
+            event,
+            payload,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct StreamError {
+    error: String,
+}
+
+impl StreamError {
+    pub fn new(error: &MastodonError) -> Self {
+        Self { error: error.to_string() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stream() {
+        let stream = Stream::parse("user", None, None).unwrap();
+        assert_eq!(stream, Stream::User);
+        assert_eq!(stream.identifier(), vec!["user"]);
+
+        let stream = Stream::parse("hashtag", Some("#Test"), None).unwrap();
+        assert_eq!(stream, Stream::Hashtag("test".to_owned()));
+        assert_eq!(stream.identifier(), vec!["hashtag", "test"]);
+
+        let stream = Stream::parse("list", None, Some("12")).unwrap();
+        assert_eq!(stream, Stream::List(12));
+        assert_eq!(stream.identifier(), vec!["list", "12"]);
+    }
+
+    #[test]
+    fn test_parse_stream_invalid() {
+        assert!(Stream::parse("hashtag", None, None).is_err());
+        assert!(Stream::parse("list", None, Some("test")).is_err());
+        assert!(Stream::parse("direct", None, None).is_err());
+    }
+
+    #[test]
+    fn test_may_include_post() {
+        let tags = vec!["Test".to_owned()];
+        assert!(Stream::User.may_include_post(false, true, &[]));
+        assert!(!Stream::UserNotification.may_include_post(true, true, &[]));
+        assert!(Stream::Public.may_include_post(true, false, &[]));
+        assert!(!Stream::Public.may_include_post(false, false, &[]));
+        assert!(!Stream::PublicLocal.may_include_post(true, false, &[]));
+        assert!(Stream::PublicRemote.may_include_post(true, false, &[]));
+        let stream = Stream::Hashtag("test".to_owned());
+        assert!(stream.may_include_post(true, false, &tags));
+        assert!(!stream.may_include_post(true, false, &[]));
+        let stream = Stream::HashtagLocal("test".to_owned());
+        assert!(!stream.may_include_post(true, false, &tags));
+    }
+
+    #[test]
+    fn test_parse_client_message() {
+        let message = r#"{"type":"subscribe","stream":"hashtag","tag":"test"}"#;
+        let message: ClientMessage = serde_json::from_str(message).unwrap();
+        assert!(matches!(message.message_type, ClientMessageType::Subscribe));
+        assert_eq!(message.stream, "hashtag");
+        assert_eq!(message.tag.as_deref(), Some("test"));
+    }
+}
@@ -0,0 +1,415 @@
+/// https://docs.joinmastodon.org/methods/streaming/
+use std::time::Duration;
+
+use actix_web::{
+    dev::ConnectionInfo,
+    get,
+    http::header as http_header,
+    rt::time::{interval, interval_at, Instant},
+    web,
+    HttpRequest,
+    HttpResponse,
+    Scope,
+};
+use actix_ws::{CloseCode, Message, MessageStream, Session};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+use mitra_activitypub::authority::Authority;
+use mitra_config::Config;
+use mitra_models::{
+    accounts::types::User,
+    database::{get_database_client, DatabaseConnectionPool},
+    events::types::StreamEvent,
+    posts::types::Visibility,
+    relationships::{
+        queries::has_relationship,
+        types::RelationshipType,
+    },
+};
+use mitra_utils::languages::Language;
+
+use crate::{
+    http::{get_language_preferences, get_request_base_url},
+    mastodon_api::{
+        auth::{
+            get_streaming_access_token,
+            get_user_by_token,
+            get_validated_session,
+        },
+        errors::MastodonError,
+        media_server::ClientMediaServer,
+        notifications::types::Notification,
+        statuses::helpers::build_status,
+    },
+    state::AppState,
+};
+
+use super::helpers::{
+    check_stream_access,
+    get_stream_notification,
+    get_stream_post,
+};
+use super::types::{
+    ClientMessage,
+    ClientMessageType,
+    Stream,
+    StreamError,
+    StreamMessage,
+    StreamingQueryParams,
+    EVENT_DELETE,
+    EVENT_NOTIFICATION,
+    EVENT_UPDATE,
+};
+
+const HEARTBEAT_INTERVAL: u64 = 30; // seconds
+const REVALIDATION_INTERVAL: u64 = 60; // seconds
+
+struct StreamingSession {
+    db_pool: web::Data<DatabaseConnectionPool>,
+    authority: Authority,
+    media_server: ClientMediaServer,
+    language_preferences: Vec<Language>,
+    access_token: String,
+    user: User,
+    streams: Vec<Stream>,
+}
+
+impl StreamingSession {
+    /// Reloads user and checks access to subscribed streams.
+    /// Returns false if access token is no longer valid.
+    async fn revalidate(&mut self) -> Result<bool, MastodonError> {
+        let db_client = &**get_database_client(&self.db_pool).await?;
+        self.user = match get_user_by_token(db_client, &self.access_token).await {
+            Ok(user) => user,
+            Err(MastodonError::AuthError(_)) => return Ok(false),
+            Err(other_error) => return Err(other_error),
+        };
+        let mut revoked = vec![];
+        for stream in self.streams.iter() {
+            match check_stream_access(db_client, &self.user, stream).await {
+                Ok(_) => (),
+                Err(MastodonError::PermissionError | MastodonError::NotFound(_)) => {
+                    log::info!("access to stream {stream:?} revoked");
+                    revoked.push(stream.clone());
+                },
+                Err(other_error) => return Err(other_error),
+            };
+        };
+        self.streams.retain(|stream| !revoked.contains(stream));
+        Ok(true)
+    }
+
+    async fn update_subscriptions(
+        &mut self,
+        message: ClientMessage,
+    ) -> Result<(), MastodonError> {
+        let stream = Stream::parse(
+            &message.stream,
+            message.tag.as_deref(),
+            message.list.as_deref(),
+        )?;
+        match message.message_type {
+            ClientMessageType::Subscribe => {
+                if self.streams.contains(&stream) {
+                    return Ok(());
+                };
+                let db_client = &**get_database_client(&self.db_pool).await?;
+                check_stream_access(db_client, &self.user, &stream).await?;
+                self.streams.push(stream);
+            },
+            ClientMessageType::Unsubscribe => {
+                self.streams.retain(|item| *item != stream);
+            },
+        };
+        Ok(())
+    }
+
+    async fn handle_client_message(&mut self, text: &str) -> Option<String> {
+        let result = match serde_json::from_str(text) {
+            Ok(message) => self.update_subscriptions(message).await,
+            Err(_) => Err(MastodonError::ValidationError("invalid message".to_owned())),
+        };
+        result.err().map(|error| {
+            serde_json::to_string(&StreamError::new(&error))
+                .expect("error should be serializable")
+        })
+    }
+
+    async fn build_messages(
+        &self,
+        event: StreamEvent,
+    ) -> Result<Vec<StreamMessage>, MastodonError> {
+        let mut messages = vec![];
+        if self.streams.is_empty() {
+            return Ok(messages);
+        };
+        match event {
+            StreamEvent::PostCreated { post_id, is_public, is_local, tags } => {
+                let streams: Vec<_> = self.streams.iter()
+                    .filter(|stream| {
+                        stream.may_include_post(is_public, is_local, &tags)
+                    })
+                    .collect();
+                if streams.is_empty() {
+                    return Ok(messages);
+                };
+                let db_client = &**get_database_client(&self.db_pool).await?;
+                // Status is the same for every stream
+                let mut maybe_payload: Option<String> = None;
+                for stream in streams {
+                    let Some(post) = get_stream_post(
+                        db_client,
+                        &self.user,
+                        stream,
+                        post_id,
+                    ).await? else {
+                        continue;
+                    };
+                    let payload = match maybe_payload {
+                        Some(ref payload) => payload.clone(),
+                        None => {
+                            let status = build_status(
+                                db_client,
+                                &self.authority,
+                                &self.media_server,
+                                Some(&self.user),
+                                post,
+                                &self.language_preferences,
+                            ).await?;
+                            let payload = serde_json::to_string(&status)
+                                .map_err(MastodonError::from_internal)?;
+                            maybe_payload = Some(payload.clone());
+                            payload
+                        },
+                    };
+                    messages.push(StreamMessage::new(
+                        stream,
+                        EVENT_UPDATE,
+                        payload,
+                    ));
+                };
+            },
+            StreamEvent::PostDeleted { post_id, author_id, visibility } => {
+                let payload = post_id.to_string();
+                if visibility == Visibility::Public {
+                    for stream in self.streams.iter() {
+                        if *stream == Stream::UserNotification {
+                            continue;
+                        };
+                        messages.push(StreamMessage::new(
+                            stream,
+                            EVENT_DELETE,
+                            payload.clone(),
+                        ));
+                    };
+                    return Ok(messages);
+                };
+                // Non-public posts can only appear in home timeline
+                if !self.streams.contains(&Stream::User) {
+                    return Ok(messages);
+                };
+                let is_visible = if author_id == self.user.id {
+                    true
+                } else if visibility == Visibility::Followers {
+                    let db_client = &**get_database_client(&self.db_pool).await?;
+                    has_relationship(
+                        db_client,
+                        self.user.id,
+                        author_id,
+                        RelationshipType::Follow,
+                    ).await?
+                } else {
+                    // Recipients of deleted post are not known
+                    false
+                };
+                if is_visible {
+                    messages.push(StreamMessage::new(
+                        &Stream::User,
+                        EVENT_DELETE,
+                        payload,
+                    ));
+                };
+            },
+            StreamEvent::NotificationCreated { notification_id, recipient_id } => {
+                if recipient_id != self.user.id {
+                    return Ok(messages);
+                };
+                let db_client = &**get_database_client(&self.db_pool).await?;
+                let Some(notification) = get_stream_notification(
+                    db_client,
+                    &self.user,
+                    notification_id,
+                ).await? else {
+                    return Ok(messages);
+                };
+                let notification = Notification::from_db(
+                    &self.authority,
+                    &self.media_server,
+                    notification,
+                );
+                let payload = serde_json::to_string(&notification)
+                    .map_err(MastodonError::from_internal)?;
+                for stream in self.streams.iter() {
+                    if !stream.includes_notifications() {
+                        continue;
+                    };
+                    messages.push(StreamMessage::new(
+                        stream,
+                        EVENT_NOTIFICATION,
+                        payload.clone(),
+                    ));
+                };
+            },
+        };
+        Ok(messages)
+    }
+
+    async fn run(
+        mut self,
+        mut session: Session,
+        mut message_stream: MessageStream,
+        mut event_receiver: Receiver<StreamEvent>,
+    ) -> () {
+        let mut heartbeat = interval(Duration::from_secs(HEARTBEAT_INTERVAL));
+        let revalidation_period = Duration::from_secs(REVALIDATION_INTERVAL);
+        let mut revalidation = interval_at(
+            Instant::now() + revalidation_period,
+            revalidation_period,
+        );
+        loop {
+            tokio::select! {
+                maybe_message = message_stream.recv() => {
+                    match maybe_message {
+                        Some(Ok(Message::Text(text))) => {
+                            if let Some(error) = self.handle_client_message(&text).await {
+                                if session.text(error).await.is_err() {
+                                    return;
+                                };
+                            };
+                        },
+                        Some(Ok(Message::Ping(data))) => {
+                            if session.pong(&data).await.is_err() {
+                                return;
+                            };
+                        },
+                        Some(Ok(Message::Close(reason))) => {
+                            session.close(reason).await.ok();
+                            return;
+                        },
+                        Some(Ok(_)) => continue,
+                        Some(Err(_)) | None => break,
+                    };
+                },
+                result = event_receiver.recv() => {
+                    let event = match result {
+                        Ok(event) => event,
+                        Err(RecvError::Lagged(count)) => {
+                            log::warn!("streaming session skipped {count} events");
+                            continue;
+                        },
+                        Err(RecvError::Closed) => break,
+                    };
+                    let messages = match self.build_messages(event).await {
+                        Ok(messages) => messages,
+                        Err(error) => {
+                            log::error!("streaming error: {error}");
+                            continue;
+                        },
+                    };
+                    for message in messages {
+                        let message = serde_json::to_string(&message)
+                            .expect("message should be serializable");
+                        if session.text(message).await.is_err() {
+                            return;
+                        };
+                    };
+                },
+                _ = heartbeat.tick() => {
+                    if session.ping(b"").await.is_err() {
+                        return;
+                    };
+                },
+                _ = revalidation.tick() => {
+                    match self.revalidate().await {
+                        Ok(true) => (),
+                        Ok(false) => {
+                            // Token is revoked or user is deleted
+                            session.close(Some(CloseCode::Policy.into())).await.ok();
+                            return;
+                        },
+                        Err(error) => {
+                            log::error!("streaming error: {error}");
+                        },
+                    };
+                },
+            };
+        };
+        session.close(None).await.ok();
+    }
+}
+
+#[get("")]
+async fn streaming_view(
+    app_state: web::Data<AppState>,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
+    body: web::Payload,
+    query_params: web::Query<StreamingQueryParams>,
+) -> Result<HttpResponse, MastodonError> {
+    let access_token = get_streaming_access_token(&request)
+        .ok_or(MastodonError::AuthError("access token is required"))?;
+    let db_client = &**get_database_client(&db_pool).await?;
+    let current_user = match get_validated_session(&request, &access_token) {
+        Some((_, user)) => user,
+        None => get_user_by_token(db_client, &access_token).await?,
+    };
+    let mut streams = vec![];
+    if let Some(ref stream_name) = query_params.stream {
+        let stream = Stream::parse(
+            stream_name,
+            query_params.tag.as_deref(),
+            query_params.list.as_deref(),
+        )?;
+        check_stream_access(db_client, &current_user, &stream).await?;
+        streams.push(stream);
+    };
+    let (mut response, session, message_stream) =
+        actix_ws::handle(&request, body)
+            .map_err(|_| MastodonError::ValidationError("invalid handshake".to_owned()))?;
+    if let Some(protocol) = request.headers()
+        .get(http_header::SEC_WEBSOCKET_PROTOCOL)
+    {
+        response.headers_mut()
+            .insert(http_header::SEC_WEBSOCKET_PROTOCOL, protocol.clone());
+    };
+    let base_url = get_request_base_url(connection_info);
+    let streaming_session = StreamingSession {
+        db_pool: db_pool.clone(),
+        authority: Authority::from(&config.instance()),
+        media_server: ClientMediaServer::new(&config, &base_url),
+        language_preferences: get_language_preferences(&request),
+        access_token: access_token,
+        user: current_user,
+        streams,
+    };
+    let event_receiver = app_state.stream_events.subscribe();
+    actix_web::rt::spawn(streaming_session.run(
+        session,
+        message_stream,
+        event_receiver,
+    ));
+    Ok(response)
+}
+
+#[get("/health")]
+async fn streaming_health_view() -> HttpResponse {
+    HttpResponse::Ok().body("OK")
+}
+
+pub fn streaming_api_scope() -> Scope {
+    web::scope("/v1/streaming")
+        .service(streaming_health_view)
+        .service(streaming_view)
+}
@@ -9,6 +9,7 @@ use mitra_adapters::payments::{
 };
 use mitra_models::{
     invoices::types::{Invoice as DbInvoice, InvoiceStatus},
+    payment_methods::types::PayoutSplit,
     subscriptions::types::{Subscription as DbSubscription},
 };
 
@@ -90,6 +91,8 @@ pub enum SubscriptionOption {
         chain_id: ChainId,
         price: u64,
         payout_address: String,
+        #[serde(default)]
+        payout_splits: Vec<PayoutSplit>,
     },
     MoneroLight {
         chain_id: ChainId,
@@ -9,14 +9,15 @@ use actix_web::{
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 use uuid::Uuid;
 
 use mitra_activitypub::{
-    adapters::users::create_or_update_local_actor,
+    adapters::{
+        subscriptions::grant_subscription,
+        users::create_or_update_local_actor,
+    },
     authority::Authority,
     builders::{
-        add_person::prepare_add_subscriber,
         offer_agreement::prepare_offer_agreement,
         update_person::prepare_update_person,
     },
@@ -26,12 +27,10 @@ use mitra_adapters::payments::{
         create_payment_address,
         validate_monero_address,
         validate_monero_standard_address,
+        validate_payout_splits,
         PaymentError,
     },
-    subscriptions::{
-        create_or_update_local_subscription,
-        validate_subscription_price,
-    },
+    subscriptions::validate_subscription_price,
 };
 use mitra_config::Config;
 use mitra_models::{
@@ -52,7 +51,7 @@ use mitra_models::{
             create_payment_method,
             get_payment_method_by_chain_id,
         },
-        types::{PaymentMethodData, PaymentType},
+        types::{PaymentMethodData, PaymentType, PayoutSplits},
     },
     profiles::queries::{
         get_profile_by_id,
@@ -86,7 +85,7 @@ use mitra_validators::{
 use crate::http::get_request_base_url;
 use crate::mastodon_api::{
     accounts::types::Account,
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
     media_server::ClientMediaServer,
 };
@@ -108,7 +107,7 @@ async fn create_subscription_view(
     subscription_form: web::Json<SubscriptionCreateForm>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let subscriber = get_profile_by_id(
         db_client,
         subscription_form.subscriber_id,
@@ -128,21 +127,16 @@ async fn create_subscription_view(
     if !is_follower && !is_subscriber {
         return Err(ValidationError("account should be either follower or subscriber").into());
     };
-    let subscription = create_or_update_local_subscription(
+    if subscription_form.duration <= 0 {
+        return Err(ValidationError("duration must be positive").into());
+    };
+    let subscription = grant_subscription(
+        &config.instance(),
         db_client,
-        &subscriber, // sender
-        &current_user, // recipient
+        &subscriber,
+        &current_user,
         subscription_form.duration.into(),
     ).await?;
-    if let Some(ref remote_subscriber) = subscriber.actor_json {
-        prepare_add_subscriber(
-            &config.instance(),
-            remote_subscriber,
-            &current_user,
-            subscription.expires_at,
-            None, // no invoice
-        ).save_and_enqueue(db_client).await?;
-    };
     let details = SubscriptionDetails::from(subscription);
     Ok(HttpResponse::Ok().json(details))
 }
@@ -153,7 +147,7 @@ async fn get_subscription_options(
     db_pool: web::Data<DatabaseConnectionPool>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let mut options: Vec<SubscriptionOption> = vec![];
     for payment_option in current_user.profile.payment_options.inner() {
         let subscription_info = match payment_option {
@@ -176,6 +170,7 @@ async fn get_subscription_options(
                     chain_id: subscription_info.chain_id.clone(),
                     price: subscription_info.price.into(),
                     payout_address: payment_method.payout_address,
+                    payout_splits: payment_method.payout_splits.into_inner(),
                 }
             },
             PaymentType::MoneroLight => {
@@ -202,13 +197,18 @@ async fn register_subscription_option(
     subscription_option: web::Json<SubscriptionOption>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &mut **get_database_client(&db_pool).await?;
-    let mut current_user = get_current_user(db_client, auth.token()).await?;
+    let mut current_user = get_current_user(db_client, &auth).await?;
     if !current_user.role.has_permission(Permission::ManageSubscriptionOptions) {
         return Err(MastodonError::PermissionError);
     };
 
     let (payment_method_data, payment_option) = match subscription_option.into_inner() {
-        SubscriptionOption::Monero { chain_id, price, payout_address } => {
+        SubscriptionOption::Monero {
+            chain_id,
+            price,
+            payout_address,
+            payout_splits,
+        } => {
             let monero_config = config.monero_config()
                 .ok_or(MastodonError::NotSupported)?;
             if chain_id != monero_config.chain_id {
@@ -218,12 +218,14 @@ async fn register_subscription_option(
                 .map_err(|_| ValidationError("price must be greater than 0"))?;
             validate_subscription_price(price)?;
             validate_monero_address(&payout_address, &chain_id)?;
+            validate_payout_splits(&payout_splits, &payout_address, &chain_id)?;
             let payment_method_data = PaymentMethodData {
                 owner_id: current_user.id,
                 payment_type: PaymentType::Monero,
                 chain_id: chain_id.clone(),
                 payout_address: payout_address,
                 view_key: None,
+                payout_splits: PayoutSplits(payout_splits),
             };
             let payment_option = PaymentOption::monero_subscription(
                 chain_id,
@@ -251,6 +253,7 @@ async fn register_subscription_option(
                 chain_id: chain_id.clone(),
                 payout_address: payout_address.to_string(),
                 view_key: Some(view_key.to_string()),
+                payout_splits: PayoutSplits::default(),
             };
             let payment_option = PaymentOption::monero_subscription(
                 chain_id,
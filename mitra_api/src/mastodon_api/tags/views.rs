@@ -0,0 +1,46 @@
+use actix_web::{
+    get,
+    web,
+    HttpResponse,
+    Scope,
+};
+
+use mitra_config::Config;
+use mitra_models::{
+    database::{get_database_client, DatabaseConnectionPool},
+    tags::queries::get_tag_usage_history,
+};
+
+use crate::mastodon_api::{
+    errors::MastodonError,
+    statuses::types::Tag,
+};
+
+const TAG_HISTORY_DAYS: u16 = 7;
+
+// https://docs.joinmastodon.org/methods/tags/#get
+#[get("/{tag_name}")]
+async fn get_tag_view(
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    tag_name: web::Path<String>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let tag_name = tag_name.into_inner().to_lowercase();
+    let history = get_tag_usage_history(
+        db_client,
+        &tag_name,
+        TAG_HISTORY_DAYS,
+    ).await?;
+    let tag = Tag::with_history(
+        config.instance().uri_str(),
+        tag_name,
+        history,
+    );
+    Ok(HttpResponse::Ok().json(tag))
+}
+
+pub fn tag_api_scope() -> Scope {
+    web::scope("/v1/tags")
+        .service(get_tag_view)
+}
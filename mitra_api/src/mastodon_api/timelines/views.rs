@@ -4,10 +4,10 @@ use actix_web::{
     get,
     http::Uri,
     web,
+    HttpRequest,
     HttpResponse,
     Scope,
 };
-use actix_web_httpauth::extractors::bearer::BearerAuth;
 use uuid::Uuid;
 
 use mitra_activitypub::authority::Authority;
@@ -15,7 +15,7 @@ use mitra_adapters::dynamic_config::get_dynamic_config;
 use mitra_config::Config;
 use mitra_models::{
     accounts::types::Permission,
-    custom_feeds::queries::get_custom_feed,
+    custom_feeds::queries::get_readable_custom_feed,
     database::{get_database_client, DatabaseConnectionPool},
     groups::queries::get_group_timeline,
     posts::queries::{
@@ -27,9 +27,9 @@ use mitra_models::{
     },
 };
 
-use crate::http::get_request_base_url;
+use crate::http::{get_language_preferences, get_request_base_url};
 use crate::mastodon_api::{
-    auth::get_current_user,
+    auth::{get_current_user, BearerAuth},
     errors::MastodonError,
     media_server::ClientMediaServer,
     statuses::helpers::get_paginated_status_list,
@@ -45,11 +45,12 @@ async fn home_timeline(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     request_uri: Uri,
     query_params: web::Query<TimelineQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let posts = get_home_timeline(
         db_client,
         current_user.id,
@@ -68,6 +69,7 @@ async fn home_timeline(
         Some(&current_user),
         posts,
         &query_params.limit,
+        &get_language_preferences(&request),
     ).await?;
     Ok(response)
 }
@@ -79,13 +81,14 @@ async fn public_timeline(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     request_uri: Uri,
     query_params: web::Query<PublicTimelineQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let maybe_current_user = match auth {
         Some(auth) => {
-            let current_user = get_current_user(db_client, auth.token()).await?;
+            let current_user = get_current_user(db_client, &auth).await?;
             let dynamic_config = get_dynamic_config(db_client).await?;
             if dynamic_config.federated_timeline_restricted &&
                 !query_params.local &&
@@ -124,6 +127,7 @@ async fn public_timeline(
         maybe_current_user.as_ref(),
         posts,
         &query_params.limit,
+        &get_language_preferences(&request),
     ).await?;
     Ok(response)
 }
@@ -134,11 +138,12 @@ async fn direct_timeline(
     config: web::Data<Config>,
     connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     request_uri: Uri,
     query_params: web::Query<TimelineQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let posts = get_direct_timeline(
         db_client,
         current_user.id,
@@ -157,6 +162,7 @@ async fn direct_timeline(
         Some(&current_user),
         posts,
         &query_params.limit,
+        &get_language_preferences(&request),
     ).await?;
     Ok(response)
 }
@@ -165,15 +171,15 @@ async fn direct_timeline(
 async fn hashtag_timeline(
     auth: Option<BearerAuth>,
     config: web::Data<Config>,
-    connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     request_uri: Uri,
     hashtag: web::Path<String>,
     query_params: web::Query<TimelineQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let maybe_current_user = match auth {
-        Some(auth) => Some(get_current_user(db_client, auth.token()).await?),
+        Some(auth) => Some(get_current_user(db_client, &auth).await?),
         None => None,
     };
     let posts = get_posts_by_tag(
@@ -183,7 +189,7 @@ async fn hashtag_timeline(
         query_params.max_id,
         query_params.limit.inner(),
     ).await?;
-    let base_url = get_request_base_url(connection_info);
+    let base_url = get_request_base_url(request.connection_info().clone());
     let media_server = ClientMediaServer::new(&config, &base_url);
     let authority = Authority::from(&config.instance());
     let response = get_paginated_status_list(
@@ -195,6 +201,7 @@ async fn hashtag_timeline(
         maybe_current_user.as_ref(),
         posts,
         &query_params.limit,
+        &get_language_preferences(&request),
     ).await?;
     Ok(response)
 }
@@ -204,15 +211,15 @@ async fn hashtag_timeline(
 async fn list_timeline(
     auth: BearerAuth,
     config: web::Data<Config>,
-    connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     request_uri: Uri,
     list_id: web::Path<i32>,
     query_params: web::Query<TimelineQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
-    let feed = get_custom_feed(
+    let current_user = get_current_user(db_client, &auth).await?;
+    let feed = get_readable_custom_feed(
         db_client,
         *list_id,
         current_user.id,
@@ -224,7 +231,7 @@ async fn list_timeline(
         query_params.max_id,
         query_params.limit.inner(),
     ).await?;
-    let base_url = get_request_base_url(connection_info);
+    let base_url = get_request_base_url(request.connection_info().clone());
     let media_server = ClientMediaServer::new(&config, &base_url);
     let authority = Authority::from(&config.instance());
     let response = get_paginated_status_list(
@@ -236,6 +243,7 @@ async fn list_timeline(
         Some(&current_user),
         posts,
         &query_params.limit,
+        &get_language_preferences(&request),
     ).await?;
     Ok(response)
 }
@@ -244,14 +252,14 @@ async fn list_timeline(
 async fn group_timeline(
     auth: BearerAuth,
     config: web::Data<Config>,
-    connection_info: ConnectionInfo,
     db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
     request_uri: Uri,
     group_id: web::Path<Uuid>,
     query_params: web::Query<TimelineQueryParams>,
 ) -> Result<HttpResponse, MastodonError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let current_user = get_current_user(db_client, auth.token()).await?;
+    let current_user = get_current_user(db_client, &auth).await?;
     let posts = get_group_timeline(
         db_client,
         group_id.into_inner(),
@@ -259,7 +267,7 @@ async fn group_timeline(
         query_params.max_id,
         query_params.limit.inner(),
     ).await?;
-    let base_url = get_request_base_url(connection_info);
+    let base_url = get_request_base_url(request.connection_info().clone());
     let media_server = ClientMediaServer::new(&config, &base_url);
     let authority = Authority::from(&config.instance());
     let response = get_paginated_status_list(
@@ -271,6 +279,7 @@ async fn group_timeline(
         Some(&current_user),
         posts,
         &query_params.limit,
+        &get_language_preferences(&request),
     ).await?;
     Ok(response)
 }
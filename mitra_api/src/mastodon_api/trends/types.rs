@@ -0,0 +1,15 @@
+use serde::Deserialize;
+
+use crate::mastodon_api::pagination::PageSize;
+
+fn default_page_size() -> PageSize { PageSize::new(10) }
+
+/// https://docs.joinmastodon.org/methods/trends/#tags
+#[derive(Deserialize)]
+pub struct TrendsQueryParams {
+    #[serde(default)]
+    pub offset: u16,
+
+    #[serde(default = "default_page_size")]
+    pub limit: PageSize,
+}
@@ -0,0 +1,64 @@
+use std::cmp::min;
+
+use actix_web::{
+    get,
+    web,
+    HttpResponse,
+    Scope,
+};
+use chrono::{TimeDelta, Utc};
+
+use mitra_config::Config;
+use mitra_models::{
+    database::{get_database_client, DatabaseConnectionPool},
+    tags::queries::{get_tag_usage_history, get_trending_tags},
+};
+
+use crate::mastodon_api::{
+    errors::MastodonError,
+    statuses::types::Tag,
+};
+
+use super::types::TrendsQueryParams;
+
+pub const TRENDS_WINDOW_HOURS: i64 = 24;
+pub const TRENDS_LIMIT_MAX: u16 = 20;
+pub const TAG_HISTORY_DAYS: u16 = 7;
+
+// https://docs.joinmastodon.org/methods/trends/#tags
+#[get("/tags")]
+async fn trending_tags_view(
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    query_params: web::Query<TrendsQueryParams>,
+) -> Result<HttpResponse, MastodonError> {
+    let db_client = &**get_database_client(&db_pool).await?;
+    let created_after = Utc::now() - TimeDelta::hours(TRENDS_WINDOW_HOURS);
+    let trending_tags = get_trending_tags(
+        db_client,
+        created_after,
+        false, // without hidden
+        min(query_params.limit.inner(), TRENDS_LIMIT_MAX),
+        query_params.offset,
+    ).await?;
+    let mut tags = vec![];
+    for (tag_name, _) in trending_tags {
+        let history = get_tag_usage_history(
+            db_client,
+            &tag_name,
+            TAG_HISTORY_DAYS,
+        ).await?;
+        let tag = Tag::with_history(
+            config.instance().uri_str(),
+            tag_name,
+            history,
+        );
+        tags.push(tag);
+    };
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+pub fn trends_api_scope() -> Scope {
+    web::scope("/v1/trends")
+        .service(trending_tags_view)
+}
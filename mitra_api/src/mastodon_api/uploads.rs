@@ -1,5 +1,6 @@
 use apx_core::base64;
 
+use mitra_adapters::media::StorageQuota;
 use mitra_services::media::{
     MediaStorage,
     MediaStorageError,
@@ -19,6 +20,9 @@ pub enum UploadError {
     #[error("file size must be less than {limit}")]
     TooLarge { limit: FileSize },
 
+    #[error("storage quota exceeded (limit: {limit})")]
+    StorageQuotaExceeded { limit: FileSize },
+
     #[error("no media type")]
     NoMediaType,
 
@@ -32,6 +36,13 @@ impl From<UploadError> for MastodonError {
             UploadError::WriteError(error) => {
                 MastodonError::from_internal(error)
             },
+            quota_error @ UploadError::StorageQuotaExceeded { .. } => {
+                MastodonError::FieldValidationError {
+                    field: "file",
+                    code: "storage_quota_exceeded",
+                    message: quota_error.to_string(),
+                }
+            },
             other_error => {
                 MastodonError::ValidationError(other_error.to_string())
             },
@@ -45,6 +56,7 @@ pub fn save_b64_file(
     storage: &MediaStorage,
     file_size_limit: usize,
     allowed_media_types: &[&str],
+    maybe_storage_quota: Option<&StorageQuota>,
 ) -> Result<FileInfo, UploadError> {
     let file_data = base64::decode(b64data)?;
     if file_data.len() > file_size_limit {
@@ -52,6 +64,15 @@ pub fn save_b64_file(
             limit: FileSize::new(file_size_limit),
         });
     };
+    if let Some(storage_quota) = maybe_storage_quota {
+        if !storage_quota.can_store(file_data.len()) {
+            let limit = usize::try_from(storage_quota.limit)
+                .unwrap_or(usize::MAX);
+            return Err(UploadError::StorageQuotaExceeded {
+                limit: FileSize::new(limit),
+            });
+        };
+    };
     let media_type = if media_type == "application/ogg" {
         // Audio or video
         // https://developer.mozilla.org/en-US/docs/Web/Media/Guides/Formats/Containers#ogg
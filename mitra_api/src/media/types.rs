@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct MediaQueryParams {
+    pub expires: Option<i64>,
+    pub signature: Option<String>,
+}
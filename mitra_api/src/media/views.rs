@@ -0,0 +1,158 @@
+/// Media server with access control
+use actix_files::NamedFile;
+use actix_web::{
+    dev::ConnectionInfo,
+    get,
+    web,
+    HttpRequest,
+    HttpResponse,
+    Scope,
+};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use uuid::Uuid;
+
+use mitra_activitypub::importers::ApClient;
+use mitra_config::{Config, MediaAccessMode};
+use mitra_models::{
+    attachments::queries::get_restricted_media_posts,
+    database::{
+        get_database_client,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    oauth::queries::get_user_by_oauth_token,
+    posts::{
+        helpers::can_view_post,
+        queries::get_post_by_id,
+    },
+    profiles::types::DbActorProfile,
+};
+use mitra_services::media::{
+    FilesystemServer,
+    MediaStorage,
+    MediaUrlSigner,
+};
+
+use crate::{
+    activitypub::auth::check_request_opt,
+    errors::HttpError,
+    http::get_request_full_uri,
+};
+
+use super::types::MediaQueryParams;
+
+fn verify_signed_url(
+    config: &Config,
+    file_name: &str,
+    query_params: &MediaQueryParams,
+) -> bool {
+    let (Some(expires), Some(signature)) =
+        (query_params.expires, query_params.signature.as_ref())
+        else { return false };
+    let Ok(signature) = hex::decode(signature) else { return false };
+    let url_signer = MediaUrlSigner::new(
+        config.media_url_key(),
+        config.media_access.signed_url_lifetime,
+    );
+    url_signer.verify(file_name, expires, &signature)
+}
+
+async fn get_viewer(
+    config: &Config,
+    connection_info: &ConnectionInfo,
+    db_pool: &DatabaseConnectionPool,
+    request: &HttpRequest,
+    maybe_auth: Option<BearerAuth>,
+) -> Result<Option<DbActorProfile>, HttpError> {
+    if let Some(auth) = maybe_auth {
+        let db_client = &**get_database_client(db_pool).await?;
+        let viewer = match get_user_by_oauth_token(db_client, auth.token()).await {
+            Ok((_, user)) => user.profile,
+            Err(DatabaseError::NotFound(_)) => {
+                return Err(HttpError::AuthError("access token is invalid"));
+            },
+            Err(other_error) => return Err(other_error.into()),
+        };
+        return Ok(Some(viewer));
+    };
+    let request_full_uri = get_request_full_uri(connection_info, request.uri());
+    let ap_client = ApClient::new_with_pool(config, db_pool).await?;
+    let maybe_signer = check_request_opt(
+        &ap_client,
+        db_pool,
+        request,
+        &request_full_uri,
+    ).await?;
+    Ok(maybe_signer)
+}
+
+async fn can_view_posts(
+    db_pool: &DatabaseConnectionPool,
+    maybe_viewer: Option<&DbActorProfile>,
+    post_ids: &[Uuid],
+) -> Result<bool, HttpError> {
+    let db_client = &**get_database_client(db_pool).await?;
+    for post_id in post_ids {
+        let post = get_post_by_id(db_client, *post_id).await?;
+        if can_view_post(db_client, maybe_viewer, &post).await? {
+            return Ok(true);
+        };
+    };
+    Ok(false)
+}
+
+#[get("/{file_name}")]
+async fn media_view(
+    auth: Option<BearerAuth>,
+    config: web::Data<Config>,
+    connection_info: ConnectionInfo,
+    db_pool: web::Data<DatabaseConnectionPool>,
+    request: HttpRequest,
+    file_name: web::Path<String>,
+    query_params: web::Query<MediaQueryParams>,
+) -> Result<HttpResponse, HttpError> {
+    if file_name.starts_with('.') || file_name.contains('/') {
+        return Err(HttpError::NotFound("media"));
+    };
+    let post_ids = {
+        let db_client = &**get_database_client(&db_pool).await?;
+        get_restricted_media_posts(db_client, &file_name).await?
+    };
+    if !post_ids.is_empty() {
+        // Clients receive signed URLs in both restricted modes
+        let is_allowed = match config.media_access.mode {
+            MediaAccessMode::Public => true,
+            _ if verify_signed_url(&config, &file_name, &query_params) => true,
+            MediaAccessMode::SignedUrls => false,
+            MediaAccessMode::AuthCheck => {
+                let maybe_viewer = get_viewer(
+                    &config,
+                    &connection_info,
+                    &db_pool,
+                    &request,
+                    auth,
+                ).await?;
+                can_view_posts(
+                    &db_pool,
+                    maybe_viewer.as_ref(),
+                    &post_ids,
+                ).await?
+            },
+        };
+        if !is_allowed {
+            // Don't reveal the existence of the file
+            return Err(HttpError::NotFound("media"));
+        };
+    };
+    let MediaStorage::Filesystem(backend) = MediaStorage::new(&config);
+    let file_path = backend.media_dir.join(file_name.as_str());
+    let file = NamedFile::open_async(file_path).await
+        .map_err(|_| HttpError::NotFound("media"))?;
+    let response = file.into_response(&request);
+    Ok(response)
+}
+
+pub fn media_scope() -> Scope {
+    web::scope(FilesystemServer::BASE_PATH)
+        .service(media_view)
+}
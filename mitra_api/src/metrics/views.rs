@@ -7,19 +7,64 @@ use actix_web::{
 };
 use actix_web_httpauth::extractors::basic::BasicAuth;
 
+use mitra_activitypub::filter::get_filter_stats;
 use mitra_config::Config;
 use mitra_models::{
     background_jobs::{
-        queries::get_job_count,
+        queries::{get_dead_letter_count, get_job_count},
         types::JobType,
     },
     database::{get_database_client, DatabaseConnectionPool},
 };
+use mitra_services::monero::wallet::get_wallet_rpc_stats;
 
 use crate::errors::HttpError;
 
 const OPENMETRICS_MEDIA_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
 
+fn format_monero_wallet_rpc_metrics() -> String {
+    let stats = get_wallet_rpc_stats();
+    if stats.is_empty() {
+        return String::new();
+    };
+    let mut requests = "# TYPE mitra_monero_wallet_rpc_requests counter\n".to_owned();
+    let mut failures = "# TYPE mitra_monero_wallet_rpc_failures counter\n".to_owned();
+    let mut latency = "# TYPE mitra_monero_wallet_rpc_latency_seconds summary\n".to_owned();
+    for (url, item) in stats {
+        requests += &format!(
+            "mitra_monero_wallet_rpc_requests_total{{endpoint=\"{url}\"}} {}\n",
+            item.requests,
+        );
+        failures += &format!(
+            "mitra_monero_wallet_rpc_failures_total{{endpoint=\"{url}\"}} {}\n",
+            item.failures,
+        );
+        latency += &format!(
+            "mitra_monero_wallet_rpc_latency_seconds_sum{{endpoint=\"{url}\"}} {}\n",
+            item.latency_total.as_secs_f64(),
+        );
+        latency += &format!(
+            "mitra_monero_wallet_rpc_latency_seconds_count{{endpoint=\"{url}\"}} {}\n",
+            item.requests,
+        );
+    };
+    requests + &failures + &latency
+}
+
+fn format_filter_metrics() -> String {
+    let stats = get_filter_stats();
+    if stats.is_empty() {
+        return String::new();
+    };
+    let mut matches = "# TYPE mitra_filter_rule_matches counter\n".to_owned();
+    for (action, target, count) in stats {
+        matches += &format!(
+            "mitra_filter_rule_matches_total{{action=\"{action}\",target=\"{target}\"}} {count}\n",
+        );
+    };
+    matches
+}
+
 #[get("")]
 async fn metrics_view(
     auth: BasicAuth,
@@ -40,10 +85,14 @@ async fn metrics_view(
         get_job_count(db_client, JobType::IncomingActivity).await?;
     let outgoing_activities =
         get_job_count(db_client, JobType::OutgoingActivity).await?;
+    let dead_letters = get_dead_letter_count(db_client).await?;
     let body = format!(
         include_str!("templates/openmetrics.txt"),
         incoming_activity_queue_size=incoming_activities,
         outgoing_activity_queue_size=outgoing_activities,
+        dead_letter_queue_size=dead_letters,
+        monero_wallet_rpc_metrics=format_monero_wallet_rpc_metrics(),
+        filter_metrics=format_filter_metrics(),
     );
     let response = HttpResponse::Ok()
         .content_type(OPENMETRICS_MEDIA_TYPE)
@@ -1,8 +1,11 @@
 use mitra_activitypub::identifiers::local_actor_id;
+use mitra_adapters::{
+    dynamic_config::DynamicConfig,
+    instance::get_instance_contact,
+};
 use mitra_config::Config;
 use mitra_models::{
     accounts::queries::{
-        get_admin_user,
         get_active_user_count,
         get_user_count,
     },
@@ -39,13 +42,14 @@ pub async fn get_usage(db_client: &impl DatabaseClient)
 
 pub async fn get_instance_staff(
     config: &Config,
+    dynamic_config: &DynamicConfig,
     db_client: &impl DatabaseClient,
 ) -> Result<Vec<String>, DatabaseError> {
-    let maybe_admin = if config.instance_staff_public {
-        get_admin_user(db_client).await?
-    } else {
-        None
-    };
+    let maybe_admin = get_instance_contact(
+        config,
+        dynamic_config,
+        db_client,
+    ).await?;
     let instance_staff = match maybe_admin {
         Some(admin) => {
             let admin_actor_id = local_actor_id(
@@ -1,6 +1,7 @@
 // http://nodeinfo.diaspora.software/schema.html
 use serde::Serialize;
 
+use mitra_adapters::dynamic_config::DynamicConfig;
 use mitra_config::{
     Config,
     RegistrationType,
@@ -89,10 +90,16 @@ pub struct Metadata {
 }
 
 impl Metadata {
-    pub fn new(config: &Config, instance_staff: Vec<String>) -> Self {
+    pub fn new(
+        config: &Config,
+        dynamic_config: &DynamicConfig,
+        instance_staff: Vec<String>,
+    ) -> Self {
+        let node_description = dynamic_config.instance_description.clone()
+            .unwrap_or(config.instance_short_description.clone());
         Self {
             node_name: config.instance_title.clone(),
-            node_description: config.instance_short_description.clone(),
+            node_description,
             federation: FederationMetadata {
                 enabled: config.federation.enabled,
             },
@@ -2,6 +2,7 @@
 use actix_web::{get, web, HttpResponse};
 use apx_sdk::jrd::{JsonResourceDescriptor, Link};
 
+use mitra_adapters::dynamic_config::get_dynamic_config;
 use mitra_config::Config;
 use mitra_models::database::{get_database_client, DatabaseConnectionPool};
 
@@ -38,8 +39,13 @@ pub async fn get_nodeinfo_2_0(
 ) -> Result<HttpResponse, HttpError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let usage = get_usage(db_client).await?;
-    let instance_staff = get_instance_staff(&config, db_client).await?;
-    let metadata = Metadata::new(&config, instance_staff);
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let instance_staff = get_instance_staff(
+        &config,
+        &dynamic_config,
+        db_client,
+    ).await?;
+    let metadata = Metadata::new(&config, &dynamic_config, instance_staff);
     let nodeinfo = NodeInfo20::new(&config, usage, metadata);
     let response = HttpResponse::Ok().json(nodeinfo);
     Ok(response)
@@ -52,8 +58,13 @@ pub async fn get_nodeinfo_2_1(
 ) -> Result<HttpResponse, HttpError> {
     let db_client = &**get_database_client(&db_pool).await?;
     let usage = get_usage(db_client).await?;
-    let instance_staff = get_instance_staff(&config, db_client).await?;
-    let metadata = Metadata::new(&config, instance_staff);
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let instance_staff = get_instance_staff(
+        &config,
+        &dynamic_config,
+        db_client,
+    ).await?;
+    let metadata = Metadata::new(&config, &dynamic_config, instance_staff);
     let nodeinfo = NodeInfo21::new(&config, usage, metadata);
     let response = HttpResponse::Ok().json(nodeinfo);
     Ok(response)
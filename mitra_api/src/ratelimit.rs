@@ -84,7 +84,8 @@ impl RatelimitConfigs {
             registration: ratelimit_config(extractor, 2, 300, false),
             login: ratelimit_config(extractor, 5, 120, false),
             search: ratelimit_config(extractor, 2, 30, true),
-            fetch_collection: ratelimit_config(extractor, 6, 300, false),
+            // Permissive; to be used with extractor
+            fetch_collection: ratelimit_config(extractor, 6, 300, true),
         }
     }
 }
@@ -7,6 +7,7 @@ use actix_web::{
     dev::Service,
     http::{header as http_header},
     middleware::{
+        from_fn,
         ErrorHandlers,
         ErrorHandlerResponse,
         Logger as ActixLogger,
@@ -19,7 +20,7 @@ use actix_web::{
 };
 use log::Level;
 
-use mitra_config::{Config, Environment};
+use mitra_config::{Config, Environment, MediaAccessMode};
 use mitra_models::database::DatabaseConnectionPool;
 use mitra_services::{
     media::{FilesystemServer, MediaStorage},
@@ -29,12 +30,20 @@ use mitra_utils::files::set_file_permissions;
 use crate::{
     activitypub::views as activitypub,
     atom::views::atom_scope,
+    correlation::correlation_id_middleware,
+    health::views::health_api_scope,
     http::{
         create_default_headers_middleware,
         json_error_handler,
         log_response_error,
     },
-    mastodon_api::{mastodon_api_scope, oauth_api_scope},
+    maintenance::maintenance_mode_middleware,
+    mastodon_api::{
+        mastodon_api_scope,
+        oauth_api_scope,
+        streaming::listener::start_event_listener,
+    },
+    media::views::media_scope,
     metrics::views::metrics_api_scope,
     nodeinfo::views as nodeinfo,
     ratelimit::RatelimitConfigs,
@@ -48,6 +57,7 @@ pub async fn run_server(
     db_pool: DatabaseConnectionPool,
 ) -> std::io::Result<()> {
     let app_state = web::Data::new(AppState::default());
+    start_event_listener(&config, app_state.stream_events.clone());
     let media_storage = MediaStorage::new(&config);
     let num_workers = std::cmp::max(num_cpus::get(), 4);
     let http_socket_addr = config.http_socket();
@@ -89,11 +99,13 @@ pub async fn run_server(
                     .expose_any_header()
             },
         };
-        let payload_size_limit = 2 * config.limits.media.file_size_limit;
+        // Not reloadable: file size is checked again by media handlers
+        let payload_size_limit = 2 * config.limits().media.file_size_limit;
         let mut app = App::new()
             // NOTE: middlewares are executed in the reverse order
             // https://docs.rs/actix-web/latest/actix_web/middleware/#ordering
             .wrap(NormalizePath::trim())
+            .wrap(from_fn(maintenance_mode_middleware))
             .wrap(cors_config)
             .wrap(ErrorHandlers::new()
                 .default_handler_client(|response| {
@@ -109,6 +121,7 @@ pub async fn run_server(
                     Ok(ErrorHandlerResponse::Response(response.map_into_left_body()))
                 })
             )
+            .wrap(from_fn(correlation_id_middleware))
             .wrap_fn(|request, service| {
                 // Fix for https://github.com/actix/actix-web/issues/3191
                 let path = request.path().to_owned();
@@ -138,6 +151,7 @@ pub async fn run_server(
                 ratelimit_configs.clone(),
             ))
             .service(metrics_api_scope(config.metrics.is_some()))
+            .service(health_api_scope())
             .service(webfinger::webfinger_view)
             .service(activitypub::activitypub_scope())
             .service(activitypub::actor_scope())
@@ -145,6 +159,7 @@ pub async fn run_server(
             .service(activitypub::object_view)
             .service(activitypub::replies_collection)
             .service(activitypub::emoji_view)
+            .service(activitypub::quote_authorization_view)
             .service(activitypub::tag_view)
             .service(activitypub::conversation_view)
             .service(activitypub::activity_view)
@@ -157,8 +172,10 @@ pub async fn run_server(
             .service(nodeinfo::get_nodeinfo_jrd)
             .service(nodeinfo::get_nodeinfo_2_0)
             .service(nodeinfo::get_nodeinfo_2_1)
+            .service(web_client::index_page_overlay())
             .service(web_client::profile_page_overlay())
             .service(web_client::post_page_overlay())
+            .service(web_client::authorize_interaction_redirect())
             .service(
                 // Fallback for well-known paths
                 web::resource("/.well-known/{path}")
@@ -166,10 +183,14 @@ pub async fn run_server(
             );
         #[expect(irrefutable_let_patterns)]
         if let MediaStorage::Filesystem(ref backend) = media_storage {
-            app = app.service(actix_files::Files::new(
-                FilesystemServer::BASE_PATH,
-                backend.media_dir.clone(),
-            ));
+            if config.media_access.mode == MediaAccessMode::Public {
+                app = app.service(actix_files::Files::new(
+                    FilesystemServer::BASE_PATH,
+                    backend.media_dir.clone(),
+                ));
+            } else {
+                app = app.service(media_scope());
+            };
         };
         if let Some(ref web_client_dir) = config.web_client_dir {
             app = app.service(web_client::themeable_web_client_service(
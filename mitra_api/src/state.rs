@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 use std::time::Instant;
 
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
+
+use mitra_activitypub::actors::cache::ActorCache;
+use mitra_models::events::types::StreamEvent;
 
 pub struct TimedCache {
     store: HashMap<String, (Instant, String)>,
@@ -51,10 +54,22 @@ impl TimedCache {
 
 const POST_CACHE_EXPIRY_TIME: u64 = 60 * 60; // 1 hour
 const POST_CACHE_SIZE: usize = 100;
+const WEB_CLIENT_CACHE_EXPIRY_TIME: u64 = 60; // 1 minute
+const WEB_CLIENT_CACHE_SIZE: usize = 2;
+const ACCOUNT_ACTIVITY_CACHE_EXPIRY_TIME: u64 = 60 * 10; // 10 minutes
+const ACCOUNT_ACTIVITY_CACHE_SIZE: usize = 1000;
+const STREAM_EVENT_QUEUE_SIZE: usize = 1000;
 
 // https://actix.rs/docs/application/#shared-mutable-state
 pub struct AppState {
     pub post_id_cache: Mutex<TimedCache>,
+    // Web client index page
+    pub web_client_cache: Mutex<TimedCache>,
+    pub account_activity_cache: Mutex<TimedCache>,
+    // Serialized documents of local actors
+    pub actor_cache: ActorCache,
+    // Events for streaming API sessions
+    pub stream_events: broadcast::Sender<StreamEvent>,
 }
 
 impl Default for AppState {
@@ -64,6 +79,16 @@ impl Default for AppState {
                 POST_CACHE_EXPIRY_TIME,
                 POST_CACHE_SIZE,
             )),
+            web_client_cache: Mutex::new(TimedCache::new(
+                WEB_CLIENT_CACHE_EXPIRY_TIME,
+                WEB_CLIENT_CACHE_SIZE,
+            )),
+            account_activity_cache: Mutex::new(TimedCache::new(
+                ACCOUNT_ACTIVITY_CACHE_EXPIRY_TIME,
+                ACCOUNT_ACTIVITY_CACHE_SIZE,
+            )),
+            actor_cache: ActorCache::default(),
+            stream_events: broadcast::channel(STREAM_EVENT_QUEUE_SIZE).0,
         }
     }
 }
@@ -1,4 +1,4 @@
 mod types;
 pub mod urls;
-mod utils;
+pub mod utils;
 pub mod views;
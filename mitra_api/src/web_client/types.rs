@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Serialize)]
 pub struct MetadataBlock {
@@ -9,3 +9,16 @@ pub struct MetadataBlock {
     pub image_url: String,
     pub atom_url: Option<String>,
 }
+
+#[derive(Serialize)]
+pub struct InstanceMetadataBlock {
+    pub instance_title: String,
+    pub description: String,
+    pub page_type: &'static str,
+    pub image_url: String,
+}
+
+#[derive(Deserialize)]
+pub struct AuthorizeInteractionQueryParams {
+    pub uri: String,
+}
@@ -1,7 +1,14 @@
+use mitra_config::Config;
+
 // mitra-web
 const INDEX_TITLE_ELEMENT: &str = "<title>Mitra - Federated social network</title>";
 const INDEX_DESCRIPTION_ELEMENT: &str = r#"<meta name="description" content="Federated social network">"#;
 
+/// Returns true if index.html is served with page metadata
+pub fn is_opengraph_enabled(config: &Config) -> bool {
+    config.web_client_dir.is_some() && config.web_client_rewrite_index
+}
+
 pub fn replace_index_metadata(
     index_html: String,
     metadata: String,
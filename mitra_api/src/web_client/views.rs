@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use actix_files::{Files, NamedFile};
+use actix_files::Files;
 use actix_web::{
     dev::{fn_service, ServiceRequest, ServiceResponse},
     guard,
@@ -9,7 +9,10 @@ use actix_web::{
     Resource,
 };
 use apx_sdk::{
-    core::http_types::header_map_adapter,
+    core::{
+        http_types::header_map_adapter,
+        url::common::url_encode,
+    },
     http_server::is_activitypub_request,
 };
 use uuid::Uuid;
@@ -21,6 +24,7 @@ use mitra_activitypub::{
         compatible_profile_actor_id,
     },
 };
+use mitra_adapters::dynamic_config::get_dynamic_config;
 use mitra_config::Config;
 use mitra_models::{
     database::{
@@ -29,7 +33,7 @@ use mitra_models::{
         DatabaseError,
     },
     posts::{
-        queries::get_post_by_id,
+        queries::{get_post_by_id, increment_post_view_count},
     },
     profiles::queries::get_profile_by_acct,
 };
@@ -38,21 +42,90 @@ use mitra_utils::html::extract_title;
 use crate::{
     atom::urls::get_user_feed_url,
     errors::HttpError,
+    state::AppState,
     templates::render_template,
 };
 
 use super::{
-    types::MetadataBlock,
-    urls::get_opengraph_image_url,
-    utils::replace_index_metadata,
+    types::{
+        AuthorizeInteractionQueryParams,
+        InstanceMetadataBlock,
+        MetadataBlock,
+    },
+    urls::{get_opengraph_image_url, get_search_page_url},
+    utils::{is_opengraph_enabled, replace_index_metadata},
 };
 
 const INDEX_FILE: &str = "index.html";
+const INDEX_PAGE_CACHE_KEY: &str = "index_page";
 const CUSTOM_CSS_PATH: &str = "/assets/custom.css";
+// Paths that are never handled by frontend
+const RESERVED_PATH_PREFIXES: [&str; 4] = [
+    "/.well-known/",
+    "/api/",
+    "/nodeinfo/",
+    "/oauth/",
+];
 
 // https://ogp.me/#types
 const OG_TYPE_ARTICLE: &str = "article";
 const OG_TYPE_PROFILE: &str = "profile";
+const OG_TYPE_WEBSITE: &str = "website";
+
+fn is_reserved_path(path: &str) -> bool {
+    RESERVED_PATH_PREFIXES.iter()
+        .any(|prefix| path.starts_with(prefix))
+}
+
+/// Returns contents of index.html (cached)
+async fn read_index_file(
+    config: &Config,
+    app_state: &AppState,
+) -> Result<String, HttpError> {
+    let mut cache = app_state.web_client_cache.lock().await;
+    if let Some(index_html) = cache.get(INDEX_FILE) {
+        return Ok(index_html.to_owned());
+    };
+    let web_client_dir = config.web_client_dir.as_ref()
+        .expect("web_client_dir should be defined");
+    let index_html = tokio::fs::read_to_string(web_client_dir.join(INDEX_FILE))
+        .await
+        .map_err(HttpError::from_internal)?;
+    cache.set(INDEX_FILE.to_string(), index_html.clone());
+    Ok(index_html)
+}
+
+/// Returns index.html with instance metadata (cached)
+async fn render_index_page(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+    app_state: &AppState,
+) -> Result<String, HttpError> {
+    let maybe_page = app_state.web_client_cache.lock().await
+        .get(INDEX_PAGE_CACHE_KEY)
+        .map(|page| page.to_owned());
+    if let Some(page) = maybe_page {
+        return Ok(page);
+    };
+    let index_html = read_index_file(config, app_state).await?;
+    let db_client = &**get_database_client(db_pool).await?;
+    let dynamic_config = get_dynamic_config(db_client).await?;
+    let context = InstanceMetadataBlock {
+        instance_title: config.instance_title.clone(),
+        description: dynamic_config.instance_description
+            .unwrap_or(config.instance_short_description.clone()),
+        page_type: OG_TYPE_WEBSITE,
+        image_url: get_opengraph_image_url(config.instance().uri_str()),
+    };
+    let metadata_block = render_template(
+        include_str!("templates/instance_metadata_block.html"),
+        context,
+    )?;
+    let page = replace_index_metadata(index_html, metadata_block);
+    app_state.web_client_cache.lock().await
+        .set(INDEX_PAGE_CACHE_KEY.to_string(), page.clone());
+    Ok(page)
+}
 
 fn web_client_service(web_client_dir: &Path) -> Files {
     Files::new("/", web_client_dir)
@@ -61,19 +134,40 @@ fn web_client_service(web_client_dir: &Path) -> Files {
         .default_handler(fn_service(|service_request: ServiceRequest| {
             // Workaround for https://github.com/actix/actix-web/issues/2617
             let (request, _) = service_request.into_parts();
-            let index_path = request.app_data::<web::Data<Config>>()
+            let config = request.app_data::<web::Data<Config>>()
                 .expect("app data should contain config")
-                .web_client_dir.as_ref()
-                .expect("web_client_dir should be present in config")
-                .join(INDEX_FILE);
-            async {
-                if request.path() == CUSTOM_CSS_PATH {
+                .clone();
+            let db_pool = request.app_data::<web::Data<DatabaseConnectionPool>>()
+                .expect("app data should contain database connection pool")
+                .clone();
+            let app_state = request.app_data::<web::Data<AppState>>()
+                .expect("app data should contain app state")
+                .clone();
+            async move {
+                if request.path() == CUSTOM_CSS_PATH ||
+                    is_reserved_path(request.path())
+                {
                     // Don't serve index.html if custom.css doesn't exist
+                    // or if route is not handled by frontend
                     let response = HttpResponse::NotFound().finish();
                     return Ok(ServiceResponse::new(request, response));
                 };
-                let index_file = NamedFile::open_async(index_path).await?;
-                let response = index_file.into_response(&request);
+                if config.web_client_rewrite_index {
+                    // Insert instance metadata
+                    let page = render_index_page(
+                        &config,
+                        &db_pool,
+                        &app_state,
+                    ).await?;
+                    let response = HttpResponse::Ok()
+                        .content_type("text/html")
+                        .body(page);
+                    return Ok(ServiceResponse::new(request, response));
+                };
+                let index_html = read_index_file(&config, &app_state).await?;
+                let response = HttpResponse::Ok()
+                    .content_type("text/html")
+                    .body(index_html);
                 Ok(ServiceResponse::new(request, response))
             }
         }))
@@ -103,10 +197,28 @@ fn opengraph_guard() -> impl guard::Guard {
     guard::fn_guard(|ctx| {
         let config = ctx.app_data::<web::Data<Config>>()
             .expect("app data should contain config");
-        config.web_client_dir.is_some() && config.web_client_rewrite_index
+        is_opengraph_enabled(config)
     })
 }
 
+async fn index_page_view(
+    app_state: web::Data<AppState>,
+    config: web::Data<Config>,
+    db_pool: web::Data<DatabaseConnectionPool>,
+) -> Result<HttpResponse, HttpError> {
+    let page = render_index_page(&config, &db_pool, &app_state).await?;
+    let response = HttpResponse::Ok()
+        .content_type("text/html")
+        .body(page);
+    Ok(response)
+}
+
+pub fn index_page_overlay() -> Resource {
+    web::resource("/")
+        .guard(opengraph_guard())
+        .route(web::get().to(index_page_view))
+}
+
 async fn profile_page_redirect_view(
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
@@ -123,15 +235,13 @@ async fn profile_page_redirect_view(
 }
 
 async fn profile_page_opengraph_view(
+    app_state: web::Data<AppState>,
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
     acct: web::Path<String>,
 ) -> Result<HttpResponse, HttpError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let web_client_dir = config.web_client_dir.as_ref()
-        .expect("web_client_dir should be defined");
-    let index_html = std::fs::read_to_string(web_client_dir.join(INDEX_FILE))
-        .map_err(HttpError::from_internal)?;
+    let index_html = read_index_file(&config, &app_state).await?;
     let page = match get_profile_by_acct(db_client, &acct).await {
         Ok(profile) => {
             // Rewrite index.html and insert metadata
@@ -195,17 +305,18 @@ async fn post_page_redirect_view(
 }
 
 async fn post_page_opengraph_view(
+    app_state: web::Data<AppState>,
     config: web::Data<Config>,
     db_pool: web::Data<DatabaseConnectionPool>,
     post_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, HttpError> {
     let db_client = &**get_database_client(&db_pool).await?;
-    let web_client_dir = config.web_client_dir.as_ref()
-        .expect("web_client_dir should be defined");
-    let index_html = std::fs::read_to_string(web_client_dir.join(INDEX_FILE))
-        .map_err(HttpError::from_internal)?;
+    let index_html = read_index_file(&config, &app_state).await?;
     let page = match get_post_by_id(db_client, *post_id).await {
         Ok(post) if post.is_public() => {
+            if config.post_view_count_enabled && post.is_local() {
+                increment_post_view_count(db_client, post.id).await?;
+            };
             // Rewrite index.html and insert metadata
             let title_short = format!("Post by @{}", post.author.preferred_handle());
             let title = if post.in_reply_to_id.is_none() {
@@ -248,3 +359,22 @@ pub fn post_page_overlay() -> Resource {
         .route(web::get().guard(activitypub_guard()).to(post_page_redirect_view))
         .route(web::get().guard(opengraph_guard()).to(post_page_opengraph_view))
 }
+
+/// Handles Mastodon-style remote interaction requests
+async fn authorize_interaction_view(
+    config: web::Data<Config>,
+    query_params: web::Query<AuthorizeInteractionQueryParams>,
+) -> HttpResponse {
+    let search_page_url = get_search_page_url(
+        config.instance().uri_str(),
+        &url_encode(query_params.uri.trim()),
+    );
+    HttpResponse::Found()
+        .append_header(("Location", search_page_url))
+        .finish()
+}
+
+pub fn authorize_interaction_redirect() -> Resource {
+    web::resource("/authorize_interaction")
+        .route(web::get().to(authorize_interaction_view))
+}
@@ -15,9 +15,14 @@ use mitra_activitypub::{
     },
     importers::get_profile_by_actor_id,
     utils::db_url_to_http_url,
+    webfinger::{
+        FEP_3B86_OBJECT_INTENT_RELATION_TYPE,
+        REMOTE_INTERACTION_RELATION_TYPE,
+    },
 };
 use mitra_config::Instance;
 use mitra_models::{
+    accounts::queries::get_user_by_previous_username,
     database::{
         DatabaseClient,
         DatabaseError,
@@ -35,9 +40,6 @@ use crate::{
 };
 
 const WEBFINGER_PROFILE_RELATION_TYPE: &str = "http://webfinger.net/rel/profile-page";
-const REMOTE_INTERACTION_RELATION_TYPE: &str = "http://ostatus.org/schema/1.0/subscribe";
-// https://codeberg.org/fediverse/fep/src/commit/78a31a92cb264ca603af24b4fcaae944b62edb9b/fep/3b86/fep-3b86.md#5-1-object-intent
-const FEP_3B86_OBJECT_INTENT_RELATION_TYPE: &str = "https://w3id.org/fep/3b86/Object";
 // Relation type used by Friendica
 const FEED_RELATION_TYPE: &str = "http://schemas.google.com/g/2010#updates-from";
 
@@ -96,10 +98,30 @@ pub async fn get_jrd(
             .with_template(&remote_interaction_template);
         vec![actor_link, remote_interaction_link]
     } else  {
-        let profile = get_profile_by_acct(
+        let profile = match get_profile_by_acct(
             db_client,
             webfinger_address.username(),
-        ).await?;
+        ).await {
+            Ok(profile) => profile,
+            Err(DatabaseError::NotFound(_)) => {
+                // Previous username of a renamed account
+                get_user_by_previous_username(
+                    db_client,
+                    webfinger_address.username(),
+                ).await?;
+                let actor_id = local_actor_id(
+                    instance.uri_str(),
+                    webfinger_address.username(),
+                );
+                let actor_link = Link::actor(&actor_id);
+                let jrd = JsonResourceDescriptor {
+                    subject: webfinger_address.to_acct_uri(),
+                    links: vec![actor_link],
+                };
+                return Ok(jrd);
+            },
+            Err(other_error) => return Err(other_error.into()),
+        };
         if profile.has_user_account() {
             let actor_id = local_actor_id(
                 instance.uri_str(),
@@ -168,7 +190,10 @@ mod tests {
     use serde_json::json;
     use serial_test::serial;
     use mitra_models::{
-        accounts::test_utils::create_test_user,
+        accounts::{
+            queries::rename_user,
+            test_utils::create_test_user,
+        },
         database::test_utils::create_test_database,
     };
     use super::*;
@@ -212,4 +237,27 @@ mod tests {
         });
         assert_eq!(jrd_value, expected_jrd_value);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_jrd_previous_username() {
+        let db_client = &mut create_test_database().await;
+        let instance = Instance::for_test("https://social.example");
+        let user = create_test_user(db_client, "test").await;
+        rename_user(db_client, user.id, "renamed").await.unwrap();
+        let resource = "acct:test@social.example";
+        let jrd = get_jrd(db_client, instance, resource).await.unwrap();
+        let jrd_value = serde_json::to_value(jrd).unwrap();
+        let expected_jrd_value = json!({
+            "subject": "acct:test@social.example",
+            "links": [
+                {
+                    "rel": "self",
+                    "type": "application/ld+json; profile=\"https://www.w3.org/ns/activitystreams\"",
+                    "href": "https://social.example/users/test"
+                },
+            ]
+        });
+        assert_eq!(jrd_value, expected_jrd_value);
+    }
 }
@@ -14,8 +14,11 @@ use crate::commands::{
         InviteCommand,
         ListAccounts,
         ListInviteCodes,
+        RenameAccount,
+        SetFeatureFlag,
         SetPassword,
         SetRole,
+        SetStorageQuota,
         RevokeOauthTokens,
     },
     activitypub::{
@@ -23,11 +26,19 @@ use crate::commands::{
         CreateActivity,
         FetchObject,
         ImportObject,
+        ListDeadLetters,
         LoadPortableObject,
         LoadReplies,
+        PurgeDeadLetters,
+        RequeueDeadLetter,
         SendActivity,
         Webfinger,
     },
+    announcement::{
+        AddAnnouncement,
+        DeleteAnnouncement,
+        ListAnnouncements,
+    },
     config::{
         ConfigCommand,
         GetConfig,
@@ -38,13 +49,17 @@ use crate::commands::{
         DeleteEmoji,
         EmojiCommand,
         ImportEmoji,
+        ListEmojis,
+        PruneEmojis,
     },
+    feed::{ListFeeds, UpdateFeed},
     filter::{
         AddFilterRule,
         FilterCommand,
         ListFilterRules,
         RemoveFilterRule,
     },
+    fixtures::GenerateFixtures,
     invoice::{
         ReopenInvoice,
         RepairInvoice,
@@ -56,14 +71,24 @@ use crate::commands::{
         CreateMoneroWallet,
         VerifyMoneroSignature,
     },
-    post::{CreatePost, DeletePost, ExportPosts, ImportPosts},
+    pleroma::ImportPleroma,
+    post::{CreatePost, DeletePost, ExportPosts, ImportPosts, MovePosts},
     process::Worker,
     profile::{
         DeleteUser,
         ListUnreachableActors,
+        MergeProfiles,
+        UnmergeProfile,
+    },
+    report::{Healthcheck, InstanceReport},
+    server_rule::{
+        AddServerRule,
+        DeleteServerRule,
+        ListServerRules,
+        UpdateServerRule,
     },
-    report::InstanceReport,
     storage::{
+        ActivitypubObjects,
         CheckUris,
         DeleteEmptyProfiles,
         DeleteExtraneousPosts,
@@ -72,7 +97,9 @@ use crate::commands::{
         ListLocalFiles,
         MediaCommand,
         PruneReposts,
+        VerifyMedia,
     },
+    subscription::GrantSubscription,
 };
 
 #[derive(Parser)]
@@ -102,6 +129,13 @@ pub enum Command {
     AddFilterRule(AddFilterRule),
     RemoveFilterRule(RemoveFilterRule),
     ListFilterRules(ListFilterRules),
+    AddServerRule(AddServerRule),
+    UpdateServerRule(UpdateServerRule),
+    DeleteServerRule(DeleteServerRule),
+    ListServerRules(ListServerRules),
+    AddAnnouncement(AddAnnouncement),
+    DeleteAnnouncement(DeleteAnnouncement),
+    ListAnnouncements(ListAnnouncements),
     GenerateInviteCode(GenerateInviteCode),
     ListInviteCodes(ListInviteCodes),
     #[command(visible_alias = "create-user")]
@@ -111,7 +145,10 @@ pub enum Command {
     #[command(visible_alias = "list-users")]
     ListAccounts(ListAccounts),
     SetPassword(SetPassword),
+    RenameAccount(RenameAccount),
     SetRole(SetRole),
+    SetFeatureFlag(SetFeatureFlag),
+    SetStorageQuota(SetStorageQuota),
     RevokeOauthTokens(RevokeOauthTokens),
     ImportObject(ImportObject),
     #[command(visible_alias = "fetch-replies")]
@@ -121,23 +158,34 @@ pub enum Command {
     LoadPortableObject(LoadPortableObject),
     CreateActivity(CreateActivity),
     SendActivity(SendActivity),
+    ListDeadLetters(ListDeadLetters),
+    RequeueDeadLetter(RequeueDeadLetter),
+    PurgeDeadLetters(PurgeDeadLetters),
     #[command(visible_alias = "delete-account", alias = "delete-profile")]
     DeleteUser(DeleteUser),
     CreatePost(CreatePost),
     ImportPosts(ImportPosts),
+    MovePosts(MovePosts),
     ExportPosts(ExportPosts),
+    ImportPleroma(ImportPleroma),
     DeletePost(DeletePost),
     AddEmoji(AddEmoji),
     #[command(visible_alias = "steal-emoji")]
     ImportEmoji(ImportEmoji),
     DeleteEmoji(DeleteEmoji),
+    ListEmojis(ListEmojis),
+    PruneEmojis(PruneEmojis),
     DeleteExtraneousPosts(DeleteExtraneousPosts),
     PruneReposts(PruneReposts),
     DeleteUnusedAttachments(DeleteUnusedAttachments),
     DeleteEmptyProfiles(DeleteEmptyProfiles),
     ListLocalFiles(ListLocalFiles),
     DeleteOrphanedFiles(DeleteOrphanedFiles),
+    VerifyMedia(VerifyMedia),
+    ActivitypubObjects(ActivitypubObjects),
     ListUnreachableActors(ListUnreachableActors),
+    MergeProfiles(MergeProfiles),
+    UnmergeProfile(UnmergeProfile),
     CheckUris(CheckUris),
     CreateMoneroWallet(CreateMoneroWallet),
     CreateMoneroSignature(CreateMoneroSignature),
@@ -146,7 +194,12 @@ pub enum Command {
     RepairInvoice(RepairInvoice),
     ListActiveAddresses(ListActiveAddresses),
     GetPaymentAddress(GetPaymentAddress),
+    GrantSubscription(GrantSubscription),
+    ListFeeds(ListFeeds),
+    UpdateFeed(UpdateFeed),
     InstanceReport(InstanceReport),
+    GenerateFixtures(GenerateFixtures),
+    Healthcheck(Healthcheck),
 
     /// Generate shell completions
     Completion {
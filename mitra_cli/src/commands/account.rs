@@ -1,4 +1,6 @@
-use anyhow::Error;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
 use apx_sdk::core::{
     crypto::{
         eddsa::generate_ed25519_key,
@@ -9,11 +11,15 @@ use apx_sdk::core::{
     },
 };
 use clap::{
+    builder::PossibleValuesParser,
     Parser,
     Subcommand,
 };
 
-use mitra_activitypub::adapters::users::create_or_update_local_actor;
+use mitra_activitypub::adapters::users::{
+    create_or_update_local_actor,
+    rename_account,
+};
 use mitra_adapters::{
     roles::{
         from_default_role,
@@ -32,12 +38,17 @@ use mitra_models::{
             create_user,
             get_accounts_for_admin,
             get_invite_codes,
+            get_user_by_id,
+            set_user_feature_flag,
+            set_user_keys,
             set_user_password,
             set_user_role,
+            set_user_storage_quota,
         },
         types::{
             AutomatedAccountData,
             AutomatedAccountType,
+            FeatureFlag,
             UserCreateData,
         },
     },
@@ -83,6 +94,7 @@ impl CreateAccount {
             ed25519_secret_key: ed25519_secret_key,
             invite_code: None,
             role,
+            rules_accepted_at: None,
         };
         let account = create_user(db_client, user_data).await?;
         create_or_update_local_actor(config, db_client, &account).await?;
@@ -177,6 +189,52 @@ impl SetPassword {
     }
 }
 
+/// Change username of a local user
+#[derive(Parser)]
+pub struct RenameAccount {
+    id_or_name: String,
+    new_username: String,
+    /// Generate new keys for the account
+    #[arg(long)]
+    regenerate_keys: bool,
+}
+
+impl RenameAccount {
+    pub async fn execute(
+        self,
+        config: &Config,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &mut **get_database_client(db_pool).await?;
+        validate_local_username(&self.new_username)?;
+        let mut user = get_user_by_id_or_name(
+            db_client,
+            &self.id_or_name,
+        ).await?;
+        if self.regenerate_keys {
+            let rsa_secret_key = generate_rsa_key()?;
+            let rsa_secret_key_pem =
+                rsa_secret_key_to_pkcs8_pem(&rsa_secret_key)?;
+            let ed25519_secret_key = generate_ed25519_key();
+            set_user_keys(
+                db_client,
+                user.id,
+                &rsa_secret_key_pem,
+                &ed25519_secret_key,
+            ).await?;
+            user = get_user_by_id(db_client, user.id).await?;
+        };
+        let user = rename_account(
+            config,
+            db_client,
+            &user,
+            &self.new_username,
+        ).await?;
+        println!("account renamed: {}", user.profile.username);
+        Ok(())
+    }
+}
+
 /// Change user's role
 #[derive(Parser)]
 pub struct SetRole {
@@ -202,6 +260,73 @@ impl SetRole {
     }
 }
 
+/// Enable or disable experimental feature for user
+#[derive(Parser)]
+pub struct SetFeatureFlag {
+    id_or_name: String,
+    #[arg(value_parser = PossibleValuesParser::new(
+        FeatureFlag::ALL.map(|feature_flag| feature_flag.as_str()),
+    ))]
+    feature_flag: String,
+    #[arg(long)]
+    disable: bool,
+}
+
+impl SetFeatureFlag {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        let user = get_user_by_id_or_name(
+            db_client,
+            &self.id_or_name,
+        ).await?;
+        let feature_flag = FeatureFlag::from_str(&self.feature_flag)
+            .map_err(|_| anyhow!("unknown feature flag"))?;
+        set_user_feature_flag(
+            db_client,
+            user.id,
+            feature_flag,
+            !self.disable,
+        ).await?;
+        if self.disable {
+            println!("feature disabled");
+        } else {
+            println!("feature enabled");
+        };
+        Ok(())
+    }
+}
+
+/// Set media storage quota for user (in bytes).
+/// Role quota is used if value is not provided.
+#[derive(Parser)]
+pub struct SetStorageQuota {
+    id_or_name: String,
+    storage_quota: Option<u64>,
+}
+
+impl SetStorageQuota {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        let user = get_user_by_id_or_name(
+            db_client,
+            &self.id_or_name,
+        ).await?;
+        set_user_storage_quota(
+            db_client,
+            user.id,
+            self.storage_quota,
+        ).await?;
+        println!("storage quota updated");
+        Ok(())
+    }
+}
+
 /// Revoke user's OAuth access tokens
 #[derive(Parser)]
 pub struct RevokeOauthTokens {
@@ -277,7 +402,10 @@ pub enum AccountCommand {
     Create(CreateAccount),
     List(ListAccounts),
     Password(SetPassword),
+    Rename(RenameAccount),
     Role(SetRole),
+    Feature(SetFeatureFlag),
+    Quota(SetStorageQuota),
     Logout(RevokeOauthTokens),
 }
 
@@ -291,7 +419,10 @@ impl AccountCommand {
             Self::Create(command) => command.execute(config, db_pool).await,
             Self::List(command) => command.execute(db_pool).await,
             Self::Password(command) => command.execute(db_pool).await,
+            Self::Rename(command) => command.execute(config, db_pool).await,
             Self::Role(command) => command.execute(db_pool).await,
+            Self::Feature(command) => command.execute(db_pool).await,
+            Self::Quota(command) => command.execute(db_pool).await,
             Self::Logout(command) => command.execute(db_pool).await,
         }
     }
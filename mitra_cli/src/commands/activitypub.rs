@@ -28,8 +28,9 @@ use mitra_activitypub::{
         like::build_like,
     },
     deliverer::{Recipient, Sender},
+    explain::explain_object,
     forwarder::get_activity_recipients,
-    handlers::activity::get_activity_audience,
+    handlers::activity::{get_activity_audience, handle_activity},
     identifiers::canonicalize_id,
     importers::{
         get_post_by_object_id,
@@ -44,11 +45,17 @@ use mitra_activitypub::{
         CollectionOrder,
         FetcherContext,
     },
+    queues::requeue_dead_letter,
     webfinger::fetch_webfinger_jrd,
 };
 use mitra_config::Config;
 use mitra_models::{
     accounts::queries::get_user_by_name,
+    activitypub::queries::get_incoming_activities,
+    background_jobs::queries::{
+        delete_dead_letters,
+        get_dead_letters,
+    },
     database::{
         db_client_await,
         get_database_client,
@@ -60,7 +67,10 @@ use mitra_models::{
     profiles::queries::get_remote_profile_by_actor_id,
 };
 use mitra_services::media::MediaServer;
-use mitra_utils::id::generate_ulid;
+use mitra_utils::{
+    datetime::days_before_now,
+    id::generate_ulid,
+};
 
 /// Fetch ActivityPub object and process it
 #[derive(Parser)]
@@ -271,6 +281,103 @@ impl FetchObject {
     }
 }
 
+/// Fetch object and explain how it would be processed, without saving it
+#[derive(Parser)]
+pub struct ExplainObject {
+    object_id: String,
+    #[arg(long)]
+    as_user: Option<String>,
+}
+
+impl ExplainObject {
+    pub async fn execute(
+        self,
+        config: &Config,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let maybe_user = if let Some(ref username) = self.as_user {
+            let user = get_user_by_name(
+                db_client_await!(db_pool),
+                username,
+            ).await?;
+            Some(user)
+        } else {
+            None
+        };
+        let mut ap_client = ApClient::new_with_pool(config, db_pool).await?;
+        ap_client.as_user = maybe_user;
+        let report = explain_object(
+            &ap_client,
+            db_pool,
+            &self.object_id,
+        ).await?;
+        for step in report.steps.iter() {
+            println!("[{}] {}: {}", step.status, step.name, step.message);
+        };
+        if !report.is_ok() {
+            return Err(anyhow!("object will be rejected"));
+        };
+        Ok(())
+    }
+}
+
+/// Process stored incoming activities again
+#[derive(Parser)]
+pub struct ReplayActivities {
+    /// Replay activity with the given ID
+    #[arg(long)]
+    activity_id: Option<String>,
+    /// Replay activities received from the given domain
+    #[arg(long)]
+    domain: Option<String>,
+    #[arg(long, default_value_t = 1000)]
+    limit: u32,
+}
+
+impl ReplayActivities {
+    pub async fn execute(
+        self,
+        config: &Config,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        if self.activity_id.is_none() && self.domain.is_none() {
+            return Err(anyhow!("activity ID or domain must be specified"));
+        };
+        let activities = get_incoming_activities(
+            db_client_await!(db_pool),
+            self.activity_id.as_deref(),
+            self.domain.as_deref(),
+            self.limit,
+        ).await?;
+        let ap_client = ApClient::new_with_pool(config, db_pool).await?;
+        let mut failed_count = 0;
+        for incoming_activity in activities.iter() {
+            if let Err(error) = handle_activity(
+                config,
+                &ap_client,
+                db_pool,
+                &incoming_activity.activity,
+                incoming_activity.is_authenticated,
+                Some(&incoming_activity.recipient_id),
+                Some(&incoming_activity.sender_id),
+            ).await {
+                println!(
+                    "failed to process activity {}: {}",
+                    incoming_activity.activity_id,
+                    error,
+                );
+                failed_count += 1;
+            };
+        };
+        println!(
+            "replayed {} activities ({} failed)",
+            activities.len(),
+            failed_count,
+        );
+        Ok(())
+    }
+}
+
 /// Perform WebFinger query and print JRD to stdout
 #[derive(Parser)]
 pub struct Webfinger {
@@ -502,11 +609,87 @@ impl SendActivity {
     }
 }
 
+/// List deliveries that failed after all retries
+#[derive(Parser)]
+pub struct ListDeadLetters {
+    #[arg(long, default_value_t = 100)]
+    limit: u16,
+    #[arg(long, default_value_t = 0)]
+    offset: u16,
+}
+
+impl ListDeadLetters {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        let dead_letters = get_dead_letters(
+            db_client,
+            self.limit,
+            self.offset,
+        ).await?;
+        println!(
+            "{0: <36} | {1: <35} | {2: <60} | last error",
+            "ID", "created at", "inbox",
+        );
+        for dead_letter in dead_letters {
+            println!(
+                "{0: <36} | {1: <35} | {2: <60} | {3}",
+                dead_letter.id,
+                dead_letter.created_at.to_string(),
+                dead_letter.inbox,
+                dead_letter.errors.last().map(|error| error.as_str()).unwrap_or_default(),
+            );
+        };
+        Ok(())
+    }
+}
+
+/// Re-queue failed delivery
+#[derive(Parser)]
+pub struct RequeueDeadLetter {
+    id: Uuid,
+}
+
+impl RequeueDeadLetter {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &mut **get_database_client(db_pool).await?;
+        requeue_dead_letter(db_client, self.id).await?;
+        println!("delivery re-queued");
+        Ok(())
+    }
+}
+
+/// Delete failed deliveries older than N days (or all failed deliveries)
+#[derive(Parser)]
+pub struct PurgeDeadLetters {
+    days: Option<u32>,
+}
+
+impl PurgeDeadLetters {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        let created_before = self.days.map(days_before_now);
+        let deleted_count = delete_dead_letters(db_client, created_before).await?;
+        println!("deleted {deleted_count} dead letters");
+        Ok(())
+    }
+}
+
 /// ActivityPub commands
 #[derive(Subcommand)]
 pub enum ApCommand {
     Import(ImportObject),
     Fetch(FetchObject),
+    Explain(ExplainObject),
+    Replay(ReplayActivities),
     Webfinger(Webfinger),
 }
 
@@ -519,6 +702,8 @@ impl ApCommand {
         match self {
             Self::Import(command) => command.execute(config, db_pool).await,
             Self::Fetch(command) => command.execute(config, db_pool).await,
+            Self::Explain(command) => command.execute(config, db_pool).await,
+            Self::Replay(command) => command.execute(config, db_pool).await,
             Self::Webfinger(command) => command.execute(config, db_pool).await,
         }
     }
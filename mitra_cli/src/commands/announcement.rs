@@ -0,0 +1,95 @@
+use anyhow::Error;
+use chrono::{DateTime, Utc};
+use clap::Parser;
+
+use mitra_models::{
+    announcements::{
+        queries::{
+            create_announcement,
+            delete_announcement,
+            get_announcements,
+        },
+        types::AnnouncementData,
+    },
+    database::{get_database_client, DatabaseConnectionPool},
+};
+use mitra_validators::announcements::validate_announcement_data;
+
+/// Add announcement
+#[derive(Parser)]
+pub struct AddAnnouncement {
+    /// Announcement text (markdown)
+    content: String,
+    /// Publication date (RFC 3339). Defaults to current time
+    #[arg(long)]
+    published_at: Option<DateTime<Utc>>,
+    /// Expiration date (RFC 3339)
+    #[arg(long)]
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl AddAnnouncement {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        let announcement_data = AnnouncementData {
+            content: self.content,
+            published_at: self.published_at.unwrap_or(Utc::now()),
+            expires_at: self.expires_at,
+        };
+        validate_announcement_data(&announcement_data)?;
+        let announcement =
+            create_announcement(db_client, announcement_data).await?;
+        println!("announcement added (ID: {})", announcement.id);
+        Ok(())
+    }
+}
+
+/// Delete announcement
+#[derive(Parser)]
+pub struct DeleteAnnouncement {
+    /// Announcement ID
+    id: i32,
+}
+
+impl DeleteAnnouncement {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        delete_announcement(db_client, self.id).await?;
+        println!("announcement deleted");
+        Ok(())
+    }
+}
+
+/// List announcements
+#[derive(Parser)]
+pub struct ListAnnouncements;
+
+impl ListAnnouncements {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        let announcements = get_announcements(db_client).await?;
+        for announcement in announcements {
+            let status = if announcement.is_active() {
+                "active"
+            } else {
+                "inactive"
+            };
+            println!(
+                "{0: <5} {1: <8} {2}",
+                announcement.id,
+                status,
+                announcement.content.lines().next().unwrap_or_default(),
+            );
+        };
+        Ok(())
+    }
+}
@@ -13,14 +13,24 @@ use mitra_adapters::dynamic_config::{
 };
 use mitra_models::{
     database::{get_database_client, DatabaseConnectionPool},
+    posts::queries::rebuild_post_search_index,
     properties::{
         constants::{
+            APP_APPROVAL_REQUIRED,
             DEFAULT_FTS_CONFIG,
             FAVORITE_EMOJIS,
             FEDERATED_TIMELINE_RESTRICTED,
             FILTER_BLOCKLIST_PUBLIC,
             FILTER_KEYWORDS,
+            INSTANCE_CONTACT_ACCOUNT,
+            INSTANCE_DESCRIPTION,
+            INSTANCE_EXTENDED_DESCRIPTION,
+            INSTANCE_THUMBNAIL,
             LIKE_EMOJI,
+            MAINTENANCE_MODE,
+            REACTION_ALLOWLIST,
+            REACTION_POLICY,
+            ROLE_LIMITS,
         },
         queries::{
             set_internal_property,
@@ -30,7 +40,10 @@ use mitra_models::{
 
 #[derive(Clone, ValueEnum)]
 enum ParameterName {
-    /// Default text search configuration (default: "simple")
+    /// Require approval of new client applications by administrator (true or false, default: false)
+    #[clap(name = APP_APPROVAL_REQUIRED)]
+    AppApprovalRequired,
+    /// Default text search configuration (default: "simple", changing it rebuilds search index)
     #[clap(name = DEFAULT_FTS_CONFIG)]
     DefaultFtsConfig,
     /// Which emojis to show by default in the emoji picker (an array of strings)
@@ -45,20 +58,53 @@ enum ParameterName {
     /// Keywords for reject-keywords filter action (an array of strings, example: ["foo", "bar"])
     #[clap(name = FILTER_KEYWORDS)]
     FilterKeywords,
+    /// ID of the contact account (overrides default contact, null to reset)
+    #[clap(name = INSTANCE_CONTACT_ACCOUNT)]
+    InstanceContactAccount,
+    /// Short instance description (overrides instance_short_description, null to reset)
+    #[clap(name = INSTANCE_DESCRIPTION)]
+    InstanceDescription,
+    /// Extended instance description in Markdown (overrides instance_description, null to reset)
+    #[clap(name = INSTANCE_EXTENDED_DESCRIPTION)]
+    InstanceExtendedDescription,
+    /// URL of the instance thumbnail image (null to reset)
+    #[clap(name = INSTANCE_THUMBNAIL)]
+    InstanceThumbnail,
     /// Emoji that is used to represent the "like" reaction ("thumbs_up" or "heart", default: "thumbs_up")
     #[clap(name = LIKE_EMOJI)]
     LikeEmoji,
+    /// Reject write operations and incoming activities (true or false, default: false)
+    #[clap(name = MAINTENANCE_MODE)]
+    MaintenanceMode,
+    /// Emojis accepted when reaction policy is "allowlist" (an array of unicode emojis, local emoji shortcodes or emoji IDs, example: ["❤️", ":blobcat:"])
+    #[clap(name = REACTION_ALLOWLIST)]
+    ReactionAllowlist,
+    /// Which reactions are accepted ("any", "allowlist" or "likes_only", default: "any")
+    #[clap(name = REACTION_POLICY)]
+    ReactionPolicy,
+    /// Per-role limits (example: {"user": {"character_limit": 500, "attachment_limit": 2, "storage_quota": 100000000}})
+    #[clap(name = ROLE_LIMITS)]
+    RoleLimits,
 }
 
 impl ParameterName {
     fn as_str(&self) -> &'static str {
         let name_str = match self {
+            Self::AppApprovalRequired => APP_APPROVAL_REQUIRED,
             Self::DefaultFtsConfig => DEFAULT_FTS_CONFIG,
             Self::FavoriteEmojis => FAVORITE_EMOJIS,
             Self::FederatedTimelineRestricted => FEDERATED_TIMELINE_RESTRICTED,
             Self::FilterBlocklistPublic => FILTER_BLOCKLIST_PUBLIC,
             Self::FilterKeywords => FILTER_KEYWORDS,
+            Self::InstanceContactAccount => INSTANCE_CONTACT_ACCOUNT,
+            Self::InstanceDescription => INSTANCE_DESCRIPTION,
+            Self::InstanceExtendedDescription => INSTANCE_EXTENDED_DESCRIPTION,
+            Self::InstanceThumbnail => INSTANCE_THUMBNAIL,
             Self::LikeEmoji => LIKE_EMOJI,
+            Self::MaintenanceMode => MAINTENANCE_MODE,
+            Self::ReactionAllowlist => REACTION_ALLOWLIST,
+            Self::ReactionPolicy => REACTION_POLICY,
+            Self::RoleLimits => ROLE_LIMITS,
         };
         assert!(EDITABLE_PROPERTIES.contains(&name_str));
         name_str
@@ -100,7 +146,7 @@ impl UpdateConfig {
         self,
         db_pool: &DatabaseConnectionPool,
     ) -> Result<(), Error> {
-        let db_client = &**get_database_client(db_pool).await?;
+        let db_client = &mut **get_database_client(db_pool).await?;
         let value = match serde_json::from_str(&self.value) {
             Ok(value) => value,
             // `from_str` can't parse strings because they are not quoted
@@ -109,6 +155,10 @@ impl UpdateConfig {
         validate_editable_parameter(self.name.as_str(), &value)?;
         set_internal_property(db_client, self.name.as_str(), &value).await?;
         println!("configuration updated");
+        if let ParameterName::DefaultFtsConfig = self.name {
+            let indexed_count = rebuild_post_search_index(db_client).await?;
+            println!("search index rebuilt: {indexed_count} posts");
+        };
         Ok(())
     }
 }
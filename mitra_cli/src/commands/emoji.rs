@@ -19,14 +19,19 @@ use mitra_models::{
         queries::{
             create_or_update_local_emoji,
             delete_emoji,
+            find_unused_remote_emojis,
             get_emoji_by_name_and_hostname,
+            search_emojis,
         },
     },
     media::types::{MediaInfo, PartialMediaInfo},
-    profiles::types::Origin::Local,
+    profiles::types::Origin::{Local, Remote},
 };
 use mitra_services::media::MediaStorage;
-use mitra_utils::files::FileSize;
+use mitra_utils::{
+    datetime::days_before_now,
+    files::FileSize,
+};
 use mitra_validators::{
     emojis::{
         clean_emoji_name,
@@ -50,10 +55,11 @@ fn validate_local_emoji_data(
     if !EMOJI_LOCAL_MEDIA_TYPES.contains(&media_type) {
         return Err(anyhow!("media type {media_type} is not supported"));
     };
-    if file_data.len() > config.limits.media.emoji_local_size_limit {
+    let limits = config.limits();
+    if file_data.len() > limits.media.emoji_local_size_limit {
         return Err(anyhow!(
             "emoji file size must be less than {}",
-            FileSize::new(config.limits.media.emoji_local_size_limit),
+            FileSize::new(limits.media.emoji_local_size_limit),
         ));
     };
     if let Some(category) = maybe_category {
@@ -88,7 +94,7 @@ impl AddEmoji {
                 &agent,
                 &self.location,
                 &EMOJI_REMOTE_MEDIA_TYPES, // media type will be checked later
-                config.limits.media.file_size_limit, // size will be checked later
+                config.limits().media.file_size_limit, // size will be checked later
             ).await?
         } else {
             let file_data = std::fs::read(&self.location)?;
@@ -155,7 +161,7 @@ impl ImportEmoji {
                     &agent,
                     &url,
                     &EMOJI_REMOTE_MEDIA_TYPES, // media type will be checked later
-                    config.limits.media.file_size_limit, // size will be checked later
+                    config.limits().media.file_size_limit, // size will be checked later
                 ).await?
             },
         };
@@ -206,12 +212,97 @@ impl DeleteEmoji {
     }
 }
 
+/// List custom emojis and their usage statistics
+#[derive(Parser)]
+pub struct ListEmojis {
+    /// Search by name
+    #[arg(long)]
+    query: Option<String>,
+    /// Show only local emojis
+    #[arg(long, conflicts_with = "remote")]
+    local: bool,
+    /// Show only remote emojis
+    #[arg(long)]
+    remote: bool,
+    #[arg(long, default_value_t = 100)]
+    limit: u16,
+    #[arg(long, default_value_t = 0)]
+    offset: u16,
+}
+
+impl ListEmojis {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        let maybe_origin = match (self.local, self.remote) {
+            (true, _) => Some(Local),
+            (_, true) => Some(Remote),
+            _ => None,
+        };
+        let emojis = search_emojis(
+            db_client,
+            self.query.as_deref(),
+            maybe_origin,
+            self.limit,
+            self.offset,
+        ).await?;
+        println!(
+            "{0: <40} | {1: <35} | {2: <10} | {3: <10} | {4: <10}",
+            "name", "hostname", "posts", "reactions", "profiles",
+        );
+        for (emoji, usage) in emojis {
+            println!(
+                "{0: <40} | {1: <35} | {2: <10} | {3: <10} | {4: <10}",
+                emoji.emoji_name,
+                emoji.hostname().unwrap_or_default(),
+                usage.posts,
+                usage.reactions,
+                usage.profiles,
+            );
+        };
+        Ok(())
+    }
+}
+
+/// Delete remote emojis that are not used and haven't been updated for N days
+#[derive(Parser)]
+pub struct PruneEmojis {
+    days: u32,
+}
+
+impl PruneEmojis {
+    pub async fn execute(
+        self,
+        config: &Config,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        let updated_before = days_before_now(self.days);
+        let emojis = find_unused_remote_emojis(
+            db_client,
+            Some(updated_before),
+        ).await?;
+        let mut deleted_count = 0;
+        for emoji_id in emojis {
+            let deletion_queue = delete_emoji(db_client, emoji_id).await?;
+            delete_orphaned_media(config, db_client, deletion_queue).await?;
+            deleted_count += 1;
+        };
+        println!("deleted {deleted_count} unused emojis");
+        Ok(())
+    }
+}
+
 /// Manage custom emojis
 #[derive(Subcommand)]
 pub enum EmojiCommand {
     Create(AddEmoji),
     Copy(ImportEmoji),
     Delete(DeleteEmoji),
+    List(ListEmojis),
+    Prune(PruneEmojis),
 }
 
 impl EmojiCommand {
@@ -224,6 +315,8 @@ impl EmojiCommand {
             Self::Create(command) => command.execute(config, db_pool).await,
             Self::Copy(command) => command.execute(config, db_pool).await,
             Self::Delete(command) => command.execute(config, db_pool).await,
+            Self::List(command) => command.execute(db_pool).await,
+            Self::Prune(command) => command.execute(config, db_pool).await,
         }
     }
 }
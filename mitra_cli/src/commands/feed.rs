@@ -0,0 +1,87 @@
+use anyhow::{anyhow, Error};
+use clap::Parser;
+use uuid::Uuid;
+
+use mitra_config::Config;
+use mitra_models::{
+    database::{get_database_client, DatabaseConnectionPool},
+    feed_sources::queries::{
+        get_feed_source_by_id,
+        get_feed_sources,
+        update_feed_source,
+    },
+};
+
+/// List followed RSS, Atom and JSON feeds
+#[derive(Parser)]
+pub struct ListFeeds;
+
+impl ListFeeds {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        let sources = get_feed_sources(db_client).await?;
+        for source in sources {
+            let fetched_at = source.source.fetched_at
+                .map(|fetched_at| fetched_at.to_rfc3339())
+                .unwrap_or("never".to_owned());
+            println!(
+                "{0} {1: <20} {2: <6} {3: <4} {4: <25} {5}",
+                source.account.id,
+                source.account.profile.username,
+                source.source.fetch_interval,
+                source.source.entry_limit,
+                fetched_at,
+                source.source.feed_url,
+            );
+        };
+        Ok(())
+    }
+}
+
+/// Change fetch interval and entry limit of a feed
+#[derive(Parser)]
+pub struct UpdateFeed {
+    /// Feed actor ID
+    id: Uuid,
+    /// Interval between fetches (in seconds)
+    #[arg(long)]
+    fetch_interval: Option<u32>,
+    /// Maximum number of entries converted into posts per fetch
+    #[arg(long)]
+    entry_limit: Option<u16>,
+}
+
+impl UpdateFeed {
+    pub async fn execute(
+        self,
+        config: &Config,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        let source = get_feed_source_by_id(db_client, self.id).await?.source;
+        let fetch_interval = self.fetch_interval
+            .unwrap_or(source.fetch_interval.try_into()?);
+        if fetch_interval < config.feeds.min_fetch_interval {
+            return Err(anyhow!(
+                "fetch interval must be at least {} seconds",
+                config.feeds.min_fetch_interval,
+            ));
+        };
+        let entry_limit = self.entry_limit
+            .unwrap_or(source.entry_limit.try_into()?);
+        if entry_limit == 0 {
+            return Err(anyhow!("entry limit must be greater than zero"));
+        };
+        update_feed_source(
+            db_client,
+            source.id,
+            fetch_interval,
+            entry_limit,
+        ).await?;
+        println!("feed updated");
+        Ok(())
+    }
+}
@@ -52,6 +52,10 @@ enum FilterAction {
     ProxyMedia,
     /// Cache all media
     CacheMedia,
+    /// Limit the number of media attachments
+    LimitMediaAttachments,
+    /// Make public posts followers-only
+    ReduceVisibility,
 }
 
 impl FilterAction {
@@ -83,6 +87,10 @@ impl FilterAction {
                 (DbFilterAction::ProxyMedia, false),
             Self::CacheMedia =>
                 (DbFilterAction::ProxyMedia, true),
+            Self::LimitMediaAttachments =>
+                (DbFilterAction::LimitMediaAttachments, false),
+            Self::ReduceVisibility =>
+                (DbFilterAction::ReduceVisibility, false),
         }
     }
 
@@ -106,6 +114,8 @@ impl FilterAction {
             (DbFilterAction::RejectKeywords, true) => Self::AcceptKeywords,
             (DbFilterAction::ProxyMedia, false) => Self::ProxyMedia,
             (DbFilterAction::ProxyMedia, true) => Self::CacheMedia,
+            (DbFilterAction::LimitMediaAttachments, _) => Self::LimitMediaAttachments,
+            (DbFilterAction::ReduceVisibility, _) => Self::ReduceVisibility,
         }
     }
 }
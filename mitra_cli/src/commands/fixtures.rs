@@ -0,0 +1,410 @@
+//! Generation of development fixtures
+use anyhow::{anyhow, Error};
+use apx_sdk::{
+    constants::AP_PUBLIC,
+    core::crypto::{
+        eddsa::generate_ed25519_key,
+        rsa::{generate_rsa_key, rsa_secret_key_to_pkcs8_pem},
+    },
+};
+use chrono::{DateTime, Duration, Utc};
+use clap::Parser;
+use rand::{
+    rngs::StdRng,
+    seq::{index::sample, IndexedRandom},
+    Rng,
+    SeedableRng,
+};
+
+use mitra_activitypub::{
+    adapters::users::create_or_update_local_actor,
+    vocabulary::PERSON,
+};
+use mitra_config::Config;
+use mitra_models::{
+    accounts::{
+        queries::{
+            create_user,
+            get_user_by_name,
+            get_user_count_without_prefix,
+        },
+        types::{Role, UserCreateData},
+    },
+    database::{
+        get_database_client,
+        DatabaseClient,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    posts::{
+        queries::create_post,
+        types::{PostContext, PostCreateData, Visibility},
+    },
+    profiles::{
+        queries::{create_profile, get_remote_profile_by_actor_id},
+        types::{
+            ActorType,
+            DbActor,
+            DbActorProfile,
+            MentionPolicy,
+            Origin,
+            ProfileCreateData,
+            WebfingerHostname,
+        },
+    },
+    reactions::{
+        queries::create_reaction,
+        types::ReactionData,
+    },
+    relationships::{
+        helpers::create_follow_request,
+        queries::follow_request_accepted,
+    },
+};
+use mitra_utils::{
+    id::generate_deterministic_ulid,
+    passwords::hash_password,
+};
+use mitra_validators::{
+    accounts::validate_local_username,
+    posts::validate_post_create_data,
+};
+
+const WORDS: [&str; 32] = [
+    "lorem", "ipsum", "dolor", "sit", "amet", "consectetur", "adipiscing",
+    "elit", "sed", "do", "eiusmod", "tempor", "incididunt", "ut", "labore",
+    "et", "dolore", "magna", "aliqua", "enim", "ad", "minim", "veniam",
+    "quis", "nostrud", "exercitation", "ullamco", "laboris", "nisi",
+    "aliquip", "ex", "commodo",
+];
+
+const REACTIONS: [&str; 4] = ["❤️", "👍", "😂", "🎉"];
+
+// Timestamps are derived from fixed date to make output reproducible
+// (2025-01-01T00:00:00Z)
+const FIXTURES_EPOCH: i64 = 1_735_689_600;
+
+fn generate_content(rng: &mut StdRng) -> String {
+    let word_count = rng.random_range(3..=40);
+    let text = (0..word_count)
+        .map(|_| *WORDS.choose(rng).expect("list should not be empty"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!("<p>{text}</p>")
+}
+
+async fn get_or_create_remote_profile(
+    db_client: &mut impl DatabaseClient,
+    username: &str,
+    hostname: &str,
+) -> Result<DbActorProfile, DatabaseError> {
+    let actor_id = format!("https://{hostname}/users/{username}");
+    match get_remote_profile_by_actor_id(db_client, &actor_id).await {
+        Ok(profile) => return Ok(profile),
+        Err(DatabaseError::NotFound(_)) => (),
+        Err(other_error) => return Err(other_error),
+    };
+    let db_actor = DbActor {
+        object_type: PERSON.to_owned(),
+        id: actor_id.clone(),
+        inbox: format!("{actor_id}/inbox"),
+        shared_inbox: Some(format!("https://{hostname}/inbox")),
+        outbox: format!("{actor_id}/outbox"),
+        followers: Some(format!("{actor_id}/followers")),
+        subscribers: None,
+        featured: None,
+        url: Some(actor_id.clone()),
+        gateways: vec![],
+        #[expect(deprecated)]
+        public_key: None,
+    };
+    let profile_data = ProfileCreateData {
+        actor_type: ActorType::Person,
+        username: username.to_owned(),
+        hostname: Some(hostname.to_owned()),
+        webfinger_hostname: WebfingerHostname::Remote(hostname.to_owned()),
+        display_name: None,
+        bio: None,
+        bio_source: None,
+        avatar: None,
+        banner: None,
+        manually_approves_followers: false,
+        mention_policy: MentionPolicy::None,
+        public_keys: vec![],
+        identity_proofs: vec![],
+        payment_options: vec![],
+        extra_fields: vec![],
+        location: None,
+        birthday: None,
+        pronouns: None,
+        aliases: vec![],
+        emojis: vec![],
+        actor_json: Some(db_actor),
+    };
+    create_profile(db_client, profile_data).await
+}
+
+/// Populate database with generated accounts, follows and posts (for development)
+#[derive(Parser)]
+pub struct GenerateFixtures {
+    /// Number of local accounts
+    #[arg(long, default_value_t = 10)]
+    accounts: usize,
+    /// Number of remote profiles
+    #[arg(long, default_value_t = 10)]
+    remote_profiles: usize,
+    /// Number of remote instances
+    #[arg(long, default_value_t = 3)]
+    remote_instances: usize,
+    /// Number of follows per local account
+    #[arg(long, default_value_t = 5)]
+    follows: usize,
+    /// Number of posts per profile
+    #[arg(long, default_value_t = 20)]
+    posts: usize,
+    /// Number of reposts per local account
+    #[arg(long, default_value_t = 5)]
+    reposts: usize,
+    /// Number of reactions per local account
+    #[arg(long, default_value_t = 10)]
+    reactions: usize,
+    /// Posts are distributed over this number of days before 2025-01-01
+    #[arg(long, default_value_t = 30)]
+    days: u32,
+    /// Username prefix
+    #[arg(long, default_value = "fixture")]
+    prefix: String,
+    /// Password for generated accounts (login is disabled if not specified)
+    #[arg(long)]
+    password: Option<String>,
+    /// Random number generator seed
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Run even if database contains accounts without the username prefix
+    #[arg(long)]
+    force: bool,
+}
+
+impl GenerateFixtures {
+    pub async fn execute(
+        self,
+        config: &Config,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        if self.remote_profiles > 0 && self.remote_instances == 0 {
+            return Err(anyhow!("number of remote instances must be greater than zero"));
+        };
+        if self.days == 0 {
+            return Err(anyhow!("number of days must be greater than zero"));
+        };
+        let db_client = &mut **get_database_client(db_pool).await?;
+        let other_user_count =
+            get_user_count_without_prefix(db_client, &self.prefix).await?;
+        if other_user_count > 0 && !self.force {
+            return Err(anyhow!(
+                "database contains {other_user_count} accounts without '{}' prefix (use --force to proceed)",
+                self.prefix,
+            ));
+        };
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let epoch = DateTime::<Utc>::from_timestamp(FIXTURES_EPOCH, 0)
+            .expect("timestamp should be valid");
+        let time_range = Duration::days(self.days.into()).num_seconds();
+
+        // Key generation is slow, all accounts share the same keys
+        let rsa_secret_key = generate_rsa_key()?;
+        let rsa_secret_key_pem =
+            rsa_secret_key_to_pkcs8_pem(&rsa_secret_key)?;
+        let ed25519_secret_key = generate_ed25519_key();
+        let password_digest = self.password.as_deref()
+            .map(hash_password)
+            .transpose()?;
+
+        let mut local_profiles = vec![];
+        for index in 0..self.accounts {
+            let username = format!("{}{index}", self.prefix);
+            validate_local_username(&username)?;
+            let user = match get_user_by_name(db_client, &username).await {
+                Ok(user) => user,
+                Err(DatabaseError::NotFound(_)) => {
+                    let user_data = UserCreateData {
+                        username: username,
+                        password_digest: password_digest.clone(),
+                        login_address_ethereum: None,
+                        login_address_monero: None,
+                        rsa_secret_key: rsa_secret_key_pem.clone(),
+                        ed25519_secret_key: ed25519_secret_key,
+                        invite_code: None,
+                        role: Role::NormalUser,
+                        rules_accepted_at: None,
+                    };
+                    let user = create_user(db_client, user_data).await?;
+                    create_or_update_local_actor(config, db_client, &user).await?;
+                    user
+                },
+                Err(other_error) => return Err(other_error.into()),
+            };
+            local_profiles.push(user.profile);
+        };
+        println!("local accounts: {}", local_profiles.len());
+
+        let mut remote_profiles = vec![];
+        for index in 0..self.remote_profiles {
+            let username = format!("{}{index}", self.prefix);
+            let instance_index = rng.random_range(0..self.remote_instances);
+            let hostname = format!("instance{instance_index}.fixtures.example");
+            let profile = get_or_create_remote_profile(
+                db_client,
+                &username,
+                &hostname,
+            ).await?;
+            remote_profiles.push(profile);
+        };
+        println!("remote profiles: {}", remote_profiles.len());
+
+        let all_profiles: Vec<_> = local_profiles.iter()
+            .chain(remote_profiles.iter())
+            .collect();
+        let mut follow_count = 0;
+        for profile in local_profiles.iter() {
+            let candidates: Vec<_> = all_profiles.iter()
+                .filter(|target| target.id != profile.id)
+                .collect();
+            let amount = self.follows.min(candidates.len());
+            for target_index in sample(&mut rng, candidates.len(), amount) {
+                let target = candidates[target_index];
+                let follow_request = match create_follow_request(
+                    db_client,
+                    profile.id,
+                    target.id,
+                ).await {
+                    Ok(follow_request) => follow_request,
+                    Err(DatabaseError::AlreadyExists(_)) => continue,
+                    Err(other_error) => return Err(other_error.into()),
+                };
+                match follow_request_accepted(db_client, follow_request.id).await {
+                    Ok(_) => follow_count += 1,
+                    Err(DatabaseError::AlreadyExists(_)) => continue,
+                    Err(other_error) => return Err(other_error.into()),
+                };
+            };
+        };
+        println!("follows created: {follow_count}");
+
+        // Post ID, author ID and creation date
+        let mut posts = vec![];
+        let mut post_count = 0;
+        for profile in all_profiles.iter() {
+            let origin = if profile.is_local() {
+                Origin::Local
+            } else {
+                Origin::Remote
+            };
+            for index in 0..self.posts {
+                let content = generate_content(&mut rng);
+                let created_at = epoch
+                    - Duration::seconds(rng.random_range(0..time_range));
+                let post_id = generate_deterministic_ulid(
+                    &format!("{}{}{}", profile.id, index, content),
+                    created_at,
+                );
+                let object_id = if profile.is_local() {
+                    None
+                } else {
+                    let actor_id = profile.expect_remote_actor_id();
+                    Some(format!("{actor_id}/posts/{index}"))
+                };
+                let post_data = PostCreateData {
+                    id: Some(post_id),
+                    context: PostContext::Top {
+                        group_id: None,
+                        object_id: None,
+                        audience: Some(AP_PUBLIC.to_owned()),
+                    },
+                    title: None,
+                    content: content,
+                    content_source: None,
+                    language: None,
+                    content_map: vec![],
+                    visibility: Visibility::Public,
+                    is_sensitive: false,
+                    poll: None,
+                    attachments: vec![],
+                    mentions: vec![],
+                    tags: vec![],
+                    links: vec![],
+                    emojis: vec![],
+                    url: None,
+                    object_id: object_id,
+                    created_at: created_at,
+                };
+                validate_post_create_data(&post_data, origin)?;
+                match create_post(db_client, profile.id, post_data).await {
+                    Ok(_) => post_count += 1,
+                    // Created during previous run
+                    Err(DatabaseError::AlreadyExists(_)) => (),
+                    Err(other_error) => return Err(other_error.into()),
+                };
+                // Existing posts are also used for reposts and reactions
+                posts.push((post_id, profile.id, created_at));
+            };
+        };
+        println!("posts created: {post_count}");
+
+        let mut repost_count = 0;
+        let mut reaction_count = 0;
+        for profile in local_profiles.iter() {
+            let candidates: Vec<_> = posts.iter()
+                .filter(|(_, author_id, _)| *author_id != profile.id)
+                .collect();
+            let amount = self.reposts.min(candidates.len());
+            for post_index in sample(&mut rng, candidates.len(), amount) {
+                let (post_id, _, post_created_at) = candidates[post_index];
+                let max_delay = (epoch - *post_created_at).num_seconds();
+                let created_at = *post_created_at
+                    + Duration::seconds(rng.random_range(0..=max_delay));
+                let mut repost_data = PostCreateData::repost(
+                    *post_id,
+                    Visibility::Public,
+                    None,
+                );
+                repost_data.id = Some(generate_deterministic_ulid(
+                    &format!("{}{}", profile.id, post_id),
+                    created_at,
+                ));
+                repost_data.created_at = created_at;
+                match create_post(db_client, profile.id, repost_data).await {
+                    Ok(_) => repost_count += 1,
+                    Err(DatabaseError::AlreadyExists(_)) => continue,
+                    Err(other_error) => return Err(other_error.into()),
+                };
+            };
+            let amount = self.reactions.min(candidates.len());
+            for post_index in sample(&mut rng, candidates.len(), amount) {
+                let (post_id, _, _) = candidates[post_index];
+                // Most reactions are likes
+                let content = if rng.random_bool(0.25) {
+                    REACTIONS.choose(&mut rng).map(|value| value.to_string())
+                } else {
+                    None
+                };
+                let reaction_data = ReactionData {
+                    author_id: profile.id,
+                    post_id: *post_id,
+                    content: content,
+                    emoji_id: None,
+                    visibility: Visibility::Public,
+                    activity_id: None,
+                };
+                match create_reaction(db_client, reaction_data).await {
+                    Ok(_) => reaction_count += 1,
+                    Err(DatabaseError::AlreadyExists(_)) => continue,
+                    Err(other_error) => return Err(other_error.into()),
+                };
+            };
+        };
+        println!("reposts created: {repost_count}");
+        println!("reactions created: {reaction_count}");
+        Ok(())
+    }
+}
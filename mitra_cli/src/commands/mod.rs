@@ -1,12 +1,18 @@
 pub mod account;
 pub mod activitypub;
+pub mod announcement;
 pub mod config;
 pub mod emoji;
+pub mod feed;
 pub mod filter;
+pub mod fixtures;
 pub mod invoice;
 pub mod monero;
+pub mod pleroma;
 pub mod post;
 pub mod profile;
 pub mod process;
 pub mod report;
+pub mod server_rule;
 pub mod storage;
+pub mod subscription;
@@ -0,0 +1,947 @@
+//! Import from Pleroma and Akkoma databases
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Error};
+use apx_sdk::{
+    constants::AP_PUBLIC,
+    core::{
+        base64,
+        crypto::{
+            eddsa::generate_ed25519_key,
+            rsa::{
+                rsa_secret_key_from_pkcs1_pem,
+                rsa_secret_key_from_pkcs8_pem,
+                rsa_secret_key_to_pkcs8_pem,
+            },
+        },
+        media_type::sniff_media_type,
+    },
+    utils::is_public,
+};
+use chrono::{DateTime, Utc};
+use clap::Parser;
+use serde_json::{Value as JsonValue};
+use uuid::Uuid;
+
+use mitra_activitypub::{
+    adapters::users::create_or_update_local_actor,
+    authority::Authority,
+    identifiers::{canonicalize_id, local_actor_id, LocalActorCollection},
+    importers::{
+        get_post_by_object_id,
+        get_profile_by_actor_id,
+        ActorIdResolver,
+        ApClient,
+    },
+    vocabulary::{HASHTAG, MENTION, NOTE},
+};
+use mitra_adapters::{
+    dynamic_config::get_dynamic_config,
+    media::{delete_orphaned_media, get_storage_quota, StorageQuota},
+    roles::RoleLimitsMap,
+};
+use mitra_config::Config;
+use mitra_models::{
+    accounts::{
+        queries::{create_user, get_user_by_name},
+        types::{Role, User, UserCreateData},
+    },
+    attachments::queries::create_attachment,
+    database::{
+        connect::create_database_client,
+        get_database_client,
+        BasicDatabaseClient,
+        DatabaseClient,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    media::types::{DeletionQueue, MediaInfo},
+    posts::{
+        queries::{create_post, get_post_by_id},
+        types::{PostContext, PostCreateData, Visibility},
+    },
+    profiles::{
+        queries::{get_remote_profile_by_actor_id, update_profile},
+        types::{DbActorProfile, Origin::Local, ProfileUpdateData},
+    },
+    relationships::{
+        helpers::create_follow_request,
+        queries::{
+            create_remote_follow_request_opt,
+            follow_request_accepted,
+        },
+    },
+};
+use mitra_services::{
+    media::MediaStorage,
+    media_metadata::get_media_metadata,
+};
+use mitra_utils::{
+    passwords::hash_password,
+    random::generate_random_sequence,
+};
+use mitra_validators::{
+    accounts::validate_local_username,
+    posts::{clean_remote_content, validate_post_create_data},
+    profiles::clean_profile_update_data,
+    tags::validate_hashtag,
+};
+
+struct PleromaUser {
+    nickname: String,
+    ap_id: String,
+    keys: Option<String>,
+    is_admin: bool,
+    name: Option<String>,
+    bio: Option<String>,
+}
+
+async fn get_local_users(
+    pleroma_client: &BasicDatabaseClient,
+) -> Result<Vec<PleromaUser>, Error> {
+    // Service actors (relay, internal fetch actor) are not imported
+    let rows = pleroma_client.query(
+        "
+        SELECT nickname, ap_id, keys, is_admin, name, bio
+        FROM users
+        WHERE
+            local IS TRUE
+            AND is_active IS TRUE
+            AND actor_type = 'Person'
+            AND nickname IS NOT NULL
+        ORDER BY inserted_at
+        ",
+        &[],
+    ).await?;
+    let mut users = vec![];
+    for row in rows {
+        let user = PleromaUser {
+            nickname: row.try_get("nickname")?,
+            ap_id: row.try_get("ap_id")?,
+            keys: row.try_get("keys")?,
+            is_admin: row.try_get("is_admin")?,
+            name: row.try_get("name")?,
+            bio: row.try_get("bio")?,
+        };
+        users.push(user);
+    };
+    Ok(users)
+}
+
+struct PleromaFollow {
+    follower_ap_id: String,
+    follower_is_local: bool,
+    following_ap_id: String,
+    following_is_local: bool,
+}
+
+async fn get_follows(
+    pleroma_client: &BasicDatabaseClient,
+) -> Result<Vec<PleromaFollow>, Error> {
+    // Accepted relationships only.
+    // The state is stored as integer in recent versions (2 = accepted).
+    let rows = pleroma_client.query(
+        "
+        SELECT
+            follower.ap_id AS follower_ap_id,
+            follower.local AS follower_is_local,
+            following.ap_id AS following_ap_id,
+            following.local AS following_is_local
+        FROM following_relationships
+        JOIN users AS follower
+            ON follower.id = following_relationships.follower_id
+        JOIN users AS following
+            ON following.id = following_relationships.following_id
+        WHERE
+            following_relationships.state::text IN ('2', 'follow_accept')
+            AND (follower.local IS TRUE OR following.local IS TRUE)
+        ",
+        &[],
+    ).await?;
+    let mut follows = vec![];
+    for row in rows {
+        let follow = PleromaFollow {
+            follower_ap_id: row.try_get("follower_ap_id")?,
+            follower_is_local: row.try_get("follower_is_local")?,
+            following_ap_id: row.try_get("following_ap_id")?,
+            following_is_local: row.try_get("following_is_local")?,
+        };
+        follows.push(follow);
+    };
+    Ok(follows)
+}
+
+async fn get_follow_activity_id(
+    pleroma_client: &BasicDatabaseClient,
+    follower_ap_id: &str,
+    following_ap_id: &str,
+) -> Result<Option<String>, Error> {
+    let maybe_row = pleroma_client.query_opt(
+        "
+        SELECT data ->> 'id' AS activity_id
+        FROM activities
+        WHERE
+            data ->> 'type' = 'Follow'
+            AND actor = $1
+            AND data ->> 'object' = $2
+        ORDER BY inserted_at DESC
+        LIMIT 1
+        ",
+        &[&follower_ap_id, &following_ap_id],
+    ).await?;
+    let maybe_activity_id = match maybe_row {
+        Some(row) => row.try_get("activity_id")?,
+        None => None,
+    };
+    Ok(maybe_activity_id)
+}
+
+struct PleromaNote {
+    id: i64,
+    data: String,
+}
+
+/// Returns a page of notes created by the given actors
+async fn get_notes(
+    pleroma_client: &BasicDatabaseClient,
+    actor_ids: &[String],
+    after_id: i64,
+    limit: i64,
+) -> Result<Vec<PleromaNote>, Error> {
+    // Oldest first, so that ancestors are imported before replies
+    let rows = pleroma_client.query(
+        "
+        SELECT id, data::text AS data
+        FROM objects
+        WHERE
+            data ->> 'type' = $1
+            AND coalesce(data ->> 'attributedTo', data ->> 'actor') = ANY($2)
+            AND id > $3
+        ORDER BY id
+        LIMIT $4
+        ",
+        &[&NOTE, &actor_ids, &after_id, &limit],
+    ).await?;
+    let mut notes = vec![];
+    for row in rows {
+        let note = PleromaNote {
+            id: row.try_get("id")?,
+            data: row.try_get("data")?,
+        };
+        notes.push(note);
+    };
+    Ok(notes)
+}
+
+fn parse_rsa_secret_key(secret_key_pem: &str) -> Result<String, Error> {
+    let secret_key = if secret_key_pem.contains("BEGIN RSA PRIVATE KEY") {
+        rsa_secret_key_from_pkcs1_pem(secret_key_pem)?
+    } else {
+        rsa_secret_key_from_pkcs8_pem(secret_key_pem.trim())?
+    };
+    let secret_key_pem = rsa_secret_key_to_pkcs8_pem(&secret_key)?;
+    Ok(secret_key_pem)
+}
+
+/// Returns true if account was created during previous import
+fn is_imported_user(
+    user: &User,
+    pleroma_user: &PleromaUser,
+) -> Result<bool, Error> {
+    let Some(secret_key_pem) = pleroma_user.keys.as_deref() else {
+        return Ok(false);
+    };
+    let Ok(rsa_secret_key_pem) = parse_rsa_secret_key(secret_key_pem) else {
+        return Ok(false);
+    };
+    let user_secret_key_pem = rsa_secret_key_to_pkcs8_pem(&user.rsa_secret_key)?;
+    Ok(user_secret_key_pem == rsa_secret_key_pem)
+}
+
+async fn import_user(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    pleroma_user: &PleromaUser,
+) -> Result<User, Error> {
+    validate_local_username(&pleroma_user.nickname)?;
+    let secret_key_pem = pleroma_user.keys.as_deref()
+        .ok_or(anyhow!("RSA key not found"))?;
+    let rsa_secret_key_pem = parse_rsa_secret_key(secret_key_pem)?;
+    // Password hashes are not compatible, users will need to reset passwords
+    let password: [u8; 32] = generate_random_sequence();
+    let password_digest = hash_password(&base64::encode(password))?;
+    let role = if pleroma_user.is_admin {
+        Role::Admin
+    } else {
+        Role::NormalUser
+    };
+    let user_data = UserCreateData {
+        username: pleroma_user.nickname.clone(),
+        password_digest: Some(password_digest),
+        login_address_ethereum: None,
+        login_address_monero: None,
+        rsa_secret_key: rsa_secret_key_pem,
+        ed25519_secret_key: generate_ed25519_key(),
+        invite_code: None,
+        role,
+        rules_accepted_at: None,
+    };
+    let mut user = create_user(db_client, user_data).await?;
+    let mut profile_data = ProfileUpdateData::from(&user.profile);
+    profile_data.display_name = pleroma_user.name.clone()
+        .filter(|name| !name.is_empty());
+    profile_data.bio = pleroma_user.bio.clone()
+        .filter(|bio| !bio.is_empty());
+    profile_data.bio_source = None;
+    match clean_profile_update_data(&mut profile_data) {
+        Ok(_) => {
+            let (profile, _) =
+                update_profile(db_client, user.id, profile_data).await?;
+            user.profile = profile;
+        },
+        Err(error) => {
+            log::warn!("profile of {} not imported: {error}", user.profile.username);
+        },
+    };
+    create_or_update_local_actor(config, db_client, &user).await?;
+    Ok(user)
+}
+
+async fn resolve_actor(
+    ap_client: &ApClient,
+    db_pool: &DatabaseConnectionPool,
+    actor_id: &str,
+    offline: bool,
+) -> Result<DbActorProfile, Error> {
+    let profile = if offline {
+        let db_client = &**get_database_client(db_pool).await?;
+        let canonical_actor_id = canonicalize_id(actor_id)?;
+        get_remote_profile_by_actor_id(
+            db_client,
+            &canonical_actor_id.to_string(),
+        ).await?
+    } else {
+        ActorIdResolver::default()
+            .only_remote()
+            .resolve(ap_client, db_pool, actor_id)
+            .await?
+    };
+    Ok(profile)
+}
+
+fn get_audience(note: &JsonValue) -> Vec<String> {
+    ["to", "cc"].iter()
+        .flat_map(|key| match &note[key] {
+            JsonValue::String(target) => vec![target.clone()],
+            JsonValue::Array(targets) => targets.iter()
+                .filter_map(|target| target.as_str())
+                .map(|target| target.to_owned())
+                .collect(),
+            _ => vec![],
+        })
+        .collect()
+}
+
+fn get_tags(note: &JsonValue, tag_type: &str) -> Vec<JsonValue> {
+    match &note["tag"] {
+        JsonValue::Array(tags) => tags.iter()
+            .filter(|tag| tag["type"].as_str() == Some(tag_type))
+            .cloned()
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn get_visibility(audience: &[String], followers: &str) -> Visibility {
+    if audience.iter().any(is_public) {
+        Visibility::Public
+    } else if audience.iter().any(|target| target == followers) {
+        Visibility::Followers
+    } else {
+        Visibility::Direct
+    }
+}
+
+fn get_hashtags(note: &JsonValue) -> Vec<String> {
+    get_tags(note, HASHTAG).iter()
+        .filter_map(|tag| tag["name"].as_str())
+        .map(|name| name.trim_start_matches('#'))
+        .filter(|name| validate_hashtag(name).is_ok())
+        .map(|name| name.to_lowercase())
+        .collect()
+}
+
+fn get_media_paths(
+    note: &JsonValue,
+    uploads_dir: &Path,
+) -> Vec<(PathBuf, Option<String>)> {
+    let attachments = match &note["attachment"] {
+        JsonValue::Array(attachments) => attachments.clone(),
+        _ => vec![],
+    };
+    attachments.iter()
+        .filter_map(|attachment| {
+            let url = match &attachment["url"] {
+                JsonValue::String(url) => url.as_str(),
+                JsonValue::Array(links) => links.first()?["href"].as_str()?,
+                _ => return None,
+            };
+            // Uploads are served from /media/ path
+            let (_, relative_path) = url.split_once("/media/")?;
+            let description = attachment["name"].as_str()
+                .filter(|name| !name.is_empty())
+                .map(|name| name.to_owned());
+            Some((uploads_dir.join(relative_path), description))
+        })
+        .collect()
+}
+
+async fn get_in_reply_to_id(
+    db_client: &impl DatabaseClient,
+    authority: &Authority,
+    imported: &HashMap<String, Uuid>,
+    in_reply_to_object_id: &str,
+) -> Result<Option<Uuid>, Error> {
+    if let Some(in_reply_to_id) = imported.get(in_reply_to_object_id) {
+        return Ok(Some(*in_reply_to_id));
+    };
+    let Ok(canonical_object_id) = canonicalize_id(in_reply_to_object_id) else {
+        return Ok(None);
+    };
+    match get_post_by_object_id(
+        db_client,
+        authority,
+        &canonical_object_id,
+    ).await {
+        Ok(post) => Ok(Some(post.id)),
+        Err(DatabaseError::NotFound(_)) => Ok(None),
+        Err(other_error) => Err(other_error.into()),
+    }
+}
+
+async fn import_media(
+    config: &Config,
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+    file_path: &Path,
+    description: Option<&str>,
+    maybe_storage_quota: Option<&mut StorageQuota>,
+    copied_files: &mut Vec<String>,
+) -> Result<Uuid, Error> {
+    let file_data = std::fs::read(file_path)?;
+    let media_type = sniff_media_type(&file_data)
+        .ok_or(anyhow!("unknown media type"))?;
+    let limits = config.limits();
+    if !limits.media.supported_media_types().contains(&media_type.as_str()) {
+        return Err(anyhow!("media type {media_type} is not supported"));
+    };
+    let file_size = file_data.len();
+    if let Some(ref storage_quota) = maybe_storage_quota {
+        if !storage_quota.can_store(file_size) {
+            return Err(anyhow!("storage quota exceeded"));
+        };
+    };
+    let storage = MediaStorage::new(config);
+    let file_info = storage.save_file(file_data, &media_type)?;
+    copied_files.push(file_info.file_name.clone());
+    let metadata = get_media_metadata(
+        &limits.media,
+        &storage,
+        &file_info.file_name,
+        &file_info.media_type,
+    ).await;
+    let attachment = create_attachment(
+        db_client,
+        owner_id,
+        MediaInfo::local(file_info),
+        description,
+        false, // not sensitive
+        metadata,
+    ).await?;
+    if let Some(storage_quota) = maybe_storage_quota {
+        storage_quota.add_usage(file_size);
+    };
+    Ok(attachment.id)
+}
+
+const NOTE_PAGE_SIZE: i64 = 500;
+
+enum NoteImportResult {
+    Created(Uuid),
+    AlreadyExists(Uuid),
+    Skipped(String),
+}
+
+struct NoteImporter<'a> {
+    config: &'a Config,
+    authority: Authority,
+    uploads_dir: Option<&'a Path>,
+    role_limits: RoleLimitsMap,
+    // Actor ID -> user
+    users: &'a HashMap<String, User>,
+    // User ID -> storage quota
+    storage_quotas: HashMap<Uuid, Option<StorageQuota>>,
+    // Object ID -> post ID
+    imported: HashMap<String, Uuid>,
+}
+
+impl NoteImporter<'_> {
+    /// Imports a single note. Database changes are reverted
+    /// and copied files are removed if note can not be imported.
+    async fn import_note(
+        &mut self,
+        db_client: &mut impl DatabaseClient,
+        note: &JsonValue,
+    ) -> Result<NoteImportResult, Error> {
+        let mut copied_files = vec![];
+        let mut transaction = db_client.transaction().await?;
+        let result = self.create_post_from_note(
+            &mut transaction,
+            note,
+            &mut copied_files,
+        ).await;
+        if let Ok(NoteImportResult::Created(_)) = result {
+            transaction.commit().await?;
+            return result;
+        };
+        transaction.rollback().await?;
+        if !copied_files.is_empty() {
+            // Files that are used by other attachments are not deleted
+            let deletion_queue = DeletionQueue {
+                files: copied_files,
+                ipfs_objects: vec![],
+            };
+            delete_orphaned_media(self.config, db_client, deletion_queue).await?;
+            // Storage usage will be re-calculated
+            let maybe_author = note["attributedTo"].as_str()
+                .or(note["actor"].as_str())
+                .and_then(|actor_id| self.users.get(actor_id));
+            if let Some(author) = maybe_author {
+                self.storage_quotas.remove(&author.id);
+            };
+        };
+        result
+    }
+
+    async fn create_post_from_note(
+        &mut self,
+        db_client: &mut impl DatabaseClient,
+        note: &JsonValue,
+        copied_files: &mut Vec<String>,
+    ) -> Result<NoteImportResult, Error> {
+        let object_id = note["id"].as_str()
+            .ok_or(anyhow!("'id' not found"))?;
+        let actor_id = note["attributedTo"].as_str()
+            .or(note["actor"].as_str())
+            .ok_or(anyhow!("'attributedTo' not found"))?;
+        let user = self.users.get(actor_id)
+            .ok_or(anyhow!("unexpected author: {actor_id}"))?;
+        let followers = LocalActorCollection::Followers.of(actor_id);
+        // Object IDs have the form /objects/<uuid>
+        let post_id = object_id.rsplit('/').next()
+            .and_then(|value| Uuid::parse_str(value).ok())
+            .ok_or(anyhow!("unexpected object ID: {object_id}"))?;
+        let content = note["content"].as_str().unwrap_or_default();
+        let created_at = note["published"].as_str()
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .map(|value| value.with_timezone(&Utc))
+            .ok_or(anyhow!("'published' not found"))?;
+        let audience = get_audience(note);
+        let visibility = get_visibility(&audience, &followers);
+        let mut mentions = vec![];
+        for tag in get_tags(note, MENTION) {
+            let Some(href) = tag["href"].as_str() else {
+                continue;
+            };
+            let Ok(canonical_actor_id) = canonicalize_id(href) else {
+                continue;
+            };
+            match get_profile_by_actor_id(
+                db_client,
+                &self.authority,
+                &canonical_actor_id,
+            ).await {
+                Ok(profile) => mentions.push(profile.id),
+                // Unknown actors are ignored
+                Err(DatabaseError::NotFound(_)) => continue,
+                Err(other_error) => return Err(other_error.into()),
+            };
+        };
+        if visibility == Visibility::Direct && mentions.is_empty() {
+            return Ok(NoteImportResult::Skipped("recipients not found".to_owned()));
+        };
+        let tags = get_hashtags(note);
+        let context = if let Some(in_reply_to_object_id) = note["inReplyTo"].as_str() {
+            let Some(in_reply_to_id) = get_in_reply_to_id(
+                db_client,
+                &self.authority,
+                &self.imported,
+                in_reply_to_object_id,
+            ).await? else {
+                return Ok(NoteImportResult::Skipped("parent not found".to_owned()));
+            };
+            let in_reply_to = get_post_by_id(db_client, in_reply_to_id).await?;
+            PostContext::Reply {
+                conversation_id: in_reply_to.expect_conversation().id,
+                in_reply_to_id: in_reply_to.id,
+            }
+        } else {
+            let audience = match visibility {
+                Visibility::Public => Some(AP_PUBLIC.to_owned()),
+                Visibility::Followers => Some(followers),
+                _ => None,
+            };
+            PostContext::Top {
+                group_id: None,
+                object_id: None,
+                audience,
+            }
+        };
+        let mut attachments = vec![];
+        if let Some(uploads_dir) = self.uploads_dir {
+            if !self.storage_quotas.contains_key(&user.id) {
+                let maybe_storage_quota = get_storage_quota(
+                    db_client,
+                    &self.role_limits,
+                    user,
+                ).await?;
+                self.storage_quotas.insert(user.id, maybe_storage_quota);
+            };
+            let mut maybe_storage_quota = self.storage_quotas.get_mut(&user.id)
+                .and_then(|maybe_storage_quota| maybe_storage_quota.as_mut());
+            for (file_path, description) in get_media_paths(note, uploads_dir) {
+                match import_media(
+                    self.config,
+                    db_client,
+                    user.id,
+                    &file_path,
+                    description.as_deref(),
+                    maybe_storage_quota.as_deref_mut(),
+                    copied_files,
+                ).await {
+                    Ok(attachment_id) => attachments.push(attachment_id),
+                    Err(error) => {
+                        println!("skipping file {}: {error}", file_path.display());
+                    },
+                };
+            };
+        };
+        let post_data = PostCreateData {
+            id: Some(post_id),
+            context,
+            title: None,
+            content: clean_remote_content(content),
+            content_source: None,
+            language: None,
+            content_map: vec![],
+            visibility,
+            is_sensitive: note["sensitive"].as_bool().unwrap_or(false),
+            poll: None,
+            attachments,
+            mentions,
+            tags,
+            links: vec![],
+            emojis: vec![],
+            url: None,
+            object_id: None,
+            created_at,
+        };
+        if let Err(error) = validate_post_create_data(&post_data, Local) {
+            return Ok(NoteImportResult::Skipped(error.to_string()));
+        };
+        let result = match create_post(db_client, user.id, post_data).await {
+            Ok(post) => NoteImportResult::Created(post.id),
+            Err(DatabaseError::AlreadyExists(_)) => {
+                NoteImportResult::AlreadyExists(post_id)
+            },
+            Err(other_error) => return Err(other_error.into()),
+        };
+        Ok(result)
+    }
+}
+
+/// Import users, follows and posts from Pleroma or Akkoma database
+#[derive(Parser)]
+pub struct ImportPleroma {
+    /// Pleroma database URL
+    database_url: String,
+    /// Path to Pleroma uploads directory
+    #[arg(long)]
+    uploads_dir: Option<PathBuf>,
+    /// Don't fetch remote actors that are not known to this instance
+    #[arg(long)]
+    offline: bool,
+}
+
+impl ImportPleroma {
+    pub async fn execute(
+        self,
+        config: &Config,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let pleroma_client =
+            create_database_client(&self.database_url, None).await?;
+        let ap_client = ApClient::new_with_pool(config, db_pool).await?;
+        let instance = config.instance();
+        let authority = Authority::from(&instance);
+        let db_client = &mut **get_database_client(db_pool).await?;
+
+        let pleroma_users = get_local_users(&pleroma_client).await?;
+        // Existing accounts with the same names must belong to
+        // previously imported users
+        let mut conflicts = vec![];
+        for pleroma_user in pleroma_users.iter() {
+            match get_user_by_name(db_client, &pleroma_user.nickname).await {
+                Ok(user) => {
+                    if !is_imported_user(&user, pleroma_user)? {
+                        conflicts.push(pleroma_user.nickname.clone());
+                    };
+                },
+                Err(DatabaseError::NotFound(_)) => continue,
+                Err(other_error) => return Err(other_error.into()),
+            };
+        };
+        if !conflicts.is_empty() {
+            return Err(anyhow!(
+                "accounts with these names already exist: {}",
+                conflicts.join(", "),
+            ));
+        };
+
+        // Actor ID -> user
+        let mut users = HashMap::new();
+        for pleroma_user in pleroma_users {
+            let actor_id =
+                local_actor_id(instance.uri_str(), &pleroma_user.nickname);
+            if actor_id != pleroma_user.ap_id {
+                log::warn!(
+                    "actor ID of {} will change to {actor_id}",
+                    pleroma_user.ap_id,
+                );
+            };
+            let user = match get_user_by_name(
+                db_client,
+                &pleroma_user.nickname,
+            ).await {
+                Ok(user) => {
+                    println!("account already imported: {}", user.profile.username);
+                    user
+                },
+                Err(DatabaseError::NotFound(_)) => {
+                    match import_user(config, db_client, &pleroma_user).await {
+                        Ok(user) => {
+                            println!("account created: {}", user.profile.username);
+                            user
+                        },
+                        Err(error) => {
+                            println!("skipping account {}: {error}", pleroma_user.ap_id);
+                            continue;
+                        },
+                    }
+                },
+                Err(other_error) => return Err(other_error.into()),
+            };
+            users.insert(pleroma_user.ap_id.clone(), user);
+        };
+
+        let mut follow_count = 0;
+        for follow in get_follows(&pleroma_client).await? {
+            let source_id = if follow.follower_is_local {
+                let Some(user) = users.get(&follow.follower_ap_id) else {
+                    continue;
+                };
+                user.id
+            } else {
+                match resolve_actor(
+                    &ap_client,
+                    db_pool,
+                    &follow.follower_ap_id,
+                    self.offline,
+                ).await {
+                    Ok(profile) => profile.id,
+                    Err(error) => {
+                        println!("skipping follower {}: {error}", follow.follower_ap_id);
+                        continue;
+                    },
+                }
+            };
+            let target_id = if follow.following_is_local {
+                let Some(user) = users.get(&follow.following_ap_id) else {
+                    continue;
+                };
+                user.id
+            } else {
+                match resolve_actor(
+                    &ap_client,
+                    db_pool,
+                    &follow.following_ap_id,
+                    self.offline,
+                ).await {
+                    Ok(profile) => profile.id,
+                    Err(error) => {
+                        println!("skipping followee {}: {error}", follow.following_ap_id);
+                        continue;
+                    },
+                }
+            };
+            let result = if follow.follower_is_local {
+                create_follow_request(db_client, source_id, target_id).await
+            } else {
+                // Activity ID is needed for processing Undo(Follow)
+                let Some(activity_id) = get_follow_activity_id(
+                    &pleroma_client,
+                    &follow.follower_ap_id,
+                    &follow.following_ap_id,
+                ).await? else {
+                    println!(
+                        "skipping follower {}: Follow activity not found",
+                        follow.follower_ap_id,
+                    );
+                    continue;
+                };
+                create_remote_follow_request_opt(
+                    db_client,
+                    source_id,
+                    target_id,
+                    &activity_id,
+                ).await.map(|(request, _)| request)
+            };
+            let follow_request = match result {
+                Ok(follow_request) => follow_request,
+                Err(DatabaseError::AlreadyExists(_)) => continue,
+                Err(other_error) => return Err(other_error.into()),
+            };
+            match follow_request_accepted(db_client, follow_request.id).await {
+                Ok(_) => follow_count += 1,
+                Err(DatabaseError::AlreadyExists(_)) => continue,
+                Err(other_error) => return Err(other_error.into()),
+            };
+        };
+        println!("follows imported: {follow_count}");
+
+        if self.uploads_dir.is_none() {
+            println!("uploads directory is not specified, media will not be imported");
+        };
+        let actor_ids: Vec<_> = users.keys().cloned().collect();
+        let dynamic_config = get_dynamic_config(db_client).await?;
+        let mut note_importer = NoteImporter {
+            config,
+            authority,
+            uploads_dir: self.uploads_dir.as_deref(),
+            role_limits: dynamic_config.role_limits,
+            users: &users,
+            storage_quotas: HashMap::new(),
+            imported: HashMap::new(),
+        };
+        let mut post_count = 0;
+        let mut last_note_id = 0;
+        loop {
+            let notes = get_notes(
+                &pleroma_client,
+                &actor_ids,
+                last_note_id,
+                NOTE_PAGE_SIZE,
+            ).await?;
+            let Some(last_note) = notes.last() else {
+                break;
+            };
+            last_note_id = last_note.id;
+            for pleroma_note in notes {
+                let note: JsonValue = match serde_json::from_str(&pleroma_note.data) {
+                    Ok(note) => note,
+                    Err(error) => {
+                        println!("skipping object {}: {error}", pleroma_note.id);
+                        continue;
+                    },
+                };
+                let object_id = note["id"].as_str()
+                    .unwrap_or_default()
+                    .to_owned();
+                match note_importer.import_note(db_client, &note).await {
+                    Ok(NoteImportResult::Created(post_id)) => {
+                        note_importer.imported.insert(object_id, post_id);
+                        post_count += 1;
+                    },
+                    Ok(NoteImportResult::AlreadyExists(post_id)) => {
+                        note_importer.imported.insert(object_id, post_id);
+                    },
+                    Ok(NoteImportResult::Skipped(reason)) => {
+                        println!("skipping post {object_id}: {reason}");
+                    },
+                    Err(error) => {
+                        println!("skipping post {object_id}: {error}");
+                    },
+                };
+            };
+        };
+        println!("posts imported: {post_count}");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn test_get_audience() {
+        let note = json!({
+            "to": "https://www.w3.org/ns/activitystreams#Public",
+            "cc": ["https://pleroma.example/users/test/followers"],
+        });
+        let audience = get_audience(&note);
+        assert_eq!(audience, vec![
+            "https://www.w3.org/ns/activitystreams#Public",
+            "https://pleroma.example/users/test/followers",
+        ]);
+    }
+
+    #[test]
+    fn test_get_visibility() {
+        let followers = "https://pleroma.example/users/test/followers";
+        let audience = vec![
+            "https://www.w3.org/ns/activitystreams#Public".to_owned(),
+        ];
+        assert_eq!(get_visibility(&audience, followers), Visibility::Public);
+        let audience = vec![followers.to_owned()];
+        assert_eq!(get_visibility(&audience, followers), Visibility::Followers);
+        let audience = vec!["https://remote.example/users/1".to_owned()];
+        assert_eq!(get_visibility(&audience, followers), Visibility::Direct);
+    }
+
+    #[test]
+    fn test_get_hashtags() {
+        let note = json!({
+            "tag": [
+                {"type": "Hashtag", "name": "#Test"},
+                {"type": "Hashtag", "name": "#not valid"},
+                {"type": "Mention", "name": "@test"},
+            ],
+        });
+        let tags = get_hashtags(&note);
+        assert_eq!(tags, vec!["test"]);
+    }
+
+    #[test]
+    fn test_get_media_paths() {
+        let note = json!({
+            "attachment": [
+                {
+                    "url": [{"href": "https://pleroma.example/media/ab/image.png"}],
+                    "name": "description",
+                },
+                {
+                    "url": "https://remote.example/image.png",
+                },
+            ],
+        });
+        let uploads_dir = Path::new("/var/lib/pleroma/uploads");
+        let paths = get_media_paths(&note, uploads_dir);
+        assert_eq!(paths, vec![(
+            uploads_dir.join("ab/image.png"),
+            Some("description".to_owned()),
+        )]);
+    }
+}
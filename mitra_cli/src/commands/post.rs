@@ -1,3 +1,6 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::Write;
 use std::path::PathBuf;
 
 use anyhow::{anyhow, Error};
@@ -16,7 +19,7 @@ use serde_json::{Value as JsonValue};
 use uuid::Uuid;
 
 use mitra_activitypub::{
-    adapters::posts::delete_local_post,
+    adapters::posts::{delete_local_post, reassign_local_posts},
     agent::build_federation_agent,
     authority::Authority,
     builders::{
@@ -52,7 +55,10 @@ use mitra_models::{
     },
     profiles::types::Origin::Local,
 };
-use mitra_services::media::{MediaServer, MediaStorage};
+use mitra_services::{
+    media::{MediaServer, MediaStorage},
+    media_metadata::get_media_metadata,
+};
 use mitra_utils::{
     files::FileSize,
     id::generate_deterministic_ulid,
@@ -90,6 +96,9 @@ pub struct CreatePost {
     /// Unique post ID
     #[arg(long)]
     id: Option<Uuid>,
+    /// Parent post ID (must be a public post by the same author)
+    #[arg(long)]
+    in_reply_to: Option<Uuid>,
 }
 
 impl CreatePost {
@@ -106,16 +115,36 @@ impl CreatePost {
             generate_post_id(author.id, &self.content, self.created_at)
         });
         let content = clean_remote_content(&self.content);
+        let context = if let Some(in_reply_to_id) = self.in_reply_to {
+            let in_reply_to = get_post_by_id(
+                db_client_await!(db_pool),
+                in_reply_to_id,
+            ).await?;
+            if in_reply_to.author.id != author.id || !in_reply_to.is_public() {
+                return Err(anyhow!("can't reply to this post"));
+            };
+            PostContext::Reply {
+                conversation_id: in_reply_to.expect_conversation().id,
+                in_reply_to_id: in_reply_to.id,
+            }
+        } else {
+            PostContext::Top {
+                group_id: None,
+                object_id: None,
+                audience: Some(AP_PUBLIC.to_owned()),
+            }
+        };
         let mut attachments = vec![];
         let storage = MediaStorage::new(config);
+        let limits = config.limits();
         for location in self.attachment.iter() {
             let (file_data, media_type) = if HttpUri::parse(location).is_ok() {
                 let agent = build_federation_agent(&config.instance(), None);
                 fetch_media(
                     &agent,
                     location,
-                    &config.limits.media.supported_media_types(),
-                    config.limits.media.file_size_limit,
+                    &limits.media.supported_media_types(),
+                    limits.media.file_size_limit,
                 ).await?
             } else {
                 let file_data = std::fs::read(location)?;
@@ -123,34 +152,39 @@ impl CreatePost {
                     .ok_or(anyhow!("unknown media type"))?;
                 (file_data, media_type)
             };
-            if !config.limits.media.supported_media_types().contains(&media_type.as_str()) {
+            if !limits.media.supported_media_types().contains(&media_type.as_str()) {
                 return Err(anyhow!("media type {media_type} is not supported"));
             };
-            if file_data.len() > config.limits.media.file_size_limit {
-                let limit = FileSize::new(config.limits.media.file_size_limit);
+            if file_data.len() > limits.media.file_size_limit {
+                let limit = FileSize::new(limits.media.file_size_limit);
                 return Err(anyhow!("file size must be less than {limit}"));
             };
             let file_info = storage.save_file(file_data, &media_type)?;
+            let metadata = get_media_metadata(
+                &limits.media,
+                &storage,
+                &file_info.file_name,
+                &file_info.media_type,
+            ).await;
             let db_client = &**get_database_client(db_pool).await?;
             let attachment = create_attachment(
                 db_client,
                 author.id,
                 MediaInfo::local(file_info),
                 None,
+                false, // not sensitive
+                metadata,
             ).await?;
             attachments.push(attachment.id);
         };
         let post_data = PostCreateData {
             id: Some(post_id),
-            context: PostContext::Top {
-                group_id: None,
-                object_id: None,
-                audience: Some(AP_PUBLIC.to_owned()),
-            },
+            context: context,
             title: None,
             content: content,
             content_source: None,
             language: None,
+            content_map: vec![],
             visibility: Visibility::Public,
             is_sensitive: false,
             poll: None,
@@ -164,7 +198,12 @@ impl CreatePost {
             created_at: self.created_at,
         };
         validate_post_create_data(&post_data, Local)?;
-        check_post_limits(&config.limits.posts, &post_data.attachments, Local)?;
+        check_post_limits(
+            &limits.posts,
+            &post_data.attachments,
+            post_data.poll.as_ref(),
+            Local,
+        )?;
         let db_client = &mut **get_database_client(db_pool).await?;
         let post = create_post(db_client, author.id, post_data).await?;
         println!("post created: {}", post.id);
@@ -179,6 +218,12 @@ pub struct ImportPosts {
     author: String,
     /// Path to outbox.json
     outbox_path: PathBuf,
+    /// Path to checkpoint file (allows resuming interrupted import)
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+    /// Report what would be created without creating anything
+    #[arg(long)]
+    dry_run: bool,
 }
 
 impl ImportPosts {
@@ -195,24 +240,53 @@ impl ImportPosts {
         let outbox: JsonValue = serde_json::from_str(&outbox_data)?;
         let activities = outbox["orderedItems"].as_array()
             .ok_or(anyhow!("'orderedItems' not found"))?;
+        // Attachment paths are relative to outbox location
+        let base_dir = self.outbox_path.parent()
+            .map(|path| path.to_path_buf())
+            .unwrap_or_default();
+        // Contains IDs of objects that were already imported
+        let checkpoint: HashSet<String> = match self.checkpoint {
+            Some(ref path) if path.exists() => {
+                std::fs::read_to_string(path)?
+                    .lines()
+                    .map(|line| line.to_owned())
+                    .collect()
+            },
+            _ => HashSet::new(),
+        };
+        let mut objects = vec![];
         for activity in activities {
-            // Only public top-level posts
+            // Only public posts
             if activity["type"].as_str() != Some("Create") {
                 continue;
             };
             let object: AttributedObject =
                 serde_json::from_value(activity["object"].clone())?;
-            if object.in_reply_to.is_some() {
-                continue;
-            };
             if !object.audience().iter().any(is_public) {
                 continue;
             };
+            objects.push(object);
+        };
+        // Ancestors should be imported before replies
+        objects.sort_by_key(|object| object.published);
+        // Object ID -> post ID
+        let mut imported = HashMap::new();
+        for object in objects {
             let content = object.content
                 .ok_or(anyhow!("'content' not found"))?;
             let created_at = object.published
                 .ok_or(anyhow!("'published' not found"))?;
-            let attachments = object.attachment.iter()
+            let maybe_in_reply_to_id = match object.in_reply_to {
+                Some(ref in_reply_to) => {
+                    match imported.get(in_reply_to) {
+                        Some(in_reply_to_id) => Some(*in_reply_to_id),
+                        // Only replies to posts from the same outbox
+                        None => continue,
+                    }
+                },
+                None => None,
+            };
+            let attachments: Vec<_> = object.attachment.iter()
                 .filter_map(|attachment| match attachment {
                     Attachment::Media(media) => Some(media.url.clone()),
                     _ => None,
@@ -220,19 +294,35 @@ impl ImportPosts {
                 .map(|location| {
                     if location.starts_with("/media_attachments") {
                         // Mastodon archive
-                        location.trim_start_matches('/').to_owned()
+                        let path = base_dir
+                            .join(location.trim_start_matches('/'));
+                        path.to_string_lossy().into_owned()
                     } else {
                         location
                     }
                 })
                 .collect();
             let post_id = generate_post_id(author.id, &content, created_at);
+            imported.insert(object.id.clone(), post_id);
+            if checkpoint.contains(&object.id) {
+                println!("post already imported: {post_id}");
+                continue;
+            };
+            if self.dry_run {
+                println!(
+                    "post will be created: {post_id} (attachments: {}, reply: {})",
+                    attachments.len(),
+                    maybe_in_reply_to_id.is_some(),
+                );
+                continue;
+            };
             let command = CreatePost {
                 author: self.author.clone(),
                 content: content,
                 created_at: created_at,
                 attachment: attachments,
                 id: Some(post_id),
+                in_reply_to: maybe_in_reply_to_id,
             };
             command.execute(config, db_pool)
                 .await
@@ -244,6 +334,13 @@ impl ImportPosts {
                         Err(error)
                     }
                 })?;
+            if let Some(ref path) = self.checkpoint {
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)?;
+                writeln!(file, "{}", object.id)?;
+            };
         };
         Ok(())
     }
@@ -311,6 +408,38 @@ impl ExportPosts {
     }
 }
 
+/// Move public posts and reposts to another local account
+#[derive(Parser)]
+pub struct MovePosts {
+    /// Current author (username or ID)
+    from: String,
+    /// New author (username or ID)
+    to: String,
+}
+
+impl MovePosts {
+    pub async fn execute(
+        self,
+        config: &Config,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &mut **get_database_client(db_pool).await?;
+        let from_user = get_user_by_id_or_name(db_client, &self.from).await?;
+        let to_user = get_user_by_id_or_name(db_client, &self.to).await?;
+        if from_user.id == to_user.id {
+            return Err(anyhow!("accounts must be different"));
+        };
+        let count = reassign_local_posts(
+            config,
+            db_client,
+            &from_user,
+            &to_user,
+        ).await?;
+        println!("posts moved: {count}");
+        Ok(())
+    }
+}
+
 /// Delete post
 #[derive(Parser)]
 pub struct DeletePost {
@@ -1,4 +1,4 @@
-use anyhow::Error;
+use anyhow::{anyhow, Error};
 use clap::Parser;
 
 use mitra_activitypub::{
@@ -16,6 +16,8 @@ use mitra_models::{
         queries::{
             delete_profile,
             find_unreachable,
+            link_profiles,
+            unlink_profile,
         },
     },
 };
@@ -83,3 +85,57 @@ impl DeleteUser {
         Ok(())
     }
 }
+
+/// Mark profile as an alternate profile of the same person
+#[derive(Parser)]
+pub struct MergeProfiles {
+    /// Alternate profile (ID or acct)
+    profile: String,
+    /// Primary profile (ID or acct)
+    primary: String,
+}
+
+impl MergeProfiles {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &mut **get_database_client(db_pool).await?;
+        let profile = get_profile_by_id_or_acct(db_client, &self.profile).await?;
+        let primary = get_profile_by_id_or_acct(db_client, &self.primary).await?;
+        if profile.id == primary.id {
+            return Err(anyhow!("profile can't be merged with itself"));
+        };
+        let is_linked = link_profiles(
+            db_client,
+            profile.id,
+            primary.id,
+            true, // manual
+        ).await?;
+        if !is_linked {
+            return Err(anyhow!("profiles are already merged"));
+        };
+        println!("profiles merged");
+        Ok(())
+    }
+}
+
+/// Remove profile from the group of merged profiles
+#[derive(Parser)]
+pub struct UnmergeProfile {
+    /// Alternate profile (ID or acct)
+    profile: String,
+}
+
+impl UnmergeProfile {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        let profile = get_profile_by_id_or_acct(db_client, &self.profile).await?;
+        unlink_profile(db_client, profile.id).await?;
+        println!("profile unmerged");
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
-use anyhow::Error;
+use std::path::Path;
+
+use anyhow::{anyhow, Error};
 use clap::Parser;
+use serde_json::{json, Map as JsonMap};
 
 use mitra_config::Config;
 use mitra_models::{
@@ -8,7 +11,11 @@ use mitra_models::{
         queries::get_job_count,
         types::JobType,
     },
-    database::{get_database_client, DatabaseConnectionPool},
+    database::{
+        get_database_client,
+        migrate::get_pending_migration_count,
+        DatabaseConnectionPool,
+    },
     invoices::{
         queries::get_invoice_summary,
         types::InvoiceStatus,
@@ -20,6 +27,7 @@ use mitra_models::{
     },
 };
 use mitra_services::{
+    media::MediaStorage,
     monero::{
         wallet::{
             get_address_count,
@@ -53,10 +61,13 @@ impl InstanceReport {
             get_job_count(db_client, JobType::DataImport).await?;
         let fetcher_queue_size =
             get_job_count(db_client, JobType::Fetcher).await?;
+        let push_queue_size =
+            get_job_count(db_client, JobType::PushNotification).await?;
         println!("incoming activity queue: {incoming_activities}");
         println!("outgoing activity queue: {outgoing_activities}");
         println!("data import queue: {data_import_queue_size}");
         println!("fetcher queue: {fetcher_queue_size}");
+        println!("push notification queue: {push_queue_size}");
         if let Some(monero_config) = config.monero_config() {
             // Invoices
             let invoice_summary = get_invoice_summary(db_client).await?;
@@ -91,3 +102,94 @@ impl InstanceReport {
         Ok(())
     }
 }
+
+fn check_directory_writable(path: &Path) -> Result<(), std::io::Error> {
+    let test_file_path = path.join(".healthcheck");
+    std::fs::write(&test_file_path, b"")?;
+    std::fs::remove_file(&test_file_path)?;
+    Ok(())
+}
+
+/// Verify that instance is operational (prints JSON report)
+#[derive(Parser)]
+pub struct Healthcheck {
+    /// Maximum acceptable size of outgoing activity queue
+    #[arg(long, default_value_t = 10000)]
+    queue_limit: u32,
+}
+
+impl Healthcheck {
+    pub async fn execute(
+        self,
+        config: &Config,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let mut results = vec![];
+        // Database
+        match get_database_client(db_pool).await {
+            Ok(db_client) => {
+                results.push(("database", Ok("connected".to_owned())));
+                let migrations_result =
+                    match get_pending_migration_count(&**db_client).await {
+                        Ok(0) => Ok("all migrations applied".to_owned()),
+                        Ok(count) => Err(format!("pending migrations: {count}")),
+                        Err(error) => Err(error.to_string()),
+                    };
+                results.push(("migrations", migrations_result));
+                let queue_result = match get_job_count(
+                    &**db_client,
+                    JobType::OutgoingActivity,
+                ).await {
+                    Ok(count) => {
+                        let message = format!("outgoing activities: {count}");
+                        if count <= i64::from(self.queue_limit) {
+                            Ok(message)
+                        } else {
+                            Err(message)
+                        }
+                    },
+                    Err(error) => Err(error.to_string()),
+                };
+                results.push(("delivery_queue", queue_result));
+            },
+            Err(error) => {
+                results.push(("database", Err(error.to_string())));
+            },
+        };
+        // Media storage
+        let storage_result = match MediaStorage::new(config) {
+            MediaStorage::Filesystem(backend) => {
+                check_directory_writable(&backend.media_dir)
+                    .map(|_| "writable".to_owned())
+                    .map_err(|error| error.to_string())
+            },
+        };
+        results.push(("media_storage", storage_result));
+        // Payments
+        if let Some(monero_config) = config.monero_config() {
+            let wallet_result = open_monero_wallet(monero_config).await
+                .map(|_| "reachable".to_owned())
+                .map_err(|error| error.to_string());
+            results.push(("monero_wallet", wallet_result));
+        };
+        let is_healthy = results.iter().all(|(_, result)| result.is_ok());
+        let checks: JsonMap<_, _> = results.into_iter()
+            .map(|(name, result)| {
+                let value = match result {
+                    Ok(message) => json!({"ok": true, "message": message}),
+                    Err(message) => json!({"ok": false, "message": message}),
+                };
+                (name.to_owned(), value)
+            })
+            .collect();
+        let report = json!({
+            "healthy": is_healthy,
+            "checks": checks,
+        });
+        println!("{report}");
+        if !is_healthy {
+            return Err(anyhow!("health check failed"));
+        };
+        Ok(())
+    }
+}
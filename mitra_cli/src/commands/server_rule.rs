@@ -0,0 +1,107 @@
+use anyhow::Error;
+use clap::Parser;
+
+use mitra_models::{
+    database::{get_database_client, DatabaseConnectionPool},
+    server_rules::queries::{
+        create_server_rule,
+        delete_server_rule,
+        get_server_rules,
+        update_server_rule,
+    },
+};
+use mitra_validators::server_rules::validate_server_rule;
+
+/// Add server rule
+#[derive(Parser)]
+pub struct AddServerRule {
+    /// Rule text
+    text: String,
+    /// Additional explanation
+    #[arg(long, default_value = "")]
+    hint: String,
+}
+
+impl AddServerRule {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        validate_server_rule(&self.text, &self.hint)?;
+        let rule = create_server_rule(
+            db_client,
+            &self.text,
+            &self.hint,
+        ).await?;
+        println!("rule added (ID: {})", rule.id);
+        Ok(())
+    }
+}
+
+/// Update server rule
+#[derive(Parser)]
+pub struct UpdateServerRule {
+    /// Rule ID
+    id: i32,
+    /// Rule text
+    text: String,
+    /// Additional explanation
+    #[arg(long, default_value = "")]
+    hint: String,
+}
+
+impl UpdateServerRule {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        validate_server_rule(&self.text, &self.hint)?;
+        update_server_rule(
+            db_client,
+            self.id,
+            &self.text,
+            &self.hint,
+        ).await?;
+        println!("rule updated");
+        Ok(())
+    }
+}
+
+/// Delete server rule
+#[derive(Parser)]
+pub struct DeleteServerRule {
+    /// Rule ID
+    id: i32,
+}
+
+impl DeleteServerRule {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        delete_server_rule(db_client, self.id).await?;
+        println!("rule deleted");
+        Ok(())
+    }
+}
+
+/// List server rules
+#[derive(Parser)]
+pub struct ListServerRules;
+
+impl ListServerRules {
+    pub async fn execute(
+        self,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        let rules = get_server_rules(db_client).await?;
+        for rule in rules {
+            println!("{0: <5} {1}", rule.id, rule.rule_text);
+        };
+        Ok(())
+    }
+}
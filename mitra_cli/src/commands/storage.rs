@@ -1,11 +1,18 @@
+use std::collections::HashSet;
+
 use anyhow::Error;
-use apx_core::url::canonical::CanonicalUri;
+use apx_core::{
+    crypto::hashes::sha256,
+    url::canonical::CanonicalUri,
+};
+use apx_sdk::fetch::fetch_media;
 use clap::{
     Parser,
     Subcommand,
 };
 
 use mitra_activitypub::{
+    agent::build_federation_agent,
     builders::undo_announce::prepare_undo_announce,
 };
 use mitra_adapters::{
@@ -14,10 +21,22 @@ use mitra_adapters::{
 use mitra_config::Config;
 use mitra_models::{
     accounts::queries::get_user_by_id,
-    activitypub::queries::get_object_ids,
+    activitypub::queries::{
+        delete_activitypub_objects,
+        delete_attributed_objects,
+        get_object_ids,
+        get_object_storage_size,
+        get_object_storage_stats,
+    },
     attachments::queries::delete_unused_attachments,
-    database::{get_database_client, DatabaseConnectionPool},
-    media::queries::{find_orphaned_files, get_local_files},
+    database::{get_database_client, DatabaseClient, DatabaseConnectionPool},
+    media::queries::{
+        clean_missing_media,
+        find_orphaned_files,
+        get_ipfs_objects,
+        get_local_files,
+        get_stored_media,
+    },
     posts::queries::{
         delete_post,
         delete_repost,
@@ -31,7 +50,10 @@ use mitra_models::{
         get_profile_by_id,
     },
 };
-use mitra_services::media::MediaStorage;
+use mitra_services::{
+    ipfs::{store as ipfs_store},
+    media::MediaStorage,
+};
 use mitra_utils::datetime::days_before_now;
 
 /// Delete old remote posts
@@ -134,6 +156,29 @@ pub struct DeleteOrphanedFiles {
     dry_run: bool,
 }
 
+async fn delete_orphaned_files(
+    config: &Config,
+    db_client: &impl DatabaseClient,
+    dry_run: bool,
+) -> Result<(), Error> {
+    let media_storage = MediaStorage::new(config);
+    let files = media_storage.list_files()?;
+    let orphaned = find_orphaned_files(db_client, files).await?;
+    if orphaned.is_empty() {
+        println!("no orphaned files found");
+        return Ok(());
+    };
+    if dry_run {
+        for file_name in orphaned {
+            println!("orphaned file: {file_name}");
+        };
+    } else {
+        delete_files(&media_storage, &orphaned);
+        println!("orphaned files deleted: {}", orphaned.len());
+    };
+    Ok(())
+}
+
 impl DeleteOrphanedFiles {
     pub async fn execute(
         self,
@@ -141,21 +186,171 @@ impl DeleteOrphanedFiles {
         db_pool: &DatabaseConnectionPool,
     ) -> Result<(), Error> {
         let db_client = &**get_database_client(db_pool).await?;
+        delete_orphaned_files(config, db_client, self.dry_run).await
+    }
+}
+
+/// Check media storage for missing, orphaned and corrupted files
+#[derive(Parser)]
+pub struct VerifyMedia {
+    /// Try to re-download missing remote files
+    #[arg(long)]
+    refetch: bool,
+    /// Remove references to missing files from database
+    /// (remote media is replaced with links)
+    #[arg(long)]
+    clean_missing: bool,
+    /// Delete files that are not referenced in database
+    #[arg(long)]
+    delete_orphaned: bool,
+}
+
+impl VerifyMedia {
+    pub async fn execute(
+        self,
+        config: &Config,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &mut **get_database_client(db_pool).await?;
         let media_storage = MediaStorage::new(config);
         let files = media_storage.list_files()?;
-        let orphaned = find_orphaned_files(db_client, files).await?;
-        if orphaned.is_empty() {
-            println!("no orphaned files found");
-            return Ok(());
+        let file_set: HashSet<String> = files.into_iter().collect();
+        let agent = build_federation_agent(&config.instance(), None);
+        let media = get_stored_media(db_client).await?;
+        let mut missing_count = 0;
+        let mut refetched_count = 0;
+        let mut cleaned_count = 0;
+        let mut mismatch_count = 0;
+        for item in media {
+            let file_info = item.expect_file_info();
+            if !file_set.contains(&file_info.file_name) {
+                missing_count += 1;
+                println!("missing file: {}", file_info.file_name);
+                if self.refetch {
+                    if let (Some(url), Some(media_type)) =
+                        (item.url(), file_info.media_type.as_ref())
+                    {
+                        match fetch_media(
+                            &agent,
+                            url,
+                            &[media_type.as_str()],
+                            config.limits().media.file_size_limit,
+                        ).await {
+                            Ok((file_data, _)) => {
+                                let new_file_info =
+                                    media_storage.save_file(file_data, media_type)?;
+                                if new_file_info.file_name == file_info.file_name {
+                                    refetched_count += 1;
+                                    println!("file restored: {}", file_info.file_name);
+                                    continue;
+                                };
+                                // Remote file has changed
+                                let orphaned = find_orphaned_files(
+                                    db_client,
+                                    vec![new_file_info.file_name],
+                                ).await?;
+                                delete_files(&media_storage, &orphaned);
+                                println!("remote file has changed: {url}");
+                            },
+                            Err(error) => {
+                                println!("failed to fetch {url}: {error}");
+                            },
+                        };
+                    };
+                };
+                if self.clean_missing {
+                    let updated_count = clean_missing_media(
+                        db_client,
+                        &file_info.file_name,
+                    ).await?;
+                    cleaned_count += updated_count;
+                    println!("references removed: {}", file_info.file_name);
+                };
+                continue;
+            };
+            let Some(digest) = file_info.digest else {
+                continue;
+            };
+            let file_data = match media_storage.read_file(&file_info.file_name) {
+                Ok(file_data) => file_data,
+                Err(error) => {
+                    log::warn!("failed to read {}: {error}", file_info.file_name);
+                    continue;
+                },
+            };
+            if sha256(&file_data) != digest {
+                mismatch_count += 1;
+                println!("hash mismatch: {}", file_info.file_name);
+            };
         };
-        if self.dry_run {
-            for file_name in orphaned {
-                println!("orphaned file: {file_name}");
+        if let Some(ref ipfs_api_url) = config.ipfs_api_url {
+            let pins: HashSet<String> = ipfs_store::list_pins(ipfs_api_url)
+                .await?
+                .into_iter()
+                .collect();
+            for cid in get_ipfs_objects(db_client).await? {
+                if !pins.contains(&cid) {
+                    println!("IPFS object is not pinned: {cid}");
+                };
             };
-        } else {
-            delete_files(&media_storage, &orphaned);
-            println!("orphaned files deleted: {}", orphaned.len());
         };
+        println!("missing files: {missing_count} ({refetched_count} restored)");
+        if self.clean_missing {
+            println!("database rows updated: {cleaned_count}");
+        };
+        println!("hash mismatches: {mismatch_count}");
+        delete_orphaned_files(
+            config,
+            db_client,
+            !self.delete_orphaned,
+        ).await?;
+        Ok(())
+    }
+}
+
+/// Show space used by stored ActivityPub objects
+#[derive(Parser)]
+pub struct ActivitypubObjects {
+    /// Delete unattributed objects older than N days
+    #[arg(long)]
+    prune_unattributed: Option<u32>,
+    /// Delete raw objects of posts deleted more than N days ago
+    #[arg(long)]
+    prune_attributed: Option<u32>,
+}
+
+impl ActivitypubObjects {
+    pub async fn execute(
+        self,
+        _config: &Config,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &**get_database_client(db_pool).await?;
+        if let Some(days) = self.prune_unattributed {
+            let deleted_count = delete_activitypub_objects(
+                db_client,
+                days_before_now(days),
+            ).await?;
+            println!("deleted {deleted_count} unattributed objects");
+        };
+        if let Some(days) = self.prune_attributed {
+            let deleted_count = delete_attributed_objects(
+                db_client,
+                days_before_now(days),
+            ).await?;
+            println!("deleted {deleted_count} attributed objects");
+        };
+        let stats = get_object_storage_stats(db_client).await?;
+        for item in stats {
+            println!(
+                "{}: {} objects, {} bytes",
+                item.category,
+                item.object_count,
+                item.object_size,
+            );
+        };
+        let total_size = get_object_storage_size(db_client).await?;
+        println!("total size: {total_size} bytes");
         Ok(())
     }
 }
@@ -0,0 +1,54 @@
+use anyhow::Error;
+use clap::Parser;
+
+use mitra_activitypub::adapters::subscriptions::grant_subscription;
+use mitra_config::Config;
+use mitra_models::{
+    accounts::helpers::get_user_by_id_or_name,
+    database::{get_database_client, DatabaseConnectionPool},
+    profiles::helpers::get_profile_by_id_or_acct,
+};
+
+const SECONDS_IN_DAY: i64 = 24 * 3600;
+
+/// Grant subscription to an account (without payment)
+#[derive(Parser)]
+pub struct GrantSubscription {
+    /// Local account that receives the subscription
+    recipient_id_or_name: String,
+    /// Subscriber (profile ID or acct)
+    subscriber_id_or_acct: String,
+    /// Duration of subscription, in days
+    #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+    days: u32,
+}
+
+impl GrantSubscription {
+    pub async fn execute(
+        self,
+        config: &Config,
+        db_pool: &DatabaseConnectionPool,
+    ) -> Result<(), Error> {
+        let db_client = &mut **get_database_client(db_pool).await?;
+        let recipient = get_user_by_id_or_name(
+            db_client,
+            &self.recipient_id_or_name,
+        ).await?;
+        let subscriber = get_profile_by_id_or_acct(
+            db_client,
+            &self.subscriber_id_or_acct,
+        ).await?;
+        if subscriber.id == recipient.id {
+            return Err(Error::msg("can't subscribe to self"));
+        };
+        let subscription = grant_subscription(
+            &config.instance(),
+            db_client,
+            &subscriber,
+            &recipient,
+            i64::from(self.days) * SECONDS_IN_DAY,
+        ).await?;
+        println!("subscription expires at {}", subscription.expires_at);
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+use serde::Deserialize;
+
+/// Access to Mastodon API for unauthenticated clients
+#[derive(Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PublicApiAccessMode {
+    // All public data is available
+    #[default]
+    Full,
+    // Timelines, directory and search are not available,
+    // individual posts and profiles are available
+    Limited,
+    // Only endpoints required for registration and logging in
+    // are available
+    Closed,
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use serde::{
     Deserialize,
     Deserializer,
@@ -9,6 +11,7 @@ pub enum AuthenticationMethod {
     Password,
     Eip4361,
     Caip122Monero,
+    Did,
 }
 
 impl<'de> Deserialize<'de> for AuthenticationMethod {
@@ -20,6 +23,7 @@ impl<'de> Deserialize<'de> for AuthenticationMethod {
             "password" => Self::Password,
             "eip4361" => Self::Eip4361,
             "caip122_monero" => Self::Caip122Monero,
+            "did" => Self::Did,
             _ => return Err(DeserializerError::custom("unknown authentication method")),
         };
         Ok(method)
@@ -33,3 +37,15 @@ pub fn default_authentication_methods() -> Vec<AuthenticationMethod> {
 pub fn default_authentication_token_lifetime() -> u32 { 86400 * 30 }
 
 pub fn default_login_message() -> String { "Do not sign this message on other sites!".to_string() }
+
+#[derive(Clone, Default, Deserialize)]
+pub struct PasswordPolicy {
+    // Minimum number of characters (0 disables the check)
+    #[serde(default)]
+    pub min_length: usize,
+    // Minimum zxcvbn strength score, from 0 to 4 (0 disables the check)
+    #[serde(default)]
+    pub min_score: u8,
+    // File containing breached passwords, one per line
+    pub breached_passwords_file: Option<PathBuf>,
+}
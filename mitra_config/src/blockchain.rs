@@ -15,6 +15,10 @@ pub struct MoneroConfig {
     // Additional information for clients
     pub chain_metadata: Option<MoneroChainMetadata>,
     pub wallet_rpc_url: String,
+    // Used when primary wallet RPC endpoint is not available.
+    // All endpoints should have access to the same wallet.
+    #[serde(default)]
+    pub wallet_rpc_fallback_urls: Vec<String>,
     pub wallet_rpc_username: Option<String>,
     pub wallet_rpc_password: Option<String>,
     // Wallet name and password are required when
@@ -27,6 +31,14 @@ pub struct MoneroConfig {
     pub tx_required_confirmations: u64,
 }
 
+impl MoneroConfig {
+    pub fn wallet_rpc_urls(&self) -> Vec<String> {
+        [self.wallet_rpc_url.clone()].into_iter()
+            .chain(self.wallet_rpc_fallback_urls.iter().cloned())
+            .collect()
+    }
+}
+
 #[derive(Clone, Deserialize)]
 pub struct MoneroLightConfig {
     pub chain_id: ChainId,
@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 use std::net::Ipv6Addr;
 use std::path::PathBuf;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 
 use apx_core::{
     crypto::{
@@ -11,11 +15,13 @@ use apx_core::{
 use log::{Level as LogLevel};
 use serde::Deserialize;
 
+use super::api_access::PublicApiAccessMode;
 use super::authentication::{
     default_authentication_methods,
     default_authentication_token_lifetime,
     default_login_message,
     AuthenticationMethod,
+    PasswordPolicy,
 };
 use super::blockchain::{
     BlockchainConfig,
@@ -24,12 +30,17 @@ use super::blockchain::{
 };
 use super::environment::Environment;
 use super::federation::FederationConfig;
+use super::feeds::FeedsConfig;
 use super::instance::Instance;
 use super::limits::Limits;
+use super::logging::LogFormat;
+use super::media_access::MediaAccessConfig;
 use super::metrics::Metrics;
+use super::reloadable::Reloadable;
 use super::registration::RegistrationConfig;
 use super::retention::RetentionConfig;
 use super::software::SoftwareMetadata;
+use super::subscriptions::SubscriptionsConfig;
 
 const DEFAULT_HTTP_HOST: &str = "127.0.0.1";
 const DEFAULT_HTTP_PORT: u32 = 8383;
@@ -43,6 +54,8 @@ const fn default_media_proxy_enabled() -> bool { true }
 
 const fn default_instance_staff_public() -> bool { true }
 
+const fn default_post_view_count_enabled() -> bool { true }
+
 #[derive(Clone, Deserialize)]
 pub struct Config {
     // Properties auto-populated from the environment
@@ -56,8 +69,14 @@ pub struct Config {
     pub config_path: String,
 
     // Core settings
+    // Reloadable
     #[serde(default = "default_log_level")]
     pub log_level: LogLevel,
+    #[serde(default)]
+    pub log_format: LogFormat,
+    // Per-module log levels (module path -> level)
+    #[serde(default)]
+    pub log_filters: HashMap<String, LogLevel>,
 
     pub database_url: String,
     pub database_connection_pool_size: Option<usize>,
@@ -72,6 +91,8 @@ pub struct Config {
     pub web_client_rewrite_index: bool,
     #[serde(default = "default_media_proxy_enabled")]
     pub media_proxy_enabled: bool,
+    #[serde(default)]
+    pub media_access: MediaAccessConfig,
 
     http_host: Option<String>,
     pub(super) http_port: Option<u32>,
@@ -100,6 +121,18 @@ pub struct Config {
     pub instance_staff_public: bool,
     #[serde(default)]
     pub instance_timeline_public: bool,
+    #[serde(default)]
+    pub public_api_access: PublicApiAccessMode,
+    // Count views of public posts
+    #[serde(default = "default_post_view_count_enabled")]
+    pub post_view_count_enabled: bool,
+    // Include public posts in full-text search results
+    #[serde(default)]
+    pub search_public_posts: bool,
+
+    // Images displayed when profile doesn't have avatar or header
+    pub default_avatar_url: Option<String>,
+    pub default_header_url: Option<String>,
 
     #[serde(default)]
     pub registration: RegistrationConfig,
@@ -110,12 +143,16 @@ pub struct Config {
     #[serde(default = "default_authentication_token_lifetime")]
     pub authentication_token_lifetime: u32,
 
+    #[serde(default)]
+    pub password_policy: PasswordPolicy,
+
     // EIP-4361 login message
     #[serde(default = "default_login_message")]
     pub login_message: String,
 
+    // Reloadable
     #[serde(default)]
-    pub limits: Limits,
+    limits: Reloadable<Limits>,
 
     #[serde(default)]
     pub retention: RetentionConfig,
@@ -123,8 +160,17 @@ pub struct Config {
     #[serde(default)]
     pub federation: FederationConfig,
 
-    pub blocked_instances: Option<Vec<String>>,
-    pub allowed_instances: Option<Vec<String>>,
+    #[serde(default)]
+    pub subscriptions: SubscriptionsConfig,
+
+    #[serde(default)]
+    pub feeds: FeedsConfig,
+
+    // Reloadable
+    #[serde(default)]
+    blocked_instances: Reloadable<Option<Vec<String>>>,
+    #[serde(default)]
+    allowed_instances: Reloadable<Option<Vec<String>>>,
 
     pub metrics: Option<Metrics>,
 
@@ -141,6 +187,14 @@ pub struct Config {
     pub(super) instance_ed25519_key: Option<Ed25519SecretKey>,
     #[serde(skip)]
     pub(super) instance_rsa_key: Option<RsaSecretKey>,
+    #[serde(skip)]
+    instance_vapid_key: Option<[u8; 32]>,
+    #[serde(skip)]
+    media_url_key: Option<[u8; 32]>,
+
+    // Incremented after each reload
+    #[serde(skip)]
+    revision: Arc<AtomicU64>,
 }
 
 impl Config {
@@ -160,6 +214,60 @@ impl Config {
         self.instance_rsa_key = Some(secret_key);
     }
 
+    pub fn set_instance_vapid_key(&mut self, secret_key: [u8; 32]) -> () {
+        assert!(
+            self.instance_vapid_key.is_none(),
+            "instance VAPID key can not be replaced",
+        );
+        self.instance_vapid_key = Some(secret_key);
+    }
+
+    /// Returns P-256 secret key for signing Web Push requests
+    pub fn instance_vapid_key(&self) -> [u8; 32] {
+        self.instance_vapid_key
+            .expect("instance VAPID key should be already generated")
+    }
+
+    pub fn set_media_url_key(&mut self, secret_key: [u8; 32]) -> () {
+        assert!(
+            self.media_url_key.is_none(),
+            "media URL key can not be replaced",
+        );
+        self.media_url_key = Some(secret_key);
+    }
+
+    /// Returns HMAC key for signing media URLs
+    pub fn media_url_key(&self) -> [u8; 32] {
+        self.media_url_key
+            .expect("media URL key should be already generated")
+    }
+
+    pub fn limits(&self) -> Arc<Limits> {
+        self.limits.get()
+    }
+
+    pub fn blocked_instances(&self) -> Option<Vec<String>> {
+        (*self.blocked_instances.get()).clone()
+    }
+
+    pub fn allowed_instances(&self) -> Option<Vec<String>> {
+        (*self.allowed_instances.get()).clone()
+    }
+
+    /// Applies reloadable parameters from a newly loaded config.
+    /// The change is visible to all clones of this config.
+    pub fn reload(&self, new_config: &Self) -> () {
+        self.limits.replace(&new_config.limits);
+        self.blocked_instances.replace(&new_config.blocked_instances);
+        self.allowed_instances.replace(&new_config.allowed_instances);
+        self.revision.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of times this config has been reloaded
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::Relaxed)
+    }
+
     pub fn http_socket(&self) -> String {
         match &self.http_socket {
             Some(http_socket) => http_socket.clone(),
@@ -217,3 +325,28 @@ impl Config {
             })
     }
 }
+
+#[cfg(any(test, feature = "test-utils"))]
+impl Config {
+    pub fn for_test(instance_url: &str) -> Self {
+        use apx_core::{
+            crypto::{
+                eddsa::generate_weak_ed25519_key,
+                rsa::generate_weak_rsa_key,
+            },
+        };
+        let config_yaml = format!(r#"
+database_url: ""
+storage_dir: ""
+instance_url: "{instance_url}"
+instance_title: test
+instance_short_description: test
+instance_description: test
+"#);
+        let mut config: Self = serde_yaml::from_str(&config_yaml).unwrap();
+        config.set_instance_ed25519_key(generate_weak_ed25519_key());
+        config.set_instance_rsa_key(generate_weak_rsa_key().unwrap());
+        config.set_media_url_key([0; 32]);
+        config
+    }
+}
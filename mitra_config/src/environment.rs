@@ -22,7 +22,7 @@ impl FromStr for Environment {
         let environment = match val {
             "development" => Environment::Development,
             "production" => Environment::Production,
-            _ => return Err(ConfigError("invalid environment type")),
+            _ => return Err(ConfigError::Invalid("invalid environment type")),
         };
         Ok(environment)
     }
@@ -6,6 +6,10 @@ const fn default_incoming_queue_worker_enabled() -> bool { true }
 const fn default_inbox_queue_batch_size() -> u32 { 20 }
 const fn default_fetcher_timeout() -> u64 { 30 }
 const fn default_fetcher_recursion_limit() -> u16 { 50 }
+const fn default_fetcher_retry_max_attempts() -> u32 { 3 }
+const fn default_fetcher_retry_delay() -> u64 { 1 }
+const fn default_fetcher_retry_max_delay() -> u64 { 10 }
+const fn default_fetcher_retry_after_enabled() -> bool { true }
 const fn default_deliverer_standalone() -> bool { true }
 const fn default_deliverer_pool_size() -> usize { 10 }
 const fn default_deliverer_timeout() -> u64 { 10 }
@@ -14,6 +18,7 @@ const fn default_fep_1b12_full_enabled() -> bool { true }
 const fn default_fep_ef61_gateway_enabled() -> bool { false }
 const fn default_fep_171b_public_enabled() -> bool { false }
 const fn default_activitypub_c2s_enabled() -> bool { false }
+const fn default_compress_attributed_objects() -> bool { false }
 
 #[derive(Clone, Deserialize)]
 pub struct FederationConfig {
@@ -34,6 +39,14 @@ pub struct FederationConfig {
     pub fetcher_timeout: u64,
     #[serde(default = "default_fetcher_recursion_limit")]
     pub fetcher_recursion_limit: u16,
+    #[serde(default = "default_fetcher_retry_max_attempts")]
+    pub fetcher_retry_max_attempts: u32,
+    #[serde(default = "default_fetcher_retry_delay")]
+    pub fetcher_retry_delay: u64,
+    #[serde(default = "default_fetcher_retry_max_delay")]
+    pub fetcher_retry_max_delay: u64,
+    #[serde(default = "default_fetcher_retry_after_enabled")]
+    pub fetcher_retry_after_enabled: bool,
 
     #[serde(default = "default_deliverer_timeout")]
     pub deliverer_timeout: u64,
@@ -65,6 +78,11 @@ pub struct FederationConfig {
 
     #[serde(default = "default_activitypub_c2s_enabled")]
     pub activitypub_c2s_enabled: bool,
+
+    // Store raw objects of remote posts as compressed binary data
+    // instead of JSONB (objects of portable users are not compressed)
+    #[serde(default = "default_compress_attributed_objects")]
+    pub compress_attributed_objects: bool,
 }
 
 impl Default for FederationConfig {
@@ -76,6 +94,10 @@ impl Default for FederationConfig {
             incoming_queue_worker_enabled: default_incoming_queue_worker_enabled(),
             fetcher_timeout: default_fetcher_timeout(),
             fetcher_recursion_limit: default_fetcher_recursion_limit(),
+            fetcher_retry_max_attempts: default_fetcher_retry_max_attempts(),
+            fetcher_retry_delay: default_fetcher_retry_delay(),
+            fetcher_retry_max_delay: default_fetcher_retry_max_delay(),
+            fetcher_retry_after_enabled: default_fetcher_retry_after_enabled(),
             deliverer_timeout: default_deliverer_timeout(),
             deliverer_log_response_length: default_deliverer_log_response_length(),
             deliverer_standalone: default_deliverer_standalone(),
@@ -88,6 +110,7 @@ impl Default for FederationConfig {
             fep_ef61_gateway_enabled: default_fep_ef61_gateway_enabled(),
             fep_171b_public_enabled: default_fep_171b_public_enabled(),
             activitypub_c2s_enabled: default_activitypub_c2s_enabled(),
+            compress_attributed_objects: default_compress_attributed_objects(),
         }
     }
 }
@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+const fn default_fetch_interval() -> u32 { 3600 }
+const fn default_min_fetch_interval() -> u32 { 600 }
+const fn default_entry_limit() -> u16 { 10 }
+const fn default_user_feed_limit() -> u32 { 20 }
+
+/// RSS, Atom and JSON Feed bridge
+#[derive(Clone, Deserialize)]
+pub struct FeedsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    // Default interval between fetches (in seconds)
+    #[serde(default = "default_fetch_interval")]
+    pub fetch_interval: u32,
+    // Shortest interval that can be set by admin (in seconds)
+    #[serde(default = "default_min_fetch_interval")]
+    pub min_fetch_interval: u32,
+    // Default maximum number of entries converted into posts per fetch
+    #[serde(default = "default_entry_limit")]
+    pub entry_limit: u16,
+    // Maximum number of new feeds a user can add
+    #[serde(default = "default_user_feed_limit")]
+    pub user_feed_limit: u32,
+}
+
+impl Default for FeedsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            fetch_interval: default_fetch_interval(),
+            min_fetch_interval: default_min_fetch_interval(),
+            entry_limit: default_entry_limit(),
+            user_feed_limit: default_user_feed_limit(),
+        }
+    }
+}
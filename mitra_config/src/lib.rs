@@ -1,17 +1,24 @@
+mod api_access;
 mod authentication;
 mod blockchain;
 mod config;
 mod environment;
 mod federation;
+mod feeds;
 mod instance;
 mod limits;
 mod loader;
+mod logging;
+mod media_access;
 mod metrics;
 mod registration;
+mod reloadable;
 mod retention;
 mod software;
+mod subscriptions;
 
-pub use authentication::AuthenticationMethod;
+pub use api_access::PublicApiAccessMode;
+pub use authentication::{AuthenticationMethod, PasswordPolicy};
 pub use blockchain::{
     BlockchainConfig,
     MoneroConfig,
@@ -19,12 +26,36 @@ pub use blockchain::{
 };
 pub use config::Config;
 pub use environment::Environment;
+pub use feeds::FeedsConfig;
 pub use instance::Instance;
-pub use limits::{Limits, MediaLimits, PostLimits};
-pub use loader::parse_config;
+pub use limits::{
+    DuplicatePostAction,
+    DuplicatePostLimits,
+    Limits,
+    MediaLimits,
+    PostLimits,
+    ProfileLimits,
+};
+pub use loader::{parse_config, try_parse_config};
+pub use logging::LogFormat;
+pub use media_access::{MediaAccessConfig, MediaAccessMode};
 pub use registration::{DefaultRole, RegistrationType};
 pub use software::SoftwareMetadata;
 
 #[derive(thiserror::Error, Debug)]
-#[error("{0}")]
-pub struct ConfigError(&'static str);
+pub enum ConfigError {
+    #[error("{0}")]
+    Invalid(&'static str),
+
+    #[error("failed to read config file {path}: {source}")]
+    ReadError {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("invalid config file {path}: {message}")]
+    ParseError {
+        path: String,
+        message: String,
+    },
+}
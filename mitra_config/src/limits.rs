@@ -40,16 +40,16 @@ fn parse_file_size(value: &str) -> Result<usize, ConfigError> {
     let file_size_re = Regex::new(FILE_SIZE_RE)
         .expect("regexp should be valid");
     let caps = file_size_re.captures(value)
-        .ok_or(ConfigError("invalid file size"))?;
+        .ok_or(ConfigError::Invalid("invalid file size"))?;
     let size: usize = caps["size"].to_string().parse()
-        .map_err(|_| ConfigError("invalid file size"))?;
+        .map_err(|_| ConfigError::Invalid("invalid file size"))?;
     let unit = caps["unit"].to_string().to_lowercase();
     let multiplier = match unit.as_str() {
         "k" => usize::pow(10, 3),
         "m" => usize::pow(10, 6),
         "g" => usize::pow(10, 9),
         "" => 1,
-        _ => return Err(ConfigError("invalid file size unit")),
+        _ => return Err(ConfigError::Invalid("invalid file size unit")),
     };
     Ok(size * multiplier)
 }
@@ -110,6 +110,11 @@ pub struct MediaLimits {
     // Add items to the list of supported media types
     #[serde(default)]
     extra_supported_types: Vec<String>,
+
+    // Timeout for extracting audio and video metadata with ffprobe
+    // (in seconds). `None` disables extraction.
+    #[serde(default)]
+    pub ffprobe_timeout: Option<u64>,
 }
 
 impl Default for MediaLimits {
@@ -121,6 +126,7 @@ impl Default for MediaLimits {
             emoji_size_limit: default_emoji_size_limit(),
             emoji_local_size_limit: default_emoji_local_size_limit(),
             extra_supported_types: vec![],
+            ffprobe_timeout: None,
         }
     }
 }
@@ -139,27 +145,120 @@ impl MediaLimits {
 }
 
 const fn default_post_character_limit() -> usize { 5000 }
+const fn default_post_character_remote_limit() -> usize { 50_000 }
 const fn default_attachment_limit() -> usize { 16 }
 // Mastodon's limit is 4
 // https://github.com/mastodon/mastodon/blob/v4.3.7/app/models/status.rb#L42
 const fn default_attachment_local_limit() -> usize { 4 }
+const fn default_poll_option_local_limit() -> usize { 20 }
+const fn default_poll_option_length_local_limit() -> usize { 1000 }
+const fn default_thread_depth_limit() -> u16 { 100 }
+const fn default_thread_size_limit() -> u16 { 500 }
 
 #[derive(Clone, Deserialize)]
 pub struct PostLimits {
     #[serde(default = "default_post_character_limit")]
     pub character_limit: usize,
+    #[serde(default = "default_post_character_remote_limit")]
+    pub character_remote_limit: usize,
     #[serde(default = "default_attachment_limit")]
     pub attachment_limit: usize,
     #[serde(default = "default_attachment_local_limit")]
     pub attachment_local_limit: usize,
+    #[serde(default = "default_poll_option_local_limit")]
+    pub poll_option_local_limit: usize,
+    #[serde(default = "default_poll_option_length_local_limit")]
+    pub poll_option_length_local_limit: usize,
+    // Max nesting level of replies in a thread
+    #[serde(default = "default_thread_depth_limit")]
+    pub thread_depth_limit: u16,
+    // Max number of replies returned in a single page
+    #[serde(default = "default_thread_size_limit")]
+    pub thread_size_limit: u16,
 }
 
 impl Default for PostLimits {
     fn default() -> Self {
         Self {
             character_limit: default_post_character_limit(),
+            character_remote_limit: default_post_character_remote_limit(),
             attachment_limit: default_attachment_limit(),
             attachment_local_limit: default_attachment_local_limit(),
+            poll_option_local_limit: default_poll_option_local_limit(),
+            poll_option_length_local_limit: default_poll_option_length_local_limit(),
+            thread_depth_limit: default_thread_depth_limit(),
+            thread_size_limit: default_thread_size_limit(),
+        }
+    }
+}
+
+const fn default_bio_length_limit() -> usize { 10000 }
+const fn default_field_limit() -> usize { 100 }
+const fn default_field_local_limit() -> usize { 10 }
+const fn default_field_name_length_limit() -> usize { 500 }
+const fn default_field_value_length_limit() -> usize { 5000 }
+
+// Remote profiles exceeding these limits are truncated
+#[derive(Clone, Deserialize)]
+pub struct ProfileLimits {
+    #[serde(default = "default_bio_length_limit")]
+    pub bio_length_limit: usize,
+    #[serde(default = "default_field_limit")]
+    pub field_limit: usize,
+    #[serde(default = "default_field_local_limit")]
+    pub field_local_limit: usize,
+    #[serde(default = "default_field_name_length_limit")]
+    pub field_name_length_limit: usize,
+    #[serde(default = "default_field_value_length_limit")]
+    pub field_value_length_limit: usize,
+}
+
+impl Default for ProfileLimits {
+    fn default() -> Self {
+        Self {
+            bio_length_limit: default_bio_length_limit(),
+            field_limit: default_field_limit(),
+            field_local_limit: default_field_local_limit(),
+            field_name_length_limit: default_field_name_length_limit(),
+            field_value_length_limit: default_field_value_length_limit(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicatePostAction {
+    #[default]
+    Reject,
+    // Accept post and mark it as sensitive
+    Flag,
+}
+
+const fn default_duplicate_post_window() -> u32 { 3600 }
+const fn default_duplicate_post_threshold() -> u32 { 3 }
+
+/// Duplicate post detection
+#[derive(Clone, Deserialize)]
+pub struct DuplicatePostLimits {
+    #[serde(default)]
+    pub enabled: bool,
+    // Time window (in seconds)
+    #[serde(default = "default_duplicate_post_window")]
+    pub window: u32,
+    // Max number of posts with identical content within time window
+    #[serde(default = "default_duplicate_post_threshold")]
+    pub threshold: u32,
+    #[serde(default)]
+    pub action: DuplicatePostAction,
+}
+
+impl Default for DuplicatePostLimits {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window: default_duplicate_post_window(),
+            threshold: default_duplicate_post_threshold(),
+            action: DuplicatePostAction::default(),
         }
     }
 }
@@ -170,6 +269,10 @@ pub struct Limits {
     pub media: MediaLimits,
     #[serde(default)]
     pub posts: PostLimits,
+    #[serde(default)]
+    pub profiles: ProfileLimits,
+    #[serde(default)]
+    pub duplicate_posts: DuplicatePostLimits,
 }
 
 #[cfg(test)]
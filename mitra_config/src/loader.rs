@@ -15,6 +15,7 @@ use super::{
         parse_instance_url,
     },
     software::SoftwareMetadata,
+    ConfigError,
 };
 
 const DEFAULT_CONFIG_PATH: &str = "config.yaml";
@@ -31,44 +32,61 @@ struct EnvConfig {
     http_port: Option<u32>,
 }
 
-fn parse_env() -> EnvConfig {
+fn parse_env() -> Result<EnvConfig, ConfigError> {
     dotenvy::from_filename(".env.local").ok();
     dotenvy::dotenv().ok();
     let config_path = std::env::var("CONFIG_PATH")
         .unwrap_or(default_config_path().to_string());
     let environment = std::env::var("ENVIRONMENT").ok()
-        .map(|val| Environment::from_str(&val).expect("invalid environment type"))
+        .map(|val| Environment::from_str(&val))
+        .transpose()?
         // Default depends on "production" feature flag
         .unwrap_or_default();
     let maybe_http_port = std::env::var("HTTP_PORT").ok()
-        .map(|val| u32::from_str(&val).expect("invalid port number"));
-    EnvConfig {
+        .map(|val| u32::from_str(&val))
+        .transpose()
+        .map_err(|_| ConfigError::Invalid("invalid port number"))?;
+    let env = EnvConfig {
         config_path,
         environment,
         http_port: maybe_http_port,
-    }
+    };
+    Ok(env)
 }
 
 pub fn parse_config(
     software_metadata: SoftwareMetadata,
 ) -> (Config, Vec<String>) {
-    let env = parse_env();
+    try_parse_config(software_metadata)
+        .unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// Reads and validates configuration file
+pub fn try_parse_config(
+    software_metadata: SoftwareMetadata,
+) -> Result<(Config, Vec<String>), ConfigError> {
+    let env = parse_env()?;
     let config_text = std::fs::read_to_string(&env.config_path)
-        .unwrap_or_else(|_| {
-            panic!("failed to read config from {}", env.config_path);
-        });
+        .map_err(|source| ConfigError::ReadError {
+            path: env.config_path.clone(),
+            source,
+        })?;
+    let parse_error = |message: String| ConfigError::ParseError {
+        path: env.config_path.clone(),
+        message,
+    };
     let mut unused_parameters = vec![];
     let mut config: Config = if env.config_path.ends_with(".toml") {
         let deserializer = toml::Deserializer::parse(&config_text)
-            .expect("invalid TOML config file");
+            .map_err(|error| parse_error(error.to_string()))?;
         serde_ignored::deserialize(deserializer, |path| {
             unused_parameters.push(path.to_string());
-        }).expect("invalid TOML config file")
+        }).map_err(|error| parse_error(error.to_string()))?
     } else {
         let deserializer = serde_yaml::Deserializer::from_str(&config_text);
         serde_ignored::deserialize(deserializer, |path| {
             unused_parameters.push(path.to_string());
-        }).expect("invalid YAML config file")
+        }).map_err(|error| parse_error(error.to_string()))?
     };
     let mut warnings = vec![];
     for parameter in unused_parameters {
@@ -88,18 +106,18 @@ pub fn parse_config(
     // Validate config
     config.http_socket();
     let instance_uri = parse_instance_url(&config.instance_url)
-        .expect("invalid instance URL");
+        .map_err(|_| ConfigError::Invalid("invalid instance URL"))?;
     if !is_correct_uri_scheme(&instance_uri) {
         let message = "instance_url may have incorrect URL scheme";
         warnings.push(message.to_owned());
     };
     if let Some(ref webfinger_hostname) = config.webfinger_hostname {
         if !is_same_apex_domain(instance_uri.hostname().as_str(), webfinger_hostname) {
-            panic!("invalid webfinger_hostname");
+            return Err(ConfigError::Invalid("invalid webfinger_hostname"));
         };
     };
     if config.authentication_methods.is_empty() {
-        panic!("authentication_methods must not be empty");
+        return Err(ConfigError::Invalid("authentication_methods must not be empty"));
     };
     if !config.federation.ssrf_protection_enabled {
         let message = "SSRF protection disabled";
@@ -109,11 +127,11 @@ pub fn parse_config(
         let message = "federation.fep_1b12_full_enabled parameter is deprecated";
         warnings.push(message.to_owned());
     };
-    if config.blocked_instances.is_some() {
+    if config.blocked_instances().is_some() {
         let message = "blocked_instances parameter is deprecated (use `mitra add-filter-rule`)";
         warnings.push(message.to_owned());
     };
-    if config.allowed_instances.is_some() {
+    if config.allowed_instances().is_some() {
         let message = "allowed_instances parameter is deprecated (use `mitra add-filter-rule`)";
         warnings.push(message.to_owned());
     };
@@ -123,13 +141,13 @@ pub fn parse_config(
                 BlockchainConfig::MoneroLight(MoneroLightConfig { chain_id, .. }) =>
             {
                 chain_id.monero_network()
-                    .expect("invalid monero chain ID");
+                    .map_err(|_| ConfigError::Invalid("invalid monero chain ID"))?;
             },
         };
     };
     if config.ipfs_api_url.is_some() != config.ipfs_gateway_url.is_some() {
-        panic!("both ipfs_api_url and ipfs_gateway_url must be set");
+        return Err(ConfigError::Invalid("both ipfs_api_url and ipfs_gateway_url must be set"));
     };
 
-    (config, warnings)
+    Ok((config, warnings))
 }
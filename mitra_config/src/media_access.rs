@@ -0,0 +1,36 @@
+use serde::Deserialize;
+
+const fn default_signed_url_lifetime() -> u32 { 60 * 60 * 24 * 7 }
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaAccessMode {
+    // Media files are accessible to everyone
+    #[default]
+    Public,
+    // Media attached to non-public posts requires a signed URL.
+    // Clients receive signed URLs.
+    SignedUrls,
+    // Media attached to non-public posts requires a signed URL
+    // or an authenticated request (access token or HTTP signature)
+    // from an actor who can view the post
+    AuthCheck,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct MediaAccessConfig {
+    #[serde(default)]
+    pub mode: MediaAccessMode,
+    // Lifetime of signed URLs (in seconds)
+    #[serde(default = "default_signed_url_lifetime")]
+    pub signed_url_lifetime: u32,
+}
+
+impl Default for MediaAccessConfig {
+    fn default() -> Self {
+        Self {
+            mode: MediaAccessMode::default(),
+            signed_url_lifetime: default_signed_url_lifetime(),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use serde::{Deserialize, Deserializer};
+
+/// Configuration parameter that can be replaced without restart.
+/// Clones of [`Reloadable`] share the same value.
+pub struct Reloadable<T>(Arc<ArcSwap<T>>);
+
+impl<T> Reloadable<T> {
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(ArcSwap::from_pointee(value)))
+    }
+
+    /// Returns a snapshot of the current value
+    pub fn get(&self) -> Arc<T> {
+        self.0.load_full()
+    }
+
+    pub(super) fn replace(&self, other: &Self) -> () {
+        self.0.store(other.get());
+    }
+}
+
+impl<T> Clone for Reloadable<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T: Default> Default for Reloadable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Reloadable<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reloadable_shared_between_clones() {
+        let value = Reloadable::new(1);
+        let value_clone = value.clone();
+        let snapshot = value.get();
+        value.replace(&Reloadable::new(2));
+        assert_eq!(*value_clone.get(), 2);
+        assert_eq!(*snapshot, 1);
+    }
+}
@@ -3,6 +3,7 @@ use serde::Deserialize;
 const fn default_extraneous_posts() -> Option<u32> { Some(15) }
 const fn default_empty_profiles() -> Option<u32> { Some(30) }
 const fn default_activitypub_objects() -> Option<u32> { Some(5) }
+const fn default_dead_letters() -> Option<u32> { Some(30) }
 
 // `None` disables pruning (not supported in TOML)
 #[derive(Clone, Deserialize)]
@@ -13,6 +14,25 @@ pub struct RetentionConfig {
     pub empty_profiles: Option<u32>,
     #[serde(default = "default_activitypub_objects")]
     pub activitypub_objects: Option<u32>,
+    // Tombstones of deleted remote profiles.
+    // `None` means that profile is deleted immediately.
+    #[serde(default)]
+    pub deleted_profiles: Option<u32>,
+    // Deliveries that failed after all retries.
+    #[serde(default = "default_dead_letters")]
+    pub dead_letters: Option<u32>,
+    // Raw objects of deleted remote posts.
+    // `None` means that objects are deleted together with posts.
+    #[serde(default)]
+    pub attributed_objects: Option<u32>,
+    // Activities delivered to inboxes.
+    // `None` means that activities are not stored.
+    #[serde(default)]
+    pub incoming_activities: Option<u32>,
+    // Delivery summaries of outgoing activities (audit log).
+    // `None` means that summaries are not recorded.
+    #[serde(default)]
+    pub outgoing_activities: Option<u32>,
 }
 
 impl Default for RetentionConfig {
@@ -21,6 +41,11 @@ impl Default for RetentionConfig {
             extraneous_posts: default_extraneous_posts(),
             empty_profiles: default_empty_profiles(),
             activitypub_objects: default_activitypub_objects(),
+            deleted_profiles: None,
+            dead_letters: default_dead_letters(),
+            attributed_objects: None,
+            incoming_activities: None,
+            outgoing_activities: None,
         }
     }
 }
@@ -0,0 +1,24 @@
+use serde::Deserialize;
+
+const fn default_reminder_days() -> u32 { 3 }
+const fn default_grace_period() -> u32 { 2 }
+
+#[derive(Clone, Deserialize)]
+pub struct SubscriptionsConfig {
+    // Number of days before expiration when reminder is sent
+    // (0 disables reminders)
+    #[serde(default = "default_reminder_days")]
+    pub reminder_days: u32,
+    // Number of days after expiration when subscriber is removed
+    #[serde(default = "default_grace_period")]
+    pub grace_period: u32,
+}
+
+impl Default for SubscriptionsConfig {
+    fn default() -> Self {
+        Self {
+            reminder_days: default_reminder_days(),
+            grace_period: default_grace_period(),
+        }
+    }
+}
@@ -0,0 +1,2 @@
+ALTER TABLE oauth_application ADD COLUMN is_approved BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE oauth_application ALTER COLUMN is_approved DROP DEFAULT;
@@ -0,0 +1,6 @@
+CREATE TABLE server_rule (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    rule_text VARCHAR(1000) NOT NULL,
+    rule_hint VARCHAR(1000) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1 @@
+ALTER TABLE user_account ADD COLUMN rules_accepted_at TIMESTAMP WITH TIME ZONE;
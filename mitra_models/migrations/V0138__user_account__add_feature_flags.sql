@@ -0,0 +1 @@
+ALTER TABLE user_account ADD COLUMN feature_flags TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,3 @@
+ALTER TABLE actor_profile ADD COLUMN location VARCHAR(500);
+ALTER TABLE actor_profile ADD COLUMN birthday DATE;
+ALTER TABLE actor_profile ADD COLUMN pronouns VARCHAR(200);
@@ -0,0 +1 @@
+ALTER TABLE subscription ADD COLUMN reminder_sent_at TIMESTAMP WITH TIME ZONE;
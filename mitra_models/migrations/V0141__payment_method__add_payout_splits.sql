@@ -0,0 +1,10 @@
+ALTER TABLE payment_method ADD COLUMN payout_splits JSONB NOT NULL DEFAULT '[]';
+
+CREATE TABLE invoice_payout (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    invoice_id UUID NOT NULL REFERENCES invoice (id) ON DELETE CASCADE,
+    payout_address VARCHAR(500) NOT NULL,
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    tx_id VARCHAR(200) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
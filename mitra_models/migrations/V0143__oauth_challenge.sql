@@ -0,0 +1,7 @@
+CREATE TABLE oauth_challenge (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    issuer VARCHAR(500) NOT NULL,
+    message TEXT UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
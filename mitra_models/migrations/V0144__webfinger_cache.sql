@@ -0,0 +1,5 @@
+CREATE TABLE webfinger_cache (
+    resource VARCHAR(2000) PRIMARY KEY,
+    jrd JSONB NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
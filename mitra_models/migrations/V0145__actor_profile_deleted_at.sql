@@ -0,0 +1 @@
+ALTER TABLE actor_profile ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1 @@
+CREATE INDEX post_conversation_id_reaction_count_btree ON post (conversation_id, reaction_count DESC);
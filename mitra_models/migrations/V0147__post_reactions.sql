@@ -0,0 +1,19 @@
+ALTER TABLE post ADD COLUMN reactions JSONB NOT NULL DEFAULT '[]';
+
+UPDATE post
+SET reactions = (
+    SELECT COALESCE(jsonb_agg(reaction_summary), '[]')
+    FROM (
+        SELECT
+            post_reaction.content,
+            (array_agg(emoji))[1] AS emoji,
+            count(post_reaction)::integer AS count
+        FROM post_reaction
+        LEFT JOIN emoji ON post_reaction.emoji_id = emoji.id
+        WHERE post_reaction.post_id = post.id
+        GROUP BY post_reaction.content
+    ) AS reaction_summary
+)
+WHERE EXISTS (
+    SELECT 1 FROM post_reaction WHERE post_reaction.post_id = post.id
+);
@@ -0,0 +1 @@
+ALTER TABLE post ADD COLUMN content_map JSONB NOT NULL DEFAULT '[]';
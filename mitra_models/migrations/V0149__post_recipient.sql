@@ -0,0 +1,44 @@
+CREATE TABLE post_recipient (
+    post_id UUID NOT NULL REFERENCES post (id) ON DELETE CASCADE,
+    profile_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    PRIMARY KEY (post_id, profile_id)
+);
+
+-- Local posts: mentioned actors
+INSERT INTO post_recipient (post_id, profile_id)
+SELECT post.id, post_mention.profile_id
+FROM post
+JOIN post_mention ON post_mention.post_id = post.id
+JOIN actor_profile ON actor_profile.id = post_mention.profile_id
+WHERE post.object_id IS NULL AND actor_profile.actor_json IS NOT NULL
+ON CONFLICT DO NOTHING;
+
+-- Local posts: authors of parent posts
+INSERT INTO post_recipient (post_id, profile_id)
+SELECT post.id, in_reply_to.author_id
+FROM post
+JOIN post AS in_reply_to ON in_reply_to.id = post.in_reply_to_id
+JOIN actor_profile ON actor_profile.id = in_reply_to.author_id
+WHERE post.object_id IS NULL AND actor_profile.actor_json IS NOT NULL
+ON CONFLICT DO NOTHING;
+
+-- Local posts: groups
+INSERT INTO post_recipient (post_id, profile_id)
+SELECT post.id, post.group_id
+FROM post
+JOIN actor_profile ON actor_profile.id = post.group_id
+WHERE post.object_id IS NULL AND actor_profile.actor_json IS NOT NULL
+ON CONFLICT DO NOTHING;
+
+-- Local posts with conversation visibility: conversation owners
+INSERT INTO post_recipient (post_id, profile_id)
+SELECT post.id, root.author_id
+FROM post
+JOIN conversation ON conversation.id = post.conversation_id
+JOIN post AS root ON root.id = conversation.root_id
+JOIN actor_profile ON actor_profile.id = root.author_id
+WHERE
+    post.object_id IS NULL
+    AND post.visibility = 5
+    AND actor_profile.actor_json IS NOT NULL
+ON CONFLICT DO NOTHING;
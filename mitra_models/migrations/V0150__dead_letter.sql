@@ -0,0 +1,11 @@
+CREATE TABLE dead_letter (
+    id UUID PRIMARY KEY,
+    activity_id VARCHAR(2000) NOT NULL,
+    inbox VARCHAR(2000) NOT NULL,
+    job_data JSONB NOT NULL,
+    errors TEXT[] NOT NULL,
+    first_failed_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX dead_letter_created_at_btree ON dead_letter (created_at);
@@ -0,0 +1 @@
+CREATE INDEX media_attachment_file_name_btree ON media_attachment ((media ->> 'file_name'));
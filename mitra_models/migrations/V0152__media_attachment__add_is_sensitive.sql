@@ -0,0 +1 @@
+ALTER TABLE media_attachment ADD COLUMN is_sensitive BOOLEAN NOT NULL DEFAULT FALSE;
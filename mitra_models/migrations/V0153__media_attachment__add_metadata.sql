@@ -0,0 +1 @@
+ALTER TABLE media_attachment ADD COLUMN metadata JSONB;
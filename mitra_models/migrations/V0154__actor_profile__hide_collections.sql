@@ -0,0 +1,2 @@
+ALTER TABLE actor_profile ADD COLUMN hide_followers BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE actor_profile ADD COLUMN hide_following BOOLEAN NOT NULL DEFAULT FALSE;
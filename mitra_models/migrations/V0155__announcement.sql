@@ -0,0 +1,21 @@
+CREATE TABLE announcement (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    content TEXT NOT NULL,
+    published_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE announcement_read (
+    announcement_id INTEGER NOT NULL REFERENCES announcement (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    PRIMARY KEY (announcement_id, user_id)
+);
+
+CREATE TABLE announcement_reaction (
+    announcement_id INTEGER NOT NULL REFERENCES announcement (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    content VARCHAR(102) NOT NULL,
+    emoji_id UUID REFERENCES emoji (id) ON DELETE CASCADE,
+    PRIMARY KEY (announcement_id, user_id, content)
+);
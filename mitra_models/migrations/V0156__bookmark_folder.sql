@@ -0,0 +1,8 @@
+CREATE TABLE bookmark_folder (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    folder_name VARCHAR(200) NOT NULL,
+    UNIQUE (owner_id, folder_name)
+);
+
+ALTER TABLE bookmark ADD COLUMN folder_id INTEGER REFERENCES bookmark_folder (id) ON DELETE SET NULL;
@@ -0,0 +1,4 @@
+CREATE TABLE post_stats (
+    post_id UUID PRIMARY KEY REFERENCES post (id) ON DELETE CASCADE,
+    view_count INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,12 @@
+CREATE TABLE incoming_activity (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    activity_id VARCHAR(2000) NOT NULL,
+    activity JSONB NOT NULL,
+    hostname VARCHAR(500) NOT NULL,
+    recipient_id VARCHAR(2000) NOT NULL,
+    sender_id VARCHAR(2000) NOT NULL,
+    is_authenticated BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX incoming_activity_activity_id_btree ON incoming_activity (activity_id);
+CREATE INDEX incoming_activity_hostname_btree ON incoming_activity (hostname);
@@ -0,0 +1 @@
+CREATE INDEX incoming_activity_created_at_btree ON incoming_activity (created_at);
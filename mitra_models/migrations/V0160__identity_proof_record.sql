@@ -0,0 +1,11 @@
+CREATE TABLE identity_proof_record (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    profile_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    proof JSONB NOT NULL,
+    valid_from TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    valid_until TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX identity_proof_record_profile_id_btree ON identity_proof_record (profile_id);
+INSERT INTO identity_proof_record (profile_id, proof)
+SELECT actor_profile.id, proof
+FROM actor_profile, jsonb_array_elements(actor_profile.identity_proofs) AS proof;
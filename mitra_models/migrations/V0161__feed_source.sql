@@ -0,0 +1,18 @@
+DROP INDEX automated_account_account_type_idx;
+CREATE UNIQUE INDEX automated_account_account_type_idx ON automated_account (account_type) WHERE account_type NOT IN (4, 5);
+
+CREATE TABLE feed_source (
+    id UUID PRIMARY KEY REFERENCES automated_account (id) ON DELETE CASCADE,
+    feed_url VARCHAR(2000) UNIQUE NOT NULL,
+    fetch_interval INTEGER NOT NULL,
+    entry_limit SMALLINT NOT NULL,
+    fetched_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE feed_entry (
+    feed_id UUID NOT NULL REFERENCES feed_source (id) ON DELETE CASCADE,
+    entry_id VARCHAR(2000) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (feed_id, entry_id)
+);
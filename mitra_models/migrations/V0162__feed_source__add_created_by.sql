@@ -0,0 +1 @@
+ALTER TABLE feed_source ADD COLUMN created_by UUID REFERENCES user_account (id) ON DELETE SET NULL;
@@ -0,0 +1,17 @@
+CREATE TABLE outgoing_activity_record (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    activity_id VARCHAR(2000) NOT NULL,
+    activity_type VARCHAR(100) NOT NULL,
+    object_id VARCHAR(2000),
+    sender_id UUID REFERENCES actor_profile (id) ON DELETE CASCADE,
+    audience TEXT[] NOT NULL,
+    recipient_id VARCHAR(2000) NOT NULL,
+    inbox VARCHAR(2000) NOT NULL,
+    delivery_status SMALLINT NOT NULL,
+    attempt_count INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (activity_id, inbox)
+);
+CREATE INDEX outgoing_activity_record_sender_id_btree ON outgoing_activity_record (sender_id);
+CREATE INDEX outgoing_activity_record_created_at_btree ON outgoing_activity_record (created_at);
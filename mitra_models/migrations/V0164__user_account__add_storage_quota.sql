@@ -0,0 +1 @@
+ALTER TABLE user_account ADD COLUMN storage_quota BIGINT;
@@ -0,0 +1,9 @@
+CREATE TABLE profile_link (
+    profile_id UUID PRIMARY KEY REFERENCES actor_profile (id) ON DELETE CASCADE,
+    primary_profile_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    is_manual BOOLEAN NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (profile_id != primary_profile_id)
+);
+CREATE INDEX profile_link_primary_profile_id_btree ON profile_link (primary_profile_id);
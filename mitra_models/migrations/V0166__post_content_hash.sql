@@ -0,0 +1,9 @@
+CREATE TABLE post_content_hash (
+    id SERIAL PRIMARY KEY,
+    post_id UUID UNIQUE NOT NULL REFERENCES post (id) ON DELETE CASCADE,
+    author_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    content_hash BYTEA NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX post_content_hash_author_id_content_hash_btree ON post_content_hash (author_id, content_hash);
+CREATE INDEX post_content_hash_created_at_btree ON post_content_hash (created_at);
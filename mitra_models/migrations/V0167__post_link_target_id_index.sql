@@ -0,0 +1 @@
+CREATE INDEX post_link_target_id_btree ON post_link (target_id);
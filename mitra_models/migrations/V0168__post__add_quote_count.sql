@@ -0,0 +1,11 @@
+ALTER TABLE post ADD COLUMN quote_count INTEGER NOT NULL CHECK (quote_count >= 0) DEFAULT 0;
+UPDATE post
+SET quote_count = quote.count
+FROM (
+    SELECT post_link.target_id, count(*)
+    FROM post_link
+    JOIN post ON post_link.source_id = post.id
+    WHERE post.visibility = 1
+    GROUP BY post_link.target_id
+) AS quote
+WHERE post.id = quote.target_id;
@@ -0,0 +1,16 @@
+ALTER TABLE activitypub_object ADD COLUMN post_deleted_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE activitypub_object ADD COLUMN object_data_compressed BYTEA;
+ALTER TABLE activitypub_object ALTER COLUMN object_data_compressed SET STORAGE EXTERNAL;
+ALTER TABLE activitypub_object ALTER COLUMN object_data DROP NOT NULL;
+ALTER TABLE activitypub_object ADD CONSTRAINT activitypub_object_data_check CHECK ((object_data IS NULL) != (object_data_compressed IS NULL));
+-- lz4 is available only if server was built with --with-lz4
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM pg_settings
+        WHERE name = 'default_toast_compression'
+            AND 'lz4' = ANY(enumvals)
+    ) THEN
+        ALTER TABLE activitypub_object ALTER COLUMN object_data SET COMPRESSION lz4;
+    END IF;
+END $$;
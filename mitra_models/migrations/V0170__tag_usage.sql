@@ -0,0 +1,9 @@
+CREATE TABLE tag_usage (
+    tag_id INTEGER NOT NULL REFERENCES tag (id) ON DELETE CASCADE,
+    post_id UUID NOT NULL REFERENCES post (id) ON DELETE CASCADE,
+    author_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (tag_id, post_id)
+);
+CREATE INDEX tag_usage_created_at_btree ON tag_usage (created_at);
+ALTER TABLE tag ADD COLUMN trendable BOOLEAN;
@@ -0,0 +1,6 @@
+CREATE TABLE username_redirect (
+    username VARCHAR(100) PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX username_redirect_user_id_btree ON username_redirect (user_id);
@@ -0,0 +1,16 @@
+CREATE TABLE scheduled_post (
+    id UUID PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    post_data JSONB,
+    scheduled_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    taken_at TIMESTAMP WITH TIME ZONE,
+    repost_of_id UUID REFERENCES post (id) ON DELETE CASCADE,
+    repost_visibility SMALLINT,
+    replace_existing BOOLEAN NOT NULL DEFAULT FALSE,
+    UNIQUE (owner_id, repost_of_id),
+    CHECK ((post_data IS NULL) != (repost_of_id IS NULL)),
+    CHECK ((repost_of_id IS NULL) = (repost_visibility IS NULL))
+);
+CREATE INDEX scheduled_post_owner_id_btree ON scheduled_post (owner_id);
+CREATE INDEX scheduled_post_scheduled_at_btree ON scheduled_post (scheduled_at);
@@ -0,0 +1,12 @@
+ALTER TABLE custom_feed ADD COLUMN is_shared BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE custom_feed ADD COLUMN actor_id UUID UNIQUE REFERENCES automated_account (id) ON DELETE SET NULL;
+
+CREATE TABLE custom_feed_subscription (
+    feed_id INTEGER NOT NULL REFERENCES custom_feed (id) ON DELETE CASCADE,
+    subscriber_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (feed_id, subscriber_id)
+);
+
+DROP INDEX automated_account_account_type_idx;
+CREATE UNIQUE INDEX automated_account_account_type_idx ON automated_account (account_type) WHERE account_type NOT IN (4, 5, 6);
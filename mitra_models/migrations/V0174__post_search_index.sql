@@ -0,0 +1,24 @@
+CREATE TABLE post_search_index (
+    post_id UUID PRIMARY KEY REFERENCES post (id) ON DELETE CASCADE,
+    search_vector TSVECTOR NOT NULL
+);
+CREATE INDEX post_search_index_search_vector_index ON post_search_index USING GIN (search_vector);
+
+INSERT INTO post_search_index (post_id, search_vector)
+SELECT
+    post.id,
+    to_tsvector(
+        COALESCE(
+            (
+                SELECT property_value #>> '{}'
+                FROM internal_property
+                WHERE property_name = 'default_fts_config'
+            ),
+            'simple'
+        )::regconfig,
+        COALESCE(post.title, '') || ' ' || post.content
+    )
+FROM post
+WHERE post.repost_of_id IS NULL;
+
+DROP INDEX post_content_tsvector_simple_index;
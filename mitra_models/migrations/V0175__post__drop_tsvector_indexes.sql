@@ -0,0 +1,15 @@
+-- Remove full-text search indexes created manually by administrators
+-- (replaced by post_search_index table)
+DO $$
+DECLARE
+    index_name TEXT;
+BEGIN
+    FOR index_name IN
+        SELECT indexname FROM pg_indexes
+        WHERE
+            tablename = 'post'
+            AND indexname LIKE 'post\_content\_tsvector\_%\_index'
+    LOOP
+        EXECUTE format('DROP INDEX %I', index_name);
+    END LOOP;
+END $$;
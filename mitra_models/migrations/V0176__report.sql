@@ -0,0 +1,11 @@
+CREATE TABLE report (
+    id UUID PRIMARY KEY,
+    reporter_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    target_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    post_ids UUID[] NOT NULL,
+    comment TEXT NOT NULL,
+    object_id VARCHAR(2000) UNIQUE,
+    is_forwarded BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX report_target_id_btree ON report (target_id);
@@ -0,0 +1,10 @@
+ALTER TABLE report ADD COLUMN assigned_account_id UUID REFERENCES user_account (id) ON DELETE SET NULL;
+ALTER TABLE report ADD COLUMN resolved_at TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE report_note (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    report_id UUID NOT NULL REFERENCES report (id) ON DELETE CASCADE,
+    author_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
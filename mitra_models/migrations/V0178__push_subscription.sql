@@ -0,0 +1,12 @@
+CREATE TABLE push_subscription (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    oauth_token_id INTEGER UNIQUE NOT NULL REFERENCES oauth_token (id) ON DELETE CASCADE,
+    endpoint VARCHAR(2000) NOT NULL,
+    public_key BYTEA NOT NULL,
+    auth_secret BYTEA NOT NULL,
+    alerts JSONB NOT NULL,
+    policy SMALLINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX push_subscription_owner_id_btree ON push_subscription (owner_id);
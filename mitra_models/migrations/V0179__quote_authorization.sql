@@ -0,0 +1,11 @@
+CREATE TABLE quote_authorization (
+    id UUID PRIMARY KEY,
+    quoted_post_id UUID NOT NULL REFERENCES post (id) ON DELETE CASCADE,
+    quote_post_id UUID UNIQUE REFERENCES post (id) ON DELETE CASCADE,
+    quote_object_id VARCHAR(2000),
+    object_id VARCHAR(2000) UNIQUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (quoted_post_id, quote_object_id),
+    CHECK ((quote_post_id IS NULL) != (quote_object_id IS NULL)),
+    CHECK ((quote_post_id IS NULL) = (object_id IS NULL))
+);
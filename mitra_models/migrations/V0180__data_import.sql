@@ -0,0 +1,10 @@
+CREATE TABLE data_import (
+    id UUID PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    import_type SMALLINT NOT NULL,
+    import_status SMALLINT NOT NULL,
+    entries JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX data_import_owner_id_btree ON data_import (owner_id);
@@ -0,0 +1 @@
+ALTER TABLE user_account ADD COLUMN moved_to VARCHAR(2000);
@@ -12,6 +12,18 @@ CREATE TABLE background_job (
     updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
+CREATE TABLE dead_letter (
+    id UUID PRIMARY KEY,
+    activity_id VARCHAR(2000) NOT NULL,
+    inbox VARCHAR(2000) NOT NULL,
+    job_data JSONB NOT NULL,
+    errors TEXT[] NOT NULL,
+    first_failed_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX dead_letter_created_at_btree ON dead_letter (created_at);
+
 CREATE TABLE instance (
     hostname VARCHAR(100) PRIMARY KEY
 );
@@ -24,6 +36,13 @@ CREATE TABLE filter_rule (
     UNIQUE (target, filter_action)
 );
 
+CREATE TABLE server_rule (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    rule_text VARCHAR(1000) NOT NULL,
+    rule_hint VARCHAR(1000) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
 CREATE TABLE actor_profile (
     id UUID PRIMARY KEY,
     user_id UUID UNIQUE, -- FK is added later
@@ -63,11 +82,36 @@ CREATE TABLE actor_profile (
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
     updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
     unreachable_since TIMESTAMP WITH TIME ZONE,
+    location VARCHAR(500),
+    birthday DATE,
+    pronouns VARCHAR(200),
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    hide_followers BOOLEAN NOT NULL DEFAULT FALSE,
+    hide_following BOOLEAN NOT NULL DEFAULT FALSE,
     CHECK (user_id IS NULL OR user_id = id),
     CHECK (automated_account_id IS NULL OR automated_account_id = id),
     CHECK (portable_user_id IS NULL OR portable_user_id = id)
 );
 
+CREATE TABLE identity_proof_record (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    profile_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    proof JSONB NOT NULL,
+    valid_from TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    valid_until TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX identity_proof_record_profile_id_btree ON identity_proof_record (profile_id);
+
+CREATE TABLE profile_link (
+    profile_id UUID PRIMARY KEY REFERENCES actor_profile (id) ON DELETE CASCADE,
+    primary_profile_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    is_manual BOOLEAN NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (profile_id != primary_profile_id)
+);
+CREATE INDEX profile_link_primary_profile_id_btree ON profile_link (primary_profile_id);
+
 CREATE TABLE user_invite_code (
     code VARCHAR(100) PRIMARY KEY,
     used BOOLEAN NOT NULL DEFAULT FALSE,
@@ -86,13 +130,31 @@ CREATE TABLE user_account (
     user_role SMALLINT NOT NULL,
     client_config JSONB NOT NULL DEFAULT '{}',
     shared_client_config JSONB NOT NULL DEFAULT '{}',
-    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    rules_accepted_at TIMESTAMP WITH TIME ZONE,
+    feature_flags TEXT[] NOT NULL DEFAULT '{}',
+    storage_quota BIGINT,
+    moved_to VARCHAR(2000)
 );
 
 ALTER TABLE actor_profile
     ADD CONSTRAINT actor_profile_user_id_fkey
     FOREIGN KEY (user_id) REFERENCES user_account (id) ON DELETE RESTRICT;
 
+CREATE TABLE username_redirect (
+    username VARCHAR(100) PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX username_redirect_user_id_btree ON username_redirect (user_id);
+
+CREATE TABLE user_recovery_code (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    code_digest BYTEA UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
 CREATE TABLE caip122_nonce (
     account_id VARCHAR(500) NOT NULL,
     nonce VARCHAR(100) NOT NULL,
@@ -108,6 +170,7 @@ CREATE TABLE oauth_application (
     redirect_uri VARCHAR(2000) NOT NULL,
     client_id UUID UNIQUE NOT NULL,
     client_secret VARCHAR(100) NOT NULL,
+    is_approved BOOLEAN NOT NULL,
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
@@ -130,6 +193,38 @@ CREATE TABLE oauth_token (
     expires_at TIMESTAMP WITH TIME ZONE NOT NULL
 );
 
+CREATE TABLE oauth_challenge (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    issuer VARCHAR(500) NOT NULL,
+    message TEXT UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE TABLE push_subscription (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    oauth_token_id INTEGER UNIQUE NOT NULL REFERENCES oauth_token (id) ON DELETE CASCADE,
+    endpoint VARCHAR(2000) NOT NULL,
+    public_key BYTEA NOT NULL,
+    auth_secret BYTEA NOT NULL,
+    alerts JSONB NOT NULL,
+    policy SMALLINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX push_subscription_owner_id_btree ON push_subscription (owner_id);
+
+CREATE TABLE data_import (
+    id UUID PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    import_type SMALLINT NOT NULL,
+    import_status SMALLINT NOT NULL,
+    entries JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX data_import_owner_id_btree ON data_import (owner_id);
+
 CREATE TABLE automated_account (
     id UUID PRIMARY KEY REFERENCES actor_profile (id) ON DELETE CASCADE,
     account_type SMALLINT NOT NULL,
@@ -138,12 +233,29 @@ CREATE TABLE automated_account (
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE UNIQUE INDEX automated_account_account_type_idx ON automated_account (account_type) WHERE account_type != 4;
+CREATE UNIQUE INDEX automated_account_account_type_idx ON automated_account (account_type) WHERE account_type NOT IN (4, 5, 6);
 
 ALTER TABLE actor_profile
     ADD CONSTRAINT actor_profile_automated_account_id_fkey
     FOREIGN KEY (automated_account_id) REFERENCES automated_account (id) ON DELETE RESTRICT;
 
+CREATE TABLE feed_source (
+    id UUID PRIMARY KEY REFERENCES automated_account (id) ON DELETE CASCADE,
+    feed_url VARCHAR(2000) UNIQUE NOT NULL,
+    fetch_interval INTEGER NOT NULL,
+    entry_limit SMALLINT NOT NULL,
+    fetched_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by UUID REFERENCES user_account (id) ON DELETE SET NULL
+);
+
+CREATE TABLE feed_entry (
+    feed_id UUID NOT NULL REFERENCES feed_source (id) ON DELETE CASCADE,
+    entry_id VARCHAR(2000) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (feed_id, entry_id)
+);
+
 CREATE TABLE portable_user_account (
     id UUID PRIMARY KEY REFERENCES actor_profile (id) ON DELETE CASCADE,
     rsa_secret_key BYTEA NOT NULL,
@@ -201,6 +313,9 @@ CREATE TABLE post (
     ipfs_cid VARCHAR(200),
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
     updated_at TIMESTAMP WITH TIME ZONE,
+    reactions JSONB NOT NULL DEFAULT '[]',
+    content_map JSONB NOT NULL DEFAULT '[]',
+    quote_count INTEGER NOT NULL CHECK (quote_count >= 0) DEFAULT 0,
     UNIQUE (author_id, repost_of_id),
     CHECK ((conversation_id IS NULL) != (repost_of_id IS NULL))
 );
@@ -211,8 +326,23 @@ CREATE INDEX post_group_id_index ON post (group_id);
 CREATE INDEX post_id_author_id_btree ON post (id, author_id);
 CREATE INDEX post_author_id_is_pinned_btree ON post (author_id, is_pinned);
 CREATE INDEX post_conversation_id_btree ON post (conversation_id);
--- all post_content_tsvector_*_index names are reserved
-CREATE INDEX post_content_tsvector_simple_index ON post USING GIN (to_tsvector('simple', COALESCE(title, '') || ' ' || content));
+CREATE INDEX post_conversation_id_reaction_count_btree ON post (conversation_id, reaction_count DESC);
+
+CREATE TABLE post_search_index (
+    post_id UUID PRIMARY KEY REFERENCES post (id) ON DELETE CASCADE,
+    search_vector TSVECTOR NOT NULL
+);
+CREATE INDEX post_search_index_search_vector_index ON post_search_index USING GIN (search_vector);
+
+CREATE TABLE post_content_hash (
+    id SERIAL PRIMARY KEY,
+    post_id UUID UNIQUE NOT NULL REFERENCES post (id) ON DELETE CASCADE,
+    author_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    content_hash BYTEA NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX post_content_hash_author_id_content_hash_btree ON post_content_hash (author_id, content_hash);
+CREATE INDEX post_content_hash_created_at_btree ON post_content_hash (created_at);
 
 CREATE TABLE conversation (
     id UUID PRIMARY KEY,
@@ -277,10 +407,13 @@ CREATE TABLE media_attachment (
     description TEXT,
     ipfs_cid VARCHAR(200),
     post_id UUID REFERENCES post (id) ON DELETE CASCADE,
+    is_sensitive BOOLEAN NOT NULL DEFAULT FALSE,
+    metadata JSONB,
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
 );
 
 CREATE INDEX media_attachment_post_id_btree ON media_attachment (post_id);
+CREATE INDEX media_attachment_file_name_btree ON media_attachment ((media ->> 'file_name'));
 
 CREATE TABLE post_mention (
     id INTEGER UNIQUE GENERATED ALWAYS AS IDENTITY,
@@ -289,9 +422,16 @@ CREATE TABLE post_mention (
     PRIMARY KEY (post_id, profile_id)
 );
 
+CREATE TABLE post_recipient (
+    post_id UUID NOT NULL REFERENCES post (id) ON DELETE CASCADE,
+    profile_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    PRIMARY KEY (post_id, profile_id)
+);
+
 CREATE TABLE tag (
     id SERIAL PRIMARY KEY,
-    tag_name VARCHAR(100) UNIQUE NOT NULL
+    tag_name VARCHAR(100) UNIQUE NOT NULL,
+    trendable BOOLEAN
 );
 
 CREATE TABLE post_tag (
@@ -300,6 +440,15 @@ CREATE TABLE post_tag (
     PRIMARY KEY (post_id, tag_id)
 );
 
+CREATE TABLE tag_usage (
+    tag_id INTEGER NOT NULL REFERENCES tag (id) ON DELETE CASCADE,
+    post_id UUID NOT NULL REFERENCES post (id) ON DELETE CASCADE,
+    author_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (tag_id, post_id)
+);
+CREATE INDEX tag_usage_created_at_btree ON tag_usage (created_at);
+
 CREATE TABLE post_link (
     source_id UUID NOT NULL REFERENCES post (id) ON DELETE CASCADE,
     target_id UUID NOT NULL REFERENCES post (id) ON DELETE CASCADE,
@@ -307,6 +456,37 @@ CREATE TABLE post_link (
     CHECK (source_id != target_id)
 );
 
+CREATE INDEX post_link_target_id_btree ON post_link (target_id);
+
+CREATE TABLE quote_authorization (
+    id UUID PRIMARY KEY,
+    quoted_post_id UUID NOT NULL REFERENCES post (id) ON DELETE CASCADE,
+    quote_post_id UUID UNIQUE REFERENCES post (id) ON DELETE CASCADE,
+    quote_object_id VARCHAR(2000),
+    object_id VARCHAR(2000) UNIQUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (quoted_post_id, quote_object_id),
+    CHECK ((quote_post_id IS NULL) != (quote_object_id IS NULL)),
+    CHECK ((quote_post_id IS NULL) = (object_id IS NULL))
+);
+
+CREATE TABLE scheduled_post (
+    id UUID PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    post_data JSONB,
+    scheduled_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    taken_at TIMESTAMP WITH TIME ZONE,
+    repost_of_id UUID REFERENCES post (id) ON DELETE CASCADE,
+    repost_visibility SMALLINT,
+    replace_existing BOOLEAN NOT NULL DEFAULT FALSE,
+    UNIQUE (owner_id, repost_of_id),
+    CHECK ((post_data IS NULL) != (repost_of_id IS NULL)),
+    CHECK ((repost_of_id IS NULL) = (repost_visibility IS NULL))
+);
+CREATE INDEX scheduled_post_owner_id_btree ON scheduled_post (owner_id);
+CREATE INDEX scheduled_post_scheduled_at_btree ON scheduled_post (scheduled_at);
+
 CREATE TABLE emoji (
     id UUID PRIMARY KEY,
     emoji_name VARCHAR(100) NOT NULL,
@@ -327,16 +507,29 @@ CREATE TABLE post_emoji (
     PRIMARY KEY (post_id, emoji_id)
 );
 
+CREATE TABLE post_stats (
+    post_id UUID PRIMARY KEY REFERENCES post (id) ON DELETE CASCADE,
+    view_count INTEGER NOT NULL DEFAULT 0
+);
+
 CREATE TABLE profile_emoji (
     profile_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
     emoji_id UUID NOT NULL REFERENCES emoji (id) ON DELETE CASCADE,
     PRIMARY KEY (profile_id, emoji_id)
 );
 
+CREATE TABLE bookmark_folder (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    folder_name VARCHAR(200) NOT NULL,
+    UNIQUE (owner_id, folder_name)
+);
+
 CREATE TABLE bookmark (
     id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
     owner_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
     post_id UUID NOT NULL REFERENCES post (id) ON DELETE CASCADE,
+    folder_id INTEGER REFERENCES bookmark_folder (id) ON DELETE SET NULL,
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
     UNIQUE (owner_id, post_id)
 );
@@ -345,6 +538,8 @@ CREATE TABLE custom_feed (
     id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
     owner_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
     feed_name VARCHAR(200) NOT NULL,
+    is_shared BOOLEAN NOT NULL DEFAULT FALSE,
+    actor_id UUID UNIQUE REFERENCES automated_account (id) ON DELETE SET NULL,
     UNIQUE (owner_id, feed_name)
 );
 
@@ -354,6 +549,13 @@ CREATE TABLE custom_feed_source (
     PRIMARY KEY (feed_id, source_id)
 );
 
+CREATE TABLE custom_feed_subscription (
+    feed_id INTEGER NOT NULL REFERENCES custom_feed (id) ON DELETE CASCADE,
+    subscriber_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (feed_id, subscriber_id)
+);
+
 CREATE TABLE timeline_marker (
     id SERIAL PRIMARY KEY,
     user_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
@@ -372,6 +574,7 @@ CREATE TABLE payment_method (
     view_key VARCHAR(500),
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    payout_splits JSONB NOT NULL DEFAULT '[]',
     UNIQUE (owner_id, chain_id)
 );
 
@@ -392,12 +595,22 @@ CREATE TABLE invoice (
     CHECK (sender_id != recipient_id)
 );
 
+CREATE TABLE invoice_payout (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    invoice_id UUID NOT NULL REFERENCES invoice (id) ON DELETE CASCADE,
+    payout_address VARCHAR(500) NOT NULL,
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    tx_id VARCHAR(200) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
 CREATE TABLE subscription (
     id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
     sender_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
     recipient_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
     expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
     updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    reminder_sent_at TIMESTAMP WITH TIME ZONE,
     UNIQUE (sender_id, recipient_id),
     CHECK (sender_id != recipient_id)
 );
@@ -413,14 +626,42 @@ CREATE TABLE notification (
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
 );
 
+CREATE TABLE report (
+    id UUID PRIMARY KEY,
+    reporter_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    target_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
+    post_ids UUID[] NOT NULL,
+    comment TEXT NOT NULL,
+    object_id VARCHAR(2000) UNIQUE,
+    is_forwarded BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    assigned_account_id UUID REFERENCES user_account (id) ON DELETE SET NULL,
+    resolved_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX report_target_id_btree ON report (target_id);
+
+CREATE TABLE report_note (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    report_id UUID NOT NULL REFERENCES report (id) ON DELETE CASCADE,
+    author_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
 CREATE TABLE activitypub_object (
     object_id VARCHAR(2000) PRIMARY KEY,
-    object_data JSONB NOT NULL,
+    object_data JSONB,
     profile_id UUID UNIQUE REFERENCES actor_profile (id) ON DELETE CASCADE,
     post_id UUID UNIQUE REFERENCES post (id) ON DELETE CASCADE,
-    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    post_deleted_at TIMESTAMP WITH TIME ZONE,
+    object_data_compressed BYTEA,
+    CONSTRAINT activitypub_object_data_check CHECK ((object_data IS NULL) != (object_data_compressed IS NULL))
 );
 
+ALTER TABLE activitypub_object
+    ALTER COLUMN object_data_compressed SET STORAGE EXTERNAL;
+
 CREATE TABLE activitypub_collection_item (
     owner_id UUID NOT NULL REFERENCES actor_profile (id) ON DELETE CASCADE,
     collection_id VARCHAR(2000) NOT NULL,
@@ -429,6 +670,44 @@ CREATE TABLE activitypub_collection_item (
     UNIQUE (collection_id, object_id)
 );
 
+CREATE TABLE incoming_activity (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    activity_id VARCHAR(2000) NOT NULL,
+    activity JSONB NOT NULL,
+    hostname VARCHAR(500) NOT NULL,
+    recipient_id VARCHAR(2000) NOT NULL,
+    sender_id VARCHAR(2000) NOT NULL,
+    is_authenticated BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX incoming_activity_activity_id_btree ON incoming_activity (activity_id);
+CREATE INDEX incoming_activity_hostname_btree ON incoming_activity (hostname);
+CREATE INDEX incoming_activity_created_at_btree ON incoming_activity (created_at);
+
+CREATE TABLE outgoing_activity_record (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    activity_id VARCHAR(2000) NOT NULL,
+    activity_type VARCHAR(100) NOT NULL,
+    object_id VARCHAR(2000),
+    sender_id UUID REFERENCES actor_profile (id) ON DELETE CASCADE,
+    audience TEXT[] NOT NULL,
+    recipient_id VARCHAR(2000) NOT NULL,
+    inbox VARCHAR(2000) NOT NULL,
+    delivery_status SMALLINT NOT NULL,
+    attempt_count INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (activity_id, inbox)
+);
+CREATE INDEX outgoing_activity_record_sender_id_btree ON outgoing_activity_record (sender_id);
+CREATE INDEX outgoing_activity_record_created_at_btree ON outgoing_activity_record (created_at);
+
+CREATE TABLE webfinger_cache (
+    resource VARCHAR(2000) PRIMARY KEY,
+    jrd JSONB NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
 CREATE TABLE activitypub_media (
     owner_id UUID NOT NULL REFERENCES portable_user_account (id) ON DELETE CASCADE,
     media JSONB NOT NULL,
@@ -437,6 +716,28 @@ CREATE TABLE activitypub_media (
     UNIQUE (owner_id, digest)
 );
 
+CREATE TABLE announcement (
+    id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    content TEXT NOT NULL,
+    published_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE announcement_read (
+    announcement_id INTEGER NOT NULL REFERENCES announcement (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    PRIMARY KEY (announcement_id, user_id)
+);
+
+CREATE TABLE announcement_reaction (
+    announcement_id INTEGER NOT NULL REFERENCES announcement (id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES user_account (id) ON DELETE CASCADE,
+    content VARCHAR(102) NOT NULL,
+    emoji_id UUID REFERENCES emoji (id) ON DELETE CASCADE,
+    PRIMARY KEY (announcement_id, user_id, content)
+);
+
 CREATE MATERIALIZED VIEW latest_post AS
     SELECT
         author_id,
@@ -2,6 +2,7 @@ use apx_core::{
     caip2::{Namespace as ChainNamespace},
     caip10::{AccountId as ChainAccountId},
     crypto::rsa::rsa_secret_key_to_pkcs1_der,
+    crypto::eddsa::Ed25519SecretKey,
     did::Did,
 };
 use chrono::{DateTime, Utc};
@@ -39,6 +40,7 @@ use super::types::{
     DbInviteCode,
     DbPortableUser,
     DbUser,
+    FeatureFlag,
     PortableUser,
     PortableUserData,
     Role,
@@ -46,7 +48,12 @@ use super::types::{
     User,
     UserCreateData,
 };
-use super::utils::generate_invite_code;
+use super::utils::{
+    generate_invite_code,
+    generate_recovery_code,
+    hash_recovery_code,
+    RECOVERY_CODE_COUNT,
+};
 
 pub async fn create_invite_code(
     db_client: &impl DatabaseClient,
@@ -127,6 +134,11 @@ async fn check_local_username_unique(
                 OR portable_user_id IS NOT NULL
             )
             AND actor_profile.username ILIKE $1
+        UNION ALL
+        -- Previous usernames can't be taken by other users
+        SELECT 1
+        FROM username_redirect
+        WHERE username ILIKE $1
         LIMIT 1
         ",
         &[&username],
@@ -171,6 +183,9 @@ pub async fn create_user(
         identity_proofs: vec![],
         payment_options: vec![],
         extra_fields: vec![],
+        location: None,
+        birthday: None,
+        pronouns: None,
         aliases: vec![],
         emojis: vec![],
         actor_json: None,
@@ -187,9 +202,10 @@ pub async fn create_user(
             rsa_private_key,
             ed25519_private_key,
             invite_code,
-            user_role
+            user_role,
+            rules_accepted_at
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
         RETURNING user_account
         ",
         &[
@@ -201,6 +217,7 @@ pub async fn create_user(
             &user_data.ed25519_secret_key,
             &user_data.invite_code,
             &user_data.role,
+            &user_data.rules_accepted_at,
         ],
     ).await.map_err(catch_unique_violation("user"))?;
     let db_user: DbUser = row.try_get("user_account")?;
@@ -238,6 +255,68 @@ pub async fn set_user_password(
     Ok(())
 }
 
+/// Replaces existing recovery codes with new ones
+pub async fn create_recovery_codes(
+    db_client: &mut impl DatabaseClient,
+    user_id: Uuid,
+) -> Result<Vec<String>, DatabaseError> {
+    let transaction = db_client.transaction().await?;
+    transaction.execute(
+        "DELETE FROM user_recovery_code WHERE owner_id = $1",
+        &[&user_id],
+    ).await?;
+    let mut codes = vec![];
+    for _ in 0..RECOVERY_CODE_COUNT {
+        let code = generate_recovery_code();
+        let code_digest = hash_recovery_code(&code);
+        transaction.execute(
+            "
+            INSERT INTO user_recovery_code (owner_id, code_digest)
+            VALUES ($1, $2)
+            ",
+            &[&user_id, &code_digest],
+        ).await.map_err(catch_unique_violation("recovery code"))?;
+        codes.push(code);
+    };
+    transaction.commit().await?;
+    Ok(codes)
+}
+
+pub async fn get_recovery_code_count(
+    db_client: &impl DatabaseClient,
+    user_id: Uuid,
+) -> Result<i64, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        SELECT count(*) FROM user_recovery_code
+        WHERE owner_id = $1
+        ",
+        &[&user_id],
+    ).await?;
+    let count = row.try_get("count")?;
+    Ok(count)
+}
+
+/// Removes recovery code, so it can't be used again
+pub async fn use_recovery_code(
+    db_client: &impl DatabaseClient,
+    user_id: Uuid,
+    code: &str,
+) -> Result<(), DatabaseError> {
+    let code_digest = hash_recovery_code(code);
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM user_recovery_code
+        WHERE owner_id = $1 AND code_digest = $2
+        ",
+        &[&user_id, &code_digest],
+    ).await?;
+    if deleted_count == 0 {
+        return Err(DatabaseError::NotFound("recovery code"));
+    };
+    Ok(())
+}
+
 pub async fn set_user_role(
     db_client: &impl DatabaseClient,
     user_id: Uuid,
@@ -256,6 +335,165 @@ pub async fn set_user_role(
     Ok(())
 }
 
+pub async fn set_user_feature_flag(
+    db_client: &impl DatabaseClient,
+    user_id: Uuid,
+    feature_flag: FeatureFlag,
+    is_enabled: bool,
+) -> Result<(), DatabaseError> {
+    let updated_count = db_client.execute(
+        "
+        UPDATE user_account
+        SET feature_flags = CASE
+            WHEN $3 THEN array_append(array_remove(feature_flags, $2), $2)
+            ELSE array_remove(feature_flags, $2)
+            END
+        WHERE id = $1
+        ",
+        &[&user_id, &feature_flag.as_str(), &is_enabled],
+    ).await?;
+    if updated_count == 0 {
+        return Err(DatabaseError::NotFound("user"));
+    };
+    Ok(())
+}
+
+pub async fn set_user_storage_quota(
+    db_client: &impl DatabaseClient,
+    user_id: Uuid,
+    storage_quota: Option<u64>,
+) -> Result<(), DatabaseError> {
+    let storage_quota = storage_quota
+        .map(i64::try_from)
+        .transpose()
+        .map_err(|_| DatabaseTypeError)?;
+    let updated_count = db_client.execute(
+        "
+        UPDATE user_account
+        SET storage_quota = $2
+        WHERE id = $1
+        ",
+        &[&user_id, &storage_quota],
+    ).await?;
+    if updated_count == 0 {
+        return Err(DatabaseError::NotFound("user"));
+    };
+    Ok(())
+}
+
+pub async fn set_user_moved_to(
+    db_client: &mut impl DatabaseClient,
+    user_id: Uuid,
+    moved_to: Option<&str>,
+) -> Result<(), DatabaseError> {
+    let transaction = db_client.transaction().await?;
+    let updated_count = transaction.execute(
+        "
+        UPDATE user_account
+        SET moved_to = $2
+        WHERE id = $1
+        ",
+        &[&user_id, &moved_to],
+    ).await?;
+    if updated_count == 0 {
+        return Err(DatabaseError::NotFound("user"));
+    };
+    // movedTo is part of actor document
+    transaction.execute(
+        "
+        UPDATE actor_profile
+        SET updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        ",
+        &[&user_id],
+    ).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Changes username of a local user.
+/// The previous username is preserved as a redirect.
+pub async fn rename_user(
+    db_client: &mut impl DatabaseClient,
+    user_id: Uuid,
+    new_username: &str,
+) -> Result<User, DatabaseError> {
+    let transaction = db_client.transaction().await?;
+    // Prevent changes to actor_profile table
+    transaction.execute(
+        "LOCK TABLE actor_profile IN EXCLUSIVE MODE",
+        &[],
+    ).await?;
+    let user = get_user_by_id(&transaction, user_id).await?;
+    // User can return to one of the previous usernames
+    transaction.execute(
+        "
+        DELETE FROM username_redirect
+        WHERE user_id = $1 AND username ILIKE $2
+        ",
+        &[&user_id, &new_username],
+    ).await?;
+    check_local_username_unique(&transaction, new_username).await?;
+    transaction.execute(
+        "
+        INSERT INTO username_redirect (username, user_id)
+        VALUES ($1, $2)
+        ",
+        &[&user.profile.username, &user_id],
+    ).await.map_err(catch_unique_violation("username"))?;
+    transaction.execute(
+        "
+        UPDATE actor_profile
+        SET
+            username = $2,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        ",
+        &[&user_id, &new_username],
+    ).await?;
+    // Stored actor document will be re-created with the new ID
+    transaction.execute(
+        "DELETE FROM activitypub_object WHERE profile_id = $1",
+        &[&user_id],
+    ).await?;
+    let user = get_user_by_id(&transaction, user_id).await?;
+    transaction.commit().await?;
+    Ok(user)
+}
+
+pub async fn set_user_keys(
+    db_client: &mut impl DatabaseClient,
+    user_id: Uuid,
+    rsa_secret_key: &str,
+    ed25519_secret_key: &Ed25519SecretKey,
+) -> Result<(), DatabaseError> {
+    let transaction = db_client.transaction().await?;
+    let updated_count = transaction.execute(
+        "
+        UPDATE user_account
+        SET
+            rsa_private_key = $2,
+            ed25519_private_key = $3
+        WHERE id = $1
+        ",
+        &[&user_id, &rsa_secret_key, ed25519_secret_key],
+    ).await?;
+    if updated_count == 0 {
+        return Err(DatabaseError::NotFound("user"));
+    };
+    // Public keys are part of actor document
+    transaction.execute(
+        "
+        UPDATE actor_profile
+        SET updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        ",
+        &[&user_id],
+    ).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
 pub async fn update_client_config(
     db_client: &impl DatabaseClient,
     user_id: Uuid,
@@ -333,6 +571,27 @@ pub async fn get_user_by_name(
     Ok(user)
 }
 
+pub async fn get_user_by_previous_username(
+    db_client: &impl DatabaseClient,
+    username: &str,
+) -> Result<User, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT user_account, actor_profile
+        FROM username_redirect
+        JOIN user_account ON (username_redirect.user_id = user_account.id)
+        JOIN actor_profile ON (user_account.id = actor_profile.id)
+        WHERE username_redirect.username = $1
+        ",
+        &[&username],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("user"))?;
+    let db_user: DbUser = row.try_get("user_account")?;
+    let db_profile: DbActorProfile = row.try_get("actor_profile")?;
+    let user = User::new(db_user, db_profile)?;
+    Ok(user)
+}
+
 pub async fn get_user_by_login_address(
     db_client: &impl DatabaseClient,
     account_id: &ChainAccountId,
@@ -460,6 +719,35 @@ pub async fn get_user_count(
     Ok(count)
 }
 
+/// Counts users whose usernames don't start with the given prefix
+pub async fn get_user_count_without_prefix(
+    db_client: &impl DatabaseClient,
+    username_prefix: &str,
+) -> Result<i64, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        SELECT count(user_account)
+        FROM user_account
+        JOIN actor_profile USING (id)
+        WHERE NOT starts_with(actor_profile.username, $1)
+        ",
+        &[&username_prefix],
+    ).await?;
+    let count = row.try_get("count")?;
+    Ok(count)
+}
+
+pub async fn get_portable_user_count(
+    db_client: &impl DatabaseClient,
+) -> Result<i64, DatabaseError> {
+    let row = db_client.query_one(
+        "SELECT count(portable_user_account) FROM portable_user_account",
+        &[],
+    ).await?;
+    let count = row.try_get("count")?;
+    Ok(count)
+}
+
 pub async fn get_active_user_count(
     db_client: &impl DatabaseClient,
     not_before: DateTime<Utc>,
@@ -538,6 +826,9 @@ pub async fn create_automated_account(
         identity_proofs: vec![],
         payment_options: vec![],
         extra_fields: vec![],
+        location: None,
+        birthday: None,
+        pronouns: None,
         aliases: vec![],
         emojis: account_data.emojis,
         actor_json: None,
@@ -641,6 +932,30 @@ pub async fn get_managed_account_by_id(
     Ok(account)
 }
 
+/// Returns ID and last update time of a local actor
+pub async fn get_local_actor_version(
+    db_client: &impl DatabaseClient,
+    username: &str,
+) -> Result<(Uuid, DateTime<Utc>), DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT actor_profile.id, actor_profile.updated_at
+        FROM actor_profile
+        WHERE
+            actor_profile.acct = $1
+            AND (
+                actor_profile.user_id IS NOT NULL
+                OR actor_profile.automated_account_id IS NOT NULL
+            )
+        ",
+        &[&username],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("account"))?;
+    let profile_id = row.try_get("id")?;
+    let updated_at = row.try_get("updated_at")?;
+    Ok((profile_id, updated_at))
+}
+
 pub async fn get_managed_account_by_username(
     db_client: &impl DatabaseClient,
     username: &str,
@@ -875,6 +1190,31 @@ mod tests {
         assert!(matches!(result, Err(DatabaseError::AlreadyExists("user"))));
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_recovery_codes() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let codes = create_recovery_codes(db_client, user.id).await.unwrap();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        let count = get_recovery_code_count(db_client, user.id).await.unwrap();
+        assert_eq!(count, 10);
+
+        use_recovery_code(db_client, user.id, &codes[0]).await.unwrap();
+        let count = get_recovery_code_count(db_client, user.id).await.unwrap();
+        assert_eq!(count, 9);
+        let error = use_recovery_code(db_client, user.id, &codes[0])
+            .await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("recovery code")));
+
+        // Regenerate
+        let new_codes = create_recovery_codes(db_client, user.id).await.unwrap();
+        let error = use_recovery_code(db_client, user.id, &codes[1])
+            .await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("recovery code")));
+        use_recovery_code(db_client, user.id, &new_codes[1]).await.unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_set_user_role() {
@@ -891,6 +1231,117 @@ mod tests {
         assert_eq!(user.role, Role::ReadOnlyUser);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_rename_user() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let user = rename_user(db_client, user.id, "renamed").await.unwrap();
+        assert_eq!(user.profile.username, "renamed");
+        assert_eq!(user.profile.acct.as_deref(), Some("renamed"));
+
+        let redirected = get_user_by_previous_username(db_client, "test")
+            .await.unwrap();
+        assert_eq!(redirected.id, user.id);
+        // Previous username can't be taken by another user
+        let user_data = UserCreateData {
+            username: "test".to_string(),
+            password_digest: Some("test".to_string()),
+            ..Default::default()
+        };
+        let error = create_user(db_client, user_data).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::AlreadyExists("user")));
+        // Return to previous username
+        let user = rename_user(db_client, user.id, "test").await.unwrap();
+        assert_eq!(user.profile.username, "test");
+        let redirected = get_user_by_previous_username(db_client, "renamed")
+            .await.unwrap();
+        assert_eq!(redirected.id, user.id);
+        let error = get_user_by_previous_username(db_client, "test")
+            .await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("user")));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_local_actor_version() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let (profile_id, updated_at) =
+            get_local_actor_version(db_client, "test").await.unwrap();
+        assert_eq!(profile_id, user.id);
+        assert_eq!(updated_at, user.profile.updated_at);
+
+        let user = rename_user(db_client, user.id, "renamed").await.unwrap();
+        let error = get_local_actor_version(db_client, "test")
+            .await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("account")));
+        let (_, updated_at) =
+            get_local_actor_version(db_client, "renamed").await.unwrap();
+        assert_eq!(updated_at, user.profile.updated_at);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_set_user_feature_flag() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        assert_eq!(user.has_feature(FeatureFlag::HigherRateLimits), false);
+        set_user_feature_flag(
+            db_client,
+            user.id,
+            FeatureFlag::HigherRateLimits,
+            true,
+        ).await.unwrap();
+        // Enabling flag twice should not create duplicates
+        set_user_feature_flag(
+            db_client,
+            user.id,
+            FeatureFlag::HigherRateLimits,
+            true,
+        ).await.unwrap();
+        let user = get_user_by_id(db_client, user.id).await.unwrap();
+        assert_eq!(user.feature_flags, vec![FeatureFlag::HigherRateLimits]);
+        assert_eq!(user.has_feature(FeatureFlag::HigherRateLimits), true);
+
+        set_user_feature_flag(
+            db_client,
+            user.id,
+            FeatureFlag::HigherRateLimits,
+            false,
+        ).await.unwrap();
+        let user = get_user_by_id(db_client, user.id).await.unwrap();
+        assert_eq!(user.feature_flags.is_empty(), true);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_set_user_storage_quota() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        assert_eq!(user.storage_quota, None);
+        set_user_storage_quota(db_client, user.id, Some(1000)).await.unwrap();
+        let user = get_user_by_id(db_client, user.id).await.unwrap();
+        assert_eq!(user.storage_quota, Some(1000));
+
+        set_user_storage_quota(db_client, user.id, None).await.unwrap();
+        let user = get_user_by_id(db_client, user.id).await.unwrap();
+        assert_eq!(user.storage_quota, None);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_set_user_moved_to() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        assert_eq!(user.moved_to, None);
+        let target_id = "https://social.example/users/test";
+        set_user_moved_to(db_client, user.id, Some(target_id)).await.unwrap();
+        let updated_user = get_user_by_id(db_client, user.id).await.unwrap();
+        assert_eq!(updated_user.moved_to.as_deref(), Some(target_id));
+        assert!(updated_user.profile.updated_at > user.profile.updated_at);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_update_client_config() {
@@ -935,6 +1386,21 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_get_user_count_without_prefix() {
+        let db_client = &mut create_test_database().await;
+        create_test_user(db_client, "fixture1").await;
+        create_test_user(db_client, "fixture2").await;
+        let count = get_user_count_without_prefix(db_client, "fixture")
+            .await.unwrap();
+        assert_eq!(count, 0);
+        create_test_user(db_client, "test").await;
+        let count = get_user_count_without_prefix(db_client, "fixture")
+            .await.unwrap();
+        assert_eq!(count, 1);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_get_admin_user() {
@@ -1021,6 +1487,8 @@ mod tests {
         assert!(user.profile.has_portable_account());
         assert_eq!(user.profile.webfinger_hostname(), WebfingerHostname::Local);
         assert_eq!(user.profile.acct.unwrap(), "test");
+        let count = get_portable_user_count(db_client).await.unwrap();
+        assert_eq!(count, 1);
     }
 
     #[tokio::test]
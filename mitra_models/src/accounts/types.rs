@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
 
 use apx_core::{
     crypto::{
@@ -59,6 +60,11 @@ pub enum Permission {
     CreatePost,
     DeleteAnyPost,
     DeleteAnyProfile,
+    ManageAnnouncements,
+    ManageApplications,
+    ManageReports,
+    ManageServerRules,
+    ManageSettings,
     ManageSubscriptionOptions,
 }
 
@@ -88,6 +94,11 @@ impl Role {
                 Permission::CreatePost,
                 Permission::DeleteAnyPost,
                 Permission::DeleteAnyProfile,
+                Permission::ManageAnnouncements,
+                Permission::ManageApplications,
+                Permission::ManageReports,
+                Permission::ManageServerRules,
+                Permission::ManageSettings,
                 Permission::ManageSubscriptionOptions,
             ],
             Self::ReadOnlyUser => vec![
@@ -130,6 +141,36 @@ impl TryFrom<i16> for Role {
 int_enum_from_sql!(Role);
 int_enum_to_sql!(Role);
 
+/// Experimental features that can be enabled for individual users.
+/// Flags are stored as strings, so new ones can be added without migrations.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FeatureFlag {
+    /// Relaxed rate limits for remote fetch requests
+    HigherRateLimits,
+}
+
+impl FeatureFlag {
+    pub const ALL: [Self; 1] = [
+        Self::HigherRateLimits,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::HigherRateLimits => "higher_rate_limits",
+        }
+    }
+}
+
+impl FromStr for FeatureFlag {
+    type Err = DatabaseTypeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter()
+            .find(|flag| flag.as_str() == value)
+            .ok_or(DatabaseTypeError)
+    }
+}
+
 pub type ClientConfig = HashMap<String, JsonValue>;
 
 #[derive(Deserialize)]
@@ -180,6 +221,52 @@ impl Serialize for DbLanguage {
 
 fn default_default_post_visibility() -> Visibility { Visibility::Public }
 
+/// Rules for accepting follow requests automatically
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FollowRequestRules {
+    /// Accept requests from actors on these servers
+    #[serde(default)]
+    pub accept_domains: Vec<String>,
+
+    /// Accept requests from actors that have been known
+    /// for at least this many days
+    pub accept_known_for_days: Option<u32>,
+}
+
+impl FollowRequestRules {
+    pub fn is_empty(&self) -> bool {
+        self.accept_domains.is_empty() && self.accept_known_for_days.is_none()
+    }
+
+    pub fn accepts(&self, source: &DbActorProfile) -> bool {
+        if let Some(ref hostname) = source.hostname {
+            let is_domain_matched = self.accept_domains.iter()
+                .any(|domain| domain.eq_ignore_ascii_case(hostname));
+            if is_domain_matched {
+                return true;
+            };
+        };
+        if let Some(days) = self.accept_known_for_days {
+            let known_for = Utc::now() - source.created_at;
+            if known_for.num_days() >= i64::from(days) {
+                return true;
+            };
+        };
+        false
+    }
+}
+
+/// Who can quote user's posts without approval
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotePolicy {
+    #[default]
+    Public,
+    Followers,
+    Nobody,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct SharedClientConfig {
@@ -187,6 +274,15 @@ pub struct SharedClientConfig {
     pub default_post_visibility: Visibility,
 
     pub default_post_language: Option<DbLanguage>,
+
+    #[serde(default)]
+    pub follow_request_rules: FollowRequestRules,
+
+    #[serde(default)]
+    pub quote_policy: QuotePolicy,
+
+    // IANA time zone name
+    pub timezone: Option<String>,
 }
 
 impl Default for SharedClientConfig {
@@ -194,6 +290,9 @@ impl Default for SharedClientConfig {
         Self {
             default_post_visibility: default_default_post_visibility(),
             default_post_language: None,
+            follow_request_rules: FollowRequestRules::default(),
+            quote_policy: QuotePolicy::default(),
+            timezone: None,
         }
     }
 }
@@ -217,6 +316,11 @@ pub struct DbUser {
     shared_client_config: SharedClientConfig,
     #[expect(dead_code)]
     created_at: DateTime<Utc>,
+    #[expect(dead_code)]
+    rules_accepted_at: Option<DateTime<Utc>>,
+    feature_flags: Vec<String>,
+    storage_quota: Option<i64>,
+    moved_to: Option<String>,
 }
 
 // Represents local user (managed account)
@@ -231,6 +335,10 @@ pub struct User {
     pub role: Role,
     pub client_config: ClientConfig,
     pub shared_client_config: SharedClientConfig,
+    pub feature_flags: Vec<FeatureFlag>,
+    pub storage_quota: Option<u64>,
+    // ID of the actor this account has been moved to
+    pub moved_to: Option<String>,
     pub profile: DbActorProfile,
 }
 
@@ -260,6 +368,9 @@ impl Default for User {
             role: Role::default(),
             client_config: ClientConfig::default(),
             shared_client_config: SharedClientConfig::default(),
+            feature_flags: vec![],
+            storage_quota: None,
+            moved_to: None,
             profile: DbActorProfile {
                 id: id,
                 user_id: Some(id),
@@ -295,6 +406,14 @@ impl User {
                 return Err(DatabaseTypeError);
             };
         };
+        // Unknown flags are ignored
+        let feature_flags = db_user.feature_flags.iter()
+            .filter_map(|value| FeatureFlag::from_str(value).ok())
+            .collect();
+        let storage_quota = db_user.storage_quota
+            .map(u64::try_from)
+            .transpose()
+            .map_err(|_| DatabaseTypeError)?;
         let user = Self {
             id: db_user.id,
             password_digest: db_user.password_digest,
@@ -305,11 +424,18 @@ impl User {
             role: db_user.user_role,
             client_config: db_user.client_config.into_inner(),
             shared_client_config: db_user.shared_client_config,
+            feature_flags: feature_flags,
+            storage_quota: storage_quota,
+            moved_to: db_user.moved_to,
             profile: db_profile,
         };
         Ok(user)
     }
 
+    pub fn has_feature(&self, feature_flag: FeatureFlag) -> bool {
+        self.feature_flags.contains(&feature_flag)
+    }
+
     /// Returns wallet address if it is verified
     pub fn public_ethereum_address(&self) -> Option<String> {
         for proof in self.profile.identity_proofs.clone().into_inner() {
@@ -336,6 +462,7 @@ pub struct UserCreateData {
     pub ed25519_secret_key: Ed25519SecretKey,
     pub invite_code: Option<String>,
     pub role: Role,
+    pub rules_accepted_at: Option<DateTime<Utc>>,
 }
 
 impl UserCreateData {
@@ -378,6 +505,7 @@ impl Default for UserCreateData {
             ed25519_secret_key: ed25519_secret_key,
             invite_code: None,
             role: Role::default(),
+            rules_accepted_at: None,
         }
     }
 }
@@ -388,6 +516,8 @@ pub enum AutomatedAccountType {
     Relay,
     Anonymous,
     Group,
+    Feed,
+    CustomFeed,
 }
 
 impl From<AutomatedAccountType> for i16 {
@@ -397,6 +527,8 @@ impl From<AutomatedAccountType> for i16 {
             AutomatedAccountType::Relay => 2,
             AutomatedAccountType::Anonymous => 3,
             AutomatedAccountType::Group => 4,
+            AutomatedAccountType::Feed => 5,
+            AutomatedAccountType::CustomFeed => 6,
         }
     }
 }
@@ -410,6 +542,8 @@ impl TryFrom<i16> for AutomatedAccountType {
             2 => Self::Relay,
             3 => Self::Anonymous,
             4 => Self::Group,
+            5 => Self::Feed,
+            6 => Self::CustomFeed,
             _ => return Err(DatabaseTypeError),
         };
         Ok(account_type)
@@ -497,6 +631,10 @@ pub trait ManagedAccount {
     fn id(&self) -> Uuid {
         self.profile().id
     }
+
+    fn moved_to(&self) -> Option<&str> {
+        None
+    }
 }
 
 impl ManagedAccount for User {
@@ -511,6 +649,10 @@ impl ManagedAccount for User {
     fn ed25519_secret_key(&self) -> Ed25519SecretKey {
         self.ed25519_secret_key
     }
+
+    fn moved_to(&self) -> Option<&str> {
+        self.moved_to.as_deref()
+    }
 }
 
 impl ManagedAccount for AutomatedAccountDetailed {
@@ -565,6 +707,10 @@ impl ManagedAccount for BoxedManagedAccount {
     fn ed25519_secret_key(&self) -> Ed25519SecretKey {
         (**self).ed25519_secret_key()
     }
+
+    fn moved_to(&self) -> Option<&str> {
+        (**self).moved_to()
+    }
 }
 
 #[derive(FromSql)]
@@ -680,4 +826,32 @@ mod tests {
         };
         assert_eq!(user.public_ethereum_address(), None);
     }
+
+    #[test]
+    fn test_follow_request_rules() {
+        let rules = FollowRequestRules::default();
+        let profile = DbActorProfile {
+            hostname: Some("social.example".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(rules.is_empty(), true);
+        assert_eq!(rules.accepts(&profile), false);
+
+        let rules = FollowRequestRules {
+            accept_domains: vec!["Social.Example".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(rules.accepts(&profile), true);
+
+        let rules = FollowRequestRules {
+            accept_known_for_days: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(rules.accepts(&profile), false);
+        let profile = DbActorProfile {
+            created_at: Utc::now() - chrono::TimeDelta::days(31),
+            ..profile
+        };
+        assert_eq!(rules.accepts(&profile), true);
+    }
 }
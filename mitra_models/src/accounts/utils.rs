@@ -1,14 +1,28 @@
+use apx_core::crypto::hashes::sha256;
 use hex;
 
 use mitra_utils::random::generate_random_sequence;
 
 const INVITE_CODE_LENGTH: usize = 32;
+const RECOVERY_CODE_LENGTH: usize = 16;
+pub const RECOVERY_CODE_COUNT: usize = 10;
 
 pub fn generate_invite_code() -> String {
     let value: [u8; INVITE_CODE_LENGTH / 2] = generate_random_sequence();
     hex::encode(value)
 }
 
+pub fn generate_recovery_code() -> String {
+    let value: [u8; RECOVERY_CODE_LENGTH / 2] = generate_random_sequence();
+    hex::encode(value)
+}
+
+pub fn hash_recovery_code(code: &str) -> [u8; 32] {
+    // Recovery codes are case-insensitive
+    let code = code.trim().to_lowercase();
+    sha256(code.as_bytes())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -18,4 +32,14 @@ mod tests {
         let invite_code = generate_invite_code();
         assert_eq!(invite_code.len(), INVITE_CODE_LENGTH);
     }
+
+    #[test]
+    fn test_generate_recovery_code() {
+        let code = generate_recovery_code();
+        assert_eq!(code.len(), RECOVERY_CODE_LENGTH);
+        assert_eq!(
+            hash_recovery_code(&code),
+            hash_recovery_code(&format!(" {} ", code.to_uppercase())),
+        );
+    }
 }
@@ -22,7 +22,8 @@ pub async fn get_collection_items_json(
     )
         .await?
         .into_iter()
-        .map(|object| object.object_data)
+        // Collection items are never compressed
+        .filter_map(|object| object.object_data)
         .collect();
     Ok(items)
 }
@@ -1,4 +1,4 @@
 pub mod constants;
 pub mod helpers;
 pub mod queries;
-mod types;
+pub mod types;
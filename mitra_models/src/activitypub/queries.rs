@@ -1,5 +1,9 @@
 use apx_core::url::canonical::CanonicalUri;
 use chrono::{DateTime, Utc};
+use miniz_oxide::{
+    deflate::compress_to_vec,
+    inflate::decompress_to_vec,
+};
 use serde_json::{Value as JsonValue};
 use uuid::Uuid;
 
@@ -13,7 +17,13 @@ use crate::{
     },
     media::types::MediaInfo,
 };
-use super::types::ActivityPubObject;
+use super::types::{
+    ActivityPubObject,
+    IncomingActivity,
+    ObjectStorageStats,
+    OutgoingActivityRecord,
+    OutgoingActivityRecordData,
+};
 
 pub async fn save_activity(
     db_client: &impl DatabaseClient,
@@ -66,26 +76,57 @@ pub async fn save_actor(
     Ok(())
 }
 
+const COMPRESSION_LEVEL: u8 = 6;
+
+fn compress_object(object_json: &JsonValue) -> Vec<u8> {
+    let object_bytes = object_json.to_string().into_bytes();
+    compress_to_vec(&object_bytes, COMPRESSION_LEVEL)
+}
+
+fn decompress_object(data: &[u8]) -> Result<JsonValue, DatabaseTypeError> {
+    let object_bytes = decompress_to_vec(data)
+        .map_err(|_| DatabaseTypeError)?;
+    serde_json::from_slice(&object_bytes)
+        .map_err(|_| DatabaseTypeError)
+}
+
+/// Saves raw object of a remote post.
+/// If `compress` is true, object is stored as compressed binary data
+/// and can not be queried with JSON operators.
 pub async fn save_attributed_object(
     db_client: &impl DatabaseClient,
     object_id: &str,
     object_json: &JsonValue,
     post_id: Uuid,
+    compress: bool,
 ) -> Result<(), DatabaseError> {
+    let (maybe_object_data, maybe_object_data_compressed) = if compress {
+        (None, Some(compress_object(object_json)))
+    } else {
+        (Some(object_json), None)
+    };
     db_client.execute(
         "
         INSERT INTO activitypub_object (
             object_id,
             object_data,
+            object_data_compressed,
             post_id
         )
-        VALUES ($1, $2, $3)
+        VALUES ($1, $2, $3, $4)
         ON CONFLICT (object_id)
         DO UPDATE SET
             object_data = $2,
-            post_id = $3
+            object_data_compressed = $3,
+            post_id = $4,
+            post_deleted_at = NULL
         ",
-        &[&object_id, &object_json, &post_id],
+        &[
+            &object_id,
+            &maybe_object_data,
+            &maybe_object_data_compressed,
+            &post_id,
+        ],
     ).await?;
     Ok(())
 }
@@ -96,17 +137,27 @@ pub async fn get_object(
 ) -> Result<JsonValue, DatabaseError> {
     let maybe_row = db_client.query_opt(
         "
-        SELECT object_data
+        SELECT object_data, object_data_compressed
         FROM activitypub_object
-        WHERE object_id = $1
+        WHERE object_id = $1 AND post_deleted_at IS NULL
         ",
         &[&object_id.to_string()],
     ).await?;
     let row = maybe_row.ok_or(DatabaseError::NotFound("activitypub object"))?;
-    let object_data = row.try_get("object_data")?;
+    let maybe_object_data = row.try_get("object_data")?;
+    let object_data = match maybe_object_data {
+        Some(object_data) => object_data,
+        None => {
+            let object_data_compressed: Vec<u8> =
+                row.try_get("object_data_compressed")?;
+            decompress_object(&object_data_compressed)?
+        },
+    };
     Ok(object_data)
 }
 
+/// Returns object if it is addressed to the given target
+/// (compressed objects are not returned)
 pub async fn get_object_as_target(
     db_client: &impl DatabaseClient,
     object_id: &str,
@@ -117,6 +168,7 @@ pub async fn get_object_as_target(
         SELECT object_data
         FROM activitypub_object
         WHERE object_id = $1
+        AND post_deleted_at IS NULL
         AND object_data -> 'to' @> to_jsonb($2::text)
         ",
         &[&object_id, &target],
@@ -148,23 +200,324 @@ pub async fn delete_activitypub_objects(
     db_client: &impl DatabaseClient,
     created_before: DateTime<Utc>,
 ) -> Result<u64, DatabaseError> {
-    // Don't delete actors, posts and activities in collections
+    // Don't delete actors, posts (including deleted posts)
+    // and activities in collections
     let deleted_count = db_client.execute(
         "
         DELETE FROM activitypub_object
         WHERE created_at < $1
             AND profile_id IS NULL
             AND post_id IS NULL
+            AND post_deleted_at IS NULL
+            AND NOT EXISTS (
+                SELECT 1 FROM activitypub_collection_item
+                WHERE object_id = activitypub_object.object_id
+            )
+        ",
+        &[&created_before],
+    ).await?;
+    Ok(deleted_count)
+}
+
+/// Deletes raw objects of posts that were deleted before the given time
+/// (objects in collections are preserved)
+pub async fn delete_attributed_objects(
+    db_client: &impl DatabaseClient,
+    deleted_before: DateTime<Utc>,
+) -> Result<u64, DatabaseError> {
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM activitypub_object
+        WHERE
+            post_id IS NULL
+            AND post_deleted_at < $1
             AND NOT EXISTS (
                 SELECT 1 FROM activitypub_collection_item
                 WHERE object_id = activitypub_object.object_id
             )
         ",
+        &[&deleted_before],
+    ).await?;
+    Ok(deleted_count)
+}
+
+pub async fn get_object_storage_stats(
+    db_client: &impl DatabaseClient,
+) -> Result<Vec<ObjectStorageStats>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT
+            CASE
+                WHEN profile_id IS NOT NULL THEN 'actor'
+                WHEN post_id IS NOT NULL THEN 'post'
+                WHEN post_deleted_at IS NOT NULL THEN 'deleted post'
+                ELSE 'other'
+            END AS category,
+            count(*) AS object_count,
+            sum(
+                coalesce(pg_column_size(object_data), 0)
+                + coalesce(pg_column_size(object_data_compressed), 0)
+            )::bigint AS object_size
+        FROM activitypub_object
+        GROUP BY category
+        ORDER BY category
+        ",
+        &[],
+    ).await?;
+    let stats = rows.iter()
+        .map(|row| {
+            let stats = ObjectStorageStats {
+                category: row.try_get("category")?,
+                object_count: row.try_get("object_count")?,
+                object_size: row.try_get("object_size")?,
+            };
+            Ok(stats)
+        })
+        .collect::<Result<_, DatabaseError>>()?;
+    Ok(stats)
+}
+
+/// Returns total size of activitypub_object table, including indexes and TOAST
+pub async fn get_object_storage_size(
+    db_client: &impl DatabaseClient,
+) -> Result<i64, DatabaseError> {
+    let row = db_client.query_one(
+        "SELECT pg_total_relation_size('activitypub_object') AS size",
+        &[],
+    ).await?;
+    let size = row.try_get("size")?;
+    Ok(size)
+}
+
+pub async fn save_incoming_activity(
+    db_client: &impl DatabaseClient,
+    activity_id: &str,
+    activity: &JsonValue,
+    hostname: &str,
+    recipient_id: &str,
+    sender_id: &str,
+    is_authenticated: bool,
+) -> Result<(), DatabaseError> {
+    db_client.execute(
+        "
+        INSERT INTO incoming_activity (
+            activity_id,
+            activity,
+            hostname,
+            recipient_id,
+            sender_id,
+            is_authenticated
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ",
+        &[
+            &activity_id,
+            &activity,
+            &hostname,
+            &recipient_id,
+            &sender_id,
+            &is_authenticated,
+        ],
+    ).await?;
+    Ok(())
+}
+
+/// Returns stored incoming activities, oldest first
+pub async fn get_incoming_activities(
+    db_client: &impl DatabaseClient,
+    maybe_activity_id: Option<&str>,
+    maybe_hostname: Option<&str>,
+    limit: u32,
+) -> Result<Vec<IncomingActivity>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT incoming_activity
+        FROM incoming_activity
+        WHERE
+            ($1::text IS NULL OR activity_id = $1)
+            AND ($2::text IS NULL OR hostname = $2)
+        ORDER BY id ASC
+        LIMIT $3
+        ",
+        &[&maybe_activity_id, &maybe_hostname, &i64::from(limit)],
+    ).await?;
+    let activities = rows.iter()
+        .map(|row| row.try_get("incoming_activity"))
+        .collect::<Result<_, _>>()?;
+    Ok(activities)
+}
+
+pub async fn delete_incoming_activities(
+    db_client: &impl DatabaseClient,
+    created_before: DateTime<Utc>,
+) -> Result<u64, DatabaseError> {
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM incoming_activity
+        WHERE created_at < $1
+        ",
+        &[&created_before],
+    ).await?;
+    Ok(deleted_count)
+}
+
+/// Creates or updates delivery records (one per inbox)
+pub async fn save_outgoing_activity_records(
+    db_client: &impl DatabaseClient,
+    record_data: OutgoingActivityRecordData,
+) -> Result<(), DatabaseError> {
+    for delivery in record_data.deliveries {
+        let attempt_increment = if delivery.is_attempted { 1 } else { 0 };
+        db_client.execute(
+            "
+            INSERT INTO outgoing_activity_record (
+                activity_id,
+                activity_type,
+                object_id,
+                sender_id,
+                audience,
+                recipient_id,
+                inbox,
+                delivery_status,
+                attempt_count
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (activity_id, inbox)
+            DO UPDATE SET
+                delivery_status = $8,
+                attempt_count = outgoing_activity_record.attempt_count + $9,
+                updated_at = CURRENT_TIMESTAMP
+            ",
+            &[
+                &record_data.activity_id,
+                &record_data.activity_type,
+                &record_data.object_id,
+                &record_data.sender_id,
+                &record_data.audience,
+                &delivery.recipient_id,
+                &delivery.inbox,
+                &delivery.status,
+                &attempt_increment,
+            ],
+        ).await?;
+    };
+    Ok(())
+}
+
+/// Returns outgoing activity records, newest first
+pub async fn get_outgoing_activity_records(
+    db_client: &impl DatabaseClient,
+    maybe_sender_id: Option<Uuid>,
+    maybe_activity_id: Option<&str>,
+    limit: u16,
+    offset: u16,
+) -> Result<Vec<OutgoingActivityRecord>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT outgoing_activity_record
+        FROM outgoing_activity_record
+        WHERE
+            ($1::uuid IS NULL OR sender_id = $1)
+            AND ($2::text IS NULL OR activity_id = $2)
+        ORDER BY id DESC
+        LIMIT $3
+        OFFSET $4
+        ",
+        &[
+            &maybe_sender_id,
+            &maybe_activity_id,
+            &i64::from(limit),
+            &i64::from(offset),
+        ],
+    ).await?;
+    let records = rows.iter()
+        .map(|row| row.try_get("outgoing_activity_record"))
+        .collect::<Result<_, _>>()?;
+    Ok(records)
+}
+
+/// Returns all outgoing activity records of the given sender, oldest first
+pub async fn get_outgoing_activity_records_by_sender(
+    db_client: &impl DatabaseClient,
+    sender_id: Uuid,
+) -> Result<Vec<OutgoingActivityRecord>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT outgoing_activity_record
+        FROM outgoing_activity_record
+        WHERE sender_id = $1
+        ORDER BY id ASC
+        ",
+        &[&sender_id],
+    ).await?;
+    let records = rows.iter()
+        .map(|row| row.try_get("outgoing_activity_record"))
+        .collect::<Result<_, _>>()?;
+    Ok(records)
+}
+
+pub async fn delete_outgoing_activity_records(
+    db_client: &impl DatabaseClient,
+    created_before: DateTime<Utc>,
+) -> Result<u64, DatabaseError> {
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM outgoing_activity_record
+        WHERE created_at < $1
+        ",
         &[&created_before],
     ).await?;
     Ok(deleted_count)
 }
 
+pub async fn get_cached_jrd(
+    db_client: &impl DatabaseClient,
+    resource: &str,
+) -> Result<JsonValue, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT jrd
+        FROM webfinger_cache
+        WHERE resource = $1 AND expires_at > CURRENT_TIMESTAMP
+        ",
+        &[&resource],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("JRD"))?;
+    let jrd = row.try_get("jrd")?;
+    Ok(jrd)
+}
+
+pub async fn save_cached_jrd(
+    db_client: &impl DatabaseClient,
+    resource: &str,
+    jrd: &JsonValue,
+    expires_at: DateTime<Utc>,
+) -> Result<(), DatabaseError> {
+    db_client.execute(
+        "
+        INSERT INTO webfinger_cache (resource, jrd, expires_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (resource)
+        DO UPDATE SET jrd = $2, expires_at = $3
+        ",
+        &[&resource, &jrd, &expires_at],
+    ).await?;
+    Ok(())
+}
+
+pub async fn delete_expired_jrds(
+    db_client: &impl DatabaseClient,
+) -> Result<u64, DatabaseError> {
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM webfinger_cache
+        WHERE expires_at <= CURRENT_TIMESTAMP
+        ",
+        &[],
+    ).await?;
+    Ok(deleted_count)
+}
+
 pub async fn add_object_to_collection(
     db_client: &impl DatabaseClient,
     owner_id: Uuid,
@@ -497,12 +850,17 @@ pub async fn get_object_ids(
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeDelta;
     use serde_json::json;
     use serial_test::serial;
     use crate::{
-        accounts::test_utils::create_test_portable_user,
+        accounts::test_utils::{create_test_portable_user, create_test_user},
+        activitypub::types::{DeliveryRecordData, DeliveryStatus},
         database::test_utils::create_test_database,
-        posts::test_utils::create_test_remote_post,
+        posts::{
+            queries::delete_post,
+            test_utils::create_test_remote_post,
+        },
         profiles::test_utils::create_test_remote_profile,
     };
     use super::*;
@@ -605,6 +963,7 @@ mod tests {
             canonical_object_id,
             &object_json,
             post.id,
+            false,
         ).await.unwrap();
 
         // Get
@@ -616,6 +975,75 @@ mod tests {
         assert_eq!(object_json_stored, object_json);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_delete_attributed_objects() {
+        let db_client = &mut create_test_database().await;
+        let profile = create_test_remote_profile(
+            db_client,
+            "test",
+            "social.example",
+            "https://social.example/users/test",
+        ).await;
+        let object_id = "https://social.example/objects/1";
+        let post = create_test_remote_post(
+            db_client,
+            profile.id,
+            "test",
+            object_id,
+        ).await;
+        let object_json = json!({
+            "type": "Note",
+            "id": object_id,
+            "content": "test",
+        });
+        save_attributed_object(
+            db_client,
+            object_id,
+            &object_json,
+            post.id,
+            true, // compressed
+        ).await.unwrap();
+        let canonical_object_id =
+            CanonicalUri::parse_canonical(object_id).unwrap();
+        let object_json_stored =
+            get_object(db_client, &canonical_object_id).await.unwrap();
+        assert_eq!(object_json_stored, object_json);
+        let stats = get_object_storage_stats(db_client).await.unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].category, "post");
+        assert_eq!(stats[0].object_count, 1);
+
+        // Objects of existing posts are not deleted
+        let deleted_count = delete_attributed_objects(
+            db_client,
+            Utc::now(),
+        ).await.unwrap();
+        assert_eq!(deleted_count, 0);
+
+        delete_post(db_client, post.id).await.unwrap();
+        let stats = get_object_storage_stats(db_client).await.unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].category, "deleted post");
+        let error = get_object(db_client, &canonical_object_id)
+            .await.err().unwrap();
+        assert_eq!(error.to_string(), "activitypub object not found");
+        // Unattributed objects retention doesn't apply
+        let deleted_count = delete_activitypub_objects(
+            db_client,
+            Utc::now(),
+        ).await.unwrap();
+        assert_eq!(deleted_count, 0);
+
+        let deleted_count = delete_attributed_objects(
+            db_client,
+            Utc::now(),
+        ).await.unwrap();
+        assert_eq!(deleted_count, 1);
+        let stats = get_object_storage_stats(db_client).await.unwrap();
+        assert_eq!(stats.len(), 0);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_get_object_as_target() {
@@ -693,7 +1121,7 @@ mod tests {
             10,
         ).await.unwrap();
         assert_eq!(items.len(), 1);
-        assert_eq!(items[0].object_data, activity);
+        assert_eq!(items[0].object_data.as_ref(), Some(&activity));
 
         // Remove from collection
         remove_object_from_collection(
@@ -857,6 +1285,28 @@ mod tests {
         ).await.unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_cached_jrd() {
+        let db_client = &create_test_database().await;
+        let resource = "acct:test@social.example";
+        let jrd = json!({
+            "subject": resource,
+            "links": [],
+        });
+        let expires_at = Utc::now() + TimeDelta::hours(1);
+        save_cached_jrd(db_client, resource, &jrd, expires_at).await.unwrap();
+        let cached_jrd = get_cached_jrd(db_client, resource).await.unwrap();
+        assert_eq!(cached_jrd, jrd);
+
+        let expires_at = Utc::now() - TimeDelta::hours(1);
+        save_cached_jrd(db_client, resource, &jrd, expires_at).await.unwrap();
+        let error = get_cached_jrd(db_client, resource).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("JRD")));
+        let deleted_count = delete_expired_jrds(db_client).await.unwrap();
+        assert_eq!(deleted_count, 1);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_get_object_ids() {
@@ -864,4 +1314,106 @@ mod tests {
         let ids = get_object_ids(db_client).await.unwrap();
         assert_eq!(ids.len(), 0);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_incoming_activities() {
+        let db_client = &create_test_database().await;
+        let activity_id = "https://social.example/activities/1";
+        let activity = json!({
+            "id": activity_id,
+            "type": "Like",
+            "actor": "https://social.example/users/1",
+            "object": "https://local.example/objects/1",
+        });
+        save_incoming_activity(
+            db_client,
+            activity_id,
+            &activity,
+            "social.example",
+            "https://local.example/users/test",
+            "https://social.example/users/1",
+            true,
+        ).await.unwrap();
+
+        let activities = get_incoming_activities(
+            db_client,
+            None,
+            Some("social.example"),
+            10,
+        ).await.unwrap();
+        assert_eq!(activities.len(), 1);
+        assert_eq!(activities[0].activity_id, activity_id);
+        assert_eq!(activities[0].activity, activity);
+        assert!(activities[0].is_authenticated);
+        let activities = get_incoming_activities(
+            db_client,
+            Some("https://social.example/activities/2"),
+            None,
+            10,
+        ).await.unwrap();
+        assert!(activities.is_empty());
+
+        let deleted_count = delete_incoming_activities(
+            db_client,
+            Utc::now(),
+        ).await.unwrap();
+        assert_eq!(deleted_count, 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_outgoing_activity_records() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let activity_id = "https://local.example/activities/1";
+        let inbox = "https://remote.example/inbox";
+        let create_record_data = |status| OutgoingActivityRecordData {
+            activity_id: activity_id.to_owned(),
+            activity_type: "Create".to_owned(),
+            object_id: Some("https://local.example/objects/1".to_owned()),
+            sender_id: Some(user.id),
+            audience: vec!["https://www.w3.org/ns/activitystreams#Public".to_owned()],
+            deliveries: vec![DeliveryRecordData {
+                recipient_id: "https://remote.example/users/1".to_owned(),
+                inbox: inbox.to_owned(),
+                status: status,
+                is_attempted: true,
+            }],
+        };
+        // First attempt fails, second attempt succeeds
+        save_outgoing_activity_records(
+            db_client,
+            create_record_data(DeliveryStatus::Pending),
+        ).await.unwrap();
+        save_outgoing_activity_records(
+            db_client,
+            create_record_data(DeliveryStatus::Delivered),
+        ).await.unwrap();
+
+        let records = get_outgoing_activity_records(
+            db_client,
+            Some(user.id),
+            None,
+            10,
+            0,
+        ).await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].activity_id, activity_id);
+        assert_eq!(records[0].activity_type, "Create");
+        assert_eq!(records[0].inbox, inbox);
+        assert_eq!(records[0].delivery_status, DeliveryStatus::Delivered);
+        assert_eq!(records[0].attempt_count, 2);
+        let records = get_outgoing_activity_records_by_sender(
+            db_client,
+            user.id,
+        ).await.unwrap();
+        assert_eq!(records.len(), 1);
+
+        let deleted_count = delete_outgoing_activity_records(
+            db_client,
+            Utc::now(),
+        ).await.unwrap();
+        assert_eq!(deleted_count, 1);
+    }
 }
@@ -3,13 +3,116 @@ use postgres_types::FromSql;
 use serde_json::{Value as JsonValue};
 use uuid::Uuid;
 
+use crate::database::{
+    int_enum::{int_enum_from_sql, int_enum_to_sql},
+    DatabaseTypeError,
+};
+
 #[expect(dead_code)]
 #[derive(FromSql)]
 #[postgres(name = "activitypub_object")]
 pub struct ActivityPubObject {
     pub object_id: String,
-    pub object_data: JsonValue,
+    // Not present if object is compressed
+    pub object_data: Option<JsonValue>,
     profile_id: Option<Uuid>,
     post_id: Option<Uuid>,
     created_at: DateTime<Utc>,
+    post_deleted_at: Option<DateTime<Utc>>,
+    object_data_compressed: Option<Vec<u8>>,
+}
+
+/// Activity delivered to inbox (stored for replaying)
+#[derive(FromSql)]
+#[postgres(name = "incoming_activity")]
+pub struct IncomingActivity {
+    pub id: i32,
+    pub activity_id: String,
+    pub activity: JsonValue,
+    pub hostname: String,
+    pub recipient_id: String,
+    pub sender_id: String,
+    pub is_authenticated: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeliveryStatus {
+    // Delivery failed, will be retried
+    Pending,
+    Delivered,
+    Failed,
+    // Inbox is unreachable or blocked
+    Skipped,
+}
+
+impl From<DeliveryStatus> for i16 {
+    fn from(value: DeliveryStatus) -> i16 {
+        match value {
+            DeliveryStatus::Pending => 1,
+            DeliveryStatus::Delivered => 2,
+            DeliveryStatus::Failed => 3,
+            DeliveryStatus::Skipped => 4,
+        }
+    }
+}
+
+impl TryFrom<i16> for DeliveryStatus {
+    type Error = DatabaseTypeError;
+
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        let status = match value {
+            1 => Self::Pending,
+            2 => Self::Delivered,
+            3 => Self::Failed,
+            4 => Self::Skipped,
+            _ => return Err(DatabaseTypeError),
+        };
+        Ok(status)
+    }
+}
+
+int_enum_from_sql!(DeliveryStatus);
+int_enum_to_sql!(DeliveryStatus);
+
+/// Delivery of outgoing activity to a single inbox (audit log)
+#[derive(FromSql)]
+#[postgres(name = "outgoing_activity_record")]
+pub struct OutgoingActivityRecord {
+    pub id: i32,
+    pub activity_id: String,
+    pub activity_type: String,
+    pub object_id: Option<String>,
+    pub sender_id: Option<Uuid>,
+    pub audience: Vec<String>,
+    pub recipient_id: String,
+    pub inbox: String,
+    pub delivery_status: DeliveryStatus,
+    pub attempt_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct DeliveryRecordData {
+    pub recipient_id: String,
+    pub inbox: String,
+    pub status: DeliveryStatus,
+    // Whether delivery was attempted during the last run
+    pub is_attempted: bool,
+}
+
+pub struct OutgoingActivityRecordData {
+    pub activity_id: String,
+    pub activity_type: String,
+    pub object_id: Option<String>,
+    pub sender_id: Option<Uuid>,
+    pub audience: Vec<String>,
+    pub deliveries: Vec<DeliveryRecordData>,
+}
+
+pub struct ObjectStorageStats {
+    pub category: String,
+    pub object_count: i64,
+    // Size of stored values (after compression)
+    pub object_size: i64,
 }
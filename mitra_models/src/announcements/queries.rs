@@ -0,0 +1,430 @@
+use uuid::Uuid;
+
+use crate::database::{
+    catch_unique_violation,
+    DatabaseClient,
+    DatabaseError,
+};
+
+use super::types::{
+    Announcement,
+    AnnouncementData,
+    AnnouncementDetailed,
+    AnnouncementReaction,
+};
+
+pub async fn create_announcement(
+    db_client: &impl DatabaseClient,
+    announcement_data: AnnouncementData,
+) -> Result<Announcement, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        INSERT INTO announcement (content, published_at, expires_at)
+        VALUES ($1, $2, $3)
+        RETURNING announcement
+        ",
+        &[
+            &announcement_data.content,
+            &announcement_data.published_at,
+            &announcement_data.expires_at,
+        ],
+    ).await?;
+    let announcement = row.try_get("announcement")?;
+    Ok(announcement)
+}
+
+pub async fn update_announcement(
+    db_client: &impl DatabaseClient,
+    announcement_id: i32,
+    announcement_data: AnnouncementData,
+) -> Result<Announcement, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE announcement
+        SET
+            content = $2,
+            published_at = $3,
+            expires_at = $4,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        RETURNING announcement
+        ",
+        &[
+            &announcement_id,
+            &announcement_data.content,
+            &announcement_data.published_at,
+            &announcement_data.expires_at,
+        ],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("announcement"))?;
+    let announcement = row.try_get("announcement")?;
+    Ok(announcement)
+}
+
+pub async fn delete_announcement(
+    db_client: &impl DatabaseClient,
+    announcement_id: i32,
+) -> Result<(), DatabaseError> {
+    let deleted_count = db_client.execute(
+        "DELETE FROM announcement WHERE id = $1",
+        &[&announcement_id],
+    ).await?;
+    if deleted_count == 0 {
+        return Err(DatabaseError::NotFound("announcement"));
+    };
+    Ok(())
+}
+
+pub async fn get_announcements(
+    db_client: &impl DatabaseClient,
+) -> Result<Vec<Announcement>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT announcement
+        FROM announcement
+        ORDER BY published_at DESC
+        ",
+        &[],
+    ).await?;
+    let announcements = rows.iter()
+        .map(|row| row.try_get("announcement"))
+        .collect::<Result<_, _>>()?;
+    Ok(announcements)
+}
+
+async fn get_announcement_reactions(
+    db_client: &impl DatabaseClient,
+    user_id: Uuid,
+    announcement_ids: &[i32],
+) -> Result<Vec<AnnouncementReaction>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT
+            announcement_reaction.announcement_id,
+            announcement_reaction.content,
+            count(*) AS count,
+            bool_or(announcement_reaction.user_id = $2) AS is_own,
+            (array_agg(emoji))[1] AS emoji
+        FROM announcement_reaction
+        LEFT JOIN emoji ON announcement_reaction.emoji_id = emoji.id
+        WHERE announcement_reaction.announcement_id = ANY($1)
+        GROUP BY
+            announcement_reaction.announcement_id,
+            announcement_reaction.content
+        ORDER BY count DESC, announcement_reaction.content
+        ",
+        &[&announcement_ids, &user_id],
+    ).await?;
+    let reactions = rows.iter()
+        .map(AnnouncementReaction::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(reactions)
+}
+
+/// Returns published announcements that have not expired yet
+pub async fn get_active_announcements(
+    db_client: &impl DatabaseClient,
+    user_id: Uuid,
+    with_dismissed: bool,
+) -> Result<Vec<AnnouncementDetailed>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT
+            announcement,
+            EXISTS (
+                SELECT 1 FROM announcement_read
+                WHERE
+                    announcement_read.announcement_id = announcement.id
+                    AND announcement_read.user_id = $1
+            ) AS is_read
+        FROM announcement
+        WHERE
+            published_at <= CURRENT_TIMESTAMP
+            AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)
+        ORDER BY published_at DESC
+        ",
+        &[&user_id],
+    ).await?;
+    let mut announcements = vec![];
+    for row in rows {
+        let is_read: bool = row.try_get("is_read")?;
+        if is_read && !with_dismissed {
+            continue;
+        };
+        let announcement = AnnouncementDetailed {
+            announcement: row.try_get("announcement")?,
+            is_read: is_read,
+            reactions: vec![],
+        };
+        announcements.push(announcement);
+    };
+    let announcement_ids: Vec<_> = announcements.iter()
+        .map(|item| item.announcement.id)
+        .collect();
+    let reactions = get_announcement_reactions(
+        db_client,
+        user_id,
+        &announcement_ids,
+    ).await?;
+    for reaction in reactions {
+        if let Some(announcement) = announcements.iter_mut()
+            .find(|item| item.announcement.id == reaction.announcement_id)
+        {
+            announcement.reactions.push(reaction);
+        };
+    };
+    Ok(announcements)
+}
+
+pub async fn dismiss_announcement(
+    db_client: &impl DatabaseClient,
+    user_id: Uuid,
+    announcement_id: i32,
+) -> Result<(), DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT 1 FROM announcement
+        WHERE
+            id = $1
+            AND published_at <= CURRENT_TIMESTAMP
+            AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)
+        ",
+        &[&announcement_id],
+    ).await?;
+    if maybe_row.is_none() {
+        return Err(DatabaseError::NotFound("announcement"));
+    };
+    db_client.execute(
+        "
+        INSERT INTO announcement_read (announcement_id, user_id)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING
+        ",
+        &[&announcement_id, &user_id],
+    ).await?;
+    Ok(())
+}
+
+pub async fn create_announcement_reaction(
+    db_client: &impl DatabaseClient,
+    user_id: Uuid,
+    announcement_id: i32,
+    content: &str,
+    maybe_emoji_id: Option<Uuid>,
+) -> Result<(), DatabaseError> {
+    let inserted_count = db_client.execute(
+        "
+        INSERT INTO announcement_reaction (
+            announcement_id,
+            user_id,
+            content,
+            emoji_id
+        )
+        SELECT id, $2, $3, $4
+        FROM announcement
+        WHERE
+            id = $1
+            AND published_at <= CURRENT_TIMESTAMP
+            AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)
+        ",
+        &[&announcement_id, &user_id, &content, &maybe_emoji_id],
+    ).await.map_err(catch_unique_violation("reaction"))?;
+    if inserted_count == 0 {
+        return Err(DatabaseError::NotFound("announcement"));
+    };
+    Ok(())
+}
+
+pub async fn delete_announcement_reaction(
+    db_client: &impl DatabaseClient,
+    user_id: Uuid,
+    announcement_id: i32,
+    content: &str,
+) -> Result<(), DatabaseError> {
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM announcement_reaction
+        USING announcement
+        WHERE
+            announcement_reaction.announcement_id = $1
+            AND announcement_reaction.user_id = $2
+            AND announcement_reaction.content = $3
+            AND announcement.id = announcement_reaction.announcement_id
+            AND announcement.published_at <= CURRENT_TIMESTAMP
+            AND (
+                announcement.expires_at IS NULL
+                OR announcement.expires_at > CURRENT_TIMESTAMP
+            )
+        ",
+        &[&announcement_id, &user_id, &content],
+    ).await?;
+    if deleted_count == 0 {
+        return Err(DatabaseError::NotFound("reaction"));
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use serial_test::serial;
+    use crate::{
+        accounts::test_utils::create_test_user,
+        database::test_utils::create_test_database,
+    };
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_update_delete_announcement() {
+        let db_client = &create_test_database().await;
+        let announcement_data = AnnouncementData {
+            content: "Test".to_string(),
+            published_at: Utc::now(),
+            expires_at: None,
+        };
+        let announcement =
+            create_announcement(db_client, announcement_data).await.unwrap();
+        assert_eq!(announcement.content, "Test");
+        assert!(announcement.is_active());
+
+        let announcement_data = AnnouncementData {
+            content: "Test updated".to_string(),
+            published_at: announcement.published_at,
+            expires_at: Some(Utc::now() - Duration::hours(1)),
+        };
+        let updated = update_announcement(
+            db_client,
+            announcement.id,
+            announcement_data,
+        ).await.unwrap();
+        assert_eq!(updated.id, announcement.id);
+        assert_eq!(updated.content, "Test updated");
+        assert!(!updated.is_active());
+
+        let announcements = get_announcements(db_client).await.unwrap();
+        assert_eq!(announcements.len(), 1);
+
+        delete_announcement(db_client, announcement.id).await.unwrap();
+        let error = delete_announcement(db_client, announcement.id)
+            .await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("announcement")));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_active_announcements() {
+        let db_client = &mut create_test_database().await;
+        let user_1 = create_test_user(db_client, "test1").await;
+        let user_2 = create_test_user(db_client, "test2").await;
+        let announcement_data = AnnouncementData {
+            content: "Test".to_string(),
+            published_at: Utc::now() - Duration::minutes(1),
+            expires_at: Some(Utc::now() + Duration::days(1)),
+        };
+        let announcement =
+            create_announcement(db_client, announcement_data).await.unwrap();
+        let scheduled_data = AnnouncementData {
+            content: "Scheduled".to_string(),
+            published_at: Utc::now() + Duration::days(1),
+            expires_at: None,
+        };
+        create_announcement(db_client, scheduled_data).await.unwrap();
+
+        create_announcement_reaction(
+            db_client,
+            user_1.id,
+            announcement.id,
+            "❤️",
+            None,
+        ).await.unwrap();
+        create_announcement_reaction(
+            db_client,
+            user_2.id,
+            announcement.id,
+            "❤️",
+            None,
+        ).await.unwrap();
+        let error = create_announcement_reaction(
+            db_client,
+            user_2.id,
+            announcement.id,
+            "❤️",
+            None,
+        ).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::AlreadyExists("reaction")));
+        dismiss_announcement(db_client, user_1.id, announcement.id)
+            .await.unwrap();
+
+        let announcements = get_active_announcements(
+            db_client,
+            user_1.id,
+            false,
+        ).await.unwrap();
+        assert_eq!(announcements.len(), 0);
+        let announcements = get_active_announcements(
+            db_client,
+            user_1.id,
+            true,
+        ).await.unwrap();
+        assert_eq!(announcements.len(), 1);
+        let announcement = &announcements[0];
+        assert!(announcement.is_read);
+        assert_eq!(announcement.reactions.len(), 1);
+        assert_eq!(announcement.reactions[0].content, "❤️");
+        assert_eq!(announcement.reactions[0].count, 2);
+        assert!(announcement.reactions[0].is_own);
+
+        delete_announcement_reaction(
+            db_client,
+            user_1.id,
+            announcement.announcement.id,
+            "❤️",
+        ).await.unwrap();
+        let announcements = get_active_announcements(
+            db_client,
+            user_2.id,
+            false,
+        ).await.unwrap();
+        assert_eq!(announcements.len(), 1);
+        assert!(!announcements[0].is_read);
+        assert_eq!(announcements[0].reactions[0].count, 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_react_to_inactive_announcement() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let scheduled_data = AnnouncementData {
+            content: "Scheduled".to_string(),
+            published_at: Utc::now() + Duration::days(1),
+            expires_at: None,
+        };
+        let scheduled =
+            create_announcement(db_client, scheduled_data).await.unwrap();
+        let expired_data = AnnouncementData {
+            content: "Expired".to_string(),
+            published_at: Utc::now() - Duration::days(2),
+            expires_at: Some(Utc::now() - Duration::days(1)),
+        };
+        let expired =
+            create_announcement(db_client, expired_data).await.unwrap();
+        for announcement_id in [scheduled.id, expired.id] {
+            let error = create_announcement_reaction(
+                db_client,
+                user.id,
+                announcement_id,
+                "❤️",
+                None,
+            ).await.err().unwrap();
+            assert!(matches!(error, DatabaseError::NotFound("announcement")));
+            let error = dismiss_announcement(
+                db_client,
+                user.id,
+                announcement_id,
+            ).await.err().unwrap();
+            assert!(matches!(error, DatabaseError::NotFound("announcement")));
+        };
+    }
+}
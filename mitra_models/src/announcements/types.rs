@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use postgres_types::FromSql;
+use tokio_postgres::{Error as PgError, Row};
+
+use crate::emojis::types::CustomEmoji;
+
+#[derive(Clone, FromSql)]
+#[postgres(name = "announcement")]
+pub struct Announcement {
+    pub id: i32,
+    pub content: String,
+    pub published_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Announcement {
+    pub fn is_active(&self) -> bool {
+        let now = Utc::now();
+        self.published_at <= now &&
+            self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+pub struct AnnouncementData {
+    pub content: String,
+    pub published_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+pub struct AnnouncementReaction {
+    pub announcement_id: i32,
+    pub content: String,
+    pub emoji: Option<CustomEmoji>,
+    pub count: i64,
+    pub is_own: bool,
+}
+
+impl TryFrom<&Row> for AnnouncementReaction {
+    type Error = PgError;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let reaction = Self {
+            announcement_id: row.try_get("announcement_id")?,
+            content: row.try_get("content")?,
+            emoji: row.try_get("emoji")?,
+            count: row.try_get("count")?,
+            is_own: row.try_get("is_own")?,
+        };
+        Ok(reaction)
+    }
+}
+
+pub struct AnnouncementDetailed {
+    pub announcement: Announcement,
+    pub is_read: bool,
+    pub reactions: Vec<AnnouncementReaction>,
+}
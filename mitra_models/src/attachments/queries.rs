@@ -3,16 +3,21 @@ use uuid::Uuid;
 
 use mitra_utils::id::generate_ulid;
 
-use crate::database::{DatabaseClient, DatabaseError};
+use crate::accounts::types::{DbUser, User};
+use crate::database::{DatabaseClient, DatabaseError, DatabaseTypeError};
 use crate::media::types::{DeletionQueue, MediaInfo};
+use crate::posts::types::Visibility;
+use crate::profiles::types::DbActorProfile;
 
-use super::types::MediaAttachment;
+use super::types::{MediaAttachment, MediaMetadata};
 
 pub async fn create_attachment(
     db_client: &impl DatabaseClient,
     owner_id: Uuid,
     media_info: MediaInfo,
     description: Option<&str>,
+    is_sensitive: bool,
+    metadata: Option<MediaMetadata>,
 ) -> Result<MediaAttachment, DatabaseError> {
     let attachment_id = generate_ulid();
     let inserted_row = db_client.query_one(
@@ -21,9 +26,11 @@ pub async fn create_attachment(
             id,
             owner_id,
             media,
-            description
+            description,
+            is_sensitive,
+            metadata
         )
-        VALUES ($1, $2, $3, $4)
+        VALUES ($1, $2, $3, $4, $5, $6)
         RETURNING media_attachment
         ",
         &[
@@ -31,6 +38,8 @@ pub async fn create_attachment(
             &owner_id,
             &media_info,
             &description,
+            &is_sensitive,
+            &metadata,
         ],
     ).await?;
     let db_attachment = inserted_row.try_get("media_attachment")?;
@@ -55,20 +64,105 @@ pub async fn get_attachment(
     Ok(db_attachment)
 }
 
+/// Returns total size of media files uploaded by user
+/// (attachments and profile images), in bytes
+pub async fn get_storage_usage(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+) -> Result<u64, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        SELECT
+            coalesce(sum(file_size), 0)::bigint AS storage_usage
+        FROM (
+            SELECT (media ->> 'file_size')::bigint AS file_size
+            FROM media_attachment
+            WHERE owner_id = $1
+            UNION ALL
+            SELECT unnest(
+                ARRAY[
+                    (avatar ->> 'file_size')::bigint,
+                    (banner ->> 'file_size')::bigint
+                ]
+            )
+            FROM actor_profile
+            WHERE id = $1
+        ) AS user_media
+        ",
+        &[&owner_id],
+    ).await?;
+    let storage_usage: i64 = row.try_get("storage_usage")?;
+    let storage_usage = u64::try_from(storage_usage)
+        .map_err(|_| DatabaseTypeError)?;
+    Ok(storage_usage)
+}
+
+/// Returns local users with the largest storage usage
+pub async fn get_storage_usage_report(
+    db_client: &impl DatabaseClient,
+    limit: u16,
+) -> Result<Vec<(User, u64)>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT user_account, actor_profile, usage.storage_usage
+        FROM user_account
+        JOIN actor_profile USING (id)
+        JOIN (
+            SELECT
+                owner_id,
+                sum(file_size)::bigint AS storage_usage
+            FROM (
+                SELECT owner_id, (media ->> 'file_size')::bigint AS file_size
+                FROM media_attachment
+                UNION ALL
+                SELECT id, unnest(
+                    ARRAY[
+                        (avatar ->> 'file_size')::bigint,
+                        (banner ->> 'file_size')::bigint
+                    ]
+                )
+                FROM actor_profile
+                WHERE user_id IS NOT NULL
+            ) AS user_media
+            WHERE file_size IS NOT NULL
+            GROUP BY owner_id
+        ) AS usage ON (usage.owner_id = user_account.id)
+        ORDER BY usage.storage_usage DESC
+        LIMIT $1
+        ",
+        &[&i64::from(limit)],
+    ).await?;
+    let report = rows.iter()
+        .map(|row| {
+            let db_user: DbUser = row.try_get("user_account")?;
+            let db_profile: DbActorProfile = row.try_get("actor_profile")?;
+            let user = User::new(db_user, db_profile)?;
+            let storage_usage: i64 = row.try_get("storage_usage")?;
+            let storage_usage = u64::try_from(storage_usage)
+                .map_err(|_| DatabaseTypeError)?;
+            Ok((user, storage_usage))
+        })
+        .collect::<Result<_, DatabaseError>>()?;
+    Ok(report)
+}
+
 pub async fn update_attachment(
     db_client: &impl DatabaseClient,
     owner_id: Uuid,
     attachment_id: Uuid,
     description: Option<&str>,
+    is_sensitive: Option<bool>,
 ) -> Result<MediaAttachment, DatabaseError> {
     let maybe_row = db_client.query_opt(
         "
         UPDATE media_attachment
-        SET description = $1
-        WHERE owner_id = $2 AND id = $3
+        SET
+            description = $1,
+            is_sensitive = coalesce($2, is_sensitive)
+        WHERE owner_id = $3 AND id = $4
         RETURNING media_attachment
         ",
-        &[&description, &owner_id, &attachment_id],
+        &[&description, &is_sensitive, &owner_id, &attachment_id],
     ).await?;
     let row = maybe_row.ok_or(DatabaseError::NotFound("attachment"))?;
     let db_attachment = row.try_get("media_attachment")?;
@@ -101,7 +195,17 @@ pub async fn delete_unused_attachments(
     let rows = db_client.query(
         "
         DELETE FROM media_attachment
-        WHERE post_id IS NULL AND created_at < $1
+        WHERE
+            post_id IS NULL
+            AND created_at < $1
+            -- Attachments of scheduled posts are not deleted
+            AND NOT EXISTS (
+                SELECT 1 FROM scheduled_post
+                WHERE
+                    scheduled_post.owner_id = media_attachment.owner_id
+                    AND scheduled_post.post_data -> 'attachments'
+                        ? media_attachment.id::text
+            )
         RETURNING media ->> 'file_name' AS file_name, ipfs_cid
         ",
         &[&created_before],
@@ -119,12 +223,53 @@ pub async fn delete_unused_attachments(
     Ok((deleted_count, DeletionQueue { files, ipfs_objects }))
 }
 
+/// Returns IDs of non-public posts the file is attached to.
+/// Empty list means that access to the file is not restricted.
+pub async fn get_restricted_media_posts(
+    db_client: &impl DatabaseClient,
+    file_name: &str,
+) -> Result<Vec<Uuid>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT media_attachment.post_id, post.visibility
+        FROM media_attachment
+        LEFT JOIN post ON post.id = media_attachment.post_id
+        WHERE media_attachment.media ->> 'file_name' = $1
+        ",
+        &[&file_name],
+    ).await?;
+    let mut post_ids = vec![];
+    for row in rows {
+        let maybe_post_id: Option<Uuid> = row.try_get("post_id")?;
+        let maybe_visibility: Option<Visibility> = row.try_get("visibility")?;
+        match (maybe_post_id, maybe_visibility) {
+            (Some(post_id), Some(visibility))
+                if visibility != Visibility::Public =>
+            {
+                post_ids.push(post_id);
+            },
+            // Not attached or attached to a public post
+            _ => return Ok(vec![]),
+        };
+    };
+    Ok(post_ids)
+}
+
 #[cfg(test)]
 mod tests {
     use serial_test::serial;
+    use crate::accounts::test_utils::create_test_user;
     use crate::database::test_utils::create_test_database;
     use crate::media::types::PartialMediaInfo;
-    use crate::profiles::test_utils::create_test_local_profile;
+    use crate::posts::{
+        queries::create_post,
+        types::PostCreateData,
+    };
+    use crate::profiles::{
+        queries::update_profile,
+        test_utils::create_test_local_profile,
+        types::ProfileUpdateData,
+    };
     use super::*;
 
     #[tokio::test]
@@ -134,17 +279,26 @@ mod tests {
         let profile = create_test_local_profile(db_client, "test").await;
         let image_info = MediaInfo::png_for_test();
         let description = "test";
+        let metadata = MediaMetadata {
+            width: Some(640),
+            height: Some(480),
+            ..Default::default()
+        };
         let attachment = create_attachment(
             db_client,
             profile.id,
             image_info.clone(),
             Some(description),
+            false,
+            Some(metadata.clone()),
         ).await.unwrap();
         assert_eq!(attachment.owner_id, profile.id);
         assert_eq!(attachment.media, PartialMediaInfo::from(image_info));
         assert_eq!(attachment.description.unwrap(), description);
         assert_eq!(attachment.ipfs_cid.is_none(), true);
         assert_eq!(attachment.post_id.is_none(), true);
+        assert_eq!(attachment.is_sensitive, false);
+        assert_eq!(attachment.metadata, Some(metadata));
     }
 
     #[tokio::test]
@@ -159,6 +313,8 @@ mod tests {
             profile_1.id,
             image_info.clone(),
             None,
+            false,
+            None,
         ).await.unwrap();
 
         let attachment = get_attachment(
@@ -176,6 +332,70 @@ mod tests {
         assert!(matches!(error, DatabaseError::NotFound(_)));
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_get_storage_usage() {
+        let db_client = &mut create_test_database().await;
+        let profile = create_test_local_profile(db_client, "test").await;
+        let storage_usage = get_storage_usage(db_client, profile.id)
+            .await.unwrap();
+        assert_eq!(storage_usage, 0);
+        for _ in 0..2 {
+            create_attachment(
+                db_client,
+                profile.id,
+                MediaInfo::png_for_test(),
+                None,
+                false,
+                None,
+            ).await.unwrap();
+        };
+        let storage_usage = get_storage_usage(db_client, profile.id)
+            .await.unwrap();
+        assert_eq!(storage_usage, 20000);
+
+        let mut profile_data = ProfileUpdateData::from(&profile);
+        profile_data.avatar =
+            Some(PartialMediaInfo::from(MediaInfo::png_for_test()));
+        update_profile(db_client, profile.id, profile_data).await.unwrap();
+        let storage_usage = get_storage_usage(db_client, profile.id)
+            .await.unwrap();
+        assert_eq!(storage_usage, 30000);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_storage_usage_report() {
+        let db_client = &mut create_test_database().await;
+        let user_1 = create_test_user(db_client, "test1").await;
+        let user_2 = create_test_user(db_client, "test2").await;
+        let _user_3 = create_test_user(db_client, "test3").await;
+        create_attachment(
+            db_client,
+            user_1.id,
+            MediaInfo::png_for_test(),
+            None,
+            false,
+            None,
+        ).await.unwrap();
+        for _ in 0..2 {
+            create_attachment(
+                db_client,
+                user_2.id,
+                MediaInfo::png_for_test(),
+                None,
+                false,
+                None,
+            ).await.unwrap();
+        };
+        let report = get_storage_usage_report(db_client, 10).await.unwrap();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].0.id, user_2.id);
+        assert_eq!(report[0].1, 20000);
+        assert_eq!(report[1].0.id, user_1.id);
+        assert_eq!(report[1].1, 10000);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_update_attachment_remove_description() {
@@ -188,6 +408,8 @@ mod tests {
             profile.id,
             image_info,
             Some(description),
+            false,
+            None,
         ).await.unwrap();
         assert_eq!(attachment.description.unwrap(), description);
 
@@ -196,8 +418,60 @@ mod tests {
             profile.id,
             attachment.id,
             None,
+            None,
         ).await.unwrap();
         assert_eq!(attachment_updated.media, attachment.media);
         assert_eq!(attachment_updated.description, None);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_restricted_media_posts() {
+        let db_client = &mut create_test_database().await;
+        let profile = create_test_local_profile(db_client, "test").await;
+        let image_info = MediaInfo::png_for_test();
+        let file_name = "test.png";
+        let attachment = create_attachment(
+            db_client,
+            profile.id,
+            image_info.clone(),
+            None,
+            false,
+            None,
+        ).await.unwrap();
+        // Not attached
+        let post_ids = get_restricted_media_posts(db_client, file_name)
+            .await.unwrap();
+        assert_eq!(post_ids.is_empty(), true);
+
+        let post_data = PostCreateData {
+            content: "test".to_string(),
+            visibility: Visibility::Followers,
+            attachments: vec![attachment.id],
+            ..PostCreateData::for_test()
+        };
+        let post = create_post(db_client, profile.id, post_data).await.unwrap();
+        let post_ids = get_restricted_media_posts(db_client, file_name)
+            .await.unwrap();
+        assert_eq!(post_ids, vec![post.id]);
+
+        // Same file is attached to a public post
+        let attachment = create_attachment(
+            db_client,
+            profile.id,
+            image_info,
+            None,
+            false,
+            None,
+        ).await.unwrap();
+        let post_data = PostCreateData {
+            content: "test".to_string(),
+            attachments: vec![attachment.id],
+            ..PostCreateData::for_test()
+        };
+        create_post(db_client, profile.id, post_data).await.unwrap();
+        let post_ids = get_restricted_media_posts(db_client, file_name)
+            .await.unwrap();
+        assert_eq!(post_ids.is_empty(), true);
+    }
 }
@@ -1,7 +1,9 @@
 use chrono::{DateTime, Utc};
 use postgres_types::FromSql;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::database::json_macro::{json_from_sql, json_to_sql};
 use crate::media::types::PartialMediaInfo;
 
 pub enum AttachmentType {
@@ -11,6 +13,26 @@ pub enum AttachmentType {
     Audio,
 }
 
+/// Dimensions and duration of media file
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct MediaMetadata {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    // Duration in seconds
+    pub duration: Option<f64>,
+    // Bits per second
+    pub bitrate: Option<u64>,
+}
+
+impl MediaMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+json_from_sql!(MediaMetadata);
+json_to_sql!(MediaMetadata);
+
 #[derive(Clone, FromSql)]
 #[postgres(name = "media_attachment")]
 pub struct MediaAttachment {
@@ -20,6 +42,8 @@ pub struct MediaAttachment {
     pub description: Option<String>,
     pub ipfs_cid: Option<String>,
     pub post_id: Option<Uuid>,
+    pub is_sensitive: bool,
+    pub metadata: Option<MediaMetadata>,
     pub created_at: DateTime<Utc>,
 }
 
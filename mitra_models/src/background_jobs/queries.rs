@@ -6,7 +6,7 @@ use crate::database::{
     DatabaseClient,
     DatabaseError,
 };
-use super::types::{BackgroundJob, JobStatus, JobType};
+use super::types::{BackgroundJob, DeadLetter, JobStatus, JobType};
 
 pub async fn enqueue_job(
     db_client: &impl DatabaseClient,
@@ -88,6 +88,23 @@ pub async fn get_job_batch(
     Ok(jobs)
 }
 
+pub async fn get_job_by_id(
+    db_client: &impl DatabaseClient,
+    job_id: Uuid,
+) -> Result<BackgroundJob, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT background_job
+        FROM background_job
+        WHERE id = $1
+        ",
+        &[&job_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("background job"))?;
+    let job = row.try_get("background_job")?;
+    Ok(job)
+}
+
 pub async fn delete_job_from_queue(
     db_client: &impl DatabaseClient,
     job_id: Uuid,
@@ -121,6 +138,124 @@ pub async fn get_job_count(
     Ok(count)
 }
 
+pub async fn create_dead_letter(
+    db_client: &impl DatabaseClient,
+    activity_id: &str,
+    inbox: &str,
+    job_data: &Value,
+    errors: &[String],
+    first_failed_at: DateTime<Utc>,
+) -> Result<Uuid, DatabaseError> {
+    let dead_letter_id = Uuid::new_v4();
+    db_client.execute(
+        "
+        INSERT INTO dead_letter (
+            id,
+            activity_id,
+            inbox,
+            job_data,
+            errors,
+            first_failed_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ",
+        &[
+            &dead_letter_id,
+            &activity_id,
+            &inbox,
+            &job_data,
+            &errors,
+            &first_failed_at,
+        ],
+    ).await?;
+    Ok(dead_letter_id)
+}
+
+pub async fn get_dead_letter(
+    db_client: &impl DatabaseClient,
+    dead_letter_id: Uuid,
+) -> Result<DeadLetter, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT dead_letter
+        FROM dead_letter
+        WHERE id = $1
+        ",
+        &[&dead_letter_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("dead letter"))?;
+    let dead_letter = row.try_get("dead_letter")?;
+    Ok(dead_letter)
+}
+
+/// Returns dead letters, newest first
+pub async fn get_dead_letters(
+    db_client: &impl DatabaseClient,
+    limit: u16,
+    offset: u16,
+) -> Result<Vec<DeadLetter>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT dead_letter
+        FROM dead_letter
+        ORDER BY created_at DESC
+        LIMIT $1 OFFSET $2
+        ",
+        &[&i64::from(limit), &i64::from(offset)],
+    ).await?;
+    let dead_letters = rows.iter()
+        .map(|row| row.try_get("dead_letter"))
+        .collect::<Result<_, _>>()?;
+    Ok(dead_letters)
+}
+
+pub async fn delete_dead_letter(
+    db_client: &impl DatabaseClient,
+    dead_letter_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM dead_letter
+        WHERE id = $1
+        ",
+        &[&dead_letter_id],
+    ).await?;
+    if deleted_count == 0 {
+        return Err(DatabaseError::NotFound("dead letter"));
+    };
+    Ok(())
+}
+
+/// Deletes dead letters created before the given time
+/// (or all dead letters if time is not specified)
+pub async fn delete_dead_letters(
+    db_client: &impl DatabaseClient,
+    created_before: Option<DateTime<Utc>>,
+) -> Result<u64, DatabaseError> {
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM dead_letter
+        WHERE $1::timestamptz IS NULL OR created_at < $1
+        ",
+        &[&created_before],
+    ).await?;
+    Ok(deleted_count)
+}
+
+pub async fn get_dead_letter_count(
+    db_client: &impl DatabaseClient,
+) -> Result<i64, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        SELECT count(dead_letter)
+        FROM dead_letter
+        ",
+        &[],
+    ).await?;
+    let count = row.try_get("count")?;
+    Ok(count)
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -157,8 +292,44 @@ mod tests {
         let batch_2 = get_job_batch(db_client, job_type, 10, 3600).await.unwrap();
         assert_eq!(batch_2.len(), 0);
 
+        let job = get_job_by_id(db_client, job_id_1).await.unwrap();
+        assert_eq!(job.job_status, JobStatus::Running);
         delete_job_from_queue(db_client, job.id).await.unwrap();
+        let error = get_job_by_id(db_client, job_id_1).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("background job")));
         let batch_3 = get_job_batch(db_client, job_type, 10, 3600).await.unwrap();
         assert_eq!(batch_3.len(), 0);
     }
+    #[tokio::test]
+    #[serial]
+    async fn test_dead_letters() {
+        let db_client = &create_test_database().await;
+        let job_data = json!({
+            "activity": {"id": "https://social.example/activities/1"},
+            "failure_count": 0,
+        });
+        let errors = vec!["connection error".to_string()];
+        let dead_letter_id = create_dead_letter(
+            db_client,
+            "https://social.example/activities/1",
+            "https://remote.example/inbox",
+            &job_data,
+            &errors,
+            Utc::now(),
+        ).await.unwrap();
+        assert_eq!(get_dead_letter_count(db_client).await.unwrap(), 1);
+
+        let dead_letters = get_dead_letters(db_client, 10, 0).await.unwrap();
+        assert_eq!(dead_letters.len(), 1);
+        let dead_letter = &dead_letters[0];
+        assert_eq!(dead_letter.id, dead_letter_id);
+        assert_eq!(dead_letter.inbox, "https://remote.example/inbox");
+        assert_eq!(dead_letter.job_data, job_data);
+        assert_eq!(dead_letter.errors, errors);
+
+        let deleted_count = delete_dead_letters(db_client, None).await.unwrap();
+        assert_eq!(deleted_count, 1);
+        let error = get_dead_letter(db_client, dead_letter_id).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("dead letter")));
+    }
 }
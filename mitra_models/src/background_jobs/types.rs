@@ -15,6 +15,7 @@ pub enum JobType {
     MediaCleanup,
     DataImport,
     Fetcher,
+    PushNotification,
 }
 
 impl From<JobType> for i16 {
@@ -25,6 +26,7 @@ impl From<JobType> for i16 {
             JobType::MediaCleanup => 3,
             JobType::DataImport => 4,
             JobType::Fetcher => 5,
+            JobType::PushNotification => 7,
         }
     }
 }
@@ -39,6 +41,7 @@ impl TryFrom<i16> for JobType {
             3 => Self::MediaCleanup,
             4 => Self::DataImport,
             5 => Self::Fetcher,
+            7 => Self::PushNotification,
             _ => return Err(DatabaseTypeError),
         };
         Ok(job_type)
@@ -89,3 +92,16 @@ pub struct BackgroundJob {
     pub scheduled_for: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+/// Delivery that failed after all retries
+#[derive(FromSql)]
+#[postgres(name = "dead_letter")]
+pub struct DeadLetter {
+    pub id: Uuid,
+    pub activity_id: String,
+    pub inbox: String,
+    pub job_data: Value,
+    pub errors: Vec<String>,
+    pub first_failed_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
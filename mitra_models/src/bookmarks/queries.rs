@@ -12,23 +12,44 @@ use crate::{
     },
 };
 
-use super::types::BookmarkedPost;
+use super::types::{BookmarkFolder, BookmarkedPost};
 
 pub async fn create_bookmark(
     db_client: &impl DatabaseClient,
     owner_id: Uuid,
     post_id: Uuid,
+    folder_id: Option<i32>,
 ) -> Result<(), DatabaseError> {
     db_client.execute(
         "
-        INSERT INTO bookmark (owner_id, post_id)
-        VALUES ($1, $2)
+        INSERT INTO bookmark (owner_id, post_id, folder_id)
+        VALUES ($1, $2, $3)
         ",
-        &[&owner_id, &post_id],
+        &[&owner_id, &post_id, &folder_id],
     ).await.map_err(catch_unique_violation("bookmark"))?;
     Ok(())
 }
 
+pub async fn set_bookmark_folder(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+    post_id: Uuid,
+    folder_id: Option<i32>,
+) -> Result<(), DatabaseError> {
+    let updated_count = db_client.execute(
+        "
+        UPDATE bookmark
+        SET folder_id = $3
+        WHERE owner_id = $1 AND post_id = $2
+        ",
+        &[&owner_id, &post_id, &folder_id],
+    ).await?;
+    if updated_count == 0 {
+        return Err(DatabaseError::NotFound("bookmark"));
+    };
+    Ok(())
+}
+
 pub async fn delete_bookmark(
     db_client: &impl DatabaseClient,
     owner_id: Uuid,
@@ -50,6 +71,7 @@ pub async fn delete_bookmark(
 pub async fn get_bookmarked_posts(
     db_client: &impl DatabaseClient,
     owner_id: Uuid,
+    folder_id: Option<i32>,
     max_bookmark_id: Option<i32>,
     limit: u16,
 ) -> Result<Vec<BookmarkedPost>, DatabaseError> {
@@ -66,6 +88,10 @@ pub async fn get_bookmarked_posts(
         JOIN bookmark ON bookmark.post_id = post.id
         WHERE
             bookmark.owner_id = $owner_id
+            AND (
+                $folder_id::integer IS NULL
+                OR bookmark.folder_id = $folder_id
+            )
             AND (
                 $max_bookmark_id::integer IS NULL
                 OR bookmark.id < $max_bookmark_id
@@ -79,6 +105,7 @@ pub async fn get_bookmarked_posts(
     let query = query!(
         &statement,
         owner_id=owner_id,
+        folder_id=folder_id,
         max_bookmark_id=max_bookmark_id,
         limit=limit,
     )?;
@@ -108,6 +135,99 @@ pub(crate) async fn find_bookmarked_by_user(
     Ok(bookmarked)
 }
 
+pub async fn create_bookmark_folder(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+    folder_name: &str,
+) -> Result<BookmarkFolder, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        INSERT INTO bookmark_folder (owner_id, folder_name)
+        VALUES ($1, $2)
+        RETURNING bookmark_folder
+        ",
+        &[&owner_id, &folder_name],
+    ).await.map_err(catch_unique_violation("bookmark folder"))?;
+    let folder = row.try_get("bookmark_folder")?;
+    Ok(folder)
+}
+
+pub async fn update_bookmark_folder(
+    db_client: &impl DatabaseClient,
+    folder_id: i32,
+    owner_id: Uuid,
+    folder_name: &str,
+) -> Result<BookmarkFolder, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE bookmark_folder
+        SET folder_name = $3
+        WHERE id = $1 AND owner_id = $2
+        RETURNING bookmark_folder
+        ",
+        &[&folder_id, &owner_id, &folder_name],
+    ).await.map_err(catch_unique_violation("bookmark folder"))?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("bookmark folder"))?;
+    let folder = row.try_get("bookmark_folder")?;
+    Ok(folder)
+}
+
+/// Deletes folder. Bookmarks are not deleted
+pub async fn delete_bookmark_folder(
+    db_client: &impl DatabaseClient,
+    folder_id: i32,
+    owner_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM bookmark_folder
+        WHERE id = $1 AND owner_id = $2
+        ",
+        &[&folder_id, &owner_id],
+    ).await?;
+    if deleted_count == 0 {
+        return Err(DatabaseError::NotFound("bookmark folder"));
+    };
+    Ok(())
+}
+
+pub async fn get_bookmark_folder(
+    db_client: &impl DatabaseClient,
+    folder_id: i32,
+    owner_id: Uuid,
+) -> Result<BookmarkFolder, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT bookmark_folder
+        FROM bookmark_folder
+        WHERE id = $1 AND owner_id = $2
+        ",
+        &[&folder_id, &owner_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("bookmark folder"))?;
+    let folder = row.try_get("bookmark_folder")?;
+    Ok(folder)
+}
+
+pub async fn get_bookmark_folders(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+) -> Result<Vec<BookmarkFolder>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT bookmark_folder
+        FROM bookmark_folder
+        WHERE owner_id = $1
+        ORDER BY folder_name ASC
+        ",
+        &[&owner_id],
+    ).await?;
+    let folders = rows.iter()
+        .map(|row| row.try_get("bookmark_folder"))
+        .collect::<Result<_, _>>()?;
+    Ok(folders)
+}
+
 #[cfg(test)]
 mod tests {
     use serial_test::serial;
@@ -130,11 +250,12 @@ mod tests {
             "test post",
         ).await;
 
-        create_bookmark(db_client, viewer.id, post.id).await.unwrap();
+        create_bookmark(db_client, viewer.id, post.id, None).await.unwrap();
         let bookmarks = get_bookmarked_posts(
             db_client,
             viewer.id,
             None,
+            None,
             5
         ).await.unwrap();
         assert_eq!(bookmarks.len(), 1);
@@ -145,8 +266,70 @@ mod tests {
             db_client,
             viewer.id,
             None,
+            None,
             5
         ).await.unwrap();
         assert_eq!(bookmarks.len(), 0);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_bookmark_folders() {
+        let db_client = &mut create_test_database().await;
+        let viewer = create_test_user(db_client, "viewer").await;
+        let author = create_test_user(db_client, "author").await;
+        let post_1 = create_test_local_post(db_client, author.id, "1").await;
+        let post_2 = create_test_local_post(db_client, author.id, "2").await;
+        let folder = create_bookmark_folder(
+            db_client,
+            viewer.id,
+            "test",
+        ).await.unwrap();
+        assert_eq!(folder.folder_name, "test");
+        let folder = update_bookmark_folder(
+            db_client,
+            folder.id,
+            viewer.id,
+            "renamed",
+        ).await.unwrap();
+        assert_eq!(folder.folder_name, "renamed");
+
+        create_bookmark(db_client, viewer.id, post_1.id, Some(folder.id))
+            .await.unwrap();
+        create_bookmark(db_client, viewer.id, post_2.id, None)
+            .await.unwrap();
+        let bookmarks = get_bookmarked_posts(
+            db_client,
+            viewer.id,
+            Some(folder.id),
+            None,
+            5
+        ).await.unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].post.id, post_1.id);
+
+        set_bookmark_folder(db_client, viewer.id, post_2.id, Some(folder.id))
+            .await.unwrap();
+        let bookmarks = get_bookmarked_posts(
+            db_client,
+            viewer.id,
+            Some(folder.id),
+            None,
+            5
+        ).await.unwrap();
+        assert_eq!(bookmarks.len(), 2);
+
+        delete_bookmark_folder(db_client, folder.id, viewer.id).await.unwrap();
+        let folders = get_bookmark_folders(db_client, viewer.id).await.unwrap();
+        assert!(folders.is_empty());
+        // Bookmarks are preserved
+        let bookmarks = get_bookmarked_posts(
+            db_client,
+            viewer.id,
+            None,
+            None,
+            5
+        ).await.unwrap();
+        assert_eq!(bookmarks.len(), 2);
+    }
 }
@@ -1,10 +1,20 @@
+use postgres_types::FromSql;
 use tokio_postgres::Row;
+use uuid::Uuid;
 
 use crate::{
     database::DatabaseError,
     posts::types::PostDetailed,
 };
 
+#[derive(FromSql)]
+#[postgres(name = "bookmark_folder")]
+pub struct BookmarkFolder {
+    pub id: i32,
+    pub owner_id: Uuid,
+    pub folder_name: String,
+}
+
 pub struct BookmarkedPost {
     pub bookmark_id: i32,
     pub post: PostDetailed,
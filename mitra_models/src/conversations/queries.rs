@@ -237,6 +237,61 @@ pub async fn set_conversation_tracking_status(
     Ok(())
 }
 
+/// Removes conversation tracking status if it is "mute".
+/// Returns `false` if conversation was not muted.
+pub async fn unmute_conversation(
+    db_client: &impl DatabaseClient,
+    conversation_id: Uuid,
+    account_id: Uuid,
+) -> Result<bool, DatabaseError> {
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM conversation_tracking
+        WHERE
+            conversation_id = $1
+            AND account_id = $2
+            AND tracking_status = $3
+        ",
+        &[&conversation_id, &account_id, &TrackingStatus::Mute],
+    ).await?;
+    Ok(deleted_count > 0)
+}
+
+/// Returns IDs of users who muted conversations of the given posts,
+/// or conversations of posts reposted or quoted by them
+pub(crate) async fn get_conversation_muters(
+    db_client: &impl DatabaseClient,
+    posts_ids: &[Uuid],
+) -> Result<Vec<Uuid>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT DISTINCT conversation_tracking.account_id
+        FROM post
+        JOIN conversation_tracking
+        ON conversation_tracking.conversation_id IN (
+            SELECT post.conversation_id
+            UNION ALL
+            SELECT repost_of.conversation_id
+            FROM post AS repost_of
+            WHERE repost_of.id = post.repost_of_id
+            UNION ALL
+            SELECT linked.conversation_id
+            FROM post_link
+            JOIN post AS linked ON post_link.target_id = linked.id
+            WHERE post_link.source_id = post.id
+        )
+        WHERE
+            post.id = ANY($1)
+            AND conversation_tracking.tracking_status = $2
+        ",
+        &[&posts_ids, &TrackingStatus::Mute],
+    ).await?;
+    let muters = rows.iter()
+        .map(|row| row.try_get("account_id"))
+        .collect::<Result<_, _>>()?;
+    Ok(muters)
+}
+
 /// Finds conversation tracking statuses for given posts
 pub(crate) async fn find_tracking_statuses_by_user(
     db_client: &impl DatabaseClient,
@@ -354,4 +409,69 @@ mod tests {
         ).await.unwrap();
         assert_eq!(statuses[0].1, None);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_conversation_muters() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_local_profile(db_client, "author").await;
+        let viewer = create_test_local_profile(db_client, "viewer").await;
+        let reposter = create_test_local_profile(db_client, "reposter").await;
+        let post = create_test_local_post(db_client, author.id, "test").await;
+        let repost_data = PostCreateData::repost(
+            post.id,
+            Visibility::Public,
+            None,
+        );
+        let repost = create_post(db_client, reposter.id, repost_data)
+            .await.unwrap();
+        let muters = get_conversation_muters(db_client, &[repost.id])
+            .await.unwrap();
+        assert_eq!(muters.is_empty(), true);
+
+        set_conversation_tracking_status(
+            db_client,
+            post.expect_conversation().id,
+            viewer.id,
+            Some(TrackingStatus::Mute),
+        ).await.unwrap();
+        let muters = get_conversation_muters(db_client, &[post.id])
+            .await.unwrap();
+        assert_eq!(muters, vec![viewer.id]);
+        let muters = get_conversation_muters(db_client, &[post.id, repost.id])
+            .await.unwrap();
+        assert_eq!(muters, vec![viewer.id]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_unmute_conversation() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_local_profile(db_client, "author").await;
+        let viewer = create_test_local_profile(db_client, "viewer").await;
+        let post = create_test_local_post(db_client, author.id, "test").await;
+        let conversation_id = post.expect_conversation().id;
+        set_conversation_tracking_status(
+            db_client,
+            conversation_id,
+            viewer.id,
+            Some(TrackingStatus::Follow),
+        ).await.unwrap();
+        let is_unmuted = unmute_conversation(db_client, conversation_id, viewer.id)
+            .await.unwrap();
+        assert_eq!(is_unmuted, false);
+
+        set_conversation_tracking_status(
+            db_client,
+            conversation_id,
+            viewer.id,
+            Some(TrackingStatus::Mute),
+        ).await.unwrap();
+        let is_unmuted = unmute_conversation(db_client, conversation_id, viewer.id)
+            .await.unwrap();
+        assert_eq!(is_unmuted, true);
+        let muters = get_conversation_muters(db_client, &[post.id])
+            .await.unwrap();
+        assert_eq!(muters.is_empty(), true);
+    }
 }
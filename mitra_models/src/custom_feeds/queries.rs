@@ -1,15 +1,39 @@
+use tokio_postgres::Row;
 use uuid::Uuid;
 
 use crate::{
+    accounts::{
+        queries::create_automated_account,
+        types::{
+            AutomatedAccount,
+            AutomatedAccountData,
+            AutomatedAccountDetailed,
+            AutomatedAccountType,
+        },
+    },
     database::{
         catch_unique_violation,
         DatabaseClient,
         DatabaseError,
     },
+    posts::types::Visibility,
     profiles::types::DbActorProfile,
+    relationships::types::RelationshipType,
 };
 
-use super::types::CustomFeed;
+use super::types::{CustomFeed, CustomFeedActor, CustomFeedActorData};
+
+impl TryFrom<&Row> for CustomFeedActor {
+    type Error = DatabaseError;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let feed: CustomFeed = row.try_get("custom_feed")?;
+        let db_account: AutomatedAccount = row.try_get("automated_account")?;
+        let db_profile: DbActorProfile = row.try_get("actor_profile")?;
+        let account = AutomatedAccountDetailed::new(db_account, db_profile)?;
+        Ok(Self { feed, account })
+    }
+}
 
 pub async fn create_custom_feed(
     db_client: &impl DatabaseClient,
@@ -179,12 +203,307 @@ pub async fn get_custom_feeds_by_source(
     Ok(feeds)
 }
 
+pub async fn set_custom_feed_shared(
+    db_client: &impl DatabaseClient,
+    feed_id: i32,
+    owner_id: Uuid,
+    is_shared: bool,
+) -> Result<CustomFeed, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE custom_feed
+        SET is_shared = $3
+        WHERE id = $1 AND owner_id = $2
+        RETURNING custom_feed
+        ",
+        &[&feed_id, &owner_id, &is_shared],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("custom feed"))?;
+    let feed = row.try_get("custom_feed")?;
+    Ok(feed)
+}
+
+pub async fn get_shared_custom_feed(
+    db_client: &impl DatabaseClient,
+    feed_id: i32,
+) -> Result<CustomFeed, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT custom_feed
+        FROM custom_feed
+        WHERE id = $1 AND is_shared IS TRUE
+        ",
+        &[&feed_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("custom feed"))?;
+    let feed = row.try_get("custom_feed")?;
+    Ok(feed)
+}
+
+pub async fn get_shared_custom_feeds(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+) -> Result<Vec<CustomFeed>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT custom_feed
+        FROM custom_feed
+        WHERE owner_id = $1 AND is_shared IS TRUE
+        ORDER BY feed_name ASC
+        ",
+        &[&owner_id],
+    ).await?;
+    let feeds = rows.iter()
+        .map(|row| row.try_get("custom_feed"))
+        .collect::<Result<_, _>>()?;
+    Ok(feeds)
+}
+
+/// Returns custom feed if it is owned by the reader,
+/// or if it is shared, the reader is subscribed to it
+/// and still follows its owner
+pub async fn get_readable_custom_feed(
+    db_client: &impl DatabaseClient,
+    feed_id: i32,
+    reader_id: Uuid,
+) -> Result<CustomFeed, DatabaseError> {
+    let statement = format!(
+        "
+        SELECT custom_feed
+        FROM custom_feed
+        WHERE
+            id = $1
+            AND (
+                owner_id = $2
+                OR is_shared IS TRUE AND EXISTS (
+                    SELECT 1
+                    FROM custom_feed_subscription
+                    WHERE feed_id = custom_feed.id AND subscriber_id = $2
+                ) AND EXISTS (
+                    SELECT 1
+                    FROM relationship
+                    WHERE
+                        source_id = $2
+                        AND target_id = custom_feed.owner_id
+                        AND relationship_type = {relationship_follow}
+                )
+            )
+        ",
+        relationship_follow=i16::from(RelationshipType::Follow),
+    );
+    let maybe_row = db_client.query_opt(
+        &statement,
+        &[&feed_id, &reader_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("custom feed"))?;
+    let feed = row.try_get("custom_feed")?;
+    Ok(feed)
+}
+
+pub async fn subscribe_to_custom_feed(
+    db_client: &impl DatabaseClient,
+    feed_id: i32,
+    subscriber_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let inserted_count = db_client.execute(
+        "
+        INSERT INTO custom_feed_subscription (feed_id, subscriber_id)
+        SELECT id, $2
+        FROM custom_feed
+        WHERE
+            id = $1
+            AND is_shared IS TRUE
+            AND owner_id != $2
+        ",
+        &[&feed_id, &subscriber_id],
+    ).await.map_err(catch_unique_violation("custom feed subscription"))?;
+    if inserted_count == 0 {
+        return Err(DatabaseError::NotFound("custom feed"));
+    };
+    Ok(())
+}
+
+pub async fn unsubscribe_from_custom_feed(
+    db_client: &impl DatabaseClient,
+    feed_id: i32,
+    subscriber_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM custom_feed_subscription
+        WHERE feed_id = $1 AND subscriber_id = $2
+        ",
+        &[&feed_id, &subscriber_id],
+    ).await?;
+    if deleted_count == 0 {
+        return Err(DatabaseError::NotFound("custom feed subscription"));
+    };
+    Ok(())
+}
+
+pub async fn get_subscribed_custom_feeds(
+    db_client: &impl DatabaseClient,
+    subscriber_id: Uuid,
+) -> Result<Vec<CustomFeed>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT custom_feed
+        FROM custom_feed
+        JOIN custom_feed_subscription
+        ON custom_feed_subscription.feed_id = custom_feed.id
+        WHERE
+            custom_feed_subscription.subscriber_id = $1
+            AND custom_feed.is_shared IS TRUE
+        ORDER BY custom_feed.feed_name ASC
+        ",
+        &[&subscriber_id],
+    ).await?;
+    let feeds = rows.iter()
+        .map(|row| row.try_get("custom_feed"))
+        .collect::<Result<_, _>>()?;
+    Ok(feeds)
+}
+
+/// Creates an actor that boosts posts from the custom feed
+pub async fn create_custom_feed_actor(
+    db_client: &mut impl DatabaseClient,
+    feed_id: i32,
+    owner_id: Uuid,
+    actor_data: CustomFeedActorData,
+) -> Result<CustomFeedActor, DatabaseError> {
+    let mut transaction = db_client.transaction().await?;
+    let maybe_row = transaction.query_opt(
+        "
+        SELECT custom_feed
+        FROM custom_feed
+        WHERE id = $1 AND owner_id = $2
+        FOR UPDATE
+        ",
+        &[&feed_id, &owner_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("custom feed"))?;
+    let feed: CustomFeed = row.try_get("custom_feed")?;
+    if feed.actor_id.is_some() {
+        return Err(DatabaseError::AlreadyExists("custom feed actor"));
+    };
+    let account_data = AutomatedAccountData {
+        username: actor_data.username,
+        bio: actor_data.bio,
+        bio_source: None,
+        emojis: vec![],
+        account_type: AutomatedAccountType::CustomFeed,
+        rsa_secret_key: actor_data.rsa_secret_key,
+        ed25519_secret_key: actor_data.ed25519_secret_key,
+    };
+    let account =
+        create_automated_account(&mut transaction, account_data).await?;
+    let row = transaction.query_one(
+        "
+        UPDATE custom_feed
+        SET actor_id = $2
+        WHERE id = $1
+        RETURNING custom_feed
+        ",
+        &[&feed.id, &account.id],
+    ).await?;
+    let feed = row.try_get("custom_feed")?;
+    transaction.commit().await?;
+    Ok(CustomFeedActor { feed, account })
+}
+
+pub async fn get_custom_feed_actors(
+    db_client: &impl DatabaseClient,
+) -> Result<Vec<CustomFeedActor>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT custom_feed, automated_account, actor_profile
+        FROM custom_feed
+        JOIN automated_account ON automated_account.id = custom_feed.actor_id
+        JOIN actor_profile ON actor_profile.id = custom_feed.actor_id
+        ",
+        &[],
+    ).await?;
+    let actors = rows.iter()
+        .map(CustomFeedActor::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(actors)
+}
+
+pub async fn get_custom_feed_actor(
+    db_client: &impl DatabaseClient,
+    feed_id: i32,
+    owner_id: Uuid,
+) -> Result<CustomFeedActor, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT custom_feed, automated_account, actor_profile
+        FROM custom_feed
+        JOIN automated_account ON automated_account.id = custom_feed.actor_id
+        JOIN actor_profile ON actor_profile.id = custom_feed.actor_id
+        WHERE custom_feed.id = $1 AND custom_feed.owner_id = $2
+        ",
+        &[&feed_id, &owner_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("custom feed actor"))?;
+    let actor = CustomFeedActor::try_from(&row)?;
+    Ok(actor)
+}
+
+/// Finds public top-level posts from feed sources
+/// that were created after the feed actor,
+/// and haven't been reposted by it yet
+pub async fn find_custom_feed_posts_to_boost(
+    db_client: &impl DatabaseClient,
+    feed_id: i32,
+    actor_id: Uuid,
+    limit: u16,
+) -> Result<Vec<Uuid>, DatabaseError> {
+    let statement = format!(
+        "
+        SELECT post.id
+        FROM post
+        JOIN custom_feed_source
+        ON custom_feed_source.source_id = post.author_id
+        JOIN automated_account
+        ON automated_account.id = $2
+        WHERE
+            custom_feed_source.feed_id = $1
+            AND post.repost_of_id IS NULL
+            AND post.in_reply_to_id IS NULL
+            AND post.visibility = {visibility_public}
+            AND post.created_at > automated_account.created_at
+            AND NOT EXISTS (
+                SELECT 1
+                FROM post AS repost
+                WHERE repost.repost_of_id = post.id AND repost.author_id = $2
+            )
+        ORDER BY post.id ASC
+        LIMIT $3
+        ",
+        visibility_public=i16::from(Visibility::Public),
+    );
+    let rows = db_client.query(
+        &statement,
+        &[&feed_id, &actor_id, &i64::from(limit)],
+    ).await?;
+    let post_ids = rows.iter()
+        .map(|row| row.try_get("id"))
+        .collect::<Result<_, _>>()?;
+    Ok(post_ids)
+}
+
 #[cfg(test)]
 mod tests {
+    use apx_core::crypto::{
+        eddsa::generate_weak_ed25519_key,
+        rsa::generate_weak_rsa_key,
+    };
     use serial_test::serial;
     use crate::{
         accounts::test_utils::create_test_user,
         database::test_utils::create_test_database,
+        posts::test_utils::create_test_local_post,
+        relationships::queries::{follow, unfollow},
     };
     use super::*;
 
@@ -295,4 +614,97 @@ mod tests {
         assert_eq!(sources.len(), 1);
         assert_eq!(sources[0].id, author_1.id);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_custom_feed_subscription() {
+        let db_client = &mut create_test_database().await;
+        let owner = create_test_user(db_client, "owner").await;
+        let subscriber = create_test_user(db_client, "subscriber").await;
+        let feed = create_custom_feed(
+            db_client,
+            owner.id,
+            "test",
+        ).await.unwrap();
+        assert_eq!(feed.is_shared, false);
+        let error = subscribe_to_custom_feed(
+            db_client,
+            feed.id,
+            subscriber.id,
+        ).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("custom feed")));
+
+        let feed = set_custom_feed_shared(
+            db_client,
+            feed.id,
+            owner.id,
+            true,
+        ).await.unwrap();
+        assert_eq!(feed.is_shared, true);
+        follow(db_client, subscriber.id, owner.id).await.unwrap();
+        subscribe_to_custom_feed(db_client, feed.id, subscriber.id)
+            .await.unwrap();
+        let feeds = get_subscribed_custom_feeds(db_client, subscriber.id)
+            .await.unwrap();
+        assert_eq!(feeds.len(), 1);
+        let feed = get_readable_custom_feed(db_client, feed.id, subscriber.id)
+            .await.unwrap();
+        assert_eq!(feed.owner_id, owner.id);
+
+        // Access is lost after unfollowing the owner
+        unfollow(db_client, subscriber.id, owner.id).await.unwrap();
+        let error = get_readable_custom_feed(db_client, feed.id, subscriber.id)
+            .await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("custom feed")));
+        follow(db_client, subscriber.id, owner.id).await.unwrap();
+
+        unsubscribe_from_custom_feed(db_client, feed.id, subscriber.id)
+            .await.unwrap();
+        let error = get_readable_custom_feed(db_client, feed.id, subscriber.id)
+            .await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("custom feed")));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_custom_feed_actor() {
+        let db_client = &mut create_test_database().await;
+        let owner = create_test_user(db_client, "owner").await;
+        let author = create_test_user(db_client, "author").await;
+        let feed = create_custom_feed(
+            db_client,
+            owner.id,
+            "test",
+        ).await.unwrap();
+        add_custom_feed_sources(db_client, feed.id, &[author.id])
+            .await.unwrap();
+        let actor_data = CustomFeedActorData {
+            username: "test_feed".to_owned(),
+            bio: None,
+            rsa_secret_key: generate_weak_rsa_key().unwrap(),
+            ed25519_secret_key: generate_weak_ed25519_key(),
+        };
+        let feed_actor = create_custom_feed_actor(
+            db_client,
+            feed.id,
+            owner.id,
+            actor_data,
+        ).await.unwrap();
+        assert_eq!(feed_actor.feed.actor_id, Some(feed_actor.account.id));
+        assert_eq!(
+            feed_actor.account.account_type,
+            AutomatedAccountType::CustomFeed,
+        );
+        let feed_actors = get_custom_feed_actors(db_client).await.unwrap();
+        assert_eq!(feed_actors.len(), 1);
+
+        let post = create_test_local_post(db_client, author.id, "test").await;
+        let post_ids = find_custom_feed_posts_to_boost(
+            db_client,
+            feed.id,
+            feed_actor.account.id,
+            10,
+        ).await.unwrap();
+        assert_eq!(post_ids, vec![post.id]);
+    }
 }
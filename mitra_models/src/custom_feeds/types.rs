@@ -1,10 +1,30 @@
+use apx_core::crypto::{
+    eddsa::Ed25519SecretKey,
+    rsa::RsaSecretKey,
+};
 use postgres_types::FromSql;
 use uuid::Uuid;
 
+use crate::accounts::types::AutomatedAccountDetailed;
+
 #[derive(FromSql)]
 #[postgres(name = "custom_feed")]
 pub struct CustomFeed {
     pub id: i32,
     pub owner_id: Uuid,
     pub feed_name: String,
+    pub is_shared: bool,
+    pub actor_id: Option<Uuid>,
+}
+
+pub struct CustomFeedActor {
+    pub feed: CustomFeed,
+    pub account: AutomatedAccountDetailed,
+}
+
+pub struct CustomFeedActorData {
+    pub username: String,
+    pub bio: Option<String>,
+    pub rsa_secret_key: RsaSecretKey,
+    pub ed25519_secret_key: Ed25519SecretKey,
 }
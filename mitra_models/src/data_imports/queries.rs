@@ -0,0 +1,266 @@
+use uuid::Uuid;
+
+use mitra_utils::id::generate_ulid;
+
+use crate::database::{DatabaseClient, DatabaseError};
+
+use super::types::{
+    DataImport,
+    ImportEntries,
+    ImportStatus,
+    ImportType,
+};
+
+pub async fn create_data_import(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+    import_type: ImportType,
+    addresses: Vec<String>,
+) -> Result<DataImport, DatabaseError> {
+    let import_id = generate_ulid();
+    let entries = ImportEntries::pending(addresses);
+    let row = db_client.query_one(
+        "
+        INSERT INTO data_import (
+            id,
+            owner_id,
+            import_type,
+            import_status,
+            entries
+        )
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING data_import
+        ",
+        &[
+            &import_id,
+            &owner_id,
+            &import_type,
+            &ImportStatus::Pending,
+            &entries,
+        ],
+    ).await?;
+    let data_import = row.try_get("data_import")?;
+    Ok(data_import)
+}
+
+pub async fn get_data_import(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+    import_id: Uuid,
+) -> Result<DataImport, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT data_import
+        FROM data_import
+        WHERE owner_id = $1 AND id = $2
+        ",
+        &[&owner_id, &import_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("data import"))?;
+    let data_import = row.try_get("data_import")?;
+    Ok(data_import)
+}
+
+pub async fn get_data_import_by_id(
+    db_client: &impl DatabaseClient,
+    import_id: Uuid,
+) -> Result<DataImport, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT data_import
+        FROM data_import
+        WHERE id = $1
+        ",
+        &[&import_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("data import"))?;
+    let data_import = row.try_get("data_import")?;
+    Ok(data_import)
+}
+
+/// Saves per-entry outcomes and marks import as completed
+pub async fn finish_data_import(
+    db_client: &impl DatabaseClient,
+    import_id: Uuid,
+    entries: ImportEntries,
+) -> Result<DataImport, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE data_import
+        SET
+            import_status = $2,
+            entries = $3,
+            finished_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        RETURNING data_import
+        ",
+        &[&import_id, &ImportStatus::Completed, &entries],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("data import"))?;
+    let data_import = row.try_get("data_import")?;
+    Ok(data_import)
+}
+
+/// Saves per-entry outcomes of import that is still in progress
+pub async fn update_data_import_entries(
+    db_client: &impl DatabaseClient,
+    import_id: Uuid,
+    entries: ImportEntries,
+) -> Result<(), DatabaseError> {
+    let updated_count = db_client.execute(
+        "
+        UPDATE data_import
+        SET entries = $3
+        WHERE id = $1 AND import_status = $2
+        ",
+        &[&import_id, &ImportStatus::Pending, &entries],
+    ).await?;
+    if updated_count == 0 {
+        return Err(DatabaseError::NotFound("data import"));
+    };
+    Ok(())
+}
+
+/// Marks import as failed (processed entries keep their outcomes)
+pub async fn fail_data_import(
+    db_client: &impl DatabaseClient,
+    import_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let updated_count = db_client.execute(
+        "
+        UPDATE data_import
+        SET
+            import_status = $2,
+            finished_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        ",
+        &[&import_id, &ImportStatus::Failed],
+    ).await?;
+    if updated_count == 0 {
+        return Err(DatabaseError::NotFound("data import"));
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use crate::{
+        accounts::test_utils::create_test_user,
+        data_imports::types::{ImportEntry, ImportOutcome},
+        database::test_utils::create_test_database,
+    };
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_and_finish_data_import() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let addresses = vec![
+            "user1@one.example".to_string(),
+            "user2@two.example".to_string(),
+        ];
+        let data_import = create_data_import(
+            db_client,
+            user.id,
+            ImportType::Mutes,
+            addresses,
+        ).await.unwrap();
+        assert_eq!(data_import.owner_id, user.id);
+        assert_eq!(data_import.import_type, ImportType::Mutes);
+        assert_eq!(data_import.import_status, ImportStatus::Pending);
+        assert_eq!(data_import.entries.inner().len(), 2);
+        assert_eq!(data_import.entries.inner()[0].outcome, None);
+        assert_eq!(data_import.finished_at.is_none(), true);
+
+        let entries = ImportEntries::new(vec![
+            ImportEntry {
+                address: "user1@one.example".to_string(),
+                outcome: Some(ImportOutcome::Imported),
+            },
+            ImportEntry {
+                address: "user2@two.example".to_string(),
+                outcome: Some(ImportOutcome::NotFound),
+            },
+        ]);
+        finish_data_import(db_client, data_import.id, entries).await.unwrap();
+        let data_import = get_data_import(
+            db_client,
+            user.id,
+            data_import.id,
+        ).await.unwrap();
+        assert_eq!(data_import.import_status, ImportStatus::Completed);
+        assert_eq!(
+            data_import.entries.inner()[1].outcome,
+            Some(ImportOutcome::NotFound),
+        );
+        assert_eq!(data_import.finished_at.is_some(), true);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_data_import_other_owner() {
+        let db_client = &mut create_test_database().await;
+        let user_1 = create_test_user(db_client, "test1").await;
+        let user_2 = create_test_user(db_client, "test2").await;
+        let data_import = create_data_import(
+            db_client,
+            user_1.id,
+            ImportType::Mutes,
+            vec![],
+        ).await.unwrap();
+        let error = get_data_import(
+            db_client,
+            user_2.id,
+            data_import.id,
+        ).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("data import")));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_update_data_import_entries_and_fail() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let addresses = vec![
+            "user1@one.example".to_string(),
+            "user2@two.example".to_string(),
+        ];
+        let data_import = create_data_import(
+            db_client,
+            user.id,
+            ImportType::Blocks,
+            addresses,
+        ).await.unwrap();
+        let mut entries = data_import.entries.into_inner();
+        entries[0].outcome = Some(ImportOutcome::Failed);
+        update_data_import_entries(
+            db_client,
+            data_import.id,
+            ImportEntries::new(entries),
+        ).await.unwrap();
+        let data_import = get_data_import_by_id(db_client, data_import.id)
+            .await.unwrap();
+        assert_eq!(data_import.import_type, ImportType::Blocks);
+        assert_eq!(data_import.import_status, ImportStatus::Pending);
+        assert_eq!(
+            data_import.entries.inner()[0].outcome,
+            Some(ImportOutcome::Failed),
+        );
+        assert_eq!(data_import.entries.inner()[1].outcome, None);
+
+        fail_data_import(db_client, data_import.id).await.unwrap();
+        let data_import = get_data_import_by_id(db_client, data_import.id)
+            .await.unwrap();
+        assert_eq!(data_import.import_status, ImportStatus::Failed);
+        assert_eq!(data_import.finished_at.is_some(), true);
+        // Can't update finished import
+        let error = update_data_import_entries(
+            db_client,
+            data_import.id,
+            data_import.entries,
+        ).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("data import")));
+    }
+}
@@ -0,0 +1,138 @@
+use chrono::{DateTime, Utc};
+use postgres_types::FromSql;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::database::{
+    int_enum::{int_enum_from_sql, int_enum_to_sql},
+    json_macro::{json_from_sql, json_to_sql},
+    DatabaseTypeError,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImportType {
+    Mutes,
+    Blocks,
+    Follows,
+}
+
+impl From<ImportType> for i16 {
+    fn from(value: ImportType) -> i16 {
+        match value {
+            ImportType::Mutes => 1,
+            ImportType::Blocks => 2,
+            ImportType::Follows => 3,
+        }
+    }
+}
+
+impl TryFrom<i16> for ImportType {
+    type Error = DatabaseTypeError;
+
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        let import_type = match value {
+            1 => Self::Mutes,
+            2 => Self::Blocks,
+            3 => Self::Follows,
+            _ => return Err(DatabaseTypeError),
+        };
+        Ok(import_type)
+    }
+}
+
+int_enum_from_sql!(ImportType);
+int_enum_to_sql!(ImportType);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImportStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+impl From<ImportStatus> for i16 {
+    fn from(value: ImportStatus) -> i16 {
+        match value {
+            ImportStatus::Pending => 1,
+            ImportStatus::Completed => 2,
+            ImportStatus::Failed => 3,
+        }
+    }
+}
+
+impl TryFrom<i16> for ImportStatus {
+    type Error = DatabaseTypeError;
+
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        let import_status = match value {
+            1 => Self::Pending,
+            2 => Self::Completed,
+            3 => Self::Failed,
+            _ => return Err(DatabaseTypeError),
+        };
+        Ok(import_status)
+    }
+}
+
+int_enum_from_sql!(ImportStatus);
+int_enum_to_sql!(ImportStatus);
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportOutcome {
+    Imported,
+    NotFound,
+    AlreadyPresent,
+    // Entry can't be imported (e.g. it is the owner's own account)
+    Invalid,
+    // Unexpected error
+    Failed,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ImportEntry {
+    pub address: String,
+    // Not present if entry has not been processed yet
+    pub outcome: Option<ImportOutcome>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ImportEntries(Vec<ImportEntry>);
+
+impl ImportEntries {
+    pub fn new(entries: Vec<ImportEntry>) -> Self {
+        Self(entries)
+    }
+
+    pub fn pending(addresses: Vec<String>) -> Self {
+        let entries = addresses.into_iter()
+            .map(|address| ImportEntry { address, outcome: None })
+            .collect();
+        Self(entries)
+    }
+
+    pub fn inner(&self) -> &[ImportEntry] {
+        let Self(entries) = self;
+        entries
+    }
+
+    pub fn into_inner(self) -> Vec<ImportEntry> {
+        let Self(entries) = self;
+        entries
+    }
+}
+
+json_from_sql!(ImportEntries);
+json_to_sql!(ImportEntries);
+
+#[derive(FromSql)]
+#[postgres(name = "data_import")]
+pub struct DataImport {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub import_type: ImportType,
+    pub import_status: ImportStatus,
+    pub entries: ImportEntries,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
@@ -1,3 +1,4 @@
+use std::future::poll_fn;
 use std::path::Path;
 
 use deadpool_postgres::Pool;
@@ -7,9 +8,15 @@ use rustls::{
     RootCertStore,
 };
 use rustls_pemfile::certs;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+};
 use tokio_postgres::{
     config::{Config as DatabaseConfig},
+    AsyncMessage,
     Client,
+    Connection,
 };
 use tokio_postgres_rustls::MakeRustlsConnect;
 
@@ -77,12 +84,58 @@ pub async fn create_database_client(
     create_database_client_from_config(&database_config, ca_file_path).await
 }
 
-pub fn create_database_connection_pool(
+async fn forward_notifications<
+    S: AsyncRead + AsyncWrite + Unpin,
+    T: AsyncRead + AsyncWrite + Unpin,
+>(
+    mut connection: Connection<S, T>,
+    sender: UnboundedSender<String>,
+) -> () {
+    loop {
+        match poll_fn(|cx| connection.poll_message(cx)).await {
+            Some(Ok(AsyncMessage::Notification(notification))) => {
+                let payload = notification.payload().to_owned();
+                if sender.send(payload).is_err() {
+                    // Receiver dropped
+                    break;
+                };
+            },
+            Some(Ok(_)) => continue,
+            Some(Err(err)) => {
+                log::error!("connection error: {}", err);
+                break;
+            },
+            None => break,
+        };
+    };
+}
+
+/// Creates database client that receives asynchronous notifications.
+/// The channel is closed when connection is terminated.
+pub async fn create_listening_database_client(
     database_url: &str,
     ca_file_path: Option<&Path>,
+) -> Result<(Client, UnboundedReceiver<String>), DatabaseConnectionError> {
+    let db_config: DatabaseConfig = database_url.parse()?;
+    let (sender, receiver) = unbounded_channel();
+    let client = if let Some(ca_file_path) = ca_file_path {
+        let connector = create_tls_connector(ca_file_path)?;
+        let (client, connection) = db_config.connect(connector).await?;
+        tokio::spawn(forward_notifications(connection, sender));
+        client
+    } else {
+        let (client, connection) = db_config.connect(tokio_postgres::NoTls).await?;
+        tokio::spawn(forward_notifications(connection, sender));
+        client
+    };
+    Ok((client, receiver))
+}
+
+pub(super) fn create_database_connection_pool_from_config(
+    database_config: DatabaseConfig,
+    ca_file_path: Option<&Path>,
     pool_size: usize,
 ) -> Result<Pool, DatabaseConnectionError> {
-    let database_config = database_url.parse()?;
     let manager = if let Some(ca_file_path) = ca_file_path {
         let connector = create_tls_connector(ca_file_path)?;
         deadpool_postgres::Manager::new(
@@ -100,3 +153,16 @@ pub fn create_database_connection_pool(
         .build()?;
     Ok(db_pool)
 }
+
+pub fn create_database_connection_pool(
+    database_url: &str,
+    ca_file_path: Option<&Path>,
+    pool_size: usize,
+) -> Result<Pool, DatabaseConnectionError> {
+    let database_config = database_url.parse()?;
+    create_database_connection_pool_from_config(
+        database_config,
+        ca_file_path,
+        pool_size,
+    )
+}
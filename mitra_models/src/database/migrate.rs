@@ -1,6 +1,8 @@
 use refinery::Error;
 use tokio_postgres::Client;
 
+use super::{DatabaseClient, DatabaseError};
+
 mod embedded {
     use refinery::embed_migrations;
     embed_migrations!("migrations");
@@ -28,3 +30,35 @@ pub async fn apply_migrations(db_client: &mut Client) -> Result<(), Error> {
     };
     Ok(())
 }
+
+/// Returns the number of embedded migrations that are not applied yet
+pub async fn get_pending_migration_count(
+    db_client: &impl DatabaseClient,
+) -> Result<usize, DatabaseError> {
+    let row = db_client.query_one(
+        "SELECT max(version) AS version FROM refinery_schema_history",
+        &[],
+    ).await?;
+    let maybe_last_version: Option<i32> = row.try_get("version")?;
+    let last_version = maybe_last_version.map(i64::from).unwrap_or(0);
+    let runner = embedded::migrations::runner();
+    let count = runner.get_migrations().iter()
+        .filter(|migration| i64::from(migration.version()) > last_version)
+        .count();
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use crate::database::test_utils::create_test_database;
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_pending_migration_count() {
+        let db_client = &create_test_database().await;
+        let count = get_pending_migration_count(db_client).await.unwrap();
+        assert_eq!(count, 0);
+    }
+}
@@ -1,3 +1,5 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+
 use super::{DatabaseClient, DatabaseError, DatabaseTypeError};
 
 pub const POSTGRES_VERSION_MIN: u32 = 150_000;
@@ -10,3 +12,115 @@ pub async fn get_postgres_version(
     let version: u32 = version_str.parse().map_err(|_| DatabaseTypeError)?;
     Ok(version)
 }
+
+/// Checks timezone name against the IANA time zone database
+/// used by PostgreSQL
+pub async fn is_valid_timezone(
+    db_client: &impl DatabaseClient,
+    timezone: &str,
+) -> Result<bool, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        SELECT EXISTS (
+            SELECT 1 FROM pg_timezone_names WHERE name = $1
+        ) AS is_valid
+        ",
+        &[&timezone],
+    ).await?;
+    let is_valid = row.try_get("is_valid")?;
+    Ok(is_valid)
+}
+
+/// Converts local time in the given timezone to UTC
+pub async fn local_time_to_utc(
+    db_client: &impl DatabaseClient,
+    local_time: NaiveDateTime,
+    timezone: &str,
+) -> Result<DateTime<Utc>, DatabaseError> {
+    let row = db_client.query_one(
+        "SELECT $1::timestamp AT TIME ZONE $2 AS utc_time",
+        &[&local_time, &timezone],
+    ).await?;
+    let utc_time = row.try_get("utc_time")?;
+    Ok(utc_time)
+}
+
+/// Returns UTC offsets (in seconds) of the given timezone
+/// at the given moments
+pub async fn get_utc_offsets(
+    db_client: &impl DatabaseClient,
+    timezone: &str,
+    moments: &[DateTime<Utc>],
+) -> Result<Vec<i32>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT
+            EXTRACT(
+                EPOCH FROM
+                (moment AT TIME ZONE $1) - (moment AT TIME ZONE 'UTC')
+            )::integer AS utc_offset
+        FROM unnest($2::timestamptz[]) WITH ORDINALITY AS item(moment, index)
+        ORDER BY index
+        ",
+        &[&timezone, &moments],
+    ).await?;
+    let offsets = rows.iter()
+        .map(|row| row.try_get("utc_offset"))
+        .collect::<Result<_, _>>()?;
+    Ok(offsets)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serial_test::serial;
+    use crate::database::test_utils::create_test_database;
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_is_valid_timezone() {
+        let db_client = &create_test_database().await;
+        let is_valid = is_valid_timezone(db_client, "Europe/Berlin")
+            .await.unwrap();
+        assert_eq!(is_valid, true);
+        let is_valid = is_valid_timezone(db_client, "Europe/Nowhere")
+            .await.unwrap();
+        assert_eq!(is_valid, false);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_local_time_to_utc() {
+        let db_client = &create_test_database().await;
+        let local_time = NaiveDateTime::parse_from_str(
+            "2025-01-15 12:00:00",
+            "%Y-%m-%d %H:%M:%S",
+        ).unwrap();
+        let utc_time = local_time_to_utc(
+            db_client,
+            local_time,
+            "Asia/Tokyo",
+        ).await.unwrap();
+        assert_eq!(
+            utc_time,
+            Utc.with_ymd_and_hms(2025, 1, 15, 3, 0, 0).unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_utc_offsets() {
+        let db_client = &create_test_database().await;
+        let moments = [
+            Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 7, 15, 12, 0, 0).unwrap(),
+        ];
+        let offsets = get_utc_offsets(
+            db_client,
+            "Europe/Berlin",
+            &moments,
+        ).await.unwrap();
+        assert_eq!(offsets, vec![3600, 7200]);
+    }
+}
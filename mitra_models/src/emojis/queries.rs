@@ -10,10 +10,14 @@ use crate::{
     },
     instances::queries::create_instance,
     media::types::{DeletionQueue, MediaInfo, PartialMediaInfo},
-    profiles::queries::update_emoji_caches,
+    posts::queries::update_reaction_caches,
+    profiles::{
+        queries::update_emoji_caches,
+        types::Origin,
+    },
 };
 
-use super::types::CustomEmoji;
+use super::types::{CustomEmoji, EmojiUsage};
 
 /// Creates emoji or updates emoji with matching `emoji_name` and `hostname`.
 /// `object_id` is replaced on update.
@@ -72,6 +76,7 @@ pub async fn create_or_update_remote_emoji(
     ).await?;
     let emoji: CustomEmoji = row.try_get("emoji")?;
     update_emoji_caches(&transaction, emoji.id).await?;
+    update_reaction_caches(&transaction, emoji.id).await?;
     transaction.commit().await?;
     let deletion_queue = DeletionQueue {
         files: detached_files,
@@ -117,6 +122,7 @@ pub async fn update_remote_emoji(
     ).await?;
     let emoji: CustomEmoji = row.try_get("emoji")?;
     update_emoji_caches(&transaction, emoji.id).await?;
+    update_reaction_caches(&transaction, emoji.id).await?;
     transaction.commit().await?;
     let deletion_queue = DeletionQueue {
         files: detached_files,
@@ -175,6 +181,7 @@ pub async fn create_or_update_local_emoji(
     ).await?;
     let emoji: CustomEmoji = row.try_get("emoji")?;
     update_emoji_caches(&transaction, emoji.id).await?;
+    update_reaction_caches(&transaction, emoji.id).await?;
     transaction.commit().await?;
     let deletion_queue = DeletionQueue {
         files: detached_files,
@@ -236,6 +243,61 @@ pub async fn get_local_emojis(
     Ok(emojis)
 }
 
+/// Returns emojis with usage statistics.
+/// Emojis are filtered by name (substring match) and by origin
+pub async fn search_emojis(
+    db_client: &impl DatabaseClient,
+    maybe_search_query: Option<&str>,
+    maybe_origin: Option<Origin>,
+    limit: u16,
+    offset: u16,
+) -> Result<Vec<(CustomEmoji, EmojiUsage)>, DatabaseError> {
+    let maybe_db_search_query = maybe_search_query
+        .map(|search_query| format!("%{search_query}%"));
+    let maybe_is_local = maybe_origin
+        .map(|origin| matches!(origin, Origin::Local));
+    let rows = db_client.query(
+        "
+        SELECT
+            emoji,
+            (
+                SELECT count(*)
+                FROM post_emoji
+                WHERE post_emoji.emoji_id = emoji.id
+            ) AS posts,
+            (
+                SELECT count(*)
+                FROM post_reaction
+                WHERE post_reaction.emoji_id = emoji.id
+            ) AS reactions,
+            (
+                SELECT count(*)
+                FROM profile_emoji
+                WHERE profile_emoji.emoji_id = emoji.id
+            ) AS profiles
+        FROM emoji
+        WHERE
+            ($1::text IS NULL OR emoji_name ILIKE $1)
+            AND ($2::boolean IS NULL OR (hostname IS NULL) = $2)
+        ORDER BY hostname NULLS FIRST, emoji_name
+        LIMIT $3 OFFSET $4
+        ",
+        &[
+            &maybe_db_search_query,
+            &maybe_is_local,
+            &i64::from(limit),
+            &i64::from(offset),
+        ],
+    ).await?;
+    let mut emojis = vec![];
+    for row in rows {
+        let emoji = row.try_get("emoji")?;
+        let usage = EmojiUsage::try_from(&row)?;
+        emojis.push((emoji, usage));
+    };
+    Ok(emojis)
+}
+
 pub async fn get_emoji_by_name_and_hostname(
     db_client: &impl DatabaseClient,
     emoji_name: &str,
@@ -283,6 +345,7 @@ pub async fn delete_emoji(
     let row = maybe_row.ok_or(DatabaseError::NotFound("emoji"))?;
     let emoji: CustomEmoji = row.try_get("emoji")?;
     update_emoji_caches(db_client, emoji.id).await?;
+    update_reaction_caches(db_client, emoji.id).await?;
     let detached_files = emoji.image.into_file_name()
         .into_iter()
         .collect();
@@ -292,8 +355,12 @@ pub async fn delete_emoji(
     })
 }
 
+/// Finds remote emojis that are not used anywhere.
+/// If `updated_before` is provided, emojis that were updated
+/// after that time are skipped.
 pub async fn find_unused_remote_emojis(
     db_client: &impl DatabaseClient,
+    updated_before: Option<DateTime<Utc>>,
 ) -> Result<Vec<Uuid>, DatabaseError> {
     let rows = db_client.query(
         "
@@ -301,6 +368,7 @@ pub async fn find_unused_remote_emojis(
         FROM emoji
         WHERE
             emoji.object_id IS NOT NULL
+            AND ($1::timestamptz IS NULL OR emoji.updated_at < $1)
             AND NOT EXISTS (
                 SELECT 1
                 FROM post_emoji
@@ -317,7 +385,7 @@ pub async fn find_unused_remote_emojis(
                 WHERE profile_emoji.emoji_id = emoji.id
             )
         ",
-        &[],
+        &[&updated_before],
     ).await?;
     let ids: Vec<Uuid> = rows.iter()
         .map(|row| row.try_get("id"))
@@ -327,6 +395,7 @@ pub async fn find_unused_remote_emojis(
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeDelta;
     use serial_test::serial;
     use crate::{
         database::test_utils::create_test_database,
@@ -473,4 +542,81 @@ mod tests {
         let profile = get_profile_by_id(db_client, profile.id).await.unwrap();
         assert_eq!(profile.emojis.into_inner().len(), 0);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_search_emojis() {
+        let db_client = &mut create_test_database().await;
+        let image = MediaInfo::png_for_test();
+        let (local_emoji, _) = create_or_update_local_emoji(
+            db_client,
+            "blobcat",
+            image.clone(),
+            None,
+        ).await.unwrap();
+        create_or_update_remote_emoji(
+            db_client,
+            "blobfox",
+            "social.example",
+            image,
+            "https://social.example/emojis/blobfox",
+            Utc::now(),
+        ).await.unwrap();
+        let profile_data = ProfileCreateData {
+            emojis: vec![local_emoji.id],
+            ..ProfileCreateData::remote_for_test(
+                "test",
+                "social.example",
+                "https://social.example/actor",
+            )
+        };
+        create_profile(db_client, profile_data).await.unwrap();
+
+        let results = search_emojis(db_client, Some("blob"), None, 10, 0)
+            .await.unwrap();
+        assert_eq!(results.len(), 2);
+        let (emoji, usage) = &results[0];
+        assert_eq!(emoji.id, local_emoji.id);
+        assert_eq!(usage.posts, 0);
+        assert_eq!(usage.reactions, 0);
+        assert_eq!(usage.profiles, 1);
+
+        let results = search_emojis(db_client, Some("cat"), None, 10, 0)
+            .await.unwrap();
+        assert_eq!(results.len(), 1);
+        let results = search_emojis(
+            db_client,
+            None,
+            Some(Origin::Remote),
+            10,
+            0,
+        ).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0.emoji_name, "blobfox");
+        let results = search_emojis(db_client, None, None, 10, 1)
+            .await.unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_find_unused_remote_emojis() {
+        let db_client = &mut create_test_database().await;
+        let image = MediaInfo::png_for_test();
+        let (emoji, _) = create_or_update_remote_emoji(
+            db_client,
+            "test",
+            "social.example",
+            image,
+            "https://social.example/emojis/test",
+            Utc::now(),
+        ).await.unwrap();
+        let emojis = find_unused_remote_emojis(db_client, None)
+            .await.unwrap();
+        assert_eq!(emojis, vec![emoji.id]);
+        let updated_before = Utc::now() - TimeDelta::days(1);
+        let emojis = find_unused_remote_emojis(db_client, Some(updated_before))
+            .await.unwrap();
+        assert_eq!(emojis.is_empty(), true);
+    }
 }
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use postgres_types::FromSql;
 use serde::Deserialize;
+use tokio_postgres::{Error as PgError, Row};
 use uuid::Uuid;
 
 use crate::media::types::PartialMediaInfo;
@@ -22,4 +23,28 @@ impl CustomEmoji {
     pub fn shortcode(&self) -> String {
         format!(":{}:", self.emoji_name)
     }
+
+    pub fn hostname(&self) -> Option<&str> {
+        self.hostname.as_deref()
+    }
+}
+
+/// Usage statistics
+pub struct EmojiUsage {
+    pub posts: i64,
+    pub reactions: i64,
+    pub profiles: i64,
+}
+
+impl TryFrom<&Row> for EmojiUsage {
+    type Error = PgError;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let usage = Self {
+            posts: row.try_get("posts")?,
+            reactions: row.try_get("reactions")?,
+            profiles: row.try_get("profiles")?,
+        };
+        Ok(usage)
+    }
 }
@@ -0,0 +1,50 @@
+use crate::database::{DatabaseClient, DatabaseError, DatabaseTypeError};
+
+use super::types::{StreamEvent, EVENT_CHANNEL};
+
+/// Publishes event.
+/// If called within a transaction, the event is delivered after commit.
+pub async fn publish_event(
+    db_client: &impl DatabaseClient,
+    event: StreamEvent,
+) -> Result<(), DatabaseError> {
+    let payload = serde_json::to_string(&event)
+        .map_err(|_| DatabaseTypeError)?;
+    db_client.execute(
+        "SELECT pg_notify($1, $2)",
+        &[&EVENT_CHANNEL, &payload],
+    ).await?;
+    Ok(())
+}
+
+/// Subscribes connection to events
+pub async fn listen_events(
+    db_client: &impl DatabaseClient,
+) -> Result<(), DatabaseError> {
+    db_client.batch_execute(&format!("LISTEN {EVENT_CHANNEL}")).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use uuid::Uuid;
+    use crate::{
+        database::test_utils::create_test_database,
+        posts::types::Visibility,
+    };
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_publish_event() {
+        let db_client = &create_test_database().await;
+        listen_events(db_client).await.unwrap();
+        let event = StreamEvent::PostDeleted {
+            post_id: Uuid::new_v4(),
+            author_id: Uuid::new_v4(),
+            visibility: Visibility::Public,
+        };
+        publish_event(db_client, event).await.unwrap();
+    }
+}
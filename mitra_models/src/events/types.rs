@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::posts::types::Visibility;
+
+/// Notification channel used for broadcasting events
+pub const EVENT_CHANNEL: &str = "mitra_events";
+
+/// Event delivered to streaming API clients
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    // Properties of the post are used for filtering
+    // before checking visibility with database queries
+    PostCreated {
+        post_id: Uuid,
+        is_public: bool,
+        is_local: bool,
+        tags: Vec<String>,
+    },
+    PostDeleted {
+        post_id: Uuid,
+        author_id: Uuid,
+        visibility: Visibility,
+    },
+    NotificationCreated {
+        notification_id: i32,
+        recipient_id: Uuid,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_event_serialization() {
+        let post_id = Uuid::new_v4();
+        let event = StreamEvent::PostCreated {
+            post_id,
+            is_public: true,
+            is_local: false,
+            tags: vec!["test".to_string()],
+        };
+        let payload = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            payload,
+            format!(r#"{{"type":"post_created","post_id":"{post_id}","is_public":true,"is_local":false,"tags":["test"]}}"#),
+        );
+        let parsed: StreamEvent = serde_json::from_str(&payload).unwrap();
+        assert_eq!(parsed, event);
+
+        let author_id = Uuid::new_v4();
+        let event = StreamEvent::PostDeleted {
+            post_id,
+            author_id,
+            visibility: Visibility::Followers,
+        };
+        let payload = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            payload,
+            format!(r#"{{"type":"post_deleted","post_id":"{post_id}","author_id":"{author_id}","visibility":3}}"#),
+        );
+        let parsed: StreamEvent = serde_json::from_str(&payload).unwrap();
+        assert_eq!(parsed, event);
+    }
+}
@@ -0,0 +1,340 @@
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+use crate::{
+    accounts::{
+        queries::create_automated_account,
+        types::{
+            AutomatedAccount,
+            AutomatedAccountData,
+            AutomatedAccountDetailed,
+            AutomatedAccountType,
+        },
+    },
+    database::{
+        catch_unique_violation,
+        DatabaseClient,
+        DatabaseError,
+    },
+    profiles::types::DbActorProfile,
+    relationships::types::RelationshipType,
+};
+
+use super::types::{FeedSource, FeedSourceCreateData, FeedSourceDetailed};
+
+impl TryFrom<&Row> for FeedSourceDetailed {
+    type Error = DatabaseError;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let source: FeedSource = row.try_get("feed_source")?;
+        let db_account: AutomatedAccount = row.try_get("automated_account")?;
+        let db_profile: DbActorProfile = row.try_get("actor_profile")?;
+        let account = AutomatedAccountDetailed::new(db_account, db_profile)?;
+        Ok(Self { source, account })
+    }
+}
+
+pub async fn create_feed_source(
+    db_client: &mut impl DatabaseClient,
+    source_data: FeedSourceCreateData,
+) -> Result<FeedSourceDetailed, DatabaseError> {
+    let fetch_interval = i32::try_from(source_data.fetch_interval)
+        .map_err(|_| DatabaseError::type_error())?;
+    let entry_limit = i16::try_from(source_data.entry_limit)
+        .map_err(|_| DatabaseError::type_error())?;
+    let mut transaction = db_client.transaction().await?;
+    let account_data = AutomatedAccountData {
+        username: source_data.username,
+        bio: source_data.bio,
+        bio_source: source_data.bio_source,
+        emojis: vec![],
+        account_type: AutomatedAccountType::Feed,
+        rsa_secret_key: source_data.rsa_secret_key,
+        ed25519_secret_key: source_data.ed25519_secret_key,
+    };
+    let account =
+        create_automated_account(&mut transaction, account_data).await?;
+    let row = transaction.query_one(
+        "
+        INSERT INTO feed_source (
+            id,
+            feed_url,
+            fetch_interval,
+            entry_limit,
+            created_by
+        )
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING feed_source
+        ",
+        &[
+            &account.id,
+            &source_data.feed_url,
+            &fetch_interval,
+            &entry_limit,
+            &source_data.created_by,
+        ],
+    ).await.map_err(catch_unique_violation("feed source"))?;
+    let source = row.try_get("feed_source")?;
+    transaction.commit().await?;
+    Ok(FeedSourceDetailed { source, account })
+}
+
+pub async fn get_feed_source_by_id(
+    db_client: &impl DatabaseClient,
+    source_id: Uuid,
+) -> Result<FeedSourceDetailed, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT feed_source, automated_account, actor_profile
+        FROM feed_source
+        JOIN automated_account USING (id)
+        JOIN actor_profile USING (id)
+        WHERE feed_source.id = $1
+        ",
+        &[&source_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("feed source"))?;
+    let source = FeedSourceDetailed::try_from(&row)?;
+    Ok(source)
+}
+
+pub async fn get_feed_source_by_url(
+    db_client: &impl DatabaseClient,
+    feed_url: &str,
+) -> Result<FeedSourceDetailed, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT feed_source, automated_account, actor_profile
+        FROM feed_source
+        JOIN automated_account USING (id)
+        JOIN actor_profile USING (id)
+        WHERE feed_source.feed_url = $1
+        ",
+        &[&feed_url],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("feed source"))?;
+    let source = FeedSourceDetailed::try_from(&row)?;
+    Ok(source)
+}
+
+pub async fn get_feed_sources(
+    db_client: &impl DatabaseClient,
+) -> Result<Vec<FeedSourceDetailed>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT feed_source, automated_account, actor_profile
+        FROM feed_source
+        JOIN automated_account USING (id)
+        JOIN actor_profile USING (id)
+        ORDER BY feed_source.created_at
+        ",
+        &[],
+    ).await?;
+    let sources = rows.iter()
+        .map(FeedSourceDetailed::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(sources)
+}
+
+pub async fn get_feed_source_count_by_creator(
+    db_client: &impl DatabaseClient,
+    user_id: Uuid,
+) -> Result<i64, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        SELECT count(feed_source) FROM feed_source
+        WHERE created_by = $1
+        ",
+        &[&user_id],
+    ).await?;
+    let count = row.try_get("count")?;
+    Ok(count)
+}
+
+/// Returns feeds that have followers and need to be fetched
+pub async fn get_feed_sources_due(
+    db_client: &impl DatabaseClient,
+) -> Result<Vec<FeedSourceDetailed>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT feed_source, automated_account, actor_profile
+        FROM feed_source
+        JOIN automated_account USING (id)
+        JOIN actor_profile USING (id)
+        WHERE
+            (
+                feed_source.fetched_at IS NULL
+                OR feed_source.fetched_at
+                    < CURRENT_TIMESTAMP
+                    - feed_source.fetch_interval * interval '1 second'
+            )
+            AND EXISTS (
+                SELECT 1 FROM relationship
+                WHERE
+                    relationship.target_id = feed_source.id
+                    AND relationship.relationship_type = $1
+            )
+        ORDER BY feed_source.fetched_at ASC NULLS FIRST
+        ",
+        &[&RelationshipType::Follow],
+    ).await?;
+    let sources = rows.iter()
+        .map(FeedSourceDetailed::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(sources)
+}
+
+pub async fn update_feed_source(
+    db_client: &impl DatabaseClient,
+    source_id: Uuid,
+    fetch_interval: u32,
+    entry_limit: u16,
+) -> Result<FeedSource, DatabaseError> {
+    let fetch_interval = i32::try_from(fetch_interval)
+        .map_err(|_| DatabaseError::type_error())?;
+    let entry_limit = i16::try_from(entry_limit)
+        .map_err(|_| DatabaseError::type_error())?;
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE feed_source
+        SET fetch_interval = $2, entry_limit = $3
+        WHERE id = $1
+        RETURNING feed_source
+        ",
+        &[&source_id, &fetch_interval, &entry_limit],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("feed source"))?;
+    let source = row.try_get("feed_source")?;
+    Ok(source)
+}
+
+pub async fn set_feed_source_fetched(
+    db_client: &impl DatabaseClient,
+    source_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let updated_count = db_client.execute(
+        "
+        UPDATE feed_source
+        SET fetched_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        ",
+        &[&source_id],
+    ).await?;
+    if updated_count == 0 {
+        return Err(DatabaseError::NotFound("feed source"));
+    };
+    Ok(())
+}
+
+/// Returns `true` if entry has not been seen before
+pub async fn add_feed_entry(
+    db_client: &impl DatabaseClient,
+    source_id: Uuid,
+    entry_id: &str,
+) -> Result<bool, DatabaseError> {
+    let inserted_count = db_client.execute(
+        "
+        INSERT INTO feed_entry (feed_id, entry_id)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING
+        ",
+        &[&source_id, &entry_id],
+    ).await?;
+    Ok(inserted_count > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use apx_core::crypto::{
+        eddsa::generate_weak_ed25519_key,
+        rsa::generate_weak_rsa_key,
+    };
+    use serial_test::serial;
+    use crate::{
+        accounts::test_utils::create_test_user,
+        database::test_utils::create_test_database,
+        relationships::queries::follow,
+    };
+    use super::*;
+
+    fn create_test_source_data(feed_url: &str) -> FeedSourceCreateData {
+        FeedSourceCreateData {
+            username: "testfeed".to_owned(),
+            bio: None,
+            bio_source: None,
+            feed_url: feed_url.to_owned(),
+            fetch_interval: 3600,
+            entry_limit: 10,
+            created_by: None,
+            rsa_secret_key: generate_weak_rsa_key().unwrap(),
+            ed25519_secret_key: generate_weak_ed25519_key(),
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_feed_source() {
+        let db_client = &mut create_test_database().await;
+        let feed_url = "https://blog.example/feed.xml";
+        let source_data = create_test_source_data(feed_url);
+        let source = create_feed_source(db_client, source_data).await.unwrap();
+        assert_eq!(source.account.account_type, AutomatedAccountType::Feed);
+        assert_eq!(source.account.profile.username, "testfeed");
+        assert_eq!(source.source.feed_url, feed_url);
+        assert_eq!(source.source.fetched_at.is_none(), true);
+
+        let source = get_feed_source_by_url(db_client, feed_url).await.unwrap();
+        assert_eq!(source.account.profile.username, "testfeed");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_feed_source_count_by_creator() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let mut source_data =
+            create_test_source_data("https://blog.example/feed.xml");
+        source_data.created_by = Some(user.id);
+        create_feed_source(db_client, source_data).await.unwrap();
+        let count = get_feed_source_count_by_creator(db_client, user.id)
+            .await.unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_feed_sources_due() {
+        let db_client = &mut create_test_database().await;
+        let source_data =
+            create_test_source_data("https://blog.example/feed.xml");
+        let source = create_feed_source(db_client, source_data).await.unwrap();
+        // No followers
+        let sources = get_feed_sources_due(db_client).await.unwrap();
+        assert_eq!(sources.is_empty(), true);
+
+        let user = create_test_user(db_client, "test").await;
+        follow(db_client, user.id, source.account.id).await.unwrap();
+        let sources = get_feed_sources_due(db_client).await.unwrap();
+        assert_eq!(sources.len(), 1);
+
+        set_feed_source_fetched(db_client, source.account.id).await.unwrap();
+        let sources = get_feed_sources_due(db_client).await.unwrap();
+        assert_eq!(sources.is_empty(), true);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_add_feed_entry() {
+        let db_client = &mut create_test_database().await;
+        let source_data =
+            create_test_source_data("https://blog.example/feed.xml");
+        let source = create_feed_source(db_client, source_data).await.unwrap();
+        let entry_id = "https://blog.example/1";
+        let is_new = add_feed_entry(db_client, source.account.id, entry_id)
+            .await.unwrap();
+        assert_eq!(is_new, true);
+        let is_new = add_feed_entry(db_client, source.account.id, entry_id)
+            .await.unwrap();
+        assert_eq!(is_new, false);
+    }
+}
@@ -0,0 +1,38 @@
+use apx_core::crypto::{
+    eddsa::Ed25519SecretKey,
+    rsa::RsaSecretKey,
+};
+use chrono::{DateTime, Utc};
+use postgres_types::FromSql;
+use uuid::Uuid;
+
+use crate::accounts::types::AutomatedAccountDetailed;
+
+#[derive(FromSql)]
+#[postgres(name = "feed_source")]
+pub struct FeedSource {
+    pub id: Uuid,
+    pub feed_url: String,
+    pub fetch_interval: i32, // seconds
+    pub entry_limit: i16,
+    pub fetched_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Option<Uuid>,
+}
+
+pub struct FeedSourceDetailed {
+    pub source: FeedSource,
+    pub account: AutomatedAccountDetailed,
+}
+
+pub struct FeedSourceCreateData {
+    pub username: String,
+    pub bio: Option<String>,
+    pub bio_source: Option<String>,
+    pub feed_url: String,
+    pub fetch_interval: u32,
+    pub entry_limit: u16,
+    pub created_by: Option<Uuid>,
+    pub rsa_secret_key: RsaSecretKey,
+    pub ed25519_secret_key: Ed25519SecretKey,
+}
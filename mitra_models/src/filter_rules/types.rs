@@ -15,6 +15,8 @@ pub enum FilterAction {
     MarkSensitive,
     RejectKeywords,
     ProxyMedia,
+    LimitMediaAttachments,
+    ReduceVisibility,
 }
 
 impl From<FilterAction> for i16 {
@@ -28,6 +30,8 @@ impl From<FilterAction> for i16 {
             FilterAction::MarkSensitive => 6,
             FilterAction::RejectKeywords => 7,
             FilterAction::ProxyMedia => 8,
+            FilterAction::LimitMediaAttachments => 9,
+            FilterAction::ReduceVisibility => 10,
         }
     }
 }
@@ -45,6 +49,8 @@ impl TryFrom<i16> for FilterAction {
             6 => Self::MarkSensitive,
             7 => Self::RejectKeywords,
             8 => Self::ProxyMedia,
+            9 => Self::LimitMediaAttachments,
+            10 => Self::ReduceVisibility,
             _ => return Err(DatabaseTypeError),
         };
         Ok(action)
@@ -15,7 +15,7 @@ use crate::{
     payment_methods::types::PaymentType,
 };
 
-use super::types::{DbChainId, Invoice, InvoiceStatus};
+use super::types::{DbChainId, Invoice, InvoicePayout, InvoiceStatus};
 
 /// Create invoice with local recipient
 pub async fn create_local_invoice(
@@ -343,6 +343,50 @@ pub(super) async fn set_remote_invoice_data(
     Ok(())
 }
 
+pub async fn create_invoice_payout(
+    db_client: &impl DatabaseClient,
+    invoice_id: Uuid,
+    payout_address: &str,
+    amount: u64,
+    tx_id: &str,
+) -> Result<InvoicePayout, DatabaseError> {
+    let amount = i64::try_from(amount).map_err(|_| DatabaseTypeError)?;
+    let row = db_client.query_one(
+        "
+        INSERT INTO invoice_payout (
+            invoice_id,
+            payout_address,
+            amount,
+            tx_id
+        )
+        VALUES ($1, $2, $3, $4)
+        RETURNING invoice_payout
+        ",
+        &[&invoice_id, &payout_address, &amount, &tx_id],
+    ).await?;
+    let payout = row.try_get("invoice_payout")?;
+    Ok(payout)
+}
+
+pub async fn get_invoice_payouts(
+    db_client: &impl DatabaseClient,
+    invoice_id: Uuid,
+) -> Result<Vec<InvoicePayout>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT invoice_payout
+        FROM invoice_payout
+        WHERE invoice_id = $1
+        ORDER BY id
+        ",
+        &[&invoice_id],
+    ).await?;
+    let payouts = rows.iter()
+        .map(|row| row.try_get("invoice_payout"))
+        .collect::<Result<_, _>>()?;
+    Ok(payouts)
+}
+
 pub async fn get_invoice_summary(
     db_client: &impl DatabaseClient,
 ) -> Result<HashMap<InvoiceStatus, i64>, DatabaseError> {
@@ -475,4 +519,35 @@ mod tests {
         ).await.err().unwrap();
         assert!(matches!(error, DatabaseError::TypeError(_)));
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_invoice_payout() {
+        let db_client = &mut create_test_database().await;
+        let (recipient_id, sender_id) =
+            create_participants(db_client).await;
+        let invoice = create_local_invoice(
+            db_client,
+            sender_id,
+            recipient_id,
+            PaymentType::Monero,
+            &ChainId::monero_mainnet(),
+            "8MxABajuo71BZya9",
+            100000000,
+        ).await.unwrap();
+        let payout = create_invoice_payout(
+            db_client,
+            invoice.id,
+            "8MxABajuo71BZyb0",
+            20000000,
+            "abcd",
+        ).await.unwrap();
+        assert_eq!(payout.invoice_id, invoice.id);
+        assert_eq!(payout.amount, 20000000);
+
+        let payouts = get_invoice_payouts(db_client, invoice.id)
+            .await.unwrap();
+        assert_eq!(payouts.len(), 1);
+        assert_eq!(payouts[0].id, payout.id);
+    }
 }
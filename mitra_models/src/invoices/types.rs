@@ -138,6 +138,18 @@ impl TryFrom<i16> for InvoiceStatus {
 int_enum_from_sql!(InvoiceStatus);
 int_enum_to_sql!(InvoiceStatus);
 
+/// Transfer of a share of invoice payment to additional payout address
+#[derive(FromSql)]
+#[postgres(name = "invoice_payout")]
+pub struct InvoicePayout {
+    pub id: i32,
+    pub invoice_id: Uuid,
+    pub payout_address: String,
+    pub amount: i64,
+    pub tx_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(FromSql)]
 #[postgres(name = "invoice")]
 pub struct Invoice {
@@ -1,13 +1,17 @@
 pub mod accounts;
 pub mod activitypub;
+pub mod announcements;
 pub mod attachments;
 pub mod background_jobs;
 pub mod bookmarks;
 pub mod caip122;
 pub mod conversations;
 pub mod custom_feeds;
+pub mod data_imports;
 pub mod database;
 pub mod emojis;
+pub mod events;
+pub mod feed_sources;
 pub mod filter_rules;
 pub mod groups;
 pub mod instances;
@@ -21,7 +25,12 @@ pub mod polls;
 pub mod posts;
 pub mod profiles;
 pub mod properties;
+pub mod push_subscriptions;
+pub mod quote_authorizations;
 pub mod reactions;
 pub mod relationships;
+pub mod reports;
+pub mod scheduled_posts;
+pub mod server_rules;
 pub mod subscriptions;
 pub mod tags;
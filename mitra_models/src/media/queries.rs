@@ -11,7 +11,7 @@ use crate::{
     },
 };
 
-use super::types::DeletionQueue;
+use super::types::{DeletionQueue, PartialMediaInfo};
 
 impl DeletionQueue {
     pub async fn into_job(
@@ -147,6 +147,136 @@ pub async fn get_local_files(
     Ok(filenames)
 }
 
+/// Returns all media records that point to files in media storage
+pub async fn get_stored_media(
+    db_client: &impl DatabaseClient,
+) -> Result<Vec<PartialMediaInfo>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT DISTINCT ON (media ->> 'file_name') media
+        FROM (
+            SELECT media FROM media_attachment
+            UNION ALL
+            SELECT unnest(ARRAY[avatar, banner]) AS media
+            FROM actor_profile
+            UNION ALL
+            SELECT image FROM emoji
+            UNION ALL
+            SELECT media FROM activitypub_media
+        ) AS db_media
+        WHERE media ->> 'file_name' IS NOT NULL
+        ",
+        &[],
+    ).await?;
+    let media = rows.iter()
+        .map(|row| row.try_get("media"))
+        .collect::<Result<_, _>>()?;
+    Ok(media)
+}
+
+/// Removes references to a missing file from database.
+/// Remote media is replaced with links, local media is deleted.
+/// Returns the number of updated rows.
+pub async fn clean_missing_media(
+    db_client: &mut impl DatabaseClient,
+    file_name: &str,
+) -> Result<u64, DatabaseError> {
+    let transaction = db_client.transaction().await?;
+    let mut updated_count = 0;
+    updated_count += transaction.execute(
+        "
+        UPDATE media_attachment
+        SET media = jsonb_build_object(
+            'type', 'link',
+            'media_type', media ->> 'media_type',
+            'url', media ->> 'url'
+        )
+        WHERE
+            media ->> 'file_name' = $1
+            AND media ->> 'url' IS NOT NULL
+            AND media ->> 'media_type' IS NOT NULL
+        ",
+        &[&file_name],
+    ).await?;
+    updated_count += transaction.execute(
+        "
+        DELETE FROM media_attachment
+        WHERE media ->> 'file_name' = $1
+        ",
+        &[&file_name],
+    ).await?;
+    for column in ["avatar", "banner"] {
+        let statement = format!(
+            "
+            UPDATE actor_profile
+            SET {column} = CASE
+                WHEN
+                    {column} ->> 'url' IS NOT NULL
+                    AND {column} ->> 'media_type' IS NOT NULL
+                THEN jsonb_build_object(
+                    'type', 'link',
+                    'media_type', {column} ->> 'media_type',
+                    'url', {column} ->> 'url'
+                )
+                ELSE NULL
+                END
+            WHERE {column} ->> 'file_name' = $1
+            ",
+        );
+        updated_count += transaction.execute(&statement, &[&file_name]).await?;
+    };
+    updated_count += transaction.execute(
+        "
+        UPDATE emoji
+        SET image = jsonb_build_object(
+            'type', 'link',
+            'media_type', image ->> 'media_type',
+            'url', image ->> 'url'
+        )
+        WHERE
+            image ->> 'file_name' = $1
+            AND image ->> 'url' IS NOT NULL
+            AND image ->> 'media_type' IS NOT NULL
+        ",
+        &[&file_name],
+    ).await?;
+    updated_count += transaction.execute(
+        "
+        DELETE FROM emoji
+        WHERE image ->> 'file_name' = $1
+        ",
+        &[&file_name],
+    ).await?;
+    updated_count += transaction.execute(
+        "
+        DELETE FROM activitypub_media
+        WHERE media ->> 'file_name' = $1
+        ",
+        &[&file_name],
+    ).await?;
+    transaction.commit().await?;
+    Ok(updated_count)
+}
+
+pub async fn get_ipfs_objects(
+    db_client: &impl DatabaseClient,
+) -> Result<Vec<String>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT ipfs_cid FROM media_attachment
+        WHERE ipfs_cid IS NOT NULL
+        UNION
+        SELECT ipfs_cid FROM post
+        WHERE ipfs_cid IS NOT NULL
+        ",
+        &[],
+    ).await?;
+    let cids = rows.iter()
+        .map(|row| row.try_get("ipfs_cid"))
+        .collect::<Result<_, _>>()?;
+    Ok(cids)
+}
+
 #[cfg(test)]
 mod tests {
     use serial_test::serial;
@@ -167,4 +297,29 @@ mod tests {
         let db_client = &create_test_database().await;
         get_local_files(db_client).await.unwrap();
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_stored_media() {
+        let db_client = &create_test_database().await;
+        let media = get_stored_media(db_client).await.unwrap();
+        assert_eq!(media.is_empty(), true);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_clean_missing_media() {
+        let db_client = &mut create_test_database().await;
+        let updated_count = clean_missing_media(db_client, "test.png")
+            .await.unwrap();
+        assert_eq!(updated_count, 0);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_ipfs_objects() {
+        let db_client = &create_test_database().await;
+        let cids = get_ipfs_objects(db_client).await.unwrap();
+        assert_eq!(cids.is_empty(), true);
+    }
 }
@@ -5,16 +5,53 @@ use crate::{
         queries::get_users_by_role,
         types::Role,
     },
+    conversations::queries::get_conversation_muters,
     database::{DatabaseClient, DatabaseError},
+    push_subscriptions::{
+        queries::get_push_subscriptions,
+        types::PushJobData,
+    },
     relationships::{
         queries::has_relationship,
         types::RelationshipType,
     },
 };
 
-use super::queries::create_notification;
+use super::queries::{create_notification, has_notification};
 use super::types::EventType;
 
+/// Creates notification and schedules its delivery
+/// to recipient's push subscriptions
+async fn create_and_push_notification(
+    db_client: &impl DatabaseClient,
+    sender_id: Uuid,
+    recipient_id: Uuid,
+    post_id: Option<Uuid>,
+    reaction_id: Option<Uuid>,
+    invoice_id: Option<Uuid>,
+    event_type: EventType,
+) -> Result<(), DatabaseError> {
+    let notification_id = create_notification(
+        db_client,
+        sender_id,
+        recipient_id,
+        post_id,
+        reaction_id,
+        invoice_id,
+        event_type,
+    ).await?;
+    let subscriptions = get_push_subscriptions(db_client, recipient_id).await?;
+    if !subscriptions.is_empty() {
+        let job_data = PushJobData {
+            recipient_id,
+            notification_id,
+            failure_count: 0,
+        };
+        job_data.into_job(db_client, 0).await?;
+    };
+    Ok(())
+}
+
 pub async fn create_follow_notification(
     db_client: &impl DatabaseClient,
     sender_id: Uuid,
@@ -28,7 +65,7 @@ pub async fn create_follow_notification(
     ).await? {
         return Ok(());
     };
-    create_notification(
+    create_and_push_notification(
         db_client,
         sender_id,
         recipient_id,
@@ -52,7 +89,7 @@ pub async fn create_follow_request_notification(
     ).await? {
         return Ok(());
     };
-    create_notification(
+    create_and_push_notification(
         db_client,
         sender_id,
         recipient_id,
@@ -77,7 +114,12 @@ pub async fn create_reply_notification(
     ).await? {
         return Ok(());
     };
-    create_notification(
+    if get_conversation_muters(db_client, &[post_id]).await?
+        .contains(&recipient_id)
+    {
+        return Ok(());
+    };
+    create_and_push_notification(
         db_client,
         sender_id,
         recipient_id,
@@ -103,7 +145,12 @@ pub async fn create_reaction_notification(
     ).await? {
         return Ok(());
     };
-    create_notification(
+    if get_conversation_muters(db_client, &[post_id]).await?
+        .contains(&recipient_id)
+    {
+        return Ok(());
+    };
+    create_and_push_notification(
         db_client,
         sender_id,
         recipient_id,
@@ -128,7 +175,12 @@ pub async fn create_mention_notification(
     ).await? {
         return Ok(());
     };
-    create_notification(
+    if get_conversation_muters(db_client, &[post_id]).await?
+        .contains(&recipient_id)
+    {
+        return Ok(());
+    };
+    create_and_push_notification(
         db_client,
         sender_id,
         recipient_id,
@@ -153,7 +205,12 @@ pub async fn create_repost_notification(
     ).await? {
         return Ok(());
     };
-    create_notification(
+    if get_conversation_muters(db_client, &[post_id]).await?
+        .contains(&recipient_id)
+    {
+        return Ok(());
+    };
+    create_and_push_notification(
         db_client,
         sender_id,
         recipient_id,
@@ -164,13 +221,43 @@ pub async fn create_repost_notification(
     ).await
 }
 
+pub async fn create_post_notification(
+    db_client: &impl DatabaseClient,
+    sender_id: Uuid,
+    recipient_id: Uuid,
+    post_id: Uuid,
+) -> Result<(), DatabaseError> {
+    if has_relationship(
+        db_client,
+        recipient_id,
+        sender_id,
+        RelationshipType::Mute
+    ).await? {
+        return Ok(());
+    };
+    if get_conversation_muters(db_client, &[post_id]).await?
+        .contains(&recipient_id)
+    {
+        return Ok(());
+    };
+    create_and_push_notification(
+        db_client,
+        sender_id,
+        recipient_id,
+        Some(post_id),
+        None,
+        None,
+        EventType::Post,
+    ).await
+}
+
 pub async fn create_subscriber_payment_notification(
     db_client: &impl DatabaseClient,
     sender_id: Uuid,
     recipient_id: Uuid,
     invoice_id: Uuid,
 ) -> Result<(), DatabaseError> {
-    create_notification(
+    create_and_push_notification(
         db_client,
         sender_id,
         recipient_id,
@@ -186,7 +273,7 @@ pub async fn create_subscriber_leaving_notification(
     sender_id: Uuid,
     recipient_id: Uuid,
 ) -> Result<(), DatabaseError> {
-    create_notification(
+    create_and_push_notification(
         db_client,
         sender_id,
         recipient_id,
@@ -202,7 +289,7 @@ pub async fn create_subscription_expiration_notification(
     sender_id: Uuid,
     recipient_id: Uuid,
 ) -> Result<(), DatabaseError> {
-    create_notification(
+    create_and_push_notification(
         db_client,
         sender_id,
         recipient_id,
@@ -213,12 +300,43 @@ pub async fn create_subscription_expiration_notification(
     ).await
 }
 
+pub async fn create_subscription_reminder_notification(
+    db_client: &impl DatabaseClient,
+    sender_id: Uuid,
+    recipient_id: Uuid,
+) -> Result<(), DatabaseError> {
+    create_and_push_notification(
+        db_client,
+        sender_id,
+        recipient_id,
+        None,
+        None,
+        None,
+        EventType::SubscriptionReminder,
+    ).await
+}
+
+pub async fn create_storage_quota_warning_notification(
+    db_client: &impl DatabaseClient,
+    recipient_id: Uuid,
+) -> Result<(), DatabaseError> {
+    create_and_push_notification(
+        db_client,
+        recipient_id, // sender is the recipient
+        recipient_id,
+        None,
+        None,
+        None,
+        EventType::StorageQuotaWarning,
+    ).await
+}
+
 pub async fn create_move_notification(
     db_client: &impl DatabaseClient,
     sender_id: Uuid,
     recipient_id: Uuid,
 ) -> Result<(), DatabaseError> {
-    create_notification(
+    create_and_push_notification(
         db_client,
         sender_id,
         recipient_id,
@@ -235,7 +353,7 @@ pub async fn create_signup_notifications(
 ) -> Result<(), DatabaseError> {
     let admins = get_users_by_role(db_client, Role::Admin).await?;
     for recipient_id in admins {
-        create_notification(
+        create_and_push_notification(
             db_client,
             sender_id,
             recipient_id,
@@ -248,6 +366,59 @@ pub async fn create_signup_notifications(
     Ok(())
 }
 
+pub async fn create_view_notification(
+    db_client: &impl DatabaseClient,
+    sender_id: Uuid,
+    recipient_id: Uuid,
+    post_id: Uuid,
+) -> Result<(), DatabaseError> {
+    if has_relationship(
+        db_client,
+        recipient_id,
+        sender_id,
+        RelationshipType::Mute
+    ).await? {
+        return Ok(());
+    };
+    if has_notification(
+        db_client,
+        sender_id,
+        recipient_id,
+        post_id,
+        EventType::View,
+    ).await? {
+        return Ok(());
+    };
+    create_and_push_notification(
+        db_client,
+        sender_id,
+        recipient_id,
+        Some(post_id),
+        None,
+        None,
+        EventType::View,
+    ).await
+}
+
+pub async fn create_report_notifications(
+    db_client: &impl DatabaseClient,
+    sender_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let admins = get_users_by_role(db_client, Role::Admin).await?;
+    for recipient_id in admins {
+        create_and_push_notification(
+            db_client,
+            sender_id,
+            recipient_id,
+            None,
+            None,
+            None,
+            EventType::Report,
+        ).await?;
+    };
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use serial_test::serial;
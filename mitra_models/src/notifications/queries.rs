@@ -1,6 +1,10 @@
 use uuid::Uuid;
 
 use crate::database::{DatabaseClient, DatabaseError};
+use crate::events::{
+    queries::publish_event,
+    types::StreamEvent,
+};
 use crate::posts::{
     helpers::{add_related_posts, add_user_actions},
     queries::post_subqueries,
@@ -17,8 +21,8 @@ pub(super) async fn create_notification(
     reaction_id: Option<Uuid>,
     invoice_id: Option<Uuid>,
     event_type: EventType,
-) -> Result<(), DatabaseError> {
-    db_client.execute(
+) -> Result<i32, DatabaseError> {
+    let row = db_client.query_one(
         "
         INSERT INTO notification (
             sender_id,
@@ -29,6 +33,7 @@ pub(super) async fn create_notification(
             event_type
         )
         VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id
         ",
         &[
             &sender_id,
@@ -39,7 +44,37 @@ pub(super) async fn create_notification(
             &event_type,
         ],
     ).await?;
-    Ok(())
+    let notification_id = row.try_get("id")?;
+    publish_event(
+        db_client,
+        StreamEvent::NotificationCreated { notification_id, recipient_id },
+    ).await?;
+    Ok(notification_id)
+}
+
+pub(super) async fn has_notification(
+    db_client: &impl DatabaseClient,
+    sender_id: Uuid,
+    recipient_id: Uuid,
+    post_id: Uuid,
+    event_type: EventType,
+) -> Result<bool, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        SELECT EXISTS (
+            SELECT 1
+            FROM notification
+            WHERE
+                sender_id = $1
+                AND recipient_id = $2
+                AND post_id = $3
+                AND event_type = $4
+        )
+        ",
+        &[&sender_id, &recipient_id, &post_id, &event_type],
+    ).await?;
+    let exists = row.try_get(0)?;
+    Ok(exists)
 }
 
 pub async fn get_notifications(
@@ -220,6 +255,40 @@ mod tests {
         assert_eq!(notifications_forward[1].id, notifications[3].id);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_has_notification() {
+        let db_client = &mut create_test_database().await;
+        let user_1 = create_test_user(db_client, "test1").await;
+        let user_2 = create_test_user(db_client, "test2").await;
+        let post = create_test_local_post(db_client, user_1.id, "test").await;
+        let exists = has_notification(
+            db_client,
+            user_2.id,
+            user_1.id,
+            post.id,
+            EventType::View,
+        ).await.unwrap();
+        assert_eq!(exists, false);
+        create_notification(
+            db_client,
+            user_2.id,
+            user_1.id,
+            Some(post.id),
+            None,
+            None,
+            EventType::View,
+        ).await.unwrap();
+        let exists = has_notification(
+            db_client,
+            user_2.id,
+            user_1.id,
+            post.id,
+            EventType::View,
+        ).await.unwrap();
+        assert_eq!(exists, true);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_delete_notifications() {
@@ -26,6 +26,11 @@ pub enum EventType {
     Move,
     SignUp,
     SubscriberLeaving,
+    SubscriptionReminder,
+    Post,
+    StorageQuotaWarning,
+    View,
+    Report,
 }
 
 impl From<EventType> for i16 {
@@ -43,6 +48,11 @@ impl From<EventType> for i16 {
             EventType::Move => 10,
             EventType::SignUp => 11,
             EventType::SubscriberLeaving => 12,
+            EventType::SubscriptionReminder => 13,
+            EventType::Post => 14,
+            EventType::StorageQuotaWarning => 15,
+            EventType::View => 16,
+            EventType::Report => 17,
         }
     }
 }
@@ -64,6 +74,11 @@ impl TryFrom<i16> for EventType {
             10 => Self::Move,
             11 => Self::SignUp,
             12 => Self::SubscriberLeaving,
+            13 => Self::SubscriptionReminder,
+            14 => Self::Post,
+            15 => Self::StorageQuotaWarning,
+            16 => Self::View,
+            17 => Self::Report,
             _ => return Err(DatabaseTypeError),
         };
         Ok(event_type)
@@ -1,3 +1,4 @@
+use apx_core::did::Did;
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
@@ -7,12 +8,13 @@ use crate::{
         catch_unique_violation,
         DatabaseClient,
         DatabaseError,
+        DatabaseTypeError,
     },
     profiles::types::DbActorProfile,
 };
 
 use super::{
-    types::{OauthApp, OauthAppData, OauthToken},
+    types::{OauthApp, OauthAppAdminInfo, OauthAppData, OauthToken},
     utils::hash_oauth_token,
 };
 
@@ -28,9 +30,10 @@ pub async fn create_oauth_app(
             scopes,
             redirect_uri,
             client_id,
-            client_secret
+            client_secret,
+            is_approved
         )
-        VALUES ($1, $2, $3, $4, $5, $6)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         RETURNING oauth_application
         ",
         &[
@@ -40,6 +43,7 @@ pub async fn create_oauth_app(
             &app_data.redirect_uri,
             &app_data.client_id,
             &app_data.client_secret,
+            &app_data.is_approved,
         ],
     ).await.map_err(catch_unique_violation("oauth_application"))?;
     let app = row.try_get("oauth_application")?;
@@ -63,6 +67,92 @@ pub async fn get_oauth_app_by_client_id(
     Ok(app)
 }
 
+pub async fn get_oauth_app_by_id(
+    db_client: &impl DatabaseClient,
+    app_id: i32,
+) -> Result<OauthApp, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT oauth_application
+        FROM oauth_application
+        WHERE id = $1
+        ",
+        &[&app_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("oauth application"))?;
+    let app = row.try_get("oauth_application")?;
+    Ok(app)
+}
+
+pub async fn get_oauth_apps(
+    db_client: &impl DatabaseClient,
+) -> Result<Vec<OauthAppAdminInfo>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT
+            oauth_application,
+            (
+                SELECT count(oauth_token)
+                FROM oauth_token
+                WHERE
+                    oauth_token.application_id = oauth_application.id
+                    AND oauth_token.expires_at > CURRENT_TIMESTAMP
+            ) AS token_count
+        FROM oauth_application
+        ORDER BY oauth_application.id DESC
+        ",
+        &[],
+    ).await?;
+    let apps = rows.iter()
+        .map(OauthAppAdminInfo::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(apps)
+}
+
+pub async fn get_oauth_app_admin_info(
+    db_client: &impl DatabaseClient,
+    app_id: i32,
+) -> Result<OauthAppAdminInfo, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT
+            oauth_application,
+            (
+                SELECT count(oauth_token)
+                FROM oauth_token
+                WHERE
+                    oauth_token.application_id = oauth_application.id
+                    AND oauth_token.expires_at > CURRENT_TIMESTAMP
+            ) AS token_count
+        FROM oauth_application
+        WHERE oauth_application.id = $1
+        ",
+        &[&app_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("oauth application"))?;
+    let app_info = OauthAppAdminInfo::try_from(&row)?;
+    Ok(app_info)
+}
+
+pub async fn set_oauth_app_approved(
+    db_client: &impl DatabaseClient,
+    app_id: i32,
+    is_approved: bool,
+) -> Result<OauthApp, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE oauth_application
+        SET is_approved = $2
+        WHERE id = $1
+        RETURNING oauth_application
+        ",
+        &[&app_id, &is_approved],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("oauth application"))?;
+    let app = row.try_get("oauth_application")?;
+    Ok(app)
+}
+
 pub async fn create_oauth_authorization(
     db_client: &impl DatabaseClient,
     authorization_code: &str,
@@ -222,6 +312,18 @@ pub async fn delete_oauth_tokens(
     Ok(())
 }
 
+/// Revokes all tokens issued to the application
+pub async fn delete_oauth_tokens_by_app(
+    db_client: &impl DatabaseClient,
+    app_id: i32,
+) -> Result<u64, DatabaseError> {
+    let deleted_count = db_client.execute(
+        "DELETE FROM oauth_token WHERE application_id = $1",
+        &[&app_id],
+    ).await?;
+    Ok(deleted_count)
+}
+
 pub async fn get_oauth_tokens(
     db_client: &impl DatabaseClient,
     owner_id: Uuid,
@@ -271,6 +373,64 @@ pub async fn get_user_by_oauth_token(
     Ok((token_id, user))
 }
 
+pub async fn create_oauth_challenge(
+    db_client: &impl DatabaseClient,
+    issuer: &Did,
+    message: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<(), DatabaseError> {
+    // Remove expired challenges
+    db_client.execute(
+        "DELETE FROM oauth_challenge WHERE expires_at <= CURRENT_TIMESTAMP",
+        &[],
+    ).await?;
+    db_client.execute(
+        "
+        INSERT INTO oauth_challenge (issuer, message, expires_at)
+        VALUES ($1, $2, $3)
+        ",
+        &[&issuer.to_string(), &message, &expires_at],
+    ).await.map_err(catch_unique_violation("challenge"))?;
+    Ok(())
+}
+
+/// Returns issuer of the challenge and locks it until
+/// the end of the transaction.
+pub async fn get_oauth_challenge(
+    db_client: &impl DatabaseClient,
+    message: &str,
+) -> Result<Did, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT issuer
+        FROM oauth_challenge
+        WHERE message = $1 AND expires_at > CURRENT_TIMESTAMP
+        FOR UPDATE
+        ",
+        &[&message],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("challenge"))?;
+    let issuer: String = row.try_get("issuer")?;
+    let issuer = issuer.parse().map_err(|_| DatabaseTypeError)?;
+    Ok(issuer)
+}
+
+/// Removes challenge.
+/// Each challenge can be used only once.
+pub async fn delete_oauth_challenge(
+    db_client: &impl DatabaseClient,
+    message: &str,
+) -> Result<(), DatabaseError> {
+    let deleted_count = db_client.execute(
+        "DELETE FROM oauth_challenge WHERE message = $1",
+        &[&message],
+    ).await?;
+    if deleted_count == 0 {
+        return Err(DatabaseError::NotFound("challenge"));
+    };
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::TimeDelta;
@@ -362,6 +522,47 @@ mod tests {
         assert_eq!(error.to_string(), "user not found");
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_get_oauth_apps_and_revoke_tokens() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let app_data = OauthAppData {
+            app_name: "test_app".to_owned(),
+            ..Default::default()
+        };
+        let app = create_oauth_app(db_client, app_data).await.unwrap();
+        assert_eq!(app.is_approved, false);
+        save_oauth_token(
+            db_client,
+            user.id,
+            Some(app.id),
+            "test-token",
+            Utc::now(),
+            Utc::now() + TimeDelta::days(7),
+        ).await.unwrap();
+        let apps = get_oauth_apps(db_client).await.unwrap();
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].app.id, app.id);
+        assert_eq!(apps[0].token_count, 1);
+
+        let app = set_oauth_app_approved(db_client, app.id, true)
+            .await.unwrap();
+        assert_eq!(app.is_approved, true);
+        let app_info = get_oauth_app_admin_info(db_client, app.id)
+            .await.unwrap();
+        assert_eq!(app_info.app.is_approved, true);
+        assert_eq!(app_info.token_count, 1);
+
+        let deleted_count = delete_oauth_tokens_by_app(db_client, app.id)
+            .await.unwrap();
+        assert_eq!(deleted_count, 1);
+        let apps = get_oauth_apps(db_client).await.unwrap();
+        assert_eq!(apps[0].token_count, 0);
+        let tokens = get_oauth_tokens(db_client, user.id).await.unwrap();
+        assert_eq!(tokens.len(), 0);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_delete_oauth_token_by_id() {
@@ -390,4 +591,44 @@ mod tests {
         let tokens = get_oauth_tokens(db_client, user.id).await.unwrap();
         assert_eq!(tokens.len(), 0);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_and_delete_oauth_challenge() {
+        let db_client = &create_test_database().await;
+        let did: Did = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"
+            .parse().unwrap();
+        let message = "test challenge";
+        create_oauth_challenge(
+            db_client,
+            &did,
+            message,
+            Utc::now() + TimeDelta::minutes(5),
+        ).await.unwrap();
+        let issuer = get_oauth_challenge(db_client, message).await.unwrap();
+        assert_eq!(issuer, did);
+        delete_oauth_challenge(db_client, message).await.unwrap();
+        // Replay
+        let error = get_oauth_challenge(db_client, message).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("challenge")));
+        let error = delete_oauth_challenge(db_client, message).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("challenge")));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_oauth_challenge_expired() {
+        let db_client = &create_test_database().await;
+        let did: Did = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"
+            .parse().unwrap();
+        let message = "test challenge";
+        create_oauth_challenge(
+            db_client,
+            &did,
+            message,
+            Utc::now() - TimeDelta::minutes(1),
+        ).await.unwrap();
+        let error = get_oauth_challenge(db_client, message).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("challenge")));
+    }
 }
@@ -13,6 +13,7 @@ pub struct OauthApp {
     pub redirect_uri: String,
     pub client_id: Uuid,
     pub client_secret: String,
+    pub is_approved: bool,
     pub created_at: DateTime<Utc>,
 }
 
@@ -24,6 +25,24 @@ pub struct OauthAppData {
     pub redirect_uri: String,
     pub client_id: Uuid,
     pub client_secret: String,
+    pub is_approved: bool,
+}
+
+pub struct OauthAppAdminInfo {
+    pub app: OauthApp,
+    pub token_count: i64,
+}
+
+impl TryFrom<&Row> for OauthAppAdminInfo {
+    type Error = PgError;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let app_info = Self {
+            app: row.try_get("oauth_application")?,
+            token_count: row.try_get("token_count")?,
+        };
+        Ok(app_info)
+    }
 }
 
 pub struct OauthToken {
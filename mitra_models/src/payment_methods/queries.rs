@@ -24,14 +24,16 @@ pub async fn create_payment_method(
             payment_type,
             chain_id,
             payout_address,
-            view_key
+            view_key,
+            payout_splits
         )
-        VALUES ($1, $2, $3, $4, $5)
+        VALUES ($1, $2, $3, $4, $5, $6)
         ON CONFLICT (owner_id, chain_id)
         DO UPDATE SET
             payment_type = $2,
             payout_address = $4,
             view_key = $5,
+            payout_splits = $6,
             updated_at = CURRENT_TIMESTAMP
         RETURNING payment_method
         ",
@@ -41,6 +43,7 @@ pub async fn create_payment_method(
             &DbChainId::new(&method_data.chain_id),
             &method_data.payout_address,
             &method_data.view_key,
+            &method_data.payout_splits,
         ],
     ).await?;
     let payment_method: PaymentMethod = row.try_get("payment_method")?;
@@ -104,7 +107,7 @@ mod tests {
     use crate::{
         accounts::test_utils::create_test_user,
         database::test_utils::create_test_database,
-        payment_methods::types::PaymentType,
+        payment_methods::types::{PaymentType, PayoutSplit, PayoutSplits},
     };
     use super::*;
 
@@ -119,6 +122,7 @@ mod tests {
             chain_id: ChainId::monero_mainnet(),
             payout_address: "abcd".to_owned(),
             view_key: None,
+            payout_splits: Default::default(),
         };
         let method = create_payment_method(
             db_client,
@@ -147,6 +151,7 @@ mod tests {
             chain_id: ChainId::monero_mainnet(),
             payout_address: "abcd".to_owned(),
             view_key: None,
+            payout_splits: Default::default(),
         };
         let method = create_payment_method(
             db_client,
@@ -158,6 +163,10 @@ mod tests {
             chain_id: ChainId::monero_mainnet(),
             payout_address: "1234".to_owned(),
             view_key: None,
+            payout_splits: PayoutSplits(vec![PayoutSplit {
+                payout_address: "5678".to_owned(),
+                percent: 10,
+            }]),
         };
         let method_updated = create_payment_method(
             db_client,
@@ -165,6 +174,7 @@ mod tests {
         ).await.unwrap();
         assert_eq!(method_updated.id, method.id);
         assert_eq!(method_updated.payout_address, "1234");
+        assert_eq!(method_updated.payout_splits.total_percent(), 10);
         assert!(method_updated.updated_at > method.updated_at);
     }
 }
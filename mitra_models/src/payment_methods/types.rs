@@ -3,11 +3,13 @@ use apx_core::{
 };
 use chrono::{DateTime, Utc};
 use postgres_types::FromSql;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
     database::{
         int_enum::{int_enum_from_sql, int_enum_to_sql},
+        json_macro::{json_from_sql, json_to_sql},
         DatabaseTypeError,
     },
     invoices::types::DbChainId,
@@ -44,12 +46,45 @@ impl TryFrom<i16> for PaymentType {
 int_enum_from_sql!(PaymentType);
 int_enum_to_sql!(PaymentType);
 
+/// Share of incoming payments that is sent to additional address
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PayoutSplit {
+    pub payout_address: String,
+    pub percent: u8,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct PayoutSplits(pub Vec<PayoutSplit>);
+
+impl PayoutSplits {
+    pub fn inner(&self) -> &[PayoutSplit] {
+        let Self(payout_splits) = self;
+        payout_splits
+    }
+
+    pub fn into_inner(self) -> Vec<PayoutSplit> {
+        let Self(payout_splits) = self;
+        payout_splits
+    }
+
+    /// Sum of all shares, in percent
+    pub fn total_percent(&self) -> u32 {
+        self.inner().iter()
+            .map(|split| u32::from(split.percent))
+            .sum()
+    }
+}
+
+json_from_sql!(PayoutSplits);
+json_to_sql!(PayoutSplits);
+
 pub struct PaymentMethodData {
     pub owner_id: Uuid,
     pub payment_type: PaymentType,
     pub chain_id: ChainId,
     pub payout_address: String,
     pub view_key: Option<String>,
+    pub payout_splits: PayoutSplits,
 }
 
 #[derive(FromSql)]
@@ -63,6 +98,7 @@ pub struct PaymentMethod {
     pub view_key: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub payout_splits: PayoutSplits,
 }
 
 impl PaymentMethod {
@@ -83,6 +119,10 @@ impl PaymentMethod {
                 if self.view_key.is_none() {
                     return Err(DatabaseTypeError);
                 };
+                // Payments are sent directly to payout address
+                if !self.payout_splits.inner().is_empty() {
+                    return Err(DatabaseTypeError);
+                };
             },
         };
         Ok(())
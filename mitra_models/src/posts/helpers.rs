@@ -1,7 +1,10 @@
 use uuid::Uuid;
 
 use crate::{
-    accounts::types::{Permission, User},
+    accounts::{
+        queries::get_user_by_id,
+        types::{Permission, QuotePolicy, User},
+    },
     bookmarks::queries::find_bookmarked_by_user,
     conversations::queries::{
         find_tracking_statuses_by_user,
@@ -10,6 +13,10 @@ use crate::{
     database::{DatabaseClient, DatabaseError},
     polls::queries::find_votes_by_user,
     profiles::types::DbActorProfile,
+    quote_authorizations::{
+        queries::get_remote_quote_authorizations,
+        types::QuoteAuthorization,
+    },
     reactions::queries::find_reacted_by_user,
     relationships::{
         queries::has_relationship,
@@ -25,12 +32,31 @@ use super::queries::{
 };
 use super::types::{PostActions, PostDetailed, RelatedPosts, Visibility};
 
+/// Returns ID of authorization for the first linked post
+fn find_quote_authorization(
+    authorizations: &[QuoteAuthorization],
+    post: &PostDetailed,
+) -> Option<String> {
+    let quoted_post_id = post.links.first()?;
+    authorizations.iter()
+        .find(|authorization| {
+            authorization.quote_post_id == Some(post.id) &&
+                authorization.quoted_post_id == *quoted_post_id
+        })
+        .and_then(|authorization| authorization.object_id.clone())
+}
+
 pub async fn add_related_posts(
     db_client: &impl DatabaseClient,
     posts: Vec<&mut PostDetailed>,
 ) -> Result<(), DatabaseError> {
-    let posts_ids = posts.iter().map(|post| post.id).collect();
-    let related = get_related_posts(db_client, posts_ids).await?;
+    let posts_ids: Vec<_> = posts.iter().map(|post| post.id).collect();
+    let related = get_related_posts(db_client, posts_ids.clone()).await?;
+    let quote_post_ids: Vec<_> = posts_ids.iter().copied()
+        .chain(posts.iter().filter_map(|post| post.repost_of_id))
+        .collect();
+    let quote_authorizations =
+        get_remote_quote_authorizations(db_client, &quote_post_ids).await?;
     let get_post = |post_id: Uuid| -> Result<PostDetailed, DatabaseError> {
         let post = related.iter()
             .find(|post| post.id == post_id)
@@ -48,6 +74,10 @@ pub async fn add_related_posts(
             let linked = get_post(linked_id)?;
             related_posts.linked.push(linked);
         };
+        related_posts.quote_authorization = find_quote_authorization(
+            &quote_authorizations,
+            post,
+        );
         if let Some(repost_of_id) = post.repost_of_id {
             let mut repost_of = get_post(repost_of_id)?;
             let mut repost_of_related_posts = RelatedPosts::default();
@@ -59,6 +89,10 @@ pub async fn add_related_posts(
                 let linked = get_post(linked_id)?;
                 repost_of_related_posts.linked.push(linked);
             };
+            repost_of_related_posts.quote_authorization = find_quote_authorization(
+                &quote_authorizations,
+                &repost_of,
+            );
             repost_of.related_posts = Some(repost_of_related_posts);
             related_posts.repost_of = Some(Box::new(repost_of));
         };
@@ -208,6 +242,40 @@ pub fn can_link_post(post: &PostDetailed) -> bool {
     true
 }
 
+/// Returns true if local post can be quoted by the given actor
+/// without explicit approval
+pub async fn can_quote_post(
+    db_client: &impl DatabaseClient,
+    post: &PostDetailed,
+    quoter_id: Uuid,
+) -> Result<bool, DatabaseError> {
+    // Only public posts can be quoted
+    if !can_link_post(post) {
+        return Ok(false);
+    };
+    let author = get_user_by_id(db_client, post.author.id).await?;
+    let is_muted = has_relationship(
+        db_client,
+        author.id,
+        quoter_id,
+        RelationshipType::Mute,
+    ).await?;
+    if is_muted {
+        return Ok(false);
+    };
+    let is_allowed = match author.shared_client_config.quote_policy {
+        QuotePolicy::Public => true,
+        QuotePolicy::Followers => has_relationship(
+            db_client,
+            quoter_id,
+            author.id,
+            RelationshipType::Follow,
+        ).await?,
+        QuotePolicy::Nobody => quoter_id == author.id,
+    };
+    Ok(is_allowed)
+}
+
 pub async fn get_local_post_by_id(
     db_client: &impl DatabaseClient,
     post_id: Uuid,
@@ -236,6 +304,7 @@ mod tests {
     use serial_test::serial;
     use crate::{
         accounts::{
+            queries::set_shared_client_config,
             test_utils::create_test_user,
             types::{Role, User},
         },
@@ -566,4 +635,33 @@ mod tests {
         ).await.err().unwrap();
         assert_eq!(error.to_string(), "post not found");
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_can_quote_post() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_user(db_client, "author").await;
+        let quoter = create_test_remote_profile(
+            db_client,
+            "quoter",
+            "remote.example",
+            "https://remote.example/actor",
+        ).await;
+        let post = create_test_local_post(db_client, author.id, "test").await;
+        let can_quote = can_quote_post(db_client, &post, quoter.id)
+            .await.unwrap();
+        assert_eq!(can_quote, true);
+
+        let mut client_config = author.shared_client_config.clone();
+        client_config.quote_policy = QuotePolicy::Followers;
+        set_shared_client_config(db_client, author.id, client_config)
+            .await.unwrap();
+        let can_quote = can_quote_post(db_client, &post, quoter.id)
+            .await.unwrap();
+        assert_eq!(can_quote, false);
+        follow(db_client, quoter.id, author.id).await.unwrap();
+        let can_quote = can_quote_post(db_client, &post, quoter.id)
+            .await.unwrap();
+        assert_eq!(can_quote, true);
+    }
 }
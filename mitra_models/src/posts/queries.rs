@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use postgres_protocol::escape::escape_literal;
 use uuid::Uuid;
 
-use mitra_utils::id::generate_ulid;
+use mitra_utils::id::{generate_deterministic_ulid, generate_ulid};
 
 use crate::attachments::{
     queries::set_attachment_ipfs_cid,
@@ -12,6 +12,7 @@ use crate::conversations::{
     queries::{
         create_conversation,
         get_conversation,
+        get_conversation_muters,
     },
     types::TrackingStatus,
 };
@@ -23,9 +24,14 @@ use crate::database::{
     DatabaseTypeError,
 };
 use crate::emojis::types::CustomEmoji;
+use crate::events::{
+    queries::publish_event,
+    types::StreamEvent,
+};
 use crate::media::types::{DeletionQueue, PartialMediaInfo};
 use crate::notifications::helpers::{
     create_mention_notification,
+    create_post_notification,
     create_reply_notification,
     create_repost_notification,
 };
@@ -37,15 +43,22 @@ use crate::profiles::{
     },
     types::DbActorProfile,
 };
-use crate::relationships::types::RelationshipType;
+use crate::relationships::{
+    queries::get_post_notification_recipients,
+    types::RelationshipType,
+};
+use crate::tags::queries::record_tag_usage;
 
 use super::types::{
     DbLanguage,
     Post,
+    PostActivity,
+    PostContentMap,
     PostContext,
     PostCreateData,
     PostDetailed,
     PostReaction,
+    PostReactions,
     PostUpdateData,
     Repost,
     Visibility,
@@ -191,21 +204,17 @@ pub async fn get_post_reactions(
     db_client: &impl DatabaseClient,
     post_id: Uuid,
 ) -> Result<Vec<PostReaction>, DatabaseError> {
-    let statement = format!(
+    let maybe_row = db_client.query_opt(
         "
-        SELECT {related_reactions}
+        SELECT post.reactions
         FROM post
         WHERE post.id = $1
         ",
-        related_reactions=RELATED_REACTIONS,
-    );
-    let maybe_row = db_client.query_opt(
-        &statement,
         &[&post_id],
     ).await?;
     let row = maybe_row.ok_or(DatabaseError::NotFound("post"))?;
-    let reactions: Vec<PostReaction> = row.try_get("reactions")?;
-    Ok(reactions)
+    let reactions: PostReactions = row.try_get("reactions")?;
+    Ok(reactions.into_inner())
 }
 
 pub async fn create_post(
@@ -265,9 +274,10 @@ pub async fn create_post(
             is_sensitive,
             url,
             object_id,
-            created_at
+            created_at,
+            content_map
         )
-        SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15
+        SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16
         WHERE
         -- don't allow replies to reposts
         NOT EXISTS (
@@ -306,6 +316,7 @@ pub async fn create_post(
             &post_data.url,
             &post_data.object_id,
             &post_data.created_at,
+            &PostContentMap::new(post_data.content_map.clone()),
         ],
     ).await.map_err(catch_unique_violation("post"))?;
     // Return NotFound error if reply/repost is not allowed
@@ -329,11 +340,20 @@ pub async fn create_post(
         db_post.id,
         post_data.tags,
     ).await?;
+    if db_post.visibility == Visibility::Public && !db_tags.is_empty() {
+        record_tag_usage(&transaction, db_post.id).await?;
+    };
+    if db_post.repost_of_id.is_none() {
+        update_post_search_index(&transaction, db_post.id).await?;
+    };
     let db_links = create_post_links(
         &transaction,
         db_post.id,
         post_data.links,
     ).await?;
+    if db_post.visibility == Visibility::Public {
+        update_quote_counts(&transaction, &db_links, 1).await?;
+    };
     let db_emojis = create_post_emojis(
         &transaction,
         db_post.id,
@@ -352,12 +372,15 @@ pub async fn create_post(
 
     // Update counters
     let author = update_post_count(&transaction, db_post.author_id, 1).await?;
-    let mut notified_users = vec![];
+    // Users who muted the conversation are not notified
+    let mut notified_users =
+        get_conversation_muters(&transaction, &[db_post.id]).await?;
     if let Some(in_reply_to_id) = db_post.in_reply_to_id {
         update_reply_count(&transaction, in_reply_to_id, 1).await?;
         let in_reply_to_author = get_post_author(&transaction, in_reply_to_id).await?;
         if in_reply_to_author.has_user_account() &&
-            in_reply_to_author.id != db_post.author_id
+            in_reply_to_author.id != db_post.author_id &&
+            !notified_users.contains(&in_reply_to_author.id)
         {
             create_reply_notification(
                 &transaction,
@@ -403,6 +426,37 @@ pub async fn create_post(
             notified_users.push(profile.id);
         };
     };
+    // Notify followers who enabled notifications about new posts
+    if db_post.in_reply_to_id.is_none() &&
+        db_post.repost_of_id.is_none() &&
+        matches!(db_post.visibility, Visibility::Public | Visibility::Followers)
+    {
+        let recipients = get_post_notification_recipients(
+            &transaction,
+            db_post.author_id,
+        ).await?;
+        for recipient_id in recipients {
+            if notified_users.contains(&recipient_id) {
+                continue;
+            };
+            create_post_notification(
+                &transaction,
+                db_post.author_id,
+                recipient_id,
+                db_post.id,
+            ).await?;
+            notified_users.push(recipient_id);
+        };
+    };
+    publish_event(
+        &transaction,
+        StreamEvent::PostCreated {
+            post_id: db_post.id,
+            is_public: db_post.visibility == Visibility::Public,
+            is_local: author.is_local(),
+            tags: db_tags.clone(),
+        },
+    ).await?;
     // Construct post object
     let post = PostDetailed::new(
         db_post,
@@ -415,7 +469,6 @@ pub async fn create_post(
         db_tags,
         db_links,
         db_emojis,
-        vec![],
     )?;
     transaction.commit().await?;
     Ok(post)
@@ -438,8 +491,9 @@ pub async fn update_post(
             language = $4,
             is_sensitive = $5,
             url = $6,
-            updated_at = $7
-        WHERE id = $8
+            updated_at = $7,
+            content_map = $8
+        WHERE id = $9
             AND repost_of_id IS NULL
             AND ipfs_cid IS NULL
         RETURNING post
@@ -452,11 +506,13 @@ pub async fn update_post(
             &post_data.is_sensitive,
             &post_data.url,
             &post_data.updated_at,
+            &PostContentMap::new(post_data.content_map.clone()),
             &post_id,
         ],
     ).await?;
     let row = maybe_row.ok_or(DatabaseError::NotFound("post"))?;
     let db_post: Post = row.try_get("post")?;
+    update_post_search_index(&transaction, db_post.id).await?;
 
     // Get conversation details
     let conversation = get_conversation(
@@ -505,10 +561,16 @@ pub async fn update_post(
         "DELETE FROM post_tag WHERE post_id = $1",
         &[&db_post.id],
     ).await?;
-    transaction.execute(
-        "DELETE FROM post_link WHERE source_id = $1",
+    let old_links_rows = transaction.query(
+        "
+        DELETE FROM post_link WHERE source_id = $1
+        RETURNING target_id
+        ",
         &[&db_post.id],
     ).await?;
+    let old_links: Vec<Uuid> = old_links_rows.iter()
+        .map(|row| row.try_get("target_id"))
+        .collect::<Result<_, _>>()?;
     transaction.execute(
         "DELETE FROM post_emoji WHERE post_id = $1",
         &[&db_post.id],
@@ -534,13 +596,15 @@ pub async fn update_post(
         db_post.id,
         post_data.links,
     ).await?;
+    if db_post.visibility == Visibility::Public {
+        update_quote_counts(&transaction, &old_links, -1).await?;
+        update_quote_counts(&transaction, &db_links, 1).await?;
+    };
     let db_emojis = create_post_emojis(
         &transaction,
         db_post.id,
         post_data.emojis,
     ).await?;
-    let db_reactions =
-        get_post_reactions(&transaction, db_post.id).await?;
     let maybe_poll = if let Some(poll_data) = post_data.poll {
         let poll = update_poll(
             &mut transaction,
@@ -553,10 +617,12 @@ pub async fn update_post(
     };
 
     // Create notifications
+    let muters = get_conversation_muters(&transaction, &[db_post.id]).await?;
     for profile in db_mentions.iter() {
         if profile.has_user_account() &&
             profile.id != db_post.author_id &&
-            !old_mentions.contains(&profile.id)
+            !old_mentions.contains(&profile.id) &&
+            !muters.contains(&profile.id)
         {
             create_mention_notification(
                 &transaction,
@@ -580,7 +646,6 @@ pub async fn update_post(
         db_tags,
         db_links,
         db_emojis,
-        db_reactions,
     )?;
     transaction.commit().await?;
     let deletion_queue = DeletionQueue {
@@ -648,21 +713,6 @@ const RELATED_EMOJIS: &str = "
         WHERE post_emoji.post_id = post.id
     ) AS emojis";
 
-const RELATED_REACTIONS: &str = "
-    ARRAY(
-        SELECT
-            json_build_object(
-                'content', post_reaction.content,
-                'emoji', (array_agg(emoji))[1],
-                'count', count(post_reaction)
-            )
-        FROM post_reaction
-        LEFT JOIN emoji
-        ON post_reaction.emoji_id = emoji.id
-        WHERE post_reaction.post_id = post.id
-        GROUP BY post_reaction.content
-    ) AS reactions";
-
 pub(crate) fn post_subqueries() -> String {
     [
         RELATED_CONVERSATION,
@@ -673,7 +723,6 @@ pub(crate) fn post_subqueries() -> String {
         RELATED_TAGS,
         RELATED_LINKS,
         RELATED_EMOJIS,
-        RELATED_REACTIONS,
     ].join(",")
 }
 
@@ -761,6 +810,32 @@ pub(crate) fn build_mute_filter() -> String {
     )
 }
 
+/// Excludes posts from conversations muted by current user,
+/// as well as reposts and quotes of such posts
+fn build_conversation_mute_filter() -> String {
+    format!(
+        "NOT EXISTS (
+            SELECT 1 FROM conversation_tracking
+            WHERE
+                conversation_tracking.account_id = $current_user_id
+                AND conversation_tracking.tracking_status = {tracking_status_mute}
+                AND conversation_tracking.conversation_id IN (
+                    SELECT post.conversation_id
+                    UNION ALL
+                    SELECT repost_of.conversation_id
+                    FROM post AS repost_of
+                    WHERE repost_of.id = post.repost_of_id
+                    UNION ALL
+                    SELECT linked.conversation_id
+                    FROM post_link
+                    JOIN post AS linked ON post_link.target_id = linked.id
+                    WHERE post_link.source_id = post.id
+                )
+        )",
+        tracking_status_mute=i16::from(TrackingStatus::Mute),
+    )
+}
+
 pub async fn get_home_timeline(
     db_client: &impl DatabaseClient,
     current_user_id: Uuid,
@@ -850,6 +925,7 @@ pub async fn get_home_timeline(
             )
             -- author is not muted
             AND {mute_filter}
+            AND {conversation_mute_filter}
             AND {visibility_filter}
             AND ($max_post_id::uuid IS NULL OR post.id < $max_post_id)
         ORDER BY post.id DESC
@@ -862,6 +938,7 @@ pub async fn get_home_timeline(
         relationship_hide_replies=i16::from(RelationshipType::HideReplies),
         tracking_status_follow=i16::from(TrackingStatus::Follow),
         mute_filter=build_mute_filter(),
+        conversation_mute_filter=build_conversation_mute_filter(),
         visibility_filter=build_visibility_filter(),
     );
     let limit: i64 = limit.into();
@@ -908,6 +985,7 @@ pub async fn get_public_timeline(
             post.visibility = {visibility_public}
             AND post.repost_of_id IS NULL
             AND {mute_filter}
+            AND {conversation_mute_filter}
             AND ($max_post_id::uuid IS NULL OR post.id < $max_post_id)
         ORDER BY post.id DESC
         LIMIT $limit
@@ -916,6 +994,7 @@ pub async fn get_public_timeline(
         filter=filter,
         visibility_public=i16::from(Visibility::Public),
         mute_filter=build_mute_filter(),
+        conversation_mute_filter=build_conversation_mute_filter(),
     );
     let limit: i64 = limit.into();
     let query = query!(
@@ -978,6 +1057,33 @@ pub async fn get_direct_timeline(
     Ok(posts)
 }
 
+pub async fn delete_post_link(
+    db_client: &impl DatabaseClient,
+    source_id: Uuid,
+    target_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let deleted_count = db_client.execute(
+        "DELETE FROM post_link WHERE source_id = $1 AND target_id = $2",
+        &[&source_id, &target_id],
+    ).await?;
+    if deleted_count == 0 {
+        return Err(DatabaseError::NotFound("post link"));
+    };
+    db_client.execute(
+        "
+        UPDATE post
+        SET quote_count = post.quote_count - 1
+        FROM post AS quote
+        WHERE
+            post.id = $2
+            AND quote.id = $1
+            AND quote.visibility = $3
+        ",
+        &[&source_id, &target_id, &Visibility::Public],
+    ).await?;
+    Ok(())
+}
+
 pub(super) async fn get_related_posts(
     db_client: &impl DatabaseClient,
     posts_ids: Vec<Uuid>,
@@ -1090,6 +1196,41 @@ pub async fn get_posts_by_author(
     Ok(posts)
 }
 
+/// Returns subscribers-only posts created by the given author
+/// (for syndication to remote subscribers)
+pub async fn get_subscribers_only_posts_by_author(
+    db_client: &impl DatabaseClient,
+    author_id: Uuid,
+    limit: u16,
+) -> Result<Vec<PostDetailed>, DatabaseError> {
+    let statement = format!(
+        "
+        SELECT
+            post,
+            actor_profile AS post_author,
+            {post_subqueries}
+        FROM post
+        JOIN actor_profile ON post.author_id = actor_profile.id
+        WHERE
+            post.author_id = $1
+            AND post.visibility = {visibility_subscribers}
+            AND post.repost_of_id IS NULL
+        ORDER BY post.id DESC
+        LIMIT $2
+        ",
+        post_subqueries=post_subqueries(),
+        visibility_subscribers=i16::from(Visibility::Subscribers),
+    );
+    let rows = db_client.query(
+        &statement,
+        &[&author_id, &i64::from(limit)],
+    ).await?;
+    let posts = rows.iter()
+        .map(PostDetailed::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(posts)
+}
+
 pub async fn get_posts_by_tag(
     db_client: &impl DatabaseClient,
     tag_name: &str,
@@ -1113,6 +1254,7 @@ pub async fn get_posts_by_tag(
             )
             AND {visibility_filter}
             AND {mute_filter}
+            AND {conversation_mute_filter}
             AND ($max_post_id::uuid IS NULL OR post.id < $max_post_id)
         ORDER BY post.id DESC
         LIMIT $limit
@@ -1120,6 +1262,7 @@ pub async fn get_posts_by_tag(
         post_subqueries=post_subqueries(),
         visibility_filter=build_visibility_filter(),
         mute_filter=build_mute_filter(),
+        conversation_mute_filter=build_conversation_mute_filter(),
     );
     let limit: i64 = limit.into();
     let query = query!(
@@ -1187,6 +1330,7 @@ pub async fn get_custom_feed_timeline(
             )
             AND {visibility_filter}
             AND {mute_filter}
+            AND {conversation_mute_filter}
             AND ($max_post_id::uuid IS NULL OR post.id < $max_post_id)
         ORDER BY post.id DESC
         LIMIT $limit
@@ -1196,6 +1340,7 @@ pub async fn get_custom_feed_timeline(
         relationship_hide_replies=i16::from(RelationshipType::HideReplies),
         visibility_filter=build_visibility_filter(),
         mute_filter=build_mute_filter(),
+        conversation_mute_filter=build_conversation_mute_filter(),
     );
     let limit: i64 = limit.into();
     let query = query!(
@@ -1241,18 +1386,50 @@ pub async fn get_post_by_id(
     Ok(post)
 }
 
+#[derive(Clone, Copy, Default)]
+pub enum ThreadOrder {
+    #[default]
+    Oldest,
+    Newest,
+    MostReacted,
+}
+
 /// Given a post ID, finds all items in thread.
-/// Results are sorted by tree path.
+/// Results are sorted by tree path,
+/// replies to the same post are sorted according to `order`.
+///
+/// Replies nested deeper than `depth_limit` are not returned
+/// (unless they are ancestors of the given post).
+/// The number of items following the given post is limited by `limit`,
+/// next page can be requested using `max_id` (ID of the last returned item).
 pub async fn get_thread(
     db_client: &impl DatabaseClient,
     post_id: Uuid,
     current_user_id: Option<Uuid>,
+    order: ThreadOrder,
+    depth_limit: u16,
+    max_id: Option<Uuid>,
+    limit: u16,
 ) -> Result<Vec<PostDetailed>, DatabaseError> {
+    // Sort keys are compared as strings
+    let sort_key = match order {
+        ThreadOrder::Oldest => "post.id::text",
+        ThreadOrder::Newest => {
+            // Inverted hexadecimal digits
+            "translate(post.id::text, '0123456789abcdef', 'fedcba9876543210')"
+        },
+        ThreadOrder::MostReacted => {
+            "lpad((2147483647 - post.reaction_count)::text, 10, '0') || post.id::text"
+        },
+    };
     let statement = format!(
         "
         WITH RECURSIVE
-        conversation_post (id, in_reply_to_id) AS (
-            SELECT post.id, post.in_reply_to_id
+        conversation_post (id, in_reply_to_id, sort_key) AS (
+            SELECT
+                post.id,
+                post.in_reply_to_id,
+                ({sort_key}) COLLATE \"C\"
             FROM post
             -- NULL != NULL
             WHERE post.conversation_id = (
@@ -1261,64 +1438,161 @@ pub async fn get_thread(
                 WHERE post.id = $post_id AND {visibility_filter}
             )
         ),
+        ancestor (id, in_reply_to_id, depth) AS (
+            SELECT
+                conversation_post.id,
+                conversation_post.in_reply_to_id,
+                1
+            FROM conversation_post
+            WHERE conversation_post.id = $post_id
+            UNION
+            SELECT
+                conversation_post.id,
+                conversation_post.in_reply_to_id,
+                ancestor.depth + 1
+            FROM conversation_post
+            JOIN ancestor ON conversation_post.id = ancestor.in_reply_to_id
+        ),
         tree_node (id, path) AS (
             SELECT
                 conversation_post.id,
-                ARRAY[conversation_post.id]
+                ARRAY[conversation_post.sort_key]
             FROM conversation_post
             WHERE conversation_post.in_reply_to_id IS NULL
             UNION
             SELECT
                 conversation_post.id,
-                array_append(tree_node.path, conversation_post.id)
+                array_append(tree_node.path, conversation_post.sort_key)
             FROM conversation_post
             JOIN tree_node ON conversation_post.in_reply_to_id = tree_node.id
+            -- target post must always be included
+            WHERE array_length(tree_node.path, 1) < greatest(
+                $depth_limit,
+                (SELECT max(ancestor.depth) FROM ancestor)
+            )
+        ),
+        thread_item AS (
+            SELECT
+                post,
+                actor_profile AS post_author,
+                {post_subqueries},
+                tree_node.path AS tree_path,
+                tree_node.path > target_node.path AS is_after_target,
+                row_number() OVER (
+                    PARTITION BY tree_node.path > target_node.path
+                    ORDER BY tree_node.path
+                ) AS item_number
+            FROM post
+            JOIN tree_node ON post.id = tree_node.id
+            JOIN actor_profile ON post.author_id = actor_profile.id
+            CROSS JOIN (
+                SELECT tree_node.path
+                FROM tree_node WHERE tree_node.id = $post_id
+            ) AS target_node
+            WHERE
+                {visibility_filter}
+                AND {mute_filter}
+                AND (
+                    $max_id::uuid IS NULL
+                    OR tree_node.path > (
+                        SELECT tree_node.path
+                        FROM tree_node WHERE tree_node.id = $max_id
+                    )
+                )
         )
-        SELECT
-            post,
-            actor_profile AS post_author,
-            {post_subqueries}
-        FROM post
-        JOIN tree_node ON post.id = tree_node.id
-        JOIN actor_profile ON post.author_id = actor_profile.id
-        WHERE
-            {visibility_filter}
-            AND {mute_filter}
-        ORDER BY tree_node.path
+        SELECT *
+        FROM thread_item
+        WHERE NOT is_after_target OR item_number <= $limit
+        ORDER BY tree_path
         ",
+        sort_key=sort_key,
         post_subqueries=post_subqueries(),
         visibility_filter=build_visibility_filter(),
         mute_filter=build_mute_filter(),
     );
+    let depth_limit: i32 = depth_limit.into();
+    let limit: i64 = limit.into();
     let query = query!(
         &statement,
         post_id=post_id,
         current_user_id=current_user_id,
+        depth_limit=depth_limit,
+        max_id=max_id,
+        limit=limit,
     )?;
     let rows = db_client.query(query.sql(), query.parameters()).await?;
     let mut posts = vec![];
     for row in rows {
         let mut post = PostDetailed::try_from(&row)?;
         if let Some(in_reply_to_id) = post.in_reply_to_id {
-            if !posts.iter().any(|item: &PostDetailed| item.id == in_reply_to_id) {
+            // When next page is requested, parent might be
+            // on one of the previous pages
+            if max_id.is_none() &&
+                !posts.iter().any(|item: &PostDetailed| item.id == in_reply_to_id)
+            {
                 post.parent_visible = false;
             };
         };
         posts.push(post);
     };
-    if posts.is_empty() {
+    if posts.is_empty() && max_id.is_none() {
         return Err(DatabaseError::NotFound("post"));
     };
     Ok(posts)
 }
 
+/// Returns public direct replies to a post, oldest first
+pub async fn get_public_replies(
+    db_client: &impl DatabaseClient,
+    post_id: Uuid,
+    min_reply_id: Option<Uuid>,
+    limit: u16,
+) -> Result<Vec<PostDetailed>, DatabaseError> {
+    let statement = format!(
+        "
+        SELECT
+            post,
+            actor_profile AS post_author,
+            {post_subqueries}
+        FROM post
+        JOIN actor_profile ON post.author_id = actor_profile.id
+        WHERE
+            post.in_reply_to_id = $post_id
+            AND post.visibility = {visibility_public}
+            AND ($min_reply_id::uuid IS NULL OR post.id > $min_reply_id)
+        ORDER BY post.id
+        LIMIT $limit
+        ",
+        post_subqueries=post_subqueries(),
+        visibility_public=i16::from(Visibility::Public),
+    );
+    let limit: i64 = limit.into();
+    let query = query!(
+        &statement,
+        post_id=post_id,
+        min_reply_id=min_reply_id,
+        limit=limit,
+    )?;
+    let rows = db_client.query(query.sql(), query.parameters()).await?;
+    let posts = rows.iter()
+        .map(PostDetailed::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(posts)
+}
+
 /// Returns all posts in a conversation.
-/// Posts are in forward-chron order, muted authors are not excluded.
+/// Root post is always the first item, muted authors are not excluded.
 pub async fn get_conversation_items(
     db_client: &impl DatabaseClient,
     conversation_id: Uuid,
     current_user_id: Option<Uuid>,
+    order: ThreadOrder,
 ) -> Result<(PostDetailed, Vec<PostDetailed>), DatabaseError> {
+    let order_by = match order {
+        ThreadOrder::Oldest => "post.id",
+        ThreadOrder::Newest => "post.id DESC",
+        ThreadOrder::MostReacted => "post.reaction_count DESC, post.id",
+    };
     let statement = format!(
         "
         SELECT
@@ -1330,8 +1604,9 @@ pub async fn get_conversation_items(
         WHERE
             conversation_id = $conversation_id
             AND {visibility_filter}
-        ORDER BY post.id
+        ORDER BY post.in_reply_to_id IS NOT NULL, {order_by}
         ",
+        order_by=order_by,
         post_subqueries=post_subqueries(),
         visibility_filter=build_visibility_filter(),
     );
@@ -1497,6 +1772,57 @@ pub async fn update_reaction_count(
     Ok(())
 }
 
+const REACTION_SUMMARY: &str = "
+    SELECT COALESCE(jsonb_agg(reaction_summary), '[]')
+    FROM (
+        SELECT
+            post_reaction.content,
+            (array_agg(emoji))[1] AS emoji,
+            count(post_reaction)::integer AS count
+        FROM post_reaction
+        LEFT JOIN emoji ON post_reaction.emoji_id = emoji.id
+        WHERE post_reaction.post_id = post.id
+        GROUP BY post_reaction.content
+    ) AS reaction_summary
+";
+
+/// Re-calculates summary of reactions for the given posts
+pub(crate) async fn update_reaction_summaries(
+    db_client: &impl DatabaseClient,
+    post_ids: &[Uuid],
+) -> Result<(), DatabaseError> {
+    let statement = format!(
+        "
+        UPDATE post
+        SET reactions = ({reaction_summary})
+        WHERE post.id = ANY($1)
+        ",
+        reaction_summary=REACTION_SUMMARY,
+    );
+    db_client.execute(&statement, &[&post_ids]).await?;
+    Ok(())
+}
+
+/// Re-calculates summary of reactions for posts
+/// with reactions that use the given emoji
+pub(crate) async fn update_reaction_caches(
+    db_client: &impl DatabaseClient,
+    emoji_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let statement = format!(
+        "
+        UPDATE post
+        SET reactions = ({reaction_summary})
+        WHERE post.reactions @> jsonb_build_array(
+            jsonb_build_object('emoji', jsonb_build_object('id', $1::uuid))
+        )
+        ",
+        reaction_summary=REACTION_SUMMARY,
+    );
+    db_client.execute(&statement, &[&emoji_id]).await?;
+    Ok(())
+}
+
 pub async fn update_repost_count(
     db_client: &impl DatabaseClient,
     post_id: Uuid,
@@ -1516,6 +1842,24 @@ pub async fn update_repost_count(
     Ok(())
 }
 
+/// Updates quote counters of linked posts
+/// (only public quotes are counted)
+async fn update_quote_counts(
+    db_client: &impl DatabaseClient,
+    posts_ids: &[Uuid],
+    change: i32,
+) -> Result<(), DatabaseError> {
+    db_client.execute(
+        "
+        UPDATE post
+        SET quote_count = quote_count + $1
+        WHERE id = ANY($2)
+        ",
+        &[&change, &posts_ids],
+    ).await?;
+    Ok(())
+}
+
 pub async fn set_post_ipfs_cid(
     db_client: &mut impl DatabaseClient,
     post_id: Uuid,
@@ -1561,6 +1905,43 @@ pub async fn get_post_author(
     Ok(author)
 }
 
+/// Records remote actors to which the post has been delivered
+/// as a primary recipient
+pub async fn add_post_recipients(
+    db_client: &impl DatabaseClient,
+    post_id: Uuid,
+    recipients: &[Uuid],
+) -> Result<(), DatabaseError> {
+    db_client.execute(
+        "
+        INSERT INTO post_recipient (post_id, profile_id)
+        SELECT $1, profile_id
+        FROM unnest($2::uuid[]) AS recipient(profile_id)
+        ON CONFLICT DO NOTHING
+        ",
+        &[&post_id, &recipients],
+    ).await?;
+    Ok(())
+}
+
+pub async fn get_post_recipients(
+    db_client: &impl DatabaseClient,
+    post_id: Uuid,
+) -> Result<Vec<Uuid>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT profile_id
+        FROM post_recipient
+        WHERE post_id = $1
+        ",
+        &[&post_id],
+    ).await?;
+    let recipients = rows.iter()
+        .map(|row| row.try_get("profile_id"))
+        .collect::<Result<_, _>>()?;
+    Ok(recipients)
+}
+
 /// Finds repost of a given post
 pub async fn get_repost_by_author(
     db_client: &impl DatabaseClient,
@@ -1623,6 +2004,50 @@ pub async fn get_post_reposts(
     Ok(reposts)
 }
 
+/// Returns posts that link to (quote) a given post
+pub async fn get_post_quotes(
+    db_client: &impl DatabaseClient,
+    post_id: Uuid,
+    current_user_id: Option<Uuid>,
+    max_post_id: Option<Uuid>,
+    limit: u16,
+) -> Result<Vec<PostDetailed>, DatabaseError> {
+    let statement = format!(
+        "
+        SELECT
+            post,
+            actor_profile AS post_author,
+            {post_subqueries}
+        FROM post_link
+        JOIN post ON post_link.source_id = post.id
+        JOIN actor_profile ON post.author_id = actor_profile.id
+        WHERE
+            post_link.target_id = $post_id
+            AND {visibility_filter}
+            AND {mute_filter}
+            AND ($max_post_id::uuid IS NULL OR post.id < $max_post_id)
+        ORDER BY post.id DESC
+        LIMIT $limit
+        ",
+        post_subqueries=post_subqueries(),
+        visibility_filter=build_visibility_filter(),
+        mute_filter=build_mute_filter(),
+    );
+    let limit: i64 = limit.into();
+    let query = query!(
+        &statement,
+        post_id=post_id,
+        current_user_id=current_user_id,
+        max_post_id=max_post_id,
+        limit=limit,
+    )?;
+    let rows = db_client.query(query.sql(), query.parameters()).await?;
+    let posts = rows.iter()
+        .map(PostDetailed::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(posts)
+}
+
 /// Finds items reposted by user among given posts
 pub(super) async fn find_reposted_by_user(
     db_client: &impl DatabaseClient,
@@ -1845,12 +2270,24 @@ pub async fn delete_post(
                 OR post.repost_of_id = context.post_id
             )
         )
-        SELECT post_id FROM context
+        SELECT post.id, post.author_id, post.visibility
+        FROM context
+        JOIN post ON post.id = context.post_id
         ",
         &[&post_id],
     ).await?;
+    let deleted_events = posts_rows.iter()
+        .map(|row| {
+            let event = StreamEvent::PostDeleted {
+                post_id: row.try_get("id")?,
+                author_id: row.try_get("author_id")?,
+                visibility: row.try_get("visibility")?,
+            };
+            Ok(event)
+        })
+        .collect::<Result<Vec<_>, DatabaseError>>()?;
     let posts: Vec<Uuid> = posts_rows.iter()
-        .map(|row| row.try_get("post_id"))
+        .map(|row| row.try_get("id"))
         .collect::<Result<_, _>>()?;
     // Get list of attached files
     let media_rows = transaction.query(
@@ -1896,6 +2333,32 @@ pub async fn delete_post(
         ",
         &[&posts],
     ).await?;
+    transaction.execute(
+        "
+        UPDATE post
+        SET quote_count = post.quote_count - quote.count
+        FROM (
+            SELECT post_link.target_id, count(*)
+            FROM post_link
+            JOIN post ON post_link.source_id = post.id
+            WHERE
+                post_link.source_id = ANY($1)
+                AND post.visibility = $2
+            GROUP BY post_link.target_id
+        ) AS quote
+        WHERE post.id = quote.target_id
+        ",
+        &[&posts, &Visibility::Public],
+    ).await?;
+    // Detach raw objects (deleted according to retention policy)
+    transaction.execute(
+        "
+        UPDATE activitypub_object
+        SET post_id = NULL, post_deleted_at = CURRENT_TIMESTAMP
+        WHERE post_id = ANY($1)
+        ",
+        &[&posts],
+    ).await?;
     // Delete post
     let maybe_post_row = transaction.query_opt(
         "
@@ -1910,6 +2373,9 @@ pub async fn delete_post(
     if let Some(parent_id) = db_post.in_reply_to_id {
         update_reply_count(&transaction, parent_id, -1).await?;
     };
+    for event in deleted_events {
+        publish_event(&transaction, event).await?;
+    };
     transaction.commit().await?;
     let deletion_queue = DeletionQueue {
         files: detached_files,
@@ -1935,22 +2401,267 @@ pub async fn delete_repost(
     // Update counters
     let repost_of_id = db_post.repost_of_id.ok_or(DatabaseTypeError)?;
     update_repost_count(&transaction, repost_of_id, -1).await?;
+    publish_event(
+        &transaction,
+        StreamEvent::PostDeleted {
+            post_id: db_post.id,
+            author_id: db_post.author_id,
+            visibility: db_post.visibility,
+        },
+    ).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Returns IDs of public posts and reposts created by the given author
+pub async fn get_public_post_ids_by_author(
+    db_client: &impl DatabaseClient,
+    author_id: Uuid,
+) -> Result<Vec<Uuid>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT post.id
+        FROM post
+        WHERE post.author_id = $1 AND post.visibility = $2
+        ORDER BY post.id
+        ",
+        &[&author_id, &Visibility::Public],
+    ).await?;
+    let post_ids = rows.iter()
+        .map(|row| row.try_get("id"))
+        .collect::<Result<_, _>>()?;
+    Ok(post_ids)
+}
+
+// Columns that reference post.id
+const POST_REFERENCES: [(&str, &str); 21] = [
+    ("post", "in_reply_to_id"),
+    ("post", "repost_of_id"),
+    ("conversation", "root_id"),
+    ("post_reaction", "post_id"),
+    ("media_attachment", "post_id"),
+    ("post_mention", "post_id"),
+    ("post_recipient", "post_id"),
+    ("post_tag", "post_id"),
+    ("post_link", "source_id"),
+    ("post_link", "target_id"),
+    ("quote_authorization", "quoted_post_id"),
+    ("quote_authorization", "quote_post_id"),
+    ("post_emoji", "post_id"),
+    ("bookmark", "post_id"),
+    ("notification", "post_id"),
+    ("activitypub_object", "post_id"),
+    ("post_stats", "post_id"),
+    ("post_content_hash", "post_id"),
+    ("tag_usage", "post_id"),
+    ("scheduled_post", "repost_of_id"),
+    ("post_search_index", "post_id"),
+];
+
+/// Changes author of a public post or a repost.
+/// The post gets a new ID, because the object with the previous ID
+/// will be deleted on behalf of the previous author.
+/// Conversation audience is updated according to `audience_map`.
+/// Returns `AlreadyExists` error if the new author has already
+/// reposted the same post.
+pub async fn reassign_post(
+    db_client: &mut impl DatabaseClient,
+    post_id: Uuid,
+    to_profile_id: Uuid,
+    audience_map: &[(String, String)],
+) -> Result<Uuid, DatabaseError> {
+    let transaction = db_client.transaction().await?;
+    let maybe_row = transaction.query_opt(
+        "
+        SELECT post
+        FROM post
+        WHERE post.id = $1 AND post.visibility = $2
+        FOR UPDATE
+        ",
+        &[&post_id, &Visibility::Public],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("post"))?;
+    let db_post: Post = row.try_get("post")?;
+    // New ID preserves chronological order
+    let new_post_id = generate_deterministic_ulid(
+        &format!("{}:{}", db_post.id, to_profile_id),
+        db_post.created_at,
+    );
+    transaction.execute(
+        "
+        INSERT INTO post
+        SELECT (jsonb_populate_record(
+            NULL::post,
+            to_jsonb(post) || jsonb_build_object(
+                'id', $2::uuid,
+                'author_id', $3::uuid
+            )
+        )).*
+        FROM post
+        WHERE post.id = $1
+        ",
+        &[&db_post.id, &new_post_id, &to_profile_id],
+    ).await.map_err(catch_unique_violation("post"))?;
+    transaction.execute(
+        "
+        INSERT INTO poll
+        SELECT (jsonb_populate_record(
+            NULL::poll,
+            to_jsonb(poll) || jsonb_build_object('id', $2::uuid)
+        )).*
+        FROM poll
+        WHERE poll.id = $1
+        ",
+        &[&db_post.id, &new_post_id],
+    ).await?;
+    transaction.execute(
+        "UPDATE poll_vote SET poll_id = $2 WHERE poll_id = $1",
+        &[&db_post.id, &new_post_id],
+    ).await?;
+    for (table, column) in POST_REFERENCES {
+        let statement = format!(
+            "UPDATE {table} SET {column} = $2 WHERE {column} = $1",
+        );
+        transaction.execute(
+            &statement,
+            &[&db_post.id, &new_post_id],
+        ).await?;
+    };
+    transaction.execute(
+        "
+        UPDATE media_attachment
+        SET owner_id = $2
+        WHERE post_id = $1
+        ",
+        &[&new_post_id, &to_profile_id],
+    ).await?;
+    transaction.execute(
+        "
+        UPDATE post_content_hash
+        SET author_id = $2
+        WHERE post_id = $1
+        ",
+        &[&new_post_id, &to_profile_id],
+    ).await?;
+    transaction.execute(
+        "
+        UPDATE tag_usage
+        SET author_id = $2
+        WHERE post_id = $1
+        ",
+        &[&new_post_id, &to_profile_id],
+    ).await?;
+    for (old_audience, new_audience) in audience_map {
+        transaction.execute(
+            "
+            UPDATE conversation
+            SET audience = $3
+            WHERE root_id = $1 AND audience = $2
+            ",
+            &[&new_post_id, old_audience, new_audience],
+        ).await?;
+    };
+    transaction.execute(
+        "DELETE FROM post WHERE id = $1",
+        &[&db_post.id],
+    ).await?;
+    update_post_count(&transaction, db_post.author_id, -1).await?;
+    update_post_count(&transaction, to_profile_id, 1).await?;
     transaction.commit().await?;
+    Ok(new_post_id)
+}
+
+// Text search configuration is taken from dynamic configuration
+const POST_SEARCH_VECTOR: &str = "
+    to_tsvector(
+        COALESCE(
+            (
+                SELECT property_value #>> '{}'
+                FROM internal_property
+                WHERE property_name = 'default_fts_config'
+            ),
+            'simple'
+        )::regconfig,
+        -- can parse HTML documents
+        COALESCE(post.title, '') || ' ' || post.content
+    )
+";
+
+async fn update_post_search_index(
+    db_client: &impl DatabaseClient,
+    post_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let statement = format!(
+        "
+        INSERT INTO post_search_index (post_id, search_vector)
+        SELECT post.id, {POST_SEARCH_VECTOR}
+        FROM post
+        WHERE post.id = $1 AND post.repost_of_id IS NULL
+        ON CONFLICT (post_id)
+        DO UPDATE SET search_vector = EXCLUDED.search_vector
+        ",
+    );
+    db_client.execute(&statement, &[&post_id]).await?;
     Ok(())
 }
 
+// Number of posts indexed in one statement
+const POST_SEARCH_INDEX_BATCH_SIZE: i64 = 1000;
+
+/// Re-creates search index
+/// (should be done after changing text search configuration).
+/// Posts are indexed in batches to avoid long-running transactions.
+pub async fn rebuild_post_search_index(
+    db_client: &impl DatabaseClient,
+) -> Result<u64, DatabaseError> {
+    let statement = format!(
+        "
+        INSERT INTO post_search_index (post_id, search_vector)
+        SELECT post.id, {POST_SEARCH_VECTOR}
+        FROM post
+        WHERE
+            post.repost_of_id IS NULL
+            AND ($1::uuid IS NULL OR post.id > $1)
+        ORDER BY post.id
+        LIMIT $2
+        ON CONFLICT (post_id)
+        DO UPDATE SET search_vector = EXCLUDED.search_vector
+        RETURNING post_id
+        ",
+    );
+    let mut indexed_count = 0;
+    let mut maybe_last_post_id: Option<Uuid> = None;
+    loop {
+        let rows = db_client.query(
+            &statement,
+            &[&maybe_last_post_id, &POST_SEARCH_INDEX_BATCH_SIZE],
+        ).await?;
+        let post_ids: Vec<Uuid> = rows.iter()
+            .map(|row| row.try_get("post_id"))
+            .collect::<Result<_, _>>()?;
+        let Some(last_post_id) = post_ids.iter().max() else {
+            break;
+        };
+        maybe_last_post_id = Some(*last_post_id);
+        indexed_count += post_ids.len() as u64;
+    };
+    Ok(indexed_count)
+}
+
+#[expect(clippy::too_many_arguments)]
 pub async fn search_posts(
     db_client: &impl DatabaseClient,
     search_config: &str,
     text: &str,
     current_user_id: Uuid,
+    only_following: bool,
+    include_public: bool,
     limit: u16,
     offset: u16,
 ) -> Result<Vec<PostDetailed>, DatabaseError> {
     // Using `escape_literal` because
     // `&str` can't be directly cast to `regconfig`
     // https://github.com/rust-postgres/rust-postgres/issues/1041
-    // and the index is not used if the value is casted to `text`
     let statement = format!(
         "
         SELECT
@@ -1958,18 +2669,31 @@ pub async fn search_posts(
             actor_profile AS post_author,
             {post_subqueries}
         FROM post
+        JOIN post_search_index ON post_search_index.post_id = post.id
         JOIN actor_profile ON post.author_id = actor_profile.id
         WHERE
-            -- can parse HTML documents
-            to_tsvector(
-                {search_config},
-                COALESCE(post.title, '') || ' ' || post.content
-            )
+            post_search_index.search_vector
                 @@ plainto_tsquery({search_config}, $1)
-            AND repost_of_id IS NULL
             AND (
+                -- public posts
+                $4 IS TRUE
+                AND post.visibility = {visibility_public}
+                -- exclude authors blocked by the current user
+                -- and authors who blocked the current user
+                -- (blocks are stored as mutes)
+                AND NOT EXISTS (
+                    SELECT 1 FROM relationship
+                    WHERE
+                        (
+                            relationship.source_id = $2
+                            AND relationship.target_id = post.author_id
+                            OR relationship.source_id = post.author_id
+                            AND relationship.target_id = $2
+                        )
+                        AND relationship.relationship_type = {relationship_mute}
+                )
                 -- posts published by the current user
-                post.author_id = $2
+                OR post.author_id = $2
                 -- posts bookmarked by the current user
                 OR EXISTS (
                     SELECT 1 FROM bookmark
@@ -1992,17 +2716,33 @@ pub async fn search_posts(
                         AND post_mention.profile_id = $2
                 )
             )
+            AND (
+                $3 IS FALSE
+                -- posts published by accounts followed by the current user
+                OR EXISTS (
+                    SELECT 1 FROM relationship
+                    WHERE
+                        relationship.source_id = $2
+                        AND relationship.target_id = post.author_id
+                        AND relationship.relationship_type = {relationship_follow}
+                )
+            )
         ORDER BY post.id DESC
-        LIMIT $3 OFFSET $4
+        LIMIT $5 OFFSET $6
         ",
         post_subqueries=post_subqueries(),
         search_config=escape_literal(search_config),
+        visibility_public=i16::from(Visibility::Public),
+        relationship_follow=i16::from(RelationshipType::Follow),
+        relationship_mute=i16::from(RelationshipType::Mute),
     );
     let rows = db_client.query(
         &statement,
         &[
             &text,
             &current_user_id,
+            &only_following,
+            &include_public,
             &i64::from(limit),
             &i64::from(offset),
         ],
@@ -2013,6 +2753,70 @@ pub async fn search_posts(
     Ok(posts)
 }
 
+/// Full-text search within a single conversation
+#[expect(clippy::too_many_arguments)]
+pub async fn search_conversation_posts(
+    db_client: &impl DatabaseClient,
+    search_config: &str,
+    conversation_id: Uuid,
+    text: &str,
+    current_user_id: Uuid,
+    only_following: bool,
+    limit: u16,
+    offset: u16,
+) -> Result<Vec<PostDetailed>, DatabaseError> {
+    let statement = format!(
+        "
+        SELECT
+            post,
+            actor_profile AS post_author,
+            {post_subqueries}
+        FROM post
+        JOIN post_search_index ON post_search_index.post_id = post.id
+        JOIN actor_profile ON post.author_id = actor_profile.id
+        WHERE
+            post.conversation_id = $conversation_id
+            AND post_search_index.search_vector
+                @@ plainto_tsquery({search_config}, $text)
+            AND {visibility_filter}
+            AND (
+                $only_following IS FALSE
+                -- posts published by accounts followed by the current user
+                OR EXISTS (
+                    SELECT 1 FROM relationship
+                    WHERE
+                        relationship.source_id = $current_user_id
+                        AND relationship.target_id = post.author_id
+                        AND relationship.relationship_type = {relationship_follow}
+                )
+            )
+        ORDER BY post.id DESC
+        LIMIT $limit OFFSET $offset
+        ",
+        post_subqueries=post_subqueries(),
+        search_config=escape_literal(search_config),
+        visibility_filter=build_visibility_filter(),
+        relationship_follow=i16::from(RelationshipType::Follow),
+    );
+    let current_user_id = Some(current_user_id);
+    let limit: i64 = limit.into();
+    let offset: i64 = offset.into();
+    let query = query!(
+        &statement,
+        conversation_id=conversation_id,
+        text=text,
+        current_user_id=current_user_id,
+        only_following=only_following,
+        limit=limit,
+        offset=offset,
+    )?;
+    let rows = db_client.query(query.sql(), query.parameters()).await?;
+    let posts = rows.iter()
+        .map(PostDetailed::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(posts)
+}
+
 pub async fn get_post_count(
     db_client: &impl DatabaseClient,
     only_local: bool,
@@ -2045,6 +2849,142 @@ pub async fn get_post_count(
     Ok(count)
 }
 
+pub async fn increment_post_view_count(
+    db_client: &impl DatabaseClient,
+    post_id: Uuid,
+) -> Result<(), DatabaseError> {
+    db_client.execute(
+        "
+        INSERT INTO post_stats (post_id, view_count)
+        VALUES ($1, 1)
+        ON CONFLICT (post_id)
+        DO UPDATE SET view_count = post_stats.view_count + 1
+        ",
+        &[&post_id],
+    ).await?;
+    Ok(())
+}
+
+pub async fn get_post_view_count(
+    db_client: &impl DatabaseClient,
+    post_id: Uuid,
+) -> Result<i32, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT view_count
+        FROM post_stats
+        WHERE post_id = $1
+        ",
+        &[&post_id],
+    ).await?;
+    let view_count = match maybe_row {
+        Some(row) => row.try_get("view_count")?,
+        None => 0,
+    };
+    Ok(view_count)
+}
+
+/// Saves hash of post content
+/// (a post is counted only once)
+pub async fn add_post_content_hash(
+    db_client: &impl DatabaseClient,
+    post_id: Uuid,
+    author_id: Uuid,
+    content_hash: &[u8],
+) -> Result<(), DatabaseError> {
+    db_client.execute(
+        "
+        INSERT INTO post_content_hash (post_id, author_id, content_hash)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (post_id) DO NOTHING
+        ",
+        &[&post_id, &author_id, &content_hash],
+    ).await?;
+    Ok(())
+}
+
+/// Returns the number of posts with the same content
+/// created by the author after the given time
+pub async fn get_post_content_hash_count(
+    db_client: &impl DatabaseClient,
+    author_id: Uuid,
+    content_hash: &[u8],
+    created_after: DateTime<Utc>,
+) -> Result<i64, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        SELECT count(post_content_hash)
+        FROM post_content_hash
+        WHERE
+            author_id = $1
+            AND content_hash = $2
+            AND created_at > $3
+        ",
+        &[&author_id, &content_hash, &created_after],
+    ).await?;
+    let count = row.try_get("count")?;
+    Ok(count)
+}
+
+pub async fn delete_post_content_hashes(
+    db_client: &impl DatabaseClient,
+    created_before: DateTime<Utc>,
+) -> Result<u64, DatabaseError> {
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM post_content_hash
+        WHERE created_at < $1
+        ",
+        &[&created_before],
+    ).await?;
+    Ok(deleted_count)
+}
+
+/// Returns weekly posting statistics for the last `weeks` weeks
+/// (most recent week first)
+pub async fn get_post_activity(
+    db_client: &impl DatabaseClient,
+    author_id: Uuid,
+    only_public: bool,
+    weeks: u16,
+) -> Result<Vec<PostActivity>, DatabaseError> {
+    let visibility_filter = if only_public {
+        format!("post.visibility = {}", i16::from(Visibility::Public))
+    } else {
+        format!("post.visibility != {}", i16::from(Visibility::Direct))
+    };
+    let statement = format!(
+        "
+        SELECT
+            week,
+            count(post.id) AS posts
+        FROM generate_series(
+            date_trunc('week', CURRENT_TIMESTAMP) - ($2 - 1) * interval '1 week',
+            date_trunc('week', CURRENT_TIMESTAMP),
+            interval '1 week'
+        ) AS week
+        LEFT JOIN post
+        ON
+            post.author_id = $1
+            AND post.repost_of_id IS NULL
+            AND {visibility_filter}
+            AND post.created_at >= week
+            AND post.created_at < week + interval '1 week'
+        GROUP BY week
+        ORDER BY week DESC
+        ",
+        visibility_filter=visibility_filter,
+    );
+    let rows = db_client.query(
+        &statement,
+        &[&author_id, &i32::from(weeks)],
+    ).await?;
+    let activity = rows.iter()
+        .map(PostActivity::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(activity)
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::TimeDelta;
@@ -2067,7 +3007,16 @@ mod tests {
         profiles::test_utils::{
             create_test_remote_profile,
         },
+        reactions::{
+            queries::create_reaction,
+            types::ReactionData,
+        },
+        notifications::{
+            queries::get_notifications,
+            types::EventType,
+        },
         relationships::queries::{
+            enable_post_notifications,
             follow,
             hide_reposts,
             subscribe,
@@ -2121,6 +3070,29 @@ mod tests {
         assert_eq!(post_2.links, vec![post_1.id]);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_create_post_notify_followers() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_user(db_client, "test").await;
+        let follower = create_test_user(db_client, "follower").await;
+        follow(db_client, follower.id, author.id).await.unwrap();
+        enable_post_notifications(db_client, follower.id, author.id)
+            .await.unwrap();
+        let post = create_test_local_post(db_client, author.id, "test").await;
+
+        let notifications = get_notifications(
+            db_client,
+            follower.id,
+            None,
+            None,
+            10,
+        ).await.unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].event_type, EventType::Post);
+        assert_eq!(notifications[0].post.as_ref().unwrap().id, post.id);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_create_post_with_group() {
@@ -2204,6 +3176,25 @@ mod tests {
         assert_eq!(deletion_queue.ipfs_objects.len(), 0);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_add_post_recipients() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_user(db_client, "test").await;
+        let recipient = create_test_remote_profile(
+            db_client,
+            "recipient",
+            "social.example",
+            "https://social.example/users/1",
+        ).await;
+        let post = create_test_local_post(db_client, author.id, "test").await;
+        add_post_recipients(db_client, post.id, &[recipient.id]).await.unwrap();
+        // Adding the same recipient again is a no-op
+        add_post_recipients(db_client, post.id, &[recipient.id]).await.unwrap();
+        let recipients = get_post_recipients(db_client, post.id).await.unwrap();
+        assert_eq!(recipients, vec![recipient.id]);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_delete_post() {
@@ -2733,6 +3724,10 @@ mod tests {
             db_client,
             post_2.id,
             Some(user_1.id),
+            ThreadOrder::Oldest,
+            100,
+            None,
+            100,
         ).await.unwrap();
         assert_eq!(thread.len(), 3);
         assert_eq!(thread[0].id, post_1.id);
@@ -2743,6 +3738,10 @@ mod tests {
             db_client,
             post_4.id,
             Some(user_1.id),
+            ThreadOrder::Oldest,
+            100,
+            None,
+            100,
         ).await.err().unwrap();
         assert_eq!(error.to_string(), "post not found");
 
@@ -2750,6 +3749,7 @@ mod tests {
             db_client,
             post_1.expect_conversation().id,
             Some(user_1.id),
+            ThreadOrder::Oldest,
         ).await.unwrap();
         assert_eq!(root.id, post_1.id);
         assert_eq!(thread.len(), 3);
@@ -2758,11 +3758,54 @@ mod tests {
             db_client,
             post_1.expect_conversation().id,
             None,
+            ThreadOrder::Oldest,
         ).await.unwrap();
         assert_eq!(root.id, post_1.id);
         assert_eq!(thread.len(), 2);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_get_public_replies() {
+        let db_client = &mut create_test_database().await;
+        let user_1 = create_test_user(db_client, "test_1").await;
+        let user_2 = create_test_user(db_client, "test_2").await;
+        let post = create_test_local_post(db_client, user_1.id, "test").await;
+        let mut reply_ids = vec![];
+        for _ in 0..3 {
+            let reply_data = PostCreateData {
+                context: PostContext::reply_to(&post),
+                content: "reply".to_string(),
+                ..Default::default()
+            };
+            let reply =
+                create_post(db_client, user_2.id, reply_data).await.unwrap();
+            reply_ids.push(reply.id);
+        };
+        let direct_reply_data = PostCreateData {
+            context: PostContext::reply_to(&post),
+            content: "direct reply".to_string(),
+            visibility: Visibility::Direct,
+            mentions: vec![user_1.id],
+            ..Default::default()
+        };
+        create_post(db_client, user_2.id, direct_reply_data).await.unwrap();
+
+        let replies = get_public_replies(db_client, post.id, None, 2)
+            .await.unwrap();
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0].id, reply_ids[0]);
+        assert_eq!(replies[1].id, reply_ids[1]);
+        let replies = get_public_replies(
+            db_client,
+            post.id,
+            Some(replies[1].id),
+            2,
+        ).await.unwrap();
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].id, reply_ids[2]);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_get_thread_with_hidden_posts() {
@@ -2804,6 +3847,10 @@ mod tests {
             db_client,
             post_1.id,
             Some(user_1.id),
+            ThreadOrder::Oldest,
+            100,
+            None,
+            100,
         ).await.unwrap();
         assert_eq!(thread.len(), 3);
         assert_eq!(thread[0].id, post_1.id);
@@ -2813,6 +3860,154 @@ mod tests {
         assert_eq!(thread[2].parent_visible, false);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_get_thread_depth_limit_and_pagination() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let post_1 = create_test_local_post(db_client, user.id, "post").await;
+        let post_data_2 = PostCreateData {
+            context: PostContext::reply_to(&post_1),
+            content: "reply 1".to_string(),
+            ..Default::default()
+        };
+        let post_2 = create_post(db_client, user.id, post_data_2).await.unwrap();
+        let post_data_3 = PostCreateData {
+            context: PostContext::reply_to(&post_2),
+            content: "reply 2".to_string(),
+            ..Default::default()
+        };
+        let post_3 = create_post(db_client, user.id, post_data_3).await.unwrap();
+        let post_data_4 = PostCreateData {
+            context: PostContext::reply_to(&post_1),
+            content: "reply 3".to_string(),
+            ..Default::default()
+        };
+        let post_4 = create_post(db_client, user.id, post_data_4).await.unwrap();
+
+        // Depth limit
+        let thread = get_thread(
+            db_client,
+            post_1.id,
+            Some(user.id),
+            ThreadOrder::Oldest,
+            2,
+            None,
+            100,
+        ).await.unwrap();
+        let thread_ids: Vec<_> = thread.iter().map(|post| post.id).collect();
+        assert_eq!(thread_ids, vec![post_1.id, post_2.id, post_4.id]);
+        // Target post is deeper than limit
+        let thread = get_thread(
+            db_client,
+            post_3.id,
+            Some(user.id),
+            ThreadOrder::Oldest,
+            2,
+            None,
+            100,
+        ).await.unwrap();
+        assert_eq!(thread.len(), 4);
+
+        // Pagination
+        let thread = get_thread(
+            db_client,
+            post_1.id,
+            Some(user.id),
+            ThreadOrder::Oldest,
+            100,
+            None,
+            1,
+        ).await.unwrap();
+        let thread_ids: Vec<_> = thread.iter().map(|post| post.id).collect();
+        assert_eq!(thread_ids, vec![post_1.id, post_2.id]);
+        let thread = get_thread(
+            db_client,
+            post_1.id,
+            Some(user.id),
+            ThreadOrder::Oldest,
+            100,
+            Some(post_2.id),
+            1,
+        ).await.unwrap();
+        let thread_ids: Vec<_> = thread.iter().map(|post| post.id).collect();
+        assert_eq!(thread_ids, vec![post_3.id]);
+        assert_eq!(thread[0].parent_visible, true);
+        let thread = get_thread(
+            db_client,
+            post_1.id,
+            Some(user.id),
+            ThreadOrder::Oldest,
+            100,
+            Some(post_4.id),
+            1,
+        ).await.unwrap();
+        assert_eq!(thread.is_empty(), true);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_thread_order() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let post_1 = create_test_local_post(db_client, user.id, "post").await;
+        let post_data_2 = PostCreateData {
+            context: PostContext::reply_to(&post_1),
+            content: "reply 1".to_string(),
+            ..Default::default()
+        };
+        let post_2 = create_post(db_client, user.id, post_data_2).await.unwrap();
+        let post_data_3 = PostCreateData {
+            context: PostContext::reply_to(&post_1),
+            content: "reply 2".to_string(),
+            ..Default::default()
+        };
+        let post_3 = create_post(db_client, user.id, post_data_3).await.unwrap();
+        let reaction_data = ReactionData {
+            author_id: user.id,
+            post_id: post_2.id,
+            content: None,
+            emoji_id: None,
+            activity_id: None,
+            visibility: Visibility::Public,
+        };
+        create_reaction(db_client, reaction_data).await.unwrap();
+
+        let thread = get_thread(
+            db_client,
+            post_1.id,
+            Some(user.id),
+            ThreadOrder::Newest,
+            100,
+            None,
+            100,
+        ).await.unwrap();
+        let thread_ids: Vec<_> = thread.iter().map(|post| post.id).collect();
+        assert_eq!(thread_ids, vec![post_1.id, post_3.id, post_2.id]);
+
+        let thread = get_thread(
+            db_client,
+            post_1.id,
+            Some(user.id),
+            ThreadOrder::MostReacted,
+            100,
+            None,
+            100,
+        ).await.unwrap();
+        let thread_ids: Vec<_> = thread.iter().map(|post| post.id).collect();
+        assert_eq!(thread_ids, vec![post_1.id, post_2.id, post_3.id]);
+
+        let (root, items) = get_conversation_items(
+            db_client,
+            post_1.expect_conversation().id,
+            Some(user.id),
+            ThreadOrder::Newest,
+        ).await.unwrap();
+        assert_eq!(root.id, post_1.id);
+        let item_ids: Vec<_> = items.iter().map(|post| post.id).collect();
+        assert_eq!(item_ids, vec![post_1.id, post_3.id, post_2.id]);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_get_thread_followers_only_conversation() {
@@ -2845,6 +4040,10 @@ mod tests {
             db_client,
             post_2.id,
             Some(user_1.id),
+            ThreadOrder::Oldest,
+            100,
+            None,
+            100,
         ).await.unwrap();
         assert_eq!(thread.len(), 2);
 
@@ -2852,6 +4051,10 @@ mod tests {
             db_client,
             post_2.id,
             Some(user_2.id),
+            ThreadOrder::Oldest,
+            100,
+            None,
+            100,
         ).await.unwrap();
         assert_eq!(thread.len(), 2);
 
@@ -2859,6 +4062,10 @@ mod tests {
             db_client,
             post_2.id,
             Some(user_3.id),
+            ThreadOrder::Oldest,
+            100,
+            None,
+            100,
         ).await.err().unwrap();
         assert_eq!(error.to_string(), "post not found");
 
@@ -2866,6 +4073,10 @@ mod tests {
             db_client,
             post_1.id,
             None,
+            ThreadOrder::Oldest,
+            100,
+            None,
+            100,
         ).await.err().unwrap();
         assert_eq!(error.to_string(), "post not found");
 
@@ -2873,6 +4084,7 @@ mod tests {
             db_client,
             post_1.expect_conversation().id,
             Some(user_1.id),
+            ThreadOrder::Oldest,
         ).await.unwrap();
         assert_eq!(root.id, post_1.id);
         assert_eq!(thread.len(), 2);
@@ -2881,6 +4093,7 @@ mod tests {
             db_client,
             post_1.expect_conversation().id,
             None,
+            ThreadOrder::Oldest,
         ).await.err().unwrap();
         assert_eq!(error.to_string(), "conversation not found");
     }
@@ -2950,6 +4163,46 @@ mod tests {
         assert_eq!(reposts.len(), 2);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_get_post_quotes() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_user(db_client, "author").await;
+        let quoter = create_test_user(db_client, "quoter").await;
+        let post = create_test_local_post(db_client, author.id, "test").await;
+        let quote_data = PostCreateData {
+            links: vec![post.id],
+            ..Default::default()
+        };
+        let quote = create_post(db_client, quoter.id, quote_data).await.unwrap();
+
+        let quotes = get_post_quotes(
+            db_client,
+            post.id,
+            None,
+            None,
+            10,
+        ).await.unwrap();
+        assert_eq!(quotes.len(), 1);
+        assert_eq!(quotes[0].id, quote.id);
+        let post = get_post_by_id(db_client, post.id).await.unwrap();
+        assert_eq!(post.quote_count, 1);
+
+        // Non-public quotes are not counted
+        let direct_quote_data = PostCreateData {
+            links: vec![post.id],
+            visibility: Visibility::Direct,
+            ..Default::default()
+        };
+        create_post(db_client, quoter.id, direct_quote_data).await.unwrap();
+        let post = get_post_by_id(db_client, post.id).await.unwrap();
+        assert_eq!(post.quote_count, 1);
+
+        delete_post(db_client, quote.id).await.unwrap();
+        let post = get_post_by_id(db_client, post.id).await.unwrap();
+        assert_eq!(post.quote_count, 0);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_find_expired_reposts() {
@@ -3003,6 +4256,72 @@ mod tests {
         assert_eq!(result, vec![post_2.id]);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_reassign_post() {
+        let db_client = &mut create_test_database().await;
+        let user_1 = create_test_user(db_client, "user1").await;
+        let user_2 = create_test_user(db_client, "user2").await;
+        let post = create_test_local_post(db_client, user_1.id, "test").await;
+        let reply_data = PostCreateData {
+            context: PostContext::reply_to(&post),
+            ..PostCreateData::for_test()
+        };
+        let reply = create_post(db_client, user_2.id, reply_data).await.unwrap();
+        let post_data = PostCreateData {
+            visibility: Visibility::Followers,
+            ..PostCreateData::for_test()
+        };
+        create_post(db_client, user_1.id, post_data).await.unwrap();
+        let post_ids = get_public_post_ids_by_author(db_client, user_1.id)
+            .await.unwrap();
+        assert_eq!(post_ids, vec![post.id]);
+
+        let new_post_id = reassign_post(
+            db_client,
+            post.id,
+            user_2.id,
+            &[],
+        ).await.unwrap();
+        assert_ne!(new_post_id, post.id);
+        let error = get_post_by_id(db_client, post.id).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("post")));
+        let new_post = get_post_by_id(db_client, new_post_id).await.unwrap();
+        assert_eq!(new_post.author.id, user_2.id);
+        assert_eq!(new_post.content, post.content);
+        assert_eq!(new_post.created_at, post.created_at);
+        assert_eq!(new_post.expect_conversation().root_id, new_post_id);
+        let reply = get_post_by_id(db_client, reply.id).await.unwrap();
+        assert_eq!(reply.in_reply_to_id, Some(new_post_id));
+        let profile_1 = get_profile_by_id(db_client, user_1.id).await.unwrap();
+        assert_eq!(profile_1.post_count, 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_subscribers_only_posts_by_author() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_user(db_client, "author").await;
+        let _post_1 = create_test_local_post(
+            db_client,
+            author.id,
+            "public post",
+        ).await;
+        let post_data = PostCreateData {
+            content: "subscribers only".to_string(),
+            visibility: Visibility::Subscribers,
+            ..Default::default()
+        };
+        let post_2 = create_post(db_client, author.id, post_data).await.unwrap();
+        let posts = get_subscribers_only_posts_by_author(
+            db_client,
+            author.id,
+            10,
+        ).await.unwrap();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].id, post_2.id);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_search_posts() {
@@ -3024,6 +4343,62 @@ mod tests {
             PREINSTALLED_FTS_CONFIG,
             "post",
             user.id,
+            false,
+            false,
+            5,
+            0, // no offset
+        ).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, post_1.id);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_search_posts_only_following() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "viewer").await;
+        let author_1 = create_test_user(db_client, "author1").await;
+        let author_2 = create_test_user(db_client, "author2").await;
+        follow(db_client, user.id, author_1.id).await.unwrap();
+        let post_1 = create_test_local_post(
+            db_client,
+            author_1.id,
+            "test post 1",
+        ).await;
+        let post_2 = create_test_local_post(
+            db_client,
+            author_2.id,
+            "test post 2",
+        ).await;
+        for post_id in [post_1.id, post_2.id] {
+            let reaction_data = ReactionData {
+                author_id: user.id,
+                post_id,
+                content: None,
+                emoji_id: None,
+                activity_id: None,
+                visibility: Visibility::Public,
+            };
+            create_reaction(db_client, reaction_data).await.unwrap();
+        };
+        let results = search_posts(
+            db_client,
+            PREINSTALLED_FTS_CONFIG,
+            "post",
+            user.id,
+            false,
+            false,
+            5,
+            0, // no offset
+        ).await.unwrap();
+        assert_eq!(results.len(), 2);
+        let results = search_posts(
+            db_client,
+            PREINSTALLED_FTS_CONFIG,
+            "post",
+            user.id,
+            true,
+            false,
             5,
             0, // no offset
         ).await.unwrap();
@@ -3031,6 +4406,133 @@ mod tests {
         assert_eq!(results[0].id, post_1.id);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_search_posts_include_public() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "viewer").await;
+        let author = create_test_user(db_client, "author").await;
+        let post = create_test_local_post(
+            db_client,
+            author.id,
+            "test post",
+        ).await;
+        let results = search_posts(
+            db_client,
+            PREINSTALLED_FTS_CONFIG,
+            "post",
+            user.id,
+            false,
+            false,
+            5,
+            0, // no offset
+        ).await.unwrap();
+        assert_eq!(results.is_empty(), true);
+        let results = search_posts(
+            db_client,
+            PREINSTALLED_FTS_CONFIG,
+            "post",
+            user.id,
+            false,
+            true,
+            5,
+            0, // no offset
+        ).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, post.id);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_search_posts_include_public_blocked() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "viewer").await;
+        let author_1 = create_test_user(db_client, "author1").await;
+        let author_2 = create_test_user(db_client, "author2").await;
+        create_test_local_post(db_client, author_1.id, "test post 1").await;
+        create_test_local_post(db_client, author_2.id, "test post 2").await;
+        mute(db_client, user.id, author_1.id).await.unwrap();
+        mute(db_client, author_2.id, user.id).await.unwrap();
+        let results = search_posts(
+            db_client,
+            PREINSTALLED_FTS_CONFIG,
+            "post",
+            user.id,
+            false,
+            true,
+            5,
+            0, // no offset
+        ).await.unwrap();
+        assert_eq!(results.is_empty(), true);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_rebuild_post_search_index() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let post = create_test_local_post(db_client, user.id, "test post").await;
+        let indexed_count =
+            rebuild_post_search_index(db_client).await.unwrap();
+        assert_eq!(indexed_count, 1);
+        let results = search_posts(
+            db_client,
+            PREINSTALLED_FTS_CONFIG,
+            "post",
+            user.id,
+            false,
+            false,
+            5,
+            0, // no offset
+        ).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, post.id);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_search_posts_after_update() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let post = create_test_local_post(
+            db_client,
+            user.id,
+            "test post",
+        ).await;
+        let post_data = PostUpdateData {
+            content: "edited".to_string(),
+            updated_at: Some(Utc::now()),
+            ..Default::default()
+        };
+        update_post(db_client, post.id, post_data).await.unwrap();
+        let results = search_posts(
+            db_client,
+            PREINSTALLED_FTS_CONFIG,
+            "post",
+            user.id,
+            false,
+            false,
+            5,
+            0, // no offset
+        ).await.unwrap();
+        assert_eq!(results.is_empty(), true);
+        let results = search_posts(
+            db_client,
+            PREINSTALLED_FTS_CONFIG,
+            "edited",
+            user.id,
+            false,
+            false,
+            5,
+            0, // no offset
+        ).await.unwrap();
+        assert_eq!(results.len(), 1);
+
+        let inserted_count =
+            rebuild_post_search_index(db_client).await.unwrap();
+        assert_eq!(inserted_count, 1);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_search_posts_sql_injection() {
@@ -3042,6 +4544,8 @@ mod tests {
             dangerous,
             "post",
             user.id,
+            false,
+            false,
             5,
             0, // no offset
         ).await.err().unwrap();
@@ -3050,4 +4554,139 @@ mod tests {
             "db error: ERROR: text search configuration \"'simple',content)--\" does not exist",
         );
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_search_conversation_posts() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "viewer").await;
+        let author = create_test_user(db_client, "author").await;
+        let post = create_test_local_post(
+            db_client,
+            author.id,
+            "test post",
+        ).await;
+        let reply_data = PostCreateData {
+            context: PostContext::reply_to(&post),
+            content: "test reply".to_string(),
+            ..PostCreateData::for_test()
+        };
+        let reply = create_post(db_client, author.id, reply_data).await.unwrap();
+        let direct_reply_data = PostCreateData {
+            context: PostContext::reply_to(&post),
+            content: "test direct reply".to_string(),
+            visibility: Visibility::Direct,
+            ..PostCreateData::for_test()
+        };
+        create_post(db_client, author.id, direct_reply_data).await.unwrap();
+        // Post in another conversation
+        create_test_local_post(db_client, author.id, "test reply").await;
+
+        let conversation_id = post.expect_conversation().id;
+        let results = search_conversation_posts(
+            db_client,
+            PREINSTALLED_FTS_CONFIG,
+            conversation_id,
+            "reply",
+            user.id,
+            false,
+            5,
+            0, // no offset
+        ).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, reply.id);
+
+        // Only following
+        let results = search_conversation_posts(
+            db_client,
+            PREINSTALLED_FTS_CONFIG,
+            conversation_id,
+            "reply",
+            user.id,
+            true,
+            5,
+            0, // no offset
+        ).await.unwrap();
+        assert_eq!(results.len(), 0);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_post_activity() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_user(db_client, "test").await;
+        create_test_local_post(db_client, author.id, "public").await;
+        let post_data = PostCreateData {
+            content: "followers only".to_string(),
+            visibility: Visibility::Followers,
+            ..PostCreateData::for_test()
+        };
+        create_post(db_client, author.id, post_data).await.unwrap();
+        let activity = get_post_activity(db_client, author.id, false, 52)
+            .await.unwrap();
+        assert_eq!(activity.len(), 52);
+        assert_eq!(activity[0].posts, 2);
+        assert_eq!(activity[1].posts, 0);
+        let activity = get_post_activity(db_client, author.id, true, 52)
+            .await.unwrap();
+        assert_eq!(activity[0].posts, 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_increment_post_view_count() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_user(db_client, "test").await;
+        let post = create_test_local_post(db_client, author.id, "test").await;
+        let view_count = get_post_view_count(db_client, post.id).await.unwrap();
+        assert_eq!(view_count, 0);
+        increment_post_view_count(db_client, post.id).await.unwrap();
+        increment_post_view_count(db_client, post.id).await.unwrap();
+        let view_count = get_post_view_count(db_client, post.id).await.unwrap();
+        assert_eq!(view_count, 2);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_add_post_content_hash() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_user(db_client, "test").await;
+        let post_1 = create_test_local_post(db_client, author.id, "test").await;
+        let post_2 = create_test_local_post(db_client, author.id, "test").await;
+        let created_after = Utc::now() - TimeDelta::hours(1);
+        add_post_content_hash(db_client, post_1.id, author.id, b"hash_1")
+            .await.unwrap();
+        // Same post
+        add_post_content_hash(db_client, post_1.id, author.id, b"hash_1")
+            .await.unwrap();
+        let count = get_post_content_hash_count(
+            db_client,
+            author.id,
+            b"hash_1",
+            created_after,
+        ).await.unwrap();
+        assert_eq!(count, 1);
+        add_post_content_hash(db_client, post_2.id, author.id, b"hash_1")
+            .await.unwrap();
+        let count = get_post_content_hash_count(
+            db_client,
+            author.id,
+            b"hash_1",
+            created_after,
+        ).await.unwrap();
+        assert_eq!(count, 2);
+        let count = get_post_content_hash_count(
+            db_client,
+            author.id,
+            b"hash_2",
+            created_after,
+        ).await.unwrap();
+        assert_eq!(count, 0);
+
+        let deleted_count = delete_post_content_hashes(
+            db_client,
+            Utc::now(),
+        ).await.unwrap();
+        assert_eq!(deleted_count, 2);
+    }
 }
@@ -9,7 +9,13 @@ use postgres_types::{
     ToSql,
     Type,
 };
-use serde::Deserialize;
+use serde::{
+    de::{Error as DeserializerError},
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
 use tokio_postgres::Row;
 use uuid::Uuid;
 
@@ -23,7 +29,7 @@ use crate::{
     },
     database::{
         int_enum::{int_enum_from_sql, int_enum_to_sql},
-        json_macro::json_from_sql,
+        json_macro::{json_from_sql, json_to_sql},
         DatabaseError,
         DatabaseTypeError,
     },
@@ -180,11 +186,14 @@ pub struct Post {
     pub reply_count: i32,
     pub reaction_count: i32,
     pub repost_count: i32,
+    pub quote_count: i32,
     pub url: Option<String>,
     pub object_id: Option<String>,
     pub ipfs_cid: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>, // edited at
+    pub reactions: PostReactions,
+    pub content_map: PostContentMap,
 }
 
 #[derive(Clone, Deserialize)]
@@ -194,7 +203,66 @@ pub struct PostReaction {
     pub count: i32,
 }
 
-json_from_sql!(PostReaction);
+/// Summary of reactions (updated when reactions are added or removed)
+#[derive(Clone, Deserialize)]
+pub struct PostReactions(Vec<PostReaction>);
+
+impl PostReactions {
+    pub fn into_inner(self) -> Vec<PostReaction> {
+        let Self(reactions) = self;
+        reactions
+    }
+}
+
+json_from_sql!(PostReactions);
+
+fn serialize_language<S>(
+    language: &Language,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+    where S: Serializer,
+{
+    serializer.serialize_str(language.to_639_3())
+}
+
+fn deserialize_language<'de, D>(
+    deserializer: D,
+) -> Result<Language, D::Error>
+    where D: Deserializer<'de>,
+{
+    let language_code = String::deserialize(deserializer)?;
+    Language::from_639_3(&language_code)
+        .ok_or(DeserializerError::custom("invalid language code"))
+}
+
+/// Post content in additional language
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ContentVariant {
+    #[serde(
+        serialize_with = "serialize_language",
+        deserialize_with = "deserialize_language",
+    )]
+    pub language: Language,
+    pub content: String,
+    pub content_source: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct PostContentMap(Vec<ContentVariant>);
+
+impl PostContentMap {
+    pub fn new(variants: Vec<ContentVariant>) -> Self {
+        Self(variants)
+    }
+
+    pub fn into_inner(self) -> Vec<ContentVariant> {
+        let Self(variants) = self;
+        variants
+    }
+}
+
+json_from_sql!(PostContentMap);
+json_to_sql!(PostContentMap);
 
 // List of user's actions
 #[derive(Clone)]
@@ -213,6 +281,8 @@ pub struct RelatedPosts {
     pub in_reply_to: Option<Box<PostDetailed>>,
     pub repost_of: Option<Box<PostDetailed>>,
     pub linked: Vec<PostDetailed>,
+    // FEP-044f authorization for the first linked post
+    pub quote_authorization: Option<String>,
 }
 
 impl RelatedPosts {
@@ -249,6 +319,7 @@ pub struct PostDetailed {
     pub content: String,
     pub content_source: Option<String>,
     pub language: Option<Language>,
+    pub content_map: Vec<ContentVariant>,
     pub conversation: Option<Conversation>,
     pub in_reply_to_id: Option<Uuid>,
     pub repost_of_id: Option<Uuid>,
@@ -259,6 +330,7 @@ pub struct PostDetailed {
     pub reply_count: i32,
     pub reaction_count: i32,
     pub repost_count: i32,
+    pub quote_count: i32,
     pub poll: Option<Poll>,
     pub attachments: Vec<MediaAttachment>,
     pub mentions: Vec<DbActorProfile>,
@@ -293,7 +365,6 @@ impl PostDetailed {
         db_tags: Vec<String>,
         db_links: Vec<Uuid>,
         db_emojis: Vec<CustomEmoji>,
-        db_reactions: Vec<PostReaction>,
     ) -> Result<Self, DatabaseTypeError> {
         // Consistency checks
         db_author.check_consistency()?;
@@ -306,11 +377,14 @@ impl PostDetailed {
         if db_post.repost_of_id.is_none() && db_post.conversation_id.is_none() {
             return Err(DatabaseTypeError);
         };
+        let db_reactions = db_post.reactions.into_inner();
+        let db_content_map = db_post.content_map.into_inner();
         if db_post.repost_of_id.is_some() && (
             db_post.title.is_some() ||
             db_post.content.len() != 0 ||
             db_post.content_source.is_some() ||
             db_post.language.is_some() ||
+            !db_content_map.is_empty() ||
             db_post.conversation_id.is_some() ||
             db_post.is_sensitive ||
             db_post.is_pinned ||
@@ -379,6 +453,7 @@ impl PostDetailed {
             content: db_post.content,
             content_source: db_post.content_source,
             language: db_post.language.map(|db_lang| db_lang.inner()),
+            content_map: db_content_map,
             conversation: maybe_conversation,
             in_reply_to_id: db_post.in_reply_to_id,
             repost_of_id: db_post.repost_of_id,
@@ -389,6 +464,7 @@ impl PostDetailed {
             reply_count: db_post.reply_count,
             reaction_count: db_post.reaction_count,
             repost_count: db_post.repost_count,
+            quote_count: db_post.quote_count,
             poll: maybe_poll,
             attachments: db_attachments,
             mentions: db_mentions,
@@ -478,6 +554,7 @@ impl Default for PostDetailed {
             content: "".to_string(),
             content_source: None,
             language: None,
+            content_map: vec![],
             conversation: Some(Conversation::for_test(post_id)),
             in_reply_to_id: None,
             repost_of_id: None,
@@ -488,6 +565,7 @@ impl Default for PostDetailed {
             reply_count: 0,
             reaction_count: 0,
             repost_count: 0,
+            quote_count: 0,
             poll: None,
             attachments: vec![],
             mentions: vec![],
@@ -522,7 +600,6 @@ impl TryFrom<&Row> for PostDetailed {
         let db_tags: Vec<String> = row.try_get("tags")?;
         let db_links: Vec<Uuid> = row.try_get("links")?;
         let db_emojis: Vec<CustomEmoji> = row.try_get("emojis")?;
-        let db_reactions: Vec<PostReaction> = row.try_get("reactions")?;
         let post = Self::new(
             db_post,
             db_profile,
@@ -534,7 +611,6 @@ impl TryFrom<&Row> for PostDetailed {
             db_tags,
             db_links,
             db_emojis,
-            db_reactions,
         )?;
         Ok(post)
     }
@@ -567,6 +643,24 @@ impl TryFrom<&Row> for Repost {
     }
 }
 
+/// Weekly posting statistics
+pub struct PostActivity {
+    pub week: DateTime<Utc>,
+    pub posts: i64,
+}
+
+impl TryFrom<&Row> for PostActivity {
+    type Error = DatabaseError;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let activity = Self {
+            week: row.try_get("week")?,
+            posts: row.try_get("posts")?,
+        };
+        Ok(activity)
+    }
+}
+
 pub enum PostContext {
     Top {
         group_id: Option<Uuid>,
@@ -619,6 +713,7 @@ pub struct PostCreateData {
     pub content: String,
     pub content_source: Option<String>,
     pub language: Option<Language>,
+    pub content_map: Vec<ContentVariant>,
     pub visibility: Visibility,
     pub is_sensitive: bool,
     pub poll: Option<PollData>,
@@ -657,6 +752,7 @@ impl PostCreateData {
             content: "".to_owned(),
             content_source: None,
             language: None,
+            content_map: vec![],
             visibility: visibility,
             is_sensitive: false,
             poll: None,
@@ -678,6 +774,7 @@ pub struct PostUpdateData {
     pub content: String,
     pub content_source: Option<String>,
     pub language: Option<Language>,
+    pub content_map: Vec<ContentVariant>,
     pub is_sensitive: bool,
     pub poll: Option<PollData>,
     pub attachments: Vec<Uuid>,
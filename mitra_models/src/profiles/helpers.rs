@@ -3,9 +3,11 @@ use uuid::Uuid;
 use crate::database::{DatabaseClient, DatabaseError};
 
 use super::queries::{
+    get_primary_profile_ids,
     get_profile_by_acct,
     get_profile_by_id,
     get_remote_profile_by_actor_id,
+    link_profiles,
     search_profiles_by_did_only,
 };
 use super::types::DbActorProfile;
@@ -66,3 +68,64 @@ pub async fn find_verified_aliases(
     };
     Ok(aliases)
 }
+
+/// Finds other known remote profiles of the same person and links them together.
+/// A profile is considered an alias if both profiles list each other
+/// in `alsoKnownAs`, or if it has an identity proof with the same issuer.
+/// The first found alias becomes the primary profile, and the given profile
+/// and the remaining aliases are linked to it.
+/// Links previously removed by admin are not restored.
+pub async fn link_aliases(
+    db_client: &mut impl DatabaseClient,
+    profile: &DbActorProfile,
+) -> Result<(), DatabaseError> {
+    let Some(ref actor_data) = profile.actor_json else {
+        return Ok(());
+    };
+    let mut aliases: Vec<DbActorProfile> = vec![];
+    for (_, maybe_alias) in find_declared_aliases(db_client, profile).await? {
+        let Some(alias) = maybe_alias else {
+            continue;
+        };
+        if alias.aliases.contains(&actor_data.id) {
+            aliases.push(alias);
+        };
+    };
+    for alias in find_verified_aliases(db_client, profile).await? {
+        if alias.is_local() || aliases.iter().any(|item| item.id == alias.id) {
+            continue;
+        };
+        aliases.push(alias);
+    };
+    // Previously known profile becomes primary
+    let Some((primary, others)) = aliases.split_first() else {
+        return Ok(());
+    };
+    link_profiles(db_client, profile.id, primary.id, false).await?;
+    for alias in others {
+        link_profiles(db_client, alias.id, primary.id, false).await?;
+    };
+    Ok(())
+}
+
+/// Removes profiles of the same person, keeping the first one
+pub async fn deduplicate_linked_profiles(
+    db_client: &impl DatabaseClient,
+    profile_ids: Vec<Uuid>,
+) -> Result<Vec<Uuid>, DatabaseError> {
+    let primary_ids = get_primary_profile_ids(db_client, &profile_ids).await?;
+    let mut seen = vec![];
+    let mut deduplicated = vec![];
+    for profile_id in profile_ids {
+        let group_id = primary_ids.iter()
+            .find(|(alternate_id, _)| *alternate_id == profile_id)
+            .map(|(_, primary_id)| *primary_id)
+            .unwrap_or(profile_id);
+        if seen.contains(&group_id) {
+            continue;
+        };
+        seen.push(group_id);
+        deduplicated.push(profile_id);
+    };
+    Ok(deduplicated)
+}
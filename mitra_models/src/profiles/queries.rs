@@ -22,6 +22,8 @@ use crate::{
     emojis::types::CustomEmoji,
     instances::queries::create_instance,
     media::types::{DeletionQueue, PartialMediaInfo},
+    posts::queries::update_reaction_summaries,
+    posts::types::Visibility,
     relationships::types::RelationshipType,
 };
 
@@ -30,6 +32,8 @@ use super::types::{
     Aliases,
     DbActorProfile,
     ExtraFields,
+    IdentityProof,
+    IdentityProofRecord,
     IdentityProofs,
     PaymentOptions,
     ProfileCreateData,
@@ -149,6 +153,71 @@ pub(crate) async fn update_emoji_caches(
     Ok(())
 }
 
+// Adds new proofs to identity proof history
+// and marks removed proofs as revoked.
+// Revoked proofs are reactivated when added again.
+async fn update_identity_proof_history(
+    db_client: &impl DatabaseClient,
+    profile_id: Uuid,
+    old_proofs: &[IdentityProof],
+    new_proofs: &[IdentityProof],
+) -> Result<(), DatabaseError> {
+    let is_same_proof = |proof_1: &IdentityProof, proof_2: &IdentityProof| {
+        proof_1.issuer == proof_2.issuer && proof_1.value == proof_2.value
+    };
+    for proof in old_proofs {
+        if new_proofs.iter().any(|item| is_same_proof(item, proof)) {
+            continue;
+        };
+        db_client.execute(
+            "
+            UPDATE identity_proof_record
+            SET valid_until = CURRENT_TIMESTAMP
+            WHERE
+                profile_id = $1
+                AND proof = $2
+                AND valid_until IS NULL
+            ",
+            &[&profile_id, &proof],
+        ).await?;
+    };
+    for proof in new_proofs {
+        if old_proofs.iter().any(|item| is_same_proof(item, proof)) {
+            continue;
+        };
+        let reactivated_count = db_client.execute(
+            "
+            UPDATE identity_proof_record
+            SET
+                valid_from = CURRENT_TIMESTAMP,
+                valid_until = NULL
+            WHERE id = (
+                SELECT id
+                FROM identity_proof_record
+                WHERE
+                    profile_id = $1
+                    AND proof = $2
+                    AND valid_until IS NOT NULL
+                ORDER BY id DESC
+                LIMIT 1
+            )
+            ",
+            &[&profile_id, &proof],
+        ).await?;
+        if reactivated_count > 0 {
+            continue;
+        };
+        db_client.execute(
+            "
+            INSERT INTO identity_proof_record (profile_id, proof)
+            VALUES ($1, $2)
+            ",
+            &[&profile_id, &proof],
+        ).await?;
+    };
+    Ok(())
+}
+
 /// Create new profile using given Client or Transaction.
 pub async fn create_profile(
     db_client: &mut impl DatabaseClient,
@@ -191,10 +260,13 @@ pub async fn create_profile(
             identity_proofs,
             payment_options,
             extra_fields,
+            location,
+            birthday,
+            pronouns,
             aliases,
             actor_json
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
         RETURNING actor_profile
         ",
         &[
@@ -214,6 +286,9 @@ pub async fn create_profile(
             &IdentityProofs(profile_data.identity_proofs),
             &PaymentOptions(profile_data.payment_options),
             &ExtraFields(profile_data.extra_fields),
+            &profile_data.location,
+            &profile_data.birthday,
+            &profile_data.pronouns,
             &Aliases::new(profile_data.aliases),
             &profile_data.actor_json,
         ],
@@ -221,6 +296,12 @@ pub async fn create_profile(
     let mut profile: DbActorProfile = row.try_get("actor_profile")?;
 
     // Create related objects
+    update_identity_proof_history(
+        &transaction,
+        profile_id,
+        &[],
+        profile.identity_proofs.inner(),
+    ).await?;
     create_profile_emojis(
         &transaction,
         profile_id,
@@ -295,11 +376,14 @@ pub async fn update_profile(
             identity_proofs = $13,
             payment_options = $14,
             extra_fields = $15,
-            aliases = $16,
-            actor_json = $17,
+            location = $16,
+            birthday = $17,
+            pronouns = $18,
+            aliases = $19,
+            actor_json = $20,
             updated_at = CURRENT_TIMESTAMP,
             unreachable_since = NULL
-        WHERE id = $18
+        WHERE id = $21
         RETURNING actor_profile
         ",
         &[
@@ -318,13 +402,23 @@ pub async fn update_profile(
             &IdentityProofs(profile_data.identity_proofs),
             &PaymentOptions(profile_data.payment_options),
             &ExtraFields(profile_data.extra_fields),
+            &profile_data.location,
+            &profile_data.birthday,
+            &profile_data.pronouns,
             &Aliases::new(profile_data.aliases),
             &profile_data.actor_json,
             &profile_id,
         ],
     ).await?;
     let row = maybe_row.ok_or(DatabaseError::NotFound("profile"))?;
+    let profile_old = profile;
     let mut profile: DbActorProfile = row.try_get("actor_profile")?;
+    update_identity_proof_history(
+        &transaction,
+        profile_id,
+        profile_old.identity_proofs.inner(),
+        profile.identity_proofs.inner(),
+    ).await?;
 
     // Delete and re-create related objects
     transaction.execute(
@@ -349,6 +443,44 @@ pub async fn update_profile(
     Ok((profile, deletion_queue))
 }
 
+pub async fn get_identity_proof_history(
+    db_client: &impl DatabaseClient,
+    profile_id: Uuid,
+) -> Result<Vec<IdentityProofRecord>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT identity_proof_record
+        FROM identity_proof_record
+        WHERE profile_id = $1
+        ORDER BY identity_proof_record.id
+        ",
+        &[&profile_id],
+    ).await?;
+    let records = rows.iter()
+        .map(|row| row.try_get("identity_proof_record"))
+        .collect::<Result<_, _>>()?;
+    Ok(records)
+}
+
+/// Returns identity proofs that were removed from profile
+pub async fn get_revoked_identity_proofs(
+    db_client: &impl DatabaseClient,
+    profile_id: Uuid,
+) -> Result<Vec<IdentityProof>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT identity_proof_record.proof
+        FROM identity_proof_record
+        WHERE profile_id = $1 AND valid_until IS NOT NULL
+        ",
+        &[&profile_id],
+    ).await?;
+    let proofs = rows.iter()
+        .map(|row| row.try_get("proof"))
+        .collect::<Result<_, _>>()?;
+    Ok(proofs)
+}
+
 pub async fn set_profile_identity_key(
     db_client: &mut impl DatabaseClient,
     profile_id: Uuid,
@@ -373,6 +505,29 @@ pub async fn set_profile_identity_key(
     Ok(profile)
 }
 
+pub async fn set_hidden_collections(
+    db_client: &impl DatabaseClient,
+    profile_id: Uuid,
+    hide_followers: bool,
+    hide_following: bool,
+) -> Result<DbActorProfile, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE actor_profile
+        SET
+            hide_followers = $2,
+            hide_following = $3,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND user_id IS NOT NULL
+        RETURNING actor_profile
+        ",
+        &[&profile_id, &hide_followers, &hide_following],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("profile"))?;
+    let profile = DbActorProfile::try_from(&row)?;
+    Ok(profile)
+}
+
 pub async fn get_profile_by_id(
     db_client: &impl DatabaseClient,
     profile_id: Uuid,
@@ -670,6 +825,21 @@ pub async fn delete_profile(
         &[&profile_id],
     ).await?;
     transaction.execute(
+        "
+        UPDATE post
+        SET quote_count = post.quote_count - quote.count
+        FROM (
+            SELECT post_link.target_id, count(*)
+            FROM post_link
+            JOIN post ON post_link.source_id = post.id
+            WHERE post.author_id = $1 AND post.visibility = $2
+            GROUP BY post_link.target_id
+        ) AS quote
+        WHERE post.id = quote.target_id
+        ",
+        &[&profile_id, &Visibility::Public],
+    ).await?;
+    let reacted_posts_rows = transaction.query(
         "
         UPDATE post
         SET reaction_count = reaction_count - 1
@@ -677,9 +847,13 @@ pub async fn delete_profile(
         WHERE
             post_reaction.post_id = post.id
             AND post_reaction.author_id = $1
+        RETURNING post.id
         ",
         &[&profile_id],
     ).await?;
+    let reacted_posts: Vec<Uuid> = reacted_posts_rows.iter()
+        .map(|row| row.try_get("id"))
+        .collect::<Result<_, _>>()?;
     transaction.execute(
         "
         UPDATE post
@@ -702,6 +876,8 @@ pub async fn delete_profile(
     if deleted_count == 0 {
         return Err(DatabaseError::NotFound("profile"));
     };
+    // Reactions are deleted by cascade
+    update_reaction_summaries(&transaction, &reacted_posts).await?;
     transaction.commit().await?;
     Ok(DeletionQueue { files: detached_files, ipfs_objects })
 }
@@ -968,6 +1144,49 @@ pub async fn find_unreachable(
     Ok(profiles)
 }
 
+/// Marks remote profile as deleted.
+/// Tombstoned profile is kept until retention window expires.
+pub async fn set_profile_deleted(
+    db_client: &impl DatabaseClient,
+    profile_id: Uuid,
+) -> Result<DbActorProfile, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE actor_profile
+        SET deleted_at = coalesce(deleted_at, CURRENT_TIMESTAMP)
+        WHERE
+            id = $1
+            AND user_id IS NULL
+            AND automated_account_id IS NULL
+            AND portable_user_id IS NULL
+        RETURNING actor_profile
+        ",
+        &[&profile_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("profile"))?;
+    let profile = DbActorProfile::try_from(&row)?;
+    Ok(profile)
+}
+
+/// Finds remote profiles marked as deleted before the specified date
+pub async fn find_deleted_profiles(
+    db_client: &impl DatabaseClient,
+    deleted_before: DateTime<Utc>,
+) -> Result<Vec<Uuid>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT actor_profile.id
+        FROM actor_profile
+        WHERE actor_profile.deleted_at < $1
+        ",
+        &[&deleted_before],
+    ).await?;
+    let profile_ids = rows.iter()
+        .map(|row| row.try_get("id"))
+        .collect::<Result<_, _>>()?;
+    Ok(profile_ids)
+}
+
 /// Finds all empty remote profiles
 /// (without any posts, reactions, relationships)
 /// updated before the specified date
@@ -1037,18 +1256,198 @@ pub async fn find_empty_profiles(
     Ok(ids)
 }
 
+/// Links profile to the primary profile of the same person.
+/// Returns `false` if profiles were not linked.
+pub async fn link_profiles(
+    db_client: &mut impl DatabaseClient,
+    profile_id: Uuid,
+    primary_profile_id: Uuid,
+    is_manual: bool,
+) -> Result<bool, DatabaseError> {
+    let transaction = db_client.transaction().await?;
+    if !is_manual {
+        // Links removed by admin are not restored automatically
+        let is_rejected = transaction.query_opt(
+            "
+            SELECT 1 FROM profile_link
+            WHERE
+                is_active IS FALSE
+                AND (
+                    profile_id = $1 AND primary_profile_id = $2
+                    OR profile_id = $2 AND primary_profile_id = $1
+                )
+            ",
+            &[&profile_id, &primary_profile_id],
+        ).await?.is_some();
+        if is_rejected {
+            return Ok(false);
+        };
+    };
+    // Alternate profile can't be primary
+    let maybe_row = transaction.query_opt(
+        "
+        SELECT primary_profile_id
+        FROM profile_link
+        WHERE profile_id = $1 AND is_active IS TRUE
+        ",
+        &[&primary_profile_id],
+    ).await?;
+    let primary_profile_id: Uuid = match maybe_row {
+        Some(row) => row.try_get("primary_profile_id")?,
+        None => primary_profile_id,
+    };
+    if primary_profile_id == profile_id {
+        // Already linked
+        return Ok(false);
+    };
+    // Automatic links don't replace manual ones
+    let maybe_row = transaction.query_opt(
+        "
+        INSERT INTO profile_link (
+            profile_id,
+            primary_profile_id,
+            is_manual
+        )
+        VALUES ($1, $2, $3)
+        ON CONFLICT (profile_id)
+        DO UPDATE SET
+            primary_profile_id = $2,
+            is_manual = $3,
+            is_active = TRUE
+        WHERE
+            $3 IS TRUE
+            OR profile_link.is_active IS TRUE
+            AND profile_link.is_manual IS FALSE
+        RETURNING profile_id
+        ",
+        &[&profile_id, &primary_profile_id, &is_manual],
+    ).await?;
+    if maybe_row.is_none() {
+        return Ok(false);
+    };
+    // Move alternate profiles to the new group
+    transaction.execute(
+        "
+        UPDATE profile_link
+        SET primary_profile_id = $2
+        WHERE
+            primary_profile_id = $1
+            AND profile_id != $2
+            AND is_active IS TRUE
+        ",
+        &[&profile_id, &primary_profile_id],
+    ).await?;
+    transaction.commit().await?;
+    Ok(true)
+}
+
+/// Removes link between profiles.
+/// Removed links are not restored automatically.
+pub async fn unlink_profile(
+    db_client: &impl DatabaseClient,
+    profile_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let updated_count = db_client.execute(
+        "
+        UPDATE profile_link
+        SET is_active = FALSE
+        WHERE profile_id = $1 AND is_active IS TRUE
+        ",
+        &[&profile_id],
+    ).await?;
+    if updated_count == 0 {
+        return Err(DatabaseError::NotFound("profile link"));
+    };
+    Ok(())
+}
+
+/// Returns primary profile (if the given profile is an alternate)
+/// and other alternate profiles of the same person
+pub async fn get_linked_profiles(
+    db_client: &impl DatabaseClient,
+    profile_id: Uuid,
+) -> Result<(Option<DbActorProfile>, Vec<DbActorProfile>), DatabaseError> {
+    let rows = db_client.query(
+        "
+        WITH group_primary AS (
+            SELECT coalesce(
+                (
+                    SELECT primary_profile_id
+                    FROM profile_link
+                    WHERE profile_id = $1 AND is_active IS TRUE
+                ),
+                $1
+            ) AS id
+        )
+        SELECT
+            actor_profile,
+            actor_profile.id = group_primary.id AS is_primary
+        FROM actor_profile, group_primary
+        WHERE
+            actor_profile.id != $1
+            AND (
+                actor_profile.id = group_primary.id
+                OR EXISTS (
+                    SELECT 1 FROM profile_link
+                    WHERE
+                        profile_link.profile_id = actor_profile.id
+                        AND profile_link.primary_profile_id = group_primary.id
+                        AND profile_link.is_active IS TRUE
+                )
+            )
+        ORDER BY actor_profile.id
+        ",
+        &[&profile_id],
+    ).await?;
+    let mut maybe_primary = None;
+    let mut alternates = vec![];
+    for row in rows {
+        let profile = DbActorProfile::try_from(&row)?;
+        let is_primary: bool = row.try_get("is_primary")?;
+        if is_primary {
+            maybe_primary = Some(profile);
+        } else {
+            alternates.push(profile);
+        };
+    };
+    Ok((maybe_primary, alternates))
+}
+
+/// Returns (profile ID, primary profile ID) pairs for linked profiles
+pub async fn get_primary_profile_ids(
+    db_client: &impl DatabaseClient,
+    profile_ids: &[Uuid],
+) -> Result<Vec<(Uuid, Uuid)>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT profile_id, primary_profile_id
+        FROM profile_link
+        WHERE profile_id = ANY($1) AND is_active IS TRUE
+        ",
+        &[&profile_ids],
+    ).await?;
+    let pairs = rows.iter()
+        .map(|row| Ok((
+            row.try_get("profile_id")?,
+            row.try_get("primary_profile_id")?,
+        )))
+        .collect::<Result<_, DatabaseError>>()?;
+    Ok(pairs)
+}
+
 #[cfg(test)]
 mod tests {
     use apx_core::{
         caip2::ChainId,
         crypto::eddsa::generate_weak_ed25519_key,
     };
+    use chrono::{NaiveDate, TimeDelta};
     use serde_json::json;
     use serial_test::serial;
     use crate::{
         accounts::{
             queries::create_user,
-            test_utils::create_test_portable_user,
+            test_utils::{create_test_portable_user, create_test_user},
             types::UserCreateData,
         },
         database::test_utils::create_test_database,
@@ -1067,7 +1466,6 @@ mod tests {
                 DbActor,
                 DbActorKey,
                 ExtraField,
-                IdentityProof,
                 IdentityProofType,
                 PaymentOption,
             },
@@ -1235,6 +1633,10 @@ mod tests {
         let mut profile_data = ProfileUpdateData::from(&profile);
         let bio = "test bio";
         profile_data.bio = Some(bio.to_string());
+        let birthday = NaiveDate::from_ymd_opt(1990, 5, 17).unwrap();
+        profile_data.location = Some("Paris".to_string());
+        profile_data.birthday = Some(birthday);
+        profile_data.pronouns = Some("they/them".to_string());
         let (profile_updated, deletion_queue) = update_profile(
             db_client,
             profile.id,
@@ -1243,11 +1645,32 @@ mod tests {
         assert_eq!(profile_updated.username, profile.username);
         assert_eq!(profile_updated.acct, profile.acct);
         assert_eq!(profile_updated.bio.unwrap(), bio);
+        assert_eq!(profile_updated.location.unwrap(), "Paris");
+        assert_eq!(profile_updated.birthday.unwrap(), birthday);
+        assert_eq!(profile_updated.pronouns.unwrap(), "they/them");
         assert!(profile_updated.updated_at != profile.updated_at);
         assert_eq!(deletion_queue.files.len(), 0);
         assert_eq!(deletion_queue.ipfs_objects.len(), 0);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_set_hidden_collections() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        assert_eq!(user.profile.hide_followers, false);
+        assert_eq!(user.profile.hide_following, false);
+        let profile = set_hidden_collections(
+            db_client,
+            user.id,
+            true,
+            false,
+        ).await.unwrap();
+        assert_eq!(profile.hide_followers, true);
+        assert_eq!(profile.hide_following, false);
+        assert!(profile.updated_at > user.profile.updated_at);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_update_profile_with_unmanaged_account() {
@@ -1273,6 +1696,65 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_identity_proof_history() {
+        let db_client = &mut create_test_database().await;
+        let identity_proof = IdentityProof {
+            issuer: Did::Pkh(DidPkh::from_ethereum_address("0x1234abcd")),
+            proof_type: IdentityProofType::FepC390JcsEip191Proof,
+            value: json!({"proofValue": "13590013185bdea963"}),
+            verified_at: None,
+        };
+        let profile_data = ProfileCreateData {
+            identity_proofs: vec![identity_proof.clone()],
+            ..ProfileCreateData::remote_for_test(
+                "test",
+                "social.example",
+                "https://social.example",
+            )
+        };
+        let profile = create_profile(db_client, profile_data).await.unwrap();
+        let history = get_identity_proof_history(db_client, profile.id)
+            .await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(history[0].valid_until.is_none());
+
+        // Revoke
+        let mut profile_data = ProfileUpdateData::from(&profile);
+        profile_data.remove_identity_proof(&identity_proof.issuer);
+        let (profile, _) = update_profile(
+            db_client,
+            profile.id,
+            profile_data,
+        ).await.unwrap();
+        assert_eq!(profile.identity_proofs.inner().len(), 0);
+        let history = get_identity_proof_history(db_client, profile.id)
+            .await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(history[0].valid_until.is_some());
+        let revoked = get_revoked_identity_proofs(db_client, profile.id)
+            .await.unwrap();
+        assert_eq!(revoked.len(), 1);
+
+        // Add again
+        let mut profile_data = ProfileUpdateData::from(&profile);
+        profile_data.add_identity_proof(identity_proof);
+        let (profile, _) = update_profile(
+            db_client,
+            profile.id,
+            profile_data,
+        ).await.unwrap();
+        assert_eq!(profile.identity_proofs.inner().len(), 1);
+        let history = get_identity_proof_history(db_client, profile.id)
+            .await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(history[0].valid_until.is_none());
+        let revoked = get_revoked_identity_proofs(db_client, profile.id)
+            .await.unwrap();
+        assert_eq!(revoked.len(), 0);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_set_profile_identity_key() {
@@ -1398,6 +1880,7 @@ mod tests {
             issuer: Did::Pkh(DidPkh::from_ethereum_address("0x1234abcd")),
             proof_type: IdentityProofType::LegacyEip191IdentityProof,
             value: json!("13590013185bdea963"),
+            verified_at: None,
         };
         let profile_data = ProfileCreateData {
             identity_proofs: vec![identity_proof],
@@ -1443,4 +1926,145 @@ mod tests {
         let profiles = find_empty_profiles(db_client, updated_before).await.unwrap();
         assert_eq!(profiles.is_empty(), true);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_set_profile_deleted() {
+        let db_client = &mut create_test_database().await;
+        let profile = create_test_remote_profile(
+            db_client,
+            "test",
+            "social.example",
+            "https://social.example/users/test",
+        ).await;
+        let profile = set_profile_deleted(db_client, profile.id).await.unwrap();
+        assert_eq!(profile.deleted_at.is_some(), true);
+
+        let profiles = find_deleted_profiles(
+            db_client,
+            Utc::now() + TimeDelta::seconds(1),
+        ).await.unwrap();
+        assert_eq!(profiles, vec![profile.id]);
+        let profiles = find_deleted_profiles(
+            db_client,
+            Utc::now() - TimeDelta::days(1),
+        ).await.unwrap();
+        assert_eq!(profiles.is_empty(), true);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_set_profile_deleted_local() {
+        let db_client = &mut create_test_database().await;
+        let profile = create_test_local_profile(db_client, "test").await;
+        let error = set_profile_deleted(db_client, profile.id).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("profile")));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_link_profiles() {
+        let db_client = &mut create_test_database().await;
+        let profile_1 = create_test_remote_profile(
+            db_client,
+            "test",
+            "one.example",
+            "https://one.example/users/test",
+        ).await;
+        let profile_2 = create_test_remote_profile(
+            db_client,
+            "test",
+            "two.example",
+            "https://two.example/users/test",
+        ).await;
+        let profile_3 = create_test_remote_profile(
+            db_client,
+            "test",
+            "three.example",
+            "https://three.example/users/test",
+        ).await;
+        let is_linked = link_profiles(
+            db_client,
+            profile_2.id,
+            profile_1.id,
+            false,
+        ).await.unwrap();
+        assert_eq!(is_linked, true);
+        // Linking to alternate profile
+        let is_linked = link_profiles(
+            db_client,
+            profile_3.id,
+            profile_2.id,
+            false,
+        ).await.unwrap();
+        assert_eq!(is_linked, true);
+        // Reverse link
+        let is_linked = link_profiles(
+            db_client,
+            profile_1.id,
+            profile_2.id,
+            false,
+        ).await.unwrap();
+        assert_eq!(is_linked, false);
+
+        let (maybe_primary, alternates) =
+            get_linked_profiles(db_client, profile_3.id).await.unwrap();
+        assert_eq!(maybe_primary.unwrap().id, profile_1.id);
+        assert_eq!(alternates.len(), 1);
+        assert_eq!(alternates[0].id, profile_2.id);
+        let (maybe_primary, alternates) =
+            get_linked_profiles(db_client, profile_1.id).await.unwrap();
+        assert_eq!(maybe_primary.is_none(), true);
+        assert_eq!(alternates.len(), 2);
+
+        let pairs = get_primary_profile_ids(
+            db_client,
+            &[profile_1.id, profile_3.id],
+        ).await.unwrap();
+        assert_eq!(pairs, vec![(profile_3.id, profile_1.id)]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_unlink_profile() {
+        let db_client = &mut create_test_database().await;
+        let profile_1 = create_test_remote_profile(
+            db_client,
+            "test",
+            "one.example",
+            "https://one.example/users/test",
+        ).await;
+        let profile_2 = create_test_remote_profile(
+            db_client,
+            "test",
+            "two.example",
+            "https://two.example/users/test",
+        ).await;
+        link_profiles(db_client, profile_2.id, profile_1.id, false)
+            .await.unwrap();
+        unlink_profile(db_client, profile_2.id).await.unwrap();
+        let (maybe_primary, alternates) =
+            get_linked_profiles(db_client, profile_2.id).await.unwrap();
+        assert_eq!(maybe_primary.is_none(), true);
+        assert_eq!(alternates.is_empty(), true);
+        // Automatic link is not restored
+        let is_linked = link_profiles(
+            db_client,
+            profile_1.id,
+            profile_2.id,
+            false,
+        ).await.unwrap();
+        assert_eq!(is_linked, false);
+        // Manual link
+        let is_linked = link_profiles(
+            db_client,
+            profile_2.id,
+            profile_1.id,
+            true,
+        ).await.unwrap();
+        assert_eq!(is_linked, true);
+
+        let error = unlink_profile(db_client, profile_1.id).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("profile link")));
+    }
 }
@@ -11,7 +11,7 @@ use apx_core::{
     did_key::DidKey,
     url::ap_uri::{is_ap_uri, ApUri},
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use postgres_types::FromSql;
 use serde::{
     Deserialize, Deserializer, Serialize, Serializer,
@@ -273,6 +273,9 @@ pub struct IdentityProof {
     pub issuer: Did,
     pub proof_type: IdentityProofType,
     pub value: JsonValue,
+    // Time of the last successful verification
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -300,6 +303,21 @@ impl IdentityProofs {
 json_from_sql!(IdentityProofs);
 json_to_sql!(IdentityProofs);
 
+json_from_sql!(IdentityProof);
+json_to_sql!(IdentityProof);
+
+/// Identity proof history entry.
+/// The proof is revoked if `valid_until` is not null.
+#[derive(FromSql)]
+#[postgres(name = "identity_proof_record")]
+pub struct IdentityProofRecord {
+    pub id: i32,
+    pub profile_id: Uuid,
+    pub proof: IdentityProof,
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
 #[derive(PartialEq)]
 pub(super) enum PaymentType {
     Link,
@@ -703,6 +721,13 @@ pub struct DbActorProfile {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub unreachable_since: Option<DateTime<Utc>>,
+    pub location: Option<String>,
+    pub birthday: Option<NaiveDate>,
+    pub pronouns: Option<String>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    // Social graph collections are hidden (local profiles only)
+    pub hide_followers: bool,
+    pub hide_following: bool,
 
     // auto-generated database fields
     #[allow(dead_code)]
@@ -972,6 +997,12 @@ impl Default for DbActorProfile {
             created_at: now,
             updated_at: now,
             unreachable_since: None,
+            location: None,
+            birthday: None,
+            pronouns: None,
+            deleted_at: None,
+            hide_followers: false,
+            hide_following: false,
         }
     }
 }
@@ -993,6 +1024,9 @@ pub struct ProfileCreateData {
     pub identity_proofs: Vec<IdentityProof>,
     pub payment_options: Vec<PaymentOption>,
     pub extra_fields: Vec<ExtraField>,
+    pub location: Option<String>,
+    pub birthday: Option<NaiveDate>,
+    pub pronouns: Option<String>,
     pub aliases: Vec<String>,
     pub emojis: Vec<Uuid>,
     pub actor_json: Option<DbActor>,
@@ -1047,6 +1081,9 @@ pub struct ProfileUpdateData {
     pub identity_proofs: Vec<IdentityProof>,
     pub payment_options: Vec<PaymentOption>,
     pub extra_fields: Vec<ExtraField>,
+    pub location: Option<String>,
+    pub birthday: Option<NaiveDate>,
+    pub pronouns: Option<String>,
     pub aliases: Vec<String>,
     pub emojis: Vec<Uuid>,
     pub actor_json: Option<DbActor>,
@@ -1123,6 +1160,9 @@ impl From<&DbActorProfile> for ProfileUpdateData {
             identity_proofs: profile.identity_proofs.into_inner(),
             payment_options: profile.payment_options.into_inner(),
             extra_fields: profile.extra_fields.into_inner(),
+            location: profile.location,
+            birthday: profile.birthday,
+            pronouns: profile.pronouns,
             aliases: profile.aliases.into_actor_ids(),
             emojis: profile.emojis.into_inner().into_iter()
                 .map(|emoji| emoji.id)
@@ -1,12 +1,27 @@
 pub const INSTANCE_ED25519_SECRET_KEY: &str = "instance_ed25519_secret_key";
 pub const INSTANCE_RSA_SECRET_KEY: &str = "instance_rsa_secret_key";
+pub const INSTANCE_VAPID_SECRET_KEY: &str = "instance_vapid_secret_key";
+pub const MEDIA_URL_SECRET_KEY: &str = "media_url_secret_key";
+pub const WORKER_HEARTBEAT: &str = "worker_heartbeat";
 
 #[deprecated]
 pub const ETHEREUM_CURRENT_BLOCK: &str = "ethereum_current_block";
 
+pub const APP_APPROVAL_REQUIRED: &str = "app_approval_required";
 pub const DEFAULT_FTS_CONFIG: &str = "default_fts_config";
 pub const FAVORITE_EMOJIS: &str = "favorite_emojis";
 pub const FEDERATED_TIMELINE_RESTRICTED: &str = "federated_timeline_restricted";
 pub const FILTER_BLOCKLIST_PUBLIC: &str =  "filter_blocklist_public";
 pub const FILTER_KEYWORDS: &str = "filter_keywords";
+pub const FILTER_MEDIA_ATTACHMENT_LIMIT: &str = "filter_media_attachment_limit";
+pub const FRONTEND_CONFIGURATIONS: &str = "frontend_configurations";
+pub const INSTANCE_CONTACT_ACCOUNT: &str = "instance_contact_account";
+pub const INSTANCE_DESCRIPTION: &str = "instance_description";
+pub const INSTANCE_EXTENDED_DESCRIPTION: &str = "instance_extended_description";
+pub const INSTANCE_THUMBNAIL: &str = "instance_thumbnail";
 pub const LIKE_EMOJI: &str = "like_emoji";
+pub const MAINTENANCE_MODE: &str = "maintenance_mode";
+pub const PORTABLE_ACCOUNTS_LIMIT: &str = "portable_accounts_limit";
+pub const REACTION_ALLOWLIST: &str = "reaction_allowlist";
+pub const REACTION_POLICY: &str = "reaction_policy";
+pub const ROLE_LIMITS: &str = "role_limits";
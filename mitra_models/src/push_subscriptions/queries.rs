@@ -0,0 +1,235 @@
+use uuid::Uuid;
+
+use crate::database::{DatabaseClient, DatabaseError};
+
+use super::types::{
+    PushAlerts,
+    PushPolicy,
+    PushSubscription,
+    PushSubscriptionData,
+};
+
+/// Creates push subscription for the access token.
+/// Existing subscription is replaced.
+pub async fn create_push_subscription(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+    oauth_token_id: i32,
+    subscription_data: PushSubscriptionData,
+) -> Result<PushSubscription, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        INSERT INTO push_subscription (
+            owner_id,
+            oauth_token_id,
+            endpoint,
+            public_key,
+            auth_secret,
+            alerts,
+            policy
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (oauth_token_id)
+        DO UPDATE SET
+            endpoint = $3,
+            public_key = $4,
+            auth_secret = $5,
+            alerts = $6,
+            policy = $7,
+            created_at = CURRENT_TIMESTAMP
+        RETURNING push_subscription
+        ",
+        &[
+            &owner_id,
+            &oauth_token_id,
+            &subscription_data.endpoint,
+            &subscription_data.public_key,
+            &subscription_data.auth_secret,
+            &subscription_data.alerts,
+            &subscription_data.policy,
+        ],
+    ).await?;
+    let subscription = row.try_get("push_subscription")?;
+    Ok(subscription)
+}
+
+pub async fn get_push_subscription(
+    db_client: &impl DatabaseClient,
+    oauth_token_id: i32,
+) -> Result<PushSubscription, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT push_subscription
+        FROM push_subscription
+        WHERE oauth_token_id = $1
+        ",
+        &[&oauth_token_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("push subscription"))?;
+    let subscription = row.try_get("push_subscription")?;
+    Ok(subscription)
+}
+
+pub async fn get_push_subscriptions(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+) -> Result<Vec<PushSubscription>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT push_subscription
+        FROM push_subscription
+        WHERE owner_id = $1
+        ",
+        &[&owner_id],
+    ).await?;
+    let subscriptions = rows.iter()
+        .map(|row| row.try_get("push_subscription"))
+        .collect::<Result<_, _>>()?;
+    Ok(subscriptions)
+}
+
+pub async fn update_push_subscription(
+    db_client: &impl DatabaseClient,
+    oauth_token_id: i32,
+    alerts: PushAlerts,
+    policy: PushPolicy,
+) -> Result<PushSubscription, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE push_subscription
+        SET alerts = $2, policy = $3
+        WHERE oauth_token_id = $1
+        RETURNING push_subscription
+        ",
+        &[&oauth_token_id, &alerts, &policy],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("push subscription"))?;
+    let subscription = row.try_get("push_subscription")?;
+    Ok(subscription)
+}
+
+pub async fn delete_push_subscription(
+    db_client: &impl DatabaseClient,
+    oauth_token_id: i32,
+) -> Result<(), DatabaseError> {
+    db_client.execute(
+        "DELETE FROM push_subscription WHERE oauth_token_id = $1",
+        &[&oauth_token_id],
+    ).await?;
+    Ok(())
+}
+
+pub async fn delete_push_subscription_by_id(
+    db_client: &impl DatabaseClient,
+    subscription_id: i32,
+) -> Result<(), DatabaseError> {
+    db_client.execute(
+        "DELETE FROM push_subscription WHERE id = $1",
+        &[&subscription_id],
+    ).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, Utc};
+    use serial_test::serial;
+    use crate::{
+        accounts::test_utils::create_test_user,
+        database::test_utils::create_test_database,
+        oauth::queries::save_oauth_token,
+    };
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_push_subscription() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let token_id = save_oauth_token(
+            db_client,
+            user.id,
+            None,
+            "test-token",
+            Utc::now(),
+            Utc::now() + TimeDelta::days(1),
+        ).await.unwrap();
+        let subscription_data = PushSubscriptionData {
+            endpoint: "https://push.example/123".to_string(),
+            public_key: vec![4; 65],
+            auth_secret: vec![1; 16],
+            alerts: PushAlerts { mention: true, ..Default::default() },
+            policy: PushPolicy::All,
+        };
+        let subscription = create_push_subscription(
+            db_client,
+            user.id,
+            token_id,
+            subscription_data,
+        ).await.unwrap();
+        assert_eq!(subscription.owner_id, user.id);
+        assert_eq!(subscription.oauth_token_id, token_id);
+        assert_eq!(subscription.alerts.mention, true);
+        assert_eq!(subscription.policy, PushPolicy::All);
+
+        // Replace
+        let subscription_data = PushSubscriptionData {
+            endpoint: "https://push.example/456".to_string(),
+            public_key: vec![4; 65],
+            auth_secret: vec![2; 16],
+            alerts: PushAlerts::default(),
+            policy: PushPolicy::Followed,
+        };
+        let subscription = create_push_subscription(
+            db_client,
+            user.id,
+            token_id,
+            subscription_data,
+        ).await.unwrap();
+        assert_eq!(subscription.endpoint, "https://push.example/456");
+        let subscriptions = get_push_subscriptions(db_client, user.id)
+            .await.unwrap();
+        assert_eq!(subscriptions.len(), 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_update_and_delete_push_subscription() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let token_id = save_oauth_token(
+            db_client,
+            user.id,
+            None,
+            "test-token",
+            Utc::now(),
+            Utc::now() + TimeDelta::days(1),
+        ).await.unwrap();
+        let subscription_data = PushSubscriptionData {
+            endpoint: "https://push.example/123".to_string(),
+            public_key: vec![4; 65],
+            auth_secret: vec![1; 16],
+            alerts: PushAlerts::default(),
+            policy: PushPolicy::All,
+        };
+        create_push_subscription(
+            db_client,
+            user.id,
+            token_id,
+            subscription_data,
+        ).await.unwrap();
+        let alerts = PushAlerts { follow: true, ..Default::default() };
+        let subscription = update_push_subscription(
+            db_client,
+            token_id,
+            alerts.clone(),
+            PushPolicy::Follower,
+        ).await.unwrap();
+        assert_eq!(subscription.alerts, alerts);
+        assert_eq!(subscription.policy, PushPolicy::Follower);
+
+        delete_push_subscription(db_client, token_id).await.unwrap();
+        let error = get_push_subscription(db_client, token_id).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("push subscription")));
+    }
+}
@@ -0,0 +1,166 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use postgres_types::FromSql;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    background_jobs::{
+        queries::enqueue_job,
+        types::JobType,
+    },
+    database::{
+        int_enum::{int_enum_from_sql, int_enum_to_sql},
+        json_macro::{json_from_sql, json_to_sql},
+        DatabaseClient,
+        DatabaseError,
+        DatabaseTypeError,
+    },
+    notifications::types::EventType,
+};
+
+/// Types of notifications that should be delivered
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct PushAlerts {
+    pub mention: bool,
+    pub status: bool,
+    pub reblog: bool,
+    pub follow: bool,
+    pub follow_request: bool,
+    pub favourite: bool,
+    pub poll: bool,
+    pub update: bool,
+    pub admin_sign_up: bool,
+    pub admin_report: bool,
+}
+
+impl PushAlerts {
+    pub fn is_enabled(&self, event_type: EventType) -> bool {
+        match event_type {
+            EventType::Follow => self.follow,
+            EventType::FollowRequest => self.follow_request,
+            EventType::Reply | EventType::Mention => self.mention,
+            EventType::Reaction => self.favourite,
+            EventType::Repost => self.reblog,
+            EventType::Post => self.status,
+            EventType::SignUp => self.admin_sign_up,
+            EventType::Report => self.admin_report,
+            // Not configurable
+            EventType::SubscriberPayment
+                | EventType::SubscriptionStart
+                | EventType::SubscriptionExpiration
+                | EventType::SubscriberLeaving
+                | EventType::SubscriptionReminder
+                | EventType::StorageQuotaWarning
+                | EventType::View
+                | EventType::Move => true,
+        }
+    }
+}
+
+json_from_sql!(PushAlerts);
+json_to_sql!(PushAlerts);
+
+/// Whose notifications should be delivered
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PushPolicy {
+    #[default]
+    All,
+    Followed,
+    Follower,
+    None,
+}
+
+impl From<PushPolicy> for i16 {
+    fn from(value: PushPolicy) -> i16 {
+        match value {
+            PushPolicy::All => 1,
+            PushPolicy::Followed => 2,
+            PushPolicy::Follower => 3,
+            PushPolicy::None => 4,
+        }
+    }
+}
+
+impl TryFrom<i16> for PushPolicy {
+    type Error = DatabaseTypeError;
+
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        let policy = match value {
+            1 => Self::All,
+            2 => Self::Followed,
+            3 => Self::Follower,
+            4 => Self::None,
+            _ => return Err(DatabaseTypeError),
+        };
+        Ok(policy)
+    }
+}
+
+int_enum_from_sql!(PushPolicy);
+int_enum_to_sql!(PushPolicy);
+
+#[derive(FromSql)]
+#[postgres(name = "push_subscription")]
+pub struct PushSubscription {
+    pub id: i32,
+    pub owner_id: Uuid,
+    pub oauth_token_id: i32,
+    pub endpoint: String,
+    // Uncompressed P-256 public key
+    pub public_key: Vec<u8>,
+    pub auth_secret: Vec<u8>,
+    pub alerts: PushAlerts,
+    pub policy: PushPolicy,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct PushSubscriptionData {
+    pub endpoint: String,
+    pub public_key: Vec<u8>,
+    pub auth_secret: Vec<u8>,
+    pub alerts: PushAlerts,
+    pub policy: PushPolicy,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct PushJobData {
+    pub recipient_id: Uuid,
+    pub notification_id: i32,
+    #[serde(default)]
+    pub failure_count: u32,
+}
+
+impl PushJobData {
+    pub async fn into_job(
+        self,
+        db_client: &impl DatabaseClient,
+        delay: u32,
+    ) -> Result<(), DatabaseError> {
+        let job_data = serde_json::to_value(self)
+            .expect("job data should be serializable");
+        let scheduled_for = Utc::now() + TimeDelta::seconds(delay.into());
+        enqueue_job(
+            db_client,
+            JobType::PushNotification,
+            &job_data,
+            scheduled_for,
+        ).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_alerts_deserialize() {
+        let value = serde_json::json!({"mention": true, "follow": true});
+        let alerts: PushAlerts = serde_json::from_value(value).unwrap();
+        assert_eq!(alerts.is_enabled(EventType::Mention), true);
+        assert_eq!(alerts.is_enabled(EventType::Reply), true);
+        assert_eq!(alerts.is_enabled(EventType::Repost), false);
+        assert_eq!(alerts.is_enabled(EventType::Move), true);
+    }
+}
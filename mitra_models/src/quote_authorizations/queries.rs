@@ -0,0 +1,213 @@
+use uuid::Uuid;
+
+use mitra_utils::id::generate_ulid;
+
+use crate::database::{DatabaseClient, DatabaseError};
+
+use super::types::QuoteAuthorization;
+
+/// Creates authorization for a remote quote of a local post.
+/// Returns existing authorization if the quote was already approved.
+pub async fn create_local_quote_authorization(
+    db_client: &impl DatabaseClient,
+    quoted_post_id: Uuid,
+    quote_object_id: &str,
+) -> Result<QuoteAuthorization, DatabaseError> {
+    let authorization_id = generate_ulid();
+    let row = db_client.query_one(
+        "
+        INSERT INTO quote_authorization (
+            id,
+            quoted_post_id,
+            quote_object_id
+        )
+        VALUES ($1, $2, $3)
+        ON CONFLICT (quoted_post_id, quote_object_id)
+        DO UPDATE SET quoted_post_id = EXCLUDED.quoted_post_id
+        RETURNING quote_authorization
+        ",
+        &[&authorization_id, &quoted_post_id, &quote_object_id],
+    ).await?;
+    let authorization = row.try_get("quote_authorization")?;
+    Ok(authorization)
+}
+
+pub async fn get_local_quote_authorization(
+    db_client: &impl DatabaseClient,
+    authorization_id: Uuid,
+) -> Result<QuoteAuthorization, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT quote_authorization
+        FROM quote_authorization
+        WHERE id = $1 AND object_id IS NULL
+        ",
+        &[&authorization_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("quote authorization"))?;
+    let authorization = row.try_get("quote_authorization")?;
+    Ok(authorization)
+}
+
+/// Saves authorization received for a local quote post.
+/// Existing authorization is replaced.
+pub async fn save_remote_quote_authorization(
+    db_client: &impl DatabaseClient,
+    quoted_post_id: Uuid,
+    quote_post_id: Uuid,
+    object_id: &str,
+) -> Result<QuoteAuthorization, DatabaseError> {
+    let authorization_id = generate_ulid();
+    let row = db_client.query_one(
+        "
+        INSERT INTO quote_authorization (
+            id,
+            quoted_post_id,
+            quote_post_id,
+            object_id
+        )
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (quote_post_id)
+        DO UPDATE SET
+            quoted_post_id = $2,
+            object_id = $4,
+            created_at = CURRENT_TIMESTAMP
+        RETURNING quote_authorization
+        ",
+        &[
+            &authorization_id,
+            &quoted_post_id,
+            &quote_post_id,
+            &object_id,
+        ],
+    ).await?;
+    let authorization = row.try_get("quote_authorization")?;
+    Ok(authorization)
+}
+
+pub async fn get_remote_quote_authorizations(
+    db_client: &impl DatabaseClient,
+    quote_post_ids: &[Uuid],
+) -> Result<Vec<QuoteAuthorization>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT quote_authorization
+        FROM quote_authorization
+        WHERE quote_post_id = ANY($1)
+        ",
+        &[&quote_post_ids],
+    ).await?;
+    let authorizations = rows.iter()
+        .map(|row| row.try_get("quote_authorization"))
+        .collect::<Result<_, _>>()?;
+    Ok(authorizations)
+}
+
+pub async fn delete_remote_quote_authorization(
+    db_client: &impl DatabaseClient,
+    quote_post_id: Uuid,
+) -> Result<(), DatabaseError> {
+    db_client.execute(
+        "DELETE FROM quote_authorization WHERE quote_post_id = $1",
+        &[&quote_post_id],
+    ).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use crate::{
+        database::test_utils::create_test_database,
+        posts::test_utils::{create_test_local_post, create_test_remote_post},
+        profiles::test_utils::{
+            create_test_local_profile,
+            create_test_remote_profile,
+        },
+    };
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_local_quote_authorization() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_local_profile(db_client, "test").await;
+        let post = create_test_local_post(db_client, author.id, "test").await;
+        let quote_object_id = "https://social.example/objects/1";
+        let authorization = create_local_quote_authorization(
+            db_client,
+            post.id,
+            quote_object_id,
+        ).await.unwrap();
+        assert_eq!(authorization.quoted_post_id, post.id);
+        assert_eq!(authorization.quote_post_id, None);
+        assert_eq!(
+            authorization.quote_object_id.as_deref(),
+            Some(quote_object_id),
+        );
+        assert_eq!(authorization.is_local(), true);
+
+        // Repeated request
+        let authorization_repeated = create_local_quote_authorization(
+            db_client,
+            post.id,
+            quote_object_id,
+        ).await.unwrap();
+        assert_eq!(authorization_repeated.id, authorization.id);
+
+        let authorization_found = get_local_quote_authorization(
+            db_client,
+            authorization.id,
+        ).await.unwrap();
+        assert_eq!(authorization_found.id, authorization.id);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_save_remote_quote_authorization() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_local_profile(db_client, "test").await;
+        let quoted_author = create_test_remote_profile(
+            db_client,
+            "remote",
+            "social.example",
+            "https://social.example/users/1",
+        ).await;
+        let quoted_post = create_test_remote_post(
+            db_client,
+            quoted_author.id,
+            "test",
+            "https://social.example/objects/1",
+        ).await;
+        let post = create_test_local_post(db_client, author.id, "quote").await;
+        let object_id = "https://social.example/quote_authorizations/1";
+        let authorization = save_remote_quote_authorization(
+            db_client,
+            quoted_post.id,
+            post.id,
+            object_id,
+        ).await.unwrap();
+        assert_eq!(authorization.quoted_post_id, quoted_post.id);
+        assert_eq!(authorization.quote_post_id, Some(post.id));
+        assert_eq!(authorization.object_id.as_deref(), Some(object_id));
+        assert_eq!(authorization.is_local(), false);
+        let result = get_local_quote_authorization(
+            db_client,
+            authorization.id,
+        ).await;
+        assert!(matches!(result, Err(DatabaseError::NotFound(_))));
+
+        let authorizations = get_remote_quote_authorizations(
+            db_client,
+            &[post.id],
+        ).await.unwrap();
+        assert_eq!(authorizations.len(), 1);
+
+        delete_remote_quote_authorization(db_client, post.id).await.unwrap();
+        let authorizations = get_remote_quote_authorizations(
+            db_client,
+            &[post.id],
+        ).await.unwrap();
+        assert_eq!(authorizations.is_empty(), true);
+    }
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use postgres_types::FromSql;
+use uuid::Uuid;
+
+/// FEP-044f quote authorization
+#[derive(Clone, FromSql)]
+#[postgres(name = "quote_authorization")]
+pub struct QuoteAuthorization {
+    pub id: Uuid,
+    pub quoted_post_id: Uuid,
+    // Local quote post (if authorization is remote)
+    pub quote_post_id: Option<Uuid>,
+    // Remote quote post (if authorization is local)
+    pub quote_object_id: Option<String>,
+    pub object_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl QuoteAuthorization {
+    pub fn is_local(&self) -> bool {
+        self.object_id.is_none()
+    }
+}
@@ -14,6 +14,7 @@ use crate::{
             get_post_author,
             post_subqueries,
             update_reaction_count,
+            update_reaction_summaries,
         },
         types::Visibility,
     },
@@ -68,6 +69,7 @@ pub async fn create_reaction(
     let row = maybe_row.ok_or(DatabaseError::AlreadyExists("reaction"))?;
     let reaction: Reaction = row.try_get("post_reaction")?;
     update_reaction_count(&transaction, reaction.post_id, 1).await?;
+    update_reaction_summaries(&transaction, &[reaction.post_id]).await?;
     let post_author = get_post_author(&transaction, reaction.post_id).await?;
     if post_author.has_user_account() && post_author.id != reaction.author_id {
         create_reaction_notification(
@@ -125,6 +127,7 @@ pub async fn delete_reaction(
         visibility: row.try_get("visibility")?,
     };
     update_reaction_count(&transaction, post_id, -1).await?;
+    update_reaction_summaries(&transaction, &[post_id]).await?;
     transaction.commit().await?;
     Ok(reaction_deleted)
 }
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use uuid::Uuid;
 
 use mitra_utils::id::generate_ulid;
@@ -26,6 +24,7 @@ use super::types::{
     FollowRequestStatus,
     RelatedActorProfile,
     RelationshipDetailed,
+    RelationshipFlags,
     RelationshipOrFollowRequest,
     RelationshipType,
 };
@@ -83,48 +82,85 @@ pub async fn get_relationships(
     Ok(relationships)
 }
 
-pub async fn get_relationships_many(
+/// Returns relationship flags for each target
+/// (in the same order as `target_ids`)
+pub async fn get_relationship_flags(
     db_client: &impl DatabaseClient,
     source_id: Uuid,
     target_ids: &[Uuid],
-) -> Result<Vec<(Uuid, Vec<RelationshipOrFollowRequest>)>, DatabaseError> {
-    let rows = db_client.query(
+) -> Result<Vec<RelationshipFlags>, DatabaseError> {
+    fn relationship_exists(
+        relationship_type: RelationshipType,
+        is_direct: bool,
+    ) -> String {
+        let (source, target) = if is_direct {
+            ("$1", "target.id")
+        } else {
+            ("target.id", "$1")
+        };
+        format!(
+            "EXISTS (
+                SELECT 1 FROM relationship
+                WHERE
+                    source_id = {source}
+                    AND target_id = {target}
+                    AND relationship_type = {relationship_type}
+            )",
+            relationship_type=i16::from(relationship_type),
+        )
+    }
+    fn follow_request_exists(is_direct: bool) -> String {
+        let (source, target) = if is_direct {
+            ("$1", "target.id")
+        } else {
+            ("target.id", "$1")
+        };
+        format!(
+            "EXISTS (
+                SELECT 1 FROM follow_request
+                WHERE
+                    source_id = {source}
+                    AND target_id = {target}
+                    AND request_status = {request_status}
+            )",
+            request_status=i16::from(FollowRequestStatus::Pending),
+        )
+    }
+    let statement = format!(
         "
-        SELECT source_id, target_id, relationship_type, created_at
-        FROM relationship
-        WHERE
-            source_id = $1 AND target_id = ANY($2)
-            OR
-            source_id = ANY($2) AND target_id = $1
-        UNION ALL
-        SELECT source_id, target_id, $4, created_at
-        FROM follow_request
-        WHERE
-            (
-                source_id = $1 AND target_id = ANY($2)
-                OR
-                source_id = ANY($2) AND target_id = $1
-            )
-            AND request_status = $3
+        SELECT
+            target.id AS target_id,
+            {following} AS following,
+            {followed_by} AS followed_by,
+            {requested} AS requested,
+            {requested_by} AS requested_by,
+            {rejected_by} AS rejected_by,
+            {subscription_to} AS subscription_to,
+            {subscription_from} AS subscription_from,
+            {hiding_reposts} AS hiding_reposts,
+            {hiding_replies} AS hiding_replies,
+            {muting} AS muting,
+            {notifying} AS notifying
+        FROM unnest($2::uuid[]) WITH ORDINALITY AS target (id, position)
+        ORDER BY target.position
         ",
-        &[
-            &source_id,
-            &target_ids,
-            &FollowRequestStatus::Pending,
-            &RelationshipType::FollowRequest,
-        ],
-    ).await?;
-    // No duplicate keys in buckets hashmap
-    let mut buckets: HashMap<Uuid, Vec<RelationshipOrFollowRequest>> =
-        HashMap::from_iter(target_ids.iter().map(|id| (*id, vec![])));
-    for row in rows {
-        let relationship = RelationshipOrFollowRequest::try_from(&row)?;
-        let target_id = relationship.with(source_id)?;
-        let target_relationships = buckets.get_mut(&target_id)
-            .ok_or(DatabaseTypeError)?;
-        target_relationships.push(relationship);
-    };
-    Ok(buckets.into_iter().collect())
+        following=relationship_exists(RelationshipType::Follow, true),
+        followed_by=relationship_exists(RelationshipType::Follow, false),
+        requested=follow_request_exists(true),
+        requested_by=follow_request_exists(false),
+        rejected_by=relationship_exists(RelationshipType::Reject, false),
+        subscription_to=relationship_exists(RelationshipType::Subscription, true),
+        subscription_from=relationship_exists(RelationshipType::Subscription, false),
+        hiding_reposts=relationship_exists(RelationshipType::HideReposts, true),
+        hiding_replies=relationship_exists(RelationshipType::HideReplies, true),
+        muting=relationship_exists(RelationshipType::Mute, true),
+        notifying=relationship_exists(RelationshipType::NotifyPosts, true),
+    );
+    let rows = db_client.query(&statement, &[&source_id, &target_ids]).await?;
+    let flags = rows.iter()
+        .map(RelationshipFlags::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(flags)
 }
 
 pub async fn has_relationship(
@@ -312,6 +348,7 @@ pub async fn unfollow(
         // Also reset repost and reply visibility settings
         show_reposts(&transaction, source_id, target_id).await?;
         show_replies(&transaction, source_id, target_id).await?;
+        disable_post_notifications(&transaction, source_id, target_id).await?;
         // Update counters only if relationship existed
         update_follower_count(&transaction, target_id, -1).await?;
         update_following_count(&transaction, source_id, -1).await?;
@@ -817,6 +854,72 @@ pub async fn show_replies(
     Ok(())
 }
 
+pub async fn enable_post_notifications(
+    db_client: &impl DatabaseClient,
+    source_id: Uuid,
+    target_id: Uuid,
+) -> Result<(), DatabaseError> {
+    db_client.execute(
+        "
+        INSERT INTO relationship (source_id, target_id, relationship_type)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (source_id, target_id, relationship_type) DO NOTHING
+        ",
+        &[&source_id, &target_id, &RelationshipType::NotifyPosts],
+    ).await?;
+    Ok(())
+}
+
+pub async fn disable_post_notifications(
+    db_client: &impl DatabaseClient,
+    source_id: Uuid,
+    target_id: Uuid,
+) -> Result<(), DatabaseError> {
+    // Does not return NotFound error
+    db_client.execute(
+        "
+        DELETE FROM relationship
+        WHERE
+            source_id = $1 AND target_id = $2
+            AND relationship_type = $3
+        ",
+        &[&source_id, &target_id, &RelationshipType::NotifyPosts],
+    ).await?;
+    Ok(())
+}
+
+/// Returns IDs of followers who want to be notified about new posts
+pub async fn get_post_notification_recipients(
+    db_client: &impl DatabaseClient,
+    profile_id: Uuid,
+) -> Result<Vec<Uuid>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT relationship.source_id
+        FROM relationship
+        WHERE
+            relationship.target_id = $1
+            AND relationship.relationship_type = $2
+            AND EXISTS (
+                SELECT 1 FROM relationship AS follow
+                WHERE
+                    follow.source_id = relationship.source_id
+                    AND follow.target_id = relationship.target_id
+                    AND follow.relationship_type = $3
+            )
+        ",
+        &[
+            &profile_id,
+            &RelationshipType::NotifyPosts,
+            &RelationshipType::Follow,
+        ],
+    ).await?;
+    let recipients = rows.iter()
+        .map(|row| row.try_get("source_id"))
+        .collect::<Result<_, _>>()?;
+    Ok(recipients)
+}
+
 pub async fn mute(
     db_client: &impl DatabaseClient,
     source_id: Uuid,
@@ -906,19 +1009,48 @@ mod tests {
         let relationship = &relationships[0];
         assert_eq!(relationship.source_id, source.id);
         assert_eq!(relationship.target_id, target.id);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_relationship_flags() {
+        let db_client = &mut create_test_database().await;
+        let source = create_test_user(db_client, "source").await;
+        let target_1 = create_test_user(db_client, "target1").await;
+        let target_2 = create_test_user(db_client, "target2").await;
+        let target_3 = create_test_user(db_client, "target3").await;
+        follow(db_client, source.id, target_1.id).await.unwrap();
+        hide_reposts(db_client, source.id, target_1.id).await.unwrap();
+        follow(db_client, target_2.id, source.id).await.unwrap();
+        mute(db_client, source.id, target_2.id).await.unwrap();
+        subscribe(db_client, target_3.id, source.id).await.unwrap();
 
-        let relationships = get_relationships_many(
+        let flags = get_relationship_flags(
             db_client,
             source.id,
-            &[target.id],
+            &[target_3.id, target_1.id, target_2.id],
         ).await.unwrap();
-        assert_eq!(relationships.len(), 1);
-        let (target_id, target_relationships) = &relationships[0];
-        assert_eq!(*target_id, target.id);
-        assert_eq!(target_relationships.len(), 1);
-        let relationship = &target_relationships[0];
-        assert_eq!(relationship.source_id, source.id);
-        assert_eq!(relationship.target_id, target.id);
+        assert_eq!(flags.len(), 3);
+        let flags_3 = &flags[0];
+        assert_eq!(flags_3.target_id, target_3.id);
+        assert!(!flags_3.following);
+        assert!(!flags_3.subscription_to);
+        assert!(flags_3.subscription_from);
+        let flags_1 = &flags[1];
+        assert_eq!(flags_1.target_id, target_1.id);
+        assert!(flags_1.following);
+        assert!(!flags_1.followed_by);
+        assert!(flags_1.hiding_reposts);
+        assert!(!flags_1.hiding_replies);
+        assert!(!flags_1.muting);
+        let flags_2 = &flags[2];
+        assert_eq!(flags_2.target_id, target_2.id);
+        assert!(!flags_2.following);
+        assert!(flags_2.followed_by);
+        assert!(!flags_2.hiding_reposts);
+        assert!(flags_2.muting);
+        assert!(!flags_2.requested);
+        assert!(!flags_2.notifying);
     }
 
     #[tokio::test]
@@ -1181,4 +1313,30 @@ mod tests {
         let result = is_local_or_followed(db_client, actor_id).await.unwrap();
         assert_eq!(result, true);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_post_notification_recipients() {
+        let db_client = &mut create_test_database().await;
+        let source = create_test_user(db_client, "source").await;
+        let target = create_test_user(db_client, "target").await;
+        enable_post_notifications(db_client, source.id, target.id)
+            .await.unwrap();
+        // Not following
+        let recipients = get_post_notification_recipients(db_client, target.id)
+            .await.unwrap();
+        assert_eq!(recipients.is_empty(), true);
+
+        follow(db_client, source.id, target.id).await.unwrap();
+        let recipients = get_post_notification_recipients(db_client, target.id)
+            .await.unwrap();
+        assert_eq!(recipients, vec![source.id]);
+
+        // Notifications are disabled on unfollow
+        unfollow(db_client, source.id, target.id).await.unwrap();
+        follow(db_client, source.id, target.id).await.unwrap();
+        let recipients = get_post_notification_recipients(db_client, target.id)
+            .await.unwrap();
+        assert_eq!(recipients.is_empty(), true);
+    }
 }
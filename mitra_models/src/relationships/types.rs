@@ -22,6 +22,7 @@ pub enum RelationshipType {
     Mute,
     Reject, // follow request rejected
     GroupAdmin,
+    NotifyPosts, // notify about new posts
 }
 
 impl From<RelationshipType> for i16 {
@@ -35,6 +36,7 @@ impl From<RelationshipType> for i16 {
             RelationshipType::Mute => 6,
             RelationshipType::Reject => 7,
             RelationshipType::GroupAdmin => 8,
+            RelationshipType::NotifyPosts => 9,
         }
     }
 }
@@ -52,6 +54,7 @@ impl TryFrom<i16> for RelationshipType {
             6 => Self::Mute,
             7 => Self::Reject,
             8 => Self::GroupAdmin,
+            9 => Self::NotifyPosts,
             _ => return Err(DatabaseTypeError),
         };
         Ok(relationship_type)
@@ -121,21 +124,6 @@ impl RelationshipOrFollowRequest {
             Err(DatabaseTypeError)
         }
     }
-
-    pub(super) fn with(
-        &self,
-        profile_id: Uuid,
-    ) -> Result<Uuid, DatabaseTypeError> {
-        if self.source_id == profile_id {
-            // Direct relationship
-            Ok(self.target_id)
-        } else if self.target_id == profile_id {
-            // Inverse relationship
-            Ok(self.source_id)
-        } else {
-            Err(DatabaseTypeError)
-        }
-    }
 }
 
 impl TryFrom<&Row> for RelationshipOrFollowRequest {
@@ -215,6 +203,45 @@ impl TryFrom<i16> for FollowRequestStatus {
 int_enum_from_sql!(FollowRequestStatus);
 int_enum_to_sql!(FollowRequestStatus);
 
+/// Relationship flags (from the point of view of the source)
+#[cfg_attr(any(test, feature = "test-utils"), derive(Default))]
+pub struct RelationshipFlags {
+    pub target_id: Uuid,
+    pub following: bool,
+    pub followed_by: bool,
+    pub requested: bool,
+    pub requested_by: bool,
+    pub rejected_by: bool,
+    pub subscription_to: bool,
+    pub subscription_from: bool,
+    pub hiding_reposts: bool,
+    pub hiding_replies: bool,
+    pub muting: bool,
+    pub notifying: bool,
+}
+
+impl TryFrom<&Row> for RelationshipFlags {
+    type Error = tokio_postgres::Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let flags = Self {
+            target_id: row.try_get("target_id")?,
+            following: row.try_get("following")?,
+            followed_by: row.try_get("followed_by")?,
+            requested: row.try_get("requested")?,
+            requested_by: row.try_get("requested_by")?,
+            rejected_by: row.try_get("rejected_by")?,
+            subscription_to: row.try_get("subscription_to")?,
+            subscription_from: row.try_get("subscription_from")?,
+            hiding_reposts: row.try_get("hiding_reposts")?,
+            hiding_replies: row.try_get("hiding_replies")?,
+            muting: row.try_get("muting")?,
+            notifying: row.try_get("notifying")?,
+        };
+        Ok(flags)
+    }
+}
+
 #[derive(FromSql)]
 #[postgres(name = "follow_request")]
 pub struct FollowRequest {
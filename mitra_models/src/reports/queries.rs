@@ -0,0 +1,309 @@
+use uuid::Uuid;
+
+use mitra_utils::id::generate_ulid;
+
+use crate::database::{
+    catch_unique_violation,
+    DatabaseClient,
+    DatabaseError,
+};
+
+use super::types::{Report, ReportCreateData, ReportNote};
+
+pub async fn create_report(
+    db_client: &impl DatabaseClient,
+    report_data: ReportCreateData,
+) -> Result<Report, DatabaseError> {
+    let report_id = generate_ulid();
+    let row = db_client.query_one(
+        "
+        INSERT INTO report (
+            id,
+            reporter_id,
+            target_id,
+            post_ids,
+            comment,
+            object_id,
+            is_forwarded
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING report
+        ",
+        &[
+            &report_id,
+            &report_data.reporter_id,
+            &report_data.target_id,
+            &report_data.post_ids,
+            &report_data.comment,
+            &report_data.object_id,
+            &report_data.is_forwarded,
+        ],
+    ).await.map_err(catch_unique_violation("report"))?;
+    let report = row.try_get("report")?;
+    Ok(report)
+}
+
+pub async fn get_report(
+    db_client: &impl DatabaseClient,
+    report_id: Uuid,
+) -> Result<Report, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT report
+        FROM report
+        WHERE id = $1
+        ",
+        &[&report_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("report"))?;
+    let report = row.try_get("report")?;
+    Ok(report)
+}
+
+pub async fn get_reports_by_target(
+    db_client: &impl DatabaseClient,
+    target_id: Uuid,
+) -> Result<Vec<Report>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT report
+        FROM report
+        WHERE target_id = $1
+        ORDER BY id DESC
+        ",
+        &[&target_id],
+    ).await?;
+    let reports = rows.iter()
+        .map(|row| row.try_get("report"))
+        .collect::<Result<_, _>>()?;
+    Ok(reports)
+}
+
+pub async fn get_reports(
+    db_client: &impl DatabaseClient,
+    resolved: bool,
+    maybe_target_id: Option<Uuid>,
+    max_report_id: Option<Uuid>,
+    limit: u16,
+) -> Result<Vec<Report>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT report
+        FROM report
+        WHERE
+            (resolved_at IS NOT NULL) = $1
+            AND ($2::uuid IS NULL OR target_id = $2)
+            AND ($3::uuid IS NULL OR id < $3)
+        ORDER BY id DESC
+        LIMIT $4
+        ",
+        &[
+            &resolved,
+            &maybe_target_id,
+            &max_report_id,
+            &i64::from(limit),
+        ],
+    ).await?;
+    let reports = rows.iter()
+        .map(|row| row.try_get("report"))
+        .collect::<Result<_, _>>()?;
+    Ok(reports)
+}
+
+pub async fn set_report_assignee(
+    db_client: &impl DatabaseClient,
+    report_id: Uuid,
+    maybe_assignee_id: Option<Uuid>,
+) -> Result<Report, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE report
+        SET assigned_account_id = $2
+        WHERE id = $1
+        RETURNING report
+        ",
+        &[&report_id, &maybe_assignee_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("report"))?;
+    let report = row.try_get("report")?;
+    Ok(report)
+}
+
+pub async fn set_report_resolved(
+    db_client: &impl DatabaseClient,
+    report_id: Uuid,
+    is_resolved: bool,
+) -> Result<Report, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE report
+        SET resolved_at = CASE WHEN $2 THEN CURRENT_TIMESTAMP ELSE NULL END
+        WHERE id = $1
+        RETURNING report
+        ",
+        &[&report_id, &is_resolved],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("report"))?;
+    let report = row.try_get("report")?;
+    Ok(report)
+}
+
+pub async fn create_report_note(
+    db_client: &impl DatabaseClient,
+    report_id: Uuid,
+    author_id: Uuid,
+    content: &str,
+) -> Result<ReportNote, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        INSERT INTO report_note (report_id, author_id, content)
+        VALUES ($1, $2, $3)
+        RETURNING report_note
+        ",
+        &[&report_id, &author_id, &content],
+    ).await?;
+    let note = row.try_get("report_note")?;
+    Ok(note)
+}
+
+/// Returns notes for the given reports
+pub async fn get_report_notes(
+    db_client: &impl DatabaseClient,
+    report_ids: &[Uuid],
+) -> Result<Vec<ReportNote>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT report_note
+        FROM report_note
+        WHERE report_id = ANY($1)
+        ORDER BY id ASC
+        ",
+        &[&report_ids],
+    ).await?;
+    let notes = rows.iter()
+        .map(|row| row.try_get("report_note"))
+        .collect::<Result<_, _>>()?;
+    Ok(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use crate::{
+        accounts::test_utils::create_test_user,
+        database::test_utils::create_test_database,
+        posts::test_utils::create_test_local_post,
+        profiles::test_utils::create_test_remote_profile,
+    };
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_report() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let post = create_test_local_post(db_client, user.id, "test").await;
+        let reporter = create_test_remote_profile(
+            db_client,
+            "reporter",
+            "social.example",
+            "https://social.example/actor",
+        ).await;
+        let report_data = ReportCreateData {
+            reporter_id: reporter.id,
+            target_id: user.id,
+            post_ids: vec![post.id],
+            comment: "spam".to_string(),
+            object_id: Some("https://social.example/flags/1".to_string()),
+            is_forwarded: false,
+        };
+        let report = create_report(db_client, report_data).await.unwrap();
+        assert_eq!(report.reporter_id, reporter.id);
+        assert_eq!(report.target_id, user.id);
+        assert_eq!(report.post_ids, vec![post.id]);
+        assert_eq!(report.comment, "spam");
+        assert_eq!(report.is_forwarded, false);
+
+        let report = get_report(db_client, report.id).await.unwrap();
+        assert_eq!(report.target_id, user.id);
+        let reports = get_reports_by_target(db_client, user.id).await.unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].id, report.id);
+
+        // Same Flag activity
+        let report_data = ReportCreateData {
+            reporter_id: reporter.id,
+            target_id: user.id,
+            post_ids: vec![],
+            comment: "".to_string(),
+            object_id: Some("https://social.example/flags/1".to_string()),
+            is_forwarded: false,
+        };
+        let error = create_report(db_client, report_data).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::AlreadyExists("report")));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_report_moderation() {
+        let db_client = &mut create_test_database().await;
+        let admin = create_test_user(db_client, "admin").await;
+        let user = create_test_user(db_client, "test").await;
+        let reporter = create_test_remote_profile(
+            db_client,
+            "reporter",
+            "social.example",
+            "https://social.example/actor",
+        ).await;
+        let report_data = ReportCreateData {
+            reporter_id: reporter.id,
+            target_id: user.id,
+            post_ids: vec![],
+            comment: "spam".to_string(),
+            object_id: None,
+            is_forwarded: false,
+        };
+        let report = create_report(db_client, report_data).await.unwrap();
+        assert_eq!(report.assigned_account_id, None);
+        assert_eq!(report.is_resolved(), false);
+
+        let report = set_report_assignee(
+            db_client,
+            report.id,
+            Some(admin.id),
+        ).await.unwrap();
+        assert_eq!(report.assigned_account_id, Some(admin.id));
+
+        let note = create_report_note(
+            db_client,
+            report.id,
+            admin.id,
+            "checked",
+        ).await.unwrap();
+        assert_eq!(note.report_id, report.id);
+        assert_eq!(note.author_id, admin.id);
+        let notes = get_report_notes(db_client, &[report.id]).await.unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].content, "checked");
+
+        let reports = get_reports(db_client, false, None, None, 10)
+            .await.unwrap();
+        assert_eq!(reports.len(), 1);
+        let report = set_report_resolved(db_client, report.id, true)
+            .await.unwrap();
+        assert_eq!(report.is_resolved(), true);
+        let reports = get_reports(db_client, false, None, None, 10)
+            .await.unwrap();
+        assert_eq!(reports.len(), 0);
+        let reports = get_reports(db_client, true, Some(user.id), None, 10)
+            .await.unwrap();
+        assert_eq!(reports.len(), 1);
+        let reports = get_reports(db_client, true, Some(admin.id), None, 10)
+            .await.unwrap();
+        assert_eq!(reports.len(), 0);
+
+        let report = set_report_resolved(db_client, report.id, false)
+            .await.unwrap();
+        assert_eq!(report.is_resolved(), false);
+    }
+}
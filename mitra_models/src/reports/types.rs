@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use postgres_types::FromSql;
+use uuid::Uuid;
+
+#[derive(FromSql)]
+#[postgres(name = "report")]
+pub struct Report {
+    pub id: Uuid,
+    pub reporter_id: Uuid,
+    pub target_id: Uuid,
+    pub post_ids: Vec<Uuid>,
+    pub comment: String,
+    // ID of Flag activity (only for incoming reports)
+    pub object_id: Option<String>,
+    pub is_forwarded: bool,
+    pub created_at: DateTime<Utc>,
+    pub assigned_account_id: Option<Uuid>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl Report {
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_at.is_some()
+    }
+}
+
+pub struct ReportCreateData {
+    pub reporter_id: Uuid,
+    pub target_id: Uuid,
+    pub post_ids: Vec<Uuid>,
+    pub comment: String,
+    pub object_id: Option<String>,
+    pub is_forwarded: bool,
+}
+
+#[derive(FromSql)]
+#[postgres(name = "report_note")]
+pub struct ReportNote {
+    pub id: i32,
+    pub report_id: Uuid,
+    pub author_id: Uuid,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
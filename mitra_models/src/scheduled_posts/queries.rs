@@ -0,0 +1,374 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use mitra_utils::id::generate_ulid;
+
+use crate::{
+    database::{
+        catch_unique_violation,
+        DatabaseClient,
+        DatabaseError,
+    },
+    posts::types::Visibility,
+};
+
+use super::types::{ScheduledPost, ScheduledPostData};
+
+pub async fn create_scheduled_post(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+    post_data: ScheduledPostData,
+    scheduled_at: DateTime<Utc>,
+) -> Result<ScheduledPost, DatabaseError> {
+    let scheduled_post_id = generate_ulid();
+    let row = db_client.query_one(
+        "
+        INSERT INTO scheduled_post (
+            id,
+            owner_id,
+            post_data,
+            scheduled_at
+        )
+        VALUES ($1, $2, $3, $4)
+        RETURNING scheduled_post
+        ",
+        &[
+            &scheduled_post_id,
+            &owner_id,
+            &post_data,
+            &scheduled_at,
+        ],
+    ).await?;
+    let scheduled_post = row.try_get("scheduled_post")?;
+    Ok(scheduled_post)
+}
+
+pub async fn create_scheduled_repost(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+    repost_of_id: Uuid,
+    visibility: Visibility,
+    replace_existing: bool,
+    scheduled_at: DateTime<Utc>,
+) -> Result<ScheduledPost, DatabaseError> {
+    let scheduled_post_id = generate_ulid();
+    let row = db_client.query_one(
+        "
+        INSERT INTO scheduled_post (
+            id,
+            owner_id,
+            repost_of_id,
+            repost_visibility,
+            replace_existing,
+            scheduled_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING scheduled_post
+        ",
+        &[
+            &scheduled_post_id,
+            &owner_id,
+            &repost_of_id,
+            &visibility,
+            &replace_existing,
+            &scheduled_at,
+        ],
+    ).await.map_err(catch_unique_violation("scheduled repost"))?;
+    let scheduled_post = row.try_get("scheduled_post")?;
+    Ok(scheduled_post)
+}
+
+pub async fn get_scheduled_posts(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+) -> Result<Vec<ScheduledPost>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT scheduled_post
+        FROM scheduled_post
+        WHERE owner_id = $1 AND repost_of_id IS NULL
+        ORDER BY scheduled_at ASC
+        ",
+        &[&owner_id],
+    ).await?;
+    let scheduled_posts = rows.iter()
+        .map(|row| row.try_get("scheduled_post"))
+        .collect::<Result<_, _>>()?;
+    Ok(scheduled_posts)
+}
+
+pub async fn get_scheduled_reposts(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+) -> Result<Vec<ScheduledPost>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT scheduled_post
+        FROM scheduled_post
+        WHERE owner_id = $1 AND repost_of_id IS NOT NULL
+        ORDER BY scheduled_at ASC
+        ",
+        &[&owner_id],
+    ).await?;
+    let scheduled_reposts = rows.iter()
+        .map(|row| row.try_get("scheduled_post"))
+        .collect::<Result<_, _>>()?;
+    Ok(scheduled_reposts)
+}
+
+/// Returns the number of scheduled posts and reposts
+pub async fn get_scheduled_post_count(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+) -> Result<i64, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        SELECT count(scheduled_post)
+        FROM scheduled_post
+        WHERE owner_id = $1
+        ",
+        &[&owner_id],
+    ).await?;
+    let count = row.try_get("count")?;
+    Ok(count)
+}
+
+pub async fn get_scheduled_post(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+    scheduled_post_id: Uuid,
+) -> Result<ScheduledPost, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        SELECT scheduled_post
+        FROM scheduled_post
+        WHERE id = $1 AND owner_id = $2 AND repost_of_id IS NULL
+        ",
+        &[&scheduled_post_id, &owner_id],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("scheduled post"))?;
+    let scheduled_post = row.try_get("scheduled_post")?;
+    Ok(scheduled_post)
+}
+
+pub async fn set_scheduled_post_time(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+    scheduled_post_id: Uuid,
+    scheduled_at: DateTime<Utc>,
+) -> Result<ScheduledPost, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE scheduled_post
+        SET scheduled_at = $3, taken_at = NULL
+        WHERE id = $1 AND owner_id = $2 AND repost_of_id IS NULL
+        RETURNING scheduled_post
+        ",
+        &[&scheduled_post_id, &owner_id, &scheduled_at],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("scheduled post"))?;
+    let scheduled_post = row.try_get("scheduled_post")?;
+    Ok(scheduled_post)
+}
+
+/// Deletes scheduled post or repost
+pub async fn delete_scheduled_post(
+    db_client: &impl DatabaseClient,
+    owner_id: Uuid,
+    scheduled_post_id: Uuid,
+) -> Result<(), DatabaseError> {
+    let deleted_count = db_client.execute(
+        "
+        DELETE FROM scheduled_post
+        WHERE id = $1 AND owner_id = $2
+        ",
+        &[&scheduled_post_id, &owner_id],
+    ).await?;
+    if deleted_count == 0 {
+        return Err(DatabaseError::NotFound("scheduled post"));
+    };
+    Ok(())
+}
+
+/// Marks scheduled posts that are due as taken and returns them.
+/// Posts that were taken but not published before the timeout
+/// are returned again.
+pub async fn take_due_scheduled_posts(
+    db_client: &impl DatabaseClient,
+    limit: u32,
+    timeout: u32,
+) -> Result<Vec<ScheduledPost>, DatabaseError> {
+    // https://github.com/sfackler/rust-postgres/issues/60
+    let timeout_pg = format!("{}S", timeout); // interval
+    let rows = db_client.query(
+        "
+        UPDATE scheduled_post
+        SET taken_at = CURRENT_TIMESTAMP
+        WHERE id IN (
+            SELECT id
+            FROM scheduled_post
+            WHERE
+                scheduled_at <= CURRENT_TIMESTAMP
+                AND (
+                    taken_at IS NULL
+                    OR taken_at < CURRENT_TIMESTAMP - $2::text::interval
+                )
+            ORDER BY scheduled_at ASC
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING scheduled_post
+        ",
+        &[&i64::from(limit), &timeout_pg],
+    ).await?;
+    let scheduled_posts = rows.iter()
+        .map(|row| row.try_get("scheduled_post"))
+        .collect::<Result<_, _>>()?;
+    Ok(scheduled_posts)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+    use serial_test::serial;
+    use crate::{
+        accounts::test_utils::create_test_user,
+        database::test_utils::create_test_database,
+        posts::test_utils::create_test_local_post,
+    };
+    use super::*;
+
+    fn scheduled_post_data_for_test() -> ScheduledPostData {
+        ScheduledPostData {
+            in_reply_to_id: None,
+            group_id: None,
+            audience: None,
+            title: None,
+            content: "test".to_string(),
+            content_source: None,
+            language: None,
+            content_map: vec![],
+            visibility: 2, // direct
+            is_sensitive: false,
+            poll: None,
+            attachments: vec![],
+            mentions: vec![],
+            tags: vec![],
+            links: vec![],
+            emojis: vec![],
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_scheduled_post() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let scheduled_at = Utc::now() + TimeDelta::hours(1);
+        let scheduled_post = create_scheduled_post(
+            db_client,
+            user.id,
+            scheduled_post_data_for_test(),
+            scheduled_at,
+        ).await.unwrap();
+        assert_eq!(scheduled_post.owner_id, user.id);
+        assert_eq!(scheduled_post.expect_post_data().content, "test");
+
+        let scheduled_posts =
+            get_scheduled_posts(db_client, user.id).await.unwrap();
+        assert_eq!(scheduled_posts.len(), 1);
+        let scheduled_post_count =
+            get_scheduled_post_count(db_client, user.id).await.unwrap();
+        assert_eq!(scheduled_post_count, 1);
+        let due_posts =
+            take_due_scheduled_posts(db_client, 10, 3600).await.unwrap();
+        assert_eq!(due_posts.is_empty(), true);
+
+        let scheduled_at = Utc::now() - TimeDelta::minutes(1);
+        set_scheduled_post_time(
+            db_client,
+            user.id,
+            scheduled_post.id,
+            scheduled_at,
+        ).await.unwrap();
+        let due_posts =
+            take_due_scheduled_posts(db_client, 10, 3600).await.unwrap();
+        assert_eq!(due_posts.len(), 1);
+        assert_eq!(due_posts[0].id, scheduled_post.id);
+        assert_eq!(due_posts[0].taken_at.is_some(), true);
+        // Taken post is not returned again until timeout
+        let due_posts =
+            take_due_scheduled_posts(db_client, 10, 3600).await.unwrap();
+        assert_eq!(due_posts.is_empty(), true);
+        let due_posts =
+            take_due_scheduled_posts(db_client, 10, 0).await.unwrap();
+        assert_eq!(due_posts.len(), 1);
+
+        // Scheduled post is deleted after publication
+        delete_scheduled_post(db_client, user.id, scheduled_post.id)
+            .await.unwrap();
+        let error = get_scheduled_post(db_client, user.id, scheduled_post.id)
+            .await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("scheduled post")));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_delete_scheduled_post() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let scheduled_post = create_scheduled_post(
+            db_client,
+            user.id,
+            scheduled_post_data_for_test(),
+            Utc::now() + TimeDelta::hours(1),
+        ).await.unwrap();
+        delete_scheduled_post(db_client, user.id, scheduled_post.id)
+            .await.unwrap();
+        let scheduled_posts =
+            get_scheduled_posts(db_client, user.id).await.unwrap();
+        assert_eq!(scheduled_posts.is_empty(), true);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_scheduled_repost() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_user(db_client, "author").await;
+        let post = create_test_local_post(db_client, author.id, "test").await;
+        let user = create_test_user(db_client, "test").await;
+        let scheduled_at = Utc::now() + TimeDelta::hours(1);
+        let scheduled_repost = create_scheduled_repost(
+            db_client,
+            user.id,
+            post.id,
+            Visibility::Public,
+            false,
+            scheduled_at,
+        ).await.unwrap();
+        assert_eq!(scheduled_repost.owner_id, user.id);
+        assert_eq!(scheduled_repost.post_data.is_none(), true);
+        assert_eq!(scheduled_repost.repost_of_id, Some(post.id));
+        assert_eq!(scheduled_repost.repost_visibility, Some(Visibility::Public));
+
+        let error = create_scheduled_repost(
+            db_client,
+            user.id,
+            post.id,
+            Visibility::Public,
+            false,
+            scheduled_at,
+        ).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::AlreadyExists("scheduled repost")));
+
+        let scheduled_reposts =
+            get_scheduled_reposts(db_client, user.id).await.unwrap();
+        assert_eq!(scheduled_reposts.len(), 1);
+        let scheduled_posts =
+            get_scheduled_posts(db_client, user.id).await.unwrap();
+        assert_eq!(scheduled_posts.is_empty(), true);
+        let scheduled_post_count =
+            get_scheduled_post_count(db_client, user.id).await.unwrap();
+        assert_eq!(scheduled_post_count, 1);
+    }
+}
@@ -0,0 +1,180 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use postgres_types::FromSql;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use mitra_utils::languages::Language;
+
+use crate::{
+    database::{
+        json_macro::{json_from_sql, json_to_sql},
+        DatabaseTypeError,
+    },
+    polls::types::{PollData, PollResult},
+    posts::types::{ContentVariant, PostContext, PostCreateData, Visibility},
+};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ScheduledPoll {
+    pub options: Vec<String>,
+    pub multiple_choices: bool,
+    // Poll duration (in seconds)
+    pub duration: i64,
+}
+
+/// Validated post data
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ScheduledPostData {
+    pub in_reply_to_id: Option<Uuid>,
+    pub group_id: Option<Uuid>,
+    pub audience: Option<String>,
+    pub title: Option<String>,
+    pub content: String,
+    pub content_source: Option<String>,
+    // ISO 639-3 language code
+    pub language: Option<String>,
+    pub content_map: Vec<ContentVariant>,
+    pub visibility: i16,
+    pub is_sensitive: bool,
+    pub poll: Option<ScheduledPoll>,
+    pub attachments: Vec<Uuid>,
+    pub mentions: Vec<Uuid>,
+    pub tags: Vec<String>,
+    pub links: Vec<Uuid>,
+    pub emojis: Vec<Uuid>,
+}
+
+impl ScheduledPostData {
+    pub fn new(post_data: PostCreateData) -> Result<Self, DatabaseTypeError> {
+        let (in_reply_to_id, group_id, audience) = match post_data.context {
+            PostContext::Top { group_id, audience, .. } => {
+                (None, group_id, audience)
+            },
+            PostContext::Reply { in_reply_to_id, .. } => {
+                (Some(in_reply_to_id), None, None)
+            },
+            PostContext::Repost { .. } => return Err(DatabaseTypeError),
+        };
+        let maybe_poll = post_data.poll.map(|poll_data| {
+            let duration = poll_data.ends_at
+                .map(|ends_at| (ends_at - post_data.created_at).num_seconds())
+                .unwrap_or_default();
+            ScheduledPoll {
+                options: poll_data.results.into_iter()
+                    .map(|result| result.option_name)
+                    .collect(),
+                multiple_choices: poll_data.multiple_choices,
+                duration: duration,
+            }
+        });
+        let scheduled_post_data = Self {
+            in_reply_to_id,
+            group_id,
+            audience,
+            title: post_data.title,
+            content: post_data.content,
+            content_source: post_data.content_source,
+            language: post_data.language
+                .map(|language| language.to_639_3().to_string()),
+            content_map: post_data.content_map,
+            visibility: post_data.visibility.into(),
+            is_sensitive: post_data.is_sensitive,
+            poll: maybe_poll,
+            attachments: post_data.attachments,
+            mentions: post_data.mentions,
+            tags: post_data.tags,
+            links: post_data.links,
+            emojis: post_data.emojis,
+        };
+        Ok(scheduled_post_data)
+    }
+
+    pub fn visibility(&self) -> Result<Visibility, DatabaseTypeError> {
+        Visibility::try_from(self.visibility)
+    }
+
+    pub fn language(&self) -> Option<Language> {
+        self.language.as_ref()
+            .and_then(|code| Language::from_639_3(code))
+    }
+
+    /// Prepares post data for publication.
+    /// Conversation ID is required if the post is a reply.
+    pub fn into_post_data(
+        self,
+        maybe_conversation_id: Option<Uuid>,
+    ) -> Result<PostCreateData, DatabaseTypeError> {
+        let created_at = Utc::now();
+        let context = match self.in_reply_to_id {
+            Some(in_reply_to_id) => PostContext::Reply {
+                conversation_id: maybe_conversation_id
+                    .ok_or(DatabaseTypeError)?,
+                in_reply_to_id: in_reply_to_id,
+            },
+            None => PostContext::Top {
+                group_id: self.group_id,
+                object_id: None,
+                audience: self.audience.clone(),
+            },
+        };
+        let visibility = self.visibility()?;
+        let language = self.language();
+        let maybe_poll_data = self.poll.map(|poll| PollData {
+            multiple_choices: poll.multiple_choices,
+            ends_at: Some(created_at + TimeDelta::seconds(poll.duration)),
+            results: poll.options.iter()
+                .map(|name| PollResult::new(name))
+                .collect(),
+            voters_count: Some(0),
+        });
+        let post_data = PostCreateData {
+            id: None,
+            context: context,
+            title: self.title,
+            content: self.content,
+            content_source: self.content_source,
+            language: language,
+            content_map: self.content_map,
+            visibility: visibility,
+            is_sensitive: self.is_sensitive,
+            poll: maybe_poll_data,
+            attachments: self.attachments,
+            mentions: self.mentions,
+            tags: self.tags,
+            links: self.links,
+            emojis: self.emojis,
+            url: None,
+            object_id: None,
+            created_at: created_at,
+        };
+        Ok(post_data)
+    }
+}
+
+json_from_sql!(ScheduledPostData);
+json_to_sql!(ScheduledPostData);
+
+/// Scheduled post or repost
+#[derive(Clone, FromSql)]
+#[postgres(name = "scheduled_post")]
+pub struct ScheduledPost {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    // Not present if this is a scheduled repost
+    pub post_data: Option<ScheduledPostData>,
+    pub scheduled_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    // Set when publication starts
+    pub taken_at: Option<DateTime<Utc>>,
+    pub repost_of_id: Option<Uuid>,
+    pub repost_visibility: Option<Visibility>,
+    // Undo existing repost and announce the post again
+    pub replace_existing: bool,
+}
+
+impl ScheduledPost {
+    pub fn expect_post_data(&self) -> &ScheduledPostData {
+        self.post_data.as_ref()
+            .expect("post data should be present")
+    }
+}
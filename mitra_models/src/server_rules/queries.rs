@@ -0,0 +1,117 @@
+use crate::database::{DatabaseClient, DatabaseError};
+
+use super::types::ServerRule;
+
+pub async fn create_server_rule(
+    db_client: &impl DatabaseClient,
+    rule_text: &str,
+    rule_hint: &str,
+) -> Result<ServerRule, DatabaseError> {
+    let row = db_client.query_one(
+        "
+        INSERT INTO server_rule (rule_text, rule_hint)
+        VALUES ($1, $2)
+        RETURNING server_rule
+        ",
+        &[&rule_text, &rule_hint],
+    ).await?;
+    let rule = row.try_get("server_rule")?;
+    Ok(rule)
+}
+
+pub async fn update_server_rule(
+    db_client: &impl DatabaseClient,
+    rule_id: i32,
+    rule_text: &str,
+    rule_hint: &str,
+) -> Result<ServerRule, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "
+        UPDATE server_rule
+        SET rule_text = $2, rule_hint = $3
+        WHERE id = $1
+        RETURNING server_rule
+        ",
+        &[&rule_id, &rule_text, &rule_hint],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("server rule"))?;
+    let rule = row.try_get("server_rule")?;
+    Ok(rule)
+}
+
+pub async fn delete_server_rule(
+    db_client: &impl DatabaseClient,
+    rule_id: i32,
+) -> Result<(), DatabaseError> {
+    let deleted_count = db_client.execute(
+        "DELETE FROM server_rule WHERE id = $1",
+        &[&rule_id],
+    ).await?;
+    if deleted_count == 0 {
+        return Err(DatabaseError::NotFound("server rule"));
+    };
+    Ok(())
+}
+
+pub async fn get_server_rules(
+    db_client: &impl DatabaseClient,
+) -> Result<Vec<ServerRule>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT server_rule
+        FROM server_rule
+        ORDER BY id ASC
+        ",
+        &[],
+    ).await?;
+    let rules = rows.iter()
+        .map(|row| row.try_get("server_rule"))
+        .collect::<Result<_, _>>()?;
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use crate::database::test_utils::create_test_database;
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_update_delete_server_rule() {
+        let db_client = &create_test_database().await;
+        let rule = create_server_rule(
+            db_client,
+            "No spam",
+            "",
+        ).await.unwrap();
+        assert_eq!(rule.rule_text, "No spam");
+        assert_eq!(rule.rule_hint, "");
+        let rule_2 = create_server_rule(
+            db_client,
+            "Be nice",
+            "",
+        ).await.unwrap();
+
+        let updated_rule = update_server_rule(
+            db_client,
+            rule.id,
+            "No spam or advertising",
+            "Including self-promotion",
+        ).await.unwrap();
+        assert_eq!(updated_rule.id, rule.id);
+        assert_eq!(updated_rule.rule_text, "No spam or advertising");
+        assert_eq!(updated_rule.rule_hint, "Including self-promotion");
+
+        let rules = get_server_rules(db_client).await.unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].id, rule.id);
+        assert_eq!(rules[1].id, rule_2.id);
+
+        delete_server_rule(db_client, rule.id).await.unwrap();
+        let rules = get_server_rules(db_client).await.unwrap();
+        assert_eq!(rules.len(), 1);
+        let error = delete_server_rule(db_client, rule.id).await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("server rule")));
+    }
+}
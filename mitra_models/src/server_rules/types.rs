@@ -0,0 +1,11 @@
+use chrono::{DateTime, Utc};
+use postgres_types::FromSql;
+
+#[derive(Clone, FromSql)]
+#[postgres(name = "server_rule")]
+pub struct ServerRule {
+    pub id: i32,
+    pub rule_text: String,
+    pub rule_hint: String,
+    pub created_at: DateTime<Utc>,
+}
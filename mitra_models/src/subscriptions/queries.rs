@@ -61,7 +61,8 @@ pub async fn update_subscription(
         UPDATE subscription
         SET
             expires_at = $2,
-            updated_at = $3
+            updated_at = $3,
+            reminder_sent_at = NULL
         WHERE id = $1
         RETURNING subscription
         ",
@@ -102,8 +103,11 @@ pub async fn get_subscription_by_participants(
     Ok(subscription)
 }
 
+/// Returns subscriptions that expired before the given time
+/// and haven't been cancelled yet
 pub async fn get_expired_subscriptions(
     db_client: &impl DatabaseClient,
+    expired_before: DateTime<Utc>,
 ) -> Result<Vec<Subscription>, DatabaseError> {
     let rows = db_client.query(
         "
@@ -115,9 +119,9 @@ pub async fn get_expired_subscriptions(
             AND relationship.target_id = subscription.recipient_id
             AND relationship.relationship_type = $1
         )
-        WHERE subscription.expires_at <= CURRENT_TIMESTAMP
+        WHERE subscription.expires_at <= $2
         ",
-        &[&RelationshipType::Subscription],
+        &[&RelationshipType::Subscription, &expired_before],
     ).await?;
     let subscriptions = rows.iter()
         .map(|row| row.try_get("subscription"))
@@ -125,6 +129,53 @@ pub async fn get_expired_subscriptions(
     Ok(subscriptions)
 }
 
+/// Returns active subscriptions that will expire before the given time
+/// and for which reminder hasn't been sent yet
+pub async fn get_expiring_subscriptions(
+    db_client: &impl DatabaseClient,
+    expires_before: DateTime<Utc>,
+) -> Result<Vec<Subscription>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT subscription
+        FROM subscription
+        JOIN relationship
+        ON (
+            relationship.source_id = subscription.sender_id
+            AND relationship.target_id = subscription.recipient_id
+            AND relationship.relationship_type = $1
+        )
+        WHERE
+            subscription.expires_at > CURRENT_TIMESTAMP
+            AND subscription.expires_at <= $2
+            AND subscription.reminder_sent_at IS NULL
+        ",
+        &[&RelationshipType::Subscription, &expires_before],
+    ).await?;
+    let subscriptions = rows.iter()
+        .map(|row| row.try_get("subscription"))
+        .collect::<Result<_, _>>()?;
+    Ok(subscriptions)
+}
+
+pub async fn set_subscription_reminder_sent(
+    db_client: &impl DatabaseClient,
+    subscription_id: i32,
+) -> Result<(), DatabaseError> {
+    let updated_count = db_client.execute(
+        "
+        UPDATE subscription
+        SET reminder_sent_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        ",
+        &[&subscription_id],
+    ).await?;
+    if updated_count == 0 {
+        return Err(DatabaseError::NotFound("subscription"));
+    };
+    Ok(())
+}
+
 pub async fn get_incoming_subscriptions(
     db_client: &impl DatabaseClient,
     recipient_id: Uuid,
@@ -200,6 +251,7 @@ pub async fn get_expired_subscription_count(
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeDelta;
     use serial_test::serial;
     use crate::{
         accounts::test_utils::create_test_user,
@@ -263,4 +315,45 @@ mod tests {
         ).await.unwrap();
         assert_eq!(results.is_empty(), true);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_expiring_subscriptions() {
+        let db_client = &mut create_test_database().await;
+        let sender = create_test_user(db_client, "sender").await;
+        let recipient = create_test_user(db_client, "recipient").await;
+        let expires_at = Utc::now() + TimeDelta::days(2);
+        let subscription = create_subscription(
+            db_client,
+            sender.id,
+            recipient.id,
+            expires_at,
+            Utc::now(),
+        ).await.unwrap();
+
+        let results = get_expiring_subscriptions(
+            db_client,
+            Utc::now() + TimeDelta::days(1),
+        ).await.unwrap();
+        assert_eq!(results.is_empty(), true);
+        let results = get_expiring_subscriptions(
+            db_client,
+            Utc::now() + TimeDelta::days(3),
+        ).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, subscription.id);
+
+        set_subscription_reminder_sent(db_client, subscription.id)
+            .await.unwrap();
+        let results = get_expiring_subscriptions(
+            db_client,
+            Utc::now() + TimeDelta::days(3),
+        ).await.unwrap();
+        assert_eq!(results.is_empty(), true);
+        let results = get_expired_subscriptions(
+            db_client,
+            Utc::now(),
+        ).await.unwrap();
+        assert_eq!(results.is_empty(), true);
+    }
 }
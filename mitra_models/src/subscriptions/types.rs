@@ -14,6 +14,7 @@ pub struct Subscription {
     pub recipient_id: Uuid,
     pub expires_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub reminder_sent_at: Option<DateTime<Utc>>,
 }
 
 pub struct SubscriptionDetailed {
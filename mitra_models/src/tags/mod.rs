@@ -1 +1,2 @@
 pub mod queries;
+pub mod types;
@@ -1,5 +1,104 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
 use crate::database::{DatabaseClient, DatabaseError};
 
+use super::types::TagUsage;
+
+/// Records usage of tags in a public post (used for trends).
+/// Publication dates of remote posts can't be trusted,
+/// so dates in the future are replaced with the current time.
+pub(crate) async fn record_tag_usage(
+    db_client: &impl DatabaseClient,
+    post_id: Uuid,
+) -> Result<(), DatabaseError> {
+    db_client.execute(
+        "
+        INSERT INTO tag_usage (tag_id, post_id, author_id, created_at)
+        SELECT
+            post_tag.tag_id,
+            post.id,
+            post.author_id,
+            least(post.created_at, CURRENT_TIMESTAMP)
+        FROM post_tag
+        JOIN post ON post_tag.post_id = post.id
+        WHERE post.id = $1
+        ON CONFLICT DO NOTHING
+        ",
+        &[&post_id],
+    ).await?;
+    Ok(())
+}
+
+/// Returns tags ranked by number of accounts using them
+/// since the specified time, together with their review status.
+/// Tags hidden by administrator are included only if `with_hidden` is set.
+pub async fn get_trending_tags(
+    db_client: &impl DatabaseClient,
+    created_after: DateTime<Utc>,
+    with_hidden: bool,
+    limit: u16,
+    offset: u16,
+) -> Result<Vec<(String, Option<bool>)>, DatabaseError> {
+    let rows = db_client.query(
+        "
+        SELECT tag.tag_name, tag.trendable
+        FROM tag_usage
+        JOIN tag ON tag_usage.tag_id = tag.id
+        WHERE
+            tag_usage.created_at > $1
+            AND ($2 OR tag.trendable IS NOT FALSE)
+        GROUP BY tag.id
+        ORDER BY
+            count(DISTINCT tag_usage.author_id) DESC,
+            count(tag_usage.post_id) DESC,
+            tag.tag_name ASC
+        LIMIT $3 OFFSET $4
+        ",
+        &[
+            &created_after,
+            &with_hidden,
+            &i64::from(limit),
+            &i64::from(offset),
+        ],
+    ).await?;
+    let tags = rows.iter()
+        .map(|row| {
+            let tag_name = row.try_get("tag_name")?;
+            let trendable = row.try_get("trendable")?;
+            Ok((tag_name, trendable))
+        })
+        .collect::<Result<_, DatabaseError>>()?;
+    Ok(tags)
+}
+
+/// Approves tag for trends (`true`) or hides it (`false`)
+pub async fn set_tag_trendable(
+    db_client: &impl DatabaseClient,
+    tag_name: &str,
+    trendable: bool,
+) -> Result<(), DatabaseError> {
+    let updated_count = db_client.execute(
+        "UPDATE tag SET trendable = $2 WHERE tag_name = $1",
+        &[&tag_name.to_lowercase(), &trendable],
+    ).await?;
+    if updated_count == 0 {
+        return Err(DatabaseError::NotFound("tag"));
+    };
+    Ok(())
+}
+
+pub async fn delete_tag_usage(
+    db_client: &impl DatabaseClient,
+    created_before: DateTime<Utc>,
+) -> Result<u64, DatabaseError> {
+    let deleted_count = db_client.execute(
+        "DELETE FROM tag_usage WHERE created_at < $1",
+        &[&created_before],
+    ).await?;
+    Ok(deleted_count)
+}
+
 pub async fn delete_tag(
     db_client: &impl DatabaseClient,
     tag_name: &str,
@@ -21,14 +120,28 @@ pub async fn search_tags(
     offset: u16,
 ) -> Result<Vec<String>, DatabaseError> {
     let db_search_query = format!("%{}%", search_query);
+    // Exact match goes first, then tags are ranked by recent usage
     let rows = db_client.query(
         "
         SELECT tag_name
         FROM tag
         WHERE tag_name ILIKE $1
-        LIMIT $2 OFFSET $3
+        ORDER BY
+            tag_name = $2 DESC,
+            (
+                SELECT count(*)
+                FROM tag_usage
+                WHERE tag_usage.tag_id = tag.id
+            ) DESC,
+            tag_name ASC
+        LIMIT $3 OFFSET $4
         ",
-        &[&db_search_query, &i64::from(limit), &i64::from(offset),],
+        &[
+            &db_search_query,
+            &search_query.to_lowercase(),
+            &i64::from(limit),
+            &i64::from(offset),
+        ],
     ).await?;
     let tags: Vec<String> = rows.iter()
         .map(|row| row.try_get("tag_name"))
@@ -36,6 +149,47 @@ pub async fn search_tags(
     Ok(tags)
 }
 
+/// Returns daily usage statistics for the last `days` days
+/// (most recent day first).
+/// Only public posts are counted.
+pub async fn get_tag_usage_history(
+    db_client: &impl DatabaseClient,
+    tag_name: &str,
+    days: u16,
+) -> Result<Vec<TagUsage>, DatabaseError> {
+    let maybe_row = db_client.query_opt(
+        "SELECT id FROM tag WHERE tag_name = $1",
+        &[&tag_name.to_lowercase()],
+    ).await?;
+    let row = maybe_row.ok_or(DatabaseError::NotFound("tag"))?;
+    let tag_id: i32 = row.try_get("id")?;
+    let rows = db_client.query(
+        "
+        SELECT
+            day,
+            count(tag_usage.post_id) AS uses,
+            count(DISTINCT tag_usage.author_id) AS accounts
+        FROM generate_series(
+            date_trunc('day', CURRENT_TIMESTAMP) - ($2 - 1) * interval '1 day',
+            date_trunc('day', CURRENT_TIMESTAMP),
+            interval '1 day'
+        ) AS day
+        LEFT JOIN tag_usage
+        ON
+            tag_usage.tag_id = $1
+            AND tag_usage.created_at >= day
+            AND tag_usage.created_at < day + interval '1 day'
+        GROUP BY day
+        ORDER BY day DESC
+        ",
+        &[&tag_id, &i32::from(days)],
+    ).await?;
+    let history = rows.iter()
+        .map(TagUsage::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(history)
+}
+
 pub async fn find_unused_tags(
     db_client: &impl DatabaseClient,
 ) -> Result<Vec<String>, DatabaseError> {
@@ -60,8 +214,16 @@ pub async fn find_unused_tags(
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeDelta;
     use serial_test::serial;
-    use crate::database::test_utils::create_test_database;
+    use crate::{
+        accounts::test_utils::create_test_user,
+        database::test_utils::create_test_database,
+        posts::{
+            queries::create_post,
+            types::{PostCreateData, Visibility},
+        },
+    };
     use super::*;
 
     #[tokio::test]
@@ -71,4 +233,119 @@ mod tests {
         let results = search_tags(db_client, "test", 1, 0).await.unwrap();
         assert_eq!(results.is_empty(), true);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_search_tags_ranking() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_user(db_client, "test").await;
+        for tags in [vec!["tests"], vec!["tests"], vec!["test", "tests"]] {
+            let post_data = PostCreateData {
+                content: "test".to_string(),
+                tags: tags.into_iter().map(|tag| tag.to_string()).collect(),
+                ..PostCreateData::for_test()
+            };
+            create_post(db_client, author.id, post_data).await.unwrap();
+        };
+        let results = search_tags(db_client, "test", 5, 0).await.unwrap();
+        assert_eq!(results, vec!["test", "tests"]);
+        let results = search_tags(db_client, "tes", 5, 0).await.unwrap();
+        assert_eq!(results, vec!["tests", "test"]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_tag_usage_history() {
+        let db_client = &mut create_test_database().await;
+        let error = get_tag_usage_history(db_client, "test", 7)
+            .await.err().unwrap();
+        assert!(matches!(error, DatabaseError::NotFound("tag")));
+
+        let author = create_test_user(db_client, "test").await;
+        let post_data = PostCreateData {
+            content: "test".to_string(),
+            tags: vec!["test".to_string()],
+            ..PostCreateData::for_test()
+        };
+        create_post(db_client, author.id, post_data).await.unwrap();
+        let history = get_tag_usage_history(db_client, "Test", 7)
+            .await.unwrap();
+        assert_eq!(history.len(), 7);
+        assert_eq!(history[0].uses, 1);
+        assert_eq!(history[0].accounts, 1);
+        assert_eq!(history[1].uses, 0);
+
+        // Non-public posts are not counted
+        let post_data = PostCreateData {
+            content: "test".to_string(),
+            tags: vec!["test".to_string()],
+            visibility: Visibility::Followers,
+            ..PostCreateData::for_test()
+        };
+        create_post(db_client, author.id, post_data).await.unwrap();
+        let history = get_tag_usage_history(db_client, "test", 7)
+            .await.unwrap();
+        assert_eq!(history[0].uses, 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_trending_tags() {
+        let db_client = &mut create_test_database().await;
+        let author_1 = create_test_user(db_client, "test1").await;
+        let author_2 = create_test_user(db_client, "test2").await;
+        for (author, tags) in [
+            (&author_1, vec!["tag1", "tag2"]),
+            (&author_1, vec!["tag1"]),
+            (&author_2, vec!["tag2"]),
+        ] {
+            let post_data = PostCreateData {
+                content: "test".to_string(),
+                tags: tags.into_iter().map(|tag| tag.to_string()).collect(),
+                ..PostCreateData::for_test()
+            };
+            create_post(db_client, author.id, post_data).await.unwrap();
+        };
+        let created_after = Utc::now() - TimeDelta::days(1);
+        let tags = get_trending_tags(db_client, created_after, false, 10, 0)
+            .await.unwrap();
+        assert_eq!(tags, vec![
+            ("tag2".to_string(), None),
+            ("tag1".to_string(), None),
+        ]);
+
+        set_tag_trendable(db_client, "tag2", false).await.unwrap();
+        let tags = get_trending_tags(db_client, created_after, false, 10, 0)
+            .await.unwrap();
+        assert_eq!(tags, vec![("tag1".to_string(), None)]);
+        let tags = get_trending_tags(db_client, created_after, true, 10, 0)
+            .await.unwrap();
+        assert_eq!(tags.len(), 2);
+
+        let deleted_count = delete_tag_usage(db_client, Utc::now())
+            .await.unwrap();
+        assert_eq!(deleted_count, 4);
+        let tags = get_trending_tags(db_client, created_after, true, 10, 0)
+            .await.unwrap();
+        assert_eq!(tags.is_empty(), true);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_record_tag_usage_future_date() {
+        let db_client = &mut create_test_database().await;
+        let author = create_test_user(db_client, "test").await;
+        let post_data = PostCreateData {
+            content: "test".to_string(),
+            tags: vec!["test".to_string()],
+            created_at: Utc::now() + TimeDelta::days(30),
+            ..PostCreateData::for_test()
+        };
+        create_post(db_client, author.id, post_data).await.unwrap();
+        // Usage is recorded with the current time
+        let deleted_count =
+            delete_tag_usage(db_client, Utc::now() + TimeDelta::hours(1))
+                .await.unwrap();
+        assert_eq!(deleted_count, 1);
+    }
 }
@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Error as PgError, Row};
+
+/// Daily usage statistics
+pub struct TagUsage {
+    pub day: DateTime<Utc>,
+    pub uses: i64,
+    pub accounts: i64,
+}
+
+impl TryFrom<&Row> for TagUsage {
+    type Error = PgError;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let usage = Self {
+            day: row.try_get("day")?,
+            uses: row.try_get("uses")?,
+            accounts: row.try_get("accounts")?,
+        };
+        Ok(usage)
+    }
+}
@@ -0,0 +1,378 @@
+//! RSS, Atom and JSON Feed parsers
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use thiserror::Error;
+
+pub const FEED_MEDIA_TYPES: [&str; 5] = [
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/feed+json",
+    "application/xml",
+    "text/xml",
+];
+
+#[derive(Debug, Error)]
+pub enum FeedError {
+    #[error("unsupported feed format")]
+    UnsupportedFormat,
+
+    #[error(transparent)]
+    XmlError(#[from] quick_xml::DeError),
+
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+}
+
+pub struct FeedEntry {
+    pub id: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    // HTML
+    pub content: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+pub struct Feed {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    // Newest entries first (if dates are known)
+    pub entries: Vec<FeedEntry>,
+}
+
+fn parse_rfc3339(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.trim()).ok()
+        .map(|datetime| datetime.with_timezone(&Utc))
+}
+
+fn parse_rfc2822(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value.trim()).ok()
+        .map(|datetime| datetime.with_timezone(&Utc))
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
+
+// RSS 2.0
+// https://www.rssboard.org/rss-specification
+
+#[derive(Deserialize)]
+struct RssItem {
+    title: Option<String>,
+    link: Option<String>,
+    guid: Option<String>,
+    description: Option<String>,
+    #[serde(rename = "content:encoded")]
+    content_encoded: Option<String>,
+    #[serde(rename = "pubDate")]
+    pub_date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RssChannel {
+    title: Option<String>,
+    description: Option<String>,
+    #[serde(rename = "item", default)]
+    items: Vec<RssItem>,
+}
+
+#[derive(Deserialize)]
+struct Rss {
+    channel: RssChannel,
+}
+
+impl From<Rss> for Feed {
+    fn from(rss: Rss) -> Self {
+        let entries = rss.channel.items.into_iter()
+            .filter_map(|item| {
+                let url = non_empty(item.link);
+                let title = non_empty(item.title);
+                let id = non_empty(item.guid)
+                    .or(url.clone())
+                    .or(title.clone())?;
+                Some(FeedEntry {
+                    id,
+                    title,
+                    url,
+                    content: non_empty(item.content_encoded)
+                        .or(non_empty(item.description)),
+                    published_at: item.pub_date.as_deref()
+                        .and_then(parse_rfc2822),
+                })
+            })
+            .collect();
+        Self {
+            title: non_empty(rss.channel.title),
+            description: non_empty(rss.channel.description),
+            entries,
+        }
+    }
+}
+
+// Atom
+// https://www.rfc-editor.org/rfc/rfc4287
+
+#[derive(Deserialize)]
+struct AtomLink {
+    #[serde(rename = "@href")]
+    href: String,
+    #[serde(rename = "@rel")]
+    rel: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AtomText {
+    #[serde(rename = "@type")]
+    text_type: Option<String>,
+    #[serde(rename = "$text", default)]
+    value: String,
+}
+
+impl AtomText {
+    fn to_html(&self) -> String {
+        match self.text_type.as_deref() {
+            Some("html") | Some("xhtml") => self.value.clone(),
+            _ => mitra_utils::html::escape_html(&self.value),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AtomEntry {
+    id: Option<String>,
+    title: Option<AtomText>,
+    #[serde(rename = "link", default)]
+    links: Vec<AtomLink>,
+    summary: Option<AtomText>,
+    content: Option<AtomText>,
+    published: Option<String>,
+    updated: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Atom {
+    title: Option<AtomText>,
+    subtitle: Option<AtomText>,
+    #[serde(rename = "entry", default)]
+    entries: Vec<AtomEntry>,
+}
+
+impl From<Atom> for Feed {
+    fn from(atom: Atom) -> Self {
+        let entries = atom.entries.into_iter()
+            .filter_map(|entry| {
+                let url = entry.links.iter()
+                    .find(|link| {
+                        matches!(link.rel.as_deref(), None | Some("alternate"))
+                    })
+                    .map(|link| link.href.clone());
+                let title = non_empty(entry.title.map(|title| title.value));
+                let id = non_empty(entry.id)
+                    .or(url.clone())
+                    .or(title.clone())?;
+                let content = entry.content.or(entry.summary)
+                    .map(|text| text.to_html());
+                Some(FeedEntry {
+                    id,
+                    title,
+                    url,
+                    content: non_empty(content),
+                    published_at: entry.published.or(entry.updated)
+                        .as_deref()
+                        .and_then(parse_rfc3339),
+                })
+            })
+            .collect();
+        Self {
+            title: non_empty(atom.title.map(|title| title.value)),
+            description: non_empty(atom.subtitle.map(|text| text.value)),
+            entries,
+        }
+    }
+}
+
+// JSON Feed
+// https://www.jsonfeed.org/version/1.1/
+
+#[derive(Deserialize)]
+struct JsonFeedItem {
+    id: Option<String>,
+    url: Option<String>,
+    title: Option<String>,
+    content_html: Option<String>,
+    content_text: Option<String>,
+    summary: Option<String>,
+    date_published: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JsonFeed {
+    title: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    items: Vec<JsonFeedItem>,
+}
+
+impl From<JsonFeed> for Feed {
+    fn from(json_feed: JsonFeed) -> Self {
+        let entries = json_feed.items.into_iter()
+            .filter_map(|item| {
+                let url = non_empty(item.url);
+                let title = non_empty(item.title);
+                let id = non_empty(item.id)
+                    .or(url.clone())
+                    .or(title.clone())?;
+                let content = non_empty(item.content_html)
+                    .or(non_empty(item.content_text)
+                        .map(|text| mitra_utils::html::escape_html(&text)))
+                    .or(non_empty(item.summary)
+                        .map(|text| mitra_utils::html::escape_html(&text)));
+                Some(FeedEntry {
+                    id,
+                    title,
+                    url,
+                    content,
+                    published_at: item.date_published.as_deref()
+                        .and_then(parse_rfc3339),
+                })
+            })
+            .collect();
+        Self {
+            title: non_empty(json_feed.title),
+            description: non_empty(json_feed.description),
+            entries,
+        }
+    }
+}
+
+/// Returns the name of the root element of XML document
+fn get_root_element_name(text: &str) -> Option<&str> {
+    let mut rest = text;
+    loop {
+        let start = rest.find('<')?;
+        rest = &rest[start + 1..];
+        // Skip XML declaration, comments and doctype
+        if rest.starts_with('?') || rest.starts_with('!') {
+            continue;
+        };
+        let end = rest.find(|char_: char| {
+            char_.is_whitespace() || char_ == '>' || char_ == '/'
+        })?;
+        return Some(&rest[..end]);
+    };
+}
+
+pub fn parse_feed(data: &[u8]) -> Result<Feed, FeedError> {
+    let text = String::from_utf8_lossy(data);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with('{') {
+        let json_feed: JsonFeed = serde_json::from_str(text)?;
+        return Ok(json_feed.into());
+    };
+    let mut feed: Feed = match get_root_element_name(text) {
+        Some("rss") => quick_xml::de::from_str::<Rss>(text)?.into(),
+        Some("feed") => quick_xml::de::from_str::<Atom>(text)?.into(),
+        _ => return Err(FeedError::UnsupportedFormat),
+    };
+    // Newest first, entries without dates at the end
+    feed.entries.sort_by(|entry_1, entry_2| {
+        entry_2.published_at.cmp(&entry_1.published_at)
+    });
+    Ok(feed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_feed_rss() {
+        let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+              <channel>
+                <title>Test blog</title>
+                <link>https://blog.example/</link>
+                <description>Test description</description>
+                <item>
+                  <title>First post</title>
+                  <link>https://blog.example/1</link>
+                  <guid>https://blog.example/1</guid>
+                  <description>Summary</description>
+                  <pubDate>Mon, 01 Jan 2024 10:00:00 +0000</pubDate>
+                </item>
+                <item>
+                  <title>Second post</title>
+                  <link>https://blog.example/2</link>
+                  <description>&lt;p&gt;Hello&lt;/p&gt;</description>
+                  <pubDate>Tue, 02 Jan 2024 10:00:00 +0000</pubDate>
+                </item>
+              </channel>
+            </rss>"#;
+        let feed = parse_feed(data.as_bytes()).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Test blog"));
+        assert_eq!(feed.entries.len(), 2);
+        let entry = &feed.entries[0];
+        assert_eq!(entry.id, "https://blog.example/2");
+        assert_eq!(entry.title.as_deref(), Some("Second post"));
+        assert_eq!(entry.content.as_deref(), Some("<p>Hello</p>"));
+        assert!(entry.published_at.is_some());
+    }
+
+    #[test]
+    fn test_parse_feed_atom() {
+        let data = r#"<?xml version="1.0" encoding="utf-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+              <title>Test feed</title>
+              <subtitle>Test description</subtitle>
+              <link href="https://blog.example/"/>
+              <entry>
+                <id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a</id>
+                <title>Atom post</title>
+                <link rel="alternate" href="https://blog.example/atom-post"/>
+                <updated>2024-01-03T18:30:02Z</updated>
+                <content type="html">&lt;p&gt;Text&lt;/p&gt;</content>
+              </entry>
+            </feed>"#;
+        let feed = parse_feed(data.as_bytes()).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Test feed"));
+        assert_eq!(feed.description.as_deref(), Some("Test description"));
+        assert_eq!(feed.entries.len(), 1);
+        let entry = &feed.entries[0];
+        assert_eq!(entry.id, "urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a");
+        assert_eq!(entry.url.as_deref(), Some("https://blog.example/atom-post"));
+        assert_eq!(entry.content.as_deref(), Some("<p>Text</p>"));
+        assert!(entry.published_at.is_some());
+    }
+
+    #[test]
+    fn test_parse_feed_json() {
+        let data = r#"{
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "JSON feed",
+            "items": [
+                {
+                    "id": "1",
+                    "url": "https://blog.example/1",
+                    "content_text": "Plain <text>",
+                    "date_published": "2024-01-01T10:00:00Z"
+                }
+            ]
+        }"#;
+        let feed = parse_feed(data.as_bytes()).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("JSON feed"));
+        assert_eq!(feed.entries.len(), 1);
+        let entry = &feed.entries[0];
+        assert_eq!(entry.id, "1");
+        assert_eq!(entry.title, None);
+        assert_eq!(entry.content.as_deref(), Some("Plain&#32;&lt;text&gt;"));
+    }
+
+    #[test]
+    fn test_parse_feed_unsupported() {
+        let data = "<html><body></body></html>";
+        let error = parse_feed(data.as_bytes()).err().unwrap();
+        assert!(matches!(error, FeedError::UnsupportedFormat));
+    }
+}
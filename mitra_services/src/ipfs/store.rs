@@ -1,4 +1,6 @@
 // https://docs.ipfs.io/reference/http/api/
+use std::collections::HashMap;
+
 use reqwest::{multipart, Client};
 use serde::Deserialize;
 
@@ -24,6 +26,25 @@ pub async fn add(ipfs_api_url: &str, data: Vec<u8>) -> Result<String, reqwest::E
     Ok(info.hash)
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all="PascalCase")]
+struct PinList {
+    keys: HashMap<String, serde_json::Value>,
+}
+
+/// Returns CIDs of objects pinned on local IPFS node.
+pub async fn list_pins(ipfs_api_url: &str) -> Result<Vec<String>, reqwest::Error> {
+    let client = Client::new();
+    let url = format!("{}/api/v0/pin/ls", ipfs_api_url);
+    let response = client.post(&url)
+        .query(&[("type", "recursive")])
+        .send().await?;
+    response.error_for_status_ref()?;
+    let pin_list: PinList = response.json().await?;
+    let cids = pin_list.keys.into_keys().collect();
+    Ok(cids)
+}
+
 /// Unpins and removes files from local IPFS node.
 pub async fn remove(
     ipfs_api_url: &str,
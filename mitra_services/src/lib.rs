@@ -1,4 +1,7 @@
 pub mod ethereum;
+pub mod feeds;
 pub mod ipfs;
 pub mod media;
+pub mod media_metadata;
 pub mod monero;
+pub mod push;
@@ -3,9 +3,12 @@ use std::io::{Error as IoError};
 use std::path::{Path, PathBuf};
 
 use apx_core::crypto::hashes::sha256;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
 use thiserror::Error;
 
-use mitra_config::Config;
+use mitra_config::{Config, MediaAccessMode};
 use mitra_utils::{
     files::{
         get_media_type_extension,
@@ -13,6 +16,7 @@ use mitra_utils::{
         FileInfo,
         FileSize,
     },
+    random::generate_random_sequence,
     sysinfo::get_available_disk_space,
 };
 
@@ -194,16 +198,77 @@ impl MediaStorage {
     }
 }
 
+pub type MediaUrlKey = [u8; 32];
+
+pub fn generate_media_url_key() -> MediaUrlKey {
+    generate_random_sequence()
+}
+
+#[derive(Clone)]
+pub struct MediaUrlSigner {
+    secret_key: MediaUrlKey,
+    lifetime: u32,
+}
+
+impl MediaUrlSigner {
+    pub fn new(secret_key: MediaUrlKey, lifetime: u32) -> Self {
+        Self { secret_key, lifetime }
+    }
+
+    fn create_mac(&self, file_name: &str, expires: i64) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret_key)
+            .expect("HMAC should accept key of any size");
+        mac.update(format!("{file_name}:{expires}").as_bytes());
+        mac
+    }
+
+    /// Returns expiration timestamp and hex-encoded signature
+    pub fn sign(&self, file_name: &str) -> (i64, String) {
+        // Expiration time is rounded up to the lifetime boundary,
+        // so URLs don't change on every request and can be cached
+        let lifetime = i64::from(self.lifetime.max(1));
+        let expires = (Utc::now().timestamp() / lifetime + 2) * lifetime;
+        let signature = self.create_mac(file_name, expires)
+            .finalize()
+            .into_bytes();
+        (expires, hex::encode(signature))
+    }
+
+    pub fn verify(
+        &self,
+        file_name: &str,
+        expires: i64,
+        signature: &[u8],
+    ) -> bool {
+        if expires < Utc::now().timestamp() {
+            return false;
+        };
+        // Constant-time comparison
+        self.create_mac(file_name, expires)
+            .verify_slice(signature)
+            .is_ok()
+    }
+}
+
 #[derive(Clone)]
 pub struct FilesystemServer {
     base_url: String,
+    url_signer: Option<MediaUrlSigner>,
 }
 
 impl FilesystemServer {
     pub const BASE_PATH: &str = "/media";
 
     pub fn new(base_url: &str) -> Self {
-        Self { base_url: base_url.to_string() }
+        Self {
+            base_url: base_url.to_string(),
+            url_signer: None,
+        }
+    }
+
+    pub fn with_url_signer(mut self, url_signer: MediaUrlSigner) -> Self {
+        self.url_signer = Some(url_signer);
+        self
     }
 
     pub fn override_base_url(&mut self, base_url: &str) -> () {
@@ -215,6 +280,18 @@ impl FilesystemServer {
     pub fn url_for(&self, file_name: &str) -> String {
         format!("{}{}/{}", self.base_url, Self::BASE_PATH, file_name)
     }
+
+    /// Returns URL of a file attached to non-public post
+    pub fn restricted_url_for(&self, file_name: &str) -> String {
+        let url = self.url_for(file_name);
+        match self.url_signer {
+            Some(ref url_signer) => {
+                let (expires, signature) = url_signer.sign(file_name);
+                format!("{url}?expires={expires}&signature={signature}")
+            },
+            None => url,
+        }
+    }
 }
 
 pub enum MediaServer {
@@ -223,7 +300,15 @@ pub enum MediaServer {
 
 impl MediaServer {
     pub fn new(config: &Config) -> Self {
-        let backend = FilesystemServer::new(config.instance().uri_str());
+        let instance = config.instance();
+        let mut backend = FilesystemServer::new(instance.uri_str());
+        if config.media_access.mode != MediaAccessMode::Public {
+            let url_signer = MediaUrlSigner::new(
+                config.media_url_key(),
+                config.media_access.signed_url_lifetime,
+            );
+            backend = backend.with_url_signer(url_signer);
+        };
         Self::Filesystem(backend)
     }
 
@@ -238,6 +323,12 @@ impl MediaServer {
             Self::Filesystem(backend) => backend.url_for(file_name),
         }
     }
+
+    pub fn restricted_url_for(&self, file_name: &str) -> String {
+        match self {
+            Self::Filesystem(backend) => backend.restricted_url_for(file_name),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -269,4 +360,21 @@ mod tests {
             "https://social.example/media/4c4b6a3be1314ab86138bef4314dde022e600960d8689a2c8f8631802d20dab6.png",
         );
     }
+
+    #[test]
+    fn test_media_url_signer() {
+        let secret_key = generate_media_url_key();
+        let url_signer = MediaUrlSigner::new(secret_key, 3600);
+        let file_name = "4c4b6a3be1314ab86138bef4314dde022e600960d8689a2c8f8631802d20dab6.png";
+        let (expires, signature) = url_signer.sign(file_name);
+        assert!(expires > Utc::now().timestamp() + 3600);
+        assert_eq!(expires % 3600, 0);
+        let signature = hex::decode(signature).unwrap();
+        assert_eq!(url_signer.verify(file_name, expires, &signature), true);
+        assert_eq!(url_signer.verify("test.png", expires, &signature), false);
+        assert_eq!(
+            url_signer.verify(file_name, expires - 3600, &signature),
+            false,
+        );
+    }
 }
@@ -0,0 +1,196 @@
+use std::io::{Error as IoError};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use image::{ImageError, ImageReader};
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::process::Command;
+
+use mitra_config::MediaLimits;
+use mitra_models::attachments::types::MediaMetadata;
+
+use crate::media::MediaStorage;
+
+const FFPROBE_COMMAND: &str = "ffprobe";
+
+#[derive(Debug, Error)]
+enum MetadataError {
+    #[error(transparent)]
+    IoError(#[from] IoError),
+
+    #[error(transparent)]
+    ImageError(#[from] ImageError),
+
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("ffprobe error: {0}")]
+    FfprobeError(String),
+}
+
+fn get_image_metadata(
+    file_path: &Path,
+) -> Result<MediaMetadata, MetadataError> {
+    // Reads image header only
+    let (width, height) = ImageReader::open(file_path)?
+        .with_guessed_format()?
+        .into_dimensions()?;
+    let metadata = MediaMetadata {
+        width: Some(width),
+        height: Some(height),
+        ..Default::default()
+    };
+    Ok(metadata)
+}
+
+#[derive(Deserialize)]
+struct FfprobeStream {
+    codec_type: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+// Numbers are represented as strings
+#[derive(Deserialize)]
+struct FfprobeFormat {
+    duration: Option<String>,
+    bit_rate: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    format: Option<FfprobeFormat>,
+}
+
+fn parse_ffprobe_output(output: &[u8]) -> Result<MediaMetadata, MetadataError> {
+    let output: FfprobeOutput = serde_json::from_slice(output)?;
+    let maybe_video_stream = output.streams.iter()
+        .find(|stream| stream.codec_type.as_deref() == Some("video"));
+    let (duration, bitrate) = match output.format {
+        Some(format) => (
+            format.duration.and_then(|value| value.parse().ok()),
+            format.bit_rate.and_then(|value| value.parse().ok()),
+        ),
+        None => (None, None),
+    };
+    let metadata = MediaMetadata {
+        width: maybe_video_stream.and_then(|stream| stream.width),
+        height: maybe_video_stream.and_then(|stream| stream.height),
+        duration: duration,
+        bitrate: bitrate,
+    };
+    Ok(metadata)
+}
+
+async fn get_image_metadata_blocking(
+    file_path: PathBuf,
+) -> Result<MediaMetadata, MetadataError> {
+    tokio::task::spawn_blocking(move || get_image_metadata(&file_path))
+        .await
+        .map_err(IoError::other)?
+}
+
+async fn get_audio_video_metadata(
+    file_path: &Path,
+    timeout: Duration,
+) -> Result<MediaMetadata, MetadataError> {
+    let command_future = Command::new(FFPROBE_COMMAND)
+        .args([
+            "-v", "error",
+            "-print_format", "json",
+            "-show_format",
+            "-show_streams",
+        ])
+        .arg(file_path)
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(timeout, command_future).await
+        .map_err(|_| MetadataError::FfprobeError("timeout".to_string()))??;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr);
+        return Err(MetadataError::FfprobeError(message.trim().to_string()));
+    };
+    parse_ffprobe_output(&output.stdout)
+}
+
+/// Extracts dimensions of images and videos, and duration of audio and video files.
+/// Audio and video files are processed with ffprobe, if it is enabled.
+pub async fn get_media_metadata(
+    media_limits: &MediaLimits,
+    media_storage: &MediaStorage,
+    file_name: &str,
+    media_type: &str,
+) -> Option<MediaMetadata> {
+    let MediaStorage::Filesystem(backend) = media_storage;
+    let file_path = backend.media_dir.join(file_name);
+    let result = if media_type.starts_with("image/") {
+        get_image_metadata_blocking(file_path).await
+    } else if media_type.starts_with("video/") ||
+        media_type.starts_with("audio/")
+    {
+        let timeout = media_limits.ffprobe_timeout?;
+        get_audio_video_metadata(
+            &file_path,
+            Duration::from_secs(timeout),
+        ).await
+    } else {
+        return None;
+    };
+    match result {
+        Ok(metadata) if !metadata.is_empty() => Some(metadata),
+        Ok(_) => None,
+        Err(error) => {
+            log::warn!("failed to extract media metadata ({file_name}): {error}");
+            None
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ffprobe_output() {
+        let output = r#"{
+            "streams": [
+                {
+                    "index": 0,
+                    "codec_type": "video",
+                    "width": 1280,
+                    "height": 720
+                },
+                {
+                    "index": 1,
+                    "codec_type": "audio"
+                }
+            ],
+            "format": {
+                "format_name": "mov,mp4,m4a,3gp,3g2,mj2",
+                "duration": "12.480000",
+                "bit_rate": "1205449"
+            }
+        }"#;
+        let metadata = parse_ffprobe_output(output.as_bytes()).unwrap();
+        assert_eq!(metadata.width, Some(1280));
+        assert_eq!(metadata.height, Some(720));
+        assert_eq!(metadata.duration, Some(12.48));
+        assert_eq!(metadata.bitrate, Some(1205449));
+    }
+
+    #[test]
+    fn test_parse_ffprobe_output_audio() {
+        let output = r#"{
+            "streams": [{"index": 0, "codec_type": "audio"}],
+            "format": {"duration": "88.654000", "bit_rate": "128000"}
+        }"#;
+        let metadata = parse_ffprobe_output(output.as_bytes()).unwrap();
+        assert_eq!(metadata.width, None);
+        assert_eq!(metadata.height, None);
+        assert_eq!(metadata.duration, Some(88.654));
+        assert_eq!(metadata.bitrate, Some(128000));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod caip122;
 pub mod light_wallet;
+mod rpc_pool;
 pub mod utils;
 pub mod wallet;
@@ -0,0 +1,124 @@
+//! Wallet RPC endpoint selection, client reuse and statistics
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+use monero_rpc::WalletClient;
+
+// Endpoint is not preferred for some time after a failure
+const FAILURE_COOLDOWN: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Default)]
+pub struct WalletRpcStats {
+    pub requests: u64,
+    pub failures: u64,
+    pub latency_total: Duration,
+    last_failure: Option<Instant>,
+}
+
+impl WalletRpcStats {
+    fn is_available(&self) -> bool {
+        match self.last_failure {
+            Some(last_failure) => last_failure.elapsed() > FAILURE_COOLDOWN,
+            None => true,
+        }
+    }
+}
+
+#[derive(Default)]
+struct WalletRpcPool {
+    // Clients are reused to keep connections (and authentication) alive
+    clients: HashMap<(String, u64), WalletClient>,
+    stats: HashMap<String, WalletRpcStats>,
+}
+
+static WALLET_RPC_POOL: OnceLock<Mutex<WalletRpcPool>> = OnceLock::new();
+
+fn get_pool() -> MutexGuard<'static, WalletRpcPool> {
+    WALLET_RPC_POOL.get_or_init(Default::default)
+        .lock()
+        .expect("lock should not be poisoned")
+}
+
+pub(super) fn get_cached_client(
+    url: &str,
+    timeout: u64,
+) -> Option<WalletClient> {
+    get_pool().clients.get(&(url.to_owned(), timeout)).cloned()
+}
+
+pub(super) fn cache_client(
+    url: &str,
+    timeout: u64,
+    wallet_client: WalletClient,
+) -> () {
+    get_pool().clients.insert((url.to_owned(), timeout), wallet_client);
+}
+
+pub(super) fn record_success(url: &str, latency: Duration) -> () {
+    let mut pool = get_pool();
+    let stats = pool.stats.entry(url.to_owned()).or_default();
+    stats.requests += 1;
+    stats.latency_total += latency;
+    stats.last_failure = None;
+}
+
+pub(super) fn record_failure(url: &str, latency: Duration) -> () {
+    let mut pool = get_pool();
+    let stats = pool.stats.entry(url.to_owned()).or_default();
+    stats.requests += 1;
+    stats.failures += 1;
+    stats.latency_total += latency;
+    stats.last_failure = Some(Instant::now());
+    // Drop clients, new connection will be established on next request
+    pool.clients.retain(|(client_url, _), _| client_url != url);
+}
+
+/// Returns endpoints in order of preference:
+/// available endpoints first, then endpoints that failed recently
+pub(super) fn sort_endpoints(urls: Vec<String>) -> Vec<String> {
+    let pool = get_pool();
+    let (available, failed): (Vec<_>, Vec<_>) = urls.into_iter()
+        .partition(|url| {
+            pool.stats.get(url)
+                .map(|stats| stats.is_available())
+                .unwrap_or(true)
+        });
+    [available, failed].concat()
+}
+
+pub fn get_wallet_rpc_stats() -> Vec<(String, WalletRpcStats)> {
+    let mut stats: Vec<_> = get_pool().stats.iter()
+        .map(|(url, stats)| (url.clone(), stats.clone()))
+        .collect();
+    stats.sort_by(|(url_a, _), (url_b, _)| url_a.cmp(url_b));
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_endpoints() {
+        let primary = "http://primary.test:18083";
+        let fallback = "http://fallback.test:18083";
+        let urls = vec![primary.to_owned(), fallback.to_owned()];
+        let sorted = sort_endpoints(urls.clone());
+        assert_eq!(sorted, urls);
+
+        record_failure(primary, Duration::from_millis(10));
+        let sorted = sort_endpoints(urls.clone());
+        assert_eq!(sorted, vec![fallback.to_owned(), primary.to_owned()]);
+
+        record_success(primary, Duration::from_millis(10));
+        let sorted = sort_endpoints(urls.clone());
+        assert_eq!(sorted, urls);
+        let stats = get_wallet_rpc_stats();
+        let (_, primary_stats) = stats.iter()
+            .find(|(url, _)| url == primary)
+            .unwrap();
+        assert_eq!(primary_stats.requests, 2);
+        assert_eq!(primary_stats.failures, 1);
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use monero_rpc::{
     GetTransfersCategory,
@@ -11,6 +11,7 @@ use monero_rpc::{
     RpcClientBuilder,
     SubaddressBalanceData,
     SweepAllArgs,
+    TransferOptions,
     TransferPriority,
     TransferType,
 };
@@ -23,9 +24,17 @@ use monero_rpc::monero::{
 
 use mitra_config::MoneroConfig;
 
+use super::rpc_pool::{
+    cache_client,
+    get_cached_client,
+    record_failure,
+    record_success,
+    sort_endpoints,
+};
 use super::utils::parse_monero_address;
 
 pub use monero_rpc::WalletClient;
+pub use super::rpc_pool::{get_wallet_rpc_stats, WalletRpcStats};
 pub type TransferCategory = GetTransfersCategory;
 
 const MONERO_RPC_TIMEOUT: u64 = 15;
@@ -57,10 +66,14 @@ pub enum MoneroError {
     OtherError(&'static str),
 }
 
-pub fn build_wallet_client(
+fn build_wallet_client_for_url(
     config: &MoneroConfig,
+    url: &str,
     timeout: u64,
 ) -> Result<WalletClient, MoneroError> {
+    if let Some(wallet_client) = get_cached_client(url, timeout) {
+        return Ok(wallet_client);
+    };
     let rpc_authentication = match config.wallet_rpc_username {
         Some(ref username) => {
             RpcAuthentication::Credentials {
@@ -74,11 +87,24 @@ pub fn build_wallet_client(
     let wallet_client = RpcClientBuilder::new()
         .rpc_authentication(rpc_authentication)
         .timeout(Duration::from_secs(timeout))
-        .build(config.wallet_rpc_url.clone())?
+        .build(url.to_owned())?
         .wallet();
+    cache_client(url, timeout, wallet_client.clone());
     Ok(wallet_client)
 }
 
+/// Returns client for the preferred wallet RPC endpoint
+pub fn build_wallet_client(
+    config: &MoneroConfig,
+    timeout: u64,
+) -> Result<WalletClient, MoneroError> {
+    let url = sort_endpoints(config.wallet_rpc_urls())
+        .into_iter()
+        .next()
+        .expect("at least one endpoint should be configured");
+    build_wallet_client_for_url(config, &url, timeout)
+}
+
 fn build_default_wallet_client(
     config: &MoneroConfig,
 ) -> Result<WalletClient, MoneroError> {
@@ -98,10 +124,15 @@ pub async fn create_monero_wallet(
 }
 
 // https://docs.getmonero.org/rpc-library/wallet-rpc/#open_wallet
-pub async fn open_monero_wallet(
+async fn open_wallet_at(
     config: &MoneroConfig,
+    url: &str,
 ) -> Result<WalletClient, MoneroError> {
-    let wallet_client = build_default_wallet_client(config)?;
+    let wallet_client = build_wallet_client_for_url(
+        config,
+        url,
+        MONERO_RPC_TIMEOUT,
+    )?;
     if let Err(error) = wallet_client.refresh(None).await {
         if error.to_string() == "Server error: No wallet file" {
             // Try to open wallet
@@ -127,6 +158,28 @@ pub async fn open_monero_wallet(
     Ok(wallet_client)
 }
 
+/// Opens wallet using the first available wallet RPC endpoint
+pub async fn open_monero_wallet(
+    config: &MoneroConfig,
+) -> Result<WalletClient, MoneroError> {
+    let mut last_error = None;
+    for url in sort_endpoints(config.wallet_rpc_urls()) {
+        let started_at = Instant::now();
+        match open_wallet_at(config, &url).await {
+            Ok(wallet_client) => {
+                record_success(&url, started_at.elapsed());
+                return Ok(wallet_client);
+            },
+            Err(error) => {
+                record_failure(&url, started_at.elapsed());
+                log::warn!("wallet RPC endpoint {url} is not available: {error}");
+                last_error = Some(error);
+            },
+        };
+    };
+    Err(last_error.expect("at least one endpoint should be configured"))
+}
+
 // https://docs.getmonero.org/rpc-library/wallet-rpc/#create_address
 pub async fn create_monero_address(
     config: &MoneroConfig,
@@ -303,6 +356,82 @@ pub async fn send_monero(
     Ok((format!("{:x}", tx_hash), amount))
 }
 
+fn map_transfer_error(error: anyhow::Error) -> MoneroError {
+    if error.to_string() == "Server error: not enough money" ||
+        error.to_string() == "Server error: not enough unlocked money"
+    {
+        MoneroError::Dust
+    } else {
+        error.into()
+    }
+}
+
+/// Sends all available balance to multiple addresses.
+/// Shares are sent to additional addresses,
+/// the rest (minus fee) is sent to the main address.
+// https://docs.getmonero.org/rpc-library/wallet-rpc/#transfer
+pub async fn send_monero_split(
+    wallet_client: &WalletClient,
+    from_account: u32,
+    from_address: u32,
+    balance: Amount,
+    to_address: &str,
+    shares: &[(String, u64)],
+) -> Result<(String, Amount), MoneroError> {
+    let to_address = parse_monero_address(to_address)?;
+    let mut destinations = HashMap::new();
+    let mut shares_total: u64 = 0;
+    for (address, amount) in shares {
+        let address = parse_monero_address(address)?;
+        destinations.insert(address, Amount::from_pico(*amount));
+        shares_total = shares_total.checked_add(*amount)
+            .ok_or(MoneroError::OtherError("amount is too big"))?;
+    };
+    let remainder = balance.as_pico().checked_sub(shares_total)
+        .ok_or(MoneroError::Dust)?;
+    let build_options = |do_not_relay| TransferOptions {
+        account_index: Some(from_account),
+        subaddr_indices: Some(vec![from_address]),
+        mixin: Some(15),
+        ring_size: Some(16),
+        // unlock_time must be zero
+        unlock_time: Some(0),
+        payment_id: None,
+        do_not_relay: Some(do_not_relay),
+        subtract_fee_from_outputs: None,
+    };
+    // Estimate fee (transaction is not relayed).
+    // Number of outputs doesn't change, so the fee will be the same.
+    let mut estimate_destinations = destinations.clone();
+    estimate_destinations.insert(to_address, Amount::from_pico(remainder / 2));
+    let estimate = wallet_client.transfer(
+        estimate_destinations,
+        TransferPriority::Default,
+        build_options(true),
+    ).await.map_err(map_transfer_error)?;
+    let main_amount = remainder.checked_sub(estimate.fee.as_pico())
+        .filter(|amount| *amount > 0)
+        .ok_or(MoneroError::Dust)?;
+    destinations.insert(to_address, Amount::from_pico(main_amount));
+    let transfer_data = wallet_client.transfer(
+        destinations,
+        TransferPriority::Default,
+        build_options(false),
+    ).await.map_err(map_transfer_error)?;
+    let HashString(tx_hash) = transfer_data.tx_hash;
+    log::info!(
+        "sent transaction {:x} from {}/{}, amount {}, fee {}",
+        tx_hash,
+        from_account,
+        from_address,
+        transfer_data.amount,
+        transfer_data.fee,
+    );
+    // Save wallet
+    wallet_client.close_wallet().await?;
+    Ok((format!("{:x}", tx_hash), transfer_data.amount))
+}
+
 // https://docs.getmonero.org/rpc-library/wallet-rpc/#get_transfer_by_txid
 pub async fn get_transaction_by_id(
     wallet_client: &WalletClient,
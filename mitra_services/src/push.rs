@@ -0,0 +1,331 @@
+//! Web Push
+//! https://www.rfc-editor.org/rfc/rfc8030
+//! https://www.rfc-editor.org/rfc/rfc8291
+//! https://www.rfc-editor.org/rfc/rfc8292
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes128Gcm,
+    Nonce,
+};
+use apx_core::base64;
+use chrono::{TimeDelta, Utc};
+use hkdf::Hkdf;
+use p256::{
+    ecdh::diffie_hellman,
+    ecdsa::{signature::Signer, Signature, SigningKey},
+    elliptic_curve::sec1::ToEncodedPoint,
+    PublicKey,
+    SecretKey,
+};
+use reqwest::{Client, StatusCode, Url};
+use serde_json::json;
+use sha2::Sha256;
+use thiserror::Error;
+
+use mitra_utils::random::generate_random_sequence;
+
+pub type VapidSecretKey = [u8; 32];
+
+const RECORD_SIZE: u32 = 4096;
+const AUTH_SECRET_LENGTH: usize = 16;
+const SALT_LENGTH: usize = 16;
+const TAG_LENGTH: usize = 16;
+const PADDING_DELIMITER: u8 = 2;
+
+const PUSH_MESSAGE_TTL: u32 = 86400 * 2; // 2 days
+const VAPID_TOKEN_LIFETIME: i64 = 12 * 3600; // 12 hours
+
+#[derive(Debug, Error)]
+pub enum PushError {
+    #[error("invalid key")]
+    InvalidKey,
+
+    #[error("payload is too large")]
+    PayloadTooLarge,
+
+    #[error("encryption error")]
+    EncryptionError,
+
+    #[error("invalid endpoint")]
+    InvalidEndpoint,
+
+    #[error("subscription is no longer valid")]
+    SubscriptionGone,
+
+    #[error(transparent)]
+    RequestError(#[from] reqwest::Error),
+}
+
+fn generate_secret_key() -> SecretKey {
+    loop {
+        let secret_key_bytes: [u8; 32] = generate_random_sequence();
+        // Fails if value is zero or is not less than curve order
+        if let Ok(secret_key) = SecretKey::from_slice(&secret_key_bytes) {
+            break secret_key;
+        };
+    }
+}
+
+pub fn generate_vapid_key() -> VapidSecretKey {
+    let secret_key = generate_secret_key();
+    secret_key.to_bytes().into()
+}
+
+/// Returns public key in uncompressed form
+fn public_key_bytes(secret_key: &SecretKey) -> Vec<u8> {
+    secret_key.public_key()
+        .to_encoded_point(false)
+        .as_bytes()
+        .to_vec()
+}
+
+fn vapid_public_key_bytes(
+    secret_key: &VapidSecretKey,
+) -> Result<Vec<u8>, PushError> {
+    let secret_key = SecretKey::from_slice(secret_key)
+        .map_err(|_| PushError::InvalidKey)?;
+    Ok(public_key_bytes(&secret_key))
+}
+
+/// Returns VAPID public key (uncompressed P-256 point, base64url-encoded)
+pub fn get_vapid_public_key(
+    secret_key: &VapidSecretKey,
+) -> Result<String, PushError> {
+    let public_key = vapid_public_key_bytes(secret_key)?;
+    Ok(base64::encode_urlsafe_no_pad(public_key))
+}
+
+fn derive_key_and_nonce(
+    shared_secret: &[u8],
+    auth_secret: &[u8],
+    ua_public_key: &[u8],
+    as_public_key: &[u8],
+    salt: &[u8],
+) -> Result<([u8; 16], [u8; 12]), PushError> {
+    let mut key_info = b"WebPush: info\0".to_vec();
+    key_info.extend_from_slice(ua_public_key);
+    key_info.extend_from_slice(as_public_key);
+    let mut ikm = [0; 32];
+    Hkdf::<Sha256>::new(Some(auth_secret), shared_secret)
+        .expand(&key_info, &mut ikm)
+        .map_err(|_| PushError::EncryptionError)?;
+    let hkdf = Hkdf::<Sha256>::new(Some(salt), &ikm);
+    let mut content_encryption_key = [0; 16];
+    hkdf.expand(b"Content-Encoding: aes128gcm\0", &mut content_encryption_key)
+        .map_err(|_| PushError::EncryptionError)?;
+    let mut nonce = [0; 12];
+    hkdf.expand(b"Content-Encoding: nonce\0", &mut nonce)
+        .map_err(|_| PushError::EncryptionError)?;
+    Ok((content_encryption_key, nonce))
+}
+
+/// Encrypts push message payload (aes128gcm content encoding)
+pub fn encrypt_payload(
+    ua_public_key: &[u8],
+    auth_secret: &[u8],
+    payload: &[u8],
+) -> Result<Vec<u8>, PushError> {
+    if auth_secret.len() != AUTH_SECRET_LENGTH {
+        return Err(PushError::InvalidKey);
+    };
+    // Single record
+    if payload.len() + 1 + TAG_LENGTH > RECORD_SIZE as usize {
+        return Err(PushError::PayloadTooLarge);
+    };
+    let ua_public_key_parsed = PublicKey::from_sec1_bytes(ua_public_key)
+        .map_err(|_| PushError::InvalidKey)?;
+    // Application server key is generated for each message
+    let as_secret_key = generate_secret_key();
+    let as_public_key = public_key_bytes(&as_secret_key);
+    let salt: [u8; SALT_LENGTH] = generate_random_sequence();
+    let shared_secret = diffie_hellman(
+        as_secret_key.to_nonzero_scalar(),
+        ua_public_key_parsed.as_affine(),
+    );
+    let (content_encryption_key, nonce) = derive_key_and_nonce(
+        shared_secret.raw_secret_bytes(),
+        auth_secret,
+        ua_public_key,
+        &as_public_key,
+        &salt,
+    )?;
+    let mut plaintext = payload.to_vec();
+    plaintext.push(PADDING_DELIMITER);
+    let cipher = Aes128Gcm::new_from_slice(&content_encryption_key)
+        .map_err(|_| PushError::EncryptionError)?;
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), plaintext.as_ref())
+        .map_err(|_| PushError::EncryptionError)?;
+    let key_id_length = u8::try_from(as_public_key.len())
+        .map_err(|_| PushError::InvalidKey)?;
+    let mut message = vec![];
+    message.extend_from_slice(&salt);
+    message.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    message.push(key_id_length);
+    message.extend_from_slice(&as_public_key);
+    message.extend_from_slice(&ciphertext);
+    Ok(message)
+}
+
+fn create_vapid_token(
+    secret_key: &VapidSecretKey,
+    audience: &str,
+    subject: &str,
+) -> Result<String, PushError> {
+    let signing_key = SigningKey::from_slice(secret_key)
+        .map_err(|_| PushError::InvalidKey)?;
+    let expires_at = Utc::now() + TimeDelta::seconds(VAPID_TOKEN_LIFETIME);
+    let header = json!({ "typ": "JWT", "alg": "ES256" });
+    let claims = json!({
+        "aud": audience,
+        "exp": expires_at.timestamp(),
+        "sub": subject,
+    });
+    let signing_input = format!(
+        "{}.{}",
+        base64::encode_urlsafe_no_pad(header.to_string()),
+        base64::encode_urlsafe_no_pad(claims.to_string()),
+    );
+    let signature: Signature = signing_key.sign(signing_input.as_bytes());
+    let token = format!(
+        "{}.{}",
+        signing_input,
+        base64::encode_urlsafe_no_pad(signature.to_bytes()),
+    );
+    Ok(token)
+}
+
+/// Sends push message to push service.
+/// `vapid_subject` should be a "mailto:" or "https:" URI.
+/// The HTTP client must be configured by the caller
+/// (proxy settings, SSRF protection, timeouts).
+pub async fn send_push_message(
+    client: &Client,
+    vapid_secret_key: &VapidSecretKey,
+    vapid_subject: &str,
+    endpoint: &str,
+    ua_public_key: &[u8],
+    auth_secret: &[u8],
+    payload: &[u8],
+) -> Result<(), PushError> {
+    let endpoint_url = Url::parse(endpoint)
+        .map_err(|_| PushError::InvalidEndpoint)?;
+    let audience = endpoint_url.origin().ascii_serialization();
+    let vapid_token = create_vapid_token(
+        vapid_secret_key,
+        &audience,
+        vapid_subject,
+    )?;
+    let vapid_public_key = get_vapid_public_key(vapid_secret_key)?;
+    let body = encrypt_payload(ua_public_key, auth_secret, payload)?;
+    let response = client.post(endpoint_url)
+        .header("Authorization", format!("vapid t={vapid_token}, k={vapid_public_key}"))
+        .header("Content-Encoding", "aes128gcm")
+        .header("Content-Type", "application/octet-stream")
+        .header("TTL", PUSH_MESSAGE_TTL.to_string())
+        .body(body)
+        .send()
+        .await?;
+    if matches!(response.status(), StatusCode::NOT_FOUND | StatusCode::GONE) {
+        return Err(PushError::SubscriptionGone);
+    };
+    response.error_for_status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use p256::ecdsa::{signature::Verifier, VerifyingKey};
+    use super::*;
+
+    fn decrypt_payload(
+        ua_secret_key: &SecretKey,
+        auth_secret: &[u8],
+        message: &[u8],
+    ) -> Vec<u8> {
+        let salt = &message[..SALT_LENGTH];
+        let record_size = u32::from_be_bytes(
+            message[SALT_LENGTH..SALT_LENGTH + 4].try_into().unwrap(),
+        );
+        assert_eq!(record_size, RECORD_SIZE);
+        let key_id_length = usize::from(message[SALT_LENGTH + 4]);
+        let key_id_start = SALT_LENGTH + 5;
+        let as_public_key = &message[key_id_start..key_id_start + key_id_length];
+        let ciphertext = &message[key_id_start + key_id_length..];
+        let shared_secret = diffie_hellman(
+            ua_secret_key.to_nonzero_scalar(),
+            PublicKey::from_sec1_bytes(as_public_key).unwrap().as_affine(),
+        );
+        let ua_public_key = public_key_bytes(ua_secret_key);
+        let (content_encryption_key, nonce) = derive_key_and_nonce(
+            shared_secret.raw_secret_bytes(),
+            auth_secret,
+            &ua_public_key,
+            as_public_key,
+            salt,
+        ).unwrap();
+        let cipher = Aes128Gcm::new_from_slice(&content_encryption_key).unwrap();
+        let mut plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext)
+            .unwrap();
+        assert_eq!(plaintext.pop(), Some(PADDING_DELIMITER));
+        plaintext
+    }
+
+    #[test]
+    fn test_encrypt_payload() {
+        let ua_secret_key = generate_secret_key();
+        let ua_public_key = public_key_bytes(&ua_secret_key);
+        let auth_secret: [u8; 16] = generate_random_sequence();
+        let payload = b"When I grow up, I want to be a watermelon";
+        let message = encrypt_payload(
+            &ua_public_key,
+            &auth_secret,
+            payload,
+        ).unwrap();
+        // Header: salt (16) + record size (4) + key ID length (1) + key ID (65)
+        assert_eq!(message.len(), 86 + payload.len() + 1 + TAG_LENGTH);
+        let decrypted = decrypt_payload(&ua_secret_key, &auth_secret, &message);
+        assert_eq!(decrypted, payload);
+    }
+
+    #[test]
+    fn test_encrypt_payload_too_large() {
+        let ua_secret_key = generate_secret_key();
+        let ua_public_key = public_key_bytes(&ua_secret_key);
+        let auth_secret: [u8; 16] = generate_random_sequence();
+        let payload = vec![0; 5000];
+        let error = encrypt_payload(
+            &ua_public_key,
+            &auth_secret,
+            &payload,
+        ).err().unwrap();
+        assert!(matches!(error, PushError::PayloadTooLarge));
+    }
+
+    #[test]
+    fn test_get_vapid_public_key() {
+        let secret_key = generate_vapid_key();
+        let public_key = get_vapid_public_key(&secret_key).unwrap();
+        let public_key_bytes = base64::decode_urlsafe_no_pad(public_key).unwrap();
+        assert_eq!(public_key_bytes.len(), 65);
+        assert_eq!(public_key_bytes[0], 4);
+    }
+
+    #[test]
+    fn test_create_vapid_token() {
+        let secret_key = generate_vapid_key();
+        let token = create_vapid_token(
+            &secret_key,
+            "https://push.example",
+            "https://social.example",
+        ).unwrap();
+        let (signing_input, signature) = token.rsplit_once('.').unwrap();
+        let signature_bytes = base64::decode_urlsafe_no_pad(signature).unwrap();
+        let signature = Signature::from_slice(&signature_bytes).unwrap();
+        let verifying_key = VerifyingKey::from_sec1_bytes(
+            &vapid_public_key_bytes(&secret_key).unwrap(),
+        ).unwrap();
+        let result = verifying_key.verify(signing_input.as_bytes(), &signature);
+        assert!(result.is_ok());
+    }
+}
@@ -6,6 +6,43 @@ pub fn parse_language_tag(value: &str) -> Option<Language> {
         .map(|(code, _)| code)
         .unwrap_or(value);
     Language::from_639_1(code)
+        // Languages without ISO 639-1 code
+        .or_else(|| Language::from_639_3(code))
+}
+
+/// Returns language tag: ISO 639-1 code,
+/// or ISO 639-3 code if the language doesn't have a two-letter code
+pub fn to_language_tag(language: Language) -> &'static str {
+    language.to_639_1().unwrap_or(language.to_639_3())
+}
+
+/// Parses Accept-Language header value
+/// and returns languages ordered by preference
+/// <https://www.rfc-editor.org/rfc/rfc9110#section-12.5.4>
+pub fn parse_accept_language(value: &str) -> Vec<Language> {
+    let mut ranges: Vec<(Language, f32)> = vec![];
+    for item in value.split(',') {
+        let mut parts = item.split(';').map(|part| part.trim());
+        let Some(language_range) = parts.next() else { continue };
+        let weight = parts
+            .find_map(|param| param.strip_prefix("q="))
+            .map(|weight| weight.parse().unwrap_or(0.0))
+            .unwrap_or(1.0);
+        if weight <= 0.0 {
+            continue;
+        };
+        let Some(language) = parse_language_tag(language_range) else {
+            // Wildcard or unknown language
+            continue;
+        };
+        if ranges.iter().any(|(other, _)| *other == language) {
+            continue;
+        };
+        ranges.push((language, weight));
+    };
+    // Stable sort
+    ranges.sort_by(|(_, weight_1), (_, weight_2)| weight_2.total_cmp(weight_1));
+    ranges.into_iter().map(|(language, _)| language).collect()
 }
 
 #[cfg(test)]
@@ -18,5 +55,29 @@ mod tests {
         let value = "zh-Hans";
         let result = parse_language_tag(value);
         assert_eq!(result, Some(Language::Zho));
+
+        let value = "haw";
+        let result = parse_language_tag(value);
+        assert_eq!(result, Some(Language::Haw));
+    }
+
+    #[test]
+    fn test_to_language_tag() {
+        assert_eq!(to_language_tag(Language::Eng), "en");
+        assert_eq!(to_language_tag(Language::Haw), "haw");
+    }
+
+    #[test]
+    fn test_parse_accept_language() {
+        let value = "fr-CH, fr;q=0.9, en;q=0.8, de;q=0.95, *;q=0.5";
+        let result = parse_accept_language(value);
+        assert_eq!(result, vec![Language::Fra, Language::Deu, Language::Eng]);
+    }
+
+    #[test]
+    fn test_parse_accept_language_zero_weight() {
+        let value = "es;q=0, pt";
+        let result = parse_accept_language(value);
+        assert_eq!(result, vec![Language::Por]);
     }
 }
@@ -12,6 +12,9 @@ const USERNAME_RE: &str = r"^[A-Za-z0-9_\-]+$";
 // https://github.com/mastodon/mastodon/blob/4b9e4f6398760cc04f9fde2c659f30ffea216e12/app/models/account.rb#L91
 const USERNAME_LENGTH_MAX: usize = 30;
 const CLIENT_CONFIG_SIZE_MAX: usize = 20 * 1000;
+// IANA time zone name (e.g. "Europe/Berlin" or "Etc/GMT+5")
+const TIMEZONE_RE: &str = r"^[A-Za-z0-9_+\-]+(/[A-Za-z0-9_+\-]+)*$";
+const TIMEZONE_LENGTH_MAX: usize = 64;
 
 pub fn validate_local_username(username: &str) -> Result<(), ValidationError> {
     validate_username(username)?;
@@ -48,6 +51,20 @@ pub fn validate_client_config_update(
     Ok(())
 }
 
+/// Checks syntax of time zone name.
+/// The name should also be looked up in the time zone database.
+pub fn validate_timezone_name(timezone: &str) -> Result<(), ValidationError> {
+    let timezone_regexp = Regex::new(TIMEZONE_RE)
+        .expect("regexp should be valid");
+    if !timezone_regexp.is_match(timezone) {
+        return Err(ValidationError("invalid time zone name"));
+    };
+    if timezone.len() > TIMEZONE_LENGTH_MAX {
+        return Err(ValidationError("time zone name is too long"));
+    };
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -73,4 +90,14 @@ mod tests {
         let result = validate_client_config_update(&config, &update);
         assert_eq!(result.is_ok(), true);
     }
+
+    #[test]
+    fn test_validate_timezone_name() {
+        assert_eq!(validate_timezone_name("UTC").is_ok(), true);
+        assert_eq!(validate_timezone_name("America/Argentina/Buenos_Aires").is_ok(), true);
+        assert_eq!(validate_timezone_name("Etc/GMT+5").is_ok(), true);
+        assert_eq!(validate_timezone_name("").is_ok(), false);
+        assert_eq!(validate_timezone_name("../etc/passwd").is_ok(), false);
+        assert_eq!(validate_timezone_name("Europe/").is_ok(), false);
+    }
 }
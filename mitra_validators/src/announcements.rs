@@ -0,0 +1,53 @@
+use mitra_models::announcements::types::AnnouncementData;
+
+use super::errors::ValidationError;
+
+const ANNOUNCEMENT_CONTENT_LENGTH_MAX: usize = 5000;
+
+pub fn validate_announcement_data(
+    announcement_data: &AnnouncementData,
+) -> Result<(), ValidationError> {
+    if announcement_data.content.trim().is_empty() {
+        return Err(ValidationError("announcement content is empty"));
+    };
+    if announcement_data.content.chars().count() > ANNOUNCEMENT_CONTENT_LENGTH_MAX {
+        return Err(ValidationError("announcement content is too long"));
+    };
+    if announcement_data.expires_at
+        .is_some_and(|expires_at| expires_at <= announcement_data.published_at)
+    {
+        return Err(ValidationError("expiration date is before publication date"));
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use super::*;
+
+    #[test]
+    fn test_validate_announcement_data() {
+        let now = Utc::now();
+        let announcement_data = AnnouncementData {
+            content: "Test".to_string(),
+            published_at: now,
+            expires_at: Some(now + Duration::days(1)),
+        };
+        assert!(validate_announcement_data(&announcement_data).is_ok());
+
+        let announcement_data = AnnouncementData {
+            content: " ".to_string(),
+            published_at: now,
+            expires_at: None,
+        };
+        assert!(validate_announcement_data(&announcement_data).is_err());
+
+        let announcement_data = AnnouncementData {
+            content: "Test".to_string(),
+            published_at: now,
+            expires_at: Some(now - Duration::days(1)),
+        };
+        assert!(validate_announcement_data(&announcement_data).is_err());
+    }
+}
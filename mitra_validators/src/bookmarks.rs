@@ -0,0 +1,20 @@
+use mitra_utils::unicode::trim_invisible;
+
+use super::errors::ValidationError;
+
+const BOOKMARK_FOLDER_NAME_SIZE_MAX: usize = 200; // database column limit
+
+pub fn clean_bookmark_folder_name(name: &str) -> &str {
+    // Sanitization is not needed because `name` is a plain-text field
+    trim_invisible(name)
+}
+
+pub fn validate_bookmark_folder_name(name: &str) -> Result<(), ValidationError> {
+    if trim_invisible(name).is_empty() {
+        return Err(ValidationError("folder name is empty"));
+    };
+    if name.len() > BOOKMARK_FOLDER_NAME_SIZE_MAX {
+        return Err(ValidationError("folder name is too long"));
+    };
+    Ok(())
+}
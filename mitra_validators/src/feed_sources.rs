@@ -0,0 +1,31 @@
+use mitra_models::feed_sources::types::FeedSourceCreateData;
+
+use crate::{
+    accounts::validate_local_username,
+    errors::ValidationError,
+    profiles::{clean_bio, validate_bio},
+};
+
+const FEED_URL_LENGTH_MAX: usize = 2000;
+
+pub fn clean_feed_source_create_data(
+    source_data: &mut FeedSourceCreateData,
+) -> () {
+    if let Some(ref bio) = source_data.bio {
+        let cleaned_bio = clean_bio(bio, false);
+        source_data.bio = Some(cleaned_bio);
+    };
+}
+
+pub fn validate_feed_source_create_data(
+    source_data: &FeedSourceCreateData,
+) -> Result<(), ValidationError> {
+    validate_local_username(&source_data.username)?;
+    if let Some(ref bio) = source_data.bio {
+        validate_bio(bio)?;
+    };
+    if source_data.feed_url.len() > FEED_URL_LENGTH_MAX {
+        return Err(ValidationError("feed URL is too long"));
+    };
+    Ok(())
+}
@@ -1,15 +1,22 @@
 pub mod accounts;
 pub mod activitypub;
+pub mod announcements;
+pub mod bookmarks;
 pub mod custom_feeds;
 pub mod emojis;
 pub mod errors;
+pub mod feed_sources;
 pub mod filter_rules;
 pub mod groups;
 pub mod invoices;
 pub mod media;
 pub mod oauth;
+pub mod passwords;
 pub mod polls;
 pub mod posts;
 pub mod profiles;
+pub mod push_subscriptions;
 pub mod reactions;
+pub mod reports;
+pub mod server_rules;
 pub mod tags;
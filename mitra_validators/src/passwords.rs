@@ -0,0 +1,81 @@
+use zxcvbn::zxcvbn;
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum PasswordError {
+    #[error("password is too short")]
+    TooShort,
+
+    #[error("password is too weak")]
+    TooWeak,
+
+    #[error("password has appeared in a data breach")]
+    Breached,
+}
+
+impl PasswordError {
+    /// Returns error code compatible with Mastodon API
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::TooShort => "ERR_TOO_SHORT",
+            Self::TooWeak => "ERR_TOO_WEAK",
+            Self::Breached => "ERR_BREACHED",
+        }
+    }
+}
+
+/// Returns zxcvbn strength score of a password (from 0 to 4)
+pub fn estimate_password_strength(password: &str) -> u8 {
+    let estimate = zxcvbn(password, &[]);
+    u8::from(estimate.score())
+}
+
+pub fn validate_password(
+    password: &str,
+    min_length: usize,
+    min_score: u8,
+) -> Result<(), PasswordError> {
+    if password.chars().count() < min_length {
+        return Err(PasswordError::TooShort);
+    };
+    if min_score > 0 && estimate_password_strength(password) < min_score {
+        return Err(PasswordError::TooWeak);
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_password_strength() {
+        assert_eq!(estimate_password_strength(""), 0);
+        assert_eq!(estimate_password_strength("password"), 0);
+        assert!(estimate_password_strength("Password1!") < 3);
+        assert_eq!(estimate_password_strength("aaaaaaaaaaaa"), 0);
+        assert_eq!(estimate_password_strength("abcdefghijkl"), 0);
+        assert_eq!(
+            estimate_password_strength("correct horse battery staple"),
+            4,
+        );
+        assert_eq!(estimate_password_strength("x7#Kq9!vR2mZ"), 4);
+    }
+
+    #[test]
+    fn test_validate_password() {
+        assert_eq!(
+            validate_password("short", 8, 0),
+            Err(PasswordError::TooShort),
+        );
+        assert_eq!(
+            validate_password("aaaaaaaaaa", 8, 3),
+            Err(PasswordError::TooWeak),
+        );
+        assert_eq!(
+            validate_password("Password1!", 8, 3),
+            Err(PasswordError::TooWeak),
+        );
+        assert_eq!(validate_password("aaaaaaaaaa", 8, 0), Ok(()));
+        assert_eq!(validate_password("x7#Kq9!vR2mZ", 8, 3), Ok(()));
+    }
+}
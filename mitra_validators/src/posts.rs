@@ -2,6 +2,7 @@ use uuid::Uuid;
 
 use mitra_models::{
     posts::types::{
+        ContentVariant,
         PostContext,
         PostCreateData,
         PostDetailed,
@@ -10,7 +11,10 @@ use mitra_models::{
     },
     profiles::types::Origin,
 };
-use mitra_utils::html::{clean_html, clean_html_all, clean_html_strict};
+use mitra_utils::{
+    html::{clean_html, clean_html_all, clean_html_strict},
+    languages::Language,
+};
 
 use super::{
     activitypub::validate_any_object_id,
@@ -22,6 +26,7 @@ pub const MENTION_LIMIT: usize = 50;
 pub const HASHTAG_LIMIT: usize = 100;
 pub const LINK_LIMIT: usize = 10;
 pub const EMOJI_LIMIT: usize = 50;
+const CONTENT_VARIANT_LIMIT: usize = 10;
 
 const TITLE_LENGTH_MAX: usize = 300;
 const CONTENT_MAX_SIZE: usize = 150_000;
@@ -93,7 +98,9 @@ pub fn validate_content(
     origin: Origin,
 ) -> Result<(), ValidationError> {
     // Check content size to not exceed the hard limit
-    // Character limit from config is not enforced at the backend
+    // Character limit from config is applied before validation
+    // (see mitra_adapters::posts::check_local_post_length
+    // and mitra_adapters::posts::truncate_remote_content)
     if content.len() > CONTENT_MAX_SIZE {
         return Err(ValidationError("post is too long"));
     };
@@ -107,6 +114,34 @@ pub fn validate_content(
     Ok(())
 }
 
+fn validate_content_map(
+    content_map: &[ContentVariant],
+    maybe_language: Option<Language>,
+    origin: Origin,
+) -> Result<(), ValidationError> {
+    if content_map.is_empty() {
+        return Ok(());
+    };
+    // Language of the main content must be known
+    let language = maybe_language
+        .ok_or(ValidationError("post language is not specified"))?;
+    if content_map.len() > CONTENT_VARIANT_LIMIT {
+        return Err(ValidationError("too many content variants"));
+    };
+    let mut languages = vec![language];
+    for variant in content_map {
+        if languages.contains(&variant.language) {
+            return Err(ValidationError("duplicate content language"));
+        };
+        languages.push(variant.language);
+        validate_content(&variant.content, origin)?;
+        if variant.content.is_empty() {
+            return Err(ValidationError("content variant can not be empty"));
+        };
+    };
+    Ok(())
+}
+
 fn validate_url(url: &str) -> Result<(), ValidationError> {
     if url.len() > URL_LENGTH_MAX {
         return Err(ValidationError("post URL is too long"));
@@ -145,6 +180,11 @@ pub fn validate_post_create_data(
     {
         return Err(ValidationError("post can not be empty"));
     };
+    validate_content_map(
+        &post_data.content_map,
+        post_data.language,
+        origin,
+    )?;
     if let Some(ref poll_data) = post_data.poll {
         validate_poll_data(poll_data)?;
     };
@@ -183,6 +223,11 @@ pub fn validate_post_update_data(
     {
         return Err(ValidationError("post can not be empty"));
     };
+    validate_content_map(
+        &post_data.content_map,
+        post_data.language,
+        origin,
+    )?;
     if let Some(ref poll_data) = post_data.poll {
         validate_poll_data(poll_data)?;
     };
@@ -263,6 +308,9 @@ pub fn validate_repost_data(
     ) {
         return Err(ValidationError("invalid repost visibility"));
     };
+    if !repost_data.content_map.is_empty() {
+        return Err(ValidationError("repost can not have content"));
+    };
     if let Some(ref object_id) = repost_data.object_id {
         validate_any_object_id(object_id)?;
     };
@@ -336,6 +384,44 @@ mod tests {
         assert_eq!(cleaned, "test");
     }
 
+    #[test]
+    fn test_validate_content_map() {
+        let variant = ContentVariant {
+            language: Language::Deu,
+            content: "<p>Hallo</p>".to_string(),
+            content_source: Some("Hallo".to_string()),
+        };
+        let content_map = vec![variant.clone()];
+        let result = validate_content_map(
+            &content_map,
+            Some(Language::Eng),
+            Origin::Local,
+        );
+        assert_eq!(result.is_ok(), true);
+        let error = validate_content_map(
+            &content_map,
+            None,
+            Origin::Local,
+        ).err().unwrap();
+        assert_eq!(error.to_string(), "post language is not specified");
+        let error = validate_content_map(
+            &content_map,
+            Some(Language::Deu),
+            Origin::Local,
+        ).err().unwrap();
+        assert_eq!(error.to_string(), "duplicate content language");
+        let content_map = vec![ContentVariant {
+            content: "".to_string(),
+            ..variant
+        }];
+        let error = validate_content_map(
+            &content_map,
+            Some(Language::Eng),
+            Origin::Local,
+        ).err().unwrap();
+        assert_eq!(error.to_string(), "content variant can not be empty");
+    }
+
     #[test]
     fn test_validate_reply_wrong_visibility() {
         let author = DbActorProfile::local_for_test("author");
@@ -47,6 +47,8 @@ pub const FIELD_NAME_LENGTH_MAX: usize = 500;
 pub const FIELD_VALUE_LENGTH_MAX: usize = 5000;
 const FIELD_ALLOWED_TAGS: [&str; 1] = ["a"];
 pub const ALIAS_LIMIT: usize = 10;
+pub const LOCATION_MAX_LENGTH: usize = 200;
+pub const PRONOUNS_MAX_LENGTH: usize = 50;
 const IDENTITY_PROOF_LIMIT: usize = 10;
 
 pub fn validate_username(username: &str) -> Result<(), ValidationError> {
@@ -107,6 +109,40 @@ fn validate_display_name(display_name: &str)
     Ok(())
 }
 
+// Used for plain-text metadata fields (location, pronouns)
+fn clean_metadata_text(
+    text: &str,
+    max_length: usize,
+    is_remote: bool,
+) -> Option<String> {
+    let mut text = trim_invisible(text).trim().to_owned();
+    if is_remote {
+        text = text.chars().take(max_length).collect();
+    };
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn validate_metadata(
+    maybe_location: Option<&str>,
+    maybe_pronouns: Option<&str>,
+) -> Result<(), ValidationError> {
+    if let Some(location) = maybe_location {
+        if location.chars().count() > LOCATION_MAX_LENGTH {
+            return Err(ValidationError("location is too long"));
+        };
+    };
+    if let Some(pronouns) = maybe_pronouns {
+        if pronouns.chars().count() > PRONOUNS_MAX_LENGTH {
+            return Err(ValidationError("pronouns are too long"));
+        };
+    };
+    Ok(())
+}
+
 fn clean_bio_html(bio: &str) -> String {
     clean_html_strict(bio, &BIO_ALLOWED_TAGS, vec![])
 }
@@ -283,6 +319,10 @@ fn validate_profile_create_data(
     validate_identity_proofs(&profile_data.identity_proofs)?;
     validate_payment_options(&profile_data.payment_options)?;
     validate_extra_fields(&profile_data.extra_fields, is_remote)?;
+    validate_metadata(
+        profile_data.location.as_deref(),
+        profile_data.pronouns.as_deref(),
+    )?;
     validate_aliases(&profile_data.aliases)?;
     if profile_data.emojis.len() > EMOJI_LIMIT {
         return Err(ValidationError("too many emojis"));
@@ -302,6 +342,14 @@ pub fn clean_profile_create_data(
         let clean_bio = clean_bio(bio, is_remote);
         profile_data.bio = Some(clean_bio);
     };
+    if let Some(ref location) = profile_data.location {
+        profile_data.location =
+            clean_metadata_text(location, LOCATION_MAX_LENGTH, is_remote);
+    };
+    if let Some(ref pronouns) = profile_data.pronouns {
+        profile_data.pronouns =
+            clean_metadata_text(pronouns, PRONOUNS_MAX_LENGTH, is_remote);
+    };
     validate_profile_create_data(profile_data)?;
     Ok(())
 }
@@ -332,6 +380,10 @@ fn validate_profile_update_data(
     validate_identity_proofs(&profile_data.identity_proofs)?;
     validate_payment_options(&profile_data.payment_options)?;
     validate_extra_fields(&profile_data.extra_fields, is_remote)?;
+    validate_metadata(
+        profile_data.location.as_deref(),
+        profile_data.pronouns.as_deref(),
+    )?;
     validate_aliases(&profile_data.aliases)?;
     if profile_data.emojis.len() > EMOJI_LIMIT {
         return Err(ValidationError("too many emojis"));
@@ -351,6 +403,14 @@ pub fn clean_profile_update_data(
         let clean_bio = clean_bio(bio, is_remote);
         profile_data.bio = Some(clean_bio);
     };
+    if let Some(ref location) = profile_data.location {
+        profile_data.location =
+            clean_metadata_text(location, LOCATION_MAX_LENGTH, is_remote);
+    };
+    if let Some(ref pronouns) = profile_data.pronouns {
+        profile_data.pronouns =
+            clean_metadata_text(pronouns, PRONOUNS_MAX_LENGTH, is_remote);
+    };
     validate_profile_update_data(profile_data)?;
     Ok(())
 }
@@ -448,6 +508,18 @@ mod tests {
         assert_eq!(validate_extra_field(&field).is_err(), false);
     }
 
+    #[test]
+    fn test_clean_metadata_text() {
+        let text = " Paris\u{200B} ";
+        let cleaned = clean_metadata_text(text, LOCATION_MAX_LENGTH, false);
+        assert_eq!(cleaned.unwrap(), "Paris");
+        let cleaned = clean_metadata_text(" ", LOCATION_MAX_LENGTH, false);
+        assert_eq!(cleaned, None);
+        let text = "x".repeat(100);
+        let cleaned = clean_metadata_text(&text, PRONOUNS_MAX_LENGTH, true);
+        assert_eq!(cleaned.unwrap().len(), PRONOUNS_MAX_LENGTH);
+    }
+
     #[test]
     fn test_clean_profile_create_data() {
         let mut profile_data = ProfileCreateData::remote_for_test(
@@ -0,0 +1,57 @@
+use apx_core::url::http_uri::HttpUri;
+
+use mitra_models::push_subscriptions::types::PushSubscriptionData;
+
+use super::errors::ValidationError;
+
+const ENDPOINT_LENGTH_MAX: usize = 2000;
+const PUBLIC_KEY_LENGTH: usize = 65;
+const AUTH_SECRET_LENGTH: usize = 16;
+
+pub fn validate_push_subscription_data(
+    subscription_data: &PushSubscriptionData,
+) -> Result<(), ValidationError> {
+    if subscription_data.endpoint.len() > ENDPOINT_LENGTH_MAX {
+        return Err(ValidationError("endpoint URL is too long"));
+    };
+    let endpoint = HttpUri::parse(&subscription_data.endpoint)
+        .map_err(|_| ValidationError("invalid endpoint URL"))?;
+    if endpoint.scheme() != "https" {
+        return Err(ValidationError("endpoint URL must use HTTPS"));
+    };
+    // Uncompressed P-256 point
+    if subscription_data.public_key.len() != PUBLIC_KEY_LENGTH ||
+        subscription_data.public_key[0] != 4
+    {
+        return Err(ValidationError("invalid public key"));
+    };
+    if subscription_data.auth_secret.len() != AUTH_SECRET_LENGTH {
+        return Err(ValidationError("invalid auth secret"));
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use mitra_models::push_subscriptions::types::{PushAlerts, PushPolicy};
+    use super::*;
+
+    #[test]
+    fn test_validate_push_subscription_data() {
+        let mut subscription_data = PushSubscriptionData {
+            endpoint: "https://push.example/123".to_string(),
+            public_key: vec![4; 65],
+            auth_secret: vec![1; 16],
+            alerts: PushAlerts::default(),
+            policy: PushPolicy::All,
+        };
+        assert!(validate_push_subscription_data(&subscription_data).is_ok());
+
+        subscription_data.endpoint = "http://push.example/123".to_string();
+        assert!(validate_push_subscription_data(&subscription_data).is_err());
+
+        subscription_data.endpoint = "https://push.example/123".to_string();
+        subscription_data.public_key = vec![4; 33];
+        assert!(validate_push_subscription_data(&subscription_data).is_err());
+    }
+}
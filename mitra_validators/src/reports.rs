@@ -0,0 +1,58 @@
+use mitra_utils::unicode::trim_invisible;
+
+use super::errors::ValidationError;
+
+const REPORT_COMMENT_LENGTH_MAX: usize = 5000;
+
+pub fn clean_report_comment(comment: &str) -> &str {
+    // Sanitization is not needed because `comment` is a plain-text field
+    trim_invisible(comment)
+}
+
+pub fn validate_report_comment(comment: &str) -> Result<(), ValidationError> {
+    if comment.chars().count() > REPORT_COMMENT_LENGTH_MAX {
+        return Err(ValidationError("report comment is too long"));
+    };
+    Ok(())
+}
+
+pub fn clean_report_note(content: &str) -> &str {
+    trim_invisible(content)
+}
+
+pub fn validate_report_note(content: &str) -> Result<(), ValidationError> {
+    if trim_invisible(content).is_empty() {
+        return Err(ValidationError("note is empty"));
+    };
+    if content.chars().count() > REPORT_COMMENT_LENGTH_MAX {
+        return Err(ValidationError("note is too long"));
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_report_comment() {
+        let comment = "spam";
+        assert!(validate_report_comment(comment).is_ok());
+        let comment = "a".repeat(REPORT_COMMENT_LENGTH_MAX + 1);
+        assert!(validate_report_comment(&comment).is_err());
+    }
+
+    #[test]
+    fn test_validate_report_note() {
+        assert!(validate_report_note("checked").is_ok());
+        assert!(validate_report_note(" ").is_err());
+    }
+
+    #[test]
+    fn test_clean_report_note() {
+        let content = "\u{200B}checked\n";
+        let cleaned = clean_report_note(content);
+        assert_eq!(cleaned, "checked");
+        assert!(validate_report_note(cleaned).is_ok());
+    }
+}
@@ -0,0 +1,35 @@
+use super::errors::ValidationError;
+
+const RULE_TEXT_LENGTH_MAX: usize = 1000;
+const RULE_HINT_LENGTH_MAX: usize = 1000;
+
+pub fn validate_server_rule(
+    rule_text: &str,
+    rule_hint: &str,
+) -> Result<(), ValidationError> {
+    if rule_text.trim().is_empty() {
+        return Err(ValidationError("rule text is empty"));
+    };
+    if rule_text.chars().count() > RULE_TEXT_LENGTH_MAX {
+        return Err(ValidationError("rule text is too long"));
+    };
+    if rule_hint.chars().count() > RULE_HINT_LENGTH_MAX {
+        return Err(ValidationError("rule hint is too long"));
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_server_rule() {
+        assert!(validate_server_rule("No spam", "").is_ok());
+        assert!(validate_server_rule("Be nice", "To everyone").is_ok());
+        assert!(validate_server_rule(" ", "").is_err());
+        let long_text = "a".repeat(1001);
+        assert!(validate_server_rule(&long_text, "").is_err());
+        assert!(validate_server_rule("No spam", &long_text).is_err());
+    }
+}
@@ -1,5 +1,8 @@
+use std::cmp::min;
+
 use apx_sdk::addresses::WebfingerAddress;
 use chrono::Utc;
+use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -11,6 +14,7 @@ use mitra_activitypub::{
     authority::Authority,
     builders::{
         move_person::prepare_move_person,
+        reject_follow::prepare_reject_follow,
         undo_follow::prepare_undo_follow,
     },
     errors::HandlerError,
@@ -20,13 +24,32 @@ use mitra_activitypub::{
         ApClient,
     },
 };
-use mitra_config::Config;
+use mitra_config::{Config, Instance};
 use mitra_models::{
-    accounts::queries::get_user_by_id,
+    accounts::{
+        queries::get_user_by_id,
+        types::User,
+    },
     background_jobs::{
         queries::enqueue_job,
         types::JobType,
     },
+    data_imports::{
+        queries::{
+            create_data_import,
+            fail_data_import,
+            finish_data_import,
+            get_data_import_by_id,
+            update_data_import_entries,
+        },
+        types::{
+            DataImport,
+            ImportEntries,
+            ImportOutcome,
+            ImportStatus,
+            ImportType,
+        },
+    },
     database::{
         db_client_await,
         get_database_client,
@@ -37,16 +60,24 @@ use mitra_models::{
     notifications::helpers::create_move_notification,
     profiles::{
         queries::get_remote_profile_by_actor_id,
+        types::DbActorProfile,
     },
     relationships::{
-        helpers::create_follow_request,
-        queries::unfollow,
+        helpers::{create_follow_request, remove_follower},
+        queries::{has_relationship, mute, unfollow},
+        types::RelationshipType,
     },
 };
 
+// Maximum number of concurrent webfinger lookups
+const IMPORTER_POOL_SIZE: usize = 5;
+// Number of entries processed before progress is saved
+const IMPORT_BATCH_SIZE: usize = 20;
+
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ImporterJobData {
+    // Legacy follows import (without status reporting)
     Follows {
         user_id: Uuid,
         address_list: Vec<String>,
@@ -56,9 +87,27 @@ pub enum ImporterJobData {
         from_actor_id: String,
         address_list: Vec<String>,
     },
+    Mutes {
+        import_id: Uuid,
+    },
+    Blocks {
+        import_id: Uuid,
+    },
+    Following {
+        import_id: Uuid,
+    },
 }
 
 impl ImporterJobData {
+    pub fn for_data_import(data_import: &DataImport) -> Self {
+        let import_id = data_import.id;
+        match data_import.import_type {
+            ImportType::Mutes => Self::Mutes { import_id },
+            ImportType::Blocks => Self::Blocks { import_id },
+            ImportType::Follows => Self::Following { import_id },
+        }
+    }
+
     pub async fn into_job(
         self,
         db_client: &impl DatabaseClient,
@@ -76,47 +125,21 @@ impl ImporterJobData {
     }
 }
 
+/// Runs follows import job created before the introduction of import status
+/// reporting. Job is converted into a data import.
 pub async fn import_follows_task(
     config: &Config,
     db_pool: &DatabaseConnectionPool,
     user_id: Uuid,
     address_list: Vec<String>,
 ) -> Result<(), anyhow::Error> {
-    let user = get_user_by_id(
+    let data_import = create_data_import(
         db_client_await!(db_pool),
         user_id,
+        ImportType::Follows,
+        address_list,
     ).await?;
-    let ap_client = ApClient::new_with_pool(config, db_pool).await?;
-    for webfinger_address in address_list {
-        let webfinger_address: WebfingerAddress = webfinger_address.parse()?;
-        let profile = match get_or_import_actor_by_webfinger_address(
-            &ap_client,
-            db_pool,
-            &webfinger_address,
-        ).await {
-            Ok(profile) => profile,
-            Err(error) if is_actor_importer_error(&error) => {
-                log::warn!(
-                    "failed to import profile {}: {}",
-                    webfinger_address,
-                    error,
-                );
-                continue;
-            },
-            Err(other_error) => return Err(other_error.into()),
-        };
-        if profile.id == user.id {
-            continue;
-        };
-        let db_client = &mut **get_database_client(db_pool).await?;
-        follow_or_create_request(
-            db_client,
-            &config.instance(),
-            &user,
-            &profile,
-        ).await?;
-    };
-    Ok(())
+    import_data_task(config, db_pool, data_import.id).await
 }
 
 pub async fn import_followers_task(
@@ -224,3 +247,319 @@ pub async fn import_followers_task(
     ).save_and_enqueue(db_client).await?;
     Ok(())
 }
+
+/// Resolves webfinger addresses concurrently.
+/// Returns import outcome for addresses that could not be resolved.
+async fn resolve_address_list(
+    ap_client: &ApClient,
+    db_pool: &DatabaseConnectionPool,
+    address_list: Vec<String>,
+) -> Vec<Result<DbActorProfile, ImportOutcome>> {
+    stream::iter(address_list)
+        .map(|address| async move {
+            let Ok(webfinger_address) = address.parse::<WebfingerAddress>() else {
+                return Err(ImportOutcome::Invalid);
+            };
+            match get_or_import_actor_by_webfinger_address(
+                ap_client,
+                db_pool,
+                &webfinger_address,
+            ).await {
+                Ok(profile) => Ok(profile),
+                Err(error) if is_actor_importer_error(&error) => {
+                    log::warn!(
+                        "failed to import profile {}: {}",
+                        webfinger_address,
+                        error,
+                    );
+                    Err(ImportOutcome::NotFound)
+                },
+                Err(other_error) => {
+                    log::error!(
+                        "failed to import profile {}: {}",
+                        webfinger_address,
+                        other_error,
+                    );
+                    Err(ImportOutcome::Failed)
+                },
+            }
+        })
+        // Preserves the order of entries
+        .buffered(IMPORTER_POOL_SIZE)
+        .collect()
+        .await
+}
+
+async fn import_entry(
+    instance: &Instance,
+    db_client: &mut impl DatabaseClient,
+    user: &User,
+    import_type: ImportType,
+    profile: &DbActorProfile,
+) -> Result<ImportOutcome, anyhow::Error> {
+    if profile.id == user.id {
+        return Ok(ImportOutcome::Invalid);
+    };
+    let outcome = match import_type {
+        ImportType::Mutes => {
+            match mute(db_client, user.id, profile.id).await {
+                Ok(()) => ImportOutcome::Imported,
+                Err(DatabaseError::AlreadyExists(_)) => {
+                    ImportOutcome::AlreadyPresent
+                },
+                Err(other_error) => return Err(other_error.into()),
+            }
+        },
+        ImportType::Blocks => {
+            // Blocked account is muted and removed from followers
+            let is_muted = match mute(db_client, user.id, profile.id).await {
+                Ok(()) => true,
+                Err(DatabaseError::AlreadyExists(_)) => false,
+                Err(other_error) => return Err(other_error.into()),
+            };
+            let is_removed = match remove_follower(
+                db_client,
+                profile.id,
+                user.id,
+            ).await {
+                Ok(maybe_follow_activity_id) => {
+                    if let (Some(remote_actor), Some(follow_activity_id)) =
+                        (profile.actor_json.as_ref(), maybe_follow_activity_id)
+                    {
+                        prepare_reject_follow(
+                            instance,
+                            user,
+                            remote_actor,
+                            &follow_activity_id,
+                        )?.save_and_enqueue(db_client).await?;
+                    };
+                    true
+                },
+                // Not a follower
+                Err(DatabaseError::NotFound(_)) => false,
+                Err(other_error) => return Err(other_error.into()),
+            };
+            if is_muted || is_removed {
+                ImportOutcome::Imported
+            } else {
+                ImportOutcome::AlreadyPresent
+            }
+        },
+        ImportType::Follows => {
+            if has_relationship(
+                db_client,
+                user.id,
+                profile.id,
+                RelationshipType::Follow,
+            ).await? {
+                ImportOutcome::AlreadyPresent
+            } else {
+                // Sends Follow activity to remote actors
+                follow_or_create_request(
+                    db_client,
+                    instance,
+                    user,
+                    profile,
+                ).await?;
+                ImportOutcome::Imported
+            }
+        },
+    };
+    Ok(outcome)
+}
+
+/// Processes pending entries in batches and saves progress after each batch
+async fn process_data_import(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+    data_import: DataImport,
+) -> Result<(), anyhow::Error> {
+    let user = get_user_by_id(
+        db_client_await!(db_pool),
+        data_import.owner_id,
+    ).await?;
+    let instance = config.instance();
+    let ap_client = ApClient::new_with_pool(config, db_pool).await?;
+    let mut entries = data_import.entries.into_inner();
+    for batch_start in (0..entries.len()).step_by(IMPORT_BATCH_SIZE) {
+        let batch_end = min(batch_start + IMPORT_BATCH_SIZE, entries.len());
+        // Entries processed before restart are skipped
+        let pending: Vec<usize> = (batch_start..batch_end)
+            .filter(|index| entries[*index].outcome.is_none())
+            .collect();
+        if pending.is_empty() {
+            continue;
+        };
+        let address_list = pending.iter()
+            .map(|index| entries[*index].address.clone())
+            .collect();
+        let profiles = resolve_address_list(
+            &ap_client,
+            db_pool,
+            address_list,
+        ).await;
+        let db_client = &mut **get_database_client(db_pool).await?;
+        for (index, maybe_profile) in pending.into_iter().zip(profiles) {
+            let outcome = match maybe_profile {
+                Ok(profile) => match import_entry(
+                    &instance,
+                    db_client,
+                    &user,
+                    data_import.import_type,
+                    &profile,
+                ).await {
+                    Ok(outcome) => outcome,
+                    Err(error) => {
+                        log::error!(
+                            "failed to import entry {}: {}",
+                            entries[index].address,
+                            error,
+                        );
+                        ImportOutcome::Failed
+                    },
+                },
+                Err(outcome) => outcome,
+            };
+            entries[index].outcome = Some(outcome);
+        };
+        update_data_import_entries(
+            db_client,
+            data_import.id,
+            ImportEntries::new(entries.clone()),
+        ).await?;
+    };
+    finish_data_import(
+        db_client_await!(db_pool),
+        data_import.id,
+        ImportEntries::new(entries),
+    ).await?;
+    Ok(())
+}
+
+/// Runs mutes, blocks or follows import.
+/// Import is marked as failed if it can not be completed.
+pub async fn import_data_task(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+    import_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    let data_import = get_data_import_by_id(
+        db_client_await!(db_pool),
+        import_id,
+    ).await?;
+    if data_import.import_status != ImportStatus::Pending {
+        return Ok(());
+    };
+    match process_data_import(config, db_pool, data_import).await {
+        Ok(()) => log::info!("data import {import_id} completed"),
+        Err(error) => {
+            log::error!("data import {import_id} failed: {error}");
+            fail_data_import(db_client_await!(db_pool), import_id).await?;
+        },
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use mitra_models::{
+        accounts::test_utils::create_test_user,
+        database::test_utils::create_test_database,
+    };
+    use super::*;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_importer_job_data_for_data_import() {
+        let db_client = &mut create_test_database().await;
+        let user = create_test_user(db_client, "test").await;
+        let data_import = create_data_import(
+            db_client,
+            user.id,
+            ImportType::Mutes,
+            vec!["user@social.example".to_string()],
+        ).await.unwrap();
+        let job_data = ImporterJobData::for_data_import(&data_import);
+        let job_data_value = serde_json::to_value(job_data).unwrap();
+        assert_eq!(job_data_value["type"], "Mutes");
+        assert_eq!(job_data_value["import_id"], data_import.id.to_string());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_import_entry_mutes() {
+        let db_client = &mut create_test_database().await;
+        let instance = Instance::for_test("https://social.example");
+        let user = create_test_user(db_client, "test").await;
+        let target = create_test_user(db_client, "target").await;
+        let outcome = import_entry(
+            &instance,
+            db_client,
+            &user,
+            ImportType::Mutes,
+            &target.profile,
+        ).await.unwrap();
+        assert_eq!(outcome, ImportOutcome::Imported);
+
+        // Import again
+        let outcome = import_entry(
+            &instance,
+            db_client,
+            &user,
+            ImportType::Mutes,
+            &target.profile,
+        ).await.unwrap();
+        assert_eq!(outcome, ImportOutcome::AlreadyPresent);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_import_entry_follows() {
+        let db_client = &mut create_test_database().await;
+        let instance = Instance::for_test("https://social.example");
+        let user = create_test_user(db_client, "test").await;
+        let target = create_test_user(db_client, "target").await;
+        let outcome = import_entry(
+            &instance,
+            db_client,
+            &user,
+            ImportType::Follows,
+            &target.profile,
+        ).await.unwrap();
+        assert_eq!(outcome, ImportOutcome::Imported);
+        let is_following = has_relationship(
+            db_client,
+            user.id,
+            target.id,
+            RelationshipType::Follow,
+        ).await.unwrap();
+        assert_eq!(is_following, true);
+
+        // Import again
+        let outcome = import_entry(
+            &instance,
+            db_client,
+            &user,
+            ImportType::Follows,
+            &target.profile,
+        ).await.unwrap();
+        assert_eq!(outcome, ImportOutcome::AlreadyPresent);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_import_entry_self() {
+        let db_client = &mut create_test_database().await;
+        let instance = Instance::for_test("https://social.example");
+        let user = create_test_user(db_client, "test").await;
+        let outcome = import_entry(
+            &instance,
+            db_client,
+            &user,
+            ImportType::Blocks,
+            &user.profile,
+        ).await.unwrap();
+        assert_eq!(outcome, ImportOutcome::Invalid);
+    }
+}
@@ -1,4 +1,6 @@
 pub mod importer;
 mod payments;
 mod periodic_tasks;
+mod push;
+pub mod scheduler;
 pub mod workers;
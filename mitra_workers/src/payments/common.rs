@@ -1,3 +1,5 @@
+use chrono::{TimeDelta, Utc};
+
 use mitra_activitypub::{
     builders::remove_person::prepare_remove_subscriber,
 };
@@ -12,18 +14,61 @@ use mitra_models::{
     notifications::helpers::{
         create_subscriber_leaving_notification,
         create_subscription_expiration_notification,
+        create_subscription_reminder_notification,
     },
     profiles::queries::get_profile_by_id,
     relationships::queries::unsubscribe,
-    subscriptions::queries::get_expired_subscriptions,
+    subscriptions::queries::{
+        get_expired_subscriptions,
+        get_expiring_subscriptions,
+        set_subscription_reminder_sent,
+    },
 };
 
+pub async fn send_subscription_reminders(
+    db_pool: &DatabaseConnectionPool,
+    reminder_days: u32,
+) -> Result<(), DatabaseError> {
+    if reminder_days == 0 {
+        return Ok(());
+    };
+    let db_client = &mut **get_database_client(db_pool).await?;
+    let expires_before = Utc::now() + TimeDelta::days(reminder_days.into());
+    for subscription in get_expiring_subscriptions(db_client, expires_before).await? {
+        let sender = get_profile_by_id(db_client, subscription.sender_id).await?;
+        log::info!(
+            "subscription expires soon: {0} to {1}",
+            sender,
+            subscription.recipient_id,
+        );
+        if sender.is_local() {
+            create_subscription_reminder_notification(
+                db_client,
+                subscription.recipient_id,
+                subscription.sender_id,
+            ).await?;
+        };
+        if !sender.is_anonymous() {
+            create_subscription_reminder_notification(
+                db_client,
+                subscription.sender_id,
+                subscription.recipient_id,
+            ).await?;
+        };
+        set_subscription_reminder_sent(db_client, subscription.id).await?;
+    };
+    Ok(())
+}
+
 pub async fn update_expired_subscriptions(
     instance: &Instance,
     db_pool: &DatabaseConnectionPool,
+    grace_period: u32,
 ) -> Result<(), DatabaseError> {
     let db_client = &mut **get_database_client(db_pool).await?;
-    for subscription in get_expired_subscriptions(db_client).await? {
+    // Subscribers are removed when grace period ends
+    let expired_before = Utc::now() - TimeDelta::days(grace_period.into());
+    for subscription in get_expired_subscriptions(db_client, expired_before).await? {
         // Remove relationship
         unsubscribe(db_client, subscription.sender_id, subscription.recipient_id).await?;
         let sender = get_profile_by_id(db_client, subscription.sender_id).await?;
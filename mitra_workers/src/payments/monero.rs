@@ -43,6 +43,7 @@ use mitra_models::{
             local_monero_light_invoice_paid,
         },
         queries::{
+            create_invoice_payout,
             create_local_invoice,
             get_invoice_by_id,
             get_local_invoice_by_address,
@@ -73,6 +74,7 @@ use mitra_services::monero::{
         get_transaction_by_id,
         open_monero_wallet,
         send_monero,
+        send_monero_split,
         MoneroError,
         TransferCategory,
         WalletClient,
@@ -271,13 +273,37 @@ async fn check_paid_invoices(
             );
             continue;
         };
-        // Send all available balance to payout address
-        let (payout_tx_id, _) = match send_monero(
-            &wallet_client_delay_tolerant,
-            address_index.major,
-            address_index.minor,
-            &payment_method.payout_address,
-        ).await {
+        // Shares of additional payout addresses
+        let payout_shares: Vec<_> = payment_method.payout_splits.inner().iter()
+            .map(|split| {
+                let amount = u128::from(balance_data.unlocked_balance.as_pico()) *
+                    u128::from(split.percent.min(100)) / 100;
+                let amount = u64::try_from(amount)
+                    .expect("share should not exceed balance");
+                (split.payout_address.clone(), amount)
+            })
+            .filter(|(_, amount)| *amount > 0)
+            .collect();
+        let send_result = if payout_shares.is_empty() {
+            // Send all available balance to payout address
+            send_monero(
+                &wallet_client_delay_tolerant,
+                address_index.major,
+                address_index.minor,
+                &payment_method.payout_address,
+            ).await
+        } else {
+            // Divide available balance between payout addresses
+            send_monero_split(
+                &wallet_client_delay_tolerant,
+                address_index.major,
+                address_index.minor,
+                balance_data.unlocked_balance,
+                &payment_method.payout_address,
+                &payout_shares,
+            ).await
+        };
+        let (payout_tx_id, _) = match send_result {
             Ok(payout_info) => payout_info,
             Err(error @ MoneroError::Dust) => {
                 log::warn!("invoice {}: {}", invoice.id, error);
@@ -298,6 +324,15 @@ async fn check_paid_invoices(
             },
         };
 
+        for (payout_address, amount) in payout_shares {
+            create_invoice_payout(
+                db_client,
+                invoice.id,
+                &payout_address,
+                amount,
+                &payout_tx_id,
+            ).await?;
+        };
         local_invoice_forwarded(
             db_client,
             invoice.id,
@@ -1,10 +1,17 @@
 use std::time::Instant;
 
 use anyhow::Error;
+use chrono::{TimeDelta, Utc};
 
-use mitra_activitypub::queues::{
-    process_queued_incoming_activities,
-    process_queued_outgoing_activities,
+use mitra_activitypub::{
+    adapters::{
+        custom_feeds::boost_custom_feed_posts,
+        feeds::{fetch_feed, publish_feed_entries},
+    },
+    queues::{
+        process_queued_incoming_activities,
+        process_queued_outgoing_activities,
+    },
 };
 use mitra_adapters::{
     media::delete_orphaned_media,
@@ -14,11 +21,16 @@ use mitra_config::Config;
 use mitra_models::{
     activitypub::queries::{
         delete_activitypub_objects,
+        delete_attributed_objects,
+        delete_expired_jrds,
+        delete_incoming_activities,
+        delete_outgoing_activity_records,
         delete_collection_items,
     },
     attachments::queries::delete_unused_attachments,
     background_jobs::{
         queries::{
+            delete_dead_letters,
             delete_job_from_queue,
             get_job_batch,
         },
@@ -29,31 +41,49 @@ use mitra_models::{
         get_database_client,
         DatabaseConnectionPool,
     },
+    custom_feeds::queries::get_custom_feed_actors,
     emojis::queries::{
         delete_emoji,
         find_unused_remote_emojis,
     },
+    feed_sources::queries::{
+        get_feed_sources_due,
+        set_feed_source_fetched,
+    },
     media::types::DeletionQueue,
     posts::{
-        queries::{delete_post, find_extraneous_posts},
+        queries::{
+            delete_post,
+            delete_post_content_hashes,
+            find_extraneous_posts,
+        },
         views::refresh_latest_post_view,
     },
+    push_subscriptions::types::PushJobData,
     profiles::queries::{
         delete_profile,
+        find_deleted_profiles,
         find_empty_profiles,
         get_profile_by_id,
     },
-    tags::queries::{delete_tag, find_unused_tags},
+    scheduled_posts::queries::take_due_scheduled_posts,
+    tags::queries::{delete_tag, delete_tag_usage, find_unused_tags},
 };
 use mitra_utils::datetime::days_before_now;
 
 use super::importer::{
+    import_data_task,
     import_followers_task,
     import_follows_task,
     ImporterJobData,
 };
+use super::push::push_notification_task;
+use super::scheduler::publish_scheduled_post;
 use super::payments::{
-    common::update_expired_subscriptions,
+    common::{
+        send_subscription_reminders,
+        update_expired_subscriptions,
+    },
     monero::{
         check_closed_monero_invoices,
         check_monero_invoices,
@@ -66,9 +96,14 @@ pub async fn subscription_expiration_monitor(
     config: &Config,
     db_pool: &DatabaseConnectionPool,
 ) -> Result<(), Error> {
+    send_subscription_reminders(
+        db_pool,
+        config.subscriptions.reminder_days,
+    ).await?;
     update_expired_subscriptions(
         &config.instance(),
         db_pool,
+        config.subscriptions.grace_period,
     ).await?;
     Ok(())
 }
@@ -140,6 +175,27 @@ pub async fn delete_empty_profiles(
     Ok(())
 }
 
+pub async fn delete_tombstoned_profiles(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+) -> Result<(), Error> {
+    let deleted_before = match config.retention.deleted_profiles {
+        Some(days) => days_before_now(days),
+        None => return Ok(()), // not configured
+    };
+    let profiles = find_deleted_profiles(
+        db_client_await!(db_pool),
+        deleted_before,
+    ).await?;
+    for profile_id in profiles {
+        let db_client = &mut **get_database_client(db_pool).await?;
+        let deletion_queue = delete_profile(db_client, profile_id).await?;
+        delete_orphaned_media(config, db_client, deletion_queue).await?;
+        log::info!("deleted tombstoned profile {}", profile_id);
+    };
+    Ok(())
+}
+
 pub async fn prune_tags(
     _config: &Config,
     db_pool: &DatabaseConnectionPool,
@@ -150,6 +206,13 @@ pub async fn prune_tags(
         delete_tag(db_client, &tag_name).await?;
         log::info!("deleted unused tag {tag_name}");
     };
+    // Usage records are only needed for trends and tag history
+    const TAG_USAGE_EXPIRATION_DAYS: u32 = 7;
+    let created_before = days_before_now(TAG_USAGE_EXPIRATION_DAYS);
+    let deleted_count = delete_tag_usage(db_client, created_before).await?;
+    if deleted_count > 0 {
+        log::info!("deleted {deleted_count} tag usage records");
+    };
     Ok(())
 }
 
@@ -158,7 +221,7 @@ pub async fn prune_remote_emojis(
     db_pool: &DatabaseConnectionPool,
 ) -> Result<(), Error> {
     let db_client = &**get_database_client(db_pool).await?;
-    let emojis = find_unused_remote_emojis(db_client).await?;
+    let emojis = find_unused_remote_emojis(db_client, None).await?;
     for emoji_id in emojis {
         let deletion_queue = delete_emoji(db_client, emoji_id).await?;
         delete_orphaned_media(config, db_client, deletion_queue).await?;
@@ -189,11 +252,58 @@ pub async fn prune_activitypub_objects(
     config: &Config,
     db_pool: &DatabaseConnectionPool,
 ) -> Result<(), Error> {
+    let db_client = &**get_database_client(db_pool).await?;
+    let deleted_count = delete_expired_jrds(db_client).await?;
+    if deleted_count > 0 {
+        log::info!("deleted {deleted_count} expired JRDs");
+    };
+    // Stored activities are deleted even if storage has been disabled
+    let incoming_activities_created_before =
+        match config.retention.incoming_activities {
+            Some(days) => days_before_now(days),
+            None => Utc::now(),
+        };
+    let deleted_count = delete_incoming_activities(
+        db_client,
+        incoming_activities_created_before,
+    ).await?;
+    if deleted_count > 0 {
+        log::info!("deleted {deleted_count} incoming activities");
+    };
+    if let Some(days) = config.retention.outgoing_activities {
+        let deleted_count = delete_outgoing_activity_records(
+            db_client,
+            days_before_now(days),
+        ).await?;
+        if deleted_count > 0 {
+            log::info!("deleted {deleted_count} outgoing activity records");
+        };
+    };
+    if let Some(days) = config.retention.dead_letters {
+        let deleted_count = delete_dead_letters(
+            db_client,
+            Some(days_before_now(days)),
+        ).await?;
+        if deleted_count > 0 {
+            log::info!("deleted {deleted_count} dead letters");
+        };
+    };
+    let attributed_objects_deleted_before =
+        match config.retention.attributed_objects {
+            Some(days) => days_before_now(days),
+            None => Utc::now(),
+        };
+    let deleted_count = delete_attributed_objects(
+        db_client,
+        attributed_objects_deleted_before,
+    ).await?;
+    if deleted_count > 0 {
+        log::info!("deleted {deleted_count} attributed objects");
+    };
     let created_before = match config.retention.activitypub_objects {
         Some(days) => days_before_now(days),
         None => return Ok(()), // not configured
     };
-    let db_client = &**get_database_client(db_pool).await?;
     let deleted_count =
         delete_activitypub_objects(db_client, created_before).await?;
     if deleted_count > 0 {
@@ -202,6 +312,23 @@ pub async fn prune_activitypub_objects(
     Ok(())
 }
 
+pub async fn prune_dead_letters(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+) -> Result<(), Error> {
+    let created_before = match config.retention.dead_letters {
+        Some(days) => days_before_now(days),
+        None => return Ok(()), // not configured
+    };
+    let db_client = &**get_database_client(db_pool).await?;
+    let deleted_count =
+        delete_dead_letters(db_client, Some(created_before)).await?;
+    if deleted_count > 0 {
+        log::info!("deleted {deleted_count} dead letters");
+    };
+    Ok(())
+}
+
 pub async fn prune_activitypub_collection_items(
     _config: &Config,
     db_pool: &DatabaseConnectionPool,
@@ -217,6 +344,21 @@ pub async fn prune_activitypub_collection_items(
     Ok(())
 }
 
+pub async fn prune_post_content_hashes(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+) -> Result<(), Error> {
+    let db_client = &**get_database_client(db_pool).await?;
+    let window = config.limits().duplicate_posts.window;
+    let created_before = Utc::now() - TimeDelta::seconds(window.into());
+    let deleted_count =
+        delete_post_content_hashes(db_client, created_before).await?;
+    if deleted_count > 0 {
+        log::info!("deleted {deleted_count} post content hashes");
+    };
+    Ok(())
+}
+
 pub async fn media_cleanup_queue_executor(
     config: &Config,
     db_pool: &DatabaseConnectionPool,
@@ -280,6 +422,11 @@ pub async fn importer_queue_executor(
                     address_list,
                 ).await?;
             },
+            ImporterJobData::Mutes { import_id } |
+            ImporterJobData::Blocks { import_id } |
+            ImporterJobData::Following { import_id } => {
+                import_data_task(config, db_pool, import_id).await?;
+            },
         };
         let db_client = &**get_database_client(db_pool).await?;
         delete_job_from_queue(db_client, job.id).await?;
@@ -287,6 +434,71 @@ pub async fn importer_queue_executor(
     Ok(())
 }
 
+pub async fn push_notification_queue_executor(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+) -> Result<(), Error> {
+    const BATCH_SIZE: u32 = 20;
+    const JOB_TIMEOUT: u32 = 600; // 10 minutes
+    let batch = get_job_batch(
+        db_client_await!(db_pool),
+        JobType::PushNotification,
+        BATCH_SIZE,
+        JOB_TIMEOUT,
+    ).await?;
+    const RETRIES_MAX: u32 = 2;
+    const RETRY_DELAY: u32 = 600; // 10 minutes
+    for job in batch {
+        let maybe_job_data: Result<PushJobData, _> =
+            serde_json::from_value(job.job_data);
+        match maybe_job_data {
+            Ok(mut job_data) => {
+                let result =
+                    push_notification_task(config, db_pool, &job_data).await;
+                if let Err(error) = result {
+                    job_data.failure_count += 1;
+                    log::warn!(
+                        "failed to process push notification job (attempt #{}): {}",
+                        job_data.failure_count,
+                        error,
+                    );
+                    if job_data.failure_count <= RETRIES_MAX {
+                        let db_client = &**get_database_client(db_pool).await?;
+                        job_data.into_job(db_client, RETRY_DELAY).await?;
+                    };
+                };
+            },
+            Err(error) => {
+                log::error!("invalid push notification job data: {error}");
+            },
+        };
+        let db_client = &**get_database_client(db_pool).await?;
+        delete_job_from_queue(db_client, job.id).await?;
+    };
+    Ok(())
+}
+
+pub async fn scheduled_post_publisher(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+) -> Result<(), Error> {
+    const BATCH_SIZE: u32 = 10;
+    const PUBLICATION_TIMEOUT: u32 = 3600; // 1 hour
+    let scheduled_posts = take_due_scheduled_posts(
+        db_client_await!(db_pool),
+        BATCH_SIZE,
+        PUBLICATION_TIMEOUT,
+    ).await?;
+    for scheduled_post in scheduled_posts {
+        let scheduled_post_id = scheduled_post.id;
+        publish_scheduled_post(config, db_pool, scheduled_post).await
+            .unwrap_or_else(|error| {
+                log::error!("failed to publish scheduled post {scheduled_post_id}: {error}");
+            });
+    };
+    Ok(())
+}
+
 pub async fn monero_payment_monitor(
     config: &Config,
     db_pool: &DatabaseConnectionPool,
@@ -360,3 +572,82 @@ pub async fn remote_invoice_monitor(
     check_open_remote_invoices(db_pool).await?;
     Ok(())
 }
+
+pub async fn feed_poller(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+) -> Result<(), Error> {
+    let instance = config.instance();
+    let sources = get_feed_sources_due(db_client_await!(db_pool)).await?;
+    for source in sources {
+        // Database connection is not held while fetching
+        let feed = match fetch_feed(&instance, &source.source.feed_url).await {
+            Ok(feed) => feed,
+            Err(error) => {
+                log::warn!(
+                    "failed to fetch feed {}: {}",
+                    source.source.feed_url,
+                    error,
+                );
+                // Wait until the next interval before trying again
+                set_feed_source_fetched(
+                    db_client_await!(db_pool),
+                    source.account.id,
+                ).await?;
+                continue;
+            },
+        };
+        let db_client = &mut **get_database_client(db_pool).await?;
+        match publish_feed_entries(config, db_client, &source, feed).await {
+            Ok(post_count) => {
+                log::info!(
+                    "fetched feed {}: {} new posts",
+                    source.source.feed_url,
+                    post_count,
+                );
+            },
+            Err(error) => {
+                log::warn!(
+                    "failed to process feed {}: {}",
+                    source.source.feed_url,
+                    error,
+                );
+                set_feed_source_fetched(db_client, source.account.id).await?;
+            },
+        };
+    };
+    Ok(())
+}
+
+pub async fn custom_feed_publisher(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+) -> Result<(), Error> {
+    let instance = config.instance();
+    let feed_actors = get_custom_feed_actors(db_client_await!(db_pool)).await?;
+    for feed_actor in feed_actors {
+        let db_client = &mut **get_database_client(db_pool).await?;
+        match boost_custom_feed_posts(
+            &instance,
+            db_client,
+            &feed_actor,
+        ).await {
+            Ok(repost_count) if repost_count > 0 => {
+                log::info!(
+                    "custom feed {}: {} new reposts",
+                    feed_actor.account.profile.username,
+                    repost_count,
+                );
+            },
+            Ok(_) => (),
+            Err(error) => {
+                log::warn!(
+                    "failed to process custom feed {}: {}",
+                    feed_actor.account.profile.username,
+                    error,
+                );
+            },
+        };
+    };
+    Ok(())
+}
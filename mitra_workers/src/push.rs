@@ -0,0 +1,184 @@
+use apx_sdk::{
+    agent::FederationAgent,
+    core::url::http_url_whatwg::get_hostname,
+    fetch::{create_http_client, require_safe_url, RedirectAction},
+};
+use serde::Serialize;
+
+use mitra_activitypub::agent::build_federation_agent;
+use mitra_config::Config;
+use mitra_models::{
+    database::{
+        get_database_client,
+        DatabaseClient,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    notifications::{
+        queries::get_notifications,
+        types::{EventType, NotificationDetailed},
+    },
+    push_subscriptions::{
+        queries::{delete_push_subscription_by_id, get_push_subscriptions},
+        types::{PushJobData, PushPolicy, PushSubscription},
+    },
+    relationships::{
+        queries::has_relationship,
+        types::RelationshipType,
+    },
+};
+use mitra_services::push::{send_push_message, PushError};
+use mitra_utils::html::extract_title;
+
+const PUSH_MESSAGE_BODY_LENGTH: usize = 140;
+const PUSH_REQUEST_TIMEOUT: u64 = 30; // seconds
+
+// https://docs.joinmastodon.org/methods/push/#receiving-push-notifications
+#[derive(Serialize)]
+struct PushMessage {
+    notification_id: String,
+    notification_type: &'static str,
+    title: String,
+    body: String,
+}
+
+impl PushMessage {
+    fn new(notification: &NotificationDetailed) -> Self {
+        let sender_name = notification.sender.display_name.clone()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(notification.sender.username.clone());
+        let (notification_type, action) = match notification.event_type {
+            EventType::Follow => ("follow", "followed you"),
+            EventType::FollowRequest => ("follow_request", "requested to follow you"),
+            EventType::Reply => ("mention", "replied to your post"),
+            EventType::Reaction => ("favourite", "reacted to your post"),
+            EventType::Mention => ("mention", "mentioned you"),
+            EventType::Repost => ("reblog", "reposted your post"),
+            EventType::SubscriberPayment => ("subscription", "paid for subscription"),
+            EventType::SubscriptionStart => ("subscription", "subscribed to you"),
+            EventType::SubscriptionExpiration => ("subscription_expiration", "subscription expired"),
+            EventType::SubscriberLeaving => ("subscriber_leaving", "unsubscribed from you"),
+            EventType::SubscriptionReminder => ("subscription_reminder", "subscription expires soon"),
+            EventType::Post => ("status", "published a new post"),
+            EventType::StorageQuotaWarning => ("storage_quota_warning", "storage quota is almost exceeded"),
+            EventType::View => ("view", "viewed your post"),
+            EventType::Move => ("move", "moved to a new account"),
+            EventType::SignUp => ("admin.sign_up", "signed up"),
+            EventType::Report => ("admin.report", "filed a report"),
+        };
+        let body = notification.post.as_ref()
+            .map(|post| extract_title(&post.content, PUSH_MESSAGE_BODY_LENGTH))
+            .unwrap_or_default();
+        Self {
+            notification_id: notification.id.to_string(),
+            notification_type,
+            title: format!("{sender_name} {action}"),
+            body,
+        }
+    }
+}
+
+async fn is_allowed_by_policy(
+    db_client: &impl DatabaseClient,
+    subscription: &PushSubscription,
+    notification: &NotificationDetailed,
+) -> Result<bool, DatabaseError> {
+    let is_allowed = match subscription.policy {
+        PushPolicy::All => true,
+        PushPolicy::Followed => has_relationship(
+            db_client,
+            subscription.owner_id,
+            notification.sender.id,
+            RelationshipType::Follow,
+        ).await?,
+        PushPolicy::Follower => has_relationship(
+            db_client,
+            notification.sender.id,
+            subscription.owner_id,
+            RelationshipType::Follow,
+        ).await?,
+        PushPolicy::None => false,
+    };
+    Ok(is_allowed)
+}
+
+async fn deliver_push_message(
+    config: &Config,
+    agent: &FederationAgent,
+    subscription: &PushSubscription,
+    payload: &[u8],
+) -> Result<(), PushError> {
+    // Endpoint is provided by the client
+    if agent.ssrf_protection_enabled {
+        require_safe_url(&subscription.endpoint)
+            .map_err(|_| PushError::InvalidEndpoint)?;
+    };
+    let hostname = get_hostname(&subscription.endpoint)
+        .map_err(|_| PushError::InvalidEndpoint)?;
+    let client = create_http_client(
+        agent,
+        &hostname,
+        PUSH_REQUEST_TIMEOUT,
+        RedirectAction::None,
+    )?;
+    send_push_message(
+        &client,
+        &config.instance_vapid_key(),
+        config.instance().uri_str(),
+        &subscription.endpoint,
+        &subscription.public_key,
+        &subscription.auth_secret,
+        payload,
+    ).await
+}
+
+pub async fn push_notification_task(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+    job_data: &PushJobData,
+) -> Result<(), DatabaseError> {
+    let db_client = &**get_database_client(db_pool).await?;
+    let maybe_notification = get_notifications(
+        db_client,
+        job_data.recipient_id,
+        Some(job_data.notification_id - 1),
+        Some(job_data.notification_id + 1),
+        1,
+    ).await?
+        .into_iter()
+        .find(|notification| notification.id == job_data.notification_id);
+    let Some(notification) = maybe_notification else {
+        // Notification has been deleted or sender is muted
+        return Ok(());
+    };
+    let message = PushMessage::new(&notification);
+    let payload = serde_json::to_vec(&message)
+        .expect("message should be serializable");
+    let subscriptions =
+        get_push_subscriptions(db_client, job_data.recipient_id).await?;
+    let agent = build_federation_agent(&config.instance(), None);
+    for subscription in subscriptions {
+        if !subscription.alerts.is_enabled(notification.event_type) {
+            continue;
+        };
+        if !is_allowed_by_policy(db_client, &subscription, &notification).await? {
+            continue;
+        };
+        match deliver_push_message(
+            config,
+            &agent,
+            &subscription,
+            &payload,
+        ).await {
+            Ok(()) => (),
+            Err(PushError::SubscriptionGone) => {
+                log::info!("push subscription expired: {}", subscription.endpoint);
+                delete_push_subscription_by_id(db_client, subscription.id).await?;
+            },
+            Err(error) => {
+                log::warn!("failed to send push message: {error}");
+            },
+        };
+    };
+    Ok(())
+}
@@ -0,0 +1,230 @@
+use uuid::Uuid;
+
+use mitra_activitypub::{
+    adapters::posts::publish_local_post,
+    builders::{
+        announce::prepare_announce,
+        undo_announce::prepare_undo_announce,
+    },
+};
+use mitra_adapters::posts::{check_duplicate_post, save_post_content_hash};
+use mitra_config::{Config, DuplicatePostAction};
+use mitra_models::{
+    accounts::queries::get_user_by_id,
+    database::{
+        get_database_client,
+        DatabaseClient,
+        DatabaseConnectionPool,
+        DatabaseError,
+    },
+    posts::{
+        helpers::can_create_post,
+        queries::{
+            create_post,
+            delete_repost,
+            get_post_by_id,
+            get_repost_by_author,
+        },
+        types::{PostCreateData, PostDetailed, RelatedPosts, Visibility},
+    },
+    scheduled_posts::{
+        queries::delete_scheduled_post,
+        types::{ScheduledPost, ScheduledPostData},
+    },
+};
+
+/// Creates repost of a scheduled post.
+/// Returns `None` if publication has been cancelled.
+async fn publish_scheduled_repost(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    owner_id: Uuid,
+    post_id: Uuid,
+    visibility: Visibility,
+    replace_existing: bool,
+) -> Result<Option<PostDetailed>, DatabaseError> {
+    let instance = config.instance();
+    let user = get_user_by_id(db_client, owner_id).await?;
+    if !can_create_post(&user) {
+        log::warn!("scheduled repost cancelled: permission denied");
+        return Ok(None);
+    };
+    let mut post = match get_post_by_id(db_client, post_id).await {
+        Ok(post) => post,
+        // Post has been deleted
+        Err(DatabaseError::NotFound(_)) => return Ok(None),
+        Err(other_error) => return Err(other_error),
+    };
+    if !post.is_public() {
+        log::warn!("scheduled repost cancelled: post is not public");
+        return Ok(None);
+    };
+    match get_repost_by_author(db_client, post.id, user.id).await {
+        Ok(repost) if replace_existing => {
+            delete_repost(db_client, repost.id).await?;
+            post.repost_count -= 1;
+            prepare_undo_announce(
+                db_client,
+                &instance,
+                &user,
+                &post,
+                &repost,
+            ).await?.save_and_enqueue(db_client).await?;
+        },
+        Ok(_) => {
+            log::info!("scheduled repost cancelled: post is already reposted");
+            return Ok(None);
+        },
+        Err(DatabaseError::NotFound(_)) => (),
+        Err(other_error) => return Err(other_error),
+    };
+    let repost_data = PostCreateData::repost(post.id, visibility, None);
+    let mut repost = match create_post(db_client, user.id, repost_data).await {
+        Ok(repost) => repost,
+        // Reposted while the task was running
+        Err(DatabaseError::AlreadyExists(_)) => return Ok(None),
+        Err(other_error) => return Err(other_error),
+    };
+    post.repost_count += 1;
+    repost.related_posts = Some(RelatedPosts {
+        repost_of: Some(Box::new(post)),
+        ..Default::default()
+    });
+    prepare_announce(
+        db_client,
+        &instance,
+        &user,
+        &repost,
+    ).await?.save_and_enqueue(db_client).await?;
+    Ok(Some(repost))
+}
+
+/// Creates post from scheduled post data.
+/// Returns `None` if publication has been cancelled.
+async fn publish_scheduled_post_data(
+    config: &Config,
+    db_client: &mut impl DatabaseClient,
+    owner_id: Uuid,
+    scheduled_post_data: ScheduledPostData,
+) -> Result<Option<PostDetailed>, DatabaseError> {
+    let user = get_user_by_id(db_client, owner_id).await?;
+    if !can_create_post(&user) {
+        log::warn!("scheduled post cancelled: permission denied");
+        return Ok(None);
+    };
+    let maybe_in_reply_to = match scheduled_post_data.in_reply_to_id {
+        Some(in_reply_to_id) => {
+            match get_post_by_id(db_client, in_reply_to_id).await {
+                Ok(in_reply_to) => Some(in_reply_to),
+                Err(DatabaseError::NotFound(_)) => {
+                    log::warn!("scheduled post cancelled: parent post has been deleted");
+                    return Ok(None);
+                },
+                Err(other_error) => return Err(other_error),
+            }
+        },
+        None => None,
+    };
+    let mut linked = vec![];
+    for post_id in scheduled_post_data.links.iter() {
+        match get_post_by_id(db_client, *post_id).await {
+            Ok(post) => linked.push(post),
+            Err(DatabaseError::NotFound(_)) => {
+                log::warn!("scheduled post cancelled: linked post has been deleted");
+                return Ok(None);
+            },
+            Err(other_error) => return Err(other_error),
+        };
+    };
+    let maybe_conversation_id = maybe_in_reply_to.as_ref()
+        .map(|in_reply_to| in_reply_to.expect_conversation().id);
+    let mut post_data = scheduled_post_data
+        .into_post_data(maybe_conversation_id)?;
+    let limits = config.limits();
+    let duplicate_post_limits = &limits.duplicate_posts;
+    if check_duplicate_post(
+        db_client,
+        duplicate_post_limits,
+        user.id,
+        &post_data.content,
+    ).await? {
+        match duplicate_post_limits.action {
+            DuplicatePostAction::Reject => {
+                log::warn!("scheduled post cancelled: duplicate post");
+                return Ok(None);
+            },
+            DuplicatePostAction::Flag => {
+                log::warn!("duplicate post flagged: {}", user.id);
+                post_data.is_sensitive = true;
+            },
+        };
+    };
+    let (post, _) = publish_local_post(
+        config,
+        db_client,
+        &user,
+        post_data,
+        maybe_in_reply_to,
+        linked,
+    ).await?;
+    save_post_content_hash(
+        db_client,
+        duplicate_post_limits,
+        post.id,
+        user.id,
+        &post.content,
+    ).await?;
+    Ok(Some(post))
+}
+
+/// Publishes scheduled post or repost and removes it from the queue.
+/// If publication fails, nothing is changed and the scheduled post
+/// will be taken again after timeout.
+pub async fn publish_scheduled_post(
+    config: &Config,
+    db_pool: &DatabaseConnectionPool,
+    scheduled_post: ScheduledPost,
+) -> Result<(), DatabaseError> {
+    let db_client = &mut **get_database_client(db_pool).await?;
+    let mut transaction = db_client.transaction().await?;
+    // Row remains locked until the end of transaction
+    match delete_scheduled_post(
+        &transaction,
+        scheduled_post.owner_id,
+        scheduled_post.id,
+    ).await {
+        Ok(()) => (),
+        // Cancelled by user, or account has been deleted
+        Err(DatabaseError::NotFound(_)) => return Ok(()),
+        Err(other_error) => return Err(other_error),
+    };
+    let maybe_post = match scheduled_post.repost_of_id {
+        Some(repost_of_id) => {
+            let visibility = scheduled_post.repost_visibility
+                .unwrap_or(Visibility::Public);
+            publish_scheduled_repost(
+                config,
+                &mut transaction,
+                scheduled_post.owner_id,
+                repost_of_id,
+                visibility,
+                scheduled_post.replace_existing,
+            ).await?
+        },
+        None => {
+            let post_data = scheduled_post.post_data
+                .expect("post data should be present");
+            publish_scheduled_post_data(
+                config,
+                &mut transaction,
+                scheduled_post.owner_id,
+                post_data,
+            ).await?
+        },
+    };
+    transaction.commit().await?;
+    if let Some(post) = maybe_post {
+        log::info!("published scheduled post {}", post.id);
+    };
+    Ok(())
+}
@@ -4,11 +4,18 @@ use std::time::Duration;
 use chrono::{DateTime, Utc};
 
 use mitra_config::Config;
-use mitra_models::database::DatabaseConnectionPool;
+use mitra_models::{
+    database::{get_database_client, DatabaseConnectionPool, DatabaseError},
+    properties::{
+        constants::WORKER_HEARTBEAT,
+        queries::set_internal_property,
+    },
+};
 
 use super::periodic_tasks::*;
 
 const WORKER_DELAY: u64 = 500;
+const WORKER_HEARTBEAT_PERIOD: i64 = 60;
 
 #[derive(Debug, Eq, Hash, PartialEq)]
 pub enum PeriodicTask {
@@ -17,20 +24,27 @@ pub enum PeriodicTask {
     FetcherQueueExecutor,
     DeleteExtraneousPosts,
     DeleteEmptyProfiles,
+    DeleteTombstonedProfiles,
     PruneTags,
     PruneRemoteEmojis,
     PruneUnusedAttachments,
     PruneActivityPubObjects,
     PruneActivityPubCollectionItems,
+    PruneDeadLetters,
+    PrunePostContentHashes,
     MediaCleanupQueueExecutor,
     RefreshMaterializedViews,
     ImporterQueueExecutor,
+    PushNotificationQueueExecutor,
+    ScheduledPostPublisher,
     RemoteInvoiceMonitor,
     SubscriptionExpirationMonitor,
     MoneroPaymentMonitor,
     MoneroRecurrentPaymentMonitor,
     MoneroLightPaymentMonitor,
     MoneroLightNonInteractivePaymentMonitor,
+    FeedPoller,
+    CustomFeedPublisher,
 }
 
 impl PeriodicTask {
@@ -42,20 +56,27 @@ impl PeriodicTask {
             Self::FetcherQueueExecutor => 10,
             Self::DeleteExtraneousPosts => 3600,
             Self::DeleteEmptyProfiles => 3600,
+            Self::DeleteTombstonedProfiles => 3600,
             Self::PruneTags => 3600,
             Self::PruneRemoteEmojis => 3600,
             Self::PruneUnusedAttachments => 3600,
             Self::PruneActivityPubObjects => 3600,
             Self::PruneActivityPubCollectionItems => 3600,
+            Self::PruneDeadLetters => 3600,
+            Self::PrunePostContentHashes => 3600,
             Self::MediaCleanupQueueExecutor => 10,
             Self::RefreshMaterializedViews => 600,
             Self::ImporterQueueExecutor => 60,
+            Self::PushNotificationQueueExecutor => 5,
+            Self::ScheduledPostPublisher => 30,
             Self::RemoteInvoiceMonitor => 600,
             Self::SubscriptionExpirationMonitor => 300,
             Self::MoneroPaymentMonitor => 30,
             Self::MoneroRecurrentPaymentMonitor => 600,
             Self::MoneroLightPaymentMonitor => 30,
             Self::MoneroLightNonInteractivePaymentMonitor => 600,
+            Self::FeedPoller => 60,
+            Self::CustomFeedPublisher => 60,
         }
     }
 
@@ -70,6 +91,14 @@ impl PeriodicTask {
     }
 }
 
+async fn save_heartbeat(
+    db_pool: &DatabaseConnectionPool,
+) -> Result<(), DatabaseError> {
+    let db_client = &**get_database_client(db_pool).await?;
+    set_internal_property(db_client, WORKER_HEARTBEAT, &Utc::now()).await?;
+    Ok(())
+}
+
 pub async fn run_worker(
     config: Config,
     db_pool: DatabaseConnectionPool,
@@ -79,9 +108,23 @@ pub async fn run_worker(
         HashMap::from_iter(tasks.into_iter().map(|task| (task, None)));
     let mut interval =
         tokio::time::interval(Duration::from_millis(WORKER_DELAY));
+    let mut last_heartbeat: Option<DateTime<Utc>> = None;
     loop {
         interval.tick().await;
 
+        let is_heartbeat_due = last_heartbeat
+            .map(|last_heartbeat| {
+                let time_passed = Utc::now() - last_heartbeat;
+                time_passed.num_seconds() >= WORKER_HEARTBEAT_PERIOD
+            })
+            .unwrap_or(true);
+        if is_heartbeat_due {
+            save_heartbeat(&db_pool).await.unwrap_or_else(|error| {
+                log::error!("failed to save heartbeat: {error}");
+            });
+            last_heartbeat = Some(Utc::now());
+        };
+
         for (task, last_run) in worker_state.iter_mut() {
             if !task.is_ready(last_run) {
                 continue;
@@ -103,6 +146,9 @@ pub async fn run_worker(
                 PeriodicTask::DeleteEmptyProfiles => {
                     delete_empty_profiles(&config, &db_pool).await
                 },
+                PeriodicTask::DeleteTombstonedProfiles => {
+                    delete_tombstoned_profiles(&config, &db_pool).await
+                },
                 PeriodicTask::PruneTags => {
                     prune_tags(&config, &db_pool).await
                 },
@@ -118,6 +164,12 @@ pub async fn run_worker(
                 PeriodicTask::PruneActivityPubCollectionItems => {
                     prune_activitypub_collection_items(&config, &db_pool).await
                 },
+                PeriodicTask::PruneDeadLetters => {
+                    prune_dead_letters(&config, &db_pool).await
+                },
+                PeriodicTask::PrunePostContentHashes => {
+                    prune_post_content_hashes(&config, &db_pool).await
+                },
                 PeriodicTask::MediaCleanupQueueExecutor => {
                     media_cleanup_queue_executor(&config, &db_pool).await
                 },
@@ -127,6 +179,12 @@ pub async fn run_worker(
                 PeriodicTask::ImporterQueueExecutor => {
                     importer_queue_executor(&config, &db_pool).await
                 },
+                PeriodicTask::PushNotificationQueueExecutor => {
+                    push_notification_queue_executor(&config, &db_pool).await
+                },
+                PeriodicTask::ScheduledPostPublisher => {
+                    scheduled_post_publisher(&config, &db_pool).await
+                },
                 PeriodicTask::RemoteInvoiceMonitor => {
                     remote_invoice_monitor(&config, &db_pool).await
                 },
@@ -145,6 +203,12 @@ pub async fn run_worker(
                 PeriodicTask::MoneroLightNonInteractivePaymentMonitor => {
                     monero_light_non_interactive_payment_monitor(&config, &db_pool).await
                 },
+                PeriodicTask::FeedPoller => {
+                    feed_poller(&config, &db_pool).await
+                },
+                PeriodicTask::CustomFeedPublisher => {
+                    custom_feed_publisher(&config, &db_pool).await
+                },
             };
             task_result.unwrap_or_else(|err| {
                 log::error!("{:?}: {}", task, err);
@@ -166,9 +230,13 @@ fn start_main_worker(
             PeriodicTask::PruneUnusedAttachments,
             PeriodicTask::PruneActivityPubObjects,
             PeriodicTask::PruneActivityPubCollectionItems,
+            PeriodicTask::PrunePostContentHashes,
             PeriodicTask::MediaCleanupQueueExecutor,
             PeriodicTask::RefreshMaterializedViews,
             PeriodicTask::ImporterQueueExecutor,
+            PeriodicTask::PushNotificationQueueExecutor,
+            PeriodicTask::ScheduledPostPublisher,
+            PeriodicTask::CustomFeedPublisher,
             PeriodicTask::RemoteInvoiceMonitor,
             PeriodicTask::SubscriptionExpirationMonitor,
         ];
@@ -184,6 +252,12 @@ fn start_main_worker(
         if config.retention.empty_profiles.is_some() {
             tasks.push(PeriodicTask::DeleteEmptyProfiles);
         };
+        if config.retention.deleted_profiles.is_some() {
+            tasks.push(PeriodicTask::DeleteTombstonedProfiles);
+        };
+        if config.retention.dead_letters.is_some() {
+            tasks.push(PeriodicTask::PruneDeadLetters);
+        };
         if config.monero_config().is_some() {
             tasks.push(PeriodicTask::MoneroPaymentMonitor);
             tasks.push(PeriodicTask::MoneroRecurrentPaymentMonitor);
@@ -192,6 +266,9 @@ fn start_main_worker(
             tasks.push(PeriodicTask::MoneroLightPaymentMonitor);
             tasks.push(PeriodicTask::MoneroLightNonInteractivePaymentMonitor);
         };
+        if config.feeds.enabled {
+            tasks.push(PeriodicTask::FeedPoller);
+        };
         run_worker(config, db_pool, tasks).await;
     });
 }